@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+alter table CradleNativeListings
+    drop column if exists approved_at,
+    drop column if exists approved_by,
+    drop column if exists lifecycle;
+
+drop type if exists listing_lifecycle;
@@ -0,0 +1,18 @@
+-- Your SQL goes here
+create type listing_lifecycle as enum (
+    'draft',
+    'submitted',
+    'approved',
+    'live',
+    'closed',
+    'settled'
+);
+
+alter table CradleNativeListings
+    add column lifecycle listing_lifecycle not null default 'draft',
+    add column approved_by uuid references CradleAccounts(id),
+    add column approved_at timestamp;
+
+-- existing listings were opened directly through the status flag
+update CradleNativeListings set lifecycle = 'live' where status in ('open', 'paused');
+update CradleNativeListings set lifecycle = 'closed' where status in ('closed', 'cancelled');
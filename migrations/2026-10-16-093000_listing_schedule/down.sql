@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+drop index if exists idx_listings_schedule;
+
+alter table CradleNativeListings
+    drop column if exists ends_at,
+    drop column if exists starts_at;
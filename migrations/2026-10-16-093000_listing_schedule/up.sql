@@ -0,0 +1,6 @@
+-- Your SQL goes here
+alter table CradleNativeListings
+    add column starts_at timestamp,
+    add column ends_at timestamp;
+
+create index if not exists idx_listings_schedule on CradleNativeListings(lifecycle, starts_at, ends_at);
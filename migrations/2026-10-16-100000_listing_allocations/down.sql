@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+drop table if exists listing_allocations;
+drop type if exists allocation_status;
+
+alter table CradleNativeListings drop column if exists allocation_mode;
+drop type if exists listing_allocation_mode;
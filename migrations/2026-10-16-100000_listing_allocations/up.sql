@@ -0,0 +1,29 @@
+-- Your SQL goes here
+create type listing_allocation_mode as enum (
+    'first_come',
+    'pro_rata'
+);
+
+alter table CradleNativeListings
+    add column allocation_mode listing_allocation_mode not null default 'first_come';
+
+create type allocation_status as enum (
+    'committed',
+    'allocated',
+    'failed'
+);
+
+-- one row per wallet per listing; commitments accumulate until the allocation pass
+create table if not exists listing_allocations (
+    id uuid primary key default uuid_generate_v4(),
+    listing_id uuid not null references CradleNativeListings(id),
+    wallet_id uuid not null references CradleWalletAccounts(id),
+    requested numeric not null default 0, -- listed asset
+    locked numeric not null default 0, -- purchase asset held while committed
+    allocated numeric not null default 0, -- listed asset
+    refunded numeric not null default 0, -- purchase asset released back
+    status allocation_status not null default 'committed',
+    created_at timestamp not null default now(),
+    allocated_at timestamp,
+    unique (listing_id, wallet_id)
+);
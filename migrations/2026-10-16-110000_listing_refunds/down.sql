@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+drop table if exists listing_refunds;
+drop type if exists refund_status;
+
+alter table CradleNativeListings drop column if exists soft_cap;
+-- enum values added to listing_lifecycle and allocation_status can't be dropped
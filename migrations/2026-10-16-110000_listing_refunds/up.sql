@@ -0,0 +1,26 @@
+-- Your SQL goes here
+alter type listing_lifecycle add value if not exists 'cancelled';
+alter type allocation_status add value if not exists 'refunded';
+
+-- minimum listed-asset amount that must sell for the listing to succeed
+alter table CradleNativeListings add column soft_cap numeric;
+
+create type refund_status as enum (
+    'pending',
+    'refunded',
+    'failed'
+);
+
+-- one row per buyer per listing so reruns only retry what hasn't gone through
+create table if not exists listing_refunds (
+    id uuid primary key default uuid_generate_v4(),
+    listing_id uuid not null references CradleNativeListings(id),
+    wallet_id uuid not null references CradleWalletAccounts(id),
+    amount numeric not null, -- listed asset returned to the listing
+    status refund_status not null default 'pending',
+    transaction_id uuid, -- ledger entry of the return
+    error text,
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now(),
+    unique (listing_id, wallet_id)
+);
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+drop table if exists distribution_payouts;
+drop table if exists listing_distributions;
+drop type if exists payout_status;
+drop type if exists distribution_status;
@@ -0,0 +1,41 @@
+-- Your SQL goes here
+create type distribution_status as enum (
+    'open',
+    'completed'
+);
+
+create type payout_status as enum (
+    'claimable',
+    'paid',
+    'failed'
+);
+
+-- a payout deposited by the listing's company, split across holders as of snapshot_at
+create table if not exists listing_distributions (
+    id uuid primary key default uuid_generate_v4(),
+    listing_id uuid not null references CradleNativeListings(id),
+    payout_asset uuid not null references asset_book(id),
+    total_amount numeric not null, -- deposited into the listing treasury
+    allocated numeric not null, -- sum of payouts; the rounding remainder stays in the treasury
+    deposit_transaction uuid, -- ledger entry of the deposit
+    status distribution_status not null default 'open',
+    snapshot_at timestamp not null,
+    created_at timestamp not null default now(),
+    completed_at timestamp
+);
+
+create table if not exists distribution_payouts (
+    id uuid primary key default uuid_generate_v4(),
+    distribution_id uuid not null references listing_distributions(id),
+    wallet_id uuid not null references CradleWalletAccounts(id),
+    holding numeric not null, -- listed asset held at the snapshot
+    amount numeric not null, -- payout asset owed
+    status payout_status not null default 'claimable',
+    transaction_id uuid, -- ledger entry of the transfer
+    error text,
+    created_at timestamp not null default now(),
+    paid_at timestamp,
+    unique (distribution_id, wallet_id)
+);
+
+create index if not exists distribution_payouts_status_idx on distribution_payouts(distribution_id, status);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+drop table if exists listing_whitelist;
+
+alter table CradleNativeListings drop column if exists whitelist_enabled;
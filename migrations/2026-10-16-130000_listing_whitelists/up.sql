@@ -0,0 +1,11 @@
+-- Your SQL goes here
+-- when set, only wallets on the listing's whitelist may purchase
+alter table CradleNativeListings add column whitelist_enabled boolean not null default false;
+
+create table if not exists listing_whitelist (
+    id uuid primary key default uuid_generate_v4(),
+    listing_id uuid not null references CradleNativeListings(id),
+    wallet_id uuid not null references CradleWalletAccounts(id),
+    added_at timestamp not null default now(),
+    unique (listing_id, wallet_id)
+);
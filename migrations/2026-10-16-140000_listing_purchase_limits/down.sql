@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+alter table CradleNativeListings drop column if exists max_per_wallet;
+alter table CradleNativeListings drop column if exists min_purchase;
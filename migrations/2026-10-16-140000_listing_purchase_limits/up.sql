@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- listed-asset amounts; a wallet's purchases on a listing are counted together
+alter table CradleNativeListings add column min_purchase numeric;
+alter table CradleNativeListings add column max_per_wallet numeric;
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+alter table CradleListedCompanies drop column if exists legal_documents_hash;
+alter table CradleNativeListings drop column if exists documents_hash;
+
+drop table if exists documents;
+drop type if exists document_owner;
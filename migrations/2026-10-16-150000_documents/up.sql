@@ -0,0 +1,25 @@
+-- Your SQL goes here
+create type document_owner as enum (
+    'listing',
+    'company'
+);
+
+-- files uploaded through /documents; the bytes live in the configured storage backend
+create table if not exists documents (
+    id uuid primary key default uuid_generate_v4(),
+    owner_type document_owner not null,
+    owner_id uuid not null, -- listing or company id
+    file_name text not null,
+    content_type text not null,
+    size bigint not null,
+    content_hash text not null, -- hex sha256 of the file
+    backend text not null, -- s3, ipfs or local
+    storage_key text not null, -- object key, CID or relative path
+    uploaded_at timestamp not null default now()
+);
+
+create index if not exists documents_owner_idx on documents(owner_type, owner_id);
+
+-- hash of the most recent upload, so the row commits to exact file contents
+alter table CradleNativeListings add column documents_hash text;
+alter table CradleListedCompanies add column legal_documents_hash text;
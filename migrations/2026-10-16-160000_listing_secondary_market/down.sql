@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+alter table CradleNativeListings drop column if exists secondary_market;
+alter table CradleNativeListings drop column if exists create_market_on_close;
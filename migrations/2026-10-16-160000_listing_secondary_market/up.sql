@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- open a spot market for the listed asset once the listing closes
+alter table CradleNativeListings add column create_market_on_close boolean not null default false;
+alter table CradleNativeListings add column secondary_market uuid references markets(id);
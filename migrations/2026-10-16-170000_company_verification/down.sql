@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+alter table CradleListedCompanies drop column if exists verified_at;
+alter table CradleListedCompanies drop column if exists verified_by;
+alter table CradleListedCompanies drop column if exists verification_status;
+
+drop type if exists company_verification;
@@ -0,0 +1,6 @@
+-- Your SQL goes here
+create type company_verification as enum ('unverified', 'verified');
+
+alter table CradleListedCompanies add column verification_status company_verification not null default 'unverified';
+alter table CradleListedCompanies add column verified_by uuid references CradleAccounts(id);
+alter table CradleListedCompanies add column verified_at timestamp;
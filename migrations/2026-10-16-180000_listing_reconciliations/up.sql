@@ -0,0 +1,17 @@
+-- Your SQL goes here
+create type reconciliation_status as enum ('matched', 'mismatch', 'failed');
+
+create table if not exists listing_reconciliations (
+    id uuid primary key default uuid_generate_v4(),
+    listing_id uuid not null references CradleNativeListings(id),
+    asset uuid not null references asset_book(id),
+    -- purchase asset the ledger says the listing contract should hold
+    expected numeric not null,
+    onchain numeric,
+    difference numeric,
+    status reconciliation_status not null,
+    error text,
+    checked_at timestamp not null default now()
+);
+
+create index if not exists listing_reconciliations_listing_idx on listing_reconciliations (listing_id, checked_at desc);
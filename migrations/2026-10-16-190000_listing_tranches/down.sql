@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+drop table if exists listing_tranche_purchases;
+drop table if exists listing_tranches;
+alter table CradleNativeListings drop column if exists tranche_mode;
+drop type if exists tranche_mode;
@@ -0,0 +1,30 @@
+-- Your SQL goes here
+create type tranche_mode as enum (
+    'sequential',
+    'parallel'
+);
+
+-- sequential: tranches sell in position order; parallel: the buyer picks one
+alter table CradleNativeListings add column tranche_mode tranche_mode not null default 'sequential';
+
+create table if not exists listing_tranches (
+    id uuid primary key default uuid_generate_v4(),
+    listing_id uuid not null references CradleNativeListings(id),
+    position integer not null,
+    name text not null,
+    price numeric not null,
+    supply numeric not null,
+    sold numeric not null default 0,
+    created_at timestamp not null default now(),
+    unique (listing_id, position)
+);
+
+create table if not exists listing_tranche_purchases (
+    id uuid primary key default uuid_generate_v4(),
+    tranche_id uuid not null references listing_tranches(id),
+    wallet_id uuid not null references CradleWalletAccounts(id),
+    amount numeric not null,
+    price numeric not null,
+    transaction_id uuid, -- ledger entry of the purchase
+    created_at timestamp not null default now()
+);
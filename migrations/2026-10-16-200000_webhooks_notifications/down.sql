@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+drop table if exists notifications;
+drop table if exists webhook_deliveries;
+drop table if exists webhooks;
+drop type if exists delivery_status;
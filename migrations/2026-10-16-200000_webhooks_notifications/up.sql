@@ -0,0 +1,47 @@
+-- Your SQL goes here
+create type delivery_status as enum (
+    'pending',
+    'delivered',
+    'failed'
+);
+
+-- issuer endpoints receiving listing events; null scope columns match everything
+create table if not exists webhooks (
+    id uuid primary key default uuid_generate_v4(),
+    url text not null,
+    secret text not null,
+    company_id uuid references CradleListedCompanies(id),
+    listing_id uuid references CradleNativeListings(id),
+    events text[] not null default '{}', -- empty for every event
+    active boolean not null default true,
+    created_at timestamp not null default now()
+);
+
+create table if not exists webhook_deliveries (
+    id uuid primary key default uuid_generate_v4(),
+    webhook_id uuid not null references webhooks(id),
+    event text not null,
+    listing_id uuid not null references CradleNativeListings(id),
+    payload jsonb not null,
+    status delivery_status not null default 'pending',
+    attempts integer not null default 0,
+    last_error text,
+    next_attempt_at timestamp not null default now(),
+    created_at timestamp not null default now(),
+    delivered_at timestamp
+);
+
+create index if not exists webhook_deliveries_pending_idx on webhook_deliveries (next_attempt_at) where status = 'pending';
+
+-- investor inbox
+create table if not exists notifications (
+    id uuid primary key default uuid_generate_v4(),
+    wallet_id uuid not null references CradleWalletAccounts(id),
+    listing_id uuid references CradleNativeListings(id),
+    event text not null,
+    payload jsonb not null,
+    read_at timestamp,
+    created_at timestamp not null default now()
+);
+
+create index if not exists notifications_wallet_idx on notifications (wallet_id, created_at desc);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+drop table if exists withdrawal_approvals;
+drop table if exists withdrawal_requests;
+drop type if exists withdrawal_status;
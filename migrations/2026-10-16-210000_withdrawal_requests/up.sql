@@ -0,0 +1,28 @@
+-- Your SQL goes here
+create type withdrawal_status as enum ('pending', 'approved', 'rejected', 'executed', 'failed');
+
+create table if not exists withdrawal_requests (
+    id uuid primary key default uuid_generate_v4(),
+    listing_id uuid not null references CradleNativeListings(id),
+    amount numeric not null,
+    requested_by uuid not null references CradleAccounts(id),
+    -- approvals needed before the contract call runs
+    required_approvals integer not null,
+    status withdrawal_status not null default 'pending',
+    reviewed_by uuid references CradleAccounts(id), -- set on rejection
+    reason text,
+    transaction_id uuid references AccountAssetsLedger(id),
+    error text,
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now()
+);
+
+create table if not exists withdrawal_approvals (
+    id uuid primary key default uuid_generate_v4(),
+    request_id uuid not null references withdrawal_requests(id),
+    approver uuid not null references CradleAccounts(id),
+    created_at timestamp not null default now(),
+    unique (request_id, approver)
+);
+
+create index if not exists withdrawal_requests_listing_idx on withdrawal_requests (listing_id, created_at desc);
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+drop table if exists corporate_actions;
+alter table CradleNativeListings drop column if exists delisted_at;
+alter table CradleNativeListings drop column if exists split_factor;
+drop type if exists corporate_action_kind;
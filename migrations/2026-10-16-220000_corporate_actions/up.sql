@@ -0,0 +1,19 @@
+-- Your SQL goes here
+create type corporate_action_kind as enum ('split', 'rename', 'delist');
+
+-- ledger amounts recorded before a split are scaled by this to get today's units
+alter table CradleNativeListings add column split_factor numeric not null default 1;
+alter table CradleNativeListings add column delisted_at timestamp;
+
+create table if not exists corporate_actions (
+    id uuid primary key default uuid_generate_v4(),
+    listing_id uuid not null references CradleNativeListings(id),
+    asset_id uuid not null references asset_book(id),
+    kind corporate_action_kind not null,
+    details jsonb not null,
+    cancelled_orders integer not null default 0,
+    initiated_by uuid not null references CradleAccounts(id),
+    created_at timestamp not null default now()
+);
+
+create index if not exists corporate_actions_listing_idx on corporate_actions (listing_id, created_at desc);
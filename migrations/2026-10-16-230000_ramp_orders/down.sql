@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+drop table if exists ramp_orders;
+drop type if exists ramp_order_status;
+drop type if exists ramp_direction;
@@ -0,0 +1,25 @@
+-- Your SQL goes here
+create type ramp_direction as enum ('onramp', 'offramp');
+create type ramp_order_status as enum ('pending', 'processing', 'completed', 'failed');
+
+create table if not exists ramp_orders (
+    -- also the order id given to the provider
+    id uuid primary key default uuid_generate_v4(),
+    direction ramp_direction not null,
+    status ramp_order_status not null default 'pending',
+    wallet_id uuid not null references CradleWalletAccounts(id),
+    asset_id uuid not null references asset_book(id),
+    amount numeric not null,
+    currency text not null,
+    channel text not null,
+    -- phone number or bank account a payout goes to
+    destination text,
+    provider_reference text,
+    -- ledger entry of the transfer to the settlement account
+    transaction_id uuid references AccountAssetsLedger(id),
+    failure_reason text,
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now()
+);
+
+create index if not exists ramp_orders_wallet_idx on ramp_orders (wallet_id, created_at desc);
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+drop table if exists ramp_order_events;
+
+alter table ramp_orders drop column if exists refund_reference;
+-- the enum value added to ramp_order_status can't be dropped
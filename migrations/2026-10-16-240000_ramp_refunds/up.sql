@@ -0,0 +1,17 @@
+-- Your SQL goes here
+alter type ramp_order_status add value if not exists 'refunded';
+
+alter table ramp_orders add column refund_reference text;
+
+-- audit trail of what was done to an order after it settled
+create table if not exists ramp_order_events (
+    id uuid primary key default uuid_generate_v4(),
+    order_id uuid not null references ramp_orders(id),
+    event text not null,
+    -- system account that triggered it; null when automated
+    actor uuid references CradleAccounts(id),
+    note text,
+    created_at timestamp not null default now()
+);
+
+create index if not exists ramp_order_events_order_idx on ramp_order_events (order_id, created_at);
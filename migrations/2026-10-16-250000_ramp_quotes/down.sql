@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+alter table ramp_orders drop column if exists fiat_amount;
+alter table ramp_orders drop column if exists quote_id;
+
+drop table if exists ramp_quotes;
@@ -0,0 +1,18 @@
+-- Your SQL goes here
+create table if not exists ramp_quotes (
+    id uuid primary key default uuid_generate_v4(),
+    asset_id uuid not null references asset_book(id),
+    currency text not null,
+    -- fiat per token at the time of the quote
+    rate numeric not null,
+    fiat_amount numeric not null,
+    token_amount numeric not null,
+    source text not null,
+    expires_at timestamp not null,
+    -- set once an order has been placed against it
+    used_at timestamp,
+    created_at timestamp not null default now()
+);
+
+alter table ramp_orders add column quote_id uuid references ramp_quotes(id);
+alter table ramp_orders add column fiat_amount numeric;
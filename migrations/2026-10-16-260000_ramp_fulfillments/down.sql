@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+drop table if exists ramp_fulfillments;
+drop type if exists fulfillment_status;
@@ -0,0 +1,21 @@
+-- Your SQL goes here
+create type fulfillment_status as enum ('pending', 'completed', 'failed');
+
+-- crediting a paid on-ramp order, one step at a time so a retry resumes
+-- where the last attempt stopped
+create table if not exists ramp_fulfillments (
+    id uuid primary key default uuid_generate_v4(),
+    order_id uuid not null unique references ramp_orders(id),
+    status fulfillment_status not null default 'pending',
+    associated_at timestamp,
+    kyced_at timestamp,
+    minted_at timestamp,
+    airdropped_at timestamp,
+    attempts integer not null default 0,
+    last_error text,
+    next_attempt_at timestamp not null default now(),
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now()
+);
+
+create index if not exists ramp_fulfillments_pending_idx on ramp_fulfillments (next_attempt_at) where status = 'pending';
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+drop table if exists ramp_receipts;
+alter table ramp_fulfillments drop column if exists airdrop_tx;
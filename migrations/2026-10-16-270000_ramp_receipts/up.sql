@@ -0,0 +1,21 @@
+-- Your SQL goes here
+alter table ramp_fulfillments add column if not exists airdrop_tx text;
+
+-- what a completed on-ramp credited, kept so the receipt can be fetched again
+create table if not exists ramp_receipts (
+    id uuid primary key default uuid_generate_v4(),
+    order_id uuid not null unique references ramp_orders(id),
+    wallet_id uuid not null references cradlewalletaccounts(id),
+    asset_id uuid not null references asset_book(id),
+    token_symbol text not null,
+    token_amount numeric not null,
+    currency text not null,
+    fiat_amount numeric,
+    fee numeric not null default 0,
+    channel text not null,
+    provider_reference text,
+    tx_hash text,
+    created_at timestamp not null default now()
+);
+
+create index if not exists ramp_receipts_wallet_idx on ramp_receipts (wallet_id);
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+drop table if exists protocol_fees;
+alter table ramp_orders drop column if exists fee_tokens;
+alter table ramp_orders drop column if exists fee;
+alter table ramp_quotes drop column if exists fee_tokens;
+alter table ramp_quotes drop column if exists fee;
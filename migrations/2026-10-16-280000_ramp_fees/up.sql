@@ -0,0 +1,22 @@
+-- Your SQL goes here
+-- fees are charged in fiat and taken out of the tokens credited, so both
+-- sides are kept
+alter table ramp_quotes add column if not exists fee numeric not null default 0;
+alter table ramp_quotes add column if not exists fee_tokens numeric not null default 0;
+alter table ramp_orders add column if not exists fee numeric not null default 0;
+alter table ramp_orders add column if not exists fee_tokens numeric not null default 0;
+
+create table if not exists protocol_fees (
+    id uuid primary key default uuid_generate_v4(),
+    source text not null,
+    reference_id uuid not null,
+    provider text,
+    asset_id uuid references asset_book(id),
+    currency text not null,
+    amount numeric not null,
+    token_amount numeric not null default 0,
+    created_at timestamp not null default now(),
+    unique (source, reference_id)
+);
+
+create index if not exists protocol_fees_created_idx on protocol_fees (source, created_at);
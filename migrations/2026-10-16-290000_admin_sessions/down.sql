@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+drop table if exists admin_sessions;
+drop table if exists admin_operators;
+drop type if exists admin_role;
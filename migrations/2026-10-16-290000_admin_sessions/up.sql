@@ -0,0 +1,26 @@
+-- Your SQL goes here
+-- operators sign in to the admin dashboard as one of the platform's system
+-- accounts; viewers can only look, operators can act
+create type admin_role as enum ('viewer', 'operator');
+
+create table if not exists admin_operators (
+    id uuid primary key default uuid_generate_v4(),
+    account_id uuid not null references cradleaccounts(id) on delete cascade,
+    username text not null unique,
+    password_hash text not null,
+    role admin_role not null default 'viewer',
+    created_at timestamp not null default now(),
+    last_login_at timestamp
+);
+
+create table if not exists admin_sessions (
+    id uuid primary key default uuid_generate_v4(),
+    operator_id uuid not null references admin_operators(id) on delete cascade,
+    -- only a digest of the cookie is kept
+    token_hash text not null unique,
+    csrf_token text not null,
+    expires_at timestamp not null,
+    created_at timestamp not null default now()
+);
+
+create index if not exists admin_sessions_expires_idx on admin_sessions (expires_at);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+alter table lendingpool drop column if exists borrow_cap;
+alter table lendingpool drop column if exists supply_cap;
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+-- caps are in reserve base units and checked before a supply or borrow goes
+-- to the pool contract; null means no cap
+alter table lendingpool add column if not exists supply_cap numeric;
+alter table lendingpool add column if not exists borrow_cap numeric;
@@ -0,0 +1,21 @@
+-- Your SQL goes here
+-- every mutation that goes through the action router from the API or the
+-- admin dashboard, with what was asked for and what came back
+create table if not exists mutation_audit_log (
+    id uuid primary key default uuid_generate_v4(),
+    -- operator username, or "api" for the shared-key endpoint
+    actor text not null,
+    -- "api" or "admin_ui"
+    source text not null,
+    -- processor and variant, e.g. "Markets.UpdateMarketStatus"
+    action text not null,
+    payload jsonb not null,
+    -- the processor's output; null when the mutation failed
+    result jsonb,
+    error text,
+    created_at timestamp not null default now()
+);
+
+create index if not exists mutation_audit_log_created_idx on mutation_audit_log (created_at);
+create index if not exists mutation_audit_log_actor_idx on mutation_audit_log (actor, created_at);
+create index if not exists mutation_audit_log_action_idx on mutation_audit_log (action, created_at);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+drop table if exists oracle_price_sources;
+drop type if exists oracle_source_kind;
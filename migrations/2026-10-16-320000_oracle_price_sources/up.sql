@@ -0,0 +1,18 @@
+-- Your SQL goes here
+create type oracle_source_kind as enum (
+    'fixed',
+    'last_trade'
+);
+
+-- where the oracle publisher gets prices for one pool's asset; params depend on the kind
+create table if not exists oracle_price_sources (
+    id uuid primary key default uuid_generate_v4(),
+    lending_pool_id uuid not null references lendingpool(id),
+    asset_id uuid not null references asset_book(id),
+    kind oracle_source_kind not null,
+    params jsonb not null default '{}',
+    enabled boolean not null default true,
+    created_at timestamp not null default now()
+);
+
+create index if not exists oracle_price_sources_target_idx on oracle_price_sources (lending_pool_id, asset_id) where enabled;
@@ -0,0 +1,10 @@
+-- This file should undo anything in `up.sql`
+-- enum values can't be dropped, so rebuild the type without it
+delete from oracle_price_sources where kind = 'twap';
+alter type oracle_source_kind rename to oracle_source_kind_old;
+create type oracle_source_kind as enum (
+    'fixed',
+    'last_trade'
+);
+alter table oracle_price_sources alter column kind type oracle_source_kind using kind::text::oracle_source_kind;
+drop type oracle_source_kind_old;
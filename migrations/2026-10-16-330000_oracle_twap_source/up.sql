@@ -0,0 +1,2 @@
+-- Your SQL goes here
+alter type oracle_source_kind add value if not exists 'twap';
@@ -0,0 +1,13 @@
+-- This file should undo anything in `up.sql`
+drop table if exists oracle_source_quotes;
+
+-- enum values can't be dropped, so rebuild the type without them
+delete from oracle_price_sources where kind in ('pyth', 'http_ticker');
+alter type oracle_source_kind rename to oracle_source_kind_old;
+create type oracle_source_kind as enum (
+    'fixed',
+    'last_trade',
+    'twap'
+);
+alter table oracle_price_sources alter column kind type oracle_source_kind using kind::text::oracle_source_kind;
+drop type oracle_source_kind_old;
@@ -0,0 +1,17 @@
+-- Your SQL goes here
+alter type oracle_source_kind add value if not exists 'pyth';
+alter type oracle_source_kind add value if not exists 'http_ticker';
+
+-- every price a source gave the publisher, and where it came from
+create table if not exists oracle_source_quotes (
+    id uuid primary key default uuid_generate_v4(),
+    source_id uuid not null references oracle_price_sources(id),
+    price numeric, -- null when the source failed
+    error text,
+    rejected boolean not null default false,
+    observed_at timestamp,
+    provenance jsonb not null default '{}',
+    fetched_at timestamp not null default now()
+);
+
+create index if not exists oracle_source_quotes_source_idx on oracle_source_quotes (source_id, fetched_at desc);
@@ -0,0 +1,14 @@
+-- Your SQL goes here
+-- every price pushed to a pool's oracle contract, for audits
+create table if not exists oracle_price_history (
+    id uuid primary key default uuid_generate_v4(),
+    lending_pool_id uuid not null references lendingpool(id),
+    asset_id uuid not null references asset_book(id),
+    price numeric not null, -- the raw value sent to the contract
+    sources jsonb not null default '[]',
+    published_by text not null,
+    transaction text not null,
+    published_at timestamp not null default now()
+);
+
+create index if not exists oracle_price_history_asset_idx on oracle_price_history (asset_id, published_at desc);
@@ -0,0 +1,2 @@
+-- Your SQL goes here
+alter table markets add column trading_schedule jsonb;
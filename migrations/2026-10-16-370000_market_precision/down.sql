@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+alter table markets
+    drop column display_symbol,
+    drop column quantity_precision,
+    drop column price_precision;
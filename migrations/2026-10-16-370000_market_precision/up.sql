@@ -0,0 +1,16 @@
+-- Your SQL goes here
+alter table markets
+    add column price_precision integer not null default 8,
+    add column quantity_precision integer,
+    add column display_symbol text;
+
+-- existing markets keep accepting whatever the base token can express
+update markets m
+set quantity_precision = base.decimals,
+    display_symbol = base.symbol || '/' || quote.symbol
+from asset_book base, asset_book quote
+where base.id = m.asset_one and quote.id = m.asset_two;
+
+alter table markets
+    alter column quantity_precision set not null,
+    alter column display_symbol set not null;
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+alter table markets
+    drop column reference_price,
+    drop column price_band_percent;
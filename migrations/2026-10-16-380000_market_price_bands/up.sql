@@ -0,0 +1,4 @@
+-- Your SQL goes here
+alter table markets
+    add column price_band_percent numeric not null default 10,
+    add column reference_price numeric;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+drop index if exists markets_symbol_idx;
+alter table markets drop column symbol;
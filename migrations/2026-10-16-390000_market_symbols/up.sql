@@ -0,0 +1,20 @@
+-- Your SQL goes here
+alter table markets add column symbol text;
+
+update markets m
+set symbol = upper(regexp_replace(base.symbol, '[^A-Za-z0-9]', '', 'g'))
+    || '-' || upper(regexp_replace(quote.symbol, '[^A-Za-z0-9]', '', 'g'))
+from asset_book base, asset_book quote
+where base.id = m.asset_one and quote.id = m.asset_two;
+
+-- assets can share a symbol, so later markets with a taken one get a suffix
+update markets m
+set symbol = m.symbol || '-' || upper(left(m.id::text, 8))
+from (
+    select id, row_number() over (partition by symbol order by created_at) as nth
+    from markets
+) ranked
+where ranked.id = m.id and ranked.nth > 1;
+
+alter table markets alter column symbol set not null;
+create unique index markets_symbol_idx on markets (symbol);
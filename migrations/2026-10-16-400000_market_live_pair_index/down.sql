@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+drop index if exists idx_unique_live_market;
+create unique index idx_unique_market_assets on markets(asset_one, asset_two);
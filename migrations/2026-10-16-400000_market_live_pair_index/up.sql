@@ -0,0 +1,6 @@
+-- Your SQL goes here
+-- a pair can be relisted once its old market is retired, and traded as
+-- different market types, but only one live market per pair and type
+drop index if exists idx_unique_market_assets;
+create unique index idx_unique_live_market on markets(asset_one, asset_two, market_type)
+    where market_status in ('active', 'suspended');
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+drop index if exists idx_market_status_schedules_market;
+drop index if exists idx_market_status_schedules_due;
+drop table if exists market_status_schedules;
+drop type if exists scheduled_change_status;
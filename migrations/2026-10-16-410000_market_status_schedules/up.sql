@@ -0,0 +1,23 @@
+-- Your SQL goes here
+create type scheduled_change_status as enum (
+    'pending',
+    'executed',
+    'cancelled',
+    'failed'
+);
+
+create table if not exists market_status_schedules (
+    id uuid primary key default uuid_generate_v4(),
+    market_id uuid not null references markets(id),
+    target_status market_status not null,
+    execute_at timestamp not null,
+    reason text,
+    status scheduled_change_status not null default 'pending',
+    executed_at timestamp,
+    error text,
+    created_at timestamp not null default now()
+);
+
+create index idx_market_status_schedules_due on market_status_schedules(execute_at)
+    where status = 'pending';
+create index idx_market_status_schedules_market on market_status_schedules(market_id);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+alter table admin_operators drop column if exists api_key_hash;
+-- postgres can't drop a value from an enum; approvers go back to operators
+update admin_operators set role = 'operator' where role = 'approver';
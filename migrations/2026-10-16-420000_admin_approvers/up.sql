@@ -0,0 +1,7 @@
+-- Your SQL goes here
+-- approvers can also sign off listings, withdrawals, corporate actions and
+-- refunds; API callers act as an operator by presenting that operator's key
+alter type admin_role add value if not exists 'approver';
+
+-- only a digest of the key is kept
+alter table admin_operators add column if not exists api_key_hash text unique;
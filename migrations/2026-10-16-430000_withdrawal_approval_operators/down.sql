@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+drop index if exists withdrawal_requests_paid_idx;
+-- withdrawals that skipped approval are also on the ledger
+delete from withdrawal_requests where requested_by is null;
+alter table withdrawal_requests alter column requested_by set not null;
+
+drop index if exists withdrawal_approvals_operator_idx;
+alter table withdrawal_approvals drop column if exists operator_id;
+alter table withdrawal_approvals add constraint withdrawal_approvals_request_id_approver_key unique (request_id, approver);
@@ -0,0 +1,12 @@
+-- Your SQL goes here
+-- approvals count per operator: someone can't approve twice by switching
+-- accounts, and two people acting as one system account are two approvals
+alter table withdrawal_approvals add column if not exists operator_id uuid references admin_operators(id);
+alter table withdrawal_approvals drop constraint if exists withdrawal_approvals_request_id_approver_key;
+create unique index if not exists withdrawal_approvals_operator_idx on withdrawal_approvals (request_id, operator_id);
+
+-- withdrawals under the approval threshold are recorded too, without a
+-- requester, so the threshold applies to what a listing paid out recently
+alter table withdrawal_requests alter column requested_by drop not null;
+create index if not exists withdrawal_requests_paid_idx on withdrawal_requests (listing_id, updated_at)
+    where status in ('approved', 'executed');
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- off-ramps whose tokens went to settlement but whose payout didn't go
+-- through, until the tokens are sent back to the wallet
+alter type ramp_order_status add value if not exists 'payout_failed';
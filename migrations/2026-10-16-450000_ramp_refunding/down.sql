@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+-- the enum value added to ramp_order_status can't be dropped
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- an order is claimed before the provider is asked to refund it, so two
+-- refunds of the same order can't both go out
+alter type ramp_order_status add value if not exists 'refunding';
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- the wallet's balance right before the airdrop is sent, so a retry after
+-- a crash can tell whether it already landed instead of sending it again
+alter table ramp_fulfillments add column if not exists airdrop_balance_before bigint;
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- a job a worker has claimed, until its lease in next_attempt_at runs out,
+-- so a refund can tell it apart from one waiting for its next attempt
+alter type fulfillment_status add value if not exists 'running';
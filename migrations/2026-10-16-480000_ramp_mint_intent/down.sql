@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+alter table ramp_fulfillments drop column if exists mint_supply_before;
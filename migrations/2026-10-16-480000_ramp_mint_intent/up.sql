@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- the token's total supply right before the mint is sent, so a retry after
+-- a crash can tell whether it already went through instead of minting again
+alter table ramp_fulfillments add column if not exists mint_supply_before bigint;
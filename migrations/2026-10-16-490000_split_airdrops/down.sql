@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+drop table if exists split_airdrops;
+drop table if exists split_airdrop_jobs;
+drop type if exists split_airdrop_status;
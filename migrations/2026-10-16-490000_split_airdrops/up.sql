@@ -0,0 +1,34 @@
+-- Your SQL goes here
+create type split_airdrop_status as enum ('pending', 'running', 'completed');
+
+-- the extra units a split owes holders: minted once, then airdropped one
+-- holder at a time, retried until every holder has them
+create table if not exists split_airdrop_jobs (
+    id uuid primary key default uuid_generate_v4(),
+    action_id uuid not null unique references corporate_actions(id),
+    asset_id uuid not null references asset_book(id),
+    units bigint not null,
+    status split_airdrop_status not null default 'pending',
+    minted_at timestamp,
+    mint_supply_before bigint,
+    attempts integer not null default 0,
+    last_error text,
+    next_attempt_at timestamp not null default now(),
+    created_at timestamp not null default now(),
+    updated_at timestamp not null default now()
+);
+
+create index if not exists split_airdrop_jobs_due_idx on split_airdrop_jobs (next_attempt_at) where status <> 'completed';
+
+create table if not exists split_airdrops (
+    id uuid primary key default uuid_generate_v4(),
+    job_id uuid not null references split_airdrop_jobs(id),
+    wallet_id uuid not null references CradleWalletAccounts(id),
+    units bigint not null,
+    balance_before bigint,
+    airdropped_at timestamp,
+    airdrop_tx text,
+    last_error text,
+    created_at timestamp not null default now(),
+    unique (job_id, wallet_id)
+);
@@ -1,8 +1,6 @@
-use contract_integrator::wallet::wallet::ActionWallet;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug)]
 pub struct AccountProcessorConfig {
 // TODO: add account specific env variables
-    pub wallet: ActionWallet
 }
\ No newline at end of file
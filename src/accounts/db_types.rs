@@ -1,3 +1,5 @@
+use crate::schema::admin_operators as AdminOperatorsTable;
+use crate::schema::admin_sessions as AdminSessionsTable;
 use crate::schema::cradleaccounts as CradleAccountsTable;
 use crate::schema::cradlewalletaccounts as CradleWalletAccountsTable;
 use chrono::NaiveDateTime;
@@ -98,3 +100,60 @@ pub struct CreateAccountAssetBook {
     pub associated_at: Option<NaiveDateTime>,
     pub kyced_at: Option<NaiveDateTime>,
 }
+
+#[derive(DbEnum, Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[ExistingTypePath = "crate::schema::sql_types::AdminRole"]
+#[serde(rename_all = "lowercase")]
+pub enum AdminRole {
+    /// Can browse the admin dashboard
+    Viewer,
+    /// Can also mint, trade, change prices and act on ramp orders
+    Operator,
+    /// Can also sign off listings, withdrawals, corporate actions and refunds
+    Approver,
+}
+
+/// A person allowed into the admin dashboard, acting as a system account.
+#[derive(Serialize, Deserialize, Queryable, Identifiable, Debug, Clone)]
+#[diesel(table_name = AdminOperatorsTable)]
+pub struct AdminOperatorRecord {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub username: String,
+    #[serde(skip_serializing)]
+    pub password_hash: String,
+    pub role: AdminRole,
+    pub created_at: NaiveDateTime,
+    pub last_login_at: Option<NaiveDateTime>,
+    #[serde(skip_serializing)]
+    pub api_key_hash: Option<String>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = AdminOperatorsTable)]
+pub struct CreateAdminOperator {
+    pub account_id: Uuid,
+    pub username: String,
+    pub password_hash: String,
+    pub role: AdminRole,
+}
+
+#[derive(Queryable, Identifiable, Debug, Clone)]
+#[diesel(table_name = AdminSessionsTable)]
+pub struct AdminSessionRecord {
+    pub id: Uuid,
+    pub operator_id: Uuid,
+    pub token_hash: String,
+    pub csrf_token: String,
+    pub expires_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = AdminSessionsTable)]
+pub struct CreateAdminSession {
+    pub operator_id: Uuid,
+    pub token_hash: String,
+    pub csrf_token: String,
+    pub expires_at: NaiveDateTime,
+}
@@ -1,5 +1,6 @@
 pub mod config;
 pub mod db_types;
 pub mod operations;
+pub mod operators;
 pub mod processor;
 pub mod processor_enums;
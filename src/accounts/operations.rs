@@ -12,22 +12,19 @@ use crate::{
     address_to_id,
     asset_book::db_types::AssetBookRecord,
     schema::accountassetbook,
-    utils::commons::DbConn,
+    utils::{commons::DbConn, wallet::SharedWallet},
 };
 use anyhow::{Result, anyhow};
 use chrono::Utc;
+use contract_integrator::utils::functions::cradle_account_factory::{
+    CradleAccountFactoryFunctionsOutput, CreateAccountInputArgs,
+};
 use contract_integrator::utils::functions::{
     access_controller::{AccessControllerFunctionsInput, AccessControllerFunctionsOutput},
     asset_manager::AssetManagerFunctionOutput,
     cradle_account::{AssociateTokenArgs, CradleAccountFunctionInput, CradleAccountFunctionOutput},
     *,
 };
-use contract_integrator::{
-    utils::functions::cradle_account_factory::{
-        CradleAccountFactoryFunctionsOutput, CreateAccountInputArgs,
-    },
-    wallet::wallet::ActionWallet,
-};
 use diesel::prelude::*;
 use diesel::{
     PgConnection,
@@ -73,7 +70,7 @@ pub async fn register_account_wallet<'a>(
 }
 
 pub async fn create_account_wallet(
-    action_wallet: &mut ActionWallet,
+    action_wallet: &SharedWallet,
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
     args: CreateCradleWalletInputArgs,
 ) -> Result<CradleWalletAccountRecord> {
@@ -205,7 +202,7 @@ pub async fn update_asset_book_record(
 
 pub async fn associate_token(
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
-    wallet: &mut ActionWallet,
+    wallet: &SharedWallet,
     instruction: AssociateTokenToWalletInputArgs,
 ) -> Result<()> {
     let is_associated = {
@@ -274,7 +271,7 @@ pub async fn associate_token(
 
 pub async fn kyc_token(
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
-    wallet: &mut ActionWallet,
+    wallet: &SharedWallet,
     instruction: GrantKYCInputArgs,
 ) -> Result<()> {
     let is_kyced = {
@@ -344,7 +341,7 @@ pub async fn kyc_token(
 }
 
 pub async fn grant_access_to_level(
-    wallet: &mut ActionWallet,
+    wallet: &SharedWallet,
     address: String,
     level: u64,
 ) -> Result<()> {
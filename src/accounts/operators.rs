@@ -0,0 +1,265 @@
+use anyhow::{Result, anyhow};
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier, password_hash::SaltString};
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use rand::{RngCore, rngs::OsRng};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    accounts::db_types::{
+        AdminOperatorRecord, AdminRole, AdminSessionRecord, CradleAccountRecord,
+        CradleAccountStatus, CradleAccountType, CreateAdminOperator, CreateAdminSession,
+    },
+    utils::commons::DbConn,
+};
+
+/// Cookie carrying the admin dashboard's session token.
+pub const SESSION_COOKIE: &str = "cradle_admin_session";
+
+const MIN_PASSWORD_LEN: usize = 12;
+
+/// How long a sign-in lasts, from ADMIN_SESSION_TTL_SECS.
+pub fn session_ttl() -> chrono::Duration {
+    let secs = std::env::var("ADMIN_SESSION_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(8 * 60 * 60);
+    chrono::Duration::seconds(secs)
+}
+
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Sessions are looked up by a digest so a leaked table can't be replayed.
+fn token_digest(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+pub fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| anyhow!("Failed to hash password: {}", e))?
+        .to_string())
+}
+
+fn verify_password(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|parsed| {
+            Argon2::default()
+                .verify_password(password.as_bytes(), &parsed)
+                .is_ok()
+        })
+        .unwrap_or(false)
+}
+
+/// Only active system accounts can be signed in as.
+fn require_system_account(conn: DbConn<'_>, account: Uuid) -> Result<()> {
+    use crate::schema::cradleaccounts::dsl::*;
+
+    let record = cradleaccounts
+        .filter(id.eq(account))
+        .get_result::<CradleAccountRecord>(conn)?;
+    if !matches!(record.account_type, CradleAccountType::System) {
+        return Err(anyhow!("Account {} is not a system account", account));
+    }
+    if matches!(
+        record.status,
+        CradleAccountStatus::Suspended | CradleAccountStatus::Closed
+    ) {
+        return Err(anyhow!("Account {} is {:?}", account, record.status));
+    }
+    Ok(())
+}
+
+/// Let someone into the admin dashboard as the system account `account_id`.
+pub fn create_operator(
+    conn: DbConn<'_>,
+    account_id: Uuid,
+    username: &str,
+    password: &str,
+    role: AdminRole,
+) -> Result<AdminOperatorRecord> {
+    require_system_account(conn, account_id)?;
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(anyhow!(
+            "Passwords need at least {} characters",
+            MIN_PASSWORD_LEN
+        ));
+    }
+
+    let operator = CreateAdminOperator {
+        account_id,
+        username: username.trim().to_lowercase(),
+        password_hash: hash_password(password)?,
+        role,
+    };
+
+    use crate::schema::admin_operators::dsl::*;
+
+    Ok(diesel::insert_into(admin_operators)
+        .values(&operator)
+        .get_result::<AdminOperatorRecord>(conn)?)
+}
+
+/// A signed-in operator, as the dashboard sees them on each request.
+#[derive(Debug, Clone)]
+pub struct AdminSession {
+    pub operator: AdminOperatorRecord,
+    /// Has to come back with every form the session submits
+    pub csrf_token: String,
+    pub expires_at: NaiveDateTime,
+}
+
+impl AdminSession {
+    pub fn can(&self, role: AdminRole) -> bool {
+        self.operator.role >= role
+    }
+}
+
+/// The operator `username` names, if `password` is theirs and their system
+/// account can still act.
+pub fn authenticate(
+    conn: DbConn<'_>,
+    username: &str,
+    password: &str,
+) -> Result<AdminOperatorRecord> {
+    let invalid = || anyhow!("Invalid username or password");
+
+    let operator = crate::schema::admin_operators::table
+        .filter(crate::schema::admin_operators::username.eq(username.trim().to_lowercase()))
+        .get_result::<AdminOperatorRecord>(conn)
+        .optional()?
+        .ok_or_else(invalid)?;
+    if !verify_password(password, &operator.password_hash) {
+        return Err(invalid());
+    }
+    require_system_account(conn, operator.account_id)?;
+    Ok(operator)
+}
+
+/// Check an operator's password and open a session. Returns the token to
+/// hand to the browser; only its digest is stored.
+pub fn login(conn: DbConn<'_>, username: &str, password: &str) -> Result<(String, AdminSession)> {
+    let operator = authenticate(conn, username, password)?;
+
+    let token = random_token();
+    let now = Utc::now().naive_utc();
+    let session = CreateAdminSession {
+        operator_id: operator.id,
+        token_hash: token_digest(&token),
+        csrf_token: random_token(),
+        expires_at: now + session_ttl(),
+    };
+
+    let created = {
+        use crate::schema::admin_sessions::dsl::*;
+
+        diesel::delete(admin_sessions.filter(expires_at.le(now))).execute(conn)?;
+        diesel::insert_into(admin_sessions)
+            .values(&session)
+            .get_result::<AdminSessionRecord>(conn)?
+    };
+    let operator = {
+        use crate::schema::admin_operators::dsl::*;
+
+        diesel::update(admin_operators.find(operator.id))
+            .set(last_login_at.eq(Some(now)))
+            .get_result::<AdminOperatorRecord>(conn)?
+    };
+
+    Ok((
+        token,
+        AdminSession {
+            operator,
+            csrf_token: created.csrf_token,
+            expires_at: created.expires_at,
+        },
+    ))
+}
+
+/// The live session a browser's token belongs to, if any.
+pub fn get_session(conn: DbConn<'_>, token: &str) -> Result<Option<AdminSession>> {
+    use crate::schema::{admin_operators, admin_sessions};
+
+    let found = admin_sessions::table
+        .inner_join(admin_operators::table)
+        .filter(admin_sessions::token_hash.eq(token_digest(token)))
+        .filter(admin_sessions::expires_at.gt(Utc::now().naive_utc()))
+        .get_result::<(AdminSessionRecord, AdminOperatorRecord)>(conn)
+        .optional()?;
+
+    Ok(found.map(|(session, operator)| AdminSession {
+        operator,
+        csrf_token: session.csrf_token,
+        expires_at: session.expires_at,
+    }))
+}
+
+pub fn logout(conn: DbConn<'_>, token: &str) -> Result<()> {
+    use crate::schema::admin_sessions::dsl::*;
+
+    diesel::delete(admin_sessions.filter(token_hash.eq(token_digest(token)))).execute(conn)?;
+    Ok(())
+}
+
+/// Give an operator a key to call the API as themselves, replacing any key
+/// they had. Returns the key; only its digest is stored.
+pub fn issue_api_key(conn: DbConn<'_>, operator_username: &str) -> Result<String> {
+    use crate::schema::admin_operators::dsl::*;
+
+    let key = random_token();
+    let updated = diesel::update(admin_operators)
+        .filter(username.eq(operator_username.trim().to_lowercase()))
+        .set(api_key_hash.eq(Some(token_digest(&key))))
+        .execute(conn)?;
+    if updated == 0 {
+        return Err(anyhow!("Unknown operator {}", operator_username));
+    }
+    Ok(key)
+}
+
+/// The operator an API key was issued to, if it's still theirs and their
+/// system account can still act.
+pub fn operator_for_api_key(conn: DbConn<'_>, key: &str) -> Result<Option<AdminOperatorRecord>> {
+    use crate::schema::admin_operators::dsl::*;
+
+    let Some(operator) = admin_operators
+        .filter(api_key_hash.eq(token_digest(key)))
+        .get_result::<AdminOperatorRecord>(conn)
+        .optional()?
+    else {
+        return Ok(None);
+    };
+    require_system_account(conn, operator.account_id)?;
+    Ok(Some(operator))
+}
+
+/// Fail unless `operator` holds at least `role`. Sessions and API keys are
+/// checked when they're presented; this catches an account suspended since.
+pub fn require_role(
+    conn: DbConn<'_>,
+    operator: &AdminOperatorRecord,
+    role: AdminRole,
+) -> Result<()> {
+    if operator.role < role {
+        return Err(anyhow!(
+            "Operator {} is {:?}; this needs {:?}",
+            operator.username,
+            operator.role,
+            role
+        ));
+    }
+    require_system_account(conn, operator.account_id)
+}
+
+/// Fail unless `operator` may sign off listings, withdrawals, corporate
+/// actions and refunds.
+pub fn require_approver(conn: DbConn<'_>, operator: &AdminOperatorRecord) -> Result<()> {
+    require_role(conn, operator, AdminRole::Approver)
+}
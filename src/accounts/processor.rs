@@ -53,7 +53,7 @@ impl ActionProcessor<AccountProcessorConfig, AccountsProcessorOutput> for Accoun
                         .get_result::<Uuid>(action_conn)?;
 
                     match create_account_wallet(
-                        &mut local_config.wallet,
+                        &app_config.wallet,
                         action_conn,
                         CreateCradleWalletInputArgs {
                             cradle_account_id: account_id,
@@ -88,7 +88,7 @@ impl ActionProcessor<AccountProcessorConfig, AccountsProcessorOutput> for Accoun
                 if let Some(action_conn) = conn {
                     use crate::schema::cradlewalletaccounts::dsl::*;
 
-                    let res = local_config
+                    let res = app_config
                         .wallet
                         .execute(ContractCallInput::CradleAccountFactory(
                             CradleAccountFactoryFunctionsInput::CreateAccount(
@@ -136,8 +136,8 @@ impl ActionProcessor<AccountProcessorConfig, AccountsProcessorOutput> for Accoun
                             AccountsProcessorInput::HandleKYCAssets(wallet_id),
                         );
 
-                        let _ = Box::pin(associate_req.process(app_config.clone())).await?;
-                        let _ = Box::pin(kyc_req.process(app_config.clone())).await?;
+                        let _ = Box::pin(associate_req.process_as(app_config, None)).await?;
+                        let _ = Box::pin(kyc_req.process_as(app_config, None)).await?;
 
                         return Ok(AccountsProcessorOutput::CreateAccountWallet(
                             CreateAccountWalletOutputArgs { id: wallet_id },
@@ -298,7 +298,7 @@ impl ActionProcessor<AccountProcessorConfig, AccountsProcessorOutput> for Accoun
 
                 match associate_token(
                     app_conn,
-                    &mut app_config.wallet,
+                    &app_config.wallet,
                     AssociateTokenToWalletInputArgs {
                         wallet_id: args.wallet_id,
                         token: args.token,
@@ -318,7 +318,7 @@ impl ActionProcessor<AccountProcessorConfig, AccountsProcessorOutput> for Accoun
 
                 match kyc_token(
                     app_conn,
-                    &mut app_config.wallet,
+                    &app_config.wallet,
                     GrantKYCInputArgs {
                         wallet_id: args.wallet_id,
                         token: args.token,
@@ -338,7 +338,7 @@ impl ActionProcessor<AccountProcessorConfig, AccountsProcessorOutput> for Accoun
                     GetWalletInputArgs::ById(args.from.clone()),
                 ));
 
-                let res = Box::pin(wallet_req.process(app_config.clone())).await?;
+                let res = Box::pin(wallet_req.process_as(app_config, None)).await?;
 
                 if let ActionRouterOutput::Accounts(AccountsProcessorOutput::GetWallet(wallet)) =
                     res
@@ -348,7 +348,7 @@ impl ActionProcessor<AccountProcessorConfig, AccountsProcessorOutput> for Accoun
                             unimplemented!("TODO: Fiat support will be added with opretium later")
                         }
                         WithdrawalType::Crypto => {
-                            let res = local_config
+                            let res = app_config
                                 .wallet
                                 .execute(ContractCallInput::CradleAccount(
                                     CradleAccountFunctionInput::Withdraw(WithdrawArgs {
@@ -408,7 +408,7 @@ impl ActionProcessor<AccountProcessorConfig, AccountsProcessorOutput> for Accoun
                         };
                         associate_token(
                             action_conn,
-                            &mut app_config.wallet,
+                            &app_config.wallet,
                             AssociateTokenToWalletInputArgs {
                                 wallet_id: wallet.id,
                                 token: token.id,
@@ -453,7 +453,7 @@ impl ActionProcessor<AccountProcessorConfig, AccountsProcessorOutput> for Accoun
                         };
                         kyc_token(
                             action_conn,
-                            &mut app_config.wallet,
+                            &app_config.wallet,
                             GrantKYCInputArgs {
                                 wallet_id: wallet_id.clone(),
                                 token: token.id,
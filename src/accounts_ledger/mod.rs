@@ -3,4 +3,5 @@ pub mod db_types;
 pub mod operations;
 pub mod processor;
 pub mod processor_enums;
+pub mod reconciliation;
 pub mod sql_queries;
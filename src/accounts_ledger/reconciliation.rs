@@ -0,0 +1,183 @@
+use anyhow::{Result, anyhow};
+use bigdecimal::BigDecimal;
+use contract_integrator::{
+    hedera::TokenId, utils::functions::commons, wallet::wallet::ActionWallet,
+};
+use diesel::prelude::*;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::accounts::db_types::CradleWalletAccountRecord;
+use crate::accounts_ledger::sql_queries::{get_deductions, get_ledger_net};
+use crate::asset_book::db_types::AssetBookRecord;
+use crate::utils::commons::DbConn;
+
+/// How much a difference between the chain and the database matters, least
+/// first.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Ok,
+    /// Tokens arrived outside the ledger, e.g. faucet airdrops
+    Info,
+    /// Tokens left the wallet without a ledger entry, or the chain couldn't
+    /// be read
+    Warning,
+    /// The wallet holds less than what's locked for its orders and loans
+    Critical,
+}
+
+/// One wallet's balance of one associated asset, on-chain and as the
+/// database sees it, in the token's smallest unit.
+#[derive(Serialize, Debug, Clone)]
+pub struct BalanceCheck {
+    pub wallet_id: Uuid,
+    pub address: String,
+    pub asset_id: Uuid,
+    pub symbol: String,
+    /// None when the chain couldn't be read
+    pub onchain: Option<BigDecimal>,
+    /// Locked for open orders and loan collateral
+    pub locked: BigDecimal,
+    /// Ledger inflows less outflows
+    pub ledger_net: BigDecimal,
+    pub severity: Severity,
+    pub note: String,
+}
+
+/// Differences within `tolerance` count as matching.
+pub fn classify(
+    onchain: &BigDecimal,
+    locked: &BigDecimal,
+    ledger_net: &BigDecimal,
+    tolerance: &BigDecimal,
+) -> (Severity, String) {
+    if onchain + tolerance < *locked {
+        return (
+            Severity::Critical,
+            format!("{} locked but only {} held", locked, onchain),
+        );
+    }
+
+    let diff = onchain - ledger_net;
+    if diff.abs() <= *tolerance {
+        (Severity::Ok, String::new())
+    } else if diff < BigDecimal::from(0) {
+        (
+            Severity::Warning,
+            format!("{} less than the ledger accounts for", diff.abs()),
+        )
+    } else {
+        (
+            Severity::Info,
+            format!("{} more than the ledger accounts for", diff),
+        )
+    }
+}
+
+/// Wallets to check: one, or every wallet.
+pub fn wallets(conn: DbConn<'_>, wallet: Option<Uuid>) -> Result<Vec<CradleWalletAccountRecord>> {
+    use crate::schema::cradlewalletaccounts::dsl::*;
+
+    let mut query = cradlewalletaccounts.into_boxed();
+    if let Some(wallet) = wallet {
+        query = query.filter(id.eq(wallet));
+    }
+    let found = query
+        .order(created_at.asc())
+        .get_results::<CradleWalletAccountRecord>(conn)?;
+    if let (Some(wallet), true) = (wallet, found.is_empty()) {
+        return Err(anyhow!("Wallet not found: {}", wallet));
+    }
+    Ok(found)
+}
+
+/// Compare each of a wallet's associated assets on-chain with its locks and
+/// ledger history. A failed chain read is reported on every asset rather
+/// than failing the run.
+pub async fn check_wallet(
+    conn: DbConn<'_>,
+    action_wallet: &ActionWallet,
+    wallet: &CradleWalletAccountRecord,
+    tolerance: &BigDecimal,
+) -> Result<Vec<BalanceCheck>> {
+    let assets = {
+        use crate::schema::accountassetbook::dsl as aab;
+        use crate::schema::asset_book::dsl as ab;
+
+        aab::accountassetbook
+            .inner_join(ab::asset_book)
+            .filter(aab::account_id.eq(wallet.id))
+            .filter(aab::associated.eq(true))
+            .select(ab::asset_book::all_columns())
+            .get_results::<AssetBookRecord>(conn)?
+    };
+    if assets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let balances = commons::get_account_balances(&action_wallet.client, &wallet.contract_id)
+        .await
+        .map_err(|e| e.to_string());
+
+    let mut checks = Vec::with_capacity(assets.len());
+    for asset in assets {
+        let locked = get_deductions(conn, wallet.address.clone(), asset.id)?.total;
+        let ledger_net = get_ledger_net(conn, wallet.address.clone(), asset.id)?.total;
+
+        let onchain = match (&balances, TokenId::from_solidity_address(&asset.token)) {
+            (Ok(balances), Ok(token)) => {
+                Ok(BigDecimal::from(*balances.tokens.get(&token).unwrap_or(&0)))
+            }
+            (Err(e), _) => Err(format!("Chain read failed: {}", e)),
+            (_, Err(e)) => Err(format!("Invalid token {}: {:?}", asset.token, e)),
+        };
+
+        let (onchain, severity, note) = match onchain {
+            Ok(balance) => {
+                let (severity, note) = classify(&balance, &locked, &ledger_net, tolerance);
+                (Some(balance), severity, note)
+            }
+            Err(note) => (None, Severity::Warning, note),
+        };
+
+        checks.push(BalanceCheck {
+            wallet_id: wallet.id,
+            address: wallet.address.clone(),
+            asset_id: asset.id,
+            symbol: asset.symbol,
+            onchain,
+            locked,
+            ledger_net,
+            severity,
+            note,
+        });
+    }
+    Ok(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(v: i64) -> BigDecimal {
+        BigDecimal::from(v)
+    }
+
+    #[test]
+    fn holding_less_than_is_locked_is_critical() {
+        let (severity, _) = classify(&d(50), &d(80), &d(50), &d(0));
+        assert_eq!(severity, Severity::Critical);
+    }
+
+    #[test]
+    fn ledger_differences_are_graded_by_direction() {
+        assert_eq!(classify(&d(100), &d(10), &d(100), &d(0)).0, Severity::Ok);
+        assert_eq!(
+            classify(&d(90), &d(10), &d(100), &d(0)).0,
+            Severity::Warning
+        );
+        assert_eq!(classify(&d(110), &d(10), &d(100), &d(0)).0, Severity::Info);
+        assert_eq!(classify(&d(98), &d(10), &d(100), &d(5)).0, Severity::Ok);
+    }
+}
@@ -59,3 +59,29 @@ pub fn get_deductions(
 
     Ok(res)
 }
+
+const LEDGER_NET_QUERY: &str = r"
+SELECT
+    COALESCE(SUM(CASE WHEN to_address = $1 THEN amount ELSE 0 END), 0)
+    - COALESCE(SUM(CASE WHEN from_address = $1 THEN amount ELSE 0 END), 0) AS total
+FROM accountassetsledger
+WHERE asset = $2
+  AND (to_address = $1 OR from_address = $1)
+  AND transaction_type NOT IN ('lock', 'unlock')
+  AND to_address <> from_address;
+";
+
+/// What the ledger says has moved into an address less what has moved out,
+/// leaving out locks, which don't move tokens.
+pub fn get_ledger_net(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    address: String,
+    asset: Uuid,
+) -> Result<DeductionResult> {
+    let res = diesel::sql_query(LEDGER_NET_QUERY)
+        .bind::<diesel::sql_types::Text, _>(address)
+        .bind::<diesel::sql_types::Uuid, _>(asset)
+        .get_result::<DeductionResult>(conn)?;
+
+    Ok(res)
+}
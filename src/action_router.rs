@@ -1,4 +1,5 @@
 use crate::accounts::config::AccountProcessorConfig;
+use crate::accounts::db_types::AdminOperatorRecord;
 use crate::accounts::processor_enums::{AccountsProcessorInput, AccountsProcessorOutput};
 use crate::asset_book::config::AssetBookConfig;
 use crate::asset_book::processor_enums::{AssetBookProcessorInput, AssetBookProcessorOutput};
@@ -41,20 +42,24 @@ pub enum ActionRouterOutput {
 }
 
 impl ActionRouterInput {
-    pub async fn process(&self, app_config: AppConfig) -> Result<ActionRouterOutput> {
+    pub async fn process(&self, mut app_config: AppConfig) -> Result<ActionRouterOutput> {
+        self.process_as(&mut app_config, None).await
+    }
+
+    /// Run the action as an authenticated operator, which approvals need.
+    /// The processors work on `app_config` itself, wallet included, rather
+    /// than a copy of it.
+    pub async fn process_as(
+        &self,
+        app_config: &mut AppConfig,
+        operator: Option<AdminOperatorRecord>,
+    ) -> Result<ActionRouterOutput> {
         match self {
             ActionRouterInput::Accounts(processor) => {
                 let mut conn = get_conn(app_config.pool.clone())?;
-                // Reuse the wallet from app_config instead of creating a new one per request.
-                // ActionWallet::from_env() is extremely expensive (parses crypto keys,
-                // initializes a new Hedera Client with network connections).
-                let mut processor_config = AccountProcessorConfig { wallet: app_config.wallet.clone() };
+                let mut processor_config = AccountProcessorConfig {};
                 let res = processor
-                    .process(
-                        &mut app_config.clone(),
-                        &mut processor_config,
-                        Some(&mut conn),
-                    )
+                    .process(app_config, &mut processor_config, Some(&mut conn))
                     .await?;
                 Ok(ActionRouterOutput::Accounts(res))
             }
@@ -64,7 +69,7 @@ impl ActionRouterInput {
                 let mut config = AssetBookConfig {};
 
                 let res = processor
-                    .process(&mut app_config.clone(), &mut config, Some(&mut conn))
+                    .process(app_config, &mut config, Some(&mut conn))
                     .await?;
 
                 Ok(ActionRouterOutput::AssetBook(res))
@@ -75,7 +80,7 @@ impl ActionRouterInput {
                 let mut config = crate::market::config::MarketsConfig {};
 
                 let res = processor
-                    .process(&mut app_config.clone(), &mut config, Some(&mut conn))
+                    .process(app_config, &mut config, Some(&mut conn))
                     .await?;
 
                 Ok(ActionRouterOutput::Markets(res))
@@ -86,7 +91,7 @@ impl ActionRouterInput {
                 let mut config = MarketTimeSeriesConfig {};
 
                 let res = processor
-                    .process(&mut app_config.clone(), &mut config, Some(&mut conn))
+                    .process(app_config, &mut config, Some(&mut conn))
                     .await?;
 
                 Ok(ActionRouterOutput::MarketTimeSeries(res))
@@ -97,7 +102,7 @@ impl ActionRouterInput {
                 let mut config = crate::order_book::config::OrderBookConfig {};
 
                 let res = processor
-                    .process(&mut app_config.clone(), &mut config, Some(&mut conn))
+                    .process(app_config, &mut config, Some(&mut conn))
                     .await?;
 
                 Ok(ActionRouterOutput::OrderBook(res))
@@ -108,7 +113,7 @@ impl ActionRouterInput {
                 let mut config = crate::lending_pool::config::LendingPoolConfig {};
 
                 let res = processor
-                    .process(&mut app_config.clone(), &mut config, Some(&mut conn))
+                    .process(app_config, &mut config, Some(&mut conn))
                     .await?;
 
                 Ok(ActionRouterOutput::Pool(res))
@@ -116,10 +121,10 @@ impl ActionRouterInput {
             ActionRouterInput::Listing(processor) => {
                 let mut conn = get_conn(app_config.pool.clone())?;
 
-                let mut config = crate::listing::config::CradleNativeListingsConfig {};
+                let mut config = crate::listing::config::CradleNativeListingsConfig { operator };
 
                 let res = processor
-                    .process(&mut app_config.clone(), &mut config, Some(&mut conn))
+                    .process(app_config, &mut config, Some(&mut conn))
                     .await?;
 
                 Ok(ActionRouterOutput::Listing(res))
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use cradle_back_end::accounts_ledger::db_types::{AccountLedgerTransactionType, LedgerRow};
+use cradle_back_end::lending_pool::db_types::{LoanRecord, LoanStatus};
+use cradle_back_end::lending_pool::liquidations::{LoanHealth, loan_health};
+use cradle_back_end::order_book::db_types::{OrderBookRecord, OrderBookTradeRecord, OrderStatus};
+use cradle_back_end::ramper::db_types::RampOrderRow;
+use cradle_back_end::utils::commons::DbConn;
+
+/// Rows shown per section; the drill-down is for support, not exports.
+const SECTION_LIMIT: i64 = 100;
+
+/// The views of one wallet on its detail page, each loaded when picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    Ledger,
+    Orders,
+    Trades,
+    Loans,
+    Listings,
+    Ramps,
+}
+
+impl Section {
+    pub const ALL: [Section; 6] = [
+        Section::Ledger,
+        Section::Orders,
+        Section::Trades,
+        Section::Loans,
+        Section::Listings,
+        Section::Ramps,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Section::Ledger => "ledger",
+            Section::Orders => "orders",
+            Section::Trades => "trades",
+            Section::Loans => "loans",
+            Section::Listings => "listings",
+            Section::Ramps => "ramps",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Section::Ledger => "Ledger",
+            Section::Orders => "Open Orders",
+            Section::Trades => "Trades",
+            Section::Loans => "Loans",
+            Section::Listings => "Listing Purchases",
+            Section::Ramps => "Ramp Orders",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Section> {
+        Self::ALL.into_iter().find(|s| s.as_str() == value)
+    }
+}
+
+/// A fill on one of the wallet's orders.
+pub struct WalletTrade {
+    pub trade: OrderBookTradeRecord,
+    /// The wallet's side of the trade
+    pub order: OrderBookRecord,
+    pub maker: bool,
+    pub market: String,
+}
+
+pub struct WalletLoan {
+    pub loan: LoanRecord,
+    /// Only worked out for active loans with an oracle price
+    pub health: Option<LoanHealth>,
+}
+
+pub fn asset_symbols(conn: DbConn<'_>) -> Result<HashMap<Uuid, String>> {
+    use cradle_back_end::schema::asset_book::dsl::*;
+
+    Ok(asset_book
+        .select((id, symbol))
+        .get_results::<(Uuid, String)>(conn)?
+        .into_iter()
+        .collect())
+}
+
+/// Ledger entries to or from the wallet's address, newest first, optionally
+/// only of some kinds.
+pub fn ledger(
+    conn: DbConn<'_>,
+    address: &str,
+    kinds: Option<&[AccountLedgerTransactionType]>,
+) -> Result<Vec<LedgerRow>> {
+    use cradle_back_end::schema::accountassetsledger::dsl::*;
+
+    let mut query = accountassetsledger
+        .filter(from_address.eq(address).or(to_address.eq(address)))
+        .into_boxed();
+    if let Some(kinds) = kinds {
+        query = query.filter(transaction_type.eq_any(kinds.to_vec()));
+    }
+
+    Ok(query
+        .order(timestamp.desc())
+        .limit(SECTION_LIMIT)
+        .get_results::<LedgerRow>(conn)?)
+}
+
+/// Open orders with the quote asset of their market, which the order table
+/// needs to tell buys from sells.
+pub fn open_orders(conn: DbConn<'_>, wallet_id: Uuid) -> Result<Vec<(OrderBookRecord, Uuid)>> {
+    let orders = {
+        use cradle_back_end::schema::orderbook::dsl::*;
+
+        orderbook
+            .filter(wallet.eq(wallet_id))
+            .filter(status.eq(OrderStatus::Open))
+            .order(created_at.desc())
+            .limit(SECTION_LIMIT)
+            .get_results::<OrderBookRecord>(conn)?
+    };
+
+    let quotes: HashMap<Uuid, Uuid> = {
+        use cradle_back_end::schema::markets::dsl::*;
+
+        markets
+            .filter(id.eq_any(orders.iter().map(|o| o.market_id).collect::<Vec<_>>()))
+            .select((id, asset_two))
+            .get_results::<(Uuid, Uuid)>(conn)?
+            .into_iter()
+            .collect()
+    };
+
+    Ok(orders
+        .into_iter()
+        .filter_map(|o| {
+            let quote = *quotes.get(&o.market_id)?;
+            Some((o, quote))
+        })
+        .collect())
+}
+
+/// Trades either side of which is one of the wallet's orders, newest first.
+pub fn trades(conn: DbConn<'_>, wallet_id: Uuid) -> Result<Vec<WalletTrade>> {
+    let orders: HashMap<Uuid, OrderBookRecord> = {
+        use cradle_back_end::schema::orderbook::dsl::*;
+
+        orderbook
+            .filter(wallet.eq(wallet_id))
+            .get_results::<OrderBookRecord>(conn)?
+            .into_iter()
+            .map(|o| (o.id, o))
+            .collect()
+    };
+    if orders.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let order_ids: Vec<Uuid> = orders.keys().cloned().collect();
+    let fills = {
+        use cradle_back_end::schema::orderbooktrades::dsl::*;
+
+        orderbooktrades
+            .filter(
+                maker_order_id
+                    .eq_any(order_ids.clone())
+                    .or(taker_order_id.eq_any(order_ids)),
+            )
+            .order(created_at.desc())
+            .limit(SECTION_LIMIT)
+            .get_results::<OrderBookTradeRecord>(conn)?
+    };
+
+    let names: HashMap<Uuid, String> = {
+        use cradle_back_end::schema::markets::dsl::*;
+
+        markets
+            .select((id, name))
+            .get_results::<(Uuid, String)>(conn)?
+            .into_iter()
+            .collect()
+    };
+
+    Ok(fills
+        .into_iter()
+        .filter_map(|trade| {
+            let maker = orders.contains_key(&trade.maker_order_id);
+            let own = if maker {
+                trade.maker_order_id
+            } else {
+                trade.taker_order_id
+            };
+            let order = orders.get(&own)?.clone();
+            Some(WalletTrade {
+                market: names.get(&order.market_id).cloned().unwrap_or_default(),
+                trade,
+                order,
+                maker,
+            })
+        })
+        .collect())
+}
+
+pub fn loans(conn: DbConn<'_>, wallet: Uuid) -> Result<Vec<WalletLoan>> {
+    let found = {
+        use cradle_back_end::schema::loans::dsl::*;
+
+        loans
+            .filter(wallet_id.eq(wallet))
+            .order(created_at.desc())
+            .limit(SECTION_LIMIT)
+            .get_results::<LoanRecord>(conn)?
+    };
+
+    let mut result = Vec::with_capacity(found.len());
+    for loan in found {
+        let health = if matches!(loan.status, LoanStatus::Active) {
+            loan_health(conn, loan.clone())?
+        } else {
+            None
+        };
+        result.push(WalletLoan { loan, health });
+    }
+    Ok(result)
+}
+
+pub fn ramp_orders(conn: DbConn<'_>, wallet: Uuid) -> Result<Vec<RampOrderRow>> {
+    use cradle_back_end::schema::ramp_orders::dsl::*;
+
+    Ok(ramp_orders
+        .filter(wallet_id.eq(wallet))
+        .order(created_at.desc())
+        .limit(SECTION_LIMIT)
+        .get_results::<RampOrderRow>(conn)?)
+}
@@ -0,0 +1,223 @@
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{Html, IntoResponse, Redirect, Response},
+    Form,
+};
+use serde::Deserialize;
+
+use cradle_back_end::accounts::db_types::AdminRole;
+use cradle_back_end::accounts::operators::{
+    AdminSession, SESSION_COOKIE, get_session, login, logout, session_ttl,
+};
+
+use super::{templates, AppState};
+
+/// Header htmx sends the session's CSRF token in, set up by `base_layout`.
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+fn session_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, value)| value.to_string())
+}
+
+/// Only for running the dashboard locally over plain http; everywhere else
+/// the session cookie is `Secure`.
+pub fn insecure_cookie() -> bool {
+    std::env::var("ADMIN_UI_INSECURE_COOKIE")
+        .map(|v| v == "true")
+        .unwrap_or(false)
+}
+
+fn session_cookie(token: &str, max_age: i64) -> String {
+    let secure = !insecure_cookie();
+    format!(
+        "{}={}; Path=/; HttpOnly; SameSite=Strict; Max-Age={}{}",
+        SESSION_COOKIE,
+        token,
+        max_age,
+        if secure { "; Secure" } else { "" }
+    )
+}
+
+/// Send the browser to the login page; htmx requests get told to navigate
+/// instead of swapping the page into a fragment.
+fn to_login(headers: &HeaderMap) -> Response {
+    if headers.contains_key("hx-request") {
+        return (StatusCode::UNAUTHORIZED, [("HX-Redirect", "/login")]).into_response();
+    }
+    Redirect::to("/login").into_response()
+}
+
+/// A blocking auth task that panicked or was cancelled.
+fn task_failed(what: &str, e: tokio::task::JoinError) -> Response {
+    eprintln!("[AUTH] {} task failed: {:?}", what, e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Html(format!("<div class='text-red-400'>{} failed</div>", what)),
+    )
+        .into_response()
+}
+
+fn csrf_matches(headers: &HeaderMap, session: &AdminSession) -> bool {
+    let Some(sent) = headers.get(CSRF_HEADER).and_then(|v| v.to_str().ok()) else {
+        return false;
+    };
+    // compare without bailing on the first difference
+    sent.len() == session.csrf_token.len()
+        && sent
+            .bytes()
+            .zip(session.csrf_token.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// Every dashboard route needs a signed-in operator, and anything that
+/// isn't a plain read has to carry the session's CSRF token. The session is
+/// left in the request's extensions for handlers and `require_operator`.
+pub async fn require_session(
+    State(state): State<AppState>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let Some(token) = session_token(req.headers()) else {
+        return to_login(req.headers());
+    };
+
+    let pool = state.pool.clone();
+    let session = match tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        get_session(&mut conn, &token)
+    })
+    .await
+    {
+        Ok(session) => session,
+        Err(e) => return task_failed("Session lookup", e),
+    };
+    let session = match session {
+        Ok(Some(session)) => session,
+        Ok(None) => return to_login(req.headers()),
+        Err(e) => {
+            eprintln!("[AUTH] Session lookup failed: {:?}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Html("<div class='text-red-400'>Session lookup failed</div>".to_string()),
+            )
+                .into_response();
+        }
+    };
+
+    if !matches!(*req.method(), Method::GET | Method::HEAD) && !csrf_matches(req.headers(), &session) {
+        return (
+            StatusCode::FORBIDDEN,
+            Html("<div class='text-red-400'>Missing or invalid CSRF token; reload the page</div>".to_string()),
+        )
+            .into_response();
+    }
+
+    req.extensions_mut().insert(session);
+    next.run(req).await
+}
+
+/// Guard for handlers that mint, trade, move funds or change prices.
+pub async fn require_operator(req: Request, next: Next) -> Response {
+    let allowed = req
+        .extensions()
+        .get::<AdminSession>()
+        .is_some_and(|session| session.can(AdminRole::Operator));
+    if !allowed {
+        return (
+            StatusCode::FORBIDDEN,
+            Html("<div class='text-red-400'>Your role can only view the dashboard</div>".to_string()),
+        )
+            .into_response();
+    }
+    next.run(req).await
+}
+
+#[derive(Deserialize)]
+pub struct LoginForm {
+    username: String,
+    password: String,
+}
+
+pub async fn login_page_handler() -> Html<String> {
+    Html(templates::login_page(None))
+}
+
+pub async fn login_handler(
+    State(state): State<AppState>,
+    Form(form): Form<LoginForm>,
+) -> Response {
+    let pool = state.pool.clone();
+    let result = match tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        login(&mut conn, &form.username, &form.password)
+    })
+    .await
+    {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("[AUTH] Sign-in task failed: {:?}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Html(templates::login_page(Some("Sign-in failed; try again"))),
+            )
+                .into_response();
+        }
+    };
+
+    match result {
+        Ok((token, session)) => {
+            eprintln!(
+                "[AUTH] {} signed in as {:?}",
+                session.operator.username, session.operator.role
+            );
+            let cookie = session_cookie(&token, session_ttl().num_seconds());
+            let mut res = Redirect::to("/").into_response();
+            if let Ok(value) = HeaderValue::from_str(&cookie) {
+                res.headers_mut().insert(header::SET_COOKIE, value);
+            }
+            res
+        }
+        Err(e) => {
+            eprintln!("[AUTH] Sign-in failed: {:?}", e);
+            (
+                StatusCode::UNAUTHORIZED,
+                Html(templates::login_page(Some("Invalid username or password"))),
+            )
+                .into_response()
+        }
+    }
+}
+
+pub async fn logout_handler(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Some(token) = session_token(&headers) {
+        let pool = state.pool.clone();
+        let result = match tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            logout(&mut conn, &token)
+        })
+        .await
+        {
+            Ok(result) => result,
+            Err(e) => return task_failed("Sign-out", e),
+        };
+        if let Err(e) = result {
+            eprintln!("[AUTH] Sign-out failed: {:?}", e);
+        }
+    }
+
+    let mut res = (StatusCode::OK, [("HX-Redirect", "/login")]).into_response();
+    if let Ok(value) = HeaderValue::from_str(&session_cookie("", 0)) {
+        res.headers_mut().insert(header::SET_COOKIE, value);
+    }
+    res
+}
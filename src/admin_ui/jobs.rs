@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{NaiveDateTime, Utc};
+use uuid::Uuid;
+
+/// Finished jobs kept around for the progress panel to show.
+const KEEP_FINISHED: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TargetStatus {
+    Pending,
+    /// Working on it; the text says which step
+    Running(String),
+    Done(String),
+    Failed(String),
+    /// Cancelled partway through; the text says how far it got
+    Stopped(String),
+    /// Never started because the job was cancelled
+    Skipped,
+}
+
+#[derive(Debug, Clone)]
+pub struct JobTarget {
+    pub label: String,
+    pub status: TargetStatus,
+}
+
+/// Work the dashboard runs after the request that started it has returned.
+/// Held in memory, so a restart forgets it.
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub id: Uuid,
+    pub kind: &'static str,
+    pub started_by: String,
+    pub started_at: NaiveDateTime,
+    pub finished_at: Option<NaiveDateTime>,
+    pub cancel_requested: bool,
+    pub targets: Vec<JobTarget>,
+}
+
+impl Job {
+    pub fn finished(&self) -> bool {
+        self.finished_at.is_some()
+    }
+
+    /// Targets that are no longer pending or running.
+    pub fn completed(&self) -> usize {
+        self.targets
+            .iter()
+            .filter(|t| !matches!(t.status, TargetStatus::Pending | TargetStatus::Running(_)))
+            .count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.targets
+            .iter()
+            .filter(|t| matches!(t.status, TargetStatus::Failed(_)))
+            .count()
+    }
+}
+
+#[derive(Default)]
+pub struct Jobs {
+    jobs: Mutex<HashMap<Uuid, Job>>,
+}
+
+impl Jobs {
+    /// Register a job with one target per label, all pending.
+    pub fn start(&self, kind: &'static str, started_by: &str, labels: Vec<String>) -> Uuid {
+        let job = Job {
+            id: Uuid::new_v4(),
+            kind,
+            started_by: started_by.to_string(),
+            started_at: Utc::now().naive_utc(),
+            finished_at: None,
+            cancel_requested: false,
+            targets: labels
+                .into_iter()
+                .map(|label| JobTarget {
+                    label,
+                    status: TargetStatus::Pending,
+                })
+                .collect(),
+        };
+        let id = job.id;
+
+        let mut jobs = self.jobs.lock().unwrap();
+        jobs.insert(id, job);
+
+        // forget the oldest finished jobs once there are too many
+        let mut finished: Vec<(NaiveDateTime, Uuid)> = jobs
+            .values()
+            .filter_map(|j| j.finished_at.map(|at| (at, j.id)))
+            .collect();
+        if finished.len() > KEEP_FINISHED {
+            finished.sort();
+            for (_, old) in &finished[..finished.len() - KEEP_FINISHED] {
+                jobs.remove(old);
+            }
+        }
+
+        id
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<Job> {
+        self.jobs.lock().unwrap().get(&id).cloned()
+    }
+
+    /// The latest jobs of one kind, newest first.
+    pub fn recent(&self, kind: &str, limit: usize) -> Vec<Job> {
+        let mut found: Vec<Job> = self
+            .jobs
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|job| job.kind == kind)
+            .cloned()
+            .collect();
+        found.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        found.truncate(limit);
+        found
+    }
+
+    pub fn set_status(&self, id: Uuid, target: usize, status: TargetStatus) {
+        if let Some(t) = self
+            .jobs
+            .lock()
+            .unwrap()
+            .get_mut(&id)
+            .and_then(|job| job.targets.get_mut(target))
+        {
+            t.status = status;
+        }
+    }
+
+    /// Ask the job to stop the next time it checks, between targets or
+    /// between chunks of one. Returns false if it already finished or
+    /// doesn't exist.
+    pub fn cancel(&self, id: Uuid) -> bool {
+        match self.jobs.lock().unwrap().get_mut(&id) {
+            Some(job) if !job.finished() => {
+                job.cancel_requested = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn cancel_requested(&self, id: Uuid) -> bool {
+        self.jobs
+            .lock()
+            .unwrap()
+            .get(&id)
+            .is_some_and(|job| job.cancel_requested)
+    }
+
+    /// Mark the job done; anything still pending was skipped.
+    pub fn finish(&self, id: Uuid) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(&id) {
+            for t in job.targets.iter_mut() {
+                if t.status == TargetStatus::Pending {
+                    t.status = TargetStatus::Skipped;
+                }
+            }
+            job.finished_at = Some(Utc::now().naive_utc());
+        }
+    }
+}
@@ -1,8 +1,9 @@
 use axum::{
     extract::{Path, Query, State},
+    middleware,
     response::{Html, IntoResponse},
     routing::{get, post},
-    Form, Router,
+    Extension, Form, Router,
 };
 use serde::Deserialize;
 use std::sync::Arc;
@@ -11,34 +12,57 @@ use bigdecimal::{BigDecimal, ToPrimitive};
 use std::str::FromStr;
 
 use cradle_back_end::utils::app_config::AppConfig;
+use cradle_back_end::accounts::operators::AdminSession;
 use cradle_back_end::accounts::db_types::{CradleWalletAccountRecord, CreateCradleAccount, CradleAccountType, CradleAccountStatus};
 use cradle_back_end::market::processor_enums::MarketProcessorInput;
 use cradle_back_end::market::db_types::MarketRecord;
+use cradle_back_end::market_time_series::db_types::TimeSeriesInterval;
+use cradle_back_end::market_time_series::processor_enum::{
+    GetHistoryInputArgs, MarketTimeSeriesProcessorInput, MarketTimeSeriesProcessorOutput,
+};
+use cradle_back_end::order_book::processor_enums::{
+    CancelOrderInputArgs, ModifyOrderInputArgs, OrderBookProcessorInput, OrderBookProcessorOutput,
+};
 use cradle_back_end::action_router::{ActionRouterInput, ActionRouterOutput};
 use cradle_back_end::cli_helper::call_action_router;
+use cradle_back_end::audit_log::operations::{AuditLogFilter, get_audit_log, process_audited, record_mutation};
 
 // Ops for Faucet/OnRamp
 use cradle_back_end::ramper::{Ramper, OnRampRequest};
+use cradle_back_end::ramper::providers::RampProvider;
+use cradle_back_end::ramper::quotes::{RampQuoteInputArgs, create_quote};
+use cradle_back_end::health::health_report;
+use cradle_back_end::api::error::ApiError;
+use cradle_back_end::aggregators::{AggregatorsConfig, AggregatorsProcessorInput, AggregatorsProcessorOutput, BackfillInputArgs};
+use cradle_back_end::aggregators::checkpoint::get_last_checkpoint;
+use cradle_back_end::utils::traits::ActionProcessor;
+use cradle_back_end::ramper::admin::{RampAdminActionInputArgs, cancel_order, ramp_summary, retry_order};
 use cradle_back_end::accounts::operations::{associate_token, kyc_token};
 use cradle_back_end::accounts::processor_enums::{AssociateTokenToWalletInputArgs, GrantKYCInputArgs};
-use cradle_back_end::asset_book::operations::{get_asset, get_wallet, mint_asset};
+use cradle_back_end::asset_book::operations::mint_asset;
+use cradle_back_end::utils::wallet::SharedWallet;
 use contract_integrator::utils::functions::{
     ContractCallInput,
     asset_manager::{AirdropArgs, AssetManagerFunctionInput},
-    commons::{ContractFunctionProcessor, get_account_balances},
+    commons::get_account_balances,
 };
 
 // Lending pool ops
 use cradle_back_end::lending_pool::db_types::{LendingPoolRecord, LoanRecord};
 use cradle_back_end::lending_pool::processor_enums::{
-    LendingPoolFunctionsInput, SupplyLiquidityInputArgs, WithdrawLiquidityInputArgs,
-    TakeLoanInputArgs, RepayLoanInputArgs
+    LendingPoolFunctionsInput, LendingPoolFunctionsOutput, SupplyLiquidityInputArgs, WithdrawLiquidityInputArgs,
+    TakeLoanInputArgs, RepayLoanInputArgs, LiquidatePositionInputArgs, DeployLendingPoolInputArgs
 };
-use cradle_back_end::lending_pool::operations::{get_pool_stats, get_pool_deposit_position, get_loan_position};
+use cradle_back_end::lending_pool::operations::{
+    get_pool_stats, get_pool_deposit_position, get_loan_position, CreateLendingPoolArgs, CreateNewYieldAsset, YieldAsset
+};
+use cradle_back_end::lending_pool::liquidations::{DEFAULT_AT_RISK_HEALTH, at_risk_loans, loan_health};
 
 // Listing ops
 use cradle_back_end::listing::db_types::{CompanyRow, CradleNativeListingRow, ListingStatus};
-use cradle_back_end::listing::processor_enums::CradleNativeListingFunctionsInput;
+use cradle_back_end::listing::processor_enums::{
+    CradleNativeListingFunctionsInput, CradleNativeListingFunctionsOutput,
+};
 use cradle_back_end::listing::operations::{
     AssetDetails, GetPurchaseFeeInputArgs, CreateCompanyInputArgs,
     CreateListingInputArgs, PurchaseListingAssetInputArgs,
@@ -49,43 +73,153 @@ use cradle_back_end::listing::operations::{
 use cradle_back_end::lending_pool::oracle::publish_price;
 use cradle_back_end::lending_pool::operations::get_pool;
 
+mod account_detail;
+mod auth;
+mod jobs;
 mod templates;
 
+use jobs::{Jobs, TargetStatus};
+
 #[derive(Clone)]
 pub struct AppState {
-    pub config: Arc<AppConfig>,
+    /// Database reads go straight to the pool
+    pub pool: diesel::r2d2::Pool<diesel::r2d2::ConnectionManager<diesel::PgConnection>>,
+    /// Shared by every handler. Its wallet takes contract calls one at a
+    /// time, so a handler only waits while another's call is in flight.
+    pub config: AppConfig,
+    pub jobs: Arc<Jobs>,
+}
+
+/// Where the page's scripts reach the API's Socket.IO server. Without
+/// ADMIN_SOCKET_KEY only the public market namespace is followed.
+pub struct SocketSettings {
+    pub api_url: String,
+    pub admin_key: Option<String>,
+}
+
+impl SocketSettings {
+    pub fn from_env() -> Self {
+        Self {
+            api_url: std::env::var("CRADLE_API_URL").unwrap_or_else(|_| "http://localhost:6969".to_string()),
+            admin_key: std::env::var("ADMIN_SOCKET_KEY").ok(),
+        }
+    }
+}
+
+/// How the audit log tells dashboard mutations from API ones.
+const AUDIT_SOURCE: &str = "admin_ui";
+
+/// Mutations from the dashboard go through here so the audit log has the
+/// operator's name on them.
+async fn run_action(
+    state: &AppState,
+    session: &AdminSession,
+    input: ActionRouterInput,
+) -> anyhow::Result<ActionRouterOutput> {
+    let mut config = state.config.clone();
+    process_audited(
+        input,
+        &mut config,
+        Some(session.operator.clone()),
+        &session.operator.username,
+        AUDIT_SOURCE,
+    )
+    .await
+}
+
+/// Reads and system lookups through the router.
+async fn dispatch(state: &AppState, input: ActionRouterInput) -> anyhow::Result<ActionRouterOutput> {
+    let mut config = state.config.clone();
+    call_action_router(input, &mut config).await
+}
+
+/// Error panel for a failed action or load: its code, a hint for failures
+/// with a known fix, and a retry button when repeating it is harmless.
+fn failed(context: &str, e: &anyhow::Error, retry: Option<templates::Retry>) -> Html<String> {
+    Html(templates::action_error(&ApiError::from_action_error(context, e), retry))
+}
+
+/// Log a mutation the dashboard makes without going through the router.
+async fn record_audit(
+    state: &AppState,
+    session: &AdminSession,
+    action: &'static str,
+    payload: serde_json::Value,
+    outcome: Result<serde_json::Value, String>,
+) {
+    let pool = state.pool.clone();
+    let actor = session.operator.username.clone();
+    let logged = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        record_mutation(&mut conn, &actor, AUDIT_SOURCE, action, payload, outcome)
+    }).await.unwrap();
+    if let Err(e) = logged {
+        eprintln!("[AUDIT] Failed to record {}: {:?}", action, e);
+    }
 }
 
 pub fn router(config: AppConfig) -> Router {
     let state = AppState {
-        config: Arc::new(config),
+        pool: config.pool.clone(),
+        config,
+        jobs: Arc::new(Jobs::default()),
     };
+    if auth::insecure_cookie() {
+        eprintln!("[AUTH] ADMIN_UI_INSECURE_COOKIE is set; session cookies will be sent over plain http");
+    }
+
+    // Anything that mints, trades, moves funds or changes prices
+    let actions = Router::new()
+        .route("/ui/order", post(place_order_handler))
+        .route("/ui/orders/cancel", post(cancel_order_handler))
+        .route("/ui/orders/amend", post(amend_order_handler))
+        .route("/ui/on_ramp", post(on_ramp_handler))
+        .route("/ui/faucet", post(faucet_handler))
+        .route("/ui/jobs/:job_id/cancel", post(cancel_job_handler))
+        .route("/ui/aggregation/run", post(run_aggregation_handler))
+        .route("/ui/lending/supply", post(supply_liquidity_handler))
+        .route("/ui/lending/withdraw", post(withdraw_liquidity_handler))
+        .route("/ui/lending/borrow", post(borrow_handler))
+        .route("/ui/lending/repay", post(repay_handler))
+        .route("/ui/lending/liquidate", post(liquidate_handler))
+        .route("/ui/lending/create_pool", post(create_pool_handler))
+        .route("/ui/listings/create_company", post(create_company_handler))
+        .route("/ui/listings/create_listing", post(create_listing_handler))
+        .route("/ui/listings/purchase", post(purchase_listing_handler))
+        .route("/ui/listings/return", post(return_listing_handler))
+        .route("/ui/listings/withdraw", post(withdraw_listing_handler))
+        .route("/ui/oracle/set_price", post(set_oracle_price_handler))
+        .route("/ui/ramps/retry", post(retry_ramp_order_handler))
+        .route("/ui/ramps/cancel", post(cancel_ramp_order_handler))
+        .route_layer(middleware::from_fn(auth::require_operator));
 
     Router::new()
         .route("/", get(index_handler))
         .route("/ui/accounts", get(get_accounts_handler))
         .route("/ui/dashboard/:account_id", get(dashboard_handler))
+        .route("/ui/account/:wallet_id/detail", get(account_detail_handler))
+        .route("/ui/account/:wallet_id/detail/:section", get(account_detail_section_handler))
         // Tabs
         .route("/ui/tabs/markets", get(markets_tab_handler))
         .route("/ui/tabs/onramp", get(on_ramp_tab_handler))
         .route("/ui/tabs/faucet", get(faucet_tab_handler))
         .route("/ui/tabs/lending", get(lending_tab_handler))
-        // Actions
+        .route("/ui/jobs/:job_id", get(job_progress_handler))
         .route("/ui/market_detail", get(market_detail_handler))
-        .route("/ui/order", post(place_order_handler))
-        .route("/ui/on_ramp", post(on_ramp_handler))
-        .route("/ui/faucet", post(faucet_handler))
-        // Lending actions
+        .route("/ui/market_chart", get(market_chart_handler))
+        .route("/ui/market_orders", get(market_orders_handler))
+        .route("/ui/orders/row", get(order_row_handler))
+        .route("/ui/orders/amend_form", get(amend_order_form_handler))
+        // Lending forms
         .route("/ui/lending/supply_form", get(supply_form_handler))
         .route("/ui/lending/borrow_form", get(borrow_form_handler))
         .route("/ui/lending/withdraw_form", get(withdraw_form_handler))
         .route("/ui/lending/repay_form", get(repay_form_handler))
-        .route("/ui/lending/supply", post(supply_liquidity_handler))
-        .route("/ui/lending/withdraw", post(withdraw_liquidity_handler))
-        .route("/ui/lending/borrow", post(borrow_handler))
-        .route("/ui/lending/repay", post(repay_handler))
         .route("/ui/lending/pool_stats", get(pool_stats_handler))
         .route("/ui/lending/user_positions", get(user_positions_handler))
+        .route("/ui/lending/at_risk", get(at_risk_loans_handler))
+        .route("/ui/lending/liquidate_form", get(liquidate_form_handler))
+        .route("/ui/lending/create_pool_form", get(create_pool_form_handler))
         // Listing tab and forms
         .route("/ui/tabs/listings", get(listings_tab_handler))
         .route("/ui/listings/create_company_form", get(create_company_form_handler))
@@ -93,21 +227,29 @@ pub fn router(config: AppConfig) -> Router {
         .route("/ui/listings/purchase_form", get(purchase_form_handler))
         .route("/ui/listings/return_form", get(return_form_handler))
         .route("/ui/listings/withdraw_form", get(withdraw_listing_form_handler))
-        // Listing actions
-        .route("/ui/listings/create_company", post(create_company_handler))
-        .route("/ui/listings/create_listing", post(create_listing_handler))
-        .route("/ui/listings/purchase", post(purchase_listing_handler))
-        .route("/ui/listings/return", post(return_listing_handler))
-        .route("/ui/listings/withdraw", post(withdraw_listing_handler))
         .route("/ui/listings/stats", get(listing_stats_handler))
         // Oracle
         .route("/ui/tabs/oracle", get(oracle_tab_handler))
-        .route("/ui/oracle/set_price", post(set_oracle_price_handler))
+        // Operations feed
+        .route("/ui/tabs/operations", get(operations_tab_handler))
+        // Ramp operations
+        .route("/ui/tabs/ramps", get(ramps_tab_handler))
+        // Mutation audit log
+        .route("/ui/tabs/audit", get(audit_tab_handler))
+        .route("/ui/audit/entries", get(audit_entries_handler))
+        // System health
+        .route("/ui/tabs/health", get(health_tab_handler))
+        .route("/ui/health/report", get(health_report_handler))
+        .merge(actions)
+        .route("/logout", post(auth::logout_handler))
+        // everything above needs a session; signing in doesn't
+        .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_session))
+        .route("/login", get(auth::login_page_handler).post(auth::login_handler))
         .with_state(state)
 }
 
-async fn index_handler() -> Html<String> {
-    Html(templates::index_page())
+async fn index_handler(Extension(session): Extension<AdminSession>) -> Html<String> {
+    Html(templates::index_page(&session, &SocketSettings::from_env()))
 }
 
 async fn get_accounts_handler(State(state): State<AppState>) -> Html<String> {
@@ -116,7 +258,7 @@ async fn get_accounts_handler(State(state): State<AppState>) -> Html<String> {
     use cradle_back_end::schema::cradlewalletaccounts::dsl as wa_dsl;
     use cradle_back_end::schema::cradleaccounts::dsl as ca_dsl;
 
-    let pool = state.config.pool.clone();
+    let pool = state.pool.clone();
     
     let accounts_result = tokio::task::spawn_blocking(move || {
         let mut conn = pool.get().expect("Failed to get db connection");
@@ -148,7 +290,7 @@ async fn dashboard_handler(
     use contract_integrator::hedera::TokenId;
     use bigdecimal::ToPrimitive;
     
-    let pool = state.config.pool.clone();
+    let pool = state.pool.clone();
     let acc_id_copy = account_id;
     let pool_copy = pool.clone();
 
@@ -186,7 +328,8 @@ async fn dashboard_handler(
         eprintln!("[DEBUG] Fetching balances for wallet: {} (contract_id: {})", wallet.id, wallet.contract_id);
         
         // Fetch on-chain balances ONCE using contract_id (following get_asset_balance pattern)
-        match get_account_balances(&state.config.wallet.client, &wallet.contract_id).await {
+        let fetched = get_account_balances(&state.config.wallet.client, &wallet.contract_id).await;
+        match fetched {
             Ok(balance_data) => {
                  // HBAR
                  if let Some(hbar_val) = balance_data.hbars.get_value().to_i64() {
@@ -198,7 +341,7 @@ async fn dashboard_handler(
                  
                  // Tokens (Filter by what we found in DB)
                  if let Some(assets) = assets_opt {
-                     let pool_for_deductions = state.config.pool.clone();
+                     let pool_for_deductions = state.pool.clone();
                      
                      for asset in assets {
                          // Following get_asset_balance pattern exactly
@@ -265,7 +408,7 @@ async fn markets_tab_handler(State(state): State<AppState>, Query(q): Query<TabQ
     use cradle_back_end::market::db_types::MarketRecord;
     use diesel::prelude::*;
     
-    let pool = state.config.pool.clone();
+    let pool = state.pool.clone();
     let markets_result = tokio::task::spawn_blocking(move || {
         let mut conn = pool.get().expect("Failed to get db connection");
         markets.load::<MarketRecord>(&mut conn)
@@ -284,16 +427,122 @@ async fn faucet_tab_handler(State(state): State<AppState>, Query(q): Query<TabQu
     use cradle_back_end::asset_book::db_types::AssetBookRecord;
     use diesel::prelude::*;
 
-    let pool = state.config.pool.clone();
-    let assets_result = tokio::task::spawn_blocking(move || {
-        let mut conn = pool.get().expect("Failed to get db connection");
-        asset_book.load::<AssetBookRecord>(&mut conn)
+    let pool = state.pool.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        let assets = asset_book.load::<AssetBookRecord>(&mut conn)?;
+        let wallets = retail_wallets(&mut conn)?;
+        anyhow::Ok((assets, wallets))
+    }).await.unwrap();
+
+    let (assets_list, wallets) = result.unwrap_or_default();
+    Html(templates::faucet_tab(q.account_id, assets_list, wallets))
+}
+
+fn retail_wallets(
+    conn: &mut diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>,
+) -> anyhow::Result<Vec<CradleWalletAccountRecord>> {
+    use cradle_back_end::schema::cradlewalletaccounts::dsl as wa_dsl;
+    use cradle_back_end::schema::cradleaccounts::dsl as ca_dsl;
+    use diesel::prelude::*;
+
+    Ok(wa_dsl::cradlewalletaccounts
+        .inner_join(ca_dsl::cradleaccounts.on(wa_dsl::cradle_account_id.eq(ca_dsl::id)))
+        .filter(ca_dsl::account_type.eq(CradleAccountType::Retail))
+        .select(wa_dsl::cradlewalletaccounts::all_columns())
+        .order(wa_dsl::created_at.asc())
+        .load::<CradleWalletAccountRecord>(conn)?)
+}
+
+
+async fn account_detail_handler(
+    State(state): State<AppState>,
+    Path(wallet_id): Path<Uuid>,
+) -> Html<String> {
+    use cradle_back_end::schema::cradlewalletaccounts::dsl::*;
+    use diesel::prelude::*;
+
+    let pool = state.pool.clone();
+    let wallet = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        anyhow::Ok(cradlewalletaccounts.find(wallet_id).first::<CradleWalletAccountRecord>(&mut conn).optional()?)
     }).await.unwrap();
 
-    let assets_list = assets_result.unwrap_or_default();
-    Html(templates::faucet_tab(q.account_id, assets_list))
+    match wallet {
+        Ok(Some(wallet)) => Html(templates::account_detail(&wallet)),
+        Ok(None) => Html("<div class='text-red-400'>Wallet not found</div>".to_string()),
+        Err(e) => {
+            eprintln!("[ACCOUNT] Failed to load wallet {}: {:?}", wallet_id, e);
+            failed("Failed to load wallet", &e, Some(templates::Retry::Get(format!("/ui/account/{}/detail", wallet_id))))
+        }
+    }
 }
 
+/// One section of the wallet detail page, loaded when its button is picked.
+async fn account_detail_section_handler(
+    State(state): State<AppState>,
+    Path((wallet_id, section)): Path<(Uuid, String)>,
+) -> Html<String> {
+    use cradle_back_end::accounts_ledger::db_types::AccountLedgerTransactionType;
+    use diesel::prelude::*;
+
+    let Some(section) = account_detail::Section::parse(&section) else {
+        return Html("<div class='text-red-400'>Unknown section</div>".to_string());
+    };
+
+    let pool = state.pool.clone();
+    let rendered = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        let wallet = {
+            use cradle_back_end::schema::cradlewalletaccounts::dsl::*;
+            cradlewalletaccounts.find(wallet_id).first::<CradleWalletAccountRecord>(&mut conn)?
+        };
+
+        let html = match section {
+            account_detail::Section::Ledger => {
+                let symbols = account_detail::asset_symbols(&mut conn)?;
+                let entries = account_detail::ledger(&mut conn, &wallet.address, None)?;
+                templates::account_ledger(&wallet.address, &entries, &symbols, "No ledger entries")
+            }
+            account_detail::Section::Orders => {
+                templates::account_open_orders(&account_detail::open_orders(&mut conn, wallet.id)?)
+            }
+            account_detail::Section::Trades => {
+                templates::account_trades(&account_detail::trades(&mut conn, wallet.id)?)
+            }
+            account_detail::Section::Loans => {
+                let symbols = account_detail::asset_symbols(&mut conn)?;
+                templates::account_loans(&account_detail::loans(&mut conn, wallet.id)?, &symbols)
+            }
+            account_detail::Section::Listings => {
+                let symbols = account_detail::asset_symbols(&mut conn)?;
+                let entries = account_detail::ledger(
+                    &mut conn,
+                    &wallet.address,
+                    Some(&[AccountLedgerTransactionType::BuyListed, AccountLedgerTransactionType::SellListed]),
+                )?;
+                templates::account_ledger(&wallet.address, &entries, &symbols, "No listing purchases")
+            }
+            account_detail::Section::Ramps => {
+                let symbols = account_detail::asset_symbols(&mut conn)?;
+                templates::account_ramp_orders(&account_detail::ramp_orders(&mut conn, wallet.id)?, &symbols)
+            }
+        };
+        anyhow::Ok(html)
+    }).await.unwrap();
+
+    match rendered {
+        Ok(html) => Html(html),
+        Err(e) => {
+            eprintln!("[ACCOUNT] Failed to load {} for wallet {}: {:?}", section.as_str(), wallet_id, e);
+            failed(
+                &format!("Failed to load {}", section.label()),
+                &e,
+                Some(templates::Retry::Get(format!("/ui/account/{}/detail/{}", wallet_id, section.as_str()))),
+            )
+        }
+    }
+}
 
 // --- DETAIL & ACTION HANDLERS ---
 
@@ -310,19 +559,22 @@ async fn market_detail_handler(
     let input = MarketProcessorInput::GetMarket(q.market_id);
     let router_input = ActionRouterInput::Markets(input);
     
-    let market_record = match call_action_router(router_input, (*state.config).clone()).await {
+    let market_record = match dispatch(&state, router_input).await {
         Ok(ActionRouterOutput::Markets(cradle_back_end::market::processor_enums::MarketProcessorOutput::GetMarket(m))) => m,
         _ => return Html("<div>Error loading market details</div>".to_string())
     };
 
+    let orders = recent_orders(&state, q.market_id).await;
+    Html(templates::market_detail(market_record, q.account_id, orders))
+}
+
+async fn recent_orders(state: &AppState, m_id: Uuid) -> Vec<cradle_back_end::order_book::db_types::OrderBookRecord> {
     use cradle_back_end::schema::orderbook::dsl as ob_dsl;
     use cradle_back_end::order_book::db_types::OrderBookRecord;
     use diesel::prelude::*;
-    
-    let pool = state.config.pool.clone();
-    let acc_id = q.account_id;
-    let m_id = q.market_id;
-    
+
+    let pool = state.pool.clone();
+
     let orders_result = tokio::task::spawn_blocking(move || {
         let mut conn = pool.get().expect("Failed to get db connection");
         ob_dsl::orderbook
@@ -332,8 +584,72 @@ async fn market_detail_handler(
             .load::<OrderBookRecord>(&mut conn)
     }).await.unwrap();
 
-    let orders = orders_result.unwrap_or_default();
-    Html(templates::market_detail(market_record, q.account_id, orders))
+    orders_result.unwrap_or_default()
+}
+
+/// The order table alone, for the market view to reload as the book moves.
+async fn market_orders_handler(
+    State(state): State<AppState>,
+    Query(q): Query<MarketDetailQuery>,
+) -> Html<String> {
+    let market = match dispatch(
+        &state,
+        ActionRouterInput::Markets(MarketProcessorInput::GetMarket(q.market_id)),
+    ).await {
+        Ok(ActionRouterOutput::Markets(cradle_back_end::market::processor_enums::MarketProcessorOutput::GetMarket(m))) => m,
+        _ => return Html("<tr><td colspan='7' class='p-4 text-center text-red-400'>Error loading market</td></tr>".to_string())
+    };
+
+    let orders = recent_orders(&state, market.id).await;
+    Html(templates::market_orders(market.asset_two, &orders))
+}
+
+#[derive(Deserialize)]
+struct MarketChartQuery {
+    market_id: Uuid,
+    interval: Option<String>,
+}
+
+/// Candles shown per chart, whatever the interval.
+const CHART_CANDLES: i64 = 150;
+
+async fn market_chart_handler(
+    State(state): State<AppState>,
+    Query(q): Query<MarketChartQuery>,
+) -> Html<String> {
+    let interval = q
+        .interval
+        .as_deref()
+        .and_then(|name| TimeSeriesInterval::ALL.iter().find(|i| i.as_str() == name))
+        .cloned()
+        .unwrap_or(TimeSeriesInterval::OneMinute);
+
+    let market = match dispatch(
+        &state,
+        ActionRouterInput::Markets(MarketProcessorInput::GetMarket(q.market_id)),
+    ).await {
+        Ok(ActionRouterOutput::Markets(cradle_back_end::market::processor_enums::MarketProcessorOutput::GetMarket(m))) => m,
+        _ => return Html("<div class='text-red-400'>Error loading market</div>".to_string())
+    };
+
+    // same query as GET /time-series/history, for the market's base asset
+    let history = MarketTimeSeriesProcessorInput::GetHistory(GetHistoryInputArgs {
+        market_id: market.id,
+        duration_secs: BigDecimal::from(interval.seconds() * CHART_CANDLES),
+        interval: interval.clone(),
+        asset_id: market.asset_one,
+    });
+    let candles = match dispatch(&state, ActionRouterInput::MarketTimeSeries(history)).await {
+        Ok(ActionRouterOutput::MarketTimeSeries(MarketTimeSeriesProcessorOutput::GetHistory(records))) => records,
+        Ok(_) => vec![],
+        Err(e) => return failed(
+            "Failed to load candles",
+            &e,
+            Some(templates::Retry::Get(format!("/ui/market_chart?market_id={}&interval={}", market.id, interval.as_str()))),
+        ),
+    };
+
+    Html(templates::market_chart(market.id, market.asset_one, &interval, candles))
 }
 
 #[derive(Deserialize)]
@@ -341,6 +657,7 @@ struct OnRampForm {
     account_id: Uuid,
     token: String,
     amount: String,
+    currency: String,
     email: String,
     result_page: Option<String>,
 }
@@ -357,49 +674,11 @@ async fn on_ramp_handler(
         Err(_) => return Html("<div class='text-red-400'>Failed to configure Ramper provider</div>".to_string())
     };
 
-    let pool = state.config.pool.clone();
-    // Getting raw conns for async ops usually requires deadpool or similar, but here we use the pool from AppConfig.
-    // The existing ramper code takes `wallet: TaskWallet` which is `&mut dyn TaskWalletTrait`.
-    // And `conn: DbConn` which is `&mut PooledConnection<...>`.
-    // Accessing these in an async handler and passing to async function `ramper.onramp` requires care with lifetimes and ownership.
-    
-    // We'll try to follow `api/handlers/ramper.rs` pattern.
-    
+    let pool = state.pool.clone();
     let mut conn = match pool.get() {
         Ok(c) => c,
         Err(_) => return Html("<div class='text-red-400'>Database connection failed</div>".to_string())
     };
-    
-    // `app_config.wallet` is `Arc<Box<dyn TaskWalletTrait>>` or similar? 
-    // In `AppConfig`, `wallet` is `Box<dyn TaskWalletTrait + Send + Sync>`.
-    // `state.config.wallet` is accessible.
-    let mut wallet_ref = state.config.wallet.clone(); // This gives us the Box? `AppConfig` has `pub wallet: Box<...>`
-    // Wait, `AppConfig` struct def: `pub wallet: Box<dyn TaskWalletTrait + Send + Sync>`.
-    // Since it's a Box, cloning `AppConfig` generally not `Clone` unless implemented manually.
-    // `AppConfig` IS NOT Clone? 
-    // `AppState` has `config: Arc<AppConfig>`.
-    // But `ramper.onramp` takes `&mut wallet`. We can't get mut ref from Arc.
-    // However, `TaskWalletTrait` methods usually take `&self` or `&mut self`.
-    // If they take `&mut self`, we are in trouble with Arc.
-    // Let's check `api/handlers/ramper.rs`: `let mut wallet = app_config.wallet.clone();`
-    // This implies `wallet` field or `AppConfig` is Clone? 
-    // Or `app_config` in handler is `State<AppConfig>`. If State extracts `AppConfig`, it must be Clone.
-    // `AppConfig` in `src/utils/app_config.rs` usually derives Clone or is Arc-wrapped internally?
-    
-    // If `AppConfig` is NOT Clone, then `State<AppConfig>` would fail if it wasn't wrapped in Arc in `router`.
-    // In `router` I wrap it: `config: Arc::new(config)`.
-    // `State` extractor gives me `State<AppState>`.
-    // So `state.config` is `Arc<AppConfig>`.
-    // I cannot clone `AppConfig` out of `Arc` if it's not Clone.
-    // But `api` handlers seem to treat `AppConfig` as something they can clone wallet from?
-    // Let's assume I can't easily clone the wallet box if I only have Arc.
-    // BUT `api/handlers/ramper.rs` does: 
-    // `State(app_config): State<AppConfig>` -> This implies `AppConfig` implements Clone!
-    // If `AppConfig` implements Clone, then `state.config.as_ref().clone()` works.
-    
-    // Let's assume `AppConfig` is Clone.
-    let app_config_clone = (*state.config).clone();
-    let mut wallet = app_config_clone.wallet; 
 
     // Parse inputs
     let token_uuid = match Uuid::parse_str(&form.token) {
@@ -411,17 +690,35 @@ async fn on_ramp_handler(
         Err(_) => return Html("<div class='text-red-400'>Invalid Amount</div>".to_string())
     };
 
+    // every on-ramp is priced through a quote at the current rate
+    let provider = match ramper.provider() {
+        Ok(p) => p,
+        Err(e) => return failed("Failed to configure Ramper provider", &e, None)
+    };
+    let quote = match create_quote(&mut conn, provider.name(), RampQuoteInputArgs {
+        token: token_uuid,
+        currency: form.currency,
+        fiat_amount: None,
+        token_amount: Some(amount_decimal),
+    }).await {
+        Ok(q) => q,
+        Err(e) => return failed("Failed to quote the on-ramp", &e, None)
+    };
+
     let req = OnRampRequest {
         token: token_uuid,
-        amount: amount_decimal,
+        amount: quote.token_amount,
         wallet_id: form.account_id,
         result_page: form.result_page.unwrap_or_else(|| "http://localhost:3000/ui".to_string()),
         email: form.email,
+        quote_id: quote.id,
+        channel: None,
+        phone: None,
     };
 
     eprintln!("[DEBUG] Calling ramper.onramp for wallet_id={}, token={}, amount={}", 
         req.wallet_id, req.token, req.amount);
-    match ramper.onramp(&mut wallet, &mut conn, req).await {
+    match ramper.onramp(&state.config.wallet, &mut conn, req).await {
         Ok(res) => {
             eprintln!("[DEBUG] On-ramp success: ref={}, url={}", res.reference, res.authorization_url);
             Html(format!(
@@ -431,104 +728,194 @@ async fn on_ramp_handler(
         },
         Err(e) => {
             eprintln!("[ERROR] On-ramp failed: {:?}", e);
-            Html(format!("<div class='text-red-400'>On-Ramp Failed: {}</div>", e))
+            failed("On-Ramp failed", &e, None)
         }
     }
 }
 
-#[derive(Deserialize)]
-struct FaucetForm {
-    account_id: Uuid,
-    asset_id: String,
-}
+/// Base units minted and airdropped per wallet and asset.
+const FAUCET_AMOUNT: u64 = 100_000_000_000_000;
 
+/// Starts a faucet job for every selected asset and target wallet. The form
+/// repeats `asset_id` and `wallet_id`, so it is read as raw pairs; `target`
+/// is `self`, `selected` or `retail`.
 async fn faucet_handler(
     State(state): State<AppState>,
-    Form(form): Form<FaucetForm>,
+    Extension(session): Extension<AdminSession>,
+    Form(fields): Form<Vec<(String, String)>>,
 ) -> Html<String> {
-    eprintln!("[DEBUG] Faucet request: account_id={}, asset_id={}", form.account_id, form.asset_id);
-    let pool = state.config.pool.clone();
-    let mut conn = match pool.get() {
-        Ok(c) => c,
-        Err(_) => return Html("<div class='text-red-400'>Database connection failed</div>".to_string())
-    };
+    use cradle_back_end::schema::asset_book::dsl as ab_dsl;
+    use cradle_back_end::schema::cradlewalletaccounts::dsl as wa_dsl;
+    use cradle_back_end::asset_book::db_types::AssetBookRecord;
+    use diesel::prelude::*;
 
-    // Need mutable wallet from config. See notes in on_ramp_handler.
-    let mut app_config_clone = (*state.config).clone();
-    let mut action_wallet = app_config_clone.wallet; // Moves wallet out
+    let mut account_id = None;
+    let mut target = String::from("self");
+    let mut asset_ids = Vec::new();
+    let mut wallet_ids = Vec::new();
+    for (name, value) in fields {
+        match name.as_str() {
+            "account_id" => account_id = Uuid::parse_str(&value).ok(),
+            "target" => target = value,
+            "asset_id" => asset_ids.extend(Uuid::parse_str(&value).ok()),
+            "wallet_id" => wallet_ids.extend(Uuid::parse_str(&value).ok()),
+            _ => {}
+        }
+    }
+    if asset_ids.is_empty() {
+        return Html("<div class='text-red-400'>Pick at least one token</div>".to_string());
+    }
 
-    let asset_uuid = match Uuid::parse_str(&form.asset_id) {
-        Ok(u) => u,
-        Err(_) => return Html("<div class='text-red-400'>Invalid Asset UUID</div>".to_string())
-    };
+    let pool = state.pool.clone();
+    let resolved = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        let assets = ab_dsl::asset_book
+            .filter(ab_dsl::id.eq_any(asset_ids))
+            .order(ab_dsl::symbol.asc())
+            .load::<AssetBookRecord>(&mut conn)?;
+        let wallets = match target.as_str() {
+            "retail" => retail_wallets(&mut conn)?,
+            "selected" => wa_dsl::cradlewalletaccounts
+                .filter(wa_dsl::id.eq_any(wallet_ids))
+                .load::<CradleWalletAccountRecord>(&mut conn)?,
+            _ => wa_dsl::cradlewalletaccounts
+                .filter(wa_dsl::id.eq_any(account_id.into_iter().collect::<Vec<_>>()))
+                .load::<CradleWalletAccountRecord>(&mut conn)?,
+        };
+        anyhow::Ok((assets, wallets))
+    }).await.unwrap();
 
-    // 1. Get Wallet Record
-    let wallet_data = match get_wallet(&mut conn, form.account_id).await {
-        Ok(w) => w,
-        Err(_) => return Html("<div class='text-red-400'>Wallet not found</div>".to_string())
+    let (assets, wallets) = match resolved {
+        Ok(found) => found,
+        Err(e) => return failed("Failed to load faucet targets", &e, None),
     };
+    if wallets.is_empty() {
+        return Html("<div class='text-red-400'>No wallets to send to</div>".to_string());
+    }
 
-    // 2. Get Asset Data
-    let token_data = match get_asset(&mut conn, asset_uuid).await {
-        Ok(t) => t,
-        Err(_) => return Html("<div class='text-red-400'>Asset not found</div>".to_string())
-    };
+    let mut labels = Vec::new();
+    let mut targets = Vec::new();
+    for wallet in &wallets {
+        for asset in &assets {
+            labels.push(format!("{} to {}", asset.symbol, wallet.address));
+            targets.push((wallet.clone(), asset.clone()));
+        }
+    }
+
+    let job_id = state.jobs.start("faucet", &session.operator.username, labels);
+    eprintln!("[FAUCET] Job {} started by {}: {} airdrops", job_id, session.operator.username, targets.len());
+    tokio::spawn(run_faucet_job(state.clone(), session, job_id, targets));
+
+    match state.jobs.get(job_id) {
+        Some(job) => Html(templates::job_progress(&job)),
+        None => Html("<div class='text-red-400'>Faucet job went missing</div>".to_string()),
+    }
+}
+
+/// Works through the targets one at a time so the system wallet's contract
+/// calls don't race each other. Stops before the next target when cancelled.
+async fn run_faucet_job(
+    state: AppState,
+    session: AdminSession,
+    job_id: Uuid,
+    targets: Vec<(CradleWalletAccountRecord, cradle_back_end::asset_book::db_types::AssetBookRecord)>,
+) {
+    for (i, (wallet, asset)) in targets.iter().enumerate() {
+        if state.jobs.cancel_requested(job_id) {
+            break;
+        }
+        let status = match faucet_once(&state, &session, &state.config.wallet, job_id, i, wallet, asset).await {
+            Ok(()) => TargetStatus::Done("Airdropped".to_string()),
+            Err(e) => {
+                eprintln!("[FAUCET] Job {}: {} to {} failed: {}", job_id, asset.symbol, wallet.address, e);
+                TargetStatus::Failed(e)
+            }
+        };
+        state.jobs.set_status(job_id, i, status);
+    }
 
-    // 3. Associate
-    if let Err(e) = associate_token(
+    state.jobs.finish(job_id);
+    eprintln!("[FAUCET] Job {} finished", job_id);
+}
+
+/// Associate, KYC, mint and airdrop one asset to one wallet.
+async fn faucet_once(
+    state: &AppState,
+    session: &AdminSession,
+    action_wallet: &SharedWallet,
+    job_id: Uuid,
+    target: usize,
+    wallet_data: &CradleWalletAccountRecord,
+    token_data: &cradle_back_end::asset_book::db_types::AssetBookRecord,
+) -> Result<(), String> {
+    let step = |name: &str| state.jobs.set_status(job_id, target, TargetStatus::Running(name.to_string()));
+    let mut conn = state.pool.get().map_err(|_| "Database connection failed".to_string())?;
+
+    step("Associating");
+    associate_token(
         &mut conn,
-        &mut action_wallet,
+        action_wallet,
         AssociateTokenToWalletInputArgs {
             wallet_id: wallet_data.id,
             token: token_data.id
         }
-    ).await {
-         return Html(format!("<div class='text-red-400'>Association failed: {}</div>", e));
-    }
+    ).await.map_err(|e| format!("Association failed: {}", e))?;
 
-    // 4. KYC
-    if let Err(e) = kyc_token(
+    step("Granting KYC");
+    kyc_token(
         &mut conn,
-        &mut action_wallet,
+        action_wallet,
         GrantKYCInputArgs {
             wallet_id: wallet_data.id,
             token: token_data.id
         }
-    ).await {
-        return Html(format!("<div class='text-red-400'>KYC failed: {}</div>", e));
-    }
+    ).await.map_err(|e| format!("KYC failed: {}", e))?;
 
-    // 5. Mint
-    let amount = 100_000_000_000_000u64; // Hardcoded large amount as per example
-    if let Err(e) = mint_asset(
-        &mut conn,
-        &mut action_wallet,
-        token_data.id,
-        amount
-    ).await {
-        return Html(format!("<div class='text-red-400'>Minting failed: {}</div>", e));
-    }
+    step("Minting");
+    mint_asset(&mut conn, action_wallet, token_data.id, FAUCET_AMOUNT)
+        .await
+        .map_err(|e| format!("Minting failed: {}", e))?;
 
-    // 6. Transfer/Airdrop (Contract Call)
+    step("Airdropping");
     let airdrop_request = ContractCallInput::AssetManager(AssetManagerFunctionInput::Airdrop(AirdropArgs {
-        amount: amount,
+        amount: FAUCET_AMOUNT,
         asset_contract: token_data.asset_manager.clone(),
         target: wallet_data.address.clone(),
     }));
+    let airdropped = action_wallet.execute(airdrop_request).await;
+    record_audit(
+        state,
+        session,
+        "Faucet.Airdrop",
+        serde_json::json!({ "wallet": wallet_data.id, "asset": token_data.id, "amount": FAUCET_AMOUNT, "job": job_id }),
+        airdropped.as_ref().map(|_| serde_json::Value::Null).map_err(|e| e.to_string()),
+    ).await;
+    airdropped
+        .map(|_| ())
+        .map_err(|e| format!("Airdrop contract call failed: {}", e))
+}
 
-    eprintln!("[DEBUG] Calling airdrop contract function");
-    match airdrop_request.process(&mut action_wallet).await {
-        Ok(_) => {
-            eprintln!("[DEBUG] Airdrop successful");
-            Html("<div class='bg-green-800 p-4 rounded text-green-200'>Airdrop Successful! Tokens sent.</div>".to_string())
-        },
-        Err(e) => {
-            eprintln!("[ERROR] Airdrop failed: {:?}", e);
-            Html(format!("<div class='text-red-400'>Airdrop Contract Call Failed: {}</div>", e))
-        }
+async fn job_progress_handler(
+    State(state): State<AppState>,
+    Path(job_id): Path<Uuid>,
+) -> Html<String> {
+    match state.jobs.get(job_id) {
+        Some(job) => Html(templates::job_progress(&job)),
+        None => Html("<div class='text-gray-400'>This job is no longer tracked; the dashboard may have restarted.</div>".to_string()),
     }
 }
 
+async fn cancel_job_handler(
+    State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
+    Path(job_id): Path<Uuid>,
+) -> Html<String> {
+    if state.jobs.cancel(job_id) {
+        eprintln!("[JOBS] {} cancelled job {}", session.operator.username, job_id);
+    }
+    job_progress_handler(State(state), Path(job_id)).await
+}
+
 // Re-add existing Place Order Handler
 #[derive(Deserialize, Debug)]
 struct PlaceOrderForm {
@@ -542,6 +929,7 @@ struct PlaceOrderForm {
 
 async fn place_order_handler(
     State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
     Form(form): Form<PlaceOrderForm>,
 ) -> Html<String> {
     eprintln!("[DEBUG] Place order request: account_id={}, market_id={}, side={}, type={}, amount={}, price={:?}", 
@@ -550,7 +938,7 @@ async fn place_order_handler(
     // Fetch Market
     let input = MarketProcessorInput::GetMarket(form.market_id);
     let router_input = ActionRouterInput::Markets(input);
-    let market_record = match call_action_router(router_input, (*state.config).clone()).await {
+    let market_record = match dispatch(&state, router_input).await {
         Ok(ActionRouterOutput::Markets(cradle_back_end::market::processor_enums::MarketProcessorOutput::GetMarket(m))) => m,
         _ => return Html("<tr><td colspan='5' class='text-red-500'>Market not found</td></tr>".to_string())
     };
@@ -566,7 +954,7 @@ async fn place_order_handler(
     use cradle_back_end::asset_book::db_types::AssetBookRecord;
     use diesel::prelude::*;
     
-    let pool = state.config.pool.clone();
+    let pool = state.pool.clone();
     let bid_asset_id_copy = bid_asset_id;
     let ask_asset_id_copy = ask_asset_id;
     
@@ -615,7 +1003,6 @@ async fn place_order_handler(
     
     eprintln!("[DEBUG] Calculated amounts - bid_amt: {}, ask_amt: {}", bid_amt, ask_amt);
 
-    use cradle_back_end::order_book::processor_enums::OrderBookProcessorInput;
     use cradle_back_end::order_book::db_types::{NewOrderBookRecord, OrderType as DbOrderType, FillMode};
     
     let o_type = match form.order_type.as_str() {
@@ -640,7 +1027,7 @@ async fn place_order_handler(
     let router_input = ActionRouterInput::OrderBook(input);
     
     eprintln!("[DEBUG] Submitting order to action router");
-    match call_action_router(router_input, (*state.config).clone()).await {
+    match run_action(&state, &session, router_input).await {
         Ok(_) => {
             eprintln!("[DEBUG] Order submitted successfully");
             Html(r#"<tr class="bg-green-900/40"><td colspan="5" class="p-3 text-center text-green-300">Order Submitted! Refreshing...</td></tr>"#.to_string())
@@ -651,6 +1038,126 @@ async fn place_order_handler(
         }
     }
 }
+#[derive(Deserialize)]
+struct OrderRowQuery {
+    order_id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct AmendOrderForm {
+    order_id: Uuid,
+    price: String,
+    bid_amount: String,
+    ask_amount: String,
+}
+
+async fn load_order(state: &AppState, order_id: Uuid) -> anyhow::Result<(cradle_back_end::order_book::db_types::OrderBookRecord, MarketRecord)> {
+    let order = match dispatch(
+        state,
+        ActionRouterInput::OrderBook(OrderBookProcessorInput::GetOrder(order_id)),
+    ).await? {
+        ActionRouterOutput::OrderBook(OrderBookProcessorOutput::GetOrder(order)) => order,
+        _ => return Err(anyhow::anyhow!("Unexpected response loading order {}", order_id)),
+    };
+    let market = match dispatch(
+        state,
+        ActionRouterInput::Markets(MarketProcessorInput::GetMarket(order.market_id)),
+    ).await? {
+        ActionRouterOutput::Markets(cradle_back_end::market::processor_enums::MarketProcessorOutput::GetMarket(m)) => m,
+        _ => return Err(anyhow::anyhow!("Unexpected response loading market {}", order.market_id)),
+    };
+    Ok((order, market))
+}
+
+/// The order's row as it stands now, with `note` under its actions.
+async fn render_order_row(state: &AppState, order_id: Uuid, note: Option<&str>) -> Html<String> {
+    match load_order(state, order_id).await {
+        Ok((order, market)) => Html(templates::order_row(&order, market.asset_two, note)),
+        Err(e) => Html(format!(
+            r#"<tr><td colspan="7" class="p-3 text-center text-red-400">Failed to load order {}: {}</td></tr>"#,
+            order_id, e
+        )),
+    }
+}
+
+async fn order_row_handler(State(state): State<AppState>, Query(q): Query<OrderRowQuery>) -> Html<String> {
+    render_order_row(&state, q.order_id, None).await
+}
+
+async fn amend_order_form_handler(State(state): State<AppState>, Query(q): Query<OrderRowQuery>) -> Html<String> {
+    match load_order(&state, q.order_id).await {
+        Ok((order, _)) => Html(templates::amend_order_row(&order)),
+        Err(e) => Html(format!(
+            r#"<tr><td colspan="7" class="p-3 text-center text-red-400">Failed to load order {}: {}</td></tr>"#,
+            q.order_id, e
+        )),
+    }
+}
+
+async fn cancel_order_handler(
+    State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
+    Form(form): Form<OrderRowQuery>,
+) -> Html<String> {
+    let input = OrderBookProcessorInput::CancelOrder(CancelOrderInputArgs {
+        order_id: form.order_id,
+        wallet: None,
+    });
+    match run_action(&state, &session, ActionRouterInput::OrderBook(input)).await {
+        Ok(_) => {
+            eprintln!("[ORDERS] {} cancelled order {}", session.operator.username, form.order_id);
+            render_order_row(&state, form.order_id, None).await
+        }
+        Err(e) => {
+            eprintln!("[ERROR] Cancelling order {} failed: {:?}", form.order_id, e);
+            render_order_row(&state, form.order_id, Some(&format!("Cancel failed: {}", e))).await
+        }
+    }
+}
+
+/// Swaps the edited row for the replacement order followed by the
+/// cancelled original.
+async fn amend_order_handler(
+    State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
+    Form(form): Form<AmendOrderForm>,
+) -> Html<String> {
+    let parse = |field: &str, value: &str| {
+        BigDecimal::from_str(value.trim()).map_err(|_| format!("Invalid {}: {}", field, value))
+    };
+    let parsed = parse("price", &form.price).and_then(|price| {
+        Ok((price, parse("bid amount", &form.bid_amount)?, parse("ask amount", &form.ask_amount)?))
+    });
+    let (price, bid_amount, ask_amount) = match parsed {
+        Ok(values) => values,
+        Err(e) => return render_order_row(&state, form.order_id, Some(&e)).await,
+    };
+
+    let input = OrderBookProcessorInput::ModifyOrder(ModifyOrderInputArgs {
+        order_id: form.order_id,
+        wallet: None,
+        price: Some(price),
+        bid_amount: Some(bid_amount),
+        ask_amount: Some(ask_amount),
+    });
+    match run_action(&state, &session, ActionRouterInput::OrderBook(input)).await {
+        Ok(ActionRouterOutput::OrderBook(OrderBookProcessorOutput::ModifyOrder(placed))) => {
+            eprintln!(
+                "[ORDERS] {} amended order {} into {}",
+                session.operator.username, form.order_id, placed.id
+            );
+            let replacement = render_order_row(&state, placed.id, None).await;
+            let original = render_order_row(&state, form.order_id, None).await;
+            Html(format!("{}{}", replacement.0, original.0))
+        }
+        Ok(_) => render_order_row(&state, form.order_id, Some("Unexpected response from the order book")).await,
+        Err(e) => {
+            eprintln!("[ERROR] Amending order {} failed: {:?}", form.order_id, e);
+            render_order_row(&state, form.order_id, Some(&format!("Amend failed: {}", e))).await
+        }
+    }
+}
+
 // Lending Form Structs
 #[derive(Deserialize)]
 struct SupplyForm {
@@ -682,6 +1189,46 @@ struct RepayForm {
     amount: String,
 }
 
+#[derive(Deserialize)]
+struct AtRiskQuery {
+    account_id: Uuid,
+    below: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LiquidateFormQuery {
+    loan_id: Uuid,
+    account_id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct LiquidateForm {
+    loan_id: Uuid,
+    account_id: Uuid,
+    amount: String,
+}
+
+#[derive(Deserialize)]
+struct CreatePoolForm {
+    name: String,
+    reserve_asset: Uuid,
+    yield_asset_mode: String,
+    yield_name: Option<String>,
+    yield_symbol: Option<String>,
+    yield_decimals: Option<String>,
+    yield_asset: Option<String>,
+    ltv: u64,
+    optimal_utilization: u64,
+    base_rate: u64,
+    slope_1: u64,
+    slope_2: u64,
+    liquidation_threshold: u64,
+    liquidation_discount: u64,
+    reserve_factor: u64,
+    supply_cap: Option<String>,
+    borrow_cap: Option<String>,
+}
+
 #[derive(Deserialize)]
 struct QueryParams {
     pool_id: Option<Uuid>,
@@ -741,6 +1288,12 @@ struct SetOraclePriceForm {
     price: String,
 }
 
+// Ramp Form Structs
+#[derive(Deserialize)]
+struct RampActionForm {
+    order_id: Uuid,
+}
+
 // Lending Handlers
 async fn lending_tab_handler(
     State(state): State<AppState>,
@@ -751,7 +1304,7 @@ async fn lending_tab_handler(
     use diesel::prelude::*;
     use cradle_back_end::schema::lendingpool::dsl::*;
     
-    let pool = state.config.pool.clone();
+    let pool = state.pool.clone();
     eprintln!("[LENDING] Fetching all pools from database");
     let pools = tokio::task::spawn_blocking(move || {
         let mut conn = pool.get().ok()?;
@@ -782,7 +1335,7 @@ async fn borrow_form_handler(
     use cradle_back_end::schema::asset_book::dsl as ab_dsl;
     use cradle_back_end::asset_book::db_types::AssetBookRecord;
     
-    let pool = state.config.pool.clone();
+    let pool = state.pool.clone();
     eprintln!("[LENDING] Fetching pool LTV and all assets");
     let (ltv, assets) = tokio::task::spawn_blocking(move || {
         let mut conn = pool.get().ok()?;
@@ -813,7 +1366,7 @@ async fn repay_form_handler(
     use cradle_back_end::schema::loans::dsl::*;
     use cradle_back_end::lending_pool::db_types::LoanStatus;
     
-    let pool_conn = state.config.pool.clone();
+    let pool_conn = state.pool.clone();
     eprintln!("[LENDING] Fetching active loans for wallet: {}", account_id_param);
     let active_loans = tokio::task::spawn_blocking(move || {
         let mut conn = pool_conn.get().ok()?;
@@ -829,6 +1382,7 @@ async fn repay_form_handler(
 
 async fn supply_liquidity_handler(
     State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
     Form(form): Form<SupplyForm>,
 ) -> Html<String> {
     eprintln!("[DEBUG] Supply liquidity: pool={}, account={}, amount={}", 
@@ -838,7 +1392,7 @@ async fn supply_liquidity_handler(
     use cradle_back_end::schema::{lendingpool::dsl as lp_dsl, asset_book::dsl as ab_dsl};
     use cradle_back_end::asset_book::db_types::AssetBookRecord;
     
-    let pool_clone = state.config.pool.clone();
+    let pool_clone = state.pool.clone();
     let pool_id = form.pool_id;
     
     // Get reserve asset decimals
@@ -865,20 +1419,21 @@ async fn supply_liquidity_handler(
         amount: scaled_amount,
     });
     
-    match call_action_router(ActionRouterInput::Pool(input), (*state.config).clone()).await {
+    match run_action(&state, &session, ActionRouterInput::Pool(input)).await {
         Ok(_) => {
             eprintln!("[DEBUG] Supply successful");
             Html("<div class='bg-green-800 p-4 rounded text-green-200'>Liquidity supplied successfully!</div>".to_string())
         },
         Err(e) => {
             eprintln!("[ERROR] Supply failed: {:?}", e);
-            Html(format!("<div class='text-red-400'>Supply failed: {}</div>", e))
+            failed("Supply failed", &e, None)
         }
     }
 }
 
 async fn withdraw_liquidity_handler(
     State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
     Form(form): Form<WithdrawForm>,
 ) -> Html<String> {
     eprintln!("[DEBUG] Withdraw liquidity: pool={}, account={}, amount={}", 
@@ -888,7 +1443,7 @@ async fn withdraw_liquidity_handler(
     use cradle_back_end::schema::{lendingpool::dsl as lp_dsl, asset_book::dsl as ab_dsl};
     use cradle_back_end::asset_book::db_types::AssetBookRecord;
     
-    let pool_clone = state.config.pool.clone();
+    let pool_clone = state.pool.clone();
     let pool_id = form.pool_id;
     
     // Get yield asset decimals
@@ -914,20 +1469,21 @@ async fn withdraw_liquidity_handler(
         amount: scaled_amount,
     });
     
-    match call_action_router(ActionRouterInput::Pool(input), (*state.config).clone()).await {
+    match run_action(&state, &session, ActionRouterInput::Pool(input)).await {
         Ok(_) => {
             eprintln!("[DEBUG] Withdraw successful");
             Html("<div class='bg-green-800 p-4 rounded text-green-200'>Withdrawal successful!</div>".to_string())
         },
         Err(e) => {
             eprintln!("[ERROR] Withdraw failed: {:?}", e);
-            Html(format!("<div class='text-red-400'>Withdrawal failed: {}</div>", e))
+            failed("Withdrawal failed", &e, None)
         }
     }
 }
 
 async fn borrow_handler(
     State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
     Form(form): Form<BorrowForm>,
 ) -> Html<String> {
     eprintln!("[DEBUG] Borrow: pool={}, account={}, loan_amount={}, collateral_asset={}, price={}", 
@@ -937,7 +1493,7 @@ async fn borrow_handler(
     use cradle_back_end::schema::{lendingpool::dsl as lp_dsl, asset_book::dsl as ab_dsl};
     use cradle_back_end::asset_book::db_types::AssetBookRecord;
     
-    let pool_clone = state.config.pool.clone();
+    let pool_clone = state.pool.clone();
     let pool_id = form.pool_id;
     let collateral_asset_uuid = match Uuid::from_str(&form.collateral_asset) {
         Ok(id) => id,
@@ -986,20 +1542,21 @@ async fn borrow_handler(
         collateral: collateral_asset_uuid,
     });
     
-    match call_action_router(ActionRouterInput::Pool(input), (*state.config).clone()).await {
+    match run_action(&state, &session, ActionRouterInput::Pool(input)).await {
         Ok(_) => {
             eprintln!("[DEBUG] Borrow successful");
             Html("<div class='bg-green-800 p-4 rounded text-green-200'>Loan taken successfully!</div>".to_string())
         },
         Err(e) => {
             eprintln!("[ERROR] Borrow failed: {:?}", e);
-            Html(format!("<div class='text-red-400'>Borrow failed: {}</div>", e))
+            failed("Borrow failed", &e, None)
         }
     }
 }
 
 async fn repay_handler(
     State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
     Form(form): Form<RepayForm>,
 ) -> Html<String> {
     eprintln!("[DEBUG] Repay: loan={}, account={}, amount={}", 
@@ -1009,7 +1566,7 @@ async fn repay_handler(
     use cradle_back_end::schema::{loans::dsl as loan_dsl, lendingpool::dsl as lp_dsl, asset_book::dsl as ab_dsl};
     use cradle_back_end::asset_book::db_types::AssetBookRecord;
     
-    let pool_clone = state.config.pool.clone();
+    let pool_clone = state.pool.clone();
     let loan_id = form.loan_id;
     
     // Get loan and reserve asset decimals
@@ -1036,14 +1593,14 @@ async fn repay_handler(
         amount: scaled_amount,
     });
     
-    match call_action_router(ActionRouterInput::Pool(input), (*state.config).clone()).await {
+    match run_action(&state, &session, ActionRouterInput::Pool(input)).await {
         Ok(_) => {
             eprintln!("[DEBUG] Repay successful");
             Html("<div class='bg-green-800 p-4 rounded text-green-200'>Loan repayment successful!</div>".to_string())
         },
         Err(e) => {
             eprintln!("[ERROR] Repay failed: {:?}", e);
-            Html(format!("<div class='text-red-400'>Repayment failed: {}</div>", e))
+            failed("Repayment failed", &e, None)
         }
     }
 }
@@ -1057,16 +1614,14 @@ async fn pool_stats_handler(
         None => return Html("<p class='text-gray-400'>No pool selected</p>".to_string())
     };
     
-    let pool_clone = state.config.pool.clone();
-    let mut wallet = state.config.wallet.clone();
-    
+    let pool_clone = state.pool.clone();
     let mut conn = match pool_clone.get() {
         Ok(c) => c,
         Err(_) => return Html("<p class='text-red-400'>Database error</p>".to_string())
     };
     
     eprintln!("[LENDING] Fetching pool stats for pool: {}", pool_id);
-    match get_pool_stats(&mut wallet, &mut conn, pool_id).await {
+    match get_pool_stats(&state.config.wallet, &mut conn, pool_id).await {
         Ok(stats) => {
             eprintln!("[LENDING] Pool stats retrieved - Supply: {}, Borrow: {}, Util: {}%", 
                 stats.total_supplied, stats.total_borrowed, stats.utilization);
@@ -1084,7 +1639,7 @@ async fn pool_stats_handler(
         },
         Err(e) => {
             eprintln!("[ERROR] Failed to get pool stats: {:?}", e);
-            Html(format!("<p class='text-red-400'>Failed to load stats: {}</p>", e))
+            failed("Failed to load stats", &e, Some(templates::Retry::Get(format!("/ui/lending/pool_stats?pool_id={}", pool_id))))
         }
     }
 }
@@ -1102,8 +1657,7 @@ async fn user_positions_handler(
         None => return Html("<p class='text-gray-400'>No wallet specified</p>".to_string())
     };
     
-    let pool_clone = state.config.pool.clone();
-    let mut wallet = state.config.wallet.clone();
+    let pool_clone = state.pool.clone();
     let mut conn = match pool_clone.get() {
         Ok(c) => c,
         Err(_) => return Html("<p class='text-red-400'>Database error</p>".to_string())
@@ -1113,7 +1667,7 @@ async fn user_positions_handler(
     
     // Get deposit position
     eprintln!("[LENDING] Fetching deposit position");
-    let deposit_html = match get_pool_deposit_position(&mut wallet, &mut conn, pool_id_param, wallet_id_param).await {
+    let deposit_html = match get_pool_deposit_position(&state.config.wallet, &mut conn, pool_id_param, wallet_id_param).await {
         Ok(pos) => format!("<p class='text-green-400'>Deposited: {} (Underlying: {})</p>", 
             pos.yield_token_balance, pos.underlying_value),
         Err(_) => "<p class='text-gray-500'>No deposits</p>".to_string()
@@ -1149,6 +1703,232 @@ async fn user_positions_handler(
     "##, deposit_html, loans_html))
 }
 
+async fn at_risk_loans_handler(
+    State(state): State<AppState>,
+    Query(q): Query<AtRiskQuery>,
+) -> Html<String> {
+    let below = q
+        .below
+        .as_deref()
+        .and_then(|b| BigDecimal::from_str(b.trim()).ok())
+        .unwrap_or_else(|| BigDecimal::from_str(DEFAULT_AT_RISK_HEALTH).unwrap());
+
+    let pool = state.pool.clone();
+    let threshold = below.clone();
+    let loans = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        at_risk_loans(&mut conn, &threshold)
+    }).await.unwrap();
+
+    match loans {
+        Ok(loans) => Html(templates::at_risk_loans(q.account_id, &below, &loans)),
+        Err(e) => failed(
+            "Failed to check loan health",
+            &e,
+            Some(templates::Retry::Get(format!("/ui/lending/at_risk?account_id={}&below={}", q.account_id, below))),
+        ),
+    }
+}
+
+async fn liquidate_form_handler(
+    State(state): State<AppState>,
+    Query(q): Query<LiquidateFormQuery>,
+) -> Html<String> {
+    use diesel::prelude::*;
+    use cradle_back_end::schema::loans::dsl as loans_dsl;
+
+    let pool = state.pool.clone();
+    let loan_id = q.loan_id;
+    let health = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        let loan = loans_dsl::loans.find(loan_id).get_result::<LoanRecord>(&mut conn)?;
+        loan_health(&mut conn, loan)
+    }).await.unwrap();
+
+    match health {
+        Ok(Some(health)) => Html(templates::liquidation_confirm(q.account_id, &health)),
+        Ok(None) => Html("<p class='text-gray-400'>This loan has no oracle price for its collateral or nothing left to cover</p>".to_string()),
+        Err(e) => failed(
+            "Failed to load loan",
+            &e,
+            Some(templates::Retry::Get(format!("/ui/lending/liquidate_form?loan_id={}&account_id={}", q.loan_id, q.account_id))),
+        ),
+    }
+}
+
+async fn liquidate_handler(
+    State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
+    Form(form): Form<LiquidateForm>,
+) -> Html<String> {
+    let amount = match BigDecimal::from_str(form.amount.trim()).ok().and_then(|a| a.to_u64()) {
+        Some(a) if a > 0 => a,
+        _ => return Html(format!("<div class='text-red-400'>Invalid amount: {}</div>", form.amount)),
+    };
+
+    let input = LendingPoolFunctionsInput::LiquidatePosition(LiquidatePositionInputArgs {
+        wallet: form.account_id,
+        loan: form.loan_id,
+        amount,
+    });
+    let liquidation_id = match run_action(&state, &session, ActionRouterInput::Pool(input)).await {
+        Ok(ActionRouterOutput::Pool(LendingPoolFunctionsOutput::LiquidatePosition(id))) => id,
+        Ok(_) => return Html("<div class='text-red-400'>Unexpected response from the lending pool</div>".to_string()),
+        Err(e) => {
+            eprintln!("[ERROR] Liquidating loan {} failed: {:?}", form.loan_id, e);
+            return failed("Liquidation failed", &e, None);
+        }
+    };
+    eprintln!(
+        "[LENDING] {} liquidated {} of loan {} from wallet {}",
+        session.operator.username, amount, form.loan_id, form.account_id
+    );
+
+    use diesel::prelude::*;
+    use cradle_back_end::schema::loanliquidations::dsl as liq_dsl;
+    use cradle_back_end::lending_pool::db_types::LoanLiquidationsRecord;
+
+    let pool = state.pool.clone();
+    let record = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        Ok::<_, anyhow::Error>(liq_dsl::loanliquidations.find(liquidation_id).get_result::<LoanLiquidationsRecord>(&mut conn)?)
+    }).await.unwrap();
+
+    match record {
+        Ok(record) => Html(templates::liquidation_result(form.account_id, &record)),
+        Err(e) => Html(format!(
+            "<div class='bg-green-800 p-4 rounded text-green-200'>Liquidation {} went through, but loading it failed: {}</div>",
+            liquidation_id, e
+        )),
+    }
+}
+
+async fn create_pool_form_handler(State(state): State<AppState>) -> Html<String> {
+    use diesel::prelude::*;
+    use cradle_back_end::schema::asset_book::dsl as ab_dsl;
+    use cradle_back_end::asset_book::db_types::AssetBookRecord;
+
+    let pool = state.pool.clone();
+    let assets = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get().ok()?;
+        ab_dsl::asset_book.load::<AssetBookRecord>(&mut conn).ok()
+    }).await.unwrap().unwrap_or_default();
+
+    Html(templates::create_pool_form(assets))
+}
+
+/// Caps come in whole reserve tokens; blank means uncapped.
+fn parse_cap(value: Option<&str>, decimals: i32) -> Result<Option<u64>, String> {
+    let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+    let scaled = BigDecimal::from_str(value)
+        .ok()
+        .filter(|v| *v > BigDecimal::from(0))
+        .map(|v| (v * BigDecimal::from(10i64.pow(decimals as u32))).round(0))
+        .and_then(|v| v.to_u64());
+    match scaled {
+        Some(cap) => Ok(Some(cap)),
+        None => Err(format!("Invalid cap: {}", value)),
+    }
+}
+
+async fn create_pool_handler(
+    State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
+    Form(form): Form<CreatePoolForm>,
+) -> Html<String> {
+    let name = form.name.trim().to_string();
+    if name.is_empty() {
+        return Html("<div class='text-red-400'>The pool needs a name</div>".to_string());
+    }
+
+    let yield_asset = if form.yield_asset_mode == "existing" {
+        match form.yield_asset.as_deref().and_then(|id| Uuid::from_str(id).ok()) {
+            Some(id) => YieldAsset::Existing(id),
+            None => return Html("<div class='text-red-400'>Pick the yield asset to use</div>".to_string()),
+        }
+    } else {
+        let yield_name = form.yield_name.as_deref().map(str::trim).unwrap_or_default();
+        let yield_symbol = form.yield_symbol.as_deref().map(str::trim).unwrap_or_default();
+        if yield_name.is_empty() || yield_symbol.is_empty() {
+            return Html("<div class='text-red-400'>A new yield asset needs a name and symbol</div>".to_string());
+        }
+        let decimals = match form.yield_decimals.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+            None => None,
+            Some(d) => match d.parse::<i32>() {
+                Ok(d) if (0..=18).contains(&d) => Some(d),
+                _ => return Html(format!("<div class='text-red-400'>Invalid decimals: {}</div>", d)),
+            },
+        };
+        YieldAsset::New(CreateNewYieldAsset {
+            name: yield_name.to_string(),
+            symbol: yield_symbol.to_string(),
+            decimals,
+            icon: None,
+        })
+    };
+
+    use diesel::prelude::*;
+    use cradle_back_end::schema::asset_book::dsl as ab_dsl;
+    use cradle_back_end::asset_book::db_types::AssetBookRecord;
+
+    let pool = state.pool.clone();
+    let reserve_asset = form.reserve_asset;
+    let reserve = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        Ok::<_, anyhow::Error>(ab_dsl::asset_book.find(reserve_asset).get_result::<AssetBookRecord>(&mut conn)?)
+    }).await.unwrap();
+    let reserve = match reserve {
+        Ok(reserve) => reserve,
+        Err(e) => return Html(format!("<div class='text-red-400'>Unknown reserve asset: {}</div>", e)),
+    };
+
+    let (supply_cap, borrow_cap) = match (
+        parse_cap(form.supply_cap.as_deref(), reserve.decimals),
+        parse_cap(form.borrow_cap.as_deref(), reserve.decimals),
+    ) {
+        (Ok(supply_cap), Ok(borrow_cap)) => (supply_cap, borrow_cap),
+        (Err(e), _) | (_, Err(e)) => return Html(format!("<div class='text-red-400'>{}</div>", e)),
+    };
+
+    let input = LendingPoolFunctionsInput::DeployLendingPool(DeployLendingPoolInputArgs {
+        pool: CreateLendingPoolArgs {
+            reserve_asset,
+            ltv: form.ltv,
+            optimal_utilization: form.optimal_utilization,
+            base_rate: form.base_rate,
+            slope_1: form.slope_1,
+            slope_2: form.slope_2,
+            liquidation_threshold: form.liquidation_threshold,
+            liquidation_discount: form.liquidation_discount,
+            reserve_factor: form.reserve_factor,
+            name: name.clone(),
+            supply_cap,
+            borrow_cap,
+        },
+        yield_asset,
+    });
+
+    match run_action(&state, &session, ActionRouterInput::Pool(input)).await {
+        Ok(ActionRouterOutput::Pool(LendingPoolFunctionsOutput::DeployLendingPool(id))) => {
+            eprintln!(
+                "[LENDING] {} deployed pool {} ({}) for {}",
+                session.operator.username, name, id, reserve.symbol
+            );
+            Html(format!(
+                "<div class='bg-green-800 p-4 rounded text-green-200'>Pool {} deployed: <span class='font-mono'>{}</span></div>",
+                name, id
+            ))
+        }
+        Ok(_) => Html("<div class='text-red-400'>Unexpected response from the lending pool</div>".to_string()),
+        Err(e) => {
+            eprintln!("[ERROR] Deploying pool {} failed: {:?}", name, e);
+            failed("Pool deployment failed", &e, None)
+        }
+    }
+}
+
 // Listing Handlers
 async fn listings_tab_handler(
     State(state): State<AppState>,
@@ -1161,7 +1941,7 @@ async fn listings_tab_handler(
     use cradle_back_end::schema::cradlenativelistings::dsl as listings_dsl;
     use cradle_back_end::schema::cradlelistedcompanies::dsl as companies_dsl;
     
-    let pool = state.config.pool.clone();
+    let pool = state.pool.clone();
     eprintln!("[LISTINGS] Fetching all listings and companies from database");
     
     let (listings, companies) = tokio::task::spawn_blocking(move || {
@@ -1197,7 +1977,7 @@ async fn create_listing_form_handler(
     use cradle_back_end::schema::asset_book::dsl as ab_dsl;
     use cradle_back_end::asset_book::db_types::AssetBookRecord;
     
-    let pool = state.config.pool.clone();
+    let pool = state.pool.clone();
     eprintln!("[LISTINGS] Fetching companies and assets");
     
     let (companies, assets) = tokio::task::spawn_blocking(move || {
@@ -1238,6 +2018,7 @@ async fn withdraw_listing_form_handler(Query(params): Query<QueryParams>) -> Htm
 
 async fn create_company_handler(
     State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
     Form(form): Form<CreateCompanyForm>,
 ) -> Html<String> {
     eprintln!("[LISTINGS] Creating company: name={}, account={}", form.name, form.account_id);
@@ -1248,20 +2029,21 @@ async fn create_company_handler(
         legal_documents: form.legal_documents,
     });
     
-    match call_action_router(ActionRouterInput::Listing(input), (*state.config).clone()).await {
+    match run_action(&state, &session, ActionRouterInput::Listing(input)).await {
         Ok(_) => {
             eprintln!("[LISTINGS] Company created successfully: {}", form.name);
             Html("<div class='bg-green-800 p-4 rounded text-green-200'>Company created successfully!</div>".to_string())
         },
         Err(e) => {
             eprintln!("[LISTINGS] Company creation failed: {:?}", e);
-            Html(format!("<div class='text-red-400'>Company creation failed: {}</div>", e))
+            failed("Company creation failed", &e, None)
         }
     }
 }
 
 async fn create_listing_handler(
     State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
     Form(form): Form<CreateListingForm>,
 ) -> Html<String> {
     eprintln!("[LISTINGS] Creating listing: name={}, company={}", form.name, form.company);
@@ -1287,7 +2069,7 @@ async fn create_listing_handler(
     };
     
     // Get asset decimals for scaling
-    let pool_clone = state.config.pool.clone();
+    let pool_clone = state.pool.clone();
     let (listed_decimals, purchase_decimals) = match tokio::task::spawn_blocking(move || {
         let mut conn = pool_clone.get().ok()?;
         let listed = ab_dsl::asset_book.find(listed_asset_uuid).first::<AssetBookRecord>(&mut conn).ok()?;
@@ -1321,20 +2103,21 @@ async fn create_listing_handler(
         max_supply: scaled_supply,
     });
     
-    match call_action_router(ActionRouterInput::Listing(input), (*state.config).clone()).await {
+    match run_action(&state, &session, ActionRouterInput::Listing(input)).await {
         Ok(_) => {
             eprintln!("[LISTINGS] Listing created successfully: {}", form.name);
             Html("<div class='bg-green-800 p-4 rounded text-green-200'>Listing created successfully!</div>".to_string())
         },
         Err(e) => {
             eprintln!("[LISTINGS] Listing creation failed: {:?}", e);
-            Html(format!("<div class='text-red-400'>Listing creation failed: {}</div>", e))
+            failed("Listing creation failed", &e, None)
         }
     }
 }
 
 async fn purchase_listing_handler(
     State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
     Form(form): Form<PurchaseListingForm>,
 ) -> Html<String> {
     eprintln!("[LISTINGS] Purchase request: listing={}, account={}, amount={}", 
@@ -1345,7 +2128,7 @@ async fn purchase_listing_handler(
     use cradle_back_end::asset_book::db_types::AssetBookRecord;
     
     // Get listing and asset decimals
-    let pool_clone = state.config.pool.clone();
+    let pool_clone = state.pool.clone();
     let listing_id = form.listing_id;
     
     let decimals = match tokio::task::spawn_blocking(move || {
@@ -1374,22 +2157,24 @@ async fn purchase_listing_handler(
         wallet: form.account_id,
         amount: scaled_amount,
         listing: form.listing_id,
+        tranche: None,
     });
     
-    match call_action_router(ActionRouterInput::Listing(input), (*state.config).clone()).await {
+    match run_action(&state, &session, ActionRouterInput::Listing(input)).await {
         Ok(_) => {
             eprintln!("[LISTINGS] Purchase successful");
             Html("<div class='bg-green-800 p-4 rounded text-green-200'>Purchase successful!</div>".to_string())
         },
         Err(e) => {
             eprintln!("[LISTINGS] Purchase failed: {:?}", e);
-            Html(format!("<div class='text-red-400'>Purchase failed: {}</div>", e))
+            failed("Purchase failed", &e, None)
         }
     }
 }
 
 async fn return_listing_handler(
     State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
     Form(form): Form<ReturnListingForm>,
 ) -> Html<String> {
     eprintln!("[LISTINGS] Return request: listing={}, account={}, amount={}", 
@@ -1400,7 +2185,7 @@ async fn return_listing_handler(
     use cradle_back_end::asset_book::db_types::AssetBookRecord;
     
     // Get listing and asset decimals
-    let pool_clone = state.config.pool.clone();
+    let pool_clone = state.pool.clone();
     let listing_id = form.listing_id;
     
     let decimals = match tokio::task::spawn_blocking(move || {
@@ -1431,20 +2216,21 @@ async fn return_listing_handler(
         listing: form.listing_id,
     });
     
-    match call_action_router(ActionRouterInput::Listing(input), (*state.config).clone()).await {
+    match run_action(&state, &session, ActionRouterInput::Listing(input)).await {
         Ok(_) => {
             eprintln!("[LISTINGS] Return successful");
             Html("<div class='bg-green-800 p-4 rounded text-green-200'>Return successful!</div>".to_string())
         },
         Err(e) => {
             eprintln!("[LISTINGS] Return failed: {:?}", e);
-            Html(format!("<div class='text-red-400'>Return failed: {}</div>", e))
+            failed("Return failed", &e, None)
         }
     }
 }
 
 async fn withdraw_listing_handler(
     State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
     Form(form): Form<WithdrawListingForm>,
 ) -> Html<String> {
     eprintln!("[LISTINGS] Withdraw request: listing={}, account={}, amount={}", 
@@ -1455,7 +2241,7 @@ async fn withdraw_listing_handler(
     use cradle_back_end::asset_book::db_types::AssetBookRecord;
     
     // Get listing and purchase asset decimals for withdrawal
-    let pool_clone = state.config.pool.clone();
+    let pool_clone = state.pool.clone();
     let listing_id = form.listing_id;
     
     let decimals = match tokio::task::spawn_blocking(move || {
@@ -1485,14 +2271,14 @@ async fn withdraw_listing_handler(
         listing: form.listing_id,
     });
     
-    match call_action_router(ActionRouterInput::Listing(input), (*state.config).clone()).await {
+    match run_action(&state, &session, ActionRouterInput::Listing(input)).await {
         Ok(_) => {
             eprintln!("[LISTINGS] Withdrawal successful");
             Html("<div class='bg-green-800 p-4 rounded text-green-200'>Withdrawal to beneficiary successful!</div>".to_string())
         },
         Err(e) => {
             eprintln!("[LISTINGS] Withdrawal failed: {:?}", e);
-            Html(format!("<div class='text-red-400'>Withdrawal failed: {}</div>", e))
+            failed("Withdrawal failed", &e, None)
         }
     }
 }
@@ -1511,18 +2297,26 @@ async fn listing_stats_handler(
     // Call GetStats via action router
     let input = CradleNativeListingFunctionsInput::GetStats(listing_id);
     
-    match call_action_router(ActionRouterInput::Listing(input), (*state.config).clone()).await {
-        Ok(ActionRouterOutput::Listing(_output)) => {
-            // For now, return a simple success message
-            // In a real implementation, you'd parse the output and display the stats
-            eprintln!("[LISTINGS] Stats retrieved successfully");
-            Html(r##"
+    match dispatch(&state, ActionRouterInput::Listing(input)).await {
+        Ok(ActionRouterOutput::Listing(CradleNativeListingFunctionsOutput::GetStats(stats))) => {
+            eprintln!("[LISTINGS] Stats retrieved - Purchased: {}, Buyers: {}",
+                stats.total_purchased, stats.unique_buyers);
+            let status_color = match stats.status {
+                ListingStatus::Open => "text-green-400",
+                ListingStatus::Paused => "text-yellow-400",
+                _ => "text-gray-300",
+            };
+            Html(format!(r##"
                 <div class="grid grid-cols-2 gap-4">
-                    <div><p class="text-gray-400">Total Purchased</p><p class="text-2xl font-bold text-white">Loading...</p></div>
-                    <div><p class="text-gray-400">Total Supply</p><p class="text-2xl font-bold text-white">Loading...</p></div>
-                    <div><p class="text-gray-400">Status</p><p class="text-2xl font-bold text-green-400">Active</p></div>
+                    <div><p class="text-gray-400">Total Purchased</p><p class="text-2xl font-bold text-white">{}</p></div>
+                    <div><p class="text-gray-400">Remaining Supply</p><p class="text-2xl font-bold text-white">{} / {}</p></div>
+                    <div><p class="text-gray-400">Unique Buyers</p><p class="text-2xl font-bold text-blue-400">{}</p></div>
+                    <div><p class="text-gray-400">Raised</p><p class="text-2xl font-bold text-white">{}</p></div>
+                    <div><p class="text-gray-400">Status</p><p class="text-2xl font-bold {}">{:?}</p></div>
+                    <div><p class="text-gray-400">Lifecycle</p><p class="text-2xl font-bold text-white">{:?}</p></div>
                 </div>
-            "##.to_string())
+            "##, stats.total_purchased, stats.remaining_supply, stats.max_supply, stats.unique_buyers,
+                stats.raised_amount, status_color, stats.status, stats.lifecycle))
         },
         Ok(_) => {
             eprintln!("[LISTINGS] Unexpected output type from action router");
@@ -1530,7 +2324,7 @@ async fn listing_stats_handler(
         },
         Err(e) => {
             eprintln!("[LISTINGS] Failed to get stats: {:?}", e);
-            Html(format!("<p class='text-red-400'>Failed to load stats: {}</p>", e))
+            failed("Failed to load stats", &e, Some(templates::Retry::Get(format!("/ui/listings/stats?listing_id={}", listing_id))))
         }
     }
 }
@@ -1548,7 +2342,7 @@ async fn oracle_tab_handler(
     use cradle_back_end::schema::asset_book::dsl as ab_dsl;
     use cradle_back_end::asset_book::db_types::AssetBookRecord;
     
-    let pool = state.config.pool.clone();
+    let pool = state.pool.clone();
     eprintln!("[ORACLE] Fetching pools and assets from database");
     
     let (pools, assets) = tokio::task::spawn_blocking(move || {
@@ -1568,6 +2362,7 @@ async fn oracle_tab_handler(
 
 async fn set_oracle_price_handler(
     State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
     Form(form): Form<SetOraclePriceForm>,
 ) -> Html<String> {
     eprintln!("[ORACLE] Set price request: pool={}, asset={}, price={}", 
@@ -1578,7 +2373,7 @@ async fn set_oracle_price_handler(
     use cradle_back_end::asset_book::db_types::AssetBookRecord;
     
     // Get pool and reserve asset to determine decimals
-    let pool_clone = state.config.pool.clone();
+    let pool_clone = state.pool.clone();
     let pool_id = form.pool_id;
     
     let decimals = match tokio::task::spawn_blocking(move || {
@@ -1605,9 +2400,7 @@ async fn set_oracle_price_handler(
     eprintln!("[ORACLE] Scaled price: {} (multiplier: 10^{})", scaled_price, decimals);
     
     // Get DB connection and wallet
-    let mut app_config_clone = (*state.config).clone();
-    let mut wallet = app_config_clone.wallet;
-    let pool_db = state.config.pool.clone();
+    let pool_db = state.pool.clone();
     let mut conn = match pool_db.get() {
         Ok(c) => c,
         Err(_) => return Html("<div class='text-red-400'>Database connection failed</div>".to_string())
@@ -1615,14 +2408,336 @@ async fn set_oracle_price_handler(
     
     // Call oracle::publish_price
     eprintln!("[ORACLE] Publishing price to oracle contract...");
-    match publish_price(&mut conn, &mut wallet, form.pool_id, form.asset_id, scaled_price).await {
+    let published = publish_price(
+        &mut conn,
+        &state.config.wallet,
+        form.pool_id,
+        form.asset_id,
+        scaled_price.clone(),
+        &session.operator.username,
+        serde_json::json!([{ "kind": "manual", "price": form.price }]),
+    ).await;
+    record_audit(
+        &state,
+        &session,
+        "Oracle.SetPrice",
+        serde_json::json!({ "pool": form.pool_id, "asset": form.asset_id, "price": scaled_price.to_string() }),
+        published.as_ref().map(|_| serde_json::Value::Null).map_err(|e| e.to_string()),
+    ).await;
+    match published {
         Ok(_) => {
             eprintln!("[ORACLE] Price published successfully");
             Html("<div class='bg-green-800 p-4 rounded text-green-200'>Oracle price updated successfully!</div>".to_string())
         },
         Err(e) => {
             eprintln!("[ORACLE] Price publication failed: {:?}", e);
-            Html(format!("<div class='text-red-400'>Failed to update oracle price: {}</div>", e))
+            // publishing the same price again is harmless
+            failed(
+                "Failed to update oracle price",
+                &e,
+                Some(templates::Retry::Post(
+                    "/ui/oracle/set_price".to_string(),
+                    serde_json::json!({ "pool_id": form.pool_id, "asset_id": form.asset_id, "price": form.price }),
+                )),
+            )
+        }
+    }
+}
+
+async fn operations_tab_handler() -> Html<String> {
+    Html(templates::operations_tab(SocketSettings::from_env().admin_key.is_some()))
+}
+
+
+async fn health_tab_handler(State(state): State<AppState>) -> Html<String> {
+    use cradle_back_end::schema::markets::dsl::*;
+    use diesel::prelude::*;
+
+    let pool = state.pool.clone();
+    let markets_list = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        anyhow::Ok(markets.order(name.asc()).load::<MarketRecord>(&mut conn)?)
+    }).await.unwrap().unwrap_or_default();
+
+    Html(templates::health_tab(markets_list, state.jobs.recent(AGGREGATION_JOB, 5)))
+}
+
+const AGGREGATION_JOB: &str = "aggregation";
+
+/// Windows handed to the backfill processor at a time. The job checks for
+/// cancellation and reports progress between chunks.
+const AGGREGATION_CHUNK: i32 = 200;
+
+/// Starts a candle backfill for the market's base asset, one target per
+/// interval. `interval` repeats, so the form is read as raw pairs.
+async fn run_aggregation_handler(
+    State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
+    Form(fields): Form<Vec<(String, String)>>,
+) -> Html<String> {
+    let mut market_id = None;
+    let mut from = None;
+    let mut to = None;
+    let mut resume = false;
+    let mut intervals = Vec::new();
+    for (field, value) in fields {
+        match field.as_str() {
+            "market_id" => market_id = Uuid::parse_str(&value).ok(),
+            "from" => from = chrono::NaiveDateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M").ok(),
+            "to" => to = chrono::NaiveDateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M").ok(),
+            "resume" => resume = value == "true",
+            "interval" => intervals.extend(
+                TimeSeriesInterval::ALL.iter().find(|i| i.as_str() == value).cloned(),
+            ),
+            _ => {}
+        }
+    }
+
+    let (Some(market_id), Some(from), Some(to)) = (market_id, from, to) else {
+        return Html("<div class='text-red-400'>Pick a market and a time range</div>".to_string());
+    };
+    if from >= to {
+        return Html("<div class='text-red-400'>The range has to end after it starts</div>".to_string());
+    }
+    if intervals.is_empty() {
+        return Html("<div class='text-red-400'>Pick at least one interval</div>".to_string());
+    }
+
+    let market = match dispatch(
+        &state,
+        ActionRouterInput::Markets(MarketProcessorInput::GetMarket(market_id)),
+    ).await {
+        Ok(ActionRouterOutput::Markets(cradle_back_end::market::processor_enums::MarketProcessorOutput::GetMarket(m))) => m,
+        _ => return Html("<div class='text-red-400'>Market not found</div>".to_string())
+    };
+
+    let labels = intervals
+        .iter()
+        .map(|i| format!("{} {} from {} to {}", market.name, i.as_str(), from.format("%Y-%m-%d %H:%M"), to.format("%Y-%m-%d %H:%M")))
+        .collect();
+    let job_id = state.jobs.start(AGGREGATION_JOB, &session.operator.username, labels);
+    eprintln!("[AGGREGATION] Job {} started by {} for market {}", job_id, session.operator.username, market.id);
+    record_audit(
+        &state,
+        &session,
+        "Aggregators.BackfillTrades",
+        serde_json::json!({
+            "market": market.id,
+            "intervals": intervals.iter().map(|i| i.as_str()).collect::<Vec<_>>(),
+            "from": from,
+            "to": to,
+            "resume": resume,
+        }),
+        Ok(serde_json::json!({ "job": job_id })),
+    ).await;
+
+    let args: Vec<BackfillInputArgs> = intervals
+        .into_iter()
+        .map(|interval| BackfillInputArgs {
+            market_id: market.id,
+            asset_id: market.asset_one,
+            interval,
+            backfill_start: from,
+            backfill_end: to,
+        })
+        .collect();
+    tokio::spawn(run_aggregation_job(state.clone(), job_id, args, resume));
+
+    match state.jobs.get(job_id) {
+        Some(job) => Html(templates::job_progress(&job)),
+        None => Html("<div class='text-red-400'>Aggregation job went missing</div>".to_string()),
+    }
+}
+
+async fn run_aggregation_job(state: AppState, job_id: Uuid, targets: Vec<BackfillInputArgs>, resume: bool) {
+    for (i, args) in targets.iter().enumerate() {
+        if state.jobs.cancel_requested(job_id) {
+            break;
+        }
+        let status = match backfill_in_chunks(&state, job_id, i, args, resume).await {
+            Ok(status) => status,
+            Err(e) => {
+                eprintln!("[AGGREGATION] Job {} {} failed: {:?}", job_id, args.interval.as_str(), e);
+                TargetStatus::Failed(e.to_string())
+            }
+        };
+        state.jobs.set_status(job_id, i, status);
+    }
+
+    state.jobs.finish(job_id);
+    eprintln!("[AGGREGATION] Job {} finished", job_id);
+}
+
+/// Runs one backfill as a series of short `BackfillTrades` calls so it can
+/// report how far it got and stop when asked.
+async fn backfill_in_chunks(
+    state: &AppState,
+    job_id: Uuid,
+    target: usize,
+    args: &BackfillInputArgs,
+    resume: bool,
+) -> anyhow::Result<TargetStatus> {
+    let mut local_config = AggregatorsConfig::default();
+    let mut conn = state.pool.get()?;
+
+    let mut start = args.backfill_start;
+    if resume
+        && let Some(checkpoint) = get_last_checkpoint(args.market_id, args.asset_id, &args.interval, &mut conn).await?
+    {
+        start = checkpoint.clamp(args.backfill_start, args.backfill_end);
+    }
+
+    let step = chrono::Duration::seconds(args.interval.seconds());
+    let total = ((args.backfill_end - start).num_seconds() + step.num_seconds() - 1) / step.num_seconds();
+    let mut done = 0i64;
+    let mut bars = 0u32;
+
+    while start < args.backfill_end {
+        if state.jobs.cancel_requested(job_id) {
+            return Ok(TargetStatus::Stopped(format!("Stopped at {} with {} bars written", start, bars)));
         }
+        state.jobs.set_status(
+            job_id,
+            target,
+            TargetStatus::Running(format!("{} of {} windows, {} bars", done, total, bars)),
+        );
+
+        let end = std::cmp::min(start + step * AGGREGATION_CHUNK, args.backfill_end);
+        let chunk = AggregatorsProcessorInput::BackfillTrades(BackfillInputArgs {
+            market_id: args.market_id,
+            asset_id: args.asset_id,
+            interval: args.interval.clone(),
+            backfill_start: start,
+            backfill_end: end,
+        });
+        let mut config = state.config.clone();
+        let processed = chunk.process(&mut config, &mut local_config, Some(&mut conn)).await?;
+        if let AggregatorsProcessorOutput::BackfillTrades(created) = processed {
+            bars += created;
+        }
+
+        done += ((end - start).num_seconds() + step.num_seconds() - 1) / step.num_seconds();
+        start = end;
+        // let the progress poll and other requests in between chunks
+        tokio::task::yield_now().await;
+    }
+
+    Ok(TargetStatus::Done(format!("{} bars written", bars)))
+}
+
+async fn health_report_handler(State(state): State<AppState>) -> Html<String> {
+    match health_report(&state.config).await {
+        Ok(report) => Html(templates::health_report(&report)),
+        Err(e) => failed("Failed to check system health", &e, Some(templates::Retry::Get("/ui/health/report".to_string()))),
+    }
+}
+
+async fn ramps_tab_handler(State(state): State<AppState>) -> Html<String> {
+    let pool = state.pool.clone();
+    let summary = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        ramp_summary(&mut conn, 7)
+    }).await.unwrap();
+
+    match summary {
+        Ok(summary) => Html(templates::ramps_tab(&summary)),
+        Err(e) => failed("Failed to load ramp activity", &e, Some(templates::Retry::Get("/ui/tabs/ramps".to_string()))),
+    }
+}
+
+async fn retry_ramp_order_handler(
+    State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
+    Form(form): Form<RampActionForm>,
+) -> Html<String> {
+    let ramper = match Ramper::from_env() {
+        Ok(r) => r,
+        Err(_) => return Html("<div class='text-red-400'>Failed to configure Ramper provider</div>".to_string())
+    };
+    let mut conn = match state.pool.get() {
+        Ok(c) => c,
+        Err(_) => return Html("<div class='text-red-400'>Database connection failed</div>".to_string())
+    };
+
+    let input = RampAdminActionInputArgs { reason: None };
+    match retry_order(&ramper, &state.config.wallet, &mut conn, form.order_id, &session.operator, input).await {
+        Ok(details) => Html(format!(
+            "<div class='bg-green-800 p-4 rounded text-green-200'>Retried order {}; it is now {:?}. Reopen the tab to refresh.</div>",
+            details.order.id, details.order.status
+        )),
+        Err(e) => failed(
+            "Retry failed",
+            &e,
+            Some(templates::Retry::Post("/ui/ramps/retry".to_string(), serde_json::json!({ "order_id": form.order_id }))),
+        ),
+    }
+}
+
+async fn cancel_ramp_order_handler(
+    State(state): State<AppState>,
+    Extension(session): Extension<AdminSession>,
+    Form(form): Form<RampActionForm>,
+) -> Html<String> {
+    let ramper = match Ramper::from_env() {
+        Ok(r) => r,
+        Err(_) => return Html("<div class='text-red-400'>Failed to configure Ramper provider</div>".to_string())
+    };
+    let mut conn = match state.pool.get() {
+        Ok(c) => c,
+        Err(_) => return Html("<div class='text-red-400'>Database connection failed</div>".to_string())
+    };
+
+    let input = RampAdminActionInputArgs { reason: None };
+    match cancel_order(&ramper, &mut conn, form.order_id, &session.operator, input).await {
+        Ok(details) => Html(format!(
+            "<div class='bg-green-800 p-4 rounded text-green-200'>Cancelled order {}. Reopen the tab to refresh.</div>",
+            details.order.id
+        )),
+        Err(e) => failed("Cancel failed", &e, None),
+    }
+}
+
+#[derive(Deserialize)]
+struct AuditQuery {
+    actor: Option<String>,
+    action: Option<String>,
+    subject: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+}
+
+impl AuditQuery {
+    /// Times come from datetime-local inputs; ones that don't parse are
+    /// dropped rather than failing the search.
+    fn filter(self) -> AuditLogFilter {
+        let time = |v: Option<String>| {
+            v.and_then(|v| chrono::NaiveDateTime::parse_from_str(v.trim(), "%Y-%m-%dT%H:%M").ok())
+        };
+        AuditLogFilter {
+            actor: self.actor,
+            action: self.action,
+            subject: self.subject,
+            since: time(self.since),
+            until: time(self.until),
+            limit: None,
+        }
+    }
+}
+
+async fn audit_tab_handler(Query(q): Query<AuditQuery>) -> Html<String> {
+    Html(templates::audit_tab(&q.filter()))
+}
+
+async fn audit_entries_handler(State(state): State<AppState>, Query(q): Query<AuditQuery>) -> Html<String> {
+    let filter = q.filter();
+    let pool = state.pool.clone();
+    let entries = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        get_audit_log(&mut conn, &filter)
+    }).await.unwrap();
+
+    match entries {
+        Ok(entries) => Html(templates::audit_entries(&entries)),
+        Err(e) => failed("Failed to load the audit log", &e, None),
     }
 }
@@ -1,13 +1,161 @@
 use cradle_back_end::accounts::db_types::{CradleAccountRecord, CradleWalletAccountRecord};
+use cradle_back_end::accounts_ledger::db_types::LedgerRow;
 use cradle_back_end::market::db_types::{MarketRecord, MarketType};
-use cradle_back_end::order_book::db_types::{OrderBookRecord, OrderType};
+use cradle_back_end::order_book::db_types::{OrderBookRecord, OrderStatus, OrderType};
 use cradle_back_end::asset_book::db_types::AssetBookRecord;
-use cradle_back_end::lending_pool::db_types::{LendingPoolRecord, LoanRecord};
+use cradle_back_end::lending_pool::db_types::{LendingPoolRecord, LoanLiquidationsRecord, LoanRecord};
+use cradle_back_end::lending_pool::liquidations::LoanHealth;
 use cradle_back_end::listing::db_types::{CradleNativeListingRow, CompanyRow, ListingStatus};
+use cradle_back_end::ramper::admin::RampSummary;
+use cradle_back_end::ramper::db_types::RampOrderRow;
+use cradle_back_end::health::HealthReport;
+use cradle_back_end::api::error::ApiError;
+use cradle_back_end::audit_log::db_types::MutationAuditRow;
+use cradle_back_end::audit_log::operations::AuditLogFilter;
+use cradle_back_end::market_time_series::db_types::{MarketTimeSeriesRecord, TimeSeriesInterval};
+use bigdecimal::ToPrimitive;
+use cradle_back_end::accounts::operators::AdminSession;
 use bigdecimal::BigDecimal;
+use std::collections::HashMap;
 use uuid::Uuid;
 
-pub fn base_layout(content: &str) -> String {
+use super::SocketSettings;
+use super::account_detail::{Section, WalletLoan, WalletTrade};
+use super::jobs::{Job, TargetStatus};
+
+/// Page-wide Socket.IO client. Keeps one connection per namespace, lets a
+/// view follow a room for as long as its element is on the page, and feeds
+/// `system:event`s to the header ticker and the Operations tab.
+const LIVE_SCRIPT: &str = r##"
+    <script>
+        window.cradleLive = (() => {
+            const meta = (name) => document.querySelector(`meta[name="${name}"]`)?.content || '';
+            const apiUrl = meta('cradle-api-url');
+            const adminKey = meta('cradle-admin-key');
+            const sockets = {};
+            const following = {};
+            const recent = [];
+
+            const showStatus = () => {
+                document.querySelectorAll('[data-live-status]').forEach(el => {
+                    const up = sockets[el.dataset.liveStatus]?.connected;
+                    el.textContent = up ? 'Live' : 'Offline';
+                    el.className = up ? 'text-xs text-green-400' : 'text-xs text-gray-500';
+                });
+            };
+
+            const socketFor = (ns) => {
+                if (ns === '/admin' && !adminKey) return null;
+                if (!sockets[ns]) {
+                    const socket = io(apiUrl + ns, ns === '/admin' ? { auth: { token: adminKey } } : {});
+                    socket.on('connect', showStatus);
+                    socket.on('disconnect', showStatus);
+                    sockets[ns] = socket;
+                }
+                return sockets[ns];
+            };
+
+            const showTicker = () => {
+                const ticker = document.getElementById('ops-ticker');
+                if (!ticker) return;
+                if (!recent.length) {
+                    ticker.textContent = adminKey ? 'No operational events' : '';
+                    ticker.className = 'text-xs text-gray-500';
+                    return;
+                }
+                ticker.textContent = `${recent[0].kind.replace(/_/g, ' ')} at ${recent[0].at} (${recent.length} recent)`;
+                ticker.className = 'text-xs text-yellow-400 hover:text-yellow-300 truncate max-w-xs';
+            };
+
+            const admin = socketFor('/admin');
+            if (admin) {
+                admin.on('system:event', (e) => {
+                    recent.unshift(e);
+                    if (recent.length > 200) recent.pop();
+                    showTicker();
+                    document.dispatchEvent(new CustomEvent('cradle:system-event', { detail: e }));
+                });
+            }
+            document.addEventListener('htmx:afterSettle', () => { showTicker(); showStatus(); });
+
+            return {
+                recent,
+                enabled: (ns) => ns !== '/admin' || !!adminKey,
+                // Join a room and call `handler(event, payload)` for `events` until
+                // `el` leaves the page or something else follows under `key`.
+                // Rooms are joined again after a reconnect.
+                follow(key, ns, subscribe, payload, events, el, handler) {
+                    following[key]?.();
+                    const socket = socketFor(ns);
+                    if (!socket) return;
+
+                    const join = () => socket.emit(subscribe, payload);
+                    const stop = () => {
+                        if (following[key] !== stop) return;
+                        delete following[key];
+                        socket.off('connect', join);
+                        events.forEach(name => socket.off(name, listeners[name]));
+                        socket.off('batch', onBatch);
+                        socket.emit('un' + subscribe, payload);
+                    };
+                    const deliver = (name, data) => {
+                        if (!document.body.contains(el)) return stop();
+                        handler(name, data);
+                    };
+                    const listeners = Object.fromEntries(events.map(name => [name, (data) => deliver(name, data)]));
+                    // busy rooms can be coalesced into `batch` emits
+                    const onBatch = (items) => items.forEach(({ event, payload }) => {
+                        if (events.includes(event)) deliver(event, payload);
+                    });
+
+                    socket.on('connect', join);
+                    events.forEach(name => socket.on(name, listeners[name]));
+                    socket.on('batch', onBatch);
+                    if (socket.connected) join();
+                    following[key] = stop;
+                    showStatus();
+                },
+            };
+        })();
+    </script>"##;
+
+pub fn base_layout(content: &str, session: Option<&AdminSession>, sockets: Option<&SocketSettings>) -> String {
+    // htmx sends the token back on every request; the server rejects writes without it
+    let session_html = match session {
+        Some(session) => format!(
+            r##"
+    <meta name="csrf-token" content="{}">
+    <script>
+        document.addEventListener('htmx:configRequest', (e) => {{
+            e.detail.headers['X-CSRF-Token'] = document.querySelector('meta[name="csrf-token"]').content;
+        }});
+    </script>"##,
+            session.csrf_token
+        ),
+        None => String::new(),
+    };
+    let operator_html = match session {
+        Some(session) => format!(
+            r##"
+    <div class="fixed bottom-3 right-4 z-50 flex items-center gap-3 bg-gray-800/90 border border-gray-700 rounded-lg px-3 py-2 text-xs text-gray-400">
+        <span>{} <span class="text-gray-500">({:?})</span></span>
+        <button class="text-blue-400 hover:text-blue-300" hx-post="/logout">Sign out</button>
+    </div>"##,
+            session.operator.username, session.operator.role
+        ),
+        None => String::new(),
+    };
+    let live_html = match sockets {
+        Some(sockets) => format!(
+            r##"
+    <meta name="cradle-api-url" content="{}">
+    <meta name="cradle-admin-key" content="{}">{LIVE_SCRIPT}"##,
+            escape_html(&sockets.api_url),
+            escape_html(sockets.admin_key.as_deref().unwrap_or_default())
+        ),
+        None => String::new(),
+    };
+
     format!(
          r##"
 <!DOCTYPE html>
@@ -18,6 +166,8 @@ pub fn base_layout(content: &str) -> String {
     <title>Cradle Admin Dashboard</title>
     <script src="https://unpkg.com/htmx.org@1.9.10"></script>
     <script src="https://cdn.tailwindcss.com"></script>
+    <script src="https://cdn.socket.io/4.7.5/socket.io.min.js"></script>
+    <script src="https://unpkg.com/lightweight-charts@4.1.3/dist/lightweight-charts.standalone.production.js"></script>{session_html}{live_html}
     <style>
         .sidebar-scroll::-webkit-scrollbar {{ width: 6px; }}
         .sidebar-scroll::-webkit-scrollbar-thumb {{ background-color: #4b5563; border-radius: 3px; }}
@@ -26,14 +176,47 @@ pub fn base_layout(content: &str) -> String {
 <body class="bg-gray-900 text-gray-100 font-sans antialiased h-screen flex overflow-hidden">
     <div id="main-content" class="flex w-full h-full">
         {content}
-    </div>
+    </div>{operator_html}
 </body>
 </html>
 "##
     )
 }
 
-pub fn index_page() -> String {
+pub fn login_page(error: Option<&str>) -> String {
+    let error_html = error
+        .map(|e| format!(r##"<div class="bg-red-900/50 border border-red-700 text-red-300 text-sm rounded-lg p-3">{}</div>"##, e))
+        .unwrap_or_default();
+
+    base_layout(
+        &format!(
+            r##"
+        <div class="flex-1 flex items-center justify-center">
+            <form method="post" action="/login" class="w-full max-w-sm bg-gray-800 p-8 rounded-2xl border border-gray-700 shadow-xl space-y-5">
+                <div class="text-center">
+                    <h1 class="text-2xl font-bold text-white">Cradle Admin</h1>
+                    <p class="text-gray-400 text-sm mt-1">Sign in to continue</p>
+                </div>
+                {error_html}
+                <div>
+                    <label class="block text-sm font-medium text-gray-300 mb-2">Username</label>
+                    <input type="text" name="username" autocomplete="username" class="w-full bg-gray-900 border border-gray-600 rounded-lg p-3 text-white focus:ring-2 focus:ring-blue-500" required>
+                </div>
+                <div>
+                    <label class="block text-sm font-medium text-gray-300 mb-2">Password</label>
+                    <input type="password" name="password" autocomplete="current-password" class="w-full bg-gray-900 border border-gray-600 rounded-lg p-3 text-white focus:ring-2 focus:ring-blue-500" required>
+                </div>
+                <button type="submit" class="w-full bg-blue-600 hover:bg-blue-500 text-white font-bold py-3 rounded-lg transition-colors">Sign in</button>
+            </form>
+        </div>
+        "##
+        ),
+        None,
+        None,
+    )
+}
+
+pub fn index_page(session: &AdminSession, sockets: &SocketSettings) -> String {
     base_layout(
         r##"
         <div class="h-full w-64 bg-gray-800 border-r border-gray-700 flex flex-col" hx-get="/ui/accounts" hx-trigger="load" hx-swap="innerHTML">
@@ -44,7 +227,9 @@ pub fn index_page() -> String {
             <h1 class="text-3xl font-bold mb-4">Cradle Admin</h1>
             <p>Select an account from the sidebar to begin.</p>
         </div>
-        "##
+        "##,
+        Some(session),
+        Some(sockets),
     )
 }
 
@@ -107,7 +292,11 @@ pub fn dashboard(account_id: Uuid, balances: Vec<Balance>) -> String {
                 <div>
                      <div class="text-xs text-gray-500 uppercase tracking-wider font-semibold">Active Account</div>
                      <div class="text-xl font-mono text-white">{}</div>
+                     <button class="text-xs text-blue-400 hover:text-blue-300"
+                             hx-get="/ui/account/{account_id}/detail" hx-target="#tab-content">Wallet history</button>
                 </div>
+                <button id="ops-ticker" class="text-xs text-gray-500" title="Latest operational event"
+                        hx-get="/ui/tabs/operations" hx-target="#tab-content"></button>
                 <div class="flex gap-3">
                     {balance_html}
                 </div>
@@ -148,6 +337,26 @@ pub fn dashboard(account_id: Uuid, balances: Vec<Balance>) -> String {
                         hx-target="#tab-content">
                     Oracle
                 </button>
+                <button class="px-6 py-3 text-sm font-medium text-gray-400 border-b-2 border-transparent hover:text-gray-200 hover:bg-gray-700/50 rounded-t-lg transition-colors focus:outline-none"
+                        hx-get="/ui/tabs/ramps"
+                        hx-target="#tab-content">
+                    Ramps
+                </button>
+                <button class="px-6 py-3 text-sm font-medium text-gray-400 border-b-2 border-transparent hover:text-gray-200 hover:bg-gray-700/50 rounded-t-lg transition-colors focus:outline-none"
+                        hx-get="/ui/tabs/audit"
+                        hx-target="#tab-content">
+                    Audit Log
+                </button>
+                <button class="px-6 py-3 text-sm font-medium text-gray-400 border-b-2 border-transparent hover:text-gray-200 hover:bg-gray-700/50 rounded-t-lg transition-colors focus:outline-none"
+                        hx-get="/ui/tabs/health"
+                        hx-target="#tab-content">
+                    Health
+                </button>
+                <button class="px-6 py-3 text-sm font-medium text-gray-400 border-b-2 border-transparent hover:text-gray-200 hover:bg-gray-700/50 rounded-t-lg transition-colors focus:outline-none"
+                        hx-get="/ui/tabs/operations"
+                        hx-target="#tab-content">
+                    Operations
+                </button>
             </div>
 
             <!-- Tab Content Area -->
@@ -220,39 +429,113 @@ pub fn markets_tab(account_id: Uuid, markets: Vec<MarketRecord>) -> String {
     )
 }
 
-pub fn market_detail(market: MarketRecord, account_id: Uuid, recent_orders: Vec<OrderBookRecord>) -> String {
-    let mut orders_html = String::new();
-    for o in recent_orders {
-        // Determine side: Buy if asking for Asset One (Base)
-        let is_buy = o.ask_asset == market.asset_two;
-        let side_text = if is_buy { "Buy" } else { "Sell" };
-        let side_color = if is_buy { "text-green-400" } else { "text-red-400" };
+/// One row of the market's order table. Open orders get cancel and amend
+/// buttons that grey the row out straight away and swap in the row the
+/// server sends back; a request that fails outright reloads the row as is.
+pub fn order_row(o: &OrderBookRecord, quote_asset: Uuid, note: Option<&str>) -> String {
+    // Buy if asking for Asset Two (Quote)
+    let is_buy = o.ask_asset == quote_asset;
+    let side_text = if is_buy { "Buy" } else { "Sell" };
+    let side_color = if is_buy { "text-green-400" } else { "text-red-400" };
+
+    let actions = if matches!(o.status, OrderStatus::Open) {
+        format!(
+            r##"<button class="text-xs text-red-400 hover:text-red-300 mr-2"
+                        hx-post="/ui/orders/cancel" hx-vals='{{"order_id": "{}"}}'
+                        hx-target="closest tr" hx-swap="outerHTML"
+                        hx-confirm="Cancel this order and release its unfilled amount?"
+                        hx-on::before-request="this.closest('tr').classList.add('opacity-50'); this.closest('tr').querySelector('[data-status]').textContent = 'Cancelling...'"
+                        hx-on::after-request="if (!event.detail.successful) htmx.trigger(this.closest('tr'), 'revert')">Cancel</button>
+                <button class="text-xs text-blue-400 hover:text-blue-300"
+                        hx-get="/ui/orders/amend_form?order_id={}"
+                        hx-target="closest tr" hx-swap="outerHTML">Amend</button>"##,
+            o.id, o.id
+        )
+    } else {
+        String::new()
+    };
+    let note = note
+        .map(|n| format!(r#"<div class="text-xs text-red-400 mt-1">{}</div>"#, n))
+        .unwrap_or_default();
 
-        orders_html.push_str(&format!(
-            r##"
-            <tr class="border-b border-gray-700 hover:bg-gray-700/50">
+    format!(
+        r##"
+            <tr class="border-b border-gray-700 hover:bg-gray-700/50"
+                hx-get="/ui/orders/row?order_id={}" hx-trigger="revert" hx-swap="outerHTML">
                 <td class="px-4 py-3 font-mono text-sm {}">{}</td>
+                <td class="px-4 py-3 text-sm">{:?}</td>
                 <td class="px-4 py-3 text-sm">{}</td>
                 <td class="px-4 py-3 text-sm">{}</td>
                 <td class="px-4 py-3 text-sm">{}</td>
-                <td class="px-4 py-3 text-sm">{}</td>
-                <td class="px-4 py-3 text-xs text-gray-400">{}</td>
+                <td class="px-4 py-3 text-xs text-gray-400" data-status>{:?}</td>
+                <td class="px-4 py-3 text-right whitespace-nowrap">{}{}</td>
             </tr>
             "##,
-            side_color,
-            side_text,
-            format!("{:?}", o.order_type),
-             o.price,
-             o.bid_amount,
-             o.ask_amount,
-             format!("{:?}", o.status)
-        ));
+        o.id,
+        side_color,
+        side_text,
+        o.order_type,
+        o.price,
+        o.bid_amount,
+        o.ask_amount,
+        o.status,
+        actions,
+        note
+    )
+}
+
+/// Editable version of an order's row. Amounts start at what is still
+/// unfilled, in the same base units the table shows.
+pub fn amend_order_row(o: &OrderBookRecord) -> String {
+    let input = "w-full bg-gray-700 border-none rounded p-1 text-sm font-mono focus:ring-1 focus:ring-blue-500";
+    format!(
+        r##"
+            <tr class="border-b border-gray-700 bg-gray-700/40">
+                <td class="px-4 py-3 text-xs text-gray-400" colspan="2">
+                    <input type="hidden" name="order_id" value="{}" />
+                    Amending {:?} order
+                </td>
+                <td class="px-2 py-2"><input type="text" name="price" value="{}" class="{}" required></td>
+                <td class="px-2 py-2"><input type="text" name="bid_amount" value="{}" class="{}" required></td>
+                <td class="px-2 py-2"><input type="text" name="ask_amount" value="{}" class="{}" required></td>
+                <td class="px-4 py-3 text-xs text-gray-400" data-status>Open</td>
+                <td class="px-4 py-3 text-right whitespace-nowrap">
+                    <button class="text-xs text-green-400 hover:text-green-300 mr-2"
+                            hx-post="/ui/orders/amend" hx-include="closest tr"
+                            hx-target="closest tr" hx-swap="outerHTML"
+                            hx-on::before-request="this.closest('tr').classList.add('opacity-50'); this.closest('tr').querySelector('[data-status]').textContent = 'Amending...'">Save</button>
+                    <button class="text-xs text-gray-400 hover:text-gray-300"
+                            hx-get="/ui/orders/row?order_id={}"
+                            hx-target="closest tr" hx-swap="outerHTML">Back</button>
+                </td>
+            </tr>
+            "##,
+        o.id,
+        o.order_type,
+        o.price, input,
+        &o.bid_amount - &o.filled_bid_amount, input,
+        &o.ask_amount - &o.filled_ask_amount, input,
+        o.id
+    )
+}
+
+/// Body of the market's order table, re-fetched whenever the book or its
+/// trades change.
+pub fn market_orders(quote_asset: Uuid, recent_orders: &[OrderBookRecord]) -> String {
+    let mut orders_html = String::new();
+    for o in recent_orders {
+        orders_html.push_str(&order_row(o, quote_asset, None));
     }
-    
+
     // Fallback if empty
     if orders_html.is_empty() {
-        orders_html = r#"<tr><td colspan="5" class="p-4 text-center text-gray-500 italic">No recent orders</td></tr>"#.to_string();
+        orders_html = r#"<tr><td colspan="7" class="p-4 text-center text-gray-500 italic">No recent orders</td></tr>"#.to_string();
     }
+    orders_html
+}
+
+pub fn market_detail(market: MarketRecord, account_id: Uuid, recent_orders: Vec<OrderBookRecord>) -> String {
+    let orders_html = market_orders(market.asset_two, &recent_orders);
 
     format!(
          r##"
@@ -273,6 +556,8 @@ pub fn market_detail(market: MarketRecord, account_id: Uuid, recent_orders: Vec<
                             <span class="font-mono text-gray-200">{}</span>
                         </div>
                      </div>
+                     <button class="mt-4 text-sm text-blue-400 hover:text-blue-300"
+                             hx-get="/ui/tabs/audit?subject={market_id}" hx-target="#tab-content">Change history</button>
                  </div>
 
                  <!-- Order Form -->
@@ -325,13 +610,18 @@ pub fn market_detail(market: MarketRecord, account_id: Uuid, recent_orders: Vec<
                  </div>
             </div>
 
+            <div class="lg:col-span-2 flex flex-col gap-6 h-full">
+            <!-- Candles -->
+            <div id="market-chart" class="bg-gray-800 rounded-xl border border-gray-700 p-4 min-h-[380px]"
+                 hx-get="/ui/market_chart?market_id={}&interval=1min" hx-trigger="load">
+                <div class="flex justify-center items-center h-[340px] text-gray-500 animate-pulse">Loading chart...</div>
+            </div>
+
             <!-- Recent Orders (Right Side) -->
-            <div class="lg:col-span-2 bg-gray-800 rounded-xl border border-gray-700 flex flex-col h-full overflow-hidden">
+            <div class="bg-gray-800 rounded-xl border border-gray-700 flex flex-col flex-1 overflow-hidden">
                 <div class="p-4 border-b border-gray-700 flex justify-between items-center bg-gray-700/30">
                     <h4 class="font-bold text-gray-200">Recent Orders</h4>
-                    <button class="text-xs text-blue-400 hover:text-blue-300"
-                            hx-get="/ui/market_detail?market_id={}&account_id={}"
-                            hx-target="#market-view">Refresh</button>
+                    <span data-live-status="/markets" class="text-xs text-gray-500">Connecting...</span>
                 </div>
                 <div class="overflow-x-auto flex-1">
                     <table class="w-full text-left">
@@ -343,15 +633,42 @@ pub fn market_detail(market: MarketRecord, account_id: Uuid, recent_orders: Vec<
                                 <th class="px-4 py-2">Bid Amt</th>
                                 <th class="px-4 py-2">Ask Amt</th>
                                 <th class="px-4 py-2">Status</th>
+                                <th class="px-4 py-2"></th>
                             </tr>
                         </thead>
-                        <tbody class="divide-y divide-gray-700/50">
+                        <tbody id="market-orders" class="divide-y divide-gray-700/50">
                             {orders_html}
                         </tbody>
                     </table>
                 </div>
             </div>
+            </div>
          </div>
+
+         <script>
+            (() => {{
+                const orders = document.getElementById('market-orders');
+                const marketId = '{market_id}';
+                let pending = null;
+
+                // a fill touches several orders at once; fetch the table once per burst,
+                // and leave it alone while an order is being amended
+                const reload = (_, e) => {{
+                    if (e?.market_id && e.market_id !== marketId) return;
+                    clearTimeout(pending);
+                    pending = setTimeout(() => {{
+                        if (orders.querySelector('input')) return;
+                        htmx.ajax('GET', '/ui/market_orders?market_id={market_id}&account_id={account_id}', {{ target: orders, swap: 'innerHTML' }});
+                    }}, 300);
+                }};
+
+                const payload = {{ market_id: marketId }};
+                window.cradleLive.follow('market-orders', '/markets', 'subscribe:orderbook', payload,
+                    ['order:placed', 'order:filled', 'order:updated', 'order:cancelled'], orders, reload);
+                window.cradleLive.follow('market-trades', '/markets', 'subscribe:trades', payload,
+                    ['trade:executed'], orders, reload);
+            }})();
+         </script>
          "##,
          market.name,
          market.description.unwrap_or_default(),
@@ -359,7 +676,105 @@ pub fn market_detail(market: MarketRecord, account_id: Uuid, recent_orders: Vec<
          market.asset_two,
          account_id,
          market.id,
-         market.id, account_id
+         market.id,
+         market_id = market.id
+    )
+}
+
+/// Candles for the market's `asset`, kept current from the `candles` room
+/// while the chart is on screen.
+pub fn market_chart(market_id: Uuid, asset: Uuid, interval: &TimeSeriesInterval, candles: Vec<MarketTimeSeriesRecord>) -> String {
+    let mut interval_buttons = String::new();
+    for i in TimeSeriesInterval::ALL.iter() {
+        let class = if i.as_str() == interval.as_str() {
+            "bg-blue-600 text-white"
+        } else {
+            "bg-gray-700 text-gray-400 hover:text-gray-200"
+        };
+        interval_buttons.push_str(&format!(
+            r##"<button class="px-2 py-1 rounded text-xs {}" hx-get="/ui/market_chart?market_id={}&interval={}" hx-target="#market-chart">{}</button>"##,
+            class, market_id, i.as_str(), i.as_str()
+        ));
+    }
+
+    // the chart wants one candle per time, oldest first; the newest record wins
+    let mut by_time: std::collections::BTreeMap<i64, (chrono::NaiveDateTime, serde_json::Value)> =
+        std::collections::BTreeMap::new();
+    for c in candles {
+        let time = c.start_time.and_utc().timestamp();
+        if by_time.get(&time).is_none_or(|(created, _)| c.created_at >= *created) {
+            by_time.insert(time, (c.created_at, serde_json::json!({
+                "time": time,
+                "open": c.open.to_f64().unwrap_or_default(),
+                "high": c.high.to_f64().unwrap_or_default(),
+                "low": c.low.to_f64().unwrap_or_default(),
+                "close": c.close.to_f64().unwrap_or_default(),
+            })));
+        }
+    }
+    let data: Vec<serde_json::Value> = by_time.into_values().map(|(_, candle)| candle).collect();
+
+    let empty_html = if data.is_empty() {
+        r##"<div id="market-chart-empty" class="absolute inset-0 flex justify-center items-center text-gray-500 italic pointer-events-none">No candles for this interval yet</div>"##
+    } else {
+        ""
+    };
+    let body = format!(
+        r##"
+            <div class="relative">
+                <div id="market-chart-canvas" class="h-[320px]"></div>
+                {empty_html}
+            </div>
+            <script>
+                (() => {{
+                    const el = document.getElementById('market-chart-canvas');
+                    const chart = LightweightCharts.createChart(el, {{
+                        height: 320,
+                        layout: {{ background: {{ color: '#1f2937' }}, textColor: '#9ca3af' }},
+                        grid: {{ vertLines: {{ color: '#374151' }}, horzLines: {{ color: '#374151' }} }},
+                        timeScale: {{ timeVisible: true, secondsVisible: {} }},
+                    }});
+                    chart.applyOptions({{ width: el.clientWidth }});
+                    const series = chart.addCandlestickSeries();
+                    series.setData({});
+                    chart.timeScale().fitContent();
+
+                    const marketId = '{market_id}';
+                    const asset = '{asset}';
+                    const interval = '{}';
+                    window.cradleLive.follow('market-candles', '/markets', 'subscribe:candles', {{ market_id: marketId, interval }},
+                        ['candle:update', 'candle:close'], el, (_, c) => {{
+                            if (c.market_id !== marketId || c.asset !== asset || c.interval !== interval) return;
+                            document.getElementById('market-chart-empty')?.remove();
+                            try {{
+                                series.update({{
+                                    time: Date.parse(c.start_time + 'Z') / 1000,
+                                    open: Number(c.open),
+                                    high: Number(c.high),
+                                    low: Number(c.low),
+                                    close: Number(c.close),
+                                }});
+                            }} catch (e) {{
+                                // a late update for a candle older than the last one drawn
+                            }}
+                        }});
+                }})();
+            </script>
+            "##,
+        interval.seconds() < 60,
+        serde_json::Value::Array(data),
+        interval.as_str()
+    );
+
+    format!(
+        r##"
+        <div class="flex justify-between items-center mb-3">
+            <h4 class="font-bold text-gray-200">Candles</h4>
+            <div class="flex flex-wrap gap-1">{}</div>
+        </div>
+        {}
+        "##,
+        interval_buttons, body
     )
 }
 
@@ -382,11 +797,13 @@ pub fn on_ramp_tab(account_id: Uuid) -> String {
                     </div>
 
                     <div>
-                        <label class="block text-sm font-medium text-gray-300 mb-2">Amount</label>
-                         <div class="relative">
-                            <span class="absolute left-3 top-3 text-gray-500">$</span>
-                            <input type="number" name="amount" placeholder="100.00" class="w-full bg-gray-900 border border-gray-600 rounded-lg p-3 pl-8 text-white focus:ring-2 focus:ring-blue-500 focus:border-transparent transition-all" required>
-                        </div>
+                        <label class="block text-sm font-medium text-gray-300 mb-2">Token Amount</label>
+                        <input type="number" name="amount" placeholder="100" class="w-full bg-gray-900 border border-gray-600 rounded-lg p-3 text-white focus:ring-2 focus:ring-blue-500 focus:border-transparent transition-all" required>
+                    </div>
+
+                    <div>
+                        <label class="block text-sm font-medium text-gray-300 mb-2">Pay In</label>
+                        <input type="text" name="currency" placeholder="Fiat currency (e.g. KES)" class="w-full bg-gray-900 border border-gray-600 rounded-lg p-3 text-white focus:ring-2 focus:ring-blue-500 focus:border-transparent transition-all" required>
                     </div>
 
                     <div>
@@ -412,46 +829,152 @@ pub fn on_ramp_tab(account_id: Uuid) -> String {
     )
 }
 
-pub fn faucet_tab(account_id: Uuid, assets: Vec<AssetBookRecord>) -> String {
+pub fn faucet_tab(account_id: Uuid, assets: Vec<AssetBookRecord>, wallets: Vec<CradleWalletAccountRecord>) -> String {
     let mut asset_opts = String::new();
     for a in assets {
         asset_opts.push_str(&format!(
-            r##"<option value="{}">{} ({})</option>"##,
-            a.id, a.symbol, a.id 
+            r##"<label class="flex items-center gap-2 text-sm text-gray-300">
+                    <input type="checkbox" name="asset_id" value="{}" class="rounded bg-gray-900 border-gray-600">
+                    <span class="font-bold">{}</span><span class="text-xs text-gray-500 font-mono">{}</span>
+                </label>"##,
+            a.id, a.symbol, a.id
+        ));
+    }
+
+    let mut wallet_opts = String::new();
+    for w in wallets {
+        wallet_opts.push_str(&format!(
+            r##"<label class="flex items-center gap-2 text-sm text-gray-300">
+                    <input type="checkbox" name="wallet_id" value="{}" class="rounded bg-gray-900 border-gray-600">
+                    <span class="font-mono text-xs">{}</span>
+                </label>"##,
+            w.id, w.address
         ));
     }
+    if wallet_opts.is_empty() {
+        wallet_opts.push_str(r##"<p class="text-gray-500 text-sm">No retail wallets yet</p>"##);
+    }
 
     format!(
          r##"
         <div class="max-w-2xl mx-auto space-y-8">
             <div class="text-center">
                 <h2 class="text-3xl font-bold text-white mb-2">Testnet Faucet</h2>
-                <p class="text-gray-400">Request free tokens for testing purposes.</p>
+                <p class="text-gray-400">Associate, KYC, mint and airdrop test tokens to one or many wallets.</p>
             </div>
 
             <div class="bg-gray-800 p-8 rounded-2xl border border-gray-700 shadow-xl">
                  <form hx-post="/ui/faucet" hx-target="#faucet-result" class="space-y-6">
                     <input type="hidden" name="account_id" value="{}" />
-                    
+
                     <div>
-                        <label class="block text-sm font-medium text-gray-300 mb-2">Token to Request</label>
-                        <select name="asset_id" class="w-full bg-gray-900 border border-gray-600 rounded-lg p-3 text-white focus:ring-2 focus:ring-blue-500 focus:border-transparent transition-all" required>
-                            <option value="">-- Select Token --</option>
+                        <label class="block text-sm font-medium text-gray-300 mb-2">Tokens</label>
+                        <div class="bg-gray-900 border border-gray-600 rounded-lg p-3 space-y-2 max-h-48 overflow-y-auto">
                             {}
-                        </select>
+                        </div>
+                    </div>
+
+                    <div>
+                        <label class="block text-sm font-medium text-gray-300 mb-2">Send To</label>
+                        <div class="space-y-2 text-sm text-gray-300">
+                            <label class="flex items-center gap-2"><input type="radio" name="target" value="self" checked> This wallet</label>
+                            <label class="flex items-center gap-2"><input type="radio" name="target" value="selected"> Wallets picked below</label>
+                            <label class="flex items-center gap-2"><input type="radio" name="target" value="retail"> All retail wallets</label>
+                        </div>
+                        <div class="mt-3 bg-gray-900 border border-gray-600 rounded-lg p-3 space-y-2 max-h-48 overflow-y-auto">
+                            {}
+                        </div>
                     </div>
 
                     <button type="submit" class="w-full bg-purple-600 hover:bg-purple-500 text-white font-bold py-4 rounded-lg shadow-lg hover:shadow-purple-500/20 transition-all transform hover:-translate-y-0.5">
                         Request Airdrop
                     </button>
-                    
-                    <div id="faucet-result" class="mt-4"></div>
                  </form>
+                 <div id="faucet-result" class="mt-6"></div>
             </div>
         </div>
         "##,
         account_id,
-        asset_opts
+        asset_opts,
+        wallet_opts
+    )
+}
+
+/// A background job's progress, polling itself until the job finishes.
+pub fn job_progress(job: &Job) -> String {
+    let mut rows = String::new();
+    for t in &job.targets {
+        let (color, text) = match &t.status {
+            TargetStatus::Pending => ("text-gray-500", "Waiting".to_string()),
+            TargetStatus::Running(step) => ("text-blue-400", format!("{}...", step)),
+            TargetStatus::Done(note) => ("text-green-400", note.clone()),
+            TargetStatus::Failed(error) => ("text-red-400", error.clone()),
+            TargetStatus::Stopped(note) => ("text-yellow-400", note.clone()),
+            TargetStatus::Skipped => ("text-gray-500", "Skipped".to_string()),
+        };
+        rows.push_str(&format!(
+            r##"<tr class="border-t border-gray-700">
+                <td class="py-2 text-gray-300 text-xs font-mono break-all">{}</td>
+                <td class="py-2 text-xs {}">{}</td>
+            </tr>"##,
+            escape_html(&t.label),
+            color,
+            escape_html(&text)
+        ));
+    }
+
+    let (poll, controls) = if job.finished() {
+        (
+            String::new(),
+            format!(
+                r##"<span class="text-xs text-gray-400">Finished {}{}</span>"##,
+                job.finished_at.map(|at| at.format("%H:%M:%S").to_string()).unwrap_or_default(),
+                if job.cancel_requested { " (cancelled)" } else { "" }
+            ),
+        )
+    } else if job.cancel_requested {
+        (
+            format!(r##"hx-get="/ui/jobs/{}" hx-trigger="every 1s" hx-swap="outerHTML""##, job.id),
+            r##"<span class="text-xs text-yellow-400">Stopping after the current target...</span>"##.to_string(),
+        )
+    } else {
+        (
+            format!(r##"hx-get="/ui/jobs/{}" hx-trigger="every 1s" hx-swap="outerHTML""##, job.id),
+            format!(
+                r##"<button class="text-xs text-red-400 hover:text-red-300"
+                        hx-post="/ui/jobs/{}/cancel" hx-target="closest [data-job]" hx-swap="outerHTML">Cancel</button>"##,
+                job.id
+            ),
+        )
+    };
+
+    format!(
+        r##"
+        <div data-job="{}" {} class="bg-gray-900 border border-gray-700 rounded-xl p-4 space-y-3">
+            <div class="flex justify-between items-center">
+                <div class="text-sm text-gray-300">
+                    <span class="font-bold text-white">{}</span> started by {} at {}:
+                    {} of {} done, <span class="text-red-400">{} failed</span>
+                </div>
+                {}
+            </div>
+            <div class="max-h-72 overflow-y-auto">
+                <table class="w-full text-left">
+                    <tbody>{}</tbody>
+                </table>
+            </div>
+        </div>
+        "##,
+        job.id,
+        poll,
+        job.kind,
+        escape_html(&job.started_by),
+        job.started_at.format("%H:%M:%S"),
+        job.completed(),
+        job.targets.len(),
+        job.failed(),
+        controls,
+        rows
     )
 }
 // Lending Tab Templates
@@ -476,8 +999,11 @@ pub fn lending_tab(account_id: Uuid, pools: Vec<LendingPoolRecord>) -> String {
 
             <!-- Pool Selector -->
             <div class="bg-gray-800 p-6 rounded-2xl border border-gray-700">
-                <label class="block text-sm font-medium text-gray-300 mb-2">Select Pool</label>
-                <select id="pool-selector" 
+                <div class="flex justify-between items-center mb-2">
+                    <label class="block text-sm font-medium text-gray-300">Select Pool</label>
+                    <span data-live-status="/admin" class="text-xs text-gray-500"></span>
+                </div>
+                <select id="pool-selector" name="pool_id"
                         class="w-full bg-gray-900 border border-gray-600 text-gray-100 rounded-lg p-3 focus:ring-2 focus:ring-blue-500"
                         hx-get="/ui/lending/pool_stats"
                         hx-target="#pool-stats"
@@ -512,6 +1038,23 @@ pub fn lending_tab(account_id: Uuid, pools: Vec<LendingPoolRecord>) -> String {
                 <h3 class="text-xl font-bold text-white mb-4">Your Positions</h3>
                 <p class="text-gray-400 text-center">Select a pool to view your positions</p>
             </div>
+
+            <!-- New Pool -->
+            <div id="create-pool-panel" class="bg-gray-800 p-6 rounded-2xl border border-gray-700">
+                <div class="flex justify-between items-center">
+                    <h3 class="text-xl font-bold text-white">New Pool</h3>
+                    <button class="text-sm text-blue-400 hover:text-blue-300"
+                            hx-get="/ui/lending/create_pool_form" hx-target="#create-pool-panel">Open form</button>
+                </div>
+            </div>
+
+            <!-- At-Risk Loans -->
+            <div class="bg-gray-800 p-6 rounded-2xl border border-gray-700">
+                <div id="at-risk-loans" hx-get="/ui/lending/at_risk?account_id={}" hx-trigger="load">
+                    <p class="text-gray-500 text-center animate-pulse">Checking loan health...</p>
+                </div>
+                <div id="liquidation-panel" class="mt-4"></div>
+            </div>
         </div>
 
         <script>
@@ -539,17 +1082,236 @@ pub fn lending_tab(account_id: Uuid, pools: Vec<LendingPoolRecord>) -> String {
                 }});
             }});
             
-            // When pool changes, update positions
+            // When pool changes, update positions and keep the pool's numbers current
+            // as supplies, borrows, repayments, liquidations and accruals come in
             document.getElementById('pool-selector').addEventListener('change', function() {{
                 const poolId = this.value;
                 const accountId = '{}';
                 if (poolId) {{
                     htmx.ajax('GET', `/ui/lending/user_positions?pool_id=${{poolId}}&wallet_id=${{accountId}}`, {{target: '#user-positions'}});
+
+                    let pending = null;
+                    const refresh = () => {{
+                        htmx.ajax('GET', `/ui/lending/pool_stats?pool_id=${{poolId}}&account_id=${{accountId}}`, {{target: '#pool-stats'}});
+                        htmx.ajax('GET', `/ui/lending/user_positions?pool_id=${{poolId}}&wallet_id=${{accountId}}`, {{target: '#user-positions'}});
+                        const atRisk = document.querySelector('#at-risk-loans form');
+                        if (atRisk) htmx.trigger(atRisk, 'submit');
+                    }};
+                    window.cradleLive.follow('lending-pool', '/admin', 'subscribe:pool', {{ pool_id: poolId }},
+                        ['lending:supply', 'lending:withdraw', 'lending:borrow', 'lending:repay', 'lending:liquidation', 'lending:accrued'],
+                        this, (_, e) => {{
+                            if (e.pool_id !== poolId) return;
+                            clearTimeout(pending);
+                            pending = setTimeout(refresh, 500);
+                        }});
                 }}
             }});
         </script>
         "##,
-        pool_opts, account_id, account_id, account_id
+        pool_opts, account_id, account_id, account_id, account_id
+    )
+}
+
+/// Loans below the health threshold, each with a button that opens the
+/// liquidation confirmation below the table.
+pub fn at_risk_loans(account_id: Uuid, below: &BigDecimal, loans: &[LoanHealth]) -> String {
+    let mut rows = String::new();
+    for h in loans {
+        let color = if h.health_factor < BigDecimal::from(1) { "text-red-400" } else { "text-yellow-400" };
+        rows.push_str(&format!(
+            r##"<tr class="border-t border-gray-700">
+                <td class="py-2 font-mono text-xs text-gray-300">{}</td>
+                <td class="py-2 font-mono text-xs text-gray-400">{}</td>
+                <td class="py-2 text-right text-gray-300">{}</td>
+                <td class="py-2 text-right text-gray-300">{}</td>
+                <td class="py-2 text-right text-gray-300">{}</td>
+                <td class="py-2 text-right font-bold {}">{}</td>
+                <td class="py-2 text-right">
+                    <button class="bg-red-700 hover:bg-red-600 text-white text-xs px-3 py-1 rounded"
+                            hx-get="/ui/lending/liquidate_form?loan_id={}&account_id={}"
+                            hx-target="#liquidation-panel">Liquidate</button>
+                </td>
+            </tr>"##,
+            h.loan.id, h.loan.wallet_id, h.collateral_amount, h.collateral_value, h.outstanding_debt,
+            color, h.health_factor, h.loan.id, account_id
+        ));
+    }
+    if rows.is_empty() {
+        rows.push_str(r##"<tr><td colspan="7" class="py-4 text-center text-gray-500">No active loans below this health factor</td></tr>"##);
+    }
+
+    format!(
+        r##"
+        <div class="flex justify-between items-center mb-4">
+            <h3 class="text-xl font-bold text-white">At-Risk Loans</h3>
+            <form class="flex items-center gap-2 text-sm" hx-get="/ui/lending/at_risk" hx-target="#at-risk-loans">
+                <input type="hidden" name="account_id" value="{}" />
+                <label class="text-gray-400">Health below</label>
+                <input type="text" name="below" value="{}" class="w-20 bg-gray-700 border-none rounded p-1 text-sm font-mono focus:ring-1 focus:ring-blue-500">
+                <button type="submit" class="text-xs text-blue-400 hover:text-blue-300">Apply</button>
+            </form>
+        </div>
+        <p class="text-xs text-gray-500 mb-3">Worked out from oracle prices and recorded repayments; accrued interest is left out, so debts can only be higher than shown. Loans whose collateral has no oracle price are not listed.</p>
+        <table class="w-full text-sm">
+            <thead><tr class="text-left text-gray-500"><th>Loan</th><th>Borrower Wallet</th><th class="text-right">Collateral</th><th class="text-right">Value</th><th class="text-right">Debt</th><th class="text-right">Health</th><th></th></tr></thead>
+            <tbody>{}</tbody>
+        </table>
+        "##,
+        account_id, below, rows
+    )
+}
+
+/// Confirmation step before liquidating: what the loan looks like now and
+/// how much of its debt the selected wallet will cover.
+pub fn liquidation_confirm(account_id: Uuid, health: &LoanHealth) -> String {
+    format!(
+        r##"
+        <div class="bg-gray-900 border border-red-800 rounded-xl p-6 space-y-4">
+            <h4 class="text-lg font-bold text-red-300">Liquidate loan {}</h4>
+            <div class="grid grid-cols-2 md:grid-cols-4 gap-4 text-sm">
+                <div><span class="block text-xs text-gray-500">Borrower wallet</span><span class="font-mono text-gray-200 text-xs">{}</span></div>
+                <div><span class="block text-xs text-gray-500">Collateral locked</span><span class="text-gray-200">{}</span></div>
+                <div><span class="block text-xs text-gray-500">Debt (at least)</span><span class="text-gray-200">{}</span></div>
+                <div><span class="block text-xs text-gray-500">Health factor</span><span class="text-red-400 font-bold">{}</span></div>
+            </div>
+            <form hx-post="/ui/lending/liquidate" hx-target="#liquidation-panel" class="space-y-4"
+                  hx-confirm="Liquidate this position? The pool contract will seize the borrower's collateral.">
+                <input type="hidden" name="loan_id" value="{}" />
+                <input type="hidden" name="account_id" value="{}" />
+                <div>
+                    <label class="block text-xs font-medium text-gray-400 mb-1">Debt to cover (reserve base units), paid by wallet {}</label>
+                    <input type="text" name="amount" value="{}" class="w-full bg-gray-700 border-none rounded p-2 text-sm font-mono focus:ring-1 focus:ring-blue-500" required>
+                </div>
+                <div class="flex gap-2">
+                    <button type="submit" class="bg-red-700 hover:bg-red-600 text-white font-bold py-2 px-4 rounded-lg">Confirm Liquidation</button>
+                    <button type="button" class="text-gray-400 hover:text-gray-300 px-4"
+                            onclick="document.getElementById('liquidation-panel').innerHTML = ''">Cancel</button>
+                </div>
+            </form>
+        </div>
+        "##,
+        health.loan.id,
+        health.loan.wallet_id,
+        health.collateral_amount,
+        health.outstanding_debt,
+        health.health_factor,
+        health.loan.id,
+        account_id,
+        account_id,
+        health.outstanding_debt
+    )
+}
+
+/// Outcome of a liquidation, with the transaction it went through in.
+pub fn liquidation_result(account_id: Uuid, liquidation: &LoanLiquidationsRecord) -> String {
+    format!(
+        r##"
+        <div class="bg-green-800 p-4 rounded text-green-200 space-y-1">
+            <p>Liquidated {} of loan {}.</p>
+            <p class="text-sm">Transaction: <span class="font-mono">{}</span></p>
+        </div>
+        <div hx-get="/ui/lending/at_risk?account_id={}" hx-trigger="load" hx-target="#at-risk-loans"></div>
+        "##,
+        liquidation.liquidation_amount,
+        liquidation.loan_id,
+        liquidation.transaction.as_deref().unwrap_or("not recorded"),
+        account_id
+    )
+}
+
+/// Deploys a pool through the factory. Rates, LTV and liquidation settings
+/// are basis points, with the lending CLI's defaults; caps are in whole
+/// reserve tokens.
+pub fn create_pool_form(assets: Vec<AssetBookRecord>) -> String {
+    let mut asset_opts = String::new();
+    for a in &assets {
+        asset_opts.push_str(&format!(r##"<option value="{}">{} ({})</option>"##, a.id, a.symbol, a.name));
+    }
+
+    let bps_field = |name: &str, label: &str, default: u64| {
+        format!(
+            r##"<div>
+                <label class="block text-xs font-medium text-gray-400 mb-1">{}</label>
+                <input type="number" min="0" max="10000" name="{}" value="{}" class="w-full bg-gray-900 border border-gray-600 rounded-lg p-2 text-white font-mono" required>
+            </div>"##,
+            label, name, default
+        )
+    };
+    let rate_fields = [
+        bps_field("ltv", "LTV", 7500),
+        bps_field("liquidation_threshold", "Liquidation Threshold", 8500),
+        bps_field("liquidation_discount", "Liquidation Discount", 500),
+        bps_field("reserve_factor", "Reserve Factor", 1000),
+        bps_field("optimal_utilization", "Optimal Utilization", 8000),
+        bps_field("base_rate", "Base Rate", 100),
+        bps_field("slope_1", "Slope 1", 4000),
+        bps_field("slope_2", "Slope 2", 6000),
+    ]
+    .join("");
+
+    format!(
+        r##"
+        <h3 class="text-xl font-bold text-white mb-4">New Pool</h3>
+        <form hx-post="/ui/lending/create_pool" hx-target="#create-pool-result" class="space-y-6"
+              hx-confirm="Deploy this lending pool? Its parameters can't be changed from here afterwards.">
+            <div class="grid grid-cols-1 md:grid-cols-2 gap-4">
+                <div>
+                    <label class="block text-sm font-medium text-gray-300 mb-2">Name</label>
+                    <input type="text" name="name" class="w-full bg-gray-900 border border-gray-600 rounded-lg p-3 text-white" required>
+                </div>
+                <div>
+                    <label class="block text-sm font-medium text-gray-300 mb-2">Reserve Asset</label>
+                    <select name="reserve_asset" class="w-full bg-gray-900 border border-gray-600 text-gray-100 rounded-lg p-3" required>
+                        <option value="">-- Select Asset --</option>
+                        {}
+                    </select>
+                </div>
+            </div>
+
+            <div class="space-y-3">
+                <label class="block text-sm font-medium text-gray-300">Yield Asset</label>
+                <div class="flex gap-4 text-sm text-gray-300">
+                    <label><input type="radio" name="yield_asset_mode" value="new" checked
+                                  onchange="document.getElementById('yield-new').classList.remove('hidden'); document.getElementById('yield-existing').classList.add('hidden')"> Issue a new one</label>
+                    <label><input type="radio" name="yield_asset_mode" value="existing"
+                                  onchange="document.getElementById('yield-existing').classList.remove('hidden'); document.getElementById('yield-new').classList.add('hidden')"> Use an existing asset</label>
+                </div>
+                <div id="yield-new" class="grid grid-cols-3 gap-4">
+                    <input type="text" name="yield_name" placeholder="Name" class="bg-gray-900 border border-gray-600 rounded-lg p-2 text-white">
+                    <input type="text" name="yield_symbol" placeholder="Symbol" class="bg-gray-900 border border-gray-600 rounded-lg p-2 text-white">
+                    <input type="number" min="0" max="18" name="yield_decimals" placeholder="Decimals (reserve's)" class="bg-gray-900 border border-gray-600 rounded-lg p-2 text-white">
+                </div>
+                <div id="yield-existing" class="hidden">
+                    <select name="yield_asset" class="w-full bg-gray-900 border border-gray-600 text-gray-100 rounded-lg p-3">
+                        <option value="">-- Select Asset --</option>
+                        {}
+                    </select>
+                </div>
+            </div>
+
+            <div>
+                <p class="text-sm font-medium text-gray-300 mb-2">Risk and Rate Model <span class="text-xs text-gray-500">(basis points)</span></p>
+                <div class="grid grid-cols-2 md:grid-cols-4 gap-4">{}</div>
+            </div>
+
+            <div class="grid grid-cols-2 gap-4">
+                <div>
+                    <label class="block text-xs font-medium text-gray-400 mb-1">Supply Cap (reserve tokens, blank for none)</label>
+                    <input type="text" name="supply_cap" class="w-full bg-gray-900 border border-gray-600 rounded-lg p-2 text-white font-mono">
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-400 mb-1">Borrow Cap (reserve tokens, blank for none)</label>
+                    <input type="text" name="borrow_cap" class="w-full bg-gray-900 border border-gray-600 rounded-lg p-2 text-white font-mono">
+                </div>
+            </div>
+
+            <button type="submit" class="w-full bg-blue-600 hover:bg-blue-500 text-white font-bold py-3 rounded-lg">
+                Deploy Pool
+            </button>
+            <div id="create-pool-result"></div>
+        </form>
+        "##,
+        asset_opts, asset_opts, rate_fields
     )
 }
 
@@ -1092,3 +1854,797 @@ pub fn oracle_tab(account_id: Uuid, pools: Vec<LendingPoolRecord>, assets: Vec<A
         pool_opts, asset_opts, account_id
     )
 }
+
+/// Live feed of `system:event` from the API's `/admin` socket namespace.
+pub fn operations_tab(enabled: bool) -> String {
+    if !enabled {
+        return r##"<div class='text-red-400'>ADMIN_SOCKET_KEY is not set; the operations feed is disabled.</div>"##.to_string();
+    }
+
+    r##"
+        <div class="space-y-6">
+            <div class="text-center">
+                <h2 class="text-3xl font-bold text-white mb-2">Operations</h2>
+                <p class="text-gray-400">Settlement failures, aggregator lag, stale oracles and contract retries as they happen.</p>
+            </div>
+
+            <div class="bg-gray-800 p-6 rounded-2xl border border-gray-700">
+                <div class="flex justify-between items-center mb-4">
+                    <h3 class="text-xl font-bold text-white">Event Feed</h3>
+                    <span data-live-status="/admin" class="text-xs text-gray-500">Connecting...</span>
+                </div>
+                <div id="ops-feed" class="space-y-2 font-mono text-sm max-h-[60vh] overflow-y-auto">
+                    <p class="text-gray-500 text-center">No events yet</p>
+                </div>
+            </div>
+        </div>
+
+        <script>
+            (() => {
+                const feed = document.getElementById('ops-feed');
+                const colors = {
+                    settlement_failed: 'text-red-400',
+                    aggregator_lag: 'text-yellow-400',
+                    oracle_stale: 'text-orange-400',
+                    contract_call_retry: 'text-blue-400',
+                };
+                let empty = true;
+
+                const show = (e) => {
+                    if (empty) { feed.innerHTML = ''; empty = false; }
+                    const { kind, at, seq, ...details } = e;
+                    const row = document.createElement('div');
+                    row.className = 'p-3 bg-gray-900 rounded-lg border border-gray-700';
+                    const head = document.createElement('div');
+                    head.className = 'flex justify-between';
+                    head.innerHTML = `<span class="${colors[kind] || 'text-gray-300'} font-bold"></span><span class="text-gray-500 text-xs"></span>`;
+                    head.children[0].textContent = kind;
+                    head.children[1].textContent = at;
+                    const body = document.createElement('div');
+                    body.className = 'text-gray-400 text-xs mt-1 break-all';
+                    body.textContent = JSON.stringify(details);
+                    row.append(head, body);
+                    feed.prepend(row);
+                    while (feed.children.length > 200) feed.lastChild.remove();
+                };
+
+                // events that came in while another tab was open, oldest first
+                [...window.cradleLive.recent].reverse().forEach(show);
+
+                const onEvent = (e) => {
+                    if (!document.body.contains(feed)) {
+                        document.removeEventListener('cradle:system-event', onEvent);
+                        return;
+                    }
+                    show(e.detail);
+                };
+                document.addEventListener('cradle:system-event', onEvent);
+            })();
+        </script>
+        "##
+    .to_string()
+}
+
+pub fn ramps_tab(summary: &RampSummary) -> String {
+    let mut pending_rows = String::new();
+    for o in &summary.pending_orders {
+        pending_rows.push_str(&format!(
+            r##"<tr class="border-t border-gray-700">
+                <td class="py-2 font-mono text-xs text-gray-300">{}</td>
+                <td class="py-2 text-gray-300">{:?}</td>
+                <td class="py-2 text-gray-300">{:?}</td>
+                <td class="py-2 text-right text-white">{} {}</td>
+                <td class="py-2 text-gray-400">{}</td>
+                <td class="py-2 text-right space-x-2">
+                    <button class="bg-blue-600 hover:bg-blue-500 text-white text-xs px-3 py-1 rounded"
+                            hx-post="/ui/ramps/retry" hx-target="#ramp-action-result"
+                            hx-vals='{{"order_id": "{}"}}'>Retry</button>
+                    <button class="bg-red-700 hover:bg-red-600 text-white text-xs px-3 py-1 rounded"
+                            hx-post="/ui/ramps/cancel" hx-target="#ramp-action-result"
+                            hx-confirm="Cancel this ramp order?"
+                            hx-vals='{{"order_id": "{}"}}'>Cancel</button>
+                </td>
+            </tr>"##,
+            o.id, o.direction, o.status, o.amount, o.currency, o.created_at.format("%Y-%m-%d %H:%M"),
+            o.id, o.id
+        ));
+    }
+    if pending_rows.is_empty() {
+        pending_rows.push_str(r##"<tr><td colspan="6" class="py-4 text-center text-gray-500">Nothing in flight</td></tr>"##);
+    }
+
+    let mut stuck_rows = String::new();
+    for f in &summary.stuck_fulfillments {
+        stuck_rows.push_str(&format!(
+            r##"<tr class="border-t border-gray-700">
+                <td class="py-2 font-mono text-xs text-gray-300">{}</td>
+                <td class="py-2 text-gray-300">{}</td>
+                <td class="py-2 text-red-300 text-xs">{}</td>
+                <td class="py-2 text-gray-400">{}</td>
+                <td class="py-2 text-right">
+                    <button class="bg-blue-600 hover:bg-blue-500 text-white text-xs px-3 py-1 rounded"
+                            hx-post="/ui/ramps/retry" hx-target="#ramp-action-result"
+                            hx-vals='{{"order_id": "{}"}}'>Retry now</button>
+                </td>
+            </tr>"##,
+            f.order_id, f.attempts, f.last_error.as_deref().unwrap_or(""),
+            f.next_attempt_at.format("%Y-%m-%d %H:%M"), f.order_id
+        ));
+    }
+    if stuck_rows.is_empty() {
+        stuck_rows.push_str(r##"<tr><td colspan="5" class="py-4 text-center text-gray-500">No failing fulfillments</td></tr>"##);
+    }
+
+    let mut failure_rows = String::new();
+    for f in &summary.failures {
+        failure_rows.push_str(&format!(
+            r##"<tr class="border-t border-gray-700"><td class="py-2 text-gray-300">{}</td><td class="py-2 text-right text-white">{}</td></tr>"##,
+            f.reason, f.orders
+        ));
+    }
+    if failure_rows.is_empty() {
+        failure_rows.push_str(r##"<tr><td colspan="2" class="py-4 text-center text-gray-500">No failures</td></tr>"##);
+    }
+
+    let mut volume_rows = String::new();
+    for v in &summary.volume {
+        volume_rows.push_str(&format!(
+            r##"<tr class="border-t border-gray-700">
+                <td class="py-2 text-gray-300">{}</td>
+                <td class="py-2 text-gray-300">{}</td>
+                <td class="py-2 text-gray-300">{:?}</td>
+                <td class="py-2 text-right text-white">{}</td>
+                <td class="py-2 text-right text-white">{}</td>
+                <td class="py-2 text-right text-white">{}</td>
+            </tr>"##,
+            v.day, v.currency, v.direction, v.orders, v.token_amount, v.fiat_amount
+        ));
+    }
+    if volume_rows.is_empty() {
+        volume_rows.push_str(r##"<tr><td colspan="6" class="py-4 text-center text-gray-500">No completed orders</td></tr>"##);
+    }
+
+    format!(
+        r##"
+        <div class="space-y-6">
+            <div class="text-center">
+                <h2 class="text-3xl font-bold text-white mb-2">Ramp Operations</h2>
+                <p class="text-gray-400">Activity since {}. Retrying and cancelling act as your operator's system account.</p>
+            </div>
+
+            <div class="grid grid-cols-3 gap-4">
+                <div class="bg-gray-800 p-4 rounded-xl border border-gray-700">
+                    <div class="text-xs text-gray-500 uppercase">In Flight</div>
+                    <div class="text-2xl font-bold text-white">{}</div>
+                </div>
+                <div class="bg-gray-800 p-4 rounded-xl border border-gray-700">
+                    <div class="text-xs text-gray-500 uppercase">Overdue</div>
+                    <div class="text-2xl font-bold text-yellow-400">{}</div>
+                </div>
+                <div class="bg-gray-800 p-4 rounded-xl border border-gray-700">
+                    <div class="text-xs text-gray-500 uppercase">Stuck Fulfillments</div>
+                    <div class="text-2xl font-bold text-red-400">{}</div>
+                </div>
+            </div>
+
+            <div id="ramp-action-result"></div>
+
+            <div class="bg-gray-800 p-6 rounded-2xl border border-gray-700">
+                <h3 class="text-xl font-bold text-white mb-4">Pending Orders</h3>
+                <table class="w-full text-sm">
+                    <thead><tr class="text-left text-gray-500"><th>Order</th><th>Direction</th><th>Status</th><th class="text-right">Amount</th><th>Created</th><th></th></tr></thead>
+                    <tbody>{}</tbody>
+                </table>
+            </div>
+
+            <div class="bg-gray-800 p-6 rounded-2xl border border-gray-700">
+                <h3 class="text-xl font-bold text-white mb-4">Stuck Fulfillments</h3>
+                <table class="w-full text-sm">
+                    <thead><tr class="text-left text-gray-500"><th>Order</th><th>Attempts</th><th>Last Error</th><th>Next Attempt</th><th></th></tr></thead>
+                    <tbody>{}</tbody>
+                </table>
+            </div>
+
+            <div class="grid grid-cols-2 gap-6">
+                <div class="bg-gray-800 p-6 rounded-2xl border border-gray-700">
+                    <h3 class="text-xl font-bold text-white mb-4">Failures by Reason</h3>
+                    <table class="w-full text-sm">
+                        <thead><tr class="text-left text-gray-500"><th>Reason</th><th class="text-right">Orders</th></tr></thead>
+                        <tbody>{}</tbody>
+                    </table>
+                </div>
+                <div class="bg-gray-800 p-6 rounded-2xl border border-gray-700">
+                    <h3 class="text-xl font-bold text-white mb-4">Daily Volume</h3>
+                    <table class="w-full text-sm">
+                        <thead><tr class="text-left text-gray-500"><th>Day</th><th>Currency</th><th>Direction</th><th class="text-right">Orders</th><th class="text-right">Tokens</th><th class="text-right">Fiat</th></tr></thead>
+                        <tbody>{}</tbody>
+                    </table>
+                </div>
+            </div>
+        </div>
+        "##,
+        summary.since.format("%Y-%m-%d"),
+        summary.pending_count,
+        summary.overdue_count,
+        summary.stuck_fulfillments.len(),
+        pending_rows,
+        stuck_rows,
+        failure_rows,
+        volume_rows
+    )
+}
+
+pub fn health_tab(markets: Vec<MarketRecord>, aggregation_jobs: Vec<Job>) -> String {
+    let market_opts: String = markets
+        .iter()
+        .map(|m| format!(r##"<option value="{}">{}</option>"##, m.id, escape_html(&m.name)))
+        .collect();
+    let interval_opts: String = TimeSeriesInterval::ALL
+        .iter()
+        .map(|i| {
+            format!(
+                r##"<label class="flex items-center gap-1 text-xs text-gray-300"><input type="checkbox" name="interval" value="{0}" class="rounded bg-gray-900 border-gray-600"{1}> {0}</label>"##,
+                i.as_str(),
+                if matches!(i, TimeSeriesInterval::OneMinute) { " checked" } else { "" }
+            )
+        })
+        .collect();
+    let jobs_html: String = aggregation_jobs.iter().map(job_progress).collect();
+
+    format!(
+        r##"
+        <div class="space-y-6">
+            <div class="text-center">
+                <h2 class="text-3xl font-bold text-white mb-2">System Health</h2>
+                <p class="text-gray-400">Database connections, the Hedera client, settlement, candle aggregation and webhook delivery. Checked every 15 seconds.</p>
+            </div>
+            <div id="health-report" hx-get="/ui/health/report" hx-trigger="load, every 15s">
+                <p class="text-gray-500 text-center animate-pulse">Checking...</p>
+            </div>
+
+            <div class="bg-gray-800 p-6 rounded-2xl border border-gray-700 space-y-4">
+                <div>
+                    <h3 class="text-xl font-bold text-white">Backfill Candles</h3>
+                    <p class="text-xs text-gray-500">Aggregates order book trades for the market's base asset in the background. Progress is checkpointed, so a cancelled or failed run can be resumed.</p>
+                </div>
+                <form hx-post="/ui/aggregation/run" hx-target="#aggregation-jobs" hx-swap="afterbegin" class="space-y-3">
+                    <div class="grid grid-cols-3 gap-3">
+                        <select name="market_id" class="bg-gray-900 border border-gray-600 rounded-lg p-2 text-sm text-white" required>
+                            <option value="">-- Market --</option>
+                            {market_opts}
+                        </select>
+                        <input type="datetime-local" name="from" class="bg-gray-900 border border-gray-600 rounded-lg p-2 text-sm text-white" required>
+                        <input type="datetime-local" name="to" class="bg-gray-900 border border-gray-600 rounded-lg p-2 text-sm text-white" required>
+                    </div>
+                    <div class="flex flex-wrap gap-3">{interval_opts}</div>
+                    <div class="flex justify-between items-center">
+                        <label class="flex items-center gap-2 text-sm text-gray-300"><input type="checkbox" name="resume" value="true" class="rounded bg-gray-900 border-gray-600"> Resume from the last checkpoint</label>
+                        <button type="submit" class="bg-blue-600 hover:bg-blue-500 text-white text-sm font-bold px-4 py-2 rounded-lg">Start</button>
+                    </div>
+                </form>
+                <div id="aggregation-jobs" class="space-y-3">{jobs_html}</div>
+            </div>
+        </div>
+        "##
+    )
+}
+
+fn health_card(label: &str, value: String, ok: bool, detail: String) -> String {
+    format!(
+        r##"<div class="bg-gray-800 p-4 rounded-xl border {}">
+                <div class="text-xs text-gray-500 uppercase">{}</div>
+                <div class="text-2xl font-bold {}">{}</div>
+                <div class="text-xs text-gray-400 mt-1">{}</div>
+            </div>"##,
+        if ok { "border-gray-700" } else { "border-red-700" },
+        label,
+        if ok { "text-white" } else { "text-red-400" },
+        value,
+        detail
+    )
+}
+
+pub fn health_report(report: &HealthReport) -> String {
+    let warnings_html = if report.warnings.is_empty() {
+        r##"<div class="bg-green-900/40 border border-green-700 text-green-300 text-sm rounded-lg p-3">All checks passed</div>"##.to_string()
+    } else {
+        let items: String = report
+            .warnings
+            .iter()
+            .map(|w| format!("<li>{}</li>", escape_html(w)))
+            .collect();
+        format!(
+            r##"<div class="bg-red-900/40 border border-red-700 text-red-300 text-sm rounded-lg p-3"><ul class="list-disc list-inside space-y-1">{}</ul></div>"##,
+            items
+        )
+    };
+
+    let db = &report.db;
+    let in_use = db.pool_connections.saturating_sub(db.pool_idle);
+    let hedera = &report.hedera;
+    let settlement = &report.settlement;
+    let webhooks = &report.webhooks;
+    let stale_oracles = report.oracles.iter().filter(|o| o.stale).count();
+    let since = |at: Option<chrono::NaiveDateTime>| {
+        at.map(|at| format!("oldest {}", at.format("%Y-%m-%d %H:%M:%S")))
+            .unwrap_or_default()
+    };
+
+    let cards = [
+        health_card(
+            "Dashboard DB Pool",
+            format!("{} / {}", in_use, db.pool_max),
+            in_use * 5 <= db.pool_max * 4,
+            format!("{} open, {} idle", db.pool_connections, db.pool_idle),
+        ),
+        health_card(
+            "Postgres Connections",
+            format!("{} / {}", db.server_connections, db.server_max),
+            db.server_connections * 5 <= db.server_max * 4,
+            format!("{} active, all processes", db.server_active),
+        ),
+        health_card(
+            "Hedera Client",
+            if hedera.reachable { "Reachable".to_string() } else { "Unreachable".to_string() },
+            hedera.reachable,
+            match (&hedera.latency_ms, &hedera.error) {
+                (Some(ms), _) => format!("{} ms", ms),
+                (None, Some(e)) => escape_html(e),
+                (None, None) => String::new(),
+            },
+        ),
+        health_card(
+            "Settlement Queue",
+            settlement.matched.to_string(),
+            settlement.failed == 0,
+            format!("{} failed {}", settlement.failed, since(settlement.oldest_matched_at)),
+        ),
+        health_card(
+            "Webhook Backlog",
+            webhooks.pending.to_string(),
+            webhooks.due == 0,
+            format!("{} due, {} retrying, {} failed {}", webhooks.due, webhooks.retrying, webhooks.failed, since(webhooks.oldest_pending_at)),
+        ),
+        health_card(
+            "Stale Oracle Prices",
+            stale_oracles.to_string(),
+            stale_oracles == 0,
+            format!("of {} published", report.oracles.len()),
+        ),
+    ]
+    .concat();
+
+    let mut lag_rows = String::new();
+    for lag in &report.aggregation {
+        lag_rows.push_str(&format!(
+            r##"<tr class="border-t border-gray-700">
+                <td class="py-2 text-gray-300">{}</td>
+                <td class="py-2 text-gray-300">{}</td>
+                <td class="py-2 text-gray-400">{}</td>
+                <td class="py-2 text-right font-bold {}">{}s</td>
+            </tr>"##,
+            escape_html(&lag.market_name),
+            lag.interval.as_str(),
+            lag.last_bar_end.format("%Y-%m-%d %H:%M:%S"),
+            if lag.behind { "text-red-400" } else { "text-green-400" },
+            lag.lag_secs
+        ));
+    }
+    if lag_rows.is_empty() {
+        lag_rows.push_str(r##"<tr><td colspan="4" class="py-4 text-center text-gray-500">No order book candles yet</td></tr>"##);
+    }
+
+    format!(
+        r##"
+        <div class="space-y-6">
+            {}
+            <div class="grid grid-cols-2 lg:grid-cols-6 gap-4">{}</div>
+            <div class="bg-gray-800 p-6 rounded-2xl border border-gray-700">
+                <h3 class="text-xl font-bold text-white mb-4">Aggregator Lag</h3>
+                <table class="w-full text-sm">
+                    <thead><tr class="text-left text-gray-500"><th>Market</th><th>Interval</th><th>Last Bar Ends</th><th class="text-right">Behind</th></tr></thead>
+                    <tbody>{}</tbody>
+                </table>
+            </div>
+            <p class="text-xs text-gray-500 text-right">Checked at {}</p>
+        </div>
+        "##,
+        warnings_html,
+        cards,
+        lag_rows,
+        report.checked_at.format("%Y-%m-%d %H:%M:%S")
+    )
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn pretty_json(value: &serde_json::Value) -> String {
+    escape_html(&serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string()))
+}
+
+/// Filters for the mutation audit log, prefilled from `filter`; matching
+/// entries load into `#audit-entries`.
+pub fn audit_tab(filter: &AuditLogFilter) -> String {
+    let text = |v: &Option<String>| escape_html(v.as_deref().unwrap_or(""));
+    let time = |v: &Option<chrono::NaiveDateTime>| {
+        v.map(|t| t.format("%Y-%m-%dT%H:%M").to_string()).unwrap_or_default()
+    };
+
+    format!(
+        r##"
+        <div class="space-y-6">
+            <div class="text-center">
+                <h2 class="text-3xl font-bold text-white mb-2">Audit Log</h2>
+                <p class="text-gray-400">Mutations made through the API and this dashboard, newest first. Lookups aren't logged.</p>
+            </div>
+
+            <form class="bg-gray-800 p-6 rounded-2xl border border-gray-700 grid grid-cols-2 md:grid-cols-5 gap-4 items-end"
+                  hx-get="/ui/audit/entries" hx-target="#audit-entries" hx-trigger="load, submit">
+                <div>
+                    <label class="block text-xs font-medium text-gray-400 mb-1">Actor</label>
+                    <input type="text" name="actor" value="{}" placeholder="username or api" class="w-full bg-gray-900 border border-gray-600 rounded-lg p-2 text-white">
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-400 mb-1">Action</label>
+                    <input type="text" name="action" value="{}" placeholder="e.g. UpdateMarketStatus" class="w-full bg-gray-900 border border-gray-600 rounded-lg p-2 text-white">
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-400 mb-1">Mentions</label>
+                    <input type="text" name="subject" value="{}" placeholder="market, order, pool id..." class="w-full bg-gray-900 border border-gray-600 rounded-lg p-2 text-white font-mono">
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-400 mb-1">From</label>
+                    <input type="datetime-local" name="since" value="{}" class="w-full bg-gray-900 border border-gray-600 rounded-lg p-2 text-white">
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-400 mb-1">To</label>
+                    <input type="datetime-local" name="until" value="{}" class="w-full bg-gray-900 border border-gray-600 rounded-lg p-2 text-white">
+                </div>
+                <button type="submit" class="col-span-2 md:col-span-5 bg-blue-600 hover:bg-blue-500 text-white font-bold py-2 rounded-lg">Search</button>
+            </form>
+
+            <div id="audit-entries" class="bg-gray-800 p-6 rounded-2xl border border-gray-700">
+                <div class="text-center text-gray-500 animate-pulse">Loading...</div>
+            </div>
+        </div>
+        "##,
+        text(&filter.actor),
+        text(&filter.action),
+        text(&filter.subject),
+        time(&filter.since),
+        time(&filter.until)
+    )
+}
+
+/// Audit entries, each expanding to its request and what came back side by
+/// side.
+pub fn audit_entries(entries: &[MutationAuditRow]) -> String {
+    let mut rows = String::new();
+    for e in entries {
+        let (status, outcome) = match (&e.result, &e.error) {
+            (_, Some(error)) => (
+                r#"<span class="text-red-400">Failed</span>"#,
+                format!(r#"<pre class="text-xs text-red-300 whitespace-pre-wrap">{}</pre>"#, escape_html(error)),
+            ),
+            (Some(result), None) => (
+                r#"<span class="text-green-400">OK</span>"#,
+                format!(r#"<pre class="text-xs text-gray-300 whitespace-pre-wrap">{}</pre>"#, pretty_json(result)),
+            ),
+            (None, None) => (r#"<span class="text-green-400">OK</span>"#, String::new()),
+        };
+        rows.push_str(&format!(
+            r##"<details class="border-t border-gray-700 py-2">
+                <summary class="cursor-pointer grid grid-cols-5 gap-4 text-sm">
+                    <span class="text-gray-400">{}</span>
+                    <span class="text-white">{}</span>
+                    <span class="text-gray-400">{}</span>
+                    <span class="font-mono text-blue-300">{}</span>
+                    <span class="text-right">{}</span>
+                </summary>
+                <div class="grid grid-cols-2 gap-4 mt-3">
+                    <div>
+                        <div class="text-xs text-gray-500 uppercase mb-1">Request</div>
+                        <pre class="text-xs text-gray-300 whitespace-pre-wrap bg-gray-900 p-3 rounded">{}</pre>
+                    </div>
+                    <div>
+                        <div class="text-xs text-gray-500 uppercase mb-1">Result</div>
+                        <div class="bg-gray-900 p-3 rounded">{}</div>
+                    </div>
+                </div>
+            </details>"##,
+            e.created_at.format("%Y-%m-%d %H:%M:%S"),
+            escape_html(&e.actor),
+            escape_html(&e.source),
+            escape_html(&e.action),
+            status,
+            pretty_json(&e.payload),
+            outcome
+        ));
+    }
+    if rows.is_empty() {
+        return r#"<p class="text-center text-gray-500">No mutations match</p>"#.to_string();
+    }
+
+    format!(
+        r##"<div class="grid grid-cols-5 gap-4 text-xs text-gray-500 uppercase pb-2">
+            <span>Time</span><span>Actor</span><span>Source</span><span>Action</span><span class="text-right">Status</span>
+        </div>
+        {}
+        <p class="text-xs text-gray-500 mt-4">Showing {} entries; narrow the filters to see older ones.</p>"##,
+        rows,
+        entries.len()
+    )
+}
+
+/// One wallet's history on a single page, a section at a time.
+pub fn account_detail(wallet: &CradleWalletAccountRecord) -> String {
+    let section_buttons: String = Section::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, section)| {
+            format!(
+                r##"<button class="px-4 py-2 text-sm rounded-lg {}"
+                        hx-get="/ui/account/{}/detail/{}" hx-target="#account-detail-section"
+                        onclick="this.parentElement.querySelectorAll('button').forEach(b => b.classList.remove('bg-blue-600', 'text-white')); this.classList.add('bg-blue-600', 'text-white')">{}</button>"##,
+                if i == 0 { "bg-blue-600 text-white" } else { "text-gray-400 hover:bg-gray-700" },
+                wallet.id,
+                section.as_str(),
+                section.label()
+            )
+        })
+        .collect();
+
+    format!(
+        r##"
+        <div class="space-y-6">
+            <div class="bg-gray-800 p-6 rounded-2xl border border-gray-700 flex justify-between items-start">
+                <div>
+                    <div class="text-xs text-gray-500 uppercase">Wallet</div>
+                    <div class="text-xl font-mono text-white">{}</div>
+                    <div class="text-xs text-gray-500 mt-1 font-mono">{}</div>
+                </div>
+                <div class="text-right text-sm text-gray-400">
+                    <div>Contract <span class="font-mono text-gray-300">{}</span></div>
+                    <div>Account <span class="font-mono text-gray-300">{}</span></div>
+                    <div>{:?}, created {}</div>
+                </div>
+            </div>
+            <div class="flex flex-wrap gap-2">{section_buttons}</div>
+            <div id="account-detail-section" hx-get="/ui/account/{}/detail/{}" hx-trigger="load">
+                <p class="text-gray-500 text-center animate-pulse">Loading...</p>
+            </div>
+        </div>
+        "##,
+        wallet.address,
+        wallet.id,
+        wallet.contract_id,
+        wallet.cradle_account_id,
+        wallet.status,
+        wallet.created_at.format("%Y-%m-%d %H:%M"),
+        wallet.id,
+        Section::Ledger.as_str()
+    )
+}
+
+fn detail_table(headers: &[&str], rows: String, empty: &str) -> String {
+    let head: String = headers
+        .iter()
+        .map(|h| format!(r#"<th class="px-4 py-3">{}</th>"#, h))
+        .collect();
+    let rows = if rows.is_empty() {
+        format!(
+            r#"<tr><td colspan="{}" class="p-4 text-center text-gray-500 italic">{}</td></tr>"#,
+            headers.len(),
+            empty
+        )
+    } else {
+        rows
+    };
+
+    format!(
+        r##"<div class="bg-gray-800 rounded-2xl border border-gray-700 overflow-x-auto">
+            <table class="w-full text-left text-sm">
+                <thead class="bg-gray-700/50 text-xs uppercase text-gray-400"><tr>{}</tr></thead>
+                <tbody>{}</tbody>
+            </table>
+        </div>"##,
+        head, rows
+    )
+}
+
+/// Ledger entries seen from the wallet: what came in, what went out and
+/// who was on the other side.
+pub fn account_ledger(address: &str, entries: &[LedgerRow], symbols: &HashMap<Uuid, String>, empty: &str) -> String {
+    let rows: String = entries
+        .iter()
+        .map(|e| {
+            let (direction, color, counterparty) = if e.from_address == e.to_address {
+                ("Self", "text-gray-300", e.to_address.as_str())
+            } else if e.to_address == address {
+                ("In", "text-green-400", e.from_address.as_str())
+            } else {
+                ("Out", "text-red-400", e.to_address.as_str())
+            };
+            format!(
+                r##"<tr class="border-t border-gray-700">
+                    <td class="px-4 py-2 text-gray-400 whitespace-nowrap">{}</td>
+                    <td class="px-4 py-2 text-gray-300">{:?}</td>
+                    <td class="px-4 py-2 {}">{}</td>
+                    <td class="px-4 py-2 text-right text-white font-mono">{} {}</td>
+                    <td class="px-4 py-2 font-mono text-xs text-gray-400">{}</td>
+                    <td class="px-4 py-2 font-mono text-xs text-gray-500">{}</td>
+                </tr>"##,
+                e.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                e.transaction_type,
+                color,
+                direction,
+                e.amount,
+                symbols.get(&e.asset).map(String::as_str).unwrap_or("?"),
+                counterparty,
+                e.transaction.as_deref().unwrap_or("-")
+            )
+        })
+        .collect();
+
+    detail_table(&["Time", "Type", "Direction", "Amount", "Counterparty", "Transaction"], rows, empty)
+}
+
+/// The wallet's open orders, with the same cancel and amend actions as the
+/// market view.
+pub fn account_open_orders(orders: &[(OrderBookRecord, Uuid)]) -> String {
+    let rows: String = orders
+        .iter()
+        .map(|(o, quote_asset)| order_row(o, *quote_asset, None))
+        .collect();
+
+    detail_table(&["Side", "Type", "Price", "Bid", "Ask", "Status", ""], rows, "No open orders")
+}
+
+pub fn account_trades(trades: &[WalletTrade]) -> String {
+    let rows: String = trades
+        .iter()
+        .map(|t| {
+            let filled = if t.maker { &t.trade.maker_filled_amount } else { &t.trade.taker_filled_amount };
+            format!(
+                r##"<tr class="border-t border-gray-700">
+                    <td class="px-4 py-2 text-gray-400 whitespace-nowrap">{}</td>
+                    <td class="px-4 py-2 text-white">{}</td>
+                    <td class="px-4 py-2 text-gray-300">{}</td>
+                    <td class="px-4 py-2 text-right font-mono text-white">{}</td>
+                    <td class="px-4 py-2 text-right font-mono text-gray-300">{}</td>
+                    <td class="px-4 py-2 text-gray-300">{:?}</td>
+                    <td class="px-4 py-2 font-mono text-xs text-gray-500">{}</td>
+                </tr>"##,
+                t.trade.created_at.format("%Y-%m-%d %H:%M:%S"),
+                t.market,
+                if t.maker { "Maker" } else { "Taker" },
+                filled,
+                t.order.price,
+                t.trade.settlement_status,
+                t.trade.settlement_tx.as_deref().unwrap_or("-")
+            )
+        })
+        .collect();
+
+    detail_table(&["Time", "Market", "Side", "Filled", "Order Price", "Settlement", "Transaction"], rows, "No trades")
+}
+
+pub fn account_loans(loans: &[WalletLoan], symbols: &HashMap<Uuid, String>) -> String {
+    let rows: String = loans
+        .iter()
+        .map(|l| {
+            let (debt, health) = match &l.health {
+                Some(h) => (
+                    h.outstanding_debt.to_string(),
+                    format!(
+                        r#"<span class="{}">{}</span>"#,
+                        if h.health_factor < BigDecimal::from(1) { "text-red-400" } else { "text-green-400" },
+                        h.health_factor
+                    ),
+                ),
+                None => ("-".to_string(), "-".to_string()),
+            };
+            format!(
+                r##"<tr class="border-t border-gray-700">
+                    <td class="px-4 py-2 text-gray-400 whitespace-nowrap">{}</td>
+                    <td class="px-4 py-2 font-mono text-xs text-gray-300">{}</td>
+                    <td class="px-4 py-2 text-right font-mono text-white">{}</td>
+                    <td class="px-4 py-2 text-gray-300">{}</td>
+                    <td class="px-4 py-2 text-right font-mono text-gray-300">{}</td>
+                    <td class="px-4 py-2 text-right font-mono">{}</td>
+                    <td class="px-4 py-2 text-gray-300">{:?}</td>
+                </tr>"##,
+                l.loan.created_at.format("%Y-%m-%d %H:%M"),
+                l.loan.pool,
+                l.loan.principal_amount,
+                symbols.get(&l.loan.collateral_asset).map(String::as_str).unwrap_or("?"),
+                debt,
+                health,
+                l.loan.status
+            )
+        })
+        .collect();
+
+    detail_table(&["Taken", "Pool", "Principal", "Collateral", "Outstanding", "Health", "Status"], rows, "No loans")
+}
+
+pub fn account_ramp_orders(orders: &[RampOrderRow], symbols: &HashMap<Uuid, String>) -> String {
+    let rows: String = orders
+        .iter()
+        .map(|o| {
+            format!(
+                r##"<tr class="border-t border-gray-700">
+                    <td class="px-4 py-2 text-gray-400 whitespace-nowrap">{}</td>
+                    <td class="px-4 py-2 text-gray-300">{:?}</td>
+                    <td class="px-4 py-2 text-right font-mono text-white">{} {}</td>
+                    <td class="px-4 py-2 text-right font-mono text-gray-300">{} {}</td>
+                    <td class="px-4 py-2 text-gray-300">{}</td>
+                    <td class="px-4 py-2 text-gray-300">{:?}</td>
+                    <td class="px-4 py-2 text-xs text-red-300">{}</td>
+                </tr>"##,
+                o.created_at.format("%Y-%m-%d %H:%M"),
+                o.direction,
+                o.amount,
+                symbols.get(&o.asset_id).map(String::as_str).unwrap_or("?"),
+                o.fiat_amount.as_ref().map(|a| a.to_string()).unwrap_or_else(|| "-".to_string()),
+                o.currency,
+                o.channel,
+                o.status,
+                o.failure_reason.as_deref().unwrap_or("")
+            )
+        })
+        .collect();
+
+    detail_table(&["Created", "Direction", "Tokens", "Fiat", "Channel", "Status", ""], rows, "No ramp orders")
+}
+
+/// A request that is safe to send twice, for the retry button on an error.
+pub enum Retry {
+    Get(String),
+    Post(String, serde_json::Value),
+}
+
+/// What went wrong, under its code, with what to do about it where that's
+/// known. The retry button swaps whatever comes back in place of the panel.
+pub fn action_error(error: &ApiError, retry: Option<Retry>) -> String {
+    let hint = error
+        .hint()
+        .map(|h| format!(r#"<div class="text-xs text-red-200 mt-1">{}</div>"#, h))
+        .unwrap_or_default();
+    let button = "shrink-0 bg-red-800 hover:bg-red-700 text-red-100 text-xs px-3 py-1 rounded";
+    let retry = match retry {
+        Some(Retry::Get(url)) => format!(
+            r##"<button class="{}" hx-get="{}" hx-target="closest [data-action-error]" hx-swap="outerHTML">Retry</button>"##,
+            button,
+            escape_html(&url)
+        ),
+        Some(Retry::Post(url, vals)) => format!(
+            r##"<button class="{}" hx-post="{}" hx-vals='{}' hx-target="closest [data-action-error]" hx-swap="outerHTML">Retry</button>"##,
+            button,
+            escape_html(&url),
+            escape_html(&vals.to_string())
+        ),
+        None => String::new(),
+    };
+
+    format!(
+        r##"<div data-action-error class="bg-red-900/40 border border-red-700 rounded-lg p-3 text-sm flex justify-between items-start gap-3">
+            <div>
+                <span class="font-mono text-xs bg-red-800 text-red-200 px-2 py-0.5 rounded mr-2">{}</span>
+                <span class="text-red-300">{}</span>
+                {}
+            </div>
+            {}
+        </div>"##,
+        error.code(),
+        escape_html(&error.message()),
+        hint,
+        retry
+    )
+}
@@ -10,7 +10,9 @@ use crate::aggregators::aggregation_block::AggregationBlock;
 use crate::aggregators::checkpoint;
 use crate::aggregators::config::AggregatorsConfig;
 use crate::aggregators::OHLCBlock;
+use crate::market_time_series::candles::emit_candle_close;
 use crate::market_time_series::db_types::{CreateMarketTimeSeriesRecord, DataProviderType, TimeSeriesInterval};
+use crate::sockets::system_events::{SystemEvent, emit_system_event};
 use crate::utils::app_config::AppConfig;
 use crate::utils::traits::ActionProcessor;
 
@@ -63,7 +65,7 @@ pub enum AggregatorsProcessorOutput {
 impl ActionProcessor<AggregatorsConfig, AggregatorsProcessorOutput> for AggregatorsProcessorInput {
     async fn process(
         &self,
-        _app_config: &mut AppConfig,
+        app_config: &mut AppConfig,
         local_config: &mut AggregatorsConfig,
         conn: Option<&mut PooledConnection<ConnectionManager<PgConnection>>>,
     ) -> anyhow::Result<AggregatorsProcessorOutput> {
@@ -104,6 +106,26 @@ impl ActionProcessor<AggregatorsConfig, AggregatorsProcessorOutput> for Aggregat
                     .returning(crate::schema::markets_time_series::id)
                     .get_result::<Uuid>(app_conn)?;
 
+                if let Ok(io) = app_config.get_io() {
+                    emit_candle_close(&io, &record).await;
+                }
+
+                // Bars landing more than two intervals after they closed mean
+                // the aggregation job is falling behind.
+                let lag_secs = (chrono::Utc::now().naive_utc() - args.end_time).num_seconds();
+                if lag_secs > 2 * args.interval.seconds() {
+                    emit_system_event(
+                        app_config,
+                        SystemEvent::AggregatorLag {
+                            market_id: args.market_id,
+                            asset_id: args.asset_id,
+                            interval: args.interval.clone(),
+                            lag_secs,
+                        },
+                    )
+                    .await;
+                }
+
                 Ok(AggregatorsProcessorOutput::AggregateTrades(bar_id))
             }
             AggregatorsProcessorInput::BackfillTrades(args) => {
@@ -5,8 +5,6 @@ use axum::{
 };
 use serde_json::json;
 
-use crate::api::response::ApiResponse;
-
 #[derive(Debug)]
 pub enum ApiError {
     BadRequest(String),
@@ -14,8 +12,97 @@ pub enum ApiError {
     NotFound(String),
     InternalError(String),
     DatabaseError(String),
+    /// The ledger or a contract turned the action down for a known reason;
+    /// `code` is one of `KNOWN_FAILURES`
+    Rejected { code: &'static str, message: String },
 }
 
+/// Failures common enough to name: the text that gives them away (Hedera
+/// status names arrive inside the SDK's error messages), their code, and
+/// what an operator can do about them.
+const KNOWN_FAILURES: &[(&str, &str, &str)] = &[
+    (
+        "TOKEN_NOT_ASSOCIATED_TO_ACCOUNT",
+        "token_not_associated",
+        "The wallet isn't associated with this token. Associate it (the faucet does) and try again.",
+    ),
+    (
+        "ACCOUNT_KYC_NOT_GRANTED_FOR_TOKEN",
+        "kyc_not_granted",
+        "The wallet hasn't been granted KYC for this token. Grant it and try again.",
+    ),
+    (
+        "INSUFFICIENT_TOKEN_BALANCE",
+        "insufficient_balance",
+        "The wallet doesn't hold enough of the token. Top it up and try again.",
+    ),
+    (
+        "INSUFFICIENT_PAYER_BALANCE",
+        "insufficient_hbar",
+        "The paying account is out of HBAR for fees.",
+    ),
+    (
+        "CONTRACT_REVERT_EXECUTED",
+        "contract_reverted",
+        "The contract refused the call. Check the amounts against caps, limits and the wallet's positions.",
+    ),
+    (
+        "borrow cap",
+        "borrow_cap_reached",
+        "The pool is at its borrow cap. Raise the cap or wait for repayments.",
+    ),
+    (
+        "oracle price is stale",
+        "oracle_stale",
+        "The pool's oracle price is older than allowed. Publish a fresh price and try again.",
+    ),
+    (
+        "outside its trading hours",
+        "market_closed",
+        "The market is between trading sessions. GET /markets/:id/schedule says when it opens.",
+    ),
+    (
+        "is not open for trading",
+        "market_not_active",
+        "The market is inactive or suspended, so it isn't taking orders.",
+    ),
+    (
+        "decimal places",
+        "precision_exceeded",
+        "Round the price to the market's price_precision and the quantity to its quantity_precision.",
+    ),
+    (
+        "outside the price band",
+        "price_band",
+        "The price is too far from the market's reference. GET /markets/:id/price-band gives the allowed range.",
+    ),
+    (
+        "Duplicate market",
+        "market_duplicate",
+        "A live market already trades this pair as this type. Retire it (set it inactive) or use it instead.",
+    ),
+    (
+        "Unknown market asset",
+        "market_asset_unknown",
+        "Both of a market's assets must be on the asset book. Create the asset first.",
+    ),
+    (
+        "Invalid market",
+        "market_invalid",
+        "A market needs a name and two different assets.",
+    ),
+    (
+        "is already used by market",
+        "market_symbol_taken",
+        "Another market has that symbol. Pick another or leave it out to have one made from the assets.",
+    ),
+    (
+        "timed out",
+        "timeout",
+        "The network didn't answer in time. The action may still have gone through; check before repeating it.",
+    ),
+];
+
 impl ApiError {
     pub fn bad_request(msg: impl Into<String>) -> Self {
         Self::BadRequest(msg.into())
@@ -37,23 +124,78 @@ impl ApiError {
         Self::DatabaseError(msg.into())
     }
 
-    fn status_code(&self) -> StatusCode {
+    /// Work out what kind of failure an action's error is, from the error
+    /// types in its chain and, for ledger rejections, its text.
+    pub fn from_action_error(context: &str, e: &anyhow::Error) -> Self {
+        let message = format!("{}: {:#}", context, e);
+
+        if let Some((_, code, _)) = KNOWN_FAILURES
+            .iter()
+            .find(|(needle, _, _)| message.contains(needle))
+        {
+            return Self::Rejected { code: *code, message };
+        }
+
+        for cause in e.chain() {
+            if let Some(db) = cause.downcast_ref::<diesel::result::Error>() {
+                return match db {
+                    diesel::result::Error::NotFound => Self::NotFound(format!("{}: record", context)),
+                    _ => Self::DatabaseError(message),
+                };
+            }
+            if cause.is::<diesel::r2d2::PoolError>() {
+                return Self::DatabaseError(message);
+            }
+        }
+
+        Self::InternalError(message)
+    }
+
+    pub fn status_code(&self) -> StatusCode {
         match self {
             ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
             ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
             ApiError::NotFound(_) => StatusCode::NOT_FOUND,
             ApiError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::Rejected { .. } => StatusCode::UNPROCESSABLE_ENTITY,
         }
     }
 
-    fn message(&self) -> String {
+    /// Stable name for the kind of failure, for clients to branch on.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::InternalError(_) => "internal_error",
+            ApiError::DatabaseError(_) => "database_error",
+            ApiError::Rejected { code, .. } => *code,
+        }
+    }
+
+    pub fn message(&self) -> String {
         match self {
             ApiError::BadRequest(msg) => msg.clone(),
             ApiError::Unauthorized(msg) => msg.clone(),
             ApiError::NotFound(msg) => format!("{} not found", msg),
             ApiError::InternalError(msg) => msg.clone(),
             ApiError::DatabaseError(msg) => msg.clone(),
+            ApiError::Rejected { message, .. } => message.clone(),
+        }
+    }
+
+    /// What to do about it, for the failures that have an answer.
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            ApiError::Rejected { code, .. } => KNOWN_FAILURES
+                .iter()
+                .find(|(_, known, _)| known == code)
+                .map(|(_, _, hint)| *hint),
+            ApiError::DatabaseError(_) => {
+                Some("The database didn't respond as expected. Try again shortly.")
+            }
+            _ => None,
         }
     }
 }
@@ -61,8 +203,14 @@ impl ApiError {
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let status = self.status_code();
-        let error_response = ApiResponse::<serde_json::Value>::error(self.message());
+        // the usual ApiResponse shape, plus the failure's code
+        let body = json!({
+            "success": false,
+            "data": null,
+            "error": self.message(),
+            "code": self.code(),
+        });
 
-        (status, Json(error_response)).into_response()
+        (status, Json(body)).into_response()
     }
 }
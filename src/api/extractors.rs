@@ -1,12 +1,13 @@
 use axum::{
     async_trait,
-    extract::{FromRequest, Request},
+    extract::{FromRequest, FromRequestParts, Request},
+    http::request::Parts,
     response::IntoResponse,
     Json,
 };
 use serde_json::Value;
 
-use crate::api::error::ApiError;
+use crate::{accounts::db_types::AdminOperatorRecord, api::error::ApiError};
 
 /// Custom extractor for ActionRouterInput JSON
 pub struct ActionRouterExtractor(pub Value);
@@ -42,3 +43,24 @@ where
         Ok(ActionRouterExtractor(value))
     }
 }
+
+/// The operator the request's `X-Operator-Key` belongs to, for handlers
+/// whose action has to be taken by a person, like an approval
+pub struct Operator(pub AdminOperatorRecord);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Operator
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<AdminOperatorRecord>()
+            .cloned()
+            .map(Operator)
+            .ok_or_else(|| ApiError::unauthorized("This action needs an X-Operator-Key header"))
+    }
+}
@@ -182,8 +182,6 @@ pub async fn get_asset_balance(
         .get()
         .map_err(|_| ApiError::DatabaseError("Failed to obtain connection".to_string()))?;
 
-    let wallet = app_config.wallet.clone();
-
     let asset = {
         use crate::schema::asset_book::dsl::*;
 
@@ -202,9 +200,10 @@ pub async fn get_asset_balance(
     }
     .map_err(|_| ApiError::DatabaseError("Failed to get wallet".to_string()))?;
 
-    let balance = commons::get_account_balances(&wallet.client, &wallet_data.contract_id)
-        .await
-        .map_err(|_| ApiError::InternalError("Failed to get balance".to_string()))?;
+    let balance =
+        commons::get_account_balances(&app_config.wallet.client, &wallet_data.contract_id)
+            .await
+            .map_err(|_| ApiError::InternalError("Failed to get balance".to_string()))?;
 
     let token_id = TokenId::from_solidity_address(&asset.token)
         .map_err(|_| ApiError::InternalError("Failed to extract token id".to_string()))?;
@@ -0,0 +1,188 @@
+use axum::{
+    Json,
+    body::Body,
+    extract::{Multipart, Path, Query, State},
+    http::header,
+    response::Response,
+};
+use bytes::Bytes;
+use hyper::StatusCode;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    api::{error::ApiError, response::ApiResponse},
+    documents::{
+        db_types::{DocumentOwner, DocumentRow},
+        operations::{
+            SignedDocumentUrl, UploadDocumentInput, download_document, get_document,
+            get_owner_documents, signed_download_url, upload_document, verify_download_signature,
+        },
+        storage::Storage,
+    },
+    map_to_api_error,
+    utils::app_config::AppConfig,
+};
+
+/// Largest accepted upload, from DOCUMENT_MAX_BYTES.
+pub fn max_upload_bytes() -> usize {
+    std::env::var("DOCUMENT_MAX_BYTES")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(20 * 1024 * 1024)
+}
+
+// POST /documents (multipart: owner_type, owner_id, file)
+pub async fn upload_document_handler(
+    State(app_config): State<AppConfig>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<ApiResponse<DocumentRow>>), ApiError> {
+    let mut owner_type: Option<DocumentOwner> = None;
+    let mut owner_id: Option<Uuid> = None;
+    let mut file: Option<(String, String, Bytes)> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::bad_request(e.to_string()))?
+    {
+        match field.name().unwrap_or_default() {
+            "owner_type" => {
+                let value = field.text().await.map_err(|e| ApiError::bad_request(e.to_string()))?;
+                owner_type = Some(match value.as_str() {
+                    "listing" => DocumentOwner::Listing,
+                    "company" => DocumentOwner::Company,
+                    _ => return Err(ApiError::bad_request("owner_type must be listing or company")),
+                });
+            }
+            "owner_id" => {
+                let value = field.text().await.map_err(|e| ApiError::bad_request(e.to_string()))?;
+                owner_id = Some(
+                    Uuid::parse_str(&value).map_err(|_| ApiError::bad_request("Invalid owner_id"))?,
+                );
+            }
+            "file" => {
+                let file_name = field.file_name().unwrap_or("document").to_string();
+                let content_type = field
+                    .content_type()
+                    .unwrap_or("application/octet-stream")
+                    .to_string();
+                let data = field.bytes().await.map_err(|e| ApiError::bad_request(e.to_string()))?;
+                file = Some((file_name, content_type, data));
+            }
+            _ => {}
+        }
+    }
+
+    let (Some(owner_type), Some(owner_id), Some((file_name, content_type, data))) =
+        (owner_type, owner_id, file)
+    else {
+        return Err(ApiError::bad_request("owner_type, owner_id and file are required"));
+    };
+
+    let storage = map_to_api_error!(Storage::from_env(), "Document storage is not configured")?;
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+
+    let row = map_to_api_error!(
+        upload_document(
+            &mut conn,
+            &storage,
+            UploadDocumentInput {
+                owner_type,
+                owner_id,
+                file_name,
+                content_type,
+                data,
+            },
+        )
+        .await,
+        "Failed to upload document"
+    )?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(row))))
+}
+
+#[derive(Deserialize)]
+pub struct DocumentQueryParams {
+    pub owner_type: DocumentOwner,
+    pub owner_id: Uuid,
+}
+
+// GET /documents?owner_type=listing&owner_id=...
+pub async fn get_documents_handler(
+    State(app_config): State<AppConfig>,
+    Query(params): Query<DocumentQueryParams>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<DocumentRow>>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+    let rows = map_to_api_error!(
+        get_owner_documents(&mut conn, params.owner_type, params.owner_id),
+        "Failed to load documents"
+    )?;
+    Ok((StatusCode::OK, Json(ApiResponse::success(rows))))
+}
+
+// GET /documents/:document_id
+pub async fn get_document_handler(
+    State(app_config): State<AppConfig>,
+    Path(document_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<DocumentRow>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+    match get_document(&mut conn, document_id) {
+        Ok(row) => Ok((StatusCode::OK, Json(ApiResponse::success(row)))),
+        Err(_) => Err(ApiError::not_found("Document")),
+    }
+}
+
+// GET /documents/:document_id/url
+pub async fn get_document_url_handler(
+    State(app_config): State<AppConfig>,
+    Path(document_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<SignedDocumentUrl>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+    if get_document(&mut conn, document_id).is_err() {
+        return Err(ApiError::not_found("Document"));
+    }
+
+    let base_url =
+        std::env::var("CRADLE_API_URL").unwrap_or_else(|_| "http://localhost:6969".to_string());
+    Ok((
+        StatusCode::OK,
+        Json(ApiResponse::success(signed_download_url(document_id, &base_url))),
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct DownloadParams {
+    pub expires: i64,
+    pub signature: String,
+}
+
+// GET /documents/:document_id/download?expires=...&signature=...
+// Authorised by the signature rather than the API key.
+pub async fn download_document_handler(
+    State(app_config): State<AppConfig>,
+    Path(document_id): Path<Uuid>,
+    Query(params): Query<DownloadParams>,
+) -> Result<Response, ApiError> {
+    verify_download_signature(document_id, params.expires, &params.signature)
+        .map_err(|e| ApiError::unauthorized(e.to_string()))?;
+
+    let storage = map_to_api_error!(Storage::from_env(), "Document storage is not configured")?;
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+
+    let (row, data) = map_to_api_error!(
+        download_document(&mut conn, &storage, document_id).await,
+        "Failed to fetch document"
+    )?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, row.content_type)
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", row.file_name.replace('"', "")),
+        )
+        .header("x-content-sha256", row.content_hash)
+        .body(Body::from(data))
+        .map_err(|e| ApiError::internal_error(e.to_string()))
+}
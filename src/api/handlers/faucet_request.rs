@@ -2,7 +2,6 @@ use axum::{Json, extract::State};
 use contract_integrator::utils::functions::{
     ContractCallInput,
     asset_manager::{AirdropArgs, AssetManagerFunctionInput},
-    commons::ContractFunctionProcessor,
 };
 use hyper::StatusCode;
 use serde::{Deserialize, Serialize};
@@ -30,7 +29,7 @@ pub async fn airdrop_request(
     Json(fields): Json<AirdropRequestFields>,
 ) -> Result<(StatusCode, Json<ApiResponse<()>>), ApiError> {
     let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain db connection")?;
-    let mut action_wallet = app_config.wallet.clone();
+    let action_wallet = &app_config.wallet;
     println!("Git acion wallet");
 
     let wallet_data = map_to_api_error!(
@@ -46,7 +45,7 @@ pub async fn airdrop_request(
     map_to_api_error!(
         associate_token(
             &mut conn,
-            &mut action_wallet,
+            action_wallet,
             AssociateTokenToWalletInputArgs {
                 wallet_id: wallet_data.id,
                 token: token_data.id
@@ -59,7 +58,7 @@ pub async fn airdrop_request(
     map_to_api_error!(
         kyc_token(
             &mut conn,
-            &mut action_wallet,
+            action_wallet,
             GrantKYCInputArgs {
                 wallet_id: wallet_data.id,
                 token: token_data.id
@@ -69,13 +68,7 @@ pub async fn airdrop_request(
         "Failed to grant kyc"
     )?;
     map_to_api_error!(
-        mint_asset(
-            &mut conn,
-            &mut action_wallet,
-            token_data.id,
-            100_000_000_000_000
-        )
-        .await,
+        mint_asset(&mut conn, action_wallet, token_data.id, 100_000_000_000_000).await,
         "Failed to mint"
     )?;
     let airdrop_request =
@@ -85,7 +78,7 @@ pub async fn airdrop_request(
             target: wallet_data.address.clone(),
         }));
 
-    match airdrop_request.process(&mut action_wallet).await {
+    match action_wallet.execute(airdrop_request).await {
         Ok(v) => Ok((StatusCode::OK, Json(ApiResponse::success(())))),
         Err(e) => {
             println!("Something went wrong:: {}", e);
@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
 };
 use contract_integrator::{operations::asset_lending::update_indices, utils::functions::asset_lending::{
@@ -21,10 +21,11 @@ use crate::{
         }, operations::{
             RepaymentAmount, get_loan_position, get_loan_repayments, get_pool_deposit_position,
             get_pool_stats, get_repaid_amount,
-        }, oracle::{PriceOracle, get_price_oracle}, processor_enums::{LendingPoolFunctionsInput, LendingPoolFunctionsOutput}
+        }, oracle::{OraclePriceHistory, OracleStaleness, PriceOracle, get_price_history, get_price_oracle, oracle_staleness}, processor_enums::{LendingPoolFunctionsInput, LendingPoolFunctionsOutput}
     },
     map_to_api_error,
     schema::lendingpoolsnapshots::lending_pool_id,
+    sockets::system_events::{SystemEvent, emit_system_event},
     utils::{app_config::AppConfig, cache},
 };
 use uuid::Uuid;
@@ -127,10 +128,9 @@ pub async fn get_pool_stats_handler(
     }
 
     let mut conn = map_to_api_error!(app_config.pool.get(), "Failed to acquire db conn")?;
-    let mut wallet = app_config.wallet.clone();
 
     let results = map_to_api_error!(
-        get_pool_stats(&mut wallet, &mut conn, pool_id).await,
+        get_pool_stats(&app_config.wallet, &mut conn, pool_id).await,
         "Failed to get stats"
     )?;
 
@@ -164,10 +164,9 @@ pub async fn get_pool_borrow_positions(
     }
 
     let mut conn = map_to_api_error!(app_config.pool.get(), "Failed to acquire db conn")?;
-    let mut wallet = app_config.wallet.clone();
 
     let results = map_to_api_error!(
-        get_loan_position(&mut wallet, &mut conn, loan_id).await,
+        get_loan_position(&app_config.wallet, &mut conn, loan_id).await,
         "Failed to get loan"
     )?;
 
@@ -201,10 +200,9 @@ pub async fn get_pool_deposit_handler(
     }
 
     let mut conn = map_to_api_error!(app_config.pool.get(), "Failed to acquire db conn")?;
-    let mut wallet = app_config.wallet.clone();
 
     let results = map_to_api_error!(
-        get_pool_deposit_position(&mut wallet, &mut conn, pool_id, wallet_id).await,
+        get_pool_deposit_position(&app_config.wallet, &mut conn, pool_id, wallet_id).await,
         "Failed to get loan"
     )?;
 
@@ -277,6 +275,18 @@ pub async fn get_oracle_price(
         "Failed to get collateral price"
     )?;
 
+    if results.is_stale() {
+        emit_system_event(
+            &app_config,
+            SystemEvent::OracleStale {
+                pool_id,
+                asset_id,
+                age_secs: results.age_secs(),
+            },
+        )
+        .await;
+    }
+
     Ok((
         StatusCode::OK,
         Json(ApiResponse {
@@ -285,4 +295,44 @@ pub async fn get_oracle_price(
             error: None,
         }),
     ))
-}
\ No newline at end of file
+}
+
+#[derive(serde::Deserialize)]
+pub struct OraclePriceHistoryParams {
+    pub asset: Option<Uuid>,
+    pub pool: Option<Uuid>,
+    /// Start of the period, inclusive
+    pub from: Option<chrono::NaiveDateTime>,
+    /// End of the period, exclusive
+    pub to: Option<chrono::NaiveDateTime>,
+}
+
+// GET /oracle/prices?asset=&pool=&from=&to=
+pub async fn get_oracle_price_history(
+    State(app_config): State<AppConfig>,
+    Query(params): Query<OraclePriceHistoryParams>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<OraclePriceHistory>>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Failed to acquire db conn")?;
+
+    let results = map_to_api_error!(
+        get_price_history(&mut conn, params.asset, params.pool, params.from, params.to),
+        "Failed to get oracle price history"
+    )?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(results))))
+}
+
+/// Age of every published oracle price against the staleness limit.
+pub async fn get_oracle_staleness(
+    State(app_config): State<AppConfig>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<OracleStaleness>>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Failed to acquire db conn")?;
+
+    let results = map_to_api_error!(
+        oracle_staleness(&mut conn),
+        "Failed to get oracle prices"
+    )?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(results))))
+}
+
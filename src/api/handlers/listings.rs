@@ -1,8 +1,33 @@
 use crate::{
-    api::{error::ApiError, response::ApiResponse},
+    api::{error::ApiError, extractors::Operator, response::ApiResponse},
     listing::{
-        db_types::{CradleNativeListingRow, ListingStatus},
+        allocation::get_allocation,
+        companies::{CompanyProfile, get_company_profile},
+        corporate_actions::get_corporate_actions,
+        db_types::{
+            CorporateActionRow, CradleNativeListingRow, DistributionPayoutRow,
+            ListingAllocationRow, ListingDistributionRow, ListingReconciliationRow,
+            ListingWhitelistRow, WithdrawalRequestRow,
+        },
+        distributions::{
+            DistributionSummary, get_distribution_summary, get_listing_distributions,
+            get_wallet_payouts,
+        },
         operations::get_listing,
+        holders::{CapTable, get_cap_table},
+        reconciliation::{
+            ReconciliationReport, get_listing_reconciliations, get_reconciliation_report,
+        },
+        refunds::{RefundSummary, get_refund_summary},
+        search::{ListingPage, ListingSearchParams, search_listings},
+        stats::{ListingStats, get_listing_stats_summary},
+        tranches::{TrancheStats, get_tranche_stats},
+        whitelist::get_whitelist,
+        withdrawals::{
+            ApproveWithdrawalInputArgs, RejectWithdrawalInputArgs, RequestWithdrawalInputArgs,
+            WithdrawalRequestDetails, approve_withdrawal, get_listing_withdrawals,
+            get_withdrawal_request, reject_withdrawal, request_withdrawal,
+        },
     },
     utils::app_config::AppConfig,
 };
@@ -10,6 +35,7 @@ use axum::{
     Json,
     extract::{Path, Query, State},
 };
+use bigdecimal::BigDecimal;
 use diesel::QueryDsl;
 use diesel::prelude::*;
 use hyper::StatusCode;
@@ -40,56 +66,459 @@ pub async fn get_listing_by_id(
     }
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct ListingQueryParams {
-    pub company: Option<Uuid>,
-    pub listed_asset: Option<Uuid>,
-    pub purchase_asset: Option<Uuid>,
-    pub status: Option<ListingStatus>,
+// /companies/{company_id}
+pub async fn get_company_by_id(
+    State(app_config): State<AppConfig>,
+    Path(company_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<CompanyProfile>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    match get_company_profile(&mut conn, company_id) {
+        Ok(v) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(v),
+                error: None,
+            }),
+        )),
+        Err(_) => Err(ApiError::NotFound("Company not found".to_string())),
+    }
 }
 
 // /listings
 pub async fn get_listings(
     State(app_config): State<AppConfig>,
-    Query(params): Query<ListingQueryParams>,
-) -> Result<(StatusCode, Json<ApiResponse<Vec<CradleNativeListingRow>>>), ApiError> {
+    Query(params): Query<ListingSearchParams>,
+) -> Result<(StatusCode, Json<ApiResponse<ListingPage>>), ApiError> {
     let mut conn = app_config
         .pool
         .get()
         .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
 
-    match {
-        use crate::schema::cradlenativelistings::dsl::*;
-        use crate::schema::cradlenativelistings::*;
+    match search_listings(&mut conn, params) {
+        Ok(page) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(page),
+                error: None,
+            }),
+        )),
+        // anything other than a query failure is a bad filter or cursor
+        Err(e) if e.downcast_ref::<diesel::result::Error>().is_none() => {
+            Err(ApiError::BadRequest(e.to_string()))
+        }
+        Err(e) => Err(ApiError::DatabaseError(e.to_string())),
+    }
+}
 
-        let mut query = cradlenativelistings.filter(id.is_not_null()).into_boxed();
+// /listings/{listing_id}/allocations/{wallet_id}
+pub async fn get_listing_allocation(
+    State(app_config): State<AppConfig>,
+    Path((listing_id, wallet_id)): Path<(Uuid, Uuid)>,
+) -> Result<(StatusCode, Json<ApiResponse<ListingAllocationRow>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    match get_allocation(&mut conn, listing_id, wallet_id) {
+        Ok(v) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(v),
+                error: None,
+            }),
+        )),
+        Err(_) => Err(ApiError::NotFound("Allocation not found".to_string())),
+    }
+}
 
-        if let Some(company_value) = &params.company {
-            query = query.filter(company.eq(company_value));
-        };
+// /listings/{listing_id}/refunds
+pub async fn get_listing_refunds(
+    State(app_config): State<AppConfig>,
+    Path(listing_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<RefundSummary>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    match get_refund_summary(&mut conn, listing_id) {
+        Ok(v) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(v),
+                error: None,
+            }),
+        )),
+        Err(e) => Err(ApiError::DatabaseError(e.to_string())),
+    }
+}
 
-        if let Some(value) = &params.purchase_asset {
-            query = query.filter(purchase_with_asset.eq(value));
-        };
+// /listings/{listing_id}/distributions
+pub async fn get_listing_distributions_handler(
+    State(app_config): State<AppConfig>,
+    Path(listing_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<ListingDistributionRow>>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    match get_listing_distributions(&mut conn, listing_id) {
+        Ok(v) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(v),
+                error: None,
+            }),
+        )),
+        Err(e) => Err(ApiError::DatabaseError(e.to_string())),
+    }
+}
 
-        if let Some(value) = &params.status {
-            query = query.filter(status.eq(value));
-        };
+// /distributions/{distribution_id}
+pub async fn get_distribution_handler(
+    State(app_config): State<AppConfig>,
+    Path(distribution_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<DistributionSummary>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    match get_distribution_summary(&mut conn, distribution_id) {
+        Ok(v) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(v),
+                error: None,
+            }),
+        )),
+        Err(e) => Err(ApiError::NotFound(format!("Distribution not found: {}", e))),
+    }
+}
+
+// /distributions/payouts/{wallet_id}
+pub async fn get_wallet_payouts_handler(
+    State(app_config): State<AppConfig>,
+    Path(wallet_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<DistributionPayoutRow>>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    match get_wallet_payouts(&mut conn, wallet_id) {
+        Ok(v) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(v),
+                error: None,
+            }),
+        )),
+        Err(e) => Err(ApiError::DatabaseError(e.to_string())),
+    }
+}
+
+// /listings/{listing_id}/whitelist
+pub async fn get_listing_whitelist(
+    State(app_config): State<AppConfig>,
+    Path(listing_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<ListingWhitelistRow>>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    match get_whitelist(&mut conn, listing_id) {
+        Ok(v) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(v),
+                error: None,
+            }),
+        )),
+        Err(e) => Err(ApiError::DatabaseError(e.to_string())),
+    }
+}
 
-        if let Some(value) = &params.listed_asset {
-            query = query.filter(listed_asset.eq(value));
-        };
+#[derive(Serialize, Deserialize)]
+pub struct ListingStatsQueryParams {
+    /// Include this wallet's remaining purchase allowance
+    pub wallet: Option<Uuid>,
+}
 
-        query.get_results::<CradleNativeListingRow>(&mut conn)
-    } {
-        Ok(results) => Ok((
+// /listings/{listing_id}/stats
+pub async fn get_listing_stats_handler(
+    State(app_config): State<AppConfig>,
+    Path(listing_id): Path<Uuid>,
+    Query(params): Query<ListingStatsQueryParams>,
+) -> Result<(StatusCode, Json<ApiResponse<ListingStats>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    let wallet = &app_config.wallet;
+    match get_listing_stats_summary(&mut conn, wallet, listing_id, params.wallet).await {
+        Ok(v) => Ok((
             StatusCode::OK,
             Json(ApiResponse {
                 success: true,
-                data: Some(results),
+                data: Some(v),
+                error: None,
+            }),
+        )),
+        Err(e) => Err(ApiError::NotFound(format!("Listing stats unavailable: {}", e))),
+    }
+}
+
+// /listings/{listing_id}/holders
+pub async fn get_listing_holders(
+    State(app_config): State<AppConfig>,
+    Path(listing_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<CapTable>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    match get_cap_table(&mut conn, &app_config.wallet, listing_id).await {
+        Ok(v) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(v),
+                error: None,
+            }),
+        )),
+        Err(e) => Err(ApiError::DatabaseError(e.to_string())),
+    }
+}
+
+// /listings/{listing_id}/reconciliation
+pub async fn get_listing_reconciliation(
+    State(app_config): State<AppConfig>,
+    Path(listing_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<ListingReconciliationRow>>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    match get_listing_reconciliations(&mut conn, listing_id) {
+        Ok(v) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(v),
+                error: None,
+            }),
+        )),
+        Err(e) => Err(ApiError::DatabaseError(e.to_string())),
+    }
+}
+
+// /reconciliation/listings
+pub async fn get_reconciliation_report_handler(
+    State(app_config): State<AppConfig>,
+) -> Result<(StatusCode, Json<ApiResponse<ReconciliationReport>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    match get_reconciliation_report(&mut conn) {
+        Ok(v) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(v),
+                error: None,
+            }),
+        )),
+        Err(e) => Err(ApiError::DatabaseError(e.to_string())),
+    }
+}
+
+// /listings/{listing_id}/tranches
+pub async fn get_listing_tranches(
+    State(app_config): State<AppConfig>,
+    Path(listing_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<TrancheStats>>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    let listing = get_listing(&mut conn, listing_id)
+        .await
+        .map_err(|_| ApiError::NotFound("Listing not found".to_string()))?;
+    match get_tranche_stats(&mut conn, &listing) {
+        Ok(v) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(v),
+                error: None,
+            }),
+        )),
+        Err(e) => Err(ApiError::DatabaseError(e.to_string())),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CreateWithdrawalBody {
+    pub amount: BigDecimal,
+}
+
+#[derive(Deserialize)]
+pub struct RejectWithdrawalBody {
+    pub reason: Option<String>,
+}
+
+// GET /listings/{listing_id}/withdrawals
+pub async fn get_listing_withdrawals_handler(
+    State(app_config): State<AppConfig>,
+    Path(listing_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<WithdrawalRequestRow>>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    match get_listing_withdrawals(&mut conn, listing_id) {
+        Ok(v) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(v),
+                error: None,
+            }),
+        )),
+        Err(e) => Err(ApiError::DatabaseError(e.to_string())),
+    }
+}
+
+// POST /listings/{listing_id}/withdrawals
+pub async fn create_withdrawal_request_handler(
+    State(app_config): State<AppConfig>,
+    Path(listing_id): Path<Uuid>,
+    Operator(requester): Operator,
+    Json(body): Json<CreateWithdrawalBody>,
+) -> Result<(StatusCode, Json<ApiResponse<WithdrawalRequestRow>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    let input = RequestWithdrawalInputArgs {
+        listing_id,
+        amount: body.amount,
+    };
+    match request_withdrawal(&app_config, &mut conn, &requester, input).await {
+        Ok(v) => Ok((
+            StatusCode::CREATED,
+            Json(ApiResponse {
+                success: true,
+                data: Some(v),
+                error: None,
+            }),
+        )),
+        Err(e) => Err(ApiError::BadRequest(e.to_string())),
+    }
+}
+
+// GET /withdrawals/{request_id}
+pub async fn get_withdrawal_request_handler(
+    State(app_config): State<AppConfig>,
+    Path(request_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<WithdrawalRequestDetails>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    match get_withdrawal_request(&mut conn, request_id) {
+        Ok(v) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(v),
+                error: None,
+            }),
+        )),
+        Err(_) => Err(ApiError::NotFound("Withdrawal request not found".to_string())),
+    }
+}
+
+// POST /withdrawals/{request_id}/approve
+pub async fn approve_withdrawal_handler(
+    State(app_config): State<AppConfig>,
+    Path(request_id): Path<Uuid>,
+    Operator(approver): Operator,
+) -> Result<(StatusCode, Json<ApiResponse<WithdrawalRequestRow>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    let input = ApproveWithdrawalInputArgs { request_id };
+    match approve_withdrawal(&app_config, &mut conn, &approver, input).await {
+        Ok(v) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(v),
+                error: None,
+            }),
+        )),
+        Err(e) => Err(ApiError::BadRequest(e.to_string())),
+    }
+}
+
+// POST /withdrawals/{request_id}/reject
+pub async fn reject_withdrawal_handler(
+    State(app_config): State<AppConfig>,
+    Path(request_id): Path<Uuid>,
+    Operator(reviewer): Operator,
+    Json(body): Json<RejectWithdrawalBody>,
+) -> Result<(StatusCode, Json<ApiResponse<WithdrawalRequestRow>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    let input = RejectWithdrawalInputArgs {
+        request_id,
+        reason: body.reason,
+    };
+    match reject_withdrawal(&app_config, &mut conn, &reviewer, input).await {
+        Ok(v) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(v),
+                error: None,
+            }),
+        )),
+        Err(e) => Err(ApiError::BadRequest(e.to_string())),
+    }
+}
+
+// /listings/{listing_id}/corporate-actions
+pub async fn get_listing_corporate_actions(
+    State(app_config): State<AppConfig>,
+    Path(listing_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<CorporateActionRow>>>), ApiError> {
+    let mut conn = app_config
+        .pool
+        .get()
+        .map_err(|_| ApiError::DatabaseError("Failed to connect".to_string()))?;
+    match get_corporate_actions(&mut conn, listing_id) {
+        Ok(v) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(v),
                 error: None,
             }),
         )),
-        Err(_) => Err(ApiError::DatabaseError("".to_string())),
+        Err(e) => Err(ApiError::DatabaseError(e.to_string())),
     }
 }
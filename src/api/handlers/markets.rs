@@ -10,9 +10,14 @@ use crate::{
     market::{
         processor_enums::{MarketProcessorInput, MarketProcessorOutput},
         db_types::MarketRecord,
+        discipline::{PriceBandStatus, price_band_status},
+        schedule::{MarketScheduleStatus, schedule_status},
+        stats::{MarketStats, market_stats},
+        symbols::find_by_symbol,
     },
     action_router::{ActionRouterInput, ActionRouterOutput},
     api::{error::ApiError, response::ApiResponse},
+    map_to_api_error,
     utils::{app_config::AppConfig, cache},
 };
 
@@ -103,3 +108,86 @@ pub async fn get_markets(
 
     Ok((StatusCode::OK, Json(ApiResponse::success(json))))
 }
+
+/// GET /markets/stats - Last price, 24h change, range and volume, and open
+/// order counts for every market, from the stats cache
+pub async fn get_markets_stats(
+    State(app_config): State<AppConfig>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<MarketStats>>>), ApiError> {
+    let pool = app_config.pool.clone();
+    let stats = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        market_stats(&mut conn)
+    })
+    .await
+    .map_err(|e| ApiError::internal_error(format!("Task join error: {}", e)))?
+    .map_err(|e| ApiError::internal_error(format!("Failed to load market stats: {}", e)))?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(stats))))
+}
+
+/// GET /markets/by-symbol/:symbol - Get a market by its symbol, e.g. CBTC-CUSD
+pub async fn get_market_by_symbol(
+    State(app_config): State<AppConfig>,
+    Path(symbol): Path<String>,
+) -> Result<(StatusCode, Json<ApiResponse<MarketRecord>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+    let market = map_to_api_error!(find_by_symbol(&mut conn, &symbol), "Failed to look up market")?
+        .ok_or_else(|| ApiError::not_found("Market"))?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(market))))
+}
+
+/// The market's trading hours, whether it's in session now and when that
+/// next changes. Not cached, since the answer moves with the clock.
+pub async fn get_market_schedule(
+    State(app_config): State<AppConfig>,
+    Path(id): Path<String>,
+) -> Result<(StatusCode, Json<ApiResponse<MarketScheduleStatus>>), ApiError> {
+    let market_id = uuid::Uuid::parse_str(&id)
+        .map_err(|_| ApiError::bad_request("Invalid market ID format"))?;
+
+    let pool = app_config.pool.clone();
+    let market = tokio::task::spawn_blocking(move || {
+        use diesel::QueryDsl;
+
+        let mut conn = pool.get()?;
+        crate::schema::markets::dsl::markets
+            .find(market_id)
+            .get_result::<MarketRecord>(&mut conn)
+            .map_err(anyhow::Error::from)
+    })
+    .await
+    .map_err(|e| ApiError::internal_error(format!("Task join error: {}", e)))?
+    .map_err(|_| ApiError::not_found("Market"))?;
+
+    let status = schedule_status(&market, chrono::Utc::now().naive_utc())
+        .map_err(|e| ApiError::internal_error(format!("Unreadable schedule: {}", e)))?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(status))))
+}
+
+/// The band limit orders on the market are held to, if it's regulated and
+/// has a reference price.
+pub async fn get_market_price_band(
+    State(app_config): State<AppConfig>,
+    Path(id): Path<String>,
+) -> Result<(StatusCode, Json<ApiResponse<PriceBandStatus>>), ApiError> {
+    use diesel::QueryDsl;
+
+    let market_id = uuid::Uuid::parse_str(&id)
+        .map_err(|_| ApiError::bad_request("Invalid market ID format"))?;
+
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+    let market = crate::schema::markets::dsl::markets
+        .find(market_id)
+        .get_result::<MarketRecord>(&mut conn)
+        .map_err(|_| ApiError::not_found("Market"))?;
+
+    let status = map_to_api_error!(
+        price_band_status(&mut conn, &market).await,
+        "Failed to work out the price band"
+    )?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(status))))
+}
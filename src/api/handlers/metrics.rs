@@ -0,0 +1,45 @@
+use axum::{
+    Json,
+    extract::State,
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+
+use crate::{
+    api::{error::ApiError, response::ApiResponse},
+    health::{HealthReport, health_report},
+    map_to_api_error,
+    sockets::metrics::{SOCKET_METRICS, SocketMetricsSnapshot},
+    utils::app_config::AppConfig,
+};
+
+/// GET /metrics - Prometheus text exposition
+pub async fn metrics() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        SOCKET_METRICS.render_prometheus(),
+    )
+}
+
+/// GET /admin/sockets - Connection and subscription stats
+pub async fn get_socket_stats()
+-> Result<(StatusCode, Json<ApiResponse<SocketMetricsSnapshot>>), ApiError> {
+    Ok((
+        StatusCode::OK,
+        Json(ApiResponse::success(SOCKET_METRICS.snapshot())),
+    ))
+}
+
+/// GET /admin/health - DB connections, Hedera reachability, settlement queue,
+/// candle aggregation lag and webhook backlog
+pub async fn get_health_report(
+    State(app_config): State<AppConfig>,
+) -> Result<(StatusCode, Json<ApiResponse<HealthReport>>), ApiError> {
+    let report = map_to_api_error!(
+        health_report(&app_config).await,
+        "Failed to check system health"
+    )?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(report))))
+}
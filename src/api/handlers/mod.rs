@@ -1,11 +1,16 @@
 pub mod accounts;
 pub mod assets;
+pub mod documents;
 pub mod faucet_request;
 pub mod health;
 pub mod lending_pools;
 pub mod listings;
 pub mod markets;
+pub mod metrics;
 pub mod mutation;
+pub mod notifications;
 pub mod orders;
+pub mod price_feeds;
 pub mod ramper;
 pub mod time_series;
+pub mod webhooks;
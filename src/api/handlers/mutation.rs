@@ -3,7 +3,12 @@ use serde_json::Value;
 use socketioxide::SocketIo;
 use crate::{
     action_router::{ActionRouterInput, ActionRouterOutput},
-    api::{error::ApiError, extractors::ActionRouterExtractor, response::ApiResponse},
+    api::{
+        error::ApiError,
+        extractors::{ActionRouterExtractor, Operator},
+        response::ApiResponse,
+    },
+    audit_log::operations::process_audited,
     utils::app_config::AppConfig,
 };
 
@@ -13,8 +18,11 @@ use crate::{
 /// Expected JSON structure:
 /// { "Accounts": { "GetAccount": { "ByID": "..." } } }
 /// or any other valid ActionRouterInput variant
+///
+/// Approvals need the approving operator's key in `X-Operator-Key`.
 pub async fn process_mutation(
-    State(app_config): State<AppConfig>,
+    State(mut app_config): State<AppConfig>,
+    operator: Option<Operator>,
     // State(io): State<SocketIo>,
     ActionRouterExtractor(payload): ActionRouterExtractor,
 ) -> Result<Json<ApiResponse<Value>>, ApiError> {
@@ -28,11 +36,14 @@ pub async fn process_mutation(
             ))
         })?;
 
-    // Process the action through the router
-    let result = action_input
-        .process(app_config)
+    // Process the action through the router; mutations land in the audit log
+    let operator = operator.map(|Operator(operator)| operator);
+    let actor = operator
+        .as_ref()
+        .map_or_else(|| "api".to_string(), |o| o.username.clone());
+    let result = process_audited(action_input, &mut app_config, operator, &actor, "api")
         .await
-        .map_err(|e| ApiError::database_error(format!("Action processing failed: {}", e)))?;
+        .map_err(|e| ApiError::from_action_error("Action processing failed", &e))?;
 
     // Serialize the result back to JSON
     let result_json = serde_json::to_value(&result)
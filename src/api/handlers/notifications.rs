@@ -0,0 +1,58 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use hyper::StatusCode;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    api::{error::ApiError, response::ApiResponse},
+    map_to_api_error,
+    notifications::{
+        db_types::NotificationRow,
+        operations::{get_wallet_notifications, mark_notifications_read},
+    },
+    utils::app_config::AppConfig,
+};
+
+#[derive(Deserialize)]
+pub struct NotificationQueryParams {
+    #[serde(default)]
+    pub unread: bool,
+}
+
+#[derive(Deserialize)]
+pub struct MarkReadInput {
+    /// Notifications to mark; all of the wallet's when empty
+    #[serde(default)]
+    pub ids: Vec<Uuid>,
+}
+
+// GET /notifications/:wallet_id?unread=true
+pub async fn get_notifications_handler(
+    State(app_config): State<AppConfig>,
+    Path(wallet_id): Path<Uuid>,
+    Query(params): Query<NotificationQueryParams>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<NotificationRow>>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+    let rows = map_to_api_error!(
+        get_wallet_notifications(&mut conn, wallet_id, params.unread),
+        "Failed to load notifications"
+    )?;
+    Ok((StatusCode::OK, Json(ApiResponse::success(rows))))
+}
+
+// POST /notifications/:wallet_id/read
+pub async fn mark_notifications_read_handler(
+    State(app_config): State<AppConfig>,
+    Path(wallet_id): Path<Uuid>,
+    Json(input): Json<MarkReadInput>,
+) -> Result<(StatusCode, Json<ApiResponse<usize>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+    let updated = map_to_api_error!(
+        mark_notifications_read(&mut conn, wallet_id, input.ids),
+        "Failed to update notifications"
+    )?;
+    Ok((StatusCode::OK, Json(ApiResponse::success(updated))))
+}
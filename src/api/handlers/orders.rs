@@ -4,11 +4,14 @@ use axum::{
     Json,
 };
 use serde::Deserialize;
+use serde_json::Value;
 
 use crate::{
     order_book::processor_enums::{OrderBookProcessorInput, OrderBookProcessorOutput},
     action_router::{ActionRouterInput, ActionRouterOutput},
     api::{error::ApiError, response::ApiResponse},
+    market::symbols::{market_symbols, resolve_market},
+    map_to_api_error,
     utils::app_config::AppConfig,
 };
 
@@ -16,12 +19,35 @@ use crate::{
 #[derive(Debug, Deserialize)]
 pub struct OrderFilterParams {
     pub wallet: Option<String>,
+    /// The market's UUID or symbol
     pub market_id: Option<String>,
     pub status: Option<String>,
     pub order_type: Option<String>,
     pub mode: Option<String>,
 }
 
+/// Add `market_symbol` next to each order's `market_id`.
+fn with_market_symbols(app_config: &AppConfig, mut value: Value) -> Result<Value, ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+    let symbols = map_to_api_error!(market_symbols(&mut conn), "Failed to load market symbols")?;
+
+    let orders = match &mut value {
+        Value::Array(orders) => orders.iter_mut().collect::<Vec<_>>(),
+        order => vec![order],
+    };
+    for order in orders {
+        let symbol = order
+            .get("market_id")
+            .and_then(Value::as_str)
+            .and_then(|id| uuid::Uuid::parse_str(id).ok())
+            .and_then(|id| symbols.get(&id).cloned());
+        if let (Value::Object(fields), Some(symbol)) = (order, symbol) {
+            fields.insert("market_symbol".to_string(), Value::String(symbol));
+        }
+    }
+    Ok(value)
+}
+
 /// GET /orders/{id} - Get order by UUID
 pub async fn get_order_by_id(
     State(app_config): State<AppConfig>,
@@ -33,7 +59,7 @@ pub async fn get_order_by_id(
     let action = ActionRouterInput::OrderBook(OrderBookProcessorInput::GetOrder(order_id));
 
     let result = action
-        .process(app_config)
+        .process(app_config.clone())
         .await
         .map_err(|_| ApiError::not_found("Order"))?;
 
@@ -43,6 +69,7 @@ pub async fn get_order_by_id(
                 OrderBookProcessorOutput::GetOrder(order) => {
                     let json = serde_json::to_value(&order)
                         .map_err(|e| ApiError::internal_error(format!("Failed to serialize: {}", e)))?;
+                    let json = with_market_symbols(&app_config, json)?;
                     Ok((StatusCode::OK, Json(ApiResponse::success(json))))
                 }
                 _ => Err(ApiError::internal_error("Unexpected response type")),
@@ -57,11 +84,21 @@ pub async fn get_orders(
     State(app_config): State<AppConfig>,
     Query(params): Query<OrderFilterParams>,
 ) -> Result<(StatusCode, Json<ApiResponse<serde_json::Value>>), ApiError> {
-    // For now, return all orders without filtering
+    let market_id = match &params.market_id {
+        Some(market) => {
+            let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+            let market = resolve_market(&mut conn, market)
+                .map_err(|_| ApiError::not_found("Market"))?;
+            Some(market.id)
+        }
+        None => None,
+    };
+
+    // Only the market filter is wired so far
     let action = ActionRouterInput::OrderBook(OrderBookProcessorInput::GetOrders(
         crate::order_book::processor_enums::GetOrdersFilter {
             wallet: None,
-            market_id: None,
+            market_id,
             status: None,
             order_type: None,
             mode: None,
@@ -69,7 +106,7 @@ pub async fn get_orders(
     ));
 
     let result = action
-        .process(app_config)
+        .process(app_config.clone())
         .await
         .map_err(|e| ApiError::database_error(format!("Failed to fetch orders: {}", e)))?;
 
@@ -79,6 +116,7 @@ pub async fn get_orders(
                 OrderBookProcessorOutput::GetOrders(orders) => {
                     let json = serde_json::to_value(&orders)
                         .map_err(|e| ApiError::internal_error(format!("Failed to serialize: {}", e)))?;
+                    let json = with_market_symbols(&app_config, json)?;
                     Ok((StatusCode::OK, Json(ApiResponse::success(json))))
                 }
                 _ => Err(ApiError::internal_error("Unexpected response type")),
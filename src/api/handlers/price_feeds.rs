@@ -0,0 +1,81 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use hyper::StatusCode;
+use uuid::Uuid;
+
+use crate::{
+    api::{error::ApiError, response::ApiResponse},
+    map_to_api_error,
+    price_feeds::{
+        aggregation::{AggregatePrice, aggregate},
+        db_types::{OracleSourceQuoteRow, OracleSourceRow},
+        operations::{
+            AddOracleSourceInputArgs, add_oracle_source, disable_oracle_source, get_oracle_sources,
+            get_source_quotes,
+        },
+    },
+    utils::app_config::AppConfig,
+};
+
+// POST /oracle-sources
+pub async fn add_oracle_source_handler(
+    State(app_config): State<AppConfig>,
+    Json(input): Json<AddOracleSourceInputArgs>,
+) -> Result<(StatusCode, Json<ApiResponse<OracleSourceRow>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+    let source =
+        add_oracle_source(&mut conn, input).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(source))))
+}
+
+// GET /oracle-sources
+pub async fn get_oracle_sources_handler(
+    State(app_config): State<AppConfig>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<OracleSourceRow>>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+    let rows = map_to_api_error!(
+        get_oracle_sources(&mut conn),
+        "Failed to load oracle sources"
+    )?;
+    Ok((StatusCode::OK, Json(ApiResponse::success(rows))))
+}
+
+// DELETE /oracle-sources/:source_id
+pub async fn disable_oracle_source_handler(
+    State(app_config): State<AppConfig>,
+    Path(source_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<OracleSourceRow>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+    match disable_oracle_source(&mut conn, source_id) {
+        Ok(row) => Ok((StatusCode::OK, Json(ApiResponse::success(row)))),
+        Err(_) => Err(ApiError::not_found("Oracle source")),
+    }
+}
+
+// GET /oracle-sources/:source_id/quotes
+pub async fn get_oracle_source_quotes_handler(
+    State(app_config): State<AppConfig>,
+    Path(source_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<OracleSourceQuoteRow>>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+    let rows = map_to_api_error!(
+        get_source_quotes(&mut conn, source_id),
+        "Failed to load oracle quotes"
+    )?;
+    Ok((StatusCode::OK, Json(ApiResponse::success(rows))))
+}
+
+// GET /oracle-aggregate/:pool_id/:asset_id
+pub async fn get_oracle_aggregate_handler(
+    State(app_config): State<AppConfig>,
+    Path((pool_id, asset_id)): Path<(Uuid, Uuid)>,
+) -> Result<(StatusCode, Json<ApiResponse<AggregatePrice>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+    let result = map_to_api_error!(
+        aggregate(&mut conn, pool_id, asset_id).await,
+        "Failed to aggregate oracle sources"
+    )?;
+    Ok((StatusCode::OK, Json(ApiResponse::success(result))))
+}
@@ -1,10 +1,30 @@
-use axum::{Json, extract::State};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::HeaderMap,
+};
+use bytes::Bytes;
+use chrono::NaiveDateTime;
 use hyper::StatusCode;
+use serde::Deserialize;
 
 use crate::{
-    api::{error::ApiError, response::ApiResponse},
+    api::{error::ApiError, extractors::Operator, response::ApiResponse},
     map_to_api_error,
-    ramper::{CallbackData, OnRampRequest, OnRampResponse, Ramper},
+    ramper::{
+        OffRampRequest, OnRampRequest, OnRampResponse, Ramper,
+        admin::{RampAdminActionInputArgs, RampSummary, cancel_order, ramp_summary, retry_order},
+        db_types::{
+            RampFulfillmentRow, RampOrderEventRow, RampOrderRow, RampQuoteRow, RampReceiptRow,
+        },
+        fees::{RampFeeTotal, ramp_fee_report},
+        fulfillment::get_fulfillment,
+        operations::{find_ramp_order, get_order_events},
+        providers::RampProvider,
+        quotes::{RampQuoteInputArgs, create_quote},
+        receipts::get_receipt,
+        refunds::{RampOrderDetails, RefundRampOrderInputArgs, refund_order},
+    },
     utils::app_config::AppConfig,
 };
 
@@ -14,28 +34,276 @@ pub async fn request_payment(
 ) -> Result<(StatusCode, Json<ApiResponse<OnRampResponse>>), ApiError> {
     let ramper = map_to_api_error!(Ramper::from_env(), "Failed to get ramper")?;
     let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
-    let mut wallet = app_config.wallet.clone();
 
     let res = map_to_api_error!(
-        ramper.onramp(&mut wallet, &mut conn, req).await,
+        ramper.onramp(&app_config.wallet, &mut conn, req).await,
         "Failed to onramp"
     )?;
 
     Ok((StatusCode::OK, Json(ApiResponse::success(res))))
 }
 
+pub async fn request_offramp(
+    State(app_config): State<AppConfig>,
+    Json(req): Json<OffRampRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<RampOrderRow>>), ApiError> {
+    let ramper = map_to_api_error!(Ramper::from_env(), "Failed to get ramper")?;
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+
+    let res = map_to_api_error!(
+        ramper.offramp(&app_config.wallet, &mut conn, req).await,
+        "Failed to offramp"
+    )?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(res))))
+}
+
+// POST /ramp/callback
 pub async fn handle_callback(
     State(app_config): State<AppConfig>,
-    Json(req): Json<CallbackData>,
+    headers: HeaderMap,
+    body: Bytes,
 ) -> Result<(StatusCode, Json<ApiResponse<()>>), ApiError> {
     let ramper = map_to_api_error!(Ramper::from_env(), "Failed to get ramper")?;
+
+    let provider = map_to_api_error!(ramper.provider(), "Failed to get ramp provider")?;
+
+    let event = provider
+        .parse_webhook(&headers, &body)
+        .map_err(|e| ApiError::unauthorized(e.to_string()))?;
+
     let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
-    let mut wallet = app_config.wallet.clone();
 
     map_to_api_error!(
-        ramper.callback_handler(&mut conn, req).await,
+        ramper
+            .callback_handler(&app_config.wallet, &mut conn, event)
+            .await,
         "Failed to handle callback"
     )?;
 
     Ok((StatusCode::OK, Json(ApiResponse::success(()))))
 }
+
+// GET /ramp/orders/:reference
+pub async fn get_ramp_order_handler(
+    State(app_config): State<AppConfig>,
+    Path(reference): Path<String>,
+) -> Result<(StatusCode, Json<ApiResponse<RampOrderRow>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+
+    let order = map_to_api_error!(
+        find_ramp_order(&mut conn, &reference),
+        "Failed to get ramp order"
+    )?
+    .ok_or_else(|| ApiError::not_found(format!("Ramp order {} not found", reference)))?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(order))))
+}
+
+// GET /ramp/orders/:reference/events
+pub async fn get_ramp_order_events_handler(
+    State(app_config): State<AppConfig>,
+    Path(reference): Path<String>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<RampOrderEventRow>>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+
+    let order = map_to_api_error!(
+        find_ramp_order(&mut conn, &reference),
+        "Failed to get ramp order"
+    )?
+    .ok_or_else(|| ApiError::not_found(format!("Ramp order {} not found", reference)))?;
+
+    let events = map_to_api_error!(
+        get_order_events(&mut conn, order.id),
+        "Failed to get ramp order events"
+    )?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(events))))
+}
+
+// GET /ramp/orders/:reference/fulfillment
+pub async fn get_ramp_fulfillment_handler(
+    State(app_config): State<AppConfig>,
+    Path(reference): Path<String>,
+) -> Result<(StatusCode, Json<ApiResponse<RampFulfillmentRow>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+
+    let order = map_to_api_error!(
+        find_ramp_order(&mut conn, &reference),
+        "Failed to get ramp order"
+    )?
+    .ok_or_else(|| ApiError::not_found(format!("Ramp order {} not found", reference)))?;
+
+    let job = map_to_api_error!(
+        get_fulfillment(&mut conn, order.id),
+        "Failed to get ramp fulfillment"
+    )?
+    .ok_or_else(|| ApiError::not_found(format!("Ramp order {} has no fulfillment", reference)))?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(job))))
+}
+
+// GET /ramp/orders/:reference/receipt
+pub async fn get_ramp_receipt_handler(
+    State(app_config): State<AppConfig>,
+    Path(reference): Path<String>,
+) -> Result<(StatusCode, Json<ApiResponse<RampReceiptRow>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+
+    let order = map_to_api_error!(
+        find_ramp_order(&mut conn, &reference),
+        "Failed to get ramp order"
+    )?
+    .ok_or_else(|| ApiError::not_found(format!("Ramp order {} not found", reference)))?;
+
+    let receipt = map_to_api_error!(
+        get_receipt(&mut conn, order.id),
+        "Failed to get ramp receipt"
+    )?
+    .ok_or_else(|| ApiError::not_found(format!("Ramp order {} has no receipt", reference)))?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(receipt))))
+}
+
+// POST /ramp/orders/:reference/refund
+pub async fn refund_ramp_order_handler(
+    State(app_config): State<AppConfig>,
+    Path(reference): Path<String>,
+    Operator(approver): Operator,
+    Json(req): Json<RefundRampOrderInputArgs>,
+) -> Result<(StatusCode, Json<ApiResponse<RampOrderDetails>>), ApiError> {
+    let ramper = map_to_api_error!(Ramper::from_env(), "Failed to get ramper")?;
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+
+    let order = map_to_api_error!(
+        find_ramp_order(&mut conn, &reference),
+        "Failed to get ramp order"
+    )?
+    .ok_or_else(|| ApiError::not_found(format!("Ramp order {} not found", reference)))?;
+
+    let res = refund_order(
+        &ramper,
+        &app_config.wallet,
+        &mut conn,
+        order.id,
+        Some(&approver),
+        req,
+    )
+    .await
+    .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(res))))
+}
+
+// POST /ramp/quote
+pub async fn create_ramp_quote_handler(
+    State(app_config): State<AppConfig>,
+    Json(req): Json<RampQuoteInputArgs>,
+) -> Result<(StatusCode, Json<ApiResponse<RampQuoteRow>>), ApiError> {
+    let ramper = map_to_api_error!(Ramper::from_env(), "Failed to get ramper")?;
+    let provider = map_to_api_error!(ramper.provider(), "Failed to get ramp provider")?;
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+
+    let quote = create_quote(&mut conn, provider.name(), req)
+        .await
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(quote))))
+}
+
+#[derive(Deserialize)]
+pub struct RampFeeReportParams {
+    /// Start of the period, inclusive
+    pub from: Option<NaiveDateTime>,
+    /// End of the period, exclusive
+    pub to: Option<NaiveDateTime>,
+}
+
+// GET /ramp/fees
+pub async fn get_ramp_fees_handler(
+    State(app_config): State<AppConfig>,
+    Query(params): Query<RampFeeReportParams>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<RampFeeTotal>>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+
+    let totals = map_to_api_error!(
+        ramp_fee_report(&mut conn, params.from, params.to),
+        "Failed to get ramp fees"
+    )?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(totals))))
+}
+
+#[derive(Deserialize)]
+pub struct RampSummaryParams {
+    /// Days of activity to cover, 7 by default
+    pub days: Option<i64>,
+}
+
+// GET /admin/ramp/summary
+pub async fn get_ramp_summary_handler(
+    State(app_config): State<AppConfig>,
+    Query(params): Query<RampSummaryParams>,
+) -> Result<(StatusCode, Json<ApiResponse<RampSummary>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+
+    let summary = map_to_api_error!(
+        ramp_summary(&mut conn, params.days.unwrap_or(7)),
+        "Failed to get ramp summary"
+    )?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(summary))))
+}
+
+// POST /admin/ramp/orders/:reference/retry
+pub async fn retry_ramp_order_handler(
+    State(app_config): State<AppConfig>,
+    Path(reference): Path<String>,
+    Operator(operator): Operator,
+    Json(req): Json<RampAdminActionInputArgs>,
+) -> Result<(StatusCode, Json<ApiResponse<RampOrderDetails>>), ApiError> {
+    let ramper = map_to_api_error!(Ramper::from_env(), "Failed to get ramper")?;
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+
+    let order = map_to_api_error!(
+        find_ramp_order(&mut conn, &reference),
+        "Failed to get ramp order"
+    )?
+    .ok_or_else(|| ApiError::not_found(format!("Ramp order {} not found", reference)))?;
+
+    let res = retry_order(
+        &ramper,
+        &app_config.wallet,
+        &mut conn,
+        order.id,
+        &operator,
+        req,
+    )
+    .await
+    .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(res))))
+}
+
+// POST /admin/ramp/orders/:reference/cancel
+pub async fn cancel_ramp_order_handler(
+    State(app_config): State<AppConfig>,
+    Path(reference): Path<String>,
+    Operator(operator): Operator,
+    Json(req): Json<RampAdminActionInputArgs>,
+) -> Result<(StatusCode, Json<ApiResponse<RampOrderDetails>>), ApiError> {
+    let ramper = map_to_api_error!(Ramper::from_env(), "Failed to get ramper")?;
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+
+    let order = map_to_api_error!(
+        find_ramp_order(&mut conn, &reference),
+        "Failed to get ramp order"
+    )?
+    .ok_or_else(|| ApiError::not_found(format!("Ramp order {} not found", reference)))?;
+
+    let res = cancel_order(&ramper, &mut conn, order.id, &operator, req)
+        .await
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(res))))
+}
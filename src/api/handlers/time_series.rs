@@ -13,12 +13,15 @@ use crate::{
     },
     action_router::{ActionRouterInput, ActionRouterOutput},
     api::{error::ApiError, response::ApiResponse},
+    map_to_api_error,
+    market::symbols::resolve_market,
     utils::{app_config::AppConfig, cache},
 };
 
 /// Query parameters for time series history
 #[derive(Debug, Deserialize)]
 pub struct TimeSeriesParams {
+    /// The market's UUID or symbol
     pub market: String,
     pub duration_secs: String,
     pub interval: String,
@@ -30,9 +33,16 @@ pub async fn get_time_series_history(
     State(app_config): State<AppConfig>,
     Query(params): Query<TimeSeriesParams>,
 ) -> Result<(StatusCode, Json<ApiResponse<serde_json::Value>>), ApiError> {
-    // Parse market UUID
-    let market_id = uuid::Uuid::parse_str(&params.market)
-        .map_err(|_| ApiError::bad_request("Invalid market UUID format"))?;
+    // Market by UUID or symbol
+    let market_id = match uuid::Uuid::parse_str(&params.market) {
+        Ok(id) => id,
+        Err(_) => {
+            let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+            resolve_market(&mut conn, &params.market)
+                .map_err(|_| ApiError::not_found("Market"))?
+                .id
+        }
+    };
 
     // Parse duration in seconds
     let duration_secs = BigDecimal::from_str(&params.duration_secs)
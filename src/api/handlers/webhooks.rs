@@ -0,0 +1,64 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use hyper::StatusCode;
+use uuid::Uuid;
+
+use crate::{
+    api::{error::ApiError, response::ApiResponse},
+    map_to_api_error,
+    utils::app_config::AppConfig,
+    webhooks::{
+        db_types::{WebhookDeliveryRow, WebhookRow},
+        operations::{
+            RegisterWebhookInputArgs, RegisteredWebhook, deactivate_webhook,
+            get_webhook_deliveries, get_webhooks, register_webhook,
+        },
+    },
+};
+
+// POST /webhooks
+pub async fn register_webhook_handler(
+    State(app_config): State<AppConfig>,
+    Json(input): Json<RegisterWebhookInputArgs>,
+) -> Result<(StatusCode, Json<ApiResponse<RegisteredWebhook>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+    let registered =
+        register_webhook(&mut conn, input).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(registered))))
+}
+
+// GET /webhooks
+pub async fn get_webhooks_handler(
+    State(app_config): State<AppConfig>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<WebhookRow>>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+    let rows = map_to_api_error!(get_webhooks(&mut conn), "Failed to load webhooks")?;
+    Ok((StatusCode::OK, Json(ApiResponse::success(rows))))
+}
+
+// DELETE /webhooks/:webhook_id
+pub async fn delete_webhook_handler(
+    State(app_config): State<AppConfig>,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<WebhookRow>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+    match deactivate_webhook(&mut conn, webhook_id) {
+        Ok(row) => Ok((StatusCode::OK, Json(ApiResponse::success(row)))),
+        Err(_) => Err(ApiError::not_found("Webhook")),
+    }
+}
+
+// GET /webhooks/:webhook_id/deliveries
+pub async fn get_webhook_deliveries_handler(
+    State(app_config): State<AppConfig>,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<WebhookDeliveryRow>>>), ApiError> {
+    let mut conn = map_to_api_error!(app_config.pool.get(), "Unable to obtain")?;
+    let rows = map_to_api_error!(
+        get_webhook_deliveries(&mut conn, webhook_id),
+        "Failed to load deliveries"
+    )?;
+    Ok((StatusCode::OK, Json(ApiResponse::success(rows))))
+}
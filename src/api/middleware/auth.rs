@@ -1,6 +1,13 @@
-use axum::http::HeaderMap;
+use axum::{extract::Request, http::HeaderMap};
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, Pool},
+};
 
-use crate::api::error::ApiError;
+use crate::{accounts::operators::operator_for_api_key, api::error::ApiError};
+
+/// Header carrying an operator's API key, for calls made as that operator.
+pub const OPERATOR_KEY_HEADER: &str = "x-operator-key";
 
 /// Extract and validate Bearer token from Authorization header
 pub async fn validate_auth(
@@ -27,3 +34,31 @@ pub async fn validate_auth(
 
     Ok(())
 }
+
+/// Leave the operator a request's operator key belongs to in its
+/// extensions. Requests without a key go on anonymously; a key that
+/// matches nobody is turned away.
+pub async fn resolve_operator(
+    req: &mut Request,
+    pool: Pool<ConnectionManager<PgConnection>>,
+) -> Result<(), ApiError> {
+    let Some(key) = req.headers().get(OPERATOR_KEY_HEADER) else {
+        return Ok(());
+    };
+    let key = key
+        .to_str()
+        .map_err(|_| ApiError::unauthorized("Invalid operator key"))?
+        .to_string();
+
+    let operator = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        operator_for_api_key(&mut conn, &key)
+    })
+    .await
+    .map_err(|e| ApiError::internal_error(format!("Operator lookup failed: {}", e)))?
+    .map_err(|e| ApiError::unauthorized(format!("Invalid operator key: {}", e)))?
+    .ok_or_else(|| ApiError::unauthorized("Invalid operator key"))?;
+
+    req.extensions_mut().insert(operator);
+    Ok(())
+}
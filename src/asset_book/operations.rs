@@ -1,5 +1,6 @@
 use anyhow::{Result, anyhow};
 use contract_integrator::{
+    hedera::{TokenId, TokenInfoQuery},
     id_to_evm_address,
     utils::functions::{
         ContractCallInput, ContractCallOutput,
@@ -11,7 +12,7 @@ use contract_integrator::{
         asset_manager::{
             AirdropArgs, AssetManagerFunctionInput, AssetManagerFunctionOutput, MintArgs,
         },
-        commons::{get_contract_addresses, get_contract_id_from_evm_address},
+        commons::{get_account_balances, get_contract_addresses, get_contract_id_from_evm_address},
     },
     wallet::wallet::ActionWallet,
 };
@@ -30,10 +31,11 @@ use crate::{
         processor_enums::CreateNewAssetInputArgs,
     },
     extract_option,
+    utils::wallet::SharedWallet,
 };
 
 pub async fn create_asset(
-    wallet: &mut ActionWallet,
+    wallet: &SharedWallet,
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
     args: CreateNewAssetInputArgs,
 ) -> Result<Uuid> {
@@ -175,7 +177,7 @@ pub async fn get_wallet(
 
 pub async fn mint_asset(
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
-    wallet: &mut ActionWallet,
+    wallet: &SharedWallet,
     asset_id: Uuid,
     amount: u64,
 ) -> Result<()> {
@@ -200,11 +202,11 @@ pub async fn mint_asset(
 
 pub async fn airdrop_asset(
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
-    wallet: &mut ActionWallet,
+    wallet: &SharedWallet,
     asset_id: Uuid,
     wallet_id: Uuid,
     amount: u64,
-) -> Result<()> {
+) -> Result<String> {
     let asset = get_asset(conn, asset_id).await?;
     let account_wallet = get_wallet(conn, wallet_id).await?;
 
@@ -220,8 +222,36 @@ pub async fn airdrop_asset(
     match res {
         ContractCallOutput::AssetManager(AssetManagerFunctionOutput::Airdrop(o)) => {
             println!("Transaction successful :: {:?}", o.transaction_id);
-            Ok(()) // TODO: record airdrops to ledger
+            Ok(o.transaction_id) // TODO: record airdrops to ledger
         }
         _ => Err(anyhow!("Failed to airdrop")),
     }
 }
+
+fn token_id(asset: &AssetBookRecord) -> Result<TokenId> {
+    TokenId::from_solidity_address(&asset.token)
+        .map_err(|e| anyhow!("Invalid token {}: {:?}", asset.token, e))
+}
+
+/// The total supply of an asset's token, read from the network.
+pub async fn token_supply(wallet: &ActionWallet, asset: &AssetBookRecord) -> Result<u64> {
+    let info = TokenInfoQuery::new()
+        .token_id(token_id(asset)?)
+        .execute(&wallet.client)
+        .await
+        .map_err(|e| anyhow!("Failed to read supply of token {}: {:?}", asset.token, e))?;
+    Ok(info.total_supply)
+}
+
+/// A wallet's balance of an asset's token, read from the network.
+pub async fn token_balance(
+    wallet: &ActionWallet,
+    account: &CradleWalletAccountRecord,
+    asset: &AssetBookRecord,
+) -> Result<u64> {
+    let token = token_id(asset)?;
+    let balances = get_account_balances(&wallet.client, &account.contract_id)
+        .await
+        .map_err(|e| anyhow!("Failed to read balance of wallet {}: {:?}", account.id, e))?;
+    Ok(*balances.tokens.get(&token).unwrap_or(&0))
+}
@@ -29,8 +29,7 @@ impl ActionProcessor<AssetBookConfig, AssetBookProcessorOutput> for AssetBookPro
 
         match self {
             AssetBookProcessorInput::CreateNewAsset(args) => {
-                let mut wallet = app_config.wallet.clone();
-                let asset_id = create_asset(&mut wallet, app_conn, args.clone()).await?;
+                let asset_id = create_asset(&app_config.wallet, app_conn, args.clone()).await?;
 
                 Ok(AssetBookProcessorOutput::CreateNewAsset(asset_id))
             }
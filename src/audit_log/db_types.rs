@@ -0,0 +1,31 @@
+use crate::schema::mutation_audit_log as MutationAuditLogTable;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = MutationAuditLogTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct MutationAuditRow {
+    pub id: Uuid,
+    pub actor: String,
+    pub source: String,
+    pub action: String,
+    pub payload: Value,
+    pub result: Option<Value>,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = MutationAuditLogTable)]
+pub struct CreateMutationAudit {
+    pub actor: String,
+    pub source: String,
+    pub action: String,
+    pub payload: Value,
+    pub result: Option<Value>,
+    pub error: Option<String>,
+}
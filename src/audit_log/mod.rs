@@ -0,0 +1,2 @@
+pub mod db_types;
+pub mod operations;
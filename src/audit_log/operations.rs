@@ -0,0 +1,171 @@
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    accounts::db_types::AdminOperatorRecord,
+    action_router::{ActionRouterInput, ActionRouterOutput},
+    audit_log::db_types::{CreateMutationAudit, MutationAuditRow},
+    utils::{app_config::AppConfig, commons::DbConn},
+};
+
+/// Entries returned when a query doesn't set a limit.
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 500;
+
+/// "Processor.Variant" for a router input, e.g. "Markets.UpdateMarketStatus".
+pub fn action_name(payload: &Value) -> String {
+    let Some((processor, inner)) = payload.as_object().and_then(|o| o.iter().next()) else {
+        return "Unknown".to_string();
+    };
+    let variant = match inner {
+        Value::String(unit) => Some(unit.as_str()),
+        Value::Object(o) => o.keys().next().map(String::as_str),
+        _ => None,
+    };
+    match variant {
+        Some(variant) => format!("{}.{}", processor, variant),
+        None => processor.clone(),
+    }
+}
+
+/// Lookups go through the router too; they aren't worth keeping.
+fn is_read(action: &str) -> bool {
+    action
+        .rsplit('.')
+        .next()
+        .is_some_and(|variant| variant.starts_with("Get"))
+}
+
+/// Keep a record of a mutation made outside the router, such as an oracle
+/// price set straight from the dashboard.
+pub fn record_mutation(
+    conn: DbConn<'_>,
+    actor: &str,
+    source: &str,
+    action: &str,
+    payload: Value,
+    outcome: std::result::Result<Value, String>,
+) -> Result<MutationAuditRow> {
+    let (result, error) = match outcome {
+        Ok(result) => (Some(result), None),
+        Err(error) => (None, Some(error)),
+    };
+    let entry = CreateMutationAudit {
+        actor: actor.to_string(),
+        source: source.to_string(),
+        action: action.to_string(),
+        payload,
+        result,
+        error,
+    };
+
+    use crate::schema::mutation_audit_log::dsl::*;
+
+    Ok(diesel::insert_into(mutation_audit_log)
+        .values(&entry)
+        .get_result::<MutationAuditRow>(conn)?)
+}
+
+/// Run an action through the router, as `operator` when the caller
+/// authenticated as one, and log it with its output or error unless it only
+/// reads. A failure to write the log is reported but doesn't undo or fail
+/// the action.
+pub async fn process_audited(
+    input: ActionRouterInput,
+    app_config: &mut AppConfig,
+    operator: Option<AdminOperatorRecord>,
+    actor: &str,
+    source: &str,
+) -> Result<ActionRouterOutput> {
+    let payload = serde_json::to_value(&input)?;
+    let action = action_name(&payload);
+    let pool = app_config.pool.clone();
+
+    let res = input.process_as(app_config, operator).await;
+    if is_read(&action) {
+        return res;
+    }
+
+    let outcome = match &res {
+        Ok(output) => serde_json::to_value(output).map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    let (actor, source) = (actor.to_string(), source.to_string());
+    let logged = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        record_mutation(&mut conn, &actor, &source, &action, payload, outcome)
+    })
+    .await;
+    match logged {
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => eprintln!("[AUDIT] Failed to record mutation: {:?}", e),
+        Err(e) => eprintln!("[AUDIT] Failed to record mutation: {:?}", e),
+    }
+
+    res
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AuditLogFilter {
+    pub actor: Option<String>,
+    /// Matches any action containing it, so "Markets" or "UpdateMarketStatus"
+    /// both work
+    pub action: Option<String>,
+    /// Matches entries whose payload or result mentions it, such as a
+    /// market id
+    pub subject: Option<String>,
+    pub since: Option<NaiveDateTime>,
+    pub until: Option<NaiveDateTime>,
+    pub limit: Option<i64>,
+}
+
+/// Logged mutations matching `filter`, newest first.
+pub fn get_audit_log(conn: DbConn<'_>, filter: &AuditLogFilter) -> Result<Vec<MutationAuditRow>> {
+    use crate::schema::mutation_audit_log::dsl::*;
+
+    let mut query = mutation_audit_log.into_boxed();
+    if let Some(name) = filter
+        .actor
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+    {
+        // usernames are stored lowercased
+        query = query.filter(actor.eq(name.to_lowercase()));
+    }
+    if let Some(name) = filter
+        .action
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+    {
+        query = query.filter(action.ilike(format!("%{}%", name)));
+    }
+    if let Some(text) = filter
+        .subject
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        query = query.filter(
+            diesel::dsl::sql::<diesel::sql_types::Bool>(
+                "(payload::text || coalesce(result::text, '')) ilike ",
+            )
+            .bind::<diesel::sql_types::Text, _>(format!("%{}%", text)),
+        );
+    }
+    if let Some(since) = filter.since {
+        query = query.filter(created_at.ge(since));
+    }
+    if let Some(until) = filter.until {
+        query = query.filter(created_at.lt(until));
+    }
+
+    Ok(query
+        .order(created_at.desc())
+        .limit(filter.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT))
+        .get_results::<MutationAuditRow>(conn)?)
+}
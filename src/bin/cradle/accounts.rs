@@ -0,0 +1,224 @@
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use clap::Subcommand;
+use serde_json::Value;
+use uuid::Uuid;
+
+use cradle_back_end::accounts::db_types::CreateCradleAccount;
+use cradle_back_end::accounts::processor_enums::{
+    AccountsProcessorInput, AssociateTokenToWalletInputArgs, CreateCradleWalletInputArgs,
+    DeleteAccountInputArgs, GetAccountInputArgs, GetWalletInputArgs, GrantKYCInputArgs,
+    UpdateAccountStatusInputArgs, UpdateAccountTypeInputArgs, UpdateWalletStatusByIdInputArgs,
+    WithdrawTokensInputArgs, WithdrawalType,
+};
+use cradle_back_end::action_router::ActionRouterInput;
+use cradle_back_end::utils::app_config::AppConfig;
+
+use crate::context::CliContext;
+use crate::dispatch;
+
+pub const ACCOUNT_TYPES: [&str; 3] = ["retail", "institutional", "system"];
+pub const ACCOUNT_STATUSES: [&str; 4] = ["unverified", "verified", "suspended", "closed"];
+pub const WALLET_STATUSES: [&str; 3] = ["active", "inactive", "suspended"];
+
+#[derive(Subcommand)]
+pub enum AccountsCommand {
+    /// Create an account and its wallet
+    Create {
+        /// The account's id in the frontend's auth provider
+        #[arg(long)]
+        linked_account: Option<String>,
+        /// retail (default), institutional or system
+        #[arg(long = "type")]
+        account_type: Option<String>,
+        /// unverified (default), verified, suspended or closed
+        #[arg(long)]
+        status: Option<String>,
+    },
+    /// Look an account up by id or linked account
+    Get {
+        id: Option<Uuid>,
+        #[arg(long, conflicts_with = "id")]
+        linked_account: Option<String>,
+    },
+    /// Create another wallet for an account
+    CreateWallet {
+        account: Option<Uuid>,
+        /// active (default), inactive or suspended
+        #[arg(long)]
+        status: Option<String>,
+    },
+    /// Look a wallet up by id or owning account
+    GetWallet {
+        id: Option<Uuid>,
+        #[arg(long, conflicts_with = "id")]
+        account: Option<Uuid>,
+    },
+    /// Verify, suspend or close an account
+    SetStatus {
+        account: Option<Uuid>,
+        #[arg(long)]
+        status: Option<String>,
+    },
+    /// Make an account retail, institutional or system
+    SetType {
+        account: Option<Uuid>,
+        #[arg(long = "type")]
+        account_type: Option<String>,
+    },
+    /// Activate, deactivate or suspend a wallet
+    SetWalletStatus {
+        wallet: Option<Uuid>,
+        #[arg(long)]
+        status: Option<String>,
+    },
+    /// Associate a wallet with an asset's token
+    Associate {
+        wallet: Option<Uuid>,
+        /// Asset id
+        #[arg(long)]
+        asset: Option<Uuid>,
+    },
+    /// Grant a wallet KYC for an asset's token
+    GrantKyc {
+        wallet: Option<Uuid>,
+        /// Asset id
+        #[arg(long)]
+        asset: Option<Uuid>,
+    },
+    /// Send tokens out of a wallet
+    Withdraw {
+        wallet: Option<Uuid>,
+        /// Token id on the ledger
+        #[arg(long)]
+        token: Option<String>,
+        /// Base units
+        #[arg(long)]
+        amount: Option<BigDecimal>,
+        /// Destination address
+        #[arg(long)]
+        to: Option<String>,
+    },
+    /// Delete an account
+    Delete { account: Option<Uuid> },
+}
+
+pub async fn run(cmd: AccountsCommand, ctx: &CliContext, app_config: &AppConfig) -> Result<Value> {
+    let input = match cmd {
+        AccountsCommand::Create {
+            linked_account,
+            account_type,
+            status,
+        } => AccountsProcessorInput::CreateAccount(CreateCradleAccount {
+            linked_account_id: ctx.string(linked_account, "linked-account", "Linked account ID")?,
+            account_type: ctx.optional_choice(
+                account_type,
+                "type",
+                "Account type",
+                &ACCOUNT_TYPES,
+            )?,
+            status: ctx.optional_choice(status, "status", "Status", &ACCOUNT_STATUSES)?,
+        }),
+        AccountsCommand::Get { id, linked_account } => {
+            let query = match (id, linked_account) {
+                (Some(id), _) => GetAccountInputArgs::ByID(id),
+                (_, Some(linked)) => GetAccountInputArgs::ByLinkedAccount(linked),
+                _ => match ctx.pick(
+                    "an id or --linked-account",
+                    "Look up by",
+                    vec!["ID", "Linked account"],
+                )? {
+                    0 => GetAccountInputArgs::ByID(ctx.uuid(None, "<id>", "Account ID")?),
+                    _ => GetAccountInputArgs::ByLinkedAccount(ctx.string(
+                        None,
+                        "linked-account",
+                        "Linked account ID",
+                    )?),
+                },
+            };
+            AccountsProcessorInput::GetAccount(query)
+        }
+        AccountsCommand::CreateWallet { account, status } => {
+            AccountsProcessorInput::CreateAccountWallet(CreateCradleWalletInputArgs {
+                cradle_account_id: ctx.uuid(account, "<account>", "Account ID")?,
+                status: ctx.optional_choice(status, "status", "Wallet status", &WALLET_STATUSES)?,
+            })
+        }
+        AccountsCommand::GetWallet { id, account } => {
+            let query = match (id, account) {
+                (Some(id), _) => GetWalletInputArgs::ById(id),
+                (_, Some(account)) => GetWalletInputArgs::ByCradleAccount(account),
+                _ => match ctx.pick(
+                    "an id or --account",
+                    "Look up by",
+                    vec!["Wallet ID", "Account ID"],
+                )? {
+                    0 => GetWalletInputArgs::ById(ctx.uuid(None, "<id>", "Wallet ID")?),
+                    _ => GetWalletInputArgs::ByCradleAccount(ctx.uuid(
+                        None,
+                        "account",
+                        "Account ID",
+                    )?),
+                },
+            };
+            AccountsProcessorInput::GetWallet(query)
+        }
+        AccountsCommand::SetStatus { account, status } => {
+            AccountsProcessorInput::UpdateAccountStatus(UpdateAccountStatusInputArgs {
+                cradle_account_id: ctx.uuid(account, "<account>", "Account ID")?,
+                status: ctx.choice(status, "status", "Status", &ACCOUNT_STATUSES)?,
+            })
+        }
+        AccountsCommand::SetType {
+            account,
+            account_type,
+        } => AccountsProcessorInput::UpdateAccountType(UpdateAccountTypeInputArgs {
+            cradle_account_id: ctx.uuid(account, "<account>", "Account ID")?,
+            account_type: ctx.choice(account_type, "type", "Account type", &ACCOUNT_TYPES)?,
+        }),
+        AccountsCommand::SetWalletStatus { wallet, status } => {
+            AccountsProcessorInput::UpdateAccountWalletStatusById(UpdateWalletStatusByIdInputArgs {
+                wallet_id: ctx.uuid(wallet, "<wallet>", "Wallet ID")?,
+                status: ctx.choice(status, "status", "Wallet status", &WALLET_STATUSES)?,
+            })
+        }
+        AccountsCommand::Associate { wallet, asset } => {
+            AccountsProcessorInput::AssociateTokenToWallet(AssociateTokenToWalletInputArgs {
+                wallet_id: ctx.uuid(wallet, "<wallet>", "Wallet ID")?,
+                token: ctx.uuid(asset, "asset", "Asset ID")?,
+            })
+        }
+        AccountsCommand::GrantKyc { wallet, asset } => {
+            AccountsProcessorInput::GrantKYC(GrantKYCInputArgs {
+                wallet_id: ctx.uuid(wallet, "<wallet>", "Wallet ID")?,
+                token: ctx.uuid(asset, "asset", "Asset ID")?,
+            })
+        }
+        AccountsCommand::Withdraw {
+            wallet,
+            token,
+            amount,
+            to,
+        } => {
+            let args = WithdrawTokensInputArgs {
+                withdrawal_type: WithdrawalType::Crypto,
+                from: ctx.uuid(wallet, "<wallet>", "Wallet ID")?,
+                token: ctx.string(token, "token", "Token id")?,
+                amount: ctx.decimal(amount, "amount", "Amount (base units)")?,
+                to: ctx.string(to, "to", "Destination address")?,
+            };
+            ctx.confirm(&format!(
+                "Send {} of {} from wallet {} to {}?",
+                args.amount, args.token, args.from, args.to
+            ))?;
+            AccountsProcessorInput::WithdrawTokens(args)
+        }
+        AccountsCommand::Delete { account } => {
+            let account = ctx.uuid(account, "<account>", "Account ID")?;
+            ctx.confirm(&format!("Delete account {}?", account))?;
+            AccountsProcessorInput::DeleteAccount(DeleteAccountInputArgs::ById(account))
+        }
+    };
+
+    dispatch(ActionRouterInput::Accounts(input), app_config).await
+}
@@ -0,0 +1,296 @@
+//! `cradle aggregate`: candle backfills from the terminal, for ranges too
+//! large to sit through in the admin UI.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDateTime};
+use clap::Subcommand;
+use diesel::prelude::*;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use serde_json::Value;
+
+use cradle_back_end::aggregators::checkpoint::get_last_checkpoint;
+use cradle_back_end::aggregators::{
+    AggregatorsConfig, AggregatorsProcessorInput, AggregatorsProcessorOutput, BackfillInputArgs,
+};
+use cradle_back_end::cli_utils::{CliError, format_table, print_info, print_warning};
+use cradle_back_end::market::db_types::MarketRecord;
+use cradle_back_end::market_time_series::db_types::TimeSeriesInterval;
+use cradle_back_end::utils::app_config::AppConfig;
+use cradle_back_end::utils::traits::ActionProcessor;
+
+use crate::context::CliContext;
+use crate::markets::{market_arg, parse_time};
+
+#[derive(Subcommand)]
+pub enum AggregateCommand {
+    /// Write candles for the market's base asset over a past range
+    Backfill {
+        /// Market id or symbol
+        #[arg(long)]
+        market: Option<String>,
+        /// 15secs, 30secs, 45secs, 1min, 5min, 15min, 30min, 1hr, 4hr, 1day
+        /// or 1week; repeat for several
+        #[arg(long = "interval")]
+        intervals: Vec<String>,
+        /// Start, UTC, as YYYY-MM-DD HH:MM
+        #[arg(long, value_parser = parse_time)]
+        from: Option<NaiveDateTime>,
+        /// End, UTC, as YYYY-MM-DD HH:MM
+        #[arg(long, value_parser = parse_time)]
+        to: Option<NaiveDateTime>,
+        /// Carry on from each interval's checkpoint instead of `--from`
+        #[arg(long)]
+        resume: bool,
+        /// Windows handed to the processor at a time
+        #[arg(long, default_value_t = 200)]
+        chunk: i32,
+    },
+}
+
+/// How one interval's backfill went. Windows with no trades get no bar.
+#[derive(Serialize)]
+struct BackfillSummary {
+    interval: &'static str,
+    windows: i64,
+    bars_written: u32,
+    empty_windows: i64,
+    /// Windows before the checkpoint, done by an earlier run
+    resumed_past: i64,
+    /// Where to pick up with `--resume`, if stopped early
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stopped_at: Option<NaiveDateTime>,
+}
+
+pub async fn run(cmd: AggregateCommand, ctx: &CliContext, app_config: &AppConfig) -> Result<Value> {
+    match cmd {
+        AggregateCommand::Backfill {
+            market,
+            intervals,
+            from,
+            to,
+            resume,
+            chunk,
+        } => {
+            let market_id = market_arg(ctx, app_config, market)?;
+            let market = {
+                use cradle_back_end::schema::markets::dsl::*;
+                markets
+                    .find(market_id)
+                    .first::<MarketRecord>(&mut app_config.pool.get()?)
+                    .optional()?
+                    .ok_or_else(|| CliError::NotFound(format!("market {}", market_id)))?
+            };
+
+            let intervals = interval_args(ctx, intervals)?;
+            let from = time_arg(ctx, from, "from", "From (UTC, YYYY-MM-DD HH:MM)")?;
+            let to = time_arg(ctx, to, "to", "To (UTC, YYYY-MM-DD HH:MM)")?;
+            if from >= to {
+                return Err(CliError::ValidationError(
+                    "The range has to end after it starts".to_string(),
+                )
+                .into());
+            }
+            if chunk < 1 {
+                return Err(
+                    CliError::ValidationError("--chunk has to be at least 1".to_string()).into(),
+                );
+            }
+
+            let stop = Arc::new(AtomicBool::new(false));
+            let on_signal = stop.clone();
+            tokio::spawn(async move {
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    on_signal.store(true, Ordering::SeqCst);
+                }
+            });
+
+            let mut summaries = Vec::new();
+            for interval in intervals {
+                let args = BackfillInputArgs {
+                    market_id: market.id,
+                    asset_id: market.asset_one,
+                    interval,
+                    backfill_start: from,
+                    backfill_end: to,
+                };
+                summaries
+                    .push(backfill(ctx, app_config, &market, &args, resume, chunk, &stop).await?);
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+            }
+
+            if ctx.json {
+                return Ok(serde_json::to_value(&summaries)?);
+            }
+            format_table(
+                vec![
+                    "Interval",
+                    "Windows",
+                    "Bars written",
+                    "Empty",
+                    "Resumed past",
+                    "Stopped at",
+                ],
+                summaries
+                    .iter()
+                    .map(|s| {
+                        vec![
+                            s.interval.to_string(),
+                            s.windows.to_string(),
+                            s.bars_written.to_string(),
+                            s.empty_windows.to_string(),
+                            s.resumed_past.to_string(),
+                            s.stopped_at.map(|t| t.to_string()).unwrap_or_default(),
+                        ]
+                    })
+                    .collect(),
+            );
+            if summaries.iter().any(|s| s.stopped_at.is_some()) {
+                print_warning("Stopped early; run again with --resume to carry on");
+            }
+            Ok(Value::Null)
+        }
+    }
+}
+
+fn interval_args(ctx: &CliContext, given: Vec<String>) -> Result<Vec<TimeSeriesInterval>> {
+    let names: Vec<&str> = TimeSeriesInterval::ALL.iter().map(|i| i.as_str()).collect();
+    let given = if given.is_empty() {
+        vec![ctx.string(None, "interval", "Interval")?]
+    } else {
+        given
+    };
+    given
+        .iter()
+        .map(|raw| {
+            TimeSeriesInterval::ALL
+                .iter()
+                .find(|i| i.as_str() == raw.trim())
+                .cloned()
+                .ok_or_else(|| {
+                    anyhow::Error::from(CliError::ValidationError(format!(
+                        "interval must be one of {}, got {}",
+                        names.join(", "),
+                        raw
+                    )))
+                })
+        })
+        .collect()
+}
+
+fn time_arg(
+    ctx: &CliContext,
+    given: Option<NaiveDateTime>,
+    flag: &str,
+    prompt: &str,
+) -> Result<NaiveDateTime> {
+    match given {
+        Some(at) => Ok(at),
+        None => parse_time(&ctx.string(None, flag, prompt)?)
+            .map_err(|e| CliError::ValidationError(e).into()),
+    }
+}
+
+/// Windows of `step` needed to cover `from..to`, counting a partial one.
+fn windows(from: NaiveDateTime, to: NaiveDateTime, step: Duration) -> i64 {
+    let secs = (to - from).num_seconds().max(0);
+    (secs + step.num_seconds() - 1) / step.num_seconds()
+}
+
+/// One interval's backfill as a series of `BackfillTrades` calls of `chunk`
+/// windows, the same as the admin UI's aggregation job. The processor saves
+/// a checkpoint after every window, so stopping between chunks loses
+/// nothing.
+async fn backfill(
+    ctx: &CliContext,
+    app_config: &AppConfig,
+    market: &MarketRecord,
+    args: &BackfillInputArgs,
+    resume: bool,
+    chunk: i32,
+    stop: &AtomicBool,
+) -> Result<BackfillSummary> {
+    let mut processor_config = app_config.clone();
+    let mut local_config = AggregatorsConfig::default();
+    let mut conn = app_config.pool.get()?;
+
+    let mut start = args.backfill_start;
+    if resume
+        && let Some(checkpoint) =
+            get_last_checkpoint(args.market_id, args.asset_id, &args.interval, &mut conn).await?
+    {
+        start = checkpoint.clamp(args.backfill_start, args.backfill_end);
+    }
+
+    let step = Duration::seconds(args.interval.seconds());
+    let total = windows(start, args.backfill_end, step);
+    let resumed_past = windows(args.backfill_start, start, step);
+
+    let bar = if ctx.json {
+        ProgressBar::hidden()
+    } else {
+        if resumed_past > 0 {
+            print_info(&format!(
+                "{} {}: resuming at {}",
+                market.symbol,
+                args.interval.as_str(),
+                start
+            ));
+        }
+        ProgressBar::new(total as u64)
+    };
+    bar.set_style(
+        ProgressStyle::with_template(
+            "{prefix} [{bar:40}] {pos}/{len} windows, {msg} ({eta} left)",
+        )?
+        .progress_chars("=> "),
+    );
+    bar.set_prefix(format!("{} {}", market.symbol, args.interval.as_str()));
+
+    let mut bars = 0u32;
+    let mut done = 0i64;
+    let mut stopped_at = None;
+    while start < args.backfill_end {
+        if stop.load(Ordering::SeqCst) {
+            stopped_at = Some(start);
+            break;
+        }
+        bar.set_message(format!("{} bars", bars));
+
+        let end = std::cmp::min(start + step * chunk, args.backfill_end);
+        let input = AggregatorsProcessorInput::BackfillTrades(BackfillInputArgs {
+            market_id: args.market_id,
+            asset_id: args.asset_id,
+            interval: args.interval.clone(),
+            backfill_start: start,
+            backfill_end: end,
+        });
+        if let AggregatorsProcessorOutput::BackfillTrades(created) = input
+            .process(&mut processor_config, &mut local_config, Some(&mut conn))
+            .await?
+        {
+            bars += created;
+        }
+
+        let covered = windows(start, end, step);
+        done += covered;
+        bar.inc(covered as u64);
+        start = end;
+        tokio::task::yield_now().await;
+    }
+    bar.finish_with_message(format!("{} bars", bars));
+
+    Ok(BackfillSummary {
+        interval: args.interval.as_str(),
+        windows: total + resumed_past,
+        bars_written: bars,
+        empty_windows: done - bars as i64,
+        resumed_past,
+        stopped_at,
+    })
+}
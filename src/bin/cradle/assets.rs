@@ -0,0 +1,147 @@
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use cradle_back_end::action_router::ActionRouterInput;
+use cradle_back_end::asset_book::operations::airdrop_asset;
+use cradle_back_end::asset_book::processor_enums::{
+    AssetBookProcessorInput, CreateExistingAssetInputArgs, CreateNewAssetInputArgs,
+    GetAssetInputArgs,
+};
+use cradle_back_end::utils::app_config::AppConfig;
+
+use crate::context::CliContext;
+use crate::dispatch;
+
+pub const ASSET_TYPES: [&str; 6] = [
+    "bridged",
+    "native",
+    "yield_bearing",
+    "chain_native",
+    "stablecoin",
+    "volatile",
+];
+
+#[derive(Args)]
+pub struct AssetDetails {
+    /// bridged, native, yield_bearing, chain_native, stablecoin or volatile
+    #[arg(long = "type")]
+    pub asset_type: Option<String>,
+    #[arg(long)]
+    pub name: Option<String>,
+    #[arg(long)]
+    pub symbol: Option<String>,
+    #[arg(long)]
+    pub decimals: Option<i64>,
+    #[arg(long)]
+    pub icon: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum AssetsCommand {
+    /// Issue a new token and put it on the book
+    Create {
+        #[command(flatten)]
+        details: AssetDetails,
+    },
+    /// Put a token that already exists on the book
+    Register {
+        /// Token id on the ledger, e.g. 0.0.12345
+        #[arg(long)]
+        token: Option<String>,
+        #[arg(long)]
+        asset_manager: Option<String>,
+        #[command(flatten)]
+        details: AssetDetails,
+    },
+    /// Look an asset up by id, token or asset manager
+    Get {
+        id: Option<Uuid>,
+        #[arg(long, conflicts_with = "id")]
+        token: Option<String>,
+        #[arg(long, conflicts_with_all = ["id", "token"])]
+        asset_manager: Option<String>,
+    },
+    /// Send a wallet tokens from an asset's manager
+    Airdrop {
+        asset: Option<Uuid>,
+        #[arg(long)]
+        wallet: Option<Uuid>,
+        /// In base units
+        #[arg(long)]
+        amount: Option<u64>,
+    },
+}
+
+pub async fn run(cmd: AssetsCommand, ctx: &CliContext, app_config: &AppConfig) -> Result<Value> {
+    let input = match cmd {
+        AssetsCommand::Create { details } => {
+            AssetBookProcessorInput::CreateNewAsset(CreateNewAssetInputArgs {
+                asset_type: ctx.choice(details.asset_type, "type", "Asset type", &ASSET_TYPES)?,
+                name: ctx.string(details.name, "name", "Name")?,
+                symbol: ctx.string(details.symbol, "symbol", "Symbol")?,
+                decimals: ctx.i64(details.decimals, "decimals", "Decimals")? as i32,
+                icon: ctx.string(details.icon, "icon", "Icon URL")?,
+            })
+        }
+        AssetsCommand::Register {
+            token,
+            asset_manager,
+            details,
+        } => AssetBookProcessorInput::CreateExistingAsset(CreateExistingAssetInputArgs {
+            token: ctx.string(token, "token", "Token id")?,
+            asset_manager: ctx.optional_string(asset_manager, "Asset manager")?,
+            asset_type: ctx.choice(details.asset_type, "type", "Asset type", &ASSET_TYPES)?,
+            name: ctx.string(details.name, "name", "Name")?,
+            symbol: ctx.string(details.symbol, "symbol", "Symbol")?,
+            decimals: ctx.i64(details.decimals, "decimals", "Decimals")? as i32,
+            icon: ctx.string(details.icon, "icon", "Icon URL")?,
+        }),
+        AssetsCommand::Get {
+            id,
+            token,
+            asset_manager,
+        } => {
+            let query = match (id, token, asset_manager) {
+                (Some(id), _, _) => GetAssetInputArgs::ById(id),
+                (_, Some(token), _) => GetAssetInputArgs::ByToken(token),
+                (_, _, Some(manager)) => GetAssetInputArgs::ByAssetManager(manager),
+                _ => match ctx.pick(
+                    "an id, --token or --asset-manager",
+                    "Look up by",
+                    vec!["ID", "Token", "Asset Manager"],
+                )? {
+                    0 => GetAssetInputArgs::ById(ctx.uuid(None, "<id>", "Asset ID")?),
+                    1 => GetAssetInputArgs::ByToken(ctx.string(None, "token", "Token id")?),
+                    _ => GetAssetInputArgs::ByAssetManager(ctx.string(
+                        None,
+                        "asset-manager",
+                        "Asset manager",
+                    )?),
+                },
+            };
+            AssetBookProcessorInput::GetAsset(query)
+        }
+        AssetsCommand::Airdrop {
+            asset,
+            wallet,
+            amount,
+        } => {
+            let asset = ctx.uuid(asset, "<asset>", "Asset ID")?;
+            let wallet = ctx.uuid(wallet, "wallet", "Wallet ID")?;
+            let amount = ctx.u64(amount, "amount", "Amount (base units)")?;
+            ctx.confirm(&format!(
+                "Airdrop {} of asset {} to wallet {}?",
+                amount, asset, wallet
+            ))?;
+
+            let mut conn = app_config.pool.get()?;
+            let transaction_id =
+                airdrop_asset(&mut conn, &app_config.wallet, asset, wallet, amount).await?;
+            return Ok(json!({ "transaction_id": transaction_id }));
+        }
+    };
+
+    dispatch(ActionRouterInput::AssetBook(input), app_config).await
+}
@@ -0,0 +1,209 @@
+use std::io::Write;
+use std::time::Duration;
+
+use anyhow::Result;
+use bigdecimal::{BigDecimal, RoundingMode};
+use clap::Subcommand;
+use diesel::prelude::*;
+use serde_json::Value;
+use uuid::Uuid;
+
+use cradle_back_end::cli_utils::formatting::{format_datetime, print_header, print_section};
+use cradle_back_end::cli_utils::{CliError, format_table, print_info};
+use cradle_back_end::market::db_types::MarketRecord;
+use cradle_back_end::order_book::depth::{BookSnapshot, DepthLevel, Side, book_snapshot};
+use cradle_back_end::sockets::cluster;
+use cradle_back_end::utils::app_config::AppConfig;
+
+use crate::context::CliContext;
+use crate::markets::market_arg;
+
+#[derive(Subcommand)]
+pub enum BookCommand {
+    /// Depth, best bid and ask, spread and recent trades of a market
+    Show {
+        /// Market id or symbol
+        market: Option<String>,
+        /// Price levels a side
+        #[arg(long, default_value_t = 10)]
+        levels: usize,
+        /// Recent trades to list
+        #[arg(long, default_value_t = 10)]
+        trades: i64,
+        /// Redraw whenever the market's orders or trades change, until
+        /// Ctrl-C
+        #[arg(long)]
+        watch: bool,
+        /// Seconds between redraws when there's no socket feed to follow
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+    },
+}
+
+pub async fn run(cmd: BookCommand, ctx: &CliContext, app_config: &AppConfig) -> Result<Value> {
+    match cmd {
+        BookCommand::Show {
+            market,
+            levels,
+            trades,
+            watch,
+            interval,
+        } => {
+            let market_id = market_arg(ctx, app_config, market)?;
+            let market = load_market(app_config, market_id)?;
+            if watch {
+                watch_book(ctx, app_config, &market, levels, trades, interval).await?;
+                return Ok(Value::Null);
+            }
+
+            let snapshot = book_snapshot(&mut app_config.pool.get()?, &market, levels, trades)?;
+            if ctx.json {
+                return Ok(serde_json::to_value(&snapshot)?);
+            }
+            render(&market, &snapshot);
+            Ok(Value::Null)
+        }
+    }
+}
+
+fn load_market(app_config: &AppConfig, market_id: Uuid) -> Result<MarketRecord> {
+    use cradle_back_end::schema::markets::dsl::*;
+
+    markets
+        .find(market_id)
+        .first::<MarketRecord>(&mut app_config.pool.get()?)
+        .optional()?
+        .ok_or_else(|| CliError::NotFound(format!("market {}", market_id)).into())
+}
+
+/// Redraw on every order book or trade event for the market. Without a
+/// cluster channel to follow, events never leave the API instance, so this
+/// falls back to polling.
+async fn watch_book(
+    ctx: &CliContext,
+    app_config: &AppConfig,
+    market: &MarketRecord,
+    levels: usize,
+    trades: i64,
+    interval: u64,
+) -> Result<()> {
+    let rooms = vec![
+        format!("orderbook:{}", market.id),
+        format!("trades:{}", market.id),
+    ];
+    let mut feed = cluster::follow(rooms).await?;
+    if feed.is_none() && !ctx.json {
+        print_info(&format!(
+            "SOCKET_CLUSTER_CHANNEL isn't set; refreshing every {}s",
+            interval
+        ));
+    }
+    let poll = Duration::from_secs(interval.max(1));
+
+    loop {
+        let snapshot = book_snapshot(&mut app_config.pool.get()?, market, levels, trades)?;
+        if ctx.json {
+            println!("{}", serde_json::to_string(&snapshot)?);
+        } else {
+            print!("\x1b[2J\x1b[H");
+            render(market, &snapshot);
+            std::io::stdout().flush()?;
+        }
+
+        let changed = async {
+            match feed.as_mut() {
+                Some(events) => {
+                    if events.recv().await.is_none() {
+                        // the subscription dropped; carry on polling
+                        feed = None;
+                        tokio::time::sleep(poll).await;
+                    }
+                    // let a burst of events land before redrawing
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    if let Some(events) = feed.as_mut() {
+                        while events.try_recv().is_ok() {}
+                    }
+                }
+                None => tokio::time::sleep(poll).await,
+            }
+        };
+        tokio::select! {
+            _ = changed => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+fn price(market: &MarketRecord, value: &BigDecimal) -> String {
+    value
+        .with_scale_round(market.price_precision as i64, RoundingMode::HalfUp)
+        .to_string()
+}
+
+fn level_rows(market: &MarketRecord, levels: &[DepthLevel]) -> Vec<Vec<String>> {
+    let mut total = BigDecimal::from(0);
+    levels
+        .iter()
+        .map(|level| {
+            total += &level.quantity;
+            vec![
+                price(market, &level.price),
+                level.quantity.normalized().to_string(),
+                total.normalized().to_string(),
+                level.orders.to_string(),
+            ]
+        })
+        .collect()
+}
+
+fn render(market: &MarketRecord, snapshot: &BookSnapshot) {
+    print_header(&format!(
+        "{} ({}) at {}",
+        market.display_symbol,
+        market.symbol,
+        format_datetime(&snapshot.taken_at)
+    ));
+
+    let show = |value: &Option<BigDecimal>| {
+        value
+            .as_ref()
+            .map(|v| price(market, v))
+            .unwrap_or_else(|| "-".to_string())
+    };
+    println!(
+        "Best bid {}   Best ask {}   Spread {}",
+        show(&snapshot.best_bid),
+        show(&snapshot.best_ask),
+        show(&snapshot.spread)
+    );
+
+    let headers = vec!["Price", "Quantity", "Total", "Orders"];
+    print_section("Asks");
+    // furthest first, so the best ask sits next to the bids
+    let mut asks = level_rows(market, &snapshot.asks);
+    asks.reverse();
+    format_table(headers.clone(), asks);
+    print_section("Bids");
+    format_table(headers, level_rows(market, &snapshot.bids));
+
+    print_section("Recent trades");
+    format_table(
+        vec!["Time", "Side", "Price", "Quantity", "Settlement"],
+        snapshot
+            .trades
+            .iter()
+            .map(|trade| {
+                vec![
+                    format_datetime(&trade.created_at),
+                    match trade.taker_side {
+                        Side::Buy => "buy".to_string(),
+                        Side::Sell => "sell".to_string(),
+                    },
+                    price(market, &trade.price),
+                    trade.quantity.normalized().to_string(),
+                    format!("{:?}", trade.settlement_status),
+                ]
+            })
+            .collect(),
+    );
+}
@@ -0,0 +1,324 @@
+use std::io::IsTerminal;
+use std::process::ExitCode;
+
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use cradle_back_end::accounts::db_types::AdminOperatorRecord;
+use cradle_back_end::accounts::operators::authenticate;
+use cradle_back_end::api::error::ApiError;
+use cradle_back_end::cli_utils::input::{Input, parse_enum};
+use cradle_back_end::cli_utils::{CliError, format_json, print_error, print_success};
+use cradle_back_end::utils::app_config::AppConfig;
+
+/// How a run of the CLI talks to whoever started it. Values given as flags
+/// are used as they are; anything missing is prompted for when there's a
+/// terminal to prompt on, and is an error otherwise.
+#[derive(Clone, Copy)]
+pub struct CliContext {
+    pub json: bool,
+    pub yes: bool,
+    interactive: bool,
+}
+
+/// How an argument is named in messages: "<market>" for positionals,
+/// "--status" for flags.
+fn arg_name(flag: &str) -> String {
+    if flag.starts_with('<') {
+        flag.to_string()
+    } else {
+        format!("--{}", flag)
+    }
+}
+
+fn invalid(flag: &str, value: &str) -> anyhow::Error {
+    CliError::ValidationError(format!("Invalid {}: {}", arg_name(flag), value)).into()
+}
+
+impl CliContext {
+    /// `--json` and `--yes` both mean nobody is there to answer prompts.
+    pub fn new(json: bool, yes: bool) -> Self {
+        Self {
+            json,
+            yes,
+            interactive: !json && !yes && std::io::stdin().is_terminal(),
+        }
+    }
+
+    pub fn is_interactive(&self) -> bool {
+        self.interactive
+    }
+
+    fn missing(&self, flag: &str) -> anyhow::Error {
+        CliError::ValidationError(format!("{} is required", arg_name(flag))).into()
+    }
+
+    pub fn string(&self, given: Option<String>, flag: &str, prompt: &str) -> Result<String> {
+        match given {
+            Some(value) => Ok(value),
+            None if self.interactive => Ok(Input::get_string(prompt)?),
+            None => Err(self.missing(flag)),
+        }
+    }
+
+    /// Left unset when not given and nobody is there to ask.
+    pub fn optional_string(&self, given: Option<String>, prompt: &str) -> Result<Option<String>> {
+        match given {
+            Some(value) => Ok(Some(value)),
+            None if self.interactive => Ok(Input::get_optional_string(prompt)?),
+            None => Ok(None),
+        }
+    }
+
+    pub fn uuid(&self, given: Option<Uuid>, flag: &str, prompt: &str) -> Result<Uuid> {
+        match given {
+            Some(value) => Ok(value),
+            None if self.interactive => Ok(Input::get_uuid(prompt)?),
+            None => Err(self.missing(flag)),
+        }
+    }
+
+    pub fn optional_uuid(&self, given: Option<Uuid>, prompt: &str) -> Result<Option<Uuid>> {
+        match given {
+            Some(value) => Ok(Some(value)),
+            None if self.interactive => Ok(Input::get_optional_uuid(prompt)?),
+            None => Ok(None),
+        }
+    }
+
+    pub fn decimal(
+        &self,
+        given: Option<BigDecimal>,
+        flag: &str,
+        prompt: &str,
+    ) -> Result<BigDecimal> {
+        match given {
+            Some(value) => Ok(value),
+            None if self.interactive => Ok(Input::get_decimal(prompt)?),
+            None => Err(self.missing(flag)),
+        }
+    }
+
+    pub fn optional_decimal(
+        &self,
+        given: Option<BigDecimal>,
+        prompt: &str,
+    ) -> Result<Option<BigDecimal>> {
+        match given {
+            Some(value) => Ok(Some(value)),
+            None if self.interactive => Ok(Input::get_optional_decimal(prompt)?),
+            None => Ok(None),
+        }
+    }
+
+    pub fn u64(&self, given: Option<u64>, flag: &str, prompt: &str) -> Result<u64> {
+        match given {
+            Some(value) => Ok(value),
+            None if self.interactive => Ok(Input::get_u64(prompt)?),
+            None => Err(self.missing(flag)),
+        }
+    }
+
+    pub fn optional_u64(&self, given: Option<u64>, prompt: &str) -> Result<Option<u64>> {
+        match given {
+            Some(value) => Ok(Some(value)),
+            None if self.interactive => match Input::get_optional_string(prompt)? {
+                Some(raw) => Ok(Some(raw.trim().parse::<u64>().map_err(|_| {
+                    CliError::ValidationError(format!("Invalid amount: {}", raw))
+                })?)),
+                None => Ok(None),
+            },
+            None => Ok(None),
+        }
+    }
+
+    pub fn i64(&self, given: Option<i64>, flag: &str, prompt: &str) -> Result<i64> {
+        match given {
+            Some(value) => Ok(value),
+            None if self.interactive => Ok(Input::get_i64(prompt)?),
+            None => Err(self.missing(flag)),
+        }
+    }
+
+    pub fn optional_i64(&self, given: Option<i64>, prompt: &str) -> Result<Option<i64>> {
+        match given {
+            Some(value) => Ok(Some(value)),
+            None if self.interactive => Ok(Input::get_optional_i64(prompt)?),
+            None => Ok(None),
+        }
+    }
+
+    /// One of an enum's serialized names, e.g. "spot".
+    pub fn choice<T: DeserializeOwned>(
+        &self,
+        given: Option<String>,
+        flag: &str,
+        prompt: &str,
+        names: &[&str],
+    ) -> Result<T> {
+        match given {
+            Some(value) => parse_enum(&value).map_err(|_| invalid(flag, &value)),
+            None if self.interactive => Ok(Input::select_enum(prompt, names)?),
+            None => Err(self.missing(flag)),
+        }
+    }
+
+    /// Left to the processor's default when not given and nobody is there
+    /// to ask.
+    pub fn optional_choice<T: DeserializeOwned>(
+        &self,
+        given: Option<String>,
+        flag: &str,
+        prompt: &str,
+        names: &[&str],
+    ) -> Result<Option<T>> {
+        match given {
+            Some(_) => self.choice(given, flag, prompt, names).map(Some),
+            None if self.interactive => Ok(Some(Input::select_enum(prompt, names)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// A filter value; "all" or nothing means no filter.
+    pub fn filter_choice<T: DeserializeOwned>(
+        &self,
+        given: Option<String>,
+        flag: &str,
+        prompt: &str,
+        names: &[&str],
+    ) -> Result<Option<T>> {
+        let picked = match given {
+            Some(value) => value,
+            None if self.interactive => {
+                let mut options = vec!["all"];
+                options.extend_from_slice(names);
+                options[Input::select_from_list(prompt, options.clone())?].to_string()
+            }
+            None => return Ok(None),
+        };
+        if picked == "all" {
+            return Ok(None);
+        }
+        parse_enum(&picked)
+            .map(Some)
+            .map_err(|_| invalid(flag, &picked))
+    }
+
+    /// Which of several ways to identify something to use, when none was
+    /// given as a flag.
+    pub fn pick(&self, flags: &str, prompt: &str, options: Vec<&str>) -> Result<usize> {
+        if self.interactive {
+            Ok(Input::select_from_list(prompt, options)?)
+        } else {
+            Err(CliError::ValidationError(format!("One of {} is required", flags)).into())
+        }
+    }
+
+    /// The admin operator to act as, signed in with the password in
+    /// CRADLE_OPERATOR_PASSWORD or one typed at the prompt.
+    pub fn operator(
+        &self,
+        given: Option<String>,
+        app_config: &AppConfig,
+    ) -> Result<AdminOperatorRecord> {
+        let username = self.string(given, "operator", "Operator username")?;
+        let password = match std::env::var("CRADLE_OPERATOR_PASSWORD") {
+            Ok(password) => password,
+            Err(_) if self.interactive => dialoguer::Password::new()
+                .with_prompt("Password")
+                .interact()?,
+            Err(_) => {
+                return Err(CliError::ValidationError(
+                    "Set CRADLE_OPERATOR_PASSWORD to act as --operator".to_string(),
+                )
+                .into());
+            }
+        };
+        let mut conn = app_config.pool.get()?;
+        authenticate(&mut conn, &username, &password)
+    }
+
+    /// Go ahead with something destructive: `--yes` or an answer at the
+    /// terminal.
+    pub fn confirm(&self, message: &str) -> Result<()> {
+        let confirmed = if self.yes {
+            true
+        } else if self.interactive {
+            cradle_back_end::cli_utils::confirm(message)?
+        } else {
+            return Err(
+                CliError::ValidationError(format!("{} Pass --yes to confirm", message)).into(),
+            );
+        };
+        if !confirmed {
+            return Err(CliError::UserCancelled.into());
+        }
+        Ok(())
+    }
+
+    /// Print what a command returned: the bare value, or the API's response
+    /// shape with `--json`.
+    pub fn print_result(&self, value: &Value) {
+        if self.json {
+            println!(
+                "{}",
+                json!({ "success": true, "data": value, "error": null })
+            );
+            return;
+        }
+        print_success("Done");
+        if !value.is_null() {
+            println!("{}", format_json(value));
+        }
+    }
+
+    pub fn print_failure(&self, e: &anyhow::Error) {
+        let code = failure_code(e);
+        if self.json {
+            println!(
+                "{}",
+                json!({ "success": false, "data": null, "error": format!("{:#}", e), "code": code })
+            );
+            return;
+        }
+        print_error(&format!("{:#}", e));
+        if let Some(hint) = ApiError::from_action_error("cli", e).hint() {
+            eprintln!("  {}", hint);
+        }
+    }
+}
+
+/// Stable name for a failure, the same `code` the API would send.
+pub fn failure_code(e: &anyhow::Error) -> &'static str {
+    match e.downcast_ref::<CliError>() {
+        Some(CliError::ValidationError(_)) => "bad_request",
+        Some(CliError::NotFound(_)) => "not_found",
+        Some(CliError::UserCancelled) => "cancelled",
+        _ => ApiError::from_action_error("cli", e).code(),
+    }
+}
+
+/// Exit codes scripts can branch on:
+/// 0 done, 1 failed, 2 bad or missing arguments, 3 not found,
+/// 4 refused (a known rejection, e.g. market_closed), 5 database
+/// unavailable, 6 not confirmed.
+pub fn exit_code(e: &anyhow::Error) -> ExitCode {
+    let code = match e.downcast_ref::<CliError>() {
+        Some(CliError::ValidationError(_)) => 2,
+        Some(CliError::NotFound(_)) => 3,
+        Some(CliError::UserCancelled) => 6,
+        Some(CliError::DatabaseError(_)) => 5,
+        Some(CliError::IoError(_)) => 1,
+        None => match ApiError::from_action_error("cli", e) {
+            ApiError::BadRequest(_) => 2,
+            ApiError::NotFound(_) => 3,
+            ApiError::Rejected { .. } => 4,
+            ApiError::DatabaseError(_) => 5,
+            ApiError::Unauthorized(_) | ApiError::InternalError(_) => 1,
+        },
+    };
+    ExitCode::from(code)
+}
@@ -0,0 +1,493 @@
+//! `cradle db`: diesel's migrations, run from the CLI with a lock so two
+//! deploys can't migrate at once, and a look at what a migration drops
+//! before it runs against tables that already hold data.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use clap::{Args, Subcommand};
+use diesel::migration::{Migration, MigrationSource};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Bool, Text};
+use diesel_migrations::{FileBasedMigrations, MigrationHarness};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use cradle_back_end::cli_utils::{CliError, print_info, print_warning};
+
+use crate::context::CliContext;
+
+/// Advisory lock key held while migrations run ("cradle" in ASCII).
+const MIGRATION_LOCK_KEY: i64 = 0x6372_6164_6c65;
+
+#[derive(Args)]
+pub struct DbArgs {
+    /// Migrations directory
+    #[arg(long, default_value = "migrations", global = true)]
+    pub dir: PathBuf,
+    #[command(subcommand)]
+    pub command: DbCommand,
+}
+
+#[derive(Subcommand)]
+pub enum DbCommand {
+    /// Apply pending migrations
+    Migrate {
+        /// Show what would run, and what it drops, without running it
+        #[arg(long)]
+        dry_run: bool,
+        /// Wait for another run's lock instead of giving up
+        #[arg(long)]
+        wait: bool,
+    },
+    /// Revert the most recently applied migrations
+    Rollback {
+        #[arg(long, default_value_t = 1)]
+        steps: usize,
+        #[arg(long)]
+        dry_run: bool,
+        #[arg(long)]
+        wait: bool,
+    },
+    /// List migrations and whether each is applied
+    Status,
+}
+
+/// A statement that can lose data, and whether its table has any to lose.
+#[derive(Serialize)]
+pub struct DestructiveChange {
+    pub migration: String,
+    pub table: String,
+    pub statement: String,
+    pub populated: bool,
+}
+
+#[derive(QueryableByName)]
+struct Flag {
+    #[diesel(sql_type = Bool)]
+    value: bool,
+}
+
+pub fn run(args: DbArgs, ctx: &CliContext) -> Result<Value> {
+    let _ = dotenvy::dotenv();
+    let url = std::env::var("DATABASE_URL")
+        .map_err(|_| CliError::ValidationError("DATABASE_URL is not set".to_string()))?;
+    let conn =
+        &mut PgConnection::establish(&url).map_err(|e| CliError::DatabaseError(e.to_string()))?;
+
+    match args.command {
+        DbCommand::Migrate { dry_run, wait } => migrate(conn, ctx, &args.dir, dry_run, wait),
+        DbCommand::Rollback {
+            steps,
+            dry_run,
+            wait,
+        } => rollback(conn, ctx, &args.dir, steps, dry_run, wait),
+        DbCommand::Status => status(conn, &args.dir),
+    }
+}
+
+fn source(dir: &Path) -> Result<FileBasedMigrations> {
+    FileBasedMigrations::from_path(dir)
+        .map_err(|e| anyhow!("No migrations at {}: {}", dir.display(), e))
+}
+
+fn migrate(
+    conn: &mut PgConnection,
+    ctx: &CliContext,
+    dir: &Path,
+    dry_run: bool,
+    wait: bool,
+) -> Result<Value> {
+    let pending = pending_names(conn, dir)?;
+    let changes = preflight(conn, dir, &pending, "up.sql")?;
+    if dry_run || pending.is_empty() {
+        return Ok(json!({ "dry_run": dry_run, "pending": pending, "destructive": changes }));
+    }
+
+    with_lock(conn, wait, |conn| {
+        // Another run may have finished while we waited for the lock.
+        let pending = conn
+            .pending_migrations(source(dir)?)
+            .map_err(|e| anyhow!(e))?;
+        if pending.is_empty() {
+            return Ok(json!({ "applied": Vec::<String>::new() }));
+        }
+        confirm_run(ctx, "Apply", pending.len(), &changes)?;
+
+        let mut applied = Vec::new();
+        for migration in &pending {
+            let name = migration.name().to_string();
+            if !ctx.json {
+                print_info(&format!("Applying {}", name));
+            }
+            conn.run_migration(&**migration)
+                .map_err(|e| anyhow!("{} failed: {}", name, e))
+                .with_context(|| applied_so_far("Applied", &applied))?;
+            applied.push(name);
+        }
+        Ok(json!({ "applied": applied }))
+    })
+}
+
+fn rollback(
+    conn: &mut PgConnection,
+    ctx: &CliContext,
+    dir: &Path,
+    steps: usize,
+    dry_run: bool,
+    wait: bool,
+) -> Result<Value> {
+    let targets = rollback_targets(conn, dir, steps)?;
+    let names: Vec<String> = targets.iter().map(|m| m.name().to_string()).collect();
+    let changes = preflight(conn, dir, &names, "down.sql")?;
+    if dry_run || targets.is_empty() {
+        return Ok(json!({ "dry_run": dry_run, "revert": names, "destructive": changes }));
+    }
+
+    with_lock(conn, wait, |conn| {
+        let targets = rollback_targets(conn, dir, steps)?;
+        confirm_run(ctx, "Revert", targets.len(), &changes)?;
+
+        let mut reverted = Vec::new();
+        for migration in &targets {
+            let name = migration.name().to_string();
+            if !ctx.json {
+                print_info(&format!("Reverting {}", name));
+            }
+            conn.revert_migration(&**migration)
+                .map_err(|e| anyhow!("{} failed: {}", name, e))
+                .with_context(|| applied_so_far("Reverted", &reverted))?;
+            reverted.push(name);
+        }
+        Ok(json!({ "reverted": reverted }))
+    })
+}
+
+fn status(conn: &mut PgConnection, dir: &Path) -> Result<Value> {
+    let applied: HashSet<String> = conn
+        .applied_migrations()
+        .map_err(|e| anyhow!(e))?
+        .iter()
+        .map(|v| v.to_string())
+        .collect();
+    let migrations = MigrationSource::<Pg>::migrations(&source(dir)?).map_err(|e| anyhow!(e))?;
+
+    let on_disk: HashSet<String> = migrations
+        .iter()
+        .map(|m| m.name().version().to_string())
+        .collect();
+    let rows: Vec<Value> = migrations
+        .iter()
+        .map(|m| {
+            json!({
+                "name": m.name().to_string(),
+                "applied": applied.contains(&m.name().version().to_string()),
+            })
+        })
+        .collect();
+    let mut unknown: Vec<&String> = applied.difference(&on_disk).collect();
+    unknown.sort();
+
+    Ok(json!({ "migrations": rows, "applied_not_on_disk": unknown }))
+}
+
+pub fn pending_names(conn: &mut PgConnection, dir: &Path) -> Result<Vec<String>> {
+    Ok(conn
+        .pending_migrations(source(dir)?)
+        .map_err(|e| anyhow!(e))?
+        .iter()
+        .map(|m| m.name().to_string())
+        .collect())
+}
+
+/// The last `steps` applied migrations, newest first.
+fn rollback_targets(
+    conn: &mut PgConnection,
+    dir: &Path,
+    steps: usize,
+) -> Result<Vec<Box<dyn Migration<Pg>>>> {
+    let mut applied: Vec<String> = conn
+        .applied_migrations()
+        .map_err(|e| anyhow!(e))?
+        .iter()
+        .map(|v| v.to_string())
+        .collect();
+    applied.sort_by(|a, b| b.cmp(a));
+    applied.truncate(steps);
+
+    let mut migrations =
+        MigrationSource::<Pg>::migrations(&source(dir)?).map_err(|e| anyhow!(e))?;
+    applied
+        .iter()
+        .map(|version| {
+            let position = migrations
+                .iter()
+                .position(|m| m.name().version().to_string() == *version)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Migration {} is applied but not in {}",
+                        version,
+                        dir.display()
+                    )
+                })?;
+            Ok(migrations.swap_remove(position))
+        })
+        .collect()
+}
+
+fn applied_so_far(verb: &str, names: &[String]) -> String {
+    if names.is_empty() {
+        "Nothing was changed".to_string()
+    } else {
+        format!("{} before the failure: {}", verb, names.join(", "))
+    }
+}
+
+fn confirm_run(
+    ctx: &CliContext,
+    verb: &str,
+    count: usize,
+    changes: &[DestructiveChange],
+) -> Result<()> {
+    let mut populated: Vec<&str> = changes
+        .iter()
+        .filter(|c| c.populated)
+        .map(|c| c.table.as_str())
+        .collect();
+    populated.sort();
+    populated.dedup();
+
+    if populated.is_empty() {
+        return ctx.confirm(&format!("{} {} migration(s)?", verb, count));
+    }
+    if !ctx.json {
+        for change in changes.iter().filter(|c| c.populated) {
+            print_warning(&format!("{}: {}", change.migration, change.statement));
+        }
+    }
+    ctx.confirm(&format!(
+        "{} {} migration(s)? This drops or rewrites data in {}, which hold rows.",
+        verb,
+        count,
+        populated.join(", ")
+    ))
+}
+
+/// Hold the migration lock for the length of `f`. Advisory locks belong to
+/// the session, so a crashed run can't leave one behind.
+fn with_lock<T>(
+    conn: &mut PgConnection,
+    wait: bool,
+    f: impl FnOnce(&mut PgConnection) -> Result<T>,
+) -> Result<T> {
+    if wait {
+        diesel::sql_query("SELECT pg_advisory_lock($1)")
+            .bind::<BigInt, _>(MIGRATION_LOCK_KEY)
+            .execute(conn)?;
+    } else {
+        let locked = diesel::sql_query("SELECT pg_try_advisory_lock($1) AS value")
+            .bind::<BigInt, _>(MIGRATION_LOCK_KEY)
+            .get_result::<Flag>(conn)?
+            .value;
+        if !locked {
+            return Err(anyhow!(
+                "Another migration run holds the lock; pass --wait to wait for it"
+            ));
+        }
+    }
+
+    let result = f(conn);
+    let unlocked = diesel::sql_query("SELECT pg_advisory_unlock($1) AS value")
+        .bind::<BigInt, _>(MIGRATION_LOCK_KEY)
+        .get_result::<Flag>(conn);
+    let value = result?;
+    unlocked?;
+    Ok(value)
+}
+
+/// Statements in the given migrations' `file` that drop or rewrite data,
+/// with whether each table has rows right now.
+fn preflight(
+    conn: &mut PgConnection,
+    dir: &Path,
+    names: &[String],
+    file: &str,
+) -> Result<Vec<DestructiveChange>> {
+    let mut changes = Vec::new();
+    for name in names {
+        let path = dir.join(name).join(file);
+        let sql = std::fs::read_to_string(&path)
+            .with_context(|| format!("Couldn't read {}", path.display()))?;
+        for (table, statement) in destructive_statements(&sql) {
+            changes.push(DestructiveChange {
+                migration: name.clone(),
+                populated: has_rows(conn, &table)?,
+                table,
+                statement,
+            });
+        }
+    }
+    Ok(changes)
+}
+
+fn has_rows(conn: &mut PgConnection, table: &str) -> Result<bool> {
+    let exists = diesel::sql_query("SELECT to_regclass($1) IS NOT NULL AS value")
+        .bind::<Text, _>(table)
+        .get_result::<Flag>(conn)?
+        .value;
+    if !exists {
+        return Ok(false);
+    }
+    let quoted = table
+        .split('.')
+        .map(|part| format!("\"{}\"", part))
+        .collect::<Vec<_>>()
+        .join(".");
+    Ok(
+        diesel::sql_query(format!("SELECT EXISTS (SELECT 1 FROM {}) AS value", quoted))
+            .get_result::<Flag>(conn)?
+            .value,
+    )
+}
+
+/// `(table, statement)` for each DROP TABLE, TRUNCATE, DELETE, DROP COLUMN
+/// and column type change in `sql`.
+fn destructive_statements(sql: &str) -> Vec<(String, String)> {
+    let without_comments: String = sql
+        .lines()
+        .map(|line| line.split("--").next().unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut found = Vec::new();
+    for statement in without_comments.split(';') {
+        let statement = statement.split_whitespace().collect::<Vec<_>>().join(" ");
+        let lower = statement.to_lowercase();
+
+        let tables = if let Some(rest) = lower.strip_prefix("drop table ") {
+            table_list(rest)
+        } else if let Some(rest) = lower.strip_prefix("truncate ") {
+            table_list(rest.strip_prefix("table ").unwrap_or(rest))
+        } else if let Some(rest) = lower.strip_prefix("delete from ") {
+            table_list(rest).into_iter().take(1).collect()
+        } else if let Some(rest) = lower.strip_prefix("alter table ") {
+            let drops = lower.contains(" drop column ");
+            let retypes = lower.contains(" alter column ") && lower.contains(" type ");
+            if drops || retypes {
+                table_list(rest).into_iter().take(1).collect()
+            } else {
+                Vec::new()
+            }
+        } else {
+            Vec::new()
+        };
+
+        for table in tables {
+            found.push((table, statement.clone()));
+        }
+    }
+    found
+}
+
+/// Table names at the start of `rest`, skipping IF EXISTS / ONLY and
+/// stopping at the first word that isn't part of the list.
+fn table_list(rest: &str) -> Vec<String> {
+    let mut rest = rest.trim_start();
+    for keyword in ["if exists ", "only "] {
+        rest = rest.strip_prefix(keyword).unwrap_or(rest);
+    }
+
+    let mut tables = Vec::new();
+    for word in rest.split_whitespace() {
+        let continues = word.ends_with(',');
+        for name in word.split(',').filter(|n| !n.is_empty()) {
+            tables.push(name.trim_matches('"').to_string());
+        }
+        if !continues {
+            break;
+        }
+    }
+    tables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tables(sql: &str) -> Vec<String> {
+        destructive_statements(sql)
+            .into_iter()
+            .map(|(table, _)| table)
+            .collect()
+    }
+
+    #[test]
+    fn finds_dropped_and_truncated_tables() {
+        assert_eq!(tables("DROP TABLE orders;"), vec!["orders"]);
+        assert_eq!(
+            tables("drop table if exists \"Orders\" cascade;"),
+            vec!["orders"]
+        );
+        assert_eq!(tables("TRUNCATE trades;"), vec!["trades"]);
+        assert_eq!(tables("TRUNCATE TABLE ONLY trades;"), vec!["trades"]);
+    }
+
+    #[test]
+    fn lists_every_table_in_a_multi_table_statement() {
+        assert_eq!(
+            tables("DROP TABLE orders, trades CASCADE;"),
+            vec!["orders", "trades"]
+        );
+        assert_eq!(
+            tables("TRUNCATE TABLE orders,trades,fills RESTART IDENTITY;"),
+            vec!["orders", "trades", "fills"]
+        );
+    }
+
+    #[test]
+    fn finds_column_type_changes_but_not_additions() {
+        assert_eq!(
+            tables("ALTER TABLE orders ALTER COLUMN amount TYPE NUMERIC;"),
+            vec!["orders"]
+        );
+        assert_eq!(
+            tables("ALTER TABLE orders\n  ALTER COLUMN amount\n  TYPE NUMERIC;"),
+            vec!["orders"]
+        );
+        assert_eq!(
+            tables("ALTER TABLE orders DROP COLUMN memo;"),
+            vec!["orders"]
+        );
+        assert!(tables("ALTER TABLE orders ADD COLUMN memo TEXT;").is_empty());
+        assert!(tables("ALTER TABLE orders ALTER COLUMN memo SET DEFAULT '';").is_empty());
+    }
+
+    #[test]
+    fn ignores_commented_out_statements() {
+        let sql = "-- DROP TABLE orders;\nCREATE TABLE fills (id UUID); -- TRUNCATE fills;\n";
+        assert!(tables(sql).is_empty());
+        assert_eq!(
+            tables("-- clean up\nDROP TABLE -- the old one\n  orders;"),
+            vec!["orders"]
+        );
+    }
+
+    #[test]
+    fn keeps_each_statement_with_its_table() {
+        let found = destructive_statements(
+            "CREATE TABLE a (id INT);\nDROP TABLE  orders;\nDELETE FROM fills WHERE id = 1;",
+        );
+        assert_eq!(
+            found,
+            vec![
+                ("orders".to_string(), "DROP TABLE orders".to_string()),
+                (
+                    "fills".to_string(),
+                    "DELETE FROM fills WHERE id = 1".to_string()
+                ),
+            ]
+        );
+    }
+}
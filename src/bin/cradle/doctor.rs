@@ -0,0 +1,319 @@
+//! `cradle doctor`: check a new environment's configuration before starting
+//! the server in it, one pass/warn/fail line per check with what to do about
+//! each problem.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use contract_integrator::wallet::wallet::ActionWallet;
+use diesel::prelude::*;
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use cradle_back_end::cli_utils::{print_error, print_success, print_warning};
+use cradle_back_end::health::hedera_health;
+use cradle_back_end::ramper::Ramper;
+use cradle_back_end::utils::cache::init_redis;
+
+use crate::context::CliContext;
+use crate::db::pending_names;
+
+/// How long the database and Redis get to answer.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+    /// Not run, e.g. because a check it depends on failed
+    Skip,
+}
+
+#[derive(Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn skip(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Skip,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+}
+
+fn env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
+pub async fn run(ctx: &CliContext) -> Result<Value> {
+    let dotenv = match dotenvy::dotenv() {
+        Ok(path) => format!("loaded {}", path.display()),
+        Err(_) => "no .env file; using the process environment".to_string(),
+    };
+
+    let mut checks = vec![Check::pass("Environment", dotenv)];
+    checks.push(match env("API_SECRET_KEY") {
+        Some(_) => Check::pass("API secret", "API_SECRET_KEY is set"),
+        None => Check::fail(
+            "API secret",
+            "API_SECRET_KEY is not set, so the server would accept the default key",
+            "Set API_SECRET_KEY to the secret shared with the frontend server",
+        ),
+    });
+
+    checks.extend(database_checks());
+
+    let wallet = load_wallet();
+    match &wallet {
+        Ok(_) => checks.push(Check::pass("Wallet", "loaded from the environment")),
+        Err(e) => checks.push(Check::fail(
+            "Wallet",
+            e.to_string(),
+            "Check the operator account id, private key and network variables the contract integrator reads",
+        )),
+    }
+    checks.push(match &wallet {
+        Ok(wallet) => {
+            let health = hedera_health(wallet).await;
+            match (health.reachable, health.latency_ms, health.error) {
+                (true, latency, _) => Check::pass(
+                    "Hedera network",
+                    format!(
+                        "read the access controller's balances in {}ms",
+                        latency.unwrap_or_default()
+                    ),
+                ),
+                (false, _, error) => Check::fail(
+                    "Hedera network",
+                    error.unwrap_or_default(),
+                    "Check the network the wallet points at is reachable from here and the operator account exists on it",
+                ),
+            }
+        }
+        Err(_) => Check::skip("Hedera network", "needs a wallet"),
+    });
+
+    checks.push(ramper_check());
+    checks.extend(socket_checks().await);
+
+    let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+    let warned = checks.iter().filter(|c| c.status == Status::Warn).count();
+    if !ctx.json {
+        for check in &checks {
+            let line = format!("{:<16} {}", check.name, check.detail);
+            match check.status {
+                Status::Pass => print_success(&line),
+                Status::Warn => print_warning(&line),
+                Status::Fail => print_error(&line),
+                Status::Skip => eprintln!("- {}", line),
+            }
+            if let Some(hint) = &check.hint {
+                eprintln!("  {:<16} {}", "", hint);
+            }
+        }
+        eprintln!();
+    }
+
+    if failed > 0 {
+        return Err(anyhow!(
+            "{} check(s) failed, {} warning(s); fix the failures before starting the server",
+            failed,
+            warned
+        ));
+    }
+    Ok(json!({ "checks": checks, "warnings": warned }))
+}
+
+/// Connect, then compare applied migrations with `migrations/`.
+fn database_checks() -> Vec<Check> {
+    let Some(url) = env("DATABASE_URL") else {
+        return vec![
+            Check::fail(
+                "Database",
+                "DATABASE_URL is not set",
+                "Set DATABASE_URL to a postgres:// connection string",
+            ),
+            Check::skip("Migrations", "needs the database"),
+        ];
+    };
+
+    let started = Instant::now();
+    let conn = diesel::r2d2::Pool::builder()
+        .max_size(1)
+        .connection_timeout(CONNECT_TIMEOUT)
+        .build(diesel::r2d2::ConnectionManager::<PgConnection>::new(url))
+        .map_err(|e| e.to_string())
+        .and_then(|pool| pool.get().map_err(|e| e.to_string()));
+    let mut conn = match conn {
+        Ok(conn) => conn,
+        Err(e) => {
+            return vec![
+                Check::fail(
+                    "Database",
+                    e,
+                    "Check the host, credentials and database name in DATABASE_URL, and that Postgres accepts connections from here",
+                ),
+                Check::skip("Migrations", "needs the database"),
+            ];
+        }
+    };
+    let database = Check::pass(
+        "Database",
+        format!("connected in {}ms", started.elapsed().as_millis()),
+    );
+
+    let dir = Path::new("migrations");
+    let migrations = if !dir.is_dir() {
+        Check::skip("Migrations", "no migrations/ directory here")
+    } else {
+        match pending_names(&mut conn, dir) {
+            Ok(pending) if pending.is_empty() => Check::pass("Migrations", "up to date"),
+            Ok(pending) => Check::warn(
+                "Migrations",
+                format!("{} pending, from {}", pending.len(), pending[0]),
+                "Run cradle db migrate",
+            ),
+            Err(e) => Check::fail(
+                "Migrations",
+                e.to_string(),
+                "Run cradle db status to see which migrations differ",
+            ),
+        }
+    };
+
+    vec![database, migrations]
+}
+
+/// The integrator panics on missing or malformed variables, so catch that
+/// and report it instead.
+fn load_wallet() -> Result<ActionWallet> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let wallet = panic::catch_unwind(AssertUnwindSafe(ActionWallet::from_env));
+    panic::set_hook(hook);
+
+    wallet.map_err(|panic| {
+        let message = panic
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| panic.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap_or_else(|| "couldn't load the wallet".to_string());
+        anyhow!(message)
+    })
+}
+
+/// Ramps are optional, but a configured one has to be valid, the same rule
+/// the server applies at startup.
+fn ramper_check() -> Check {
+    if env("RAMPER_TOKEN").is_none() {
+        return Check::warn(
+            "Ramper",
+            "RAMPER_TOKEN is not set; on- and off-ramping will be unavailable",
+            "Set the RAMPER_* variables if this environment takes payments",
+        );
+    }
+    match Ramper::from_env().and_then(|ramper| {
+        ramper.validate()?;
+        Ok(ramper)
+    }) {
+        Ok(ramper) => Check::pass(
+            "Ramper",
+            format!(
+                "{} configured for {:?}",
+                ramper.ramper_provider, ramper.ramper_environment
+            ),
+        ),
+        Err(e) => Check::fail(
+            "Ramper",
+            e.to_string(),
+            "Fix the RAMPER_* variables; the server refuses to start with an invalid ramp",
+        ),
+    }
+}
+
+/// Redis backs the cache and, when SOCKET_CLUSTER_CHANNEL is set, socket
+/// fan-out between replicas, which can't work without it.
+async fn socket_checks() -> Vec<Check> {
+    let channel = env("SOCKET_CLUSTER_CHANNEL");
+    let started = Instant::now();
+    let redis = match tokio::time::timeout(CONNECT_TIMEOUT, init_redis()).await {
+        Ok(Ok(_)) => Ok(started.elapsed()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("no response within {}s", CONNECT_TIMEOUT.as_secs())),
+    };
+
+    let redis = match (redis, &channel) {
+        (Ok(elapsed), _) => Check::pass("Redis", format!("connected in {}ms", elapsed.as_millis())),
+        (Err(e), Some(_)) => Check::fail(
+            "Redis",
+            e,
+            "Set REDIS_URL to a reachable Redis, or unset SOCKET_CLUSTER_CHANNEL to run a single instance",
+        ),
+        (Err(e), None) => Check::warn(
+            "Redis",
+            e,
+            "The server runs without a cache; set REDIS_URL to use one",
+        ),
+    };
+
+    let sockets = match &channel {
+        Some(channel) => Check::pass(
+            "Sockets",
+            format!("events fan out between replicas on {}", channel),
+        ),
+        None => Check::pass(
+            "Sockets",
+            "single instance; set SOCKET_CLUSTER_CHANNEL when running more than one",
+        ),
+    };
+
+    let admin_feed = match env("ADMIN_SOCKET_KEY") {
+        Some(_) => Check::pass("Admin feed", "ADMIN_SOCKET_KEY is set"),
+        None => Check::warn(
+            "Admin feed",
+            "ADMIN_SOCKET_KEY is not set; the /admin socket namespace is closed",
+            "Set ADMIN_SOCKET_KEY for the admin UI's operations feed",
+        ),
+    };
+
+    vec![redis, sockets, admin_feed]
+}
@@ -0,0 +1,452 @@
+//! `cradle export`: copy orders, trades and candles to files for backups and
+//! analytics. Rows are read a chunk at a time in key order, each chunk its
+//! own short query, so a large export neither holds a long transaction open
+//! nor loads a table into memory.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use arrow_json::reader::Decoder;
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{NaiveDateTime, Utc};
+use clap::{Args, ValueEnum};
+use diesel::prelude::*;
+use indicatif::{ProgressBar, ProgressStyle};
+use parquet::arrow::ArrowWriter;
+use serde::Serialize;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use cradle_back_end::cli_utils::{CliError, format_table, print_info};
+use cradle_back_end::market_time_series::db_types::MarketTimeSeriesRecord;
+use cradle_back_end::order_book::db_types::{OrderBookRecord, OrderBookTradeRecord};
+use cradle_back_end::utils::app_config::AppConfig;
+use cradle_back_end::utils::commons::DbConn;
+
+use crate::context::CliContext;
+use crate::markets::parse_time;
+
+#[derive(Args)]
+pub struct ExportArgs {
+    /// Tables to export, comma separated
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "orders,trades,time_series"
+    )]
+    tables: Vec<Table>,
+    /// Only rows from this time on (UTC, YYYY-MM-DD HH:MM): orders and
+    /// trades by creation, candles by start
+    #[arg(long, value_parser = parse_time)]
+    from: Option<NaiveDateTime>,
+    /// Only rows before this time
+    #[arg(long, value_parser = parse_time)]
+    to: Option<NaiveDateTime>,
+    #[arg(long, value_enum, default_value = "csv")]
+    format: Format,
+    /// Directory to write into; defaults to export-<timestamp>
+    #[arg(long)]
+    out: Option<PathBuf>,
+    /// Rows read per query
+    #[arg(long, default_value_t = 5000)]
+    chunk: i64,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Table {
+    Orders,
+    Trades,
+    TimeSeries,
+}
+
+impl Table {
+    fn name(&self) -> &'static str {
+        match self {
+            Table::Orders => "orders",
+            Table::Trades => "trades",
+            Table::TimeSeries => "time_series",
+        }
+    }
+
+    /// Columns for parquet, in the order the records serialize them. Ids,
+    /// enums and amounts are strings so amounts keep their full precision.
+    fn schema(&self) -> SchemaRef {
+        let timestamp = DataType::Timestamp(TimeUnit::Microsecond, None);
+        let text = |name: &str| Field::new(name, DataType::Utf8, false);
+        let maybe_text = |name: &str| Field::new(name, DataType::Utf8, true);
+        let time = |name: &str| Field::new(name, timestamp.clone(), false);
+        let maybe_time = |name: &str| Field::new(name, timestamp.clone(), true);
+        let fields = match self {
+            Table::Orders => vec![
+                text("id"),
+                text("wallet"),
+                text("market_id"),
+                text("bid_asset"),
+                text("ask_asset"),
+                text("bid_amount"),
+                text("ask_amount"),
+                text("price"),
+                text("filled_bid_amount"),
+                text("filled_ask_amount"),
+                text("mode"),
+                text("status"),
+                time("created_at"),
+                maybe_time("filled_at"),
+                maybe_time("cancelled_at"),
+                maybe_time("expires_at"),
+                text("order_type"),
+            ],
+            Table::Trades => vec![
+                text("id"),
+                text("maker_order_id"),
+                text("taker_order_id"),
+                text("maker_filled_amount"),
+                text("taker_filled_amount"),
+                maybe_text("settlement_tx"),
+                text("settlement_status"),
+                time("created_at"),
+                maybe_time("settled_at"),
+            ],
+            Table::TimeSeries => vec![
+                text("id"),
+                text("market_id"),
+                text("asset"),
+                text("open"),
+                text("high"),
+                text("low"),
+                text("close"),
+                text("volume"),
+                time("created_at"),
+                time("start_time"),
+                time("end_time"),
+                text("interval"),
+                text("data_provider_type"),
+                maybe_text("data_provider"),
+            ],
+        };
+        Arc::new(Schema::new(fields))
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Format {
+    Csv,
+    /// One JSON object per line
+    Jsonl,
+    /// One row group per chunk
+    Parquet,
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Jsonl => "jsonl",
+            Format::Parquet => "parquet",
+        }
+    }
+}
+
+enum RowWriter {
+    Csv(csv::Writer<File>),
+    Jsonl(BufWriter<File>),
+    /// Rows collect in the decoder until the chunk ends
+    Parquet(Decoder, ArrowWriter<File>),
+}
+
+impl RowWriter {
+    fn create(path: &Path, format: Format, table: Table) -> Result<Self> {
+        let file =
+            File::create(path).with_context(|| format!("Couldn't create {}", path.display()))?;
+        Ok(match format {
+            Format::Csv => RowWriter::Csv(csv::Writer::from_writer(file)),
+            Format::Jsonl => RowWriter::Jsonl(BufWriter::new(file)),
+            Format::Parquet => {
+                let schema = table.schema();
+                let decoder = arrow_json::ReaderBuilder::new(schema.clone())
+                    .with_coerce_primitive(true)
+                    .build_decoder()?;
+                RowWriter::Parquet(decoder, ArrowWriter::try_new(file, schema, None)?)
+            }
+        })
+    }
+
+    fn write<T: Serialize>(&mut self, row: &T) -> Result<()> {
+        match self {
+            RowWriter::Csv(writer) => writer.serialize(row)?,
+            RowWriter::Jsonl(writer) => {
+                serde_json::to_writer(&mut *writer, row)?;
+                writer.write_all(b"\n")?;
+            }
+            RowWriter::Parquet(decoder, _) => decoder.serialize(std::slice::from_ref(row))?,
+        }
+        Ok(())
+    }
+
+    /// Called after each chunk; parquet writes the chunk as its own row group.
+    fn end_chunk(&mut self) -> Result<()> {
+        if let RowWriter::Parquet(decoder, writer) = self {
+            if let Some(batch) = decoder.flush()? {
+                writer.write(&batch)?;
+            }
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        match self {
+            RowWriter::Csv(mut writer) => writer.flush()?,
+            RowWriter::Jsonl(mut writer) => writer.flush()?,
+            RowWriter::Parquet(_, writer) => {
+                writer.close()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Where the last chunk ended: its last row's time and id.
+type Cursor = Option<(NaiveDateTime, Uuid)>;
+
+#[derive(Serialize)]
+struct Exported {
+    table: &'static str,
+    file: PathBuf,
+    rows: u64,
+}
+
+pub async fn run(args: ExportArgs, ctx: &CliContext, app_config: &AppConfig) -> Result<Value> {
+    if let (Some(from), Some(to)) = (args.from, args.to)
+        && from >= to
+    {
+        return Err(
+            CliError::ValidationError("The range has to end after it starts".to_string()).into(),
+        );
+    }
+    if args.chunk < 1 {
+        return Err(CliError::ValidationError("--chunk has to be at least 1".to_string()).into());
+    }
+
+    let started_at = Utc::now().naive_utc();
+    let dir = args
+        .out
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("export-{}", started_at.format("%Y%m%d-%H%M%S"))));
+    let mut seen = HashSet::new();
+    let tables: Vec<Table> = args
+        .tables
+        .iter()
+        .copied()
+        .filter(|t| seen.insert(t.name()))
+        .collect();
+
+    let paths: Vec<PathBuf> = tables
+        .iter()
+        .map(|t| dir.join(format!("{}.{}", t.name(), args.format.extension())))
+        .collect();
+    let existing: Vec<String> = paths
+        .iter()
+        .filter(|p| p.exists())
+        .map(|p| p.display().to_string())
+        .collect();
+    if !existing.is_empty() {
+        ctx.confirm(&format!("Overwrite {}?", existing.join(", ")))?;
+    }
+    std::fs::create_dir_all(&dir).with_context(|| format!("Couldn't create {}", dir.display()))?;
+
+    let conn = &mut app_config.pool.get()?;
+    let mut exported = Vec::new();
+    for (table, path) in tables.iter().zip(paths) {
+        let bar = if ctx.json {
+            ProgressBar::hidden()
+        } else {
+            ProgressBar::new_spinner()
+        };
+        bar.set_style(ProgressStyle::with_template(
+            "{spinner} {prefix}: {pos} rows ({per_sec})",
+        )?);
+        bar.set_prefix(table.name());
+        bar.enable_steady_tick(Duration::from_millis(200));
+
+        let mut writer = RowWriter::create(&path, args.format, *table)?;
+        let rows = match table {
+            Table::Orders => copy(
+                conn,
+                &mut writer,
+                &bar,
+                args.chunk,
+                |conn, after, limit| orders_page(conn, args.from, args.to, after, limit),
+                |o| (o.created_at, o.id),
+            )?,
+            Table::Trades => copy(
+                conn,
+                &mut writer,
+                &bar,
+                args.chunk,
+                |conn, after, limit| trades_page(conn, args.from, args.to, after, limit),
+                |t| (t.created_at, t.id),
+            )?,
+            Table::TimeSeries => copy(
+                conn,
+                &mut writer,
+                &bar,
+                args.chunk,
+                |conn, after, limit| time_series_page(conn, args.from, args.to, after, limit),
+                |b| (b.start_time, b.id),
+            )?,
+        };
+        writer.finish()?;
+        bar.finish();
+
+        exported.push(Exported {
+            table: table.name(),
+            file: path,
+            rows,
+        });
+    }
+
+    let manifest = json!({
+        "exported_at": started_at,
+        "from": args.from,
+        "to": args.to,
+        "format": args.format,
+        "tables": exported,
+    });
+    let manifest_path = dir.join("manifest.json");
+    std::fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)
+        .with_context(|| format!("Couldn't write {}", manifest_path.display()))?;
+
+    if ctx.json {
+        return Ok(manifest);
+    }
+    format_table(
+        vec!["Table", "Rows", "File"],
+        exported
+            .iter()
+            .map(|e| {
+                vec![
+                    e.table.to_string(),
+                    e.rows.to_string(),
+                    e.file.display().to_string(),
+                ]
+            })
+            .collect(),
+    );
+    print_info(&format!("Manifest written to {}", manifest_path.display()));
+    Ok(Value::Null)
+}
+
+/// Page through a table with `page` until it comes back short, writing each
+/// row as it goes.
+fn copy<T: Serialize>(
+    conn: DbConn<'_>,
+    writer: &mut RowWriter,
+    bar: &ProgressBar,
+    chunk: i64,
+    page: impl Fn(DbConn<'_>, Cursor, i64) -> Result<Vec<T>>,
+    key: impl Fn(&T) -> (NaiveDateTime, Uuid),
+) -> Result<u64> {
+    let mut after: Cursor = None;
+    let mut rows = 0u64;
+    loop {
+        let chunk_rows = page(&mut *conn, after, chunk)?;
+        for row in &chunk_rows {
+            writer.write(row)?;
+        }
+        writer.end_chunk()?;
+        rows += chunk_rows.len() as u64;
+        bar.set_position(rows);
+
+        match chunk_rows.last() {
+            Some(last) if chunk_rows.len() as i64 == chunk => after = Some(key(last)),
+            _ => return Ok(rows),
+        }
+    }
+}
+
+fn orders_page(
+    conn: DbConn<'_>,
+    from: Option<NaiveDateTime>,
+    to: Option<NaiveDateTime>,
+    after: Cursor,
+    limit: i64,
+) -> Result<Vec<OrderBookRecord>> {
+    use cradle_back_end::schema::orderbook::dsl::*;
+
+    let mut query = orderbook.select(OrderBookRecord::as_select()).into_boxed();
+    if let Some(from) = from {
+        query = query.filter(created_at.ge(from));
+    }
+    if let Some(to) = to {
+        query = query.filter(created_at.lt(to));
+    }
+    if let Some((at, last)) = after {
+        query = query.filter(created_at.gt(at).or(created_at.eq(at).and(id.gt(last))));
+    }
+    Ok(query
+        .order((created_at.asc(), id.asc()))
+        .limit(limit)
+        .get_results(conn)?)
+}
+
+fn trades_page(
+    conn: DbConn<'_>,
+    from: Option<NaiveDateTime>,
+    to: Option<NaiveDateTime>,
+    after: Cursor,
+    limit: i64,
+) -> Result<Vec<OrderBookTradeRecord>> {
+    use cradle_back_end::schema::orderbooktrades::dsl::*;
+
+    let mut query = orderbooktrades
+        .select(OrderBookTradeRecord::as_select())
+        .into_boxed();
+    if let Some(from) = from {
+        query = query.filter(created_at.ge(from));
+    }
+    if let Some(to) = to {
+        query = query.filter(created_at.lt(to));
+    }
+    if let Some((at, last)) = after {
+        query = query.filter(created_at.gt(at).or(created_at.eq(at).and(id.gt(last))));
+    }
+    Ok(query
+        .order((created_at.asc(), id.asc()))
+        .limit(limit)
+        .get_results(conn)?)
+}
+
+fn time_series_page(
+    conn: DbConn<'_>,
+    from: Option<NaiveDateTime>,
+    to: Option<NaiveDateTime>,
+    after: Cursor,
+    limit: i64,
+) -> Result<Vec<MarketTimeSeriesRecord>> {
+    use cradle_back_end::schema::markets_time_series::dsl::*;
+
+    let mut query = markets_time_series
+        .select(MarketTimeSeriesRecord::as_select())
+        .into_boxed();
+    if let Some(from) = from {
+        query = query.filter(start_time.ge(from));
+    }
+    if let Some(to) = to {
+        query = query.filter(start_time.lt(to));
+    }
+    if let Some((at, last)) = after {
+        query = query.filter(start_time.gt(at).or(start_time.eq(at).and(id.gt(last))));
+    }
+    Ok(query
+        .order((start_time.asc(), id.asc()))
+        .limit(limit)
+        .get_results(conn)?)
+}
@@ -0,0 +1,419 @@
+//! `cradle import`: create assets or accounts from a CSV file, one action
+//! router call per row. Rows that were created are remembered in a state
+//! file next to the CSV, so a rerun after fixing the failed rows only sends
+//! those.
+
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use cradle_back_end::accounts::db_types::CreateCradleAccount;
+use cradle_back_end::accounts::processor_enums::AccountsProcessorInput;
+use cradle_back_end::action_router::ActionRouterInput;
+use cradle_back_end::asset_book::processor_enums::{
+    AssetBookProcessorInput, CreateExistingAssetInputArgs, CreateNewAssetInputArgs,
+};
+use cradle_back_end::cli_utils::input::parse_enum;
+use cradle_back_end::cli_utils::{CliError, format_table, print_info, print_warning};
+use cradle_back_end::utils::app_config::AppConfig;
+
+use crate::accounts::{ACCOUNT_STATUSES, ACCOUNT_TYPES};
+use crate::assets::ASSET_TYPES;
+use crate::context::CliContext;
+use crate::dispatch;
+
+#[derive(Subcommand)]
+pub enum ImportCommand {
+    /// Columns: type, name, symbol, decimals, icon, and token plus
+    /// asset_manager for tokens that already exist
+    Assets {
+        file: PathBuf,
+        /// Validate and preview without creating anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Ignore the state file and send every row again
+        #[arg(long)]
+        restart: bool,
+    },
+    /// Columns: linked_account_id, and optionally type and status
+    Accounts {
+        file: PathBuf,
+        #[arg(long)]
+        dry_run: bool,
+        #[arg(long)]
+        restart: bool,
+    },
+}
+
+#[derive(Deserialize)]
+struct AssetRow {
+    #[serde(rename = "type")]
+    asset_type: Option<String>,
+    name: Option<String>,
+    symbol: Option<String>,
+    decimals: Option<String>,
+    icon: Option<String>,
+    token: Option<String>,
+    asset_manager: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AccountRow {
+    linked_account_id: Option<String>,
+    #[serde(rename = "type")]
+    account_type: Option<String>,
+    status: Option<String>,
+}
+
+/// A row as read and checked. `key` identifies it across runs: the symbol
+/// for assets, the linked account for accounts.
+struct Row {
+    line: u64,
+    key: String,
+    summary: String,
+    input: Result<ActionRouterInput, String>,
+}
+
+/// Keys of the rows already created from a file.
+#[derive(Serialize, Deserialize, Default)]
+struct ImportState {
+    done: BTreeSet<String>,
+}
+
+#[derive(Serialize)]
+struct RowResult {
+    line: u64,
+    key: String,
+    /// created, failed, skipped (created by an earlier run) or invalid
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+pub async fn run(cmd: ImportCommand, ctx: &CliContext, app_config: &AppConfig) -> Result<Value> {
+    match cmd {
+        ImportCommand::Assets {
+            file,
+            dry_run,
+            restart,
+        } => {
+            let rows = read_rows(&file, asset_row)?;
+            import(ctx, app_config, &file, rows, dry_run, restart).await
+        }
+        ImportCommand::Accounts {
+            file,
+            dry_run,
+            restart,
+        } => {
+            let rows = read_rows(&file, account_row)?;
+            import(ctx, app_config, &file, rows, dry_run, restart).await
+        }
+    }
+}
+
+fn required(value: Option<String>, column: &str) -> Result<String, String> {
+    value
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| format!("{} is empty", column))
+}
+
+fn choice<T: DeserializeOwned>(value: &str, column: &str, names: &[&str]) -> Result<T, String> {
+    parse_enum(value).map_err(|_| format!("{} must be one of {}", column, names.join(", ")))
+}
+
+fn asset_row(row: AssetRow) -> (String, String, Result<ActionRouterInput, String>) {
+    let key = row.symbol.clone().unwrap_or_default();
+    let summary = format!(
+        "{} {}",
+        row.name.as_deref().unwrap_or(""),
+        row.token.as_deref().unwrap_or("(new token)")
+    );
+
+    let input = (|| -> Result<ActionRouterInput, String> {
+        let asset_type = choice(&required(row.asset_type, "type")?, "type", &ASSET_TYPES)?;
+        let name = required(row.name, "name")?;
+        let symbol = required(row.symbol, "symbol")?;
+        let decimals = required(row.decimals, "decimals")?
+            .parse::<i32>()
+            .map_err(|_| "decimals must be a whole number".to_string())?;
+        let icon = required(row.icon, "icon")?;
+
+        let input = match row.token.filter(|t| !t.is_empty()) {
+            Some(token) => {
+                AssetBookProcessorInput::CreateExistingAsset(CreateExistingAssetInputArgs {
+                    token,
+                    asset_manager: row.asset_manager.filter(|m| !m.is_empty()),
+                    asset_type,
+                    name,
+                    symbol,
+                    decimals,
+                    icon,
+                })
+            }
+            None => AssetBookProcessorInput::CreateNewAsset(CreateNewAssetInputArgs {
+                asset_type,
+                name,
+                symbol,
+                decimals,
+                icon,
+            }),
+        };
+        Ok(ActionRouterInput::AssetBook(input))
+    })();
+
+    (key, summary, input)
+}
+
+fn account_row(row: AccountRow) -> (String, String, Result<ActionRouterInput, String>) {
+    let key = row.linked_account_id.clone().unwrap_or_default();
+    let summary = format!(
+        "{} {}",
+        row.account_type.as_deref().unwrap_or("retail"),
+        row.status.as_deref().unwrap_or("unverified")
+    );
+
+    let input = (|| -> Result<ActionRouterInput, String> {
+        let linked_account_id = required(row.linked_account_id, "linked_account_id")?;
+        let account_type = match row.account_type.filter(|t| !t.is_empty()) {
+            Some(t) => Some(choice(&t, "type", &ACCOUNT_TYPES)?),
+            None => None,
+        };
+        let status = match row.status.filter(|s| !s.is_empty()) {
+            Some(s) => Some(choice(&s, "status", &ACCOUNT_STATUSES)?),
+            None => None,
+        };
+        Ok(ActionRouterInput::Accounts(
+            AccountsProcessorInput::CreateAccount(CreateCradleAccount {
+                linked_account_id,
+                account_type,
+                status,
+            }),
+        ))
+    })();
+
+    (key, summary, input)
+}
+
+/// Read and check every row. A bad row is kept with its error rather than
+/// failing the file, so the preview can show them all at once.
+fn read_rows<T: DeserializeOwned>(
+    path: &Path,
+    parse: impl Fn(T) -> (String, String, Result<ActionRouterInput, String>),
+) -> Result<Vec<Row>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .map_err(|e| CliError::ValidationError(format!("Can't read {}: {}", path.display(), e)))?;
+    let headers = reader.headers()?.clone();
+
+    let mut rows = Vec::new();
+    let mut seen = HashSet::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| CliError::ValidationError(e.to_string()))?;
+        let line = record.position().map(|p| p.line()).unwrap_or_default();
+        let (key, summary, mut input) = match record.deserialize::<T>(Some(&headers)) {
+            Ok(row) => parse(row),
+            Err(e) => (String::new(), String::new(), Err(e.to_string())),
+        };
+        if input.is_ok() && !seen.insert(key.clone()) {
+            input = Err(format!("{} appears more than once", key));
+        }
+        rows.push(Row {
+            line,
+            key,
+            summary,
+            input,
+        });
+    }
+
+    if rows.is_empty() {
+        return Err(CliError::ValidationError(format!("{} has no rows", path.display())).into());
+    }
+    Ok(rows)
+}
+
+fn state_path(file: &Path) -> PathBuf {
+    let mut name = file.as_os_str().to_owned();
+    name.push(".import-state.json");
+    PathBuf::from(name)
+}
+
+fn load_state(path: &Path) -> Result<ImportState> {
+    if !path.exists() {
+        return Ok(ImportState::default());
+    }
+    let body = std::fs::read_to_string(path)?;
+    serde_json::from_str(&body).with_context(|| format!("{} is corrupt", path.display()))
+}
+
+fn save_state(path: &Path, state: &ImportState) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(state)?)
+        .with_context(|| format!("Couldn't write {}", path.display()))
+}
+
+async fn import(
+    ctx: &CliContext,
+    app_config: &AppConfig,
+    file: &Path,
+    rows: Vec<Row>,
+    dry_run: bool,
+    restart: bool,
+) -> Result<Value> {
+    let state_file = state_path(file);
+    let mut state = if restart {
+        ImportState::default()
+    } else {
+        load_state(&state_file)?
+    };
+
+    let status_of = |row: &Row| match &row.input {
+        Err(e) => format!("invalid: {}", e),
+        Ok(_) if state.done.contains(&row.key) => "done earlier".to_string(),
+        Ok(_) => "ok".to_string(),
+    };
+    if !ctx.json {
+        format_table(
+            vec!["Line", "Key", "Row", "Check"],
+            rows.iter()
+                .map(|row| {
+                    vec![
+                        row.line.to_string(),
+                        row.key.clone(),
+                        row.summary.clone(),
+                        status_of(row),
+                    ]
+                })
+                .collect(),
+        );
+    }
+
+    let invalid = rows.iter().filter(|r| r.input.is_err()).count();
+    let to_send = rows
+        .iter()
+        .filter(|r| r.input.is_ok() && !state.done.contains(&r.key))
+        .count();
+    if dry_run {
+        let checks: Vec<Value> = rows
+            .iter()
+            .map(|row| json!({ "line": row.line, "key": row.key, "check": status_of(row) }))
+            .collect();
+        return Ok(
+            json!({ "dry_run": true, "to_create": to_send, "invalid": invalid, "rows": checks }),
+        );
+    }
+    if to_send == 0 {
+        return Err(CliError::ValidationError("Nothing to import".to_string()).into());
+    }
+
+    let skipping = if invalid > 0 {
+        format!(", skipping {} invalid row(s)", invalid)
+    } else {
+        String::new()
+    };
+    ctx.confirm(&format!("Create {} record(s){}?", to_send, skipping))?;
+
+    let mut results = Vec::new();
+    for row in rows {
+        let Row {
+            line, key, input, ..
+        } = row;
+        let input = match input {
+            Err(e) => {
+                results.push(RowResult {
+                    line,
+                    key,
+                    status: "invalid",
+                    result: None,
+                    error: Some(e),
+                });
+                continue;
+            }
+            Ok(_) if state.done.contains(&key) => {
+                results.push(RowResult {
+                    line,
+                    key,
+                    status: "skipped",
+                    result: None,
+                    error: None,
+                });
+                continue;
+            }
+            Ok(input) => input,
+        };
+
+        match dispatch(input, app_config).await {
+            Ok(value) => {
+                state.done.insert(key.clone());
+                save_state(&state_file, &state)?;
+                results.push(RowResult {
+                    line,
+                    key,
+                    status: "created",
+                    result: Some(value),
+                    error: None,
+                });
+            }
+            Err(e) => results.push(RowResult {
+                line,
+                key,
+                status: "failed",
+                result: None,
+                error: Some(format!("{:#}", e)),
+            }),
+        }
+    }
+
+    let count = |status: &str| results.iter().filter(|r| r.status == status).count();
+    let (created, failed) = (count("created"), count("failed"));
+    if !ctx.json {
+        println!();
+        format_table(
+            vec!["Line", "Key", "Result"],
+            results
+                .iter()
+                .map(|r| {
+                    vec![
+                        r.line.to_string(),
+                        r.key.clone(),
+                        r.error
+                            .as_ref()
+                            .map(|e| format!("{}: {}", r.status, e))
+                            .unwrap_or_else(|| r.status.to_string()),
+                    ]
+                })
+                .collect(),
+        );
+    }
+
+    if failed == 0 && invalid == 0 {
+        if state_file.exists() {
+            std::fs::remove_file(&state_file)?;
+        }
+    } else if !ctx.json {
+        print_warning(&format!(
+            "{} failed, {} invalid. Fix those rows and run again; created rows are remembered in {}",
+            failed,
+            invalid,
+            state_file.display()
+        ));
+    }
+    if !ctx.json && created > 0 {
+        print_info(&format!("Created {}", created));
+    }
+
+    Ok(json!({
+        "created": created,
+        "failed": failed,
+        "invalid": invalid,
+        "skipped": count("skipped"),
+        "state_file": if failed + invalid > 0 { Some(state_file) } else { None },
+        "rows": results,
+    }))
+}
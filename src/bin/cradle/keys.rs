@@ -0,0 +1,452 @@
+//! `cradle keys`: the operator account's key, and a rotation workflow that
+//! moves it to a new one in three deliberate steps. Each step is recorded in
+//! a state file next to where it's run and in the mutation audit log, so a
+//! rotation can be followed and repeated; private keys only ever live in the
+//! key file.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{NaiveDateTime, Utc};
+use clap::{Args, Subcommand};
+use contract_integrator::hedera::{
+    AccountId, AccountInfoQuery, AccountUpdateTransaction, Client, Key, PrivateKey, PublicKey,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use cradle_back_end::audit_log::operations::record_mutation;
+use cradle_back_end::cli_utils::{CliError, print_info, print_success, print_warning};
+use cradle_back_end::utils::app_config::AppConfig;
+
+use crate::context::CliContext;
+
+const AUDIT_SOURCE: &str = "cli";
+
+#[derive(Subcommand)]
+pub enum KeysCommand {
+    /// The operator account, its configured public key and the key on-chain
+    Show,
+    /// Write a new private key to a file, as a rotation candidate
+    Generate {
+        /// File to create; it must not exist yet
+        #[arg(long)]
+        out: PathBuf,
+        /// ECDSA (secp256k1) instead of Ed25519
+        #[arg(long)]
+        ecdsa: bool,
+    },
+    /// Move the operator account to a new key
+    Rotate(RotateArgs),
+}
+
+#[derive(Args)]
+pub struct RotateArgs {
+    /// Where the rotation's progress is kept
+    #[arg(long, default_value = "key-rotation.json", global = true)]
+    state: PathBuf,
+    #[command(subcommand)]
+    command: RotateCommand,
+}
+
+#[derive(Subcommand)]
+pub enum RotateCommand {
+    /// Start a rotation to the key in a file made by `keys generate`
+    Initiate {
+        #[arg(long)]
+        key_file: PathBuf,
+    },
+    /// Check the key file still matches and the account still has the key
+    /// the rotation started from
+    Verify,
+    /// Put the new key on the account
+    Finalize,
+    /// Where the rotation stands
+    Status,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum RotationStatus {
+    Initiated,
+    Verified,
+    Finalized,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Rotation {
+    id: Uuid,
+    account_id: String,
+    /// The key being replaced
+    current_public_key: String,
+    candidate_public_key: String,
+    key_file: PathBuf,
+    status: RotationStatus,
+    initiated_at: NaiveDateTime,
+    verified_at: Option<NaiveDateTime>,
+    finalized_at: Option<NaiveDateTime>,
+    transaction_id: Option<String>,
+}
+
+pub async fn run(cmd: KeysCommand, ctx: &CliContext, app_config: &AppConfig) -> Result<Value> {
+    match cmd {
+        KeysCommand::Show => show(app_config).await,
+        KeysCommand::Generate { out, ecdsa } => generate(&out, ecdsa),
+        KeysCommand::Rotate(args) => match args.command {
+            RotateCommand::Initiate { key_file } => {
+                initiate(ctx, app_config, &args.state, &key_file).await
+            }
+            RotateCommand::Verify => verify(ctx, app_config, &args.state).await,
+            RotateCommand::Finalize => finalize(ctx, app_config, &args.state).await,
+            RotateCommand::Status => Ok(serde_json::to_value(load_rotation(&args.state)?)?),
+        },
+    }
+}
+
+fn operator(client: &Client) -> Result<(AccountId, PublicKey)> {
+    match (
+        client.get_operator_account_id(),
+        client.get_operator_public_key(),
+    ) {
+        (Some(account), Some(key)) => Ok((account, key)),
+        _ => Err(anyhow!("The wallet's client has no operator set")),
+    }
+}
+
+fn key_string(key: &Key) -> String {
+    match key {
+        Key::Single(key) => key.to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+async fn onchain_key(client: &Client, account: AccountId) -> Result<String> {
+    let info = AccountInfoQuery::new()
+        .account_id(account)
+        .execute(client)
+        .await
+        .with_context(|| format!("Couldn't read account {}", account))?;
+    Ok(key_string(&info.key))
+}
+
+async fn show(app_config: &AppConfig) -> Result<Value> {
+    let client = &app_config.wallet.client;
+    let (account, configured) = operator(client)?;
+    let info = AccountInfoQuery::new()
+        .account_id(account)
+        .execute(client)
+        .await
+        .with_context(|| format!("Couldn't read account {}", account))?;
+    let onchain = key_string(&info.key);
+
+    Ok(json!({
+        "account_id": account.to_string(),
+        "configured_public_key": configured.to_string(),
+        "onchain_key": onchain,
+        "matches": onchain == configured.to_string(),
+        "balance": info.balance.to_string(),
+    }))
+}
+
+pub fn generate(out: &Path, ecdsa: bool) -> Result<Value> {
+    if out.exists() {
+        return Err(CliError::ValidationError(format!(
+            "{} already exists; pick a new file so no key gets overwritten",
+            out.display()
+        ))
+        .into());
+    }
+    let key = if ecdsa {
+        PrivateKey::generate_ecdsa()
+    } else {
+        PrivateKey::generate_ed25519()
+    };
+    write_private(out, &key)?;
+
+    Ok(json!({
+        "key_file": out,
+        "algorithm": if ecdsa { "ecdsa" } else { "ed25519" },
+        "public_key": key.public_key().to_string(),
+    }))
+}
+
+/// Owner-only on unix, since the file holds a private key.
+fn write_private(path: &Path, key: &PrivateKey) -> Result<()> {
+    std::fs::write(path, format!("{}\n", key.to_string_der()))
+        .with_context(|| format!("Couldn't write {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+fn read_private(path: &Path) -> Result<PrivateKey> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("Couldn't read {}", path.display()))?;
+    PrivateKey::from_str(raw.trim())
+        .map_err(|e| anyhow!("{} doesn't hold a private key: {}", path.display(), e))
+}
+
+fn load_rotation(path: &Path) -> Result<Rotation> {
+    if !path.exists() {
+        return Err(CliError::NotFound(format!(
+            "rotation state {}; start one with keys rotate initiate",
+            path.display()
+        ))
+        .into());
+    }
+    let body = std::fs::read_to_string(path)?;
+    serde_json::from_str(&body).with_context(|| format!("{} is corrupt", path.display()))
+}
+
+fn save_rotation(path: &Path, rotation: &Rotation) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(rotation)?)
+        .with_context(|| format!("Couldn't write {}", path.display()))
+}
+
+/// Record a rotation step in the audit log. The step has already happened,
+/// so a failure to log is reported rather than failing it.
+fn audit(
+    app_config: &AppConfig,
+    action: &str,
+    rotation: &Rotation,
+    outcome: std::result::Result<Value, String>,
+) {
+    let actor = std::env::var("USER").unwrap_or_else(|_| "unknown".to_string());
+    let payload = json!({
+        "rotation": rotation.id,
+        "account_id": rotation.account_id,
+        "current_public_key": rotation.current_public_key,
+        "candidate_public_key": rotation.candidate_public_key,
+    });
+    let logged = app_config
+        .pool
+        .get()
+        .map_err(anyhow::Error::from)
+        .and_then(|mut conn| {
+            record_mutation(&mut conn, &actor, AUDIT_SOURCE, action, payload, outcome)
+        });
+    if let Err(e) = logged {
+        print_warning(&format!(
+            "Couldn't record {} in the audit log: {}",
+            action, e
+        ));
+    }
+}
+
+async fn initiate(
+    ctx: &CliContext,
+    app_config: &AppConfig,
+    state: &Path,
+    key_file: &Path,
+) -> Result<Value> {
+    if let Ok(existing) = load_rotation(state)
+        && existing.status != RotationStatus::Finalized
+    {
+        return Err(CliError::ValidationError(format!(
+            "Rotation {} is still {:?} in {}; finish it or move the file away first",
+            existing.id,
+            existing.status,
+            state.display()
+        ))
+        .into());
+    }
+
+    let client = &app_config.wallet.client;
+    let (account, current) = operator(client)?;
+    let candidate = read_private(key_file)?.public_key();
+    if candidate.to_string() == current.to_string() {
+        return Err(CliError::ValidationError(
+            "The key file holds the key the account already uses".to_string(),
+        )
+        .into());
+    }
+    let onchain = onchain_key(client, account).await?;
+    if onchain != current.to_string() {
+        return Err(anyhow!(
+            "Account {} has key {} on-chain, not the configured {}; fix the configuration before rotating",
+            account,
+            onchain,
+            current
+        ));
+    }
+
+    ctx.confirm(&format!(
+        "Start rotating account {} from {} to {}?",
+        account, current, candidate
+    ))?;
+    let rotation = Rotation {
+        id: Uuid::new_v4(),
+        account_id: account.to_string(),
+        current_public_key: current.to_string(),
+        candidate_public_key: candidate.to_string(),
+        key_file: key_file.to_path_buf(),
+        status: RotationStatus::Initiated,
+        initiated_at: Utc::now().naive_utc(),
+        verified_at: None,
+        finalized_at: None,
+        transaction_id: None,
+    };
+    save_rotation(state, &rotation)?;
+    audit(
+        app_config,
+        "Keys.RotateInitiate",
+        &rotation,
+        Ok(json!({ "state": state })),
+    );
+
+    if !ctx.json {
+        print_info("Next: cradle keys rotate verify");
+    }
+    Ok(serde_json::to_value(&rotation)?)
+}
+
+/// The candidate key file still matches and can sign, and the account
+/// still has the key the rotation started from.
+async fn check_rotation(app_config: &AppConfig, rotation: &Rotation) -> Result<PrivateKey> {
+    let candidate = read_private(&rotation.key_file)?;
+    if candidate.public_key().to_string() != rotation.candidate_public_key {
+        return Err(anyhow!(
+            "{} no longer holds the key this rotation was started with",
+            rotation.key_file.display()
+        ));
+    }
+    let challenge = rotation.id.as_bytes();
+    candidate
+        .public_key()
+        .verify(challenge, &candidate.sign(challenge))
+        .map_err(|e| anyhow!("The candidate key can't sign: {}", e))?;
+
+    let account = AccountId::from_str(&rotation.account_id)
+        .map_err(|e| anyhow!("Invalid account {}: {}", rotation.account_id, e))?;
+    let onchain = onchain_key(&app_config.wallet.client, account).await?;
+    if onchain != rotation.current_public_key {
+        return Err(anyhow!(
+            "Account {} now has key {} on-chain; it changed since the rotation started",
+            account,
+            onchain
+        ));
+    }
+    Ok(candidate)
+}
+
+async fn verify(ctx: &CliContext, app_config: &AppConfig, state: &Path) -> Result<Value> {
+    let mut rotation = load_rotation(state)?;
+    if rotation.status == RotationStatus::Finalized {
+        return Err(
+            CliError::ValidationError("This rotation is already finalized".to_string()).into(),
+        );
+    }
+
+    if let Err(e) = check_rotation(app_config, &rotation).await {
+        audit(
+            app_config,
+            "Keys.RotateVerify",
+            &rotation,
+            Err(e.to_string()),
+        );
+        return Err(e);
+    }
+    rotation.status = RotationStatus::Verified;
+    rotation.verified_at = Some(Utc::now().naive_utc());
+    save_rotation(state, &rotation)?;
+    audit(
+        app_config,
+        "Keys.RotateVerify",
+        &rotation,
+        Ok(json!({ "verified": true })),
+    );
+
+    if !ctx.json {
+        print_success("The candidate key signs and the account is unchanged");
+        print_info("Next: cradle keys rotate finalize");
+    }
+    Ok(serde_json::to_value(&rotation)?)
+}
+
+async fn finalize(ctx: &CliContext, app_config: &AppConfig, state: &Path) -> Result<Value> {
+    let mut rotation = load_rotation(state)?;
+    match rotation.status {
+        RotationStatus::Verified => {}
+        RotationStatus::Initiated => {
+            return Err(CliError::ValidationError(
+                "Run keys rotate verify before finalizing".to_string(),
+            )
+            .into());
+        }
+        RotationStatus::Finalized => {
+            return Err(CliError::ValidationError(
+                "This rotation is already finalized".to_string(),
+            )
+            .into());
+        }
+    }
+    // the account could have changed since verify
+    let candidate = check_rotation(app_config, &rotation).await?;
+
+    ctx.confirm(&format!(
+        "Replace the key on account {}? The current key stops working for it as soon as this lands, and every service using it must be restarted with the new one.",
+        rotation.account_id
+    ))?;
+
+    let client = &app_config.wallet.client;
+    let account = AccountId::from_str(&rotation.account_id)
+        .map_err(|e| anyhow!("Invalid account {}: {}", rotation.account_id, e))?;
+    // the client's operator signs with the current key; the new key signs
+    // to show it's held
+    let submitted = async {
+        let response = AccountUpdateTransaction::new()
+            .account_id(account)
+            .key(candidate.public_key())
+            .freeze_with(client)?
+            .sign(candidate.clone())
+            .execute(client)
+            .await?;
+        response.get_receipt(client).await?;
+        anyhow::Ok(response.transaction_id.to_string())
+    }
+    .await;
+    let transaction_id = match submitted {
+        Ok(id) => id,
+        Err(e) => {
+            audit(
+                app_config,
+                "Keys.RotateFinalize",
+                &rotation,
+                Err(e.to_string()),
+            );
+            return Err(e.context("The key update failed; the account keeps its current key"));
+        }
+    };
+
+    rotation.status = RotationStatus::Finalized;
+    rotation.finalized_at = Some(Utc::now().naive_utc());
+    rotation.transaction_id = Some(transaction_id.clone());
+    save_rotation(state, &rotation)?;
+    audit(
+        app_config,
+        "Keys.RotateFinalize",
+        &rotation,
+        Ok(json!({ "transaction_id": transaction_id })),
+    );
+
+    let onchain = onchain_key(client, account).await?;
+    if onchain != rotation.candidate_public_key {
+        print_warning(&format!(
+            "Account {} reports key {}; check transaction {}",
+            account, onchain, transaction_id
+        ));
+    }
+    if !ctx.json {
+        print_success(&format!("Account {} now uses the new key", account));
+        print_info(&format!(
+            "Put the key in {} into the operator key setting of every service and restart them; this process still holds the old one",
+            rotation.key_file.display()
+        ));
+    }
+    Ok(serde_json::to_value(&rotation)?)
+}
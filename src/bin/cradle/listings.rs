@@ -0,0 +1,356 @@
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+use bigdecimal::BigDecimal;
+use clap::{Args, Subcommand};
+use contract_integrator::hedera::ContractId;
+use contract_integrator::utils::functions::access_controller::{
+    AccessControllerArgs, AccessControllerFunctionsInput, AccessControllerFunctionsOutput,
+};
+use contract_integrator::utils::functions::{ContractCallInput, ContractCallOutput};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use cradle_back_end::accounts::db_types::AdminOperatorRecord;
+use cradle_back_end::action_router::ActionRouterInput;
+use cradle_back_end::asset_book::processor_enums::CreateNewAssetInputArgs;
+use cradle_back_end::listing::lifecycle::ReviewListingInputArgs;
+use cradle_back_end::listing::operations::{
+    AssetDetails, CreateCompanyInputArgs, CreateListingInputArgs, GetPurchaseFeeInputArgs,
+    PurchaseListingAssetInputArgs, ReturnAssetListingInputArgs, WithdrawToBeneficiaryInputArgsBody,
+    get_listing,
+};
+use cradle_back_end::listing::processor_enums::{
+    CradleNativeListingFunctionsInput, UpdateListingStatusInputArgs,
+};
+use cradle_back_end::utils::app_config::AppConfig;
+
+use crate::assets::ASSET_TYPES;
+use crate::context::CliContext;
+use crate::{dispatch, dispatch_as};
+
+pub const LISTING_STATUSES: [&str; 5] = ["pending", "open", "closed", "paused", "cancelled"];
+
+#[derive(Args)]
+pub struct ListingArg {
+    /// Listing id
+    pub listing: Option<Uuid>,
+}
+
+#[derive(Args)]
+pub struct ReviewArgs {
+    pub listing: Option<Uuid>,
+    /// Username of the approver making the decision
+    #[arg(long)]
+    pub operator: Option<String>,
+}
+
+/// The token a new listing sells: one already on the book, or one issued
+/// for it.
+#[derive(Args)]
+pub struct ListingAsset {
+    /// An asset already on the book
+    #[arg(long, conflicts_with_all = ["asset_type", "asset_name", "asset_symbol"])]
+    pub asset: Option<Uuid>,
+    /// bridged, native, yield_bearing, chain_native, stablecoin or volatile
+    #[arg(long)]
+    pub asset_type: Option<String>,
+    #[arg(long)]
+    pub asset_name: Option<String>,
+    #[arg(long)]
+    pub asset_symbol: Option<String>,
+    #[arg(long)]
+    pub asset_decimals: Option<i64>,
+    #[arg(long)]
+    pub asset_icon: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum ListingsCommand {
+    /// Register the company behind listings
+    CreateCompany {
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        description: Option<String>,
+        /// Link to the company's legal documents
+        #[arg(long)]
+        legal_documents: Option<String>,
+    },
+    /// Create a draft listing for a company
+    Create {
+        #[arg(long)]
+        company: Option<Uuid>,
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        description: Option<String>,
+        /// Link to the listing's documents
+        #[arg(long)]
+        documents: Option<String>,
+        #[command(flatten)]
+        asset: ListingAsset,
+        /// Asset purchases are paid in
+        #[arg(long)]
+        purchase_asset: Option<Uuid>,
+        #[arg(long)]
+        price: Option<BigDecimal>,
+        #[arg(long)]
+        max_supply: Option<BigDecimal>,
+    },
+    /// Sales, holders and progress of a listing
+    Stats(ListingArg),
+    /// Fee on a purchase of a given amount
+    Fee {
+        listing: Option<Uuid>,
+        #[arg(long)]
+        amount: Option<BigDecimal>,
+    },
+    /// Buy listing tokens for a wallet
+    Purchase {
+        listing: Option<Uuid>,
+        #[arg(long)]
+        wallet: Option<Uuid>,
+        #[arg(long)]
+        amount: Option<BigDecimal>,
+        /// Needed when tranches sell in parallel
+        #[arg(long)]
+        tranche: Option<Uuid>,
+    },
+    /// Give listing tokens back from a wallet
+    Return {
+        listing: Option<Uuid>,
+        #[arg(long)]
+        wallet: Option<Uuid>,
+        #[arg(long)]
+        amount: Option<BigDecimal>,
+    },
+    /// Pay raised funds out to the listing's beneficiary
+    WithdrawToBeneficiary {
+        listing: Option<Uuid>,
+        #[arg(long)]
+        amount: Option<BigDecimal>,
+    },
+    /// Grant the listing contract an access level on the access controller
+    GrantAccess {
+        listing: Option<Uuid>,
+        #[arg(long)]
+        level: Option<u64>,
+    },
+    /// Submit a draft listing for review
+    Submit(ListingArg),
+    /// Approve a submitted listing
+    Approve(ReviewArgs),
+    /// Send a submitted listing back
+    Reject(ReviewArgs),
+    /// Open a listing for purchases
+    GoLive(ListingArg),
+    /// Stop taking purchases
+    Close(ListingArg),
+    /// Settle a closed listing with its beneficiary
+    Settle(ListingArg),
+    /// Cancel a listing that hasn't settled
+    Cancel(ListingArg),
+    /// Refund purchasers of a cancelled listing
+    Refund(ListingArg),
+    /// Compare a listing's records with the chain
+    Reconcile(ListingArg),
+    /// Open the secondary market for a closed listing
+    CreateSecondaryMarket(ListingArg),
+    /// Pay out a distribution to holders
+    PayDistribution {
+        /// Distribution id
+        distribution: Option<Uuid>,
+    },
+    /// Set the on-chain listing status
+    SetStatus {
+        listing: Option<Uuid>,
+        /// pending, open, closed, paused or cancelled
+        #[arg(long)]
+        status: Option<String>,
+    },
+}
+
+fn listing_id(ctx: &CliContext, given: Option<Uuid>) -> Result<Uuid> {
+    ctx.uuid(given, "<listing>", "Listing ID")
+}
+
+fn review(
+    ctx: &CliContext,
+    args: ReviewArgs,
+    app_config: &AppConfig,
+) -> Result<(ReviewListingInputArgs, AdminOperatorRecord)> {
+    let input = ReviewListingInputArgs {
+        listing_id: listing_id(ctx, args.listing)?,
+    };
+    Ok((input, ctx.operator(args.operator, app_config)?))
+}
+
+fn listing_asset(ctx: &CliContext, args: ListingAsset) -> Result<AssetDetails> {
+    let new = match args.asset {
+        Some(asset) => return Ok(AssetDetails::Existing(asset)),
+        None if args.asset_name.is_some() || args.asset_symbol.is_some() => true,
+        None => {
+            ctx.pick(
+                "--asset or --asset-name",
+                "Listed asset",
+                vec!["New", "Existing"],
+            )? == 0
+        }
+    };
+    if !new {
+        return Ok(AssetDetails::Existing(ctx.uuid(None, "asset", "Asset ID")?));
+    }
+    Ok(AssetDetails::New(CreateNewAssetInputArgs {
+        asset_type: ctx.choice(args.asset_type, "asset-type", "Asset type", &ASSET_TYPES)?,
+        name: ctx.string(args.asset_name, "asset-name", "Asset name")?,
+        symbol: ctx.string(args.asset_symbol, "asset-symbol", "Symbol")?,
+        decimals: ctx.i64(args.asset_decimals, "asset-decimals", "Decimals")? as i32,
+        icon: ctx.string(args.asset_icon, "asset-icon", "Icon URL")?,
+    }))
+}
+
+async fn grant_access(
+    ctx: &CliContext,
+    listing: Option<Uuid>,
+    level: Option<u64>,
+    app_config: &AppConfig,
+) -> Result<Value> {
+    let listing = listing_id(ctx, listing)?;
+    let level = ctx.u64(level, "level", "Access level")?;
+    ctx.confirm(&format!(
+        "Grant listing {} access level {}?",
+        listing, level
+    ))?;
+
+    let mut conn = app_config.pool.get()?;
+    let record = get_listing(&mut conn, listing).await?;
+    let account = ContractId::from_str(&record.listing_contract_id)?.to_solidity_address()?;
+
+    let call = ContractCallInput::AccessController(AccessControllerFunctionsInput::GrantAccess(
+        AccessControllerArgs { level, account },
+    ));
+    match app_config.wallet.execute(call).await? {
+        ContractCallOutput::AccessController(AccessControllerFunctionsOutput::GrantAccess(
+            output,
+        )) => Ok(json!({ "transaction_id": output.transaction_id })),
+        _ => Err(anyhow!("Unexpected output granting access")),
+    }
+}
+
+pub async fn run(cmd: ListingsCommand, ctx: &CliContext, app_config: &AppConfig) -> Result<Value> {
+    use CradleNativeListingFunctionsInput as L;
+
+    let input = match cmd {
+        ListingsCommand::CreateCompany {
+            name,
+            description,
+            legal_documents,
+        } => L::CreateCompany(CreateCompanyInputArgs {
+            name: ctx.string(name, "name", "Company name")?,
+            description: ctx.string(description, "description", "Description")?,
+            legal_documents: ctx.string(
+                legal_documents,
+                "legal-documents",
+                "Link to legal documents",
+            )?,
+        }),
+        ListingsCommand::Create {
+            company,
+            name,
+            description,
+            documents,
+            asset,
+            purchase_asset,
+            price,
+            max_supply,
+        } => L::CreateListing(CreateListingInputArgs {
+            company: ctx.uuid(company, "company", "Company ID")?,
+            name: ctx.string(name, "name", "Name")?,
+            description: ctx.string(description, "description", "Description")?,
+            documents: ctx.string(documents, "documents", "Link to listing documents")?,
+            asset: listing_asset(ctx, asset)?,
+            purchase_asset: ctx.uuid(purchase_asset, "purchase-asset", "Purchase asset ID")?,
+            purchase_price: ctx.decimal(price, "price", "Price")?,
+            max_supply: ctx.decimal(max_supply, "max-supply", "Max supply")?,
+        }),
+        ListingsCommand::Stats(args) => L::GetStats(listing_id(ctx, args.listing)?),
+        ListingsCommand::Fee { listing, amount } => L::GetFee(GetPurchaseFeeInputArgs {
+            listing_id: listing_id(ctx, listing)?,
+            amount: ctx.decimal(amount, "amount", "Amount")?,
+        }),
+        ListingsCommand::Purchase {
+            listing,
+            wallet,
+            amount,
+            tranche,
+        } => L::Purchase(PurchaseListingAssetInputArgs {
+            listing: listing_id(ctx, listing)?,
+            wallet: ctx.uuid(wallet, "wallet", "Wallet ID")?,
+            amount: ctx.decimal(amount, "amount", "Amount")?,
+            tranche: ctx.optional_uuid(tranche, "Tranche ID")?,
+        }),
+        ListingsCommand::Return {
+            listing,
+            wallet,
+            amount,
+        } => L::ReturnAsset(ReturnAssetListingInputArgs {
+            listing: listing_id(ctx, listing)?,
+            wallet: ctx.uuid(wallet, "wallet", "Wallet ID")?,
+            amount: ctx.decimal(amount, "amount", "Amount")?,
+        }),
+        ListingsCommand::WithdrawToBeneficiary { listing, amount } => {
+            let args = WithdrawToBeneficiaryInputArgsBody {
+                listing: listing_id(ctx, listing)?,
+                amount: ctx.decimal(amount, "amount", "Amount")?,
+            };
+            ctx.confirm(&format!(
+                "Pay {} from listing {} to its beneficiary?",
+                args.amount, args.listing
+            ))?;
+            L::WithdrawToBeneficiary(args)
+        }
+        ListingsCommand::GrantAccess { listing, level } => {
+            return grant_access(ctx, listing, level, app_config).await;
+        }
+        ListingsCommand::Submit(args) => L::SubmitListing(listing_id(ctx, args.listing)?),
+        ListingsCommand::Approve(args) => {
+            let (input, operator) = review(ctx, args, app_config)?;
+            let input = ActionRouterInput::Listing(L::ApproveListing(input));
+            return dispatch_as(input, app_config, operator).await;
+        }
+        ListingsCommand::Reject(args) => {
+            let (input, operator) = review(ctx, args, app_config)?;
+            let input = ActionRouterInput::Listing(L::RejectListing(input));
+            return dispatch_as(input, app_config, operator).await;
+        }
+        ListingsCommand::GoLive(args) => L::GoLive(listing_id(ctx, args.listing)?),
+        ListingsCommand::Close(args) => L::CloseListing(listing_id(ctx, args.listing)?),
+        ListingsCommand::Settle(args) => L::SettleListing(listing_id(ctx, args.listing)?),
+        ListingsCommand::Cancel(args) => {
+            let listing = listing_id(ctx, args.listing)?;
+            ctx.confirm(&format!("Cancel listing {}?", listing))?;
+            L::CancelListing(listing)
+        }
+        ListingsCommand::Refund(args) => {
+            let listing = listing_id(ctx, args.listing)?;
+            ctx.confirm(&format!("Refund every purchaser of listing {}?", listing))?;
+            L::RefundListing(listing)
+        }
+        ListingsCommand::Reconcile(args) => L::ReconcileListing(listing_id(ctx, args.listing)?),
+        ListingsCommand::CreateSecondaryMarket(args) => {
+            L::CreateSecondaryMarket(listing_id(ctx, args.listing)?)
+        }
+        ListingsCommand::PayDistribution { distribution } => {
+            L::PayDistribution(ctx.uuid(distribution, "<distribution>", "Distribution ID")?)
+        }
+        ListingsCommand::SetStatus { listing, status } => {
+            L::UpdateStatus(UpdateListingStatusInputArgs {
+                listing_id: listing_id(ctx, listing)?,
+                status: ctx.choice(status, "status", "Status", &LISTING_STATUSES)?,
+            })
+        }
+    };
+
+    dispatch(ActionRouterInput::Listing(input), app_config).await
+}
@@ -0,0 +1,190 @@
+//! `cradle`: one admin CLI for every processor, e.g. `cradle markets list`.
+//! Each subcommand takes its values as flags, prompts for whatever's missing
+//! when run at a terminal, and goes through the action router, the same as
+//! `POST /process`.
+
+mod accounts;
+mod aggregate;
+mod assets;
+mod book;
+mod context;
+mod db;
+mod doctor;
+mod export;
+mod import;
+mod keys;
+mod listings;
+mod markets;
+mod operators;
+mod orders;
+mod pools;
+mod reconcile;
+mod smoke;
+mod top;
+
+use std::process::ExitCode;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use serde_json::Value;
+
+use cradle_back_end::accounts::db_types::AdminOperatorRecord;
+use cradle_back_end::action_router::{ActionRouterInput, ActionRouterOutput};
+use cradle_back_end::cli_helper::{call_action_router, initialize_app_config};
+use cradle_back_end::utils::app_config::AppConfig;
+
+use context::{CliContext, exit_code};
+
+#[derive(Parser)]
+#[command(name = "cradle", about = "Cradle platform administration")]
+struct Cli {
+    /// Print the result as JSON, in the API's response shape, and never
+    /// prompt
+    #[arg(long, global = true)]
+    json: bool,
+    /// Answer yes to confirmations and never prompt; missing values are
+    /// errors
+    #[arg(long, short = 'y', global = true)]
+    yes: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Asset book
+    #[command(subcommand)]
+    Assets(assets::AssetsCommand),
+    /// Markets, their status, bands and scheduled changes
+    #[command(subcommand)]
+    Markets(markets::MarketsCommand),
+    /// Order book
+    #[command(subcommand)]
+    Orders(orders::OrdersCommand),
+    /// Order book depth and trades, read straight from the database
+    #[command(subcommand)]
+    Book(book::BookCommand),
+    /// Lending pools
+    #[command(subcommand)]
+    Pools(pools::PoolsCommand),
+    /// Native listings
+    #[command(subcommand)]
+    Listings(listings::ListingsCommand),
+    /// Cradle accounts and wallets
+    #[command(subcommand)]
+    Accounts(accounts::AccountsCommand),
+    /// Admin dashboard operators and their API keys
+    #[command(subcommand)]
+    Operators(operators::OperatorsCommand),
+    /// Candle aggregation
+    #[command(subcommand)]
+    Aggregate(aggregate::AggregateCommand),
+    /// Create assets or accounts in bulk from a CSV file
+    #[command(subcommand)]
+    Import(import::ImportCommand),
+    /// Live dashboard of markets, trades, lending pools and aggregation
+    Top(top::TopArgs),
+    /// Copy orders, trades and candles to CSV, JSON lines or parquet files
+    Export(export::ExportArgs),
+    /// Run the critical path once against this environment, e.g. after a
+    /// deploy
+    Smoke(smoke::SmokeArgs),
+    /// Check on-chain balances against the database
+    #[command(subcommand)]
+    Reconcile(reconcile::ReconcileCommand),
+    /// The operator account's key, and rotating it
+    #[command(subcommand)]
+    Keys(keys::KeysCommand),
+    /// Database migrations
+    Db(db::DbArgs),
+    /// Check this environment's configuration before starting the server
+    Doctor,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let ctx = CliContext::new(cli.json, cli.yes);
+
+    match run(cli.command, &ctx).await {
+        Ok(value) => {
+            ctx.print_result(&value);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            ctx.print_failure(&e);
+            exit_code(&e)
+        }
+    }
+}
+
+async fn run(command: Command, ctx: &CliContext) -> Result<Value> {
+    // Migrations only need the database and the doctor checks the
+    // environment itself; both often run before the rest of it is set up.
+    // Generating a key needs nothing at all.
+    match command {
+        Command::Db(args) => return db::run(args, ctx),
+        Command::Doctor => return doctor::run(ctx).await,
+        Command::Keys(keys::KeysCommand::Generate { out, ecdsa }) => {
+            return keys::generate(&out, ecdsa);
+        }
+        _ => {}
+    }
+    let app_config = initialize_app_config()?;
+
+    match command {
+        Command::Assets(cmd) => assets::run(cmd, ctx, &app_config).await,
+        Command::Markets(cmd) => markets::run(cmd, ctx, &app_config).await,
+        Command::Orders(cmd) => orders::run(cmd, ctx, &app_config).await,
+        Command::Book(cmd) => book::run(cmd, ctx, &app_config).await,
+        Command::Pools(cmd) => pools::run(cmd, ctx, &app_config).await,
+        Command::Listings(cmd) => listings::run(cmd, ctx, &app_config).await,
+        Command::Accounts(cmd) => accounts::run(cmd, ctx, &app_config).await,
+        Command::Operators(cmd) => operators::run(cmd, ctx, &app_config).await,
+        Command::Import(cmd) => import::run(cmd, ctx, &app_config).await,
+        Command::Aggregate(cmd) => aggregate::run(cmd, ctx, &app_config).await,
+        Command::Top(args) => top::run(args, ctx, &app_config).await,
+        Command::Export(args) => export::run(args, ctx, &app_config).await,
+        Command::Reconcile(cmd) => reconcile::run(cmd, ctx, &app_config).await,
+        Command::Smoke(args) => smoke::run(args, ctx, &app_config).await,
+        Command::Keys(cmd) => keys::run(cmd, ctx, &app_config).await,
+        Command::Db(_) | Command::Doctor => unreachable!("handled before loading the config"),
+    }
+}
+
+/// What a router output carries, without the processor and action tags,
+/// e.g. the record for `{"Markets": {"GetMarket": {...}}}`. Outputs that
+/// carry nothing come back as null.
+pub fn payload(output: &ActionRouterOutput) -> Value {
+    let mut value = serde_json::to_value(output).unwrap_or(Value::Null);
+    for _ in 0..2 {
+        value = match value {
+            Value::Object(map) if map.len() == 1 => map
+                .into_iter()
+                .next()
+                .map(|(_, v)| v)
+                .unwrap_or(Value::Null),
+            Value::String(_) => Value::Null,
+            other => other,
+        };
+    }
+    value
+}
+
+/// Send `input` through the router and return what it carries.
+pub async fn dispatch(input: ActionRouterInput, app_config: &AppConfig) -> Result<Value> {
+    let output = call_action_router(input, &mut app_config.clone()).await?;
+    Ok(payload(&output))
+}
+
+/// `dispatch` as an operator, for actions like approvals that need one.
+pub async fn dispatch_as(
+    input: ActionRouterInput,
+    app_config: &AppConfig,
+    operator: AdminOperatorRecord,
+) -> Result<Value> {
+    let output = input
+        .process_as(&mut app_config.clone(), Some(operator))
+        .await?;
+    Ok(payload(&output))
+}
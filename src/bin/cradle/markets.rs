@@ -0,0 +1,293 @@
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use clap::Subcommand;
+use serde_json::Value;
+use uuid::Uuid;
+
+use cradle_back_end::action_router::ActionRouterInput;
+use cradle_back_end::cli_utils::CliError;
+use cradle_back_end::market::db_types::{CreateMarket, CreateMarketStatusSchedule};
+use cradle_back_end::market::processor_enums::{
+    GetMarketsFilter, MarketProcessorInput, UpdateMarketDisplayInputArgs,
+    UpdateMarketPriceBandInputArgs, UpdateMarketRegulationInputArgs, UpdateMarketStatusInputArgs,
+    UpdateMarketTypeInputArgs,
+};
+use cradle_back_end::market::symbols::find_by_symbol;
+use cradle_back_end::utils::app_config::AppConfig;
+
+use crate::context::CliContext;
+use crate::dispatch;
+
+pub const MARKET_TYPES: [&str; 3] = ["spot", "derivative", "futures"];
+pub const MARKET_STATUSES: [&str; 3] = ["active", "inactive", "suspended"];
+pub const REGULATIONS: [&str; 2] = ["regulated", "unregulated"];
+
+#[derive(Subcommand)]
+pub enum MarketsCommand {
+    /// Open a market between two assets on the book
+    Create {
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        description: Option<String>,
+        #[arg(long)]
+        icon: Option<String>,
+        /// Base asset id
+        #[arg(long)]
+        base: Option<Uuid>,
+        /// Quote asset id
+        #[arg(long)]
+        quote: Option<Uuid>,
+        /// spot (default), derivative or futures
+        #[arg(long = "type")]
+        market_type: Option<String>,
+        /// active (default), inactive or suspended
+        #[arg(long)]
+        status: Option<String>,
+        /// regulated or unregulated (default)
+        #[arg(long)]
+        regulation: Option<String>,
+        #[arg(long)]
+        price_precision: Option<i64>,
+        #[arg(long)]
+        quantity_precision: Option<i64>,
+        /// e.g. cBTC/cUSD; made from the asset symbols when left out
+        #[arg(long)]
+        display_symbol: Option<String>,
+        /// e.g. CBTC-CUSD; made from the asset symbols when left out
+        #[arg(long)]
+        symbol: Option<String>,
+    },
+    /// Show one market
+    Get {
+        /// Market id or symbol
+        market: Option<String>,
+    },
+    /// List markets, optionally by status
+    List {
+        /// active, inactive, suspended or all
+        #[arg(long)]
+        status: Option<String>,
+    },
+    /// Activate, deactivate or suspend a market
+    SetStatus {
+        market: Option<String>,
+        /// active, inactive or suspended
+        #[arg(long)]
+        status: Option<String>,
+    },
+    /// Make a market spot, derivative or futures
+    SetType {
+        market: Option<String>,
+        #[arg(long = "type")]
+        market_type: Option<String>,
+    },
+    /// Make a market regulated or unregulated
+    SetRegulation {
+        market: Option<String>,
+        #[arg(long)]
+        regulation: Option<String>,
+    },
+    /// Set the price band regulated markets hold limit orders to
+    SetBand {
+        market: Option<String>,
+        /// Percent either side of the reference
+        #[arg(long)]
+        percent: Option<BigDecimal>,
+        /// Fixed reference; left out, the band follows recent trades
+        #[arg(long)]
+        reference_price: Option<BigDecimal>,
+    },
+    /// Change the precision or display symbol
+    SetDisplay {
+        market: Option<String>,
+        #[arg(long)]
+        price_precision: Option<i64>,
+        #[arg(long)]
+        quantity_precision: Option<i64>,
+        #[arg(long)]
+        display_symbol: Option<String>,
+    },
+    /// Change a market's status at a given time
+    ScheduleStatus {
+        market: Option<String>,
+        /// active, inactive or suspended
+        #[arg(long)]
+        status: Option<String>,
+        /// UTC, "YYYY-MM-DD HH:MM"
+        #[arg(long, value_parser = parse_time)]
+        at: Option<NaiveDateTime>,
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Cancel a pending scheduled status change
+    CancelScheduled {
+        /// Scheduled change id
+        id: Option<Uuid>,
+    },
+    /// List a market's scheduled status changes
+    Scheduled { market: Option<String> },
+}
+
+/// A UTC time as "YYYY-MM-DD HH:MM" or "YYYY-MM-DDTHH:MM:SS".
+pub fn parse_time(raw: &str) -> std::result::Result<NaiveDateTime, String> {
+    let raw = raw.trim();
+    NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M")
+        .or_else(|_| NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S"))
+        .map_err(|_| format!("expected YYYY-MM-DD HH:MM (UTC), got {}", raw))
+}
+
+/// A market id from a UUID or symbol, e.g. "CBTC-CUSD".
+pub fn market_arg(ctx: &CliContext, app_config: &AppConfig, given: Option<String>) -> Result<Uuid> {
+    let given = ctx.string(given, "<market>", "Market (id or symbol)")?;
+    resolve(app_config, &given)
+}
+
+pub fn optional_market_arg(
+    ctx: &CliContext,
+    app_config: &AppConfig,
+    given: Option<String>,
+) -> Result<Option<Uuid>> {
+    match ctx.optional_string(given, "Market (id or symbol)")? {
+        Some(given) => Ok(Some(resolve(app_config, &given)?)),
+        None => Ok(None),
+    }
+}
+
+fn resolve(app_config: &AppConfig, given: &str) -> Result<Uuid> {
+    if let Ok(id) = Uuid::parse_str(given.trim()) {
+        return Ok(id);
+    }
+    let mut conn = app_config.pool.get()?;
+    match find_by_symbol(&mut conn, given)? {
+        Some(market) => Ok(market.id),
+        None => Err(CliError::NotFound(format!("market {}", given)).into()),
+    }
+}
+
+fn scheduled_at(ctx: &CliContext, given: Option<NaiveDateTime>) -> Result<NaiveDateTime> {
+    let raw = match given {
+        Some(at) => return Ok(at),
+        None => ctx.string(None, "at", "Change at (UTC, YYYY-MM-DD HH:MM)")?,
+    };
+    parse_time(&raw).map_err(|e| CliError::ValidationError(e).into())
+}
+
+pub async fn run(cmd: MarketsCommand, ctx: &CliContext, app_config: &AppConfig) -> Result<Value> {
+    let input = match cmd {
+        MarketsCommand::Create {
+            name,
+            description,
+            icon,
+            base,
+            quote,
+            market_type,
+            status,
+            regulation,
+            price_precision,
+            quantity_precision,
+            display_symbol,
+            symbol,
+        } => MarketProcessorInput::CreateMarket(CreateMarket {
+            name: ctx.string(name, "name", "Market name")?,
+            description: ctx.optional_string(description, "Description")?,
+            icon: ctx.optional_string(icon, "Icon URL")?,
+            asset_one: ctx.uuid(base, "base", "Base asset ID")?,
+            asset_two: ctx.uuid(quote, "quote", "Quote asset ID")?,
+            market_type: ctx.optional_choice(market_type, "type", "Market type", &MARKET_TYPES)?,
+            market_status: ctx.optional_choice(status, "status", "Status", &MARKET_STATUSES)?,
+            market_regulation: ctx.optional_choice(
+                regulation,
+                "regulation",
+                "Regulation",
+                &REGULATIONS,
+            )?,
+            price_precision: ctx
+                .optional_i64(price_precision, "Price precision")?
+                .map(|p| p as i32),
+            quantity_precision: ctx
+                .optional_i64(quantity_precision, "Quantity precision")?
+                .map(|p| p as i32),
+            display_symbol: ctx.optional_string(display_symbol, "Display symbol")?,
+            symbol: ctx.optional_string(symbol, "Symbol")?,
+        }),
+        MarketsCommand::Get { market } => {
+            MarketProcessorInput::GetMarket(market_arg(ctx, app_config, market)?)
+        }
+        MarketsCommand::List { status } => MarketProcessorInput::GetMarkets(GetMarketsFilter {
+            status: ctx.filter_choice(status, "status", "Status", &MARKET_STATUSES)?,
+            market_type: None,
+            regulation: None,
+        }),
+        MarketsCommand::SetStatus { market, status } => {
+            MarketProcessorInput::UpdateMarketStatus(UpdateMarketStatusInputArgs {
+                market_id: market_arg(ctx, app_config, market)?,
+                status: ctx.choice(status, "status", "Status", &MARKET_STATUSES)?,
+            })
+        }
+        MarketsCommand::SetType {
+            market,
+            market_type,
+        } => MarketProcessorInput::UpdateMarketType(UpdateMarketTypeInputArgs {
+            market_id: market_arg(ctx, app_config, market)?,
+            market_type: ctx.choice(market_type, "type", "Market type", &MARKET_TYPES)?,
+        }),
+        MarketsCommand::SetRegulation { market, regulation } => {
+            MarketProcessorInput::UpdateMarketRegulation(UpdateMarketRegulationInputArgs {
+                market_id: market_arg(ctx, app_config, market)?,
+                regulation: ctx.choice(regulation, "regulation", "Regulation", &REGULATIONS)?,
+            })
+        }
+        MarketsCommand::SetBand {
+            market,
+            percent,
+            reference_price,
+        } => MarketProcessorInput::UpdateMarketPriceBand(UpdateMarketPriceBandInputArgs {
+            market_id: market_arg(ctx, app_config, market)?,
+            price_band_percent: ctx.decimal(percent, "percent", "Band, percent either side")?,
+            reference_price: ctx.optional_decimal(
+                reference_price,
+                "Fixed reference price (blank to follow trades)",
+            )?,
+        }),
+        MarketsCommand::SetDisplay {
+            market,
+            price_precision,
+            quantity_precision,
+            display_symbol,
+        } => MarketProcessorInput::UpdateMarketDisplay(UpdateMarketDisplayInputArgs {
+            market_id: market_arg(ctx, app_config, market)?,
+            price_precision: ctx
+                .optional_i64(price_precision, "Price precision")?
+                .map(|p| p as i32),
+            quantity_precision: ctx
+                .optional_i64(quantity_precision, "Quantity precision")?
+                .map(|p| p as i32),
+            display_symbol: ctx.optional_string(display_symbol, "Display symbol")?,
+        }),
+        MarketsCommand::ScheduleStatus {
+            market,
+            status,
+            at,
+            reason,
+        } => MarketProcessorInput::ScheduleMarketStatus(CreateMarketStatusSchedule {
+            market_id: market_arg(ctx, app_config, market)?,
+            target_status: ctx.choice(status, "status", "Status", &MARKET_STATUSES)?,
+            execute_at: scheduled_at(ctx, at)?,
+            reason: ctx.optional_string(reason, "Reason")?,
+        }),
+        MarketsCommand::CancelScheduled { id } => {
+            MarketProcessorInput::CancelScheduledMarketStatus(ctx.uuid(
+                id,
+                "<id>",
+                "Scheduled change ID",
+            )?)
+        }
+        MarketsCommand::Scheduled { market } => {
+            MarketProcessorInput::GetScheduledMarketStatuses(market_arg(ctx, app_config, market)?)
+        }
+    };
+
+    dispatch(ActionRouterInput::Markets(input), app_config).await
+}
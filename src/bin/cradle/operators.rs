@@ -0,0 +1,81 @@
+//! `cradle operators`: people allowed into the admin dashboard, and the API
+//! keys they send as X-Operator-Key.
+
+use anyhow::Result;
+use clap::Subcommand;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use cradle_back_end::accounts::db_types::AdminRole;
+use cradle_back_end::accounts::operators::{create_operator, issue_api_key};
+use cradle_back_end::cli_utils::{CliError, print_info};
+use cradle_back_end::utils::app_config::AppConfig;
+
+use crate::context::CliContext;
+
+pub const ADMIN_ROLES: [&str; 3] = ["viewer", "operator", "approver"];
+
+#[derive(Subcommand)]
+pub enum OperatorsCommand {
+    /// Let someone sign in to the admin dashboard as a system account
+    Add {
+        /// The system account the operator acts as
+        #[arg(long)]
+        account: Option<Uuid>,
+        #[arg(long)]
+        username: Option<String>,
+        /// viewer, operator or approver
+        #[arg(long)]
+        role: Option<String>,
+    },
+    /// Issue an operator a new API key, replacing any earlier one
+    IssueKey {
+        /// Operator username
+        username: Option<String>,
+    },
+}
+
+/// The new operator's password, from CRADLE_NEW_OPERATOR_PASSWORD or typed
+/// twice at the prompt.
+fn new_password(ctx: &CliContext) -> Result<String> {
+    match std::env::var("CRADLE_NEW_OPERATOR_PASSWORD") {
+        Ok(password) => Ok(password),
+        Err(_) if ctx.is_interactive() => Ok(dialoguer::Password::new()
+            .with_prompt("Password")
+            .with_confirmation("Repeat password", "Passwords don't match")
+            .interact()?),
+        Err(_) => Err(CliError::ValidationError(
+            "Set CRADLE_NEW_OPERATOR_PASSWORD to add an operator".to_string(),
+        )
+        .into()),
+    }
+}
+
+pub async fn run(cmd: OperatorsCommand, ctx: &CliContext, app_config: &AppConfig) -> Result<Value> {
+    match cmd {
+        OperatorsCommand::Add {
+            account,
+            username,
+            role,
+        } => {
+            let account = ctx.uuid(account, "account", "System account the operator acts as")?;
+            let username = ctx.string(username, "username", "Username")?;
+            let role: AdminRole = ctx.choice(role, "role", "Role", &ADMIN_ROLES)?;
+            let password = new_password(ctx)?;
+
+            let mut conn = app_config.pool.get()?;
+            let operator = create_operator(&mut conn, account, &username, &password, role)?;
+            Ok(serde_json::to_value(operator)?)
+        }
+        OperatorsCommand::IssueKey { username } => {
+            let username = ctx.string(username, "<username>", "Operator username")?;
+
+            let mut conn = app_config.pool.get()?;
+            let key = issue_api_key(&mut conn, &username)?;
+            print_info(
+                "Send this as X-Operator-Key; it replaces any earlier key and won't be shown again",
+            );
+            Ok(json!({ "api_key": key }))
+        }
+    }
+}
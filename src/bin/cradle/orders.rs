@@ -0,0 +1,138 @@
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use clap::Subcommand;
+use serde_json::Value;
+use uuid::Uuid;
+
+use cradle_back_end::action_router::ActionRouterInput;
+use cradle_back_end::order_book::db_types::NewOrderBookRecord;
+use cradle_back_end::order_book::processor_enums::{
+    CancelOrderInputArgs, GetOrdersFilter, ModifyOrderInputArgs, OrderBookProcessorInput,
+};
+use cradle_back_end::utils::app_config::AppConfig;
+
+use crate::context::CliContext;
+use crate::dispatch;
+use crate::markets::{market_arg, optional_market_arg};
+
+pub const FILL_MODES: [&str; 3] = ["good-till-cancel", "immediate-or-cancel", "fill-or-kill"];
+pub const ORDER_TYPES: [&str; 2] = ["limit", "market"];
+pub const ORDER_STATUSES: [&str; 3] = ["open", "closed", "cancelled"];
+
+#[derive(Subcommand)]
+pub enum OrdersCommand {
+    /// Place an order for a wallet
+    Place {
+        #[arg(long)]
+        wallet: Option<Uuid>,
+        /// Market id or symbol
+        #[arg(long)]
+        market: Option<String>,
+        /// Asset the wallet receives
+        #[arg(long)]
+        bid_asset: Option<Uuid>,
+        /// Asset the wallet gives
+        #[arg(long)]
+        ask_asset: Option<Uuid>,
+        #[arg(long)]
+        bid_amount: Option<BigDecimal>,
+        #[arg(long)]
+        ask_amount: Option<BigDecimal>,
+        #[arg(long)]
+        price: Option<BigDecimal>,
+        /// good-till-cancel (default), immediate-or-cancel or fill-or-kill
+        #[arg(long)]
+        mode: Option<String>,
+        /// limit (default) or market
+        #[arg(long = "type")]
+        order_type: Option<String>,
+    },
+    /// Show one order
+    Get { id: Option<Uuid> },
+    /// List orders by wallet, market and status
+    List {
+        #[arg(long)]
+        wallet: Option<Uuid>,
+        /// Market id or symbol
+        #[arg(long)]
+        market: Option<String>,
+        /// open, closed, cancelled or all
+        #[arg(long)]
+        status: Option<String>,
+    },
+    /// Cancel an open order
+    Cancel { id: Option<Uuid> },
+    /// Change an open order's price or amounts
+    Modify {
+        id: Option<Uuid>,
+        #[arg(long)]
+        price: Option<BigDecimal>,
+        #[arg(long)]
+        bid_amount: Option<BigDecimal>,
+        #[arg(long)]
+        ask_amount: Option<BigDecimal>,
+    },
+}
+
+pub async fn run(cmd: OrdersCommand, ctx: &CliContext, app_config: &AppConfig) -> Result<Value> {
+    let input = match cmd {
+        OrdersCommand::Place {
+            wallet,
+            market,
+            bid_asset,
+            ask_asset,
+            bid_amount,
+            ask_amount,
+            price,
+            mode,
+            order_type,
+        } => OrderBookProcessorInput::PlaceOrder(NewOrderBookRecord {
+            wallet: ctx.uuid(wallet, "wallet", "Wallet ID")?,
+            market_id: market_arg(ctx, app_config, market)?,
+            bid_asset: ctx.uuid(bid_asset, "bid-asset", "Bid asset ID (asset received)")?,
+            ask_asset: ctx.uuid(ask_asset, "ask-asset", "Ask asset ID (asset given)")?,
+            bid_amount: ctx.decimal(bid_amount, "bid-amount", "Bid amount")?,
+            ask_amount: ctx.decimal(ask_amount, "ask-amount", "Ask amount")?,
+            price: ctx.decimal(price, "price", "Price")?,
+            mode: ctx.optional_choice(mode, "mode", "Fill mode", &FILL_MODES)?,
+            expires_at: None,
+            order_type: ctx.optional_choice(order_type, "type", "Order type", &ORDER_TYPES)?,
+        }),
+        OrdersCommand::Get { id } => {
+            OrderBookProcessorInput::GetOrder(ctx.uuid(id, "<id>", "Order ID")?)
+        }
+        OrdersCommand::List {
+            wallet,
+            market,
+            status,
+        } => OrderBookProcessorInput::GetOrders(GetOrdersFilter {
+            wallet: ctx.optional_uuid(wallet, "Wallet ID")?,
+            market_id: optional_market_arg(ctx, app_config, market)?,
+            status: ctx.filter_choice(status, "status", "Status", &ORDER_STATUSES)?,
+            order_type: None,
+            mode: None,
+        }),
+        OrdersCommand::Cancel { id } => {
+            let order_id = ctx.uuid(id, "<id>", "Order ID")?;
+            ctx.confirm(&format!("Cancel order {}?", order_id))?;
+            OrderBookProcessorInput::CancelOrder(CancelOrderInputArgs {
+                order_id,
+                wallet: None,
+            })
+        }
+        OrdersCommand::Modify {
+            id,
+            price,
+            bid_amount,
+            ask_amount,
+        } => OrderBookProcessorInput::ModifyOrder(ModifyOrderInputArgs {
+            order_id: ctx.uuid(id, "<id>", "Order ID")?,
+            wallet: None,
+            price: ctx.optional_decimal(price, "New price")?,
+            bid_amount: ctx.optional_decimal(bid_amount, "New bid amount")?,
+            ask_amount: ctx.optional_decimal(ask_amount, "New ask amount")?,
+        }),
+    };
+
+    dispatch(ActionRouterInput::OrderBook(input), app_config).await
+}
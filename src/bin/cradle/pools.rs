@@ -0,0 +1,284 @@
+use anyhow::Result;
+use clap::Subcommand;
+use serde_json::Value;
+use uuid::Uuid;
+
+use cradle_back_end::action_router::ActionRouterInput;
+use cradle_back_end::lending_pool::operations::{
+    CreateLendingPoolArgs, CreateNewYieldAsset, YieldAsset,
+};
+use cradle_back_end::lending_pool::processor_enums::{
+    DeployLendingPoolInputArgs, GetLendingPoolInput, LendingPoolFunctionsInput,
+    LiquidatePositionInputArgs, RepayLoanInputArgs, SupplyLiquidityInputArgs, TakeLoanInputArgs,
+    WithdrawLiquidityInputArgs,
+};
+use cradle_back_end::utils::app_config::AppConfig;
+
+use crate::context::CliContext;
+use crate::dispatch;
+
+#[derive(Subcommand)]
+pub enum PoolsCommand {
+    /// Deploy a pool contract through the factory and register it; rates
+    /// and thresholds are in basis points
+    Deploy {
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        reserve_asset: Option<Uuid>,
+        #[arg(long)]
+        ltv: Option<u64>,
+        #[arg(long)]
+        optimal_utilization: Option<u64>,
+        #[arg(long)]
+        base_rate: Option<u64>,
+        #[arg(long)]
+        slope_1: Option<u64>,
+        #[arg(long)]
+        slope_2: Option<u64>,
+        #[arg(long)]
+        liquidation_threshold: Option<u64>,
+        #[arg(long)]
+        liquidation_discount: Option<u64>,
+        #[arg(long)]
+        reserve_factor: Option<u64>,
+        /// In reserve base units; uncapped when left out
+        #[arg(long)]
+        supply_cap: Option<u64>,
+        /// In reserve base units; uncapped when left out
+        #[arg(long)]
+        borrow_cap: Option<u64>,
+        /// Use this asset as the yield token instead of issuing one
+        #[arg(long)]
+        yield_asset: Option<Uuid>,
+        #[arg(long, conflicts_with = "yield_asset")]
+        yield_name: Option<String>,
+        #[arg(long, conflicts_with = "yield_asset")]
+        yield_symbol: Option<String>,
+        #[arg(long, conflicts_with = "yield_asset")]
+        yield_decimals: Option<i64>,
+        #[arg(long, conflicts_with = "yield_asset")]
+        yield_icon: Option<String>,
+    },
+    /// Look a pool up by id, name or address
+    Get {
+        id: Option<Uuid>,
+        #[arg(long, conflicts_with = "id")]
+        name: Option<String>,
+        #[arg(long, conflicts_with_all = ["id", "name"])]
+        address: Option<String>,
+    },
+    /// Record a snapshot of a pool's on-chain state
+    Snapshot { pool: Option<Uuid> },
+    /// Show a pool's latest snapshot
+    LatestSnapshot { pool: Option<Uuid> },
+    /// Supply reserve tokens from a wallet
+    Supply {
+        #[arg(long)]
+        wallet: Option<Uuid>,
+        #[arg(long)]
+        pool: Option<Uuid>,
+        /// Reserve base units
+        #[arg(long)]
+        amount: Option<u64>,
+    },
+    /// Withdraw liquidity, in yield tokens
+    Withdraw {
+        #[arg(long)]
+        wallet: Option<Uuid>,
+        #[arg(long)]
+        pool: Option<Uuid>,
+        /// Yield token base units
+        #[arg(long)]
+        amount: Option<u64>,
+    },
+    /// Borrow against collateral
+    Borrow {
+        #[arg(long)]
+        wallet: Option<Uuid>,
+        #[arg(long)]
+        pool: Option<Uuid>,
+        /// Reserve base units
+        #[arg(long)]
+        amount: Option<u64>,
+        /// Collateral asset id
+        #[arg(long)]
+        collateral: Option<Uuid>,
+    },
+    /// Repay a loan
+    Repay {
+        #[arg(long)]
+        wallet: Option<Uuid>,
+        #[arg(long)]
+        loan: Option<Uuid>,
+        /// Reserve base units
+        #[arg(long)]
+        amount: Option<u64>,
+    },
+    /// Cover an unhealthy loan's debt and take its collateral
+    Liquidate {
+        /// Liquidator's wallet
+        #[arg(long)]
+        wallet: Option<Uuid>,
+        #[arg(long)]
+        loan: Option<Uuid>,
+        /// Reserve base units
+        #[arg(long)]
+        amount: Option<u64>,
+    },
+}
+
+pub async fn run(cmd: PoolsCommand, ctx: &CliContext, app_config: &AppConfig) -> Result<Value> {
+    let input = match cmd {
+        PoolsCommand::Deploy {
+            name,
+            reserve_asset,
+            ltv,
+            optimal_utilization,
+            base_rate,
+            slope_1,
+            slope_2,
+            liquidation_threshold,
+            liquidation_discount,
+            reserve_factor,
+            supply_cap,
+            borrow_cap,
+            yield_asset,
+            yield_name,
+            yield_symbol,
+            yield_decimals,
+            yield_icon,
+        } => {
+            let pool = CreateLendingPoolArgs {
+                name: ctx.string(name, "name", "Pool name")?,
+                reserve_asset: ctx.uuid(reserve_asset, "reserve-asset", "Reserve asset ID")?,
+                ltv: ctx.u64(ltv, "ltv", "LTV (bps)")?,
+                optimal_utilization: ctx.u64(
+                    optimal_utilization,
+                    "optimal-utilization",
+                    "Optimal utilization (bps)",
+                )?,
+                base_rate: ctx.u64(base_rate, "base-rate", "Base rate (bps)")?,
+                slope_1: ctx.u64(slope_1, "slope-1", "Slope 1 (bps)")?,
+                slope_2: ctx.u64(slope_2, "slope-2", "Slope 2 (bps)")?,
+                liquidation_threshold: ctx.u64(
+                    liquidation_threshold,
+                    "liquidation-threshold",
+                    "Liquidation threshold (bps)",
+                )?,
+                liquidation_discount: ctx.u64(
+                    liquidation_discount,
+                    "liquidation-discount",
+                    "Liquidation discount (bps)",
+                )?,
+                reserve_factor: ctx.u64(
+                    reserve_factor,
+                    "reserve-factor",
+                    "Reserve factor (bps)",
+                )?,
+                supply_cap: ctx.optional_u64(supply_cap, "Supply cap (base units)")?,
+                borrow_cap: ctx.optional_u64(borrow_cap, "Borrow cap (base units)")?,
+            };
+            let existing = if yield_name.is_some() {
+                None
+            } else {
+                ctx.optional_uuid(yield_asset, "Existing yield asset ID (blank to issue one)")?
+            };
+            let yield_asset = match existing {
+                Some(asset) => YieldAsset::Existing(asset),
+                None => YieldAsset::New(CreateNewYieldAsset {
+                    name: ctx.string(yield_name, "yield-name", "Yield asset name")?,
+                    symbol: ctx.string(yield_symbol, "yield-symbol", "Yield asset symbol")?,
+                    decimals: ctx
+                        .optional_i64(yield_decimals, "Yield asset decimals")?
+                        .map(|d| d as i32),
+                    icon: ctx.optional_string(yield_icon, "Yield asset icon URL")?,
+                }),
+            };
+            LendingPoolFunctionsInput::DeployLendingPool(DeployLendingPoolInputArgs {
+                pool,
+                yield_asset,
+            })
+        }
+        PoolsCommand::Get { id, name, address } => {
+            let query = match (id, name, address) {
+                (Some(id), _, _) => GetLendingPoolInput::ById(id),
+                (_, Some(name), _) => GetLendingPoolInput::ByName(name),
+                (_, _, Some(address)) => GetLendingPoolInput::ByAddress(address),
+                _ => match ctx.pick(
+                    "an id, --name or --address",
+                    "Look up by",
+                    vec!["ID", "Name", "Address"],
+                )? {
+                    0 => GetLendingPoolInput::ById(ctx.uuid(None, "<id>", "Pool ID")?),
+                    1 => GetLendingPoolInput::ByName(ctx.string(None, "name", "Pool name")?),
+                    _ => GetLendingPoolInput::ByAddress(ctx.string(
+                        None,
+                        "address",
+                        "Pool address",
+                    )?),
+                },
+            };
+            LendingPoolFunctionsInput::GetLendingPool(query)
+        }
+        PoolsCommand::Snapshot { pool } => {
+            LendingPoolFunctionsInput::CreateSnapShot(ctx.uuid(pool, "<pool>", "Pool ID")?)
+        }
+        PoolsCommand::LatestSnapshot { pool } => {
+            LendingPoolFunctionsInput::GetSnapShot(ctx.uuid(pool, "<pool>", "Pool ID")?)
+        }
+        PoolsCommand::Supply {
+            wallet,
+            pool,
+            amount,
+        } => LendingPoolFunctionsInput::SupplyLiquidity(SupplyLiquidityInputArgs {
+            wallet: ctx.uuid(wallet, "wallet", "Wallet ID")?,
+            pool: ctx.uuid(pool, "pool", "Pool ID")?,
+            amount: ctx.u64(amount, "amount", "Amount (reserve base units)")?,
+        }),
+        PoolsCommand::Withdraw {
+            wallet,
+            pool,
+            amount,
+        } => LendingPoolFunctionsInput::WithdrawLiquidity(WithdrawLiquidityInputArgs {
+            wallet: ctx.uuid(wallet, "wallet", "Wallet ID")?,
+            pool: ctx.uuid(pool, "pool", "Pool ID")?,
+            amount: ctx.u64(amount, "amount", "Amount (yield token base units)")?,
+        }),
+        PoolsCommand::Borrow {
+            wallet,
+            pool,
+            amount,
+            collateral,
+        } => LendingPoolFunctionsInput::BorrowAsset(TakeLoanInputArgs {
+            wallet: ctx.uuid(wallet, "wallet", "Wallet ID")?,
+            pool: ctx.uuid(pool, "pool", "Pool ID")?,
+            amount: ctx.u64(amount, "amount", "Amount (reserve base units)")?,
+            collateral: ctx.uuid(collateral, "collateral", "Collateral asset ID")?,
+        }),
+        PoolsCommand::Repay {
+            wallet,
+            loan,
+            amount,
+        } => LendingPoolFunctionsInput::RepayBorrow(RepayLoanInputArgs {
+            wallet: ctx.uuid(wallet, "wallet", "Wallet ID")?,
+            loan: ctx.uuid(loan, "loan", "Loan ID")?,
+            amount: ctx.u64(amount, "amount", "Amount (reserve base units)")?,
+        }),
+        PoolsCommand::Liquidate {
+            wallet,
+            loan,
+            amount,
+        } => {
+            let args = LiquidatePositionInputArgs {
+                wallet: ctx.uuid(wallet, "wallet", "Liquidator wallet ID")?,
+                loan: ctx.uuid(loan, "loan", "Loan ID")?,
+                amount: ctx.u64(amount, "amount", "Amount (reserve base units)")?,
+            };
+            ctx.confirm(&format!("Liquidate loan {}?", args.loan))?;
+            LendingPoolFunctionsInput::LiquidatePosition(args)
+        }
+    };
+
+    dispatch(ActionRouterInput::Pool(input), app_config).await
+}
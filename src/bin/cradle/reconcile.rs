@@ -0,0 +1,165 @@
+//! `cradle reconcile`: compare what the chain holds with what the database
+//! expects, for alerting as much as for looking at.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, anyhow};
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use clap::{Subcommand, ValueEnum};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use cradle_back_end::accounts_ledger::reconciliation::{
+    BalanceCheck, Severity, check_wallet, wallets,
+};
+use cradle_back_end::cli_utils::{format_table, print_info, print_success};
+use cradle_back_end::utils::app_config::AppConfig;
+
+use crate::context::CliContext;
+
+#[derive(Subcommand)]
+pub enum ReconcileCommand {
+    /// Every wallet's on-chain balances against its locks and ledger
+    Balances {
+        /// Check one wallet instead of all of them
+        #[arg(long)]
+        wallet: Option<Uuid>,
+        /// Largest difference still treated as matching, in the token's
+        /// smallest unit
+        #[arg(long, default_value = "0")]
+        tolerance: BigDecimal,
+        /// Also write the full report to this file
+        #[arg(long)]
+        report: Option<PathBuf>,
+        /// Fail when anything this severe or worse turns up
+        #[arg(long)]
+        fail_on: Option<FailOn>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum FailOn {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl From<FailOn> for Severity {
+    fn from(value: FailOn) -> Self {
+        match value {
+            FailOn::Info => Severity::Info,
+            FailOn::Warning => Severity::Warning,
+            FailOn::Critical => Severity::Critical,
+        }
+    }
+}
+
+pub async fn run(cmd: ReconcileCommand, ctx: &CliContext, app_config: &AppConfig) -> Result<Value> {
+    match cmd {
+        ReconcileCommand::Balances {
+            wallet,
+            tolerance,
+            report,
+            fail_on,
+        } => balances(ctx, app_config, wallet, tolerance, report, fail_on).await,
+    }
+}
+
+async fn balances(
+    ctx: &CliContext,
+    app_config: &AppConfig,
+    wallet: Option<Uuid>,
+    tolerance: BigDecimal,
+    report: Option<PathBuf>,
+    fail_on: Option<FailOn>,
+) -> Result<Value> {
+    let conn = &mut app_config.pool.get()?;
+    let to_check = wallets(conn, wallet)?;
+
+    let mut checks: Vec<BalanceCheck> = Vec::new();
+    for (i, record) in to_check.iter().enumerate() {
+        if !ctx.json && to_check.len() > 1 {
+            eprint!("\rChecking wallet {}/{}", i + 1, to_check.len());
+        }
+        checks.extend(check_wallet(conn, &app_config.wallet, record, &tolerance).await?);
+    }
+    if !ctx.json && to_check.len() > 1 {
+        eprintln!();
+    }
+
+    let mut discrepancies: Vec<&BalanceCheck> = checks
+        .iter()
+        .filter(|c| c.severity != Severity::Ok)
+        .collect();
+    discrepancies.sort_by(|a, b| b.severity.cmp(&a.severity));
+    let count = |severity: Severity| checks.iter().filter(|c| c.severity == severity).count();
+
+    let result = json!({
+        "generated_at": Utc::now().naive_utc(),
+        "wallets": to_check.len(),
+        "checked": checks.len(),
+        "matched": count(Severity::Ok),
+        "info": count(Severity::Info),
+        "warning": count(Severity::Warning),
+        "critical": count(Severity::Critical),
+        "discrepancies": discrepancies,
+    });
+    if let Some(path) = &report {
+        std::fs::write(path, serde_json::to_string_pretty(&result)?)
+            .with_context(|| format!("Couldn't write {}", path.display()))?;
+    }
+
+    if !ctx.json {
+        if discrepancies.is_empty() {
+            print_success(&format!(
+                "{} balance(s) across {} wallet(s) match",
+                checks.len(),
+                to_check.len()
+            ));
+        } else {
+            format_table(
+                vec![
+                    "Severity", "Wallet", "Asset", "On-chain", "Locked", "Ledger", "Note",
+                ],
+                discrepancies
+                    .iter()
+                    .map(|c| {
+                        vec![
+                            format!("{:?}", c.severity).to_lowercase(),
+                            c.wallet_id.to_string(),
+                            c.symbol.clone(),
+                            c.onchain
+                                .as_ref()
+                                .map(|v| v.to_string())
+                                .unwrap_or_else(|| "-".to_string()),
+                            c.locked.to_string(),
+                            c.ledger_net.to_string(),
+                            c.note.clone(),
+                        ]
+                    })
+                    .collect(),
+            );
+        }
+        if let Some(path) = &report {
+            print_info(&format!("Report written to {}", path.display()));
+        }
+    }
+
+    if let Some(fail_on) = fail_on {
+        let threshold = Severity::from(fail_on);
+        let failing = discrepancies
+            .iter()
+            .filter(|c| c.severity >= threshold)
+            .count();
+        if failing > 0 {
+            return Err(anyhow!(
+                "{} balance(s) at or above {:?}",
+                failing,
+                threshold
+            ));
+        }
+    }
+
+    Ok(if ctx.json { result } else { Value::Null })
+}
@@ -0,0 +1,781 @@
+//! `cradle smoke`: run the critical path once against a deployed environment
+//! and report a pass/fail matrix. It works with real test accounts, tokens
+//! and trades, so point it at a market kept for the purpose.
+
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use anyhow::{Context, Result, anyhow};
+use bigdecimal::{BigDecimal, RoundingMode, ToPrimitive};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use clap::Args;
+use diesel::prelude::*;
+use serde::Serialize;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use cradle_back_end::accounts::db_types::{CradleAccountStatus, CreateCradleAccount};
+use cradle_back_end::accounts::processor_enums::{
+    AccountsProcessorInput, CreateAccountOutputArgs, UpdateAccountStatusInputArgs,
+};
+use cradle_back_end::action_router::ActionRouterInput;
+use cradle_back_end::aggregators::{
+    AggregateTradesInputArgs, AggregatorsConfig, AggregatorsProcessorInput,
+    AggregatorsProcessorOutput,
+};
+use cradle_back_end::asset_book::db_types::AssetBookRecord;
+use cradle_back_end::asset_book::operations::{airdrop_asset, get_asset, mint_asset};
+use cradle_back_end::cli_utils::{CliError, format_table, print_error, print_info, print_success};
+use cradle_back_end::lending_pool::db_types::{LendingPoolRecord, LoanRecord};
+use cradle_back_end::lending_pool::processor_enums::{
+    LendingPoolFunctionsInput, RepayLoanInputArgs, SupplyLiquidityInputArgs, TakeLoanInputArgs,
+};
+use cradle_back_end::market::db_types::MarketRecord;
+use cradle_back_end::market_time_series::db_types::{MarketTimeSeriesRecord, TimeSeriesInterval};
+use cradle_back_end::order_book::db_types::{
+    FillMode, NewOrderBookRecord, OrderBookRecord, OrderBookTradeRecord, OrderStatus, OrderType,
+};
+use cradle_back_end::order_book::processor_enums::{
+    CancelOrderInputArgs, OrderBookProcessorInput, OrderFillResult,
+};
+use cradle_back_end::utils::app_config::AppConfig;
+use cradle_back_end::utils::commons::DbConn;
+use cradle_back_end::utils::traits::ActionProcessor;
+
+use crate::context::CliContext;
+use crate::dispatch;
+use crate::markets::market_arg;
+
+#[derive(Args)]
+pub struct SmokeArgs {
+    /// Market to trade on, by id or symbol
+    #[arg(long)]
+    market: Option<String>,
+    /// Price of the test trade, in quote tokens per base token
+    #[arg(long)]
+    price: Option<BigDecimal>,
+    /// Base tokens to trade
+    #[arg(long, default_value = "1")]
+    quantity: BigDecimal,
+    /// Lending pool to supply to and borrow from, by id or name; the
+    /// lending steps are skipped without one
+    #[arg(long)]
+    pool: Option<String>,
+    /// Reserve tokens to supply
+    #[arg(long, default_value = "10")]
+    supply: BigDecimal,
+    /// Asset to borrow against; defaults to the market's base asset
+    #[arg(long)]
+    collateral: Option<Uuid>,
+    /// Collateral tokens to put up
+    #[arg(long, default_value = "1")]
+    collateral_amount: BigDecimal,
+    /// The server's base URL, to check it answers /health
+    #[arg(long)]
+    url: Option<String>,
+    /// Also write the matrix to this file
+    #[arg(long)]
+    report: Option<PathBuf>,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Outcome {
+    Pass,
+    Fail,
+    /// Not run, because it needs a step that failed or wasn't configured
+    Skip,
+}
+
+#[derive(Serialize)]
+struct Step {
+    name: &'static str,
+    outcome: Outcome,
+    millis: u128,
+    detail: String,
+}
+
+/// The steps so far, each printed as it finishes.
+struct Matrix {
+    steps: Vec<Step>,
+    quiet: bool,
+}
+
+impl Matrix {
+    fn push(&mut self, step: Step) {
+        if !self.quiet {
+            let line = format!("{:<16} {}", step.name, step.detail);
+            match step.outcome {
+                Outcome::Pass => print_success(&line),
+                Outcome::Fail => print_error(&line),
+                Outcome::Skip => eprintln!("- {}", line),
+            }
+        }
+        self.steps.push(step);
+    }
+
+    fn skip(&mut self, name: &'static str, detail: impl Into<String>) {
+        self.push(Step {
+            name,
+            outcome: Outcome::Skip,
+            millis: 0,
+            detail: detail.into(),
+        });
+    }
+
+    /// Run `step` on what an earlier step produced, or skip it when that
+    /// step didn't pass.
+    async fn run<I, T, Fut>(
+        &mut self,
+        name: &'static str,
+        needs: Option<I>,
+        step: impl FnOnce(I) -> Fut,
+    ) -> Option<T>
+    where
+        Fut: Future<Output = Result<(T, String)>>,
+    {
+        let Some(input) = needs else {
+            self.skip(name, "needs an earlier step");
+            return None;
+        };
+        let started = Instant::now();
+        let result = step(input).await;
+        let millis = started.elapsed().as_millis();
+        match result {
+            Ok((value, detail)) => {
+                self.push(Step {
+                    name,
+                    outcome: Outcome::Pass,
+                    millis,
+                    detail,
+                });
+                Some(value)
+            }
+            Err(e) => {
+                self.push(Step {
+                    name,
+                    outcome: Outcome::Fail,
+                    millis,
+                    detail: format!("{:#}", e),
+                });
+                None
+            }
+        }
+    }
+}
+
+/// The two test wallets: the maker sells and supplies, the taker buys and
+/// borrows.
+#[derive(Clone, Copy)]
+struct TestAccounts {
+    maker_account: Uuid,
+    maker: Uuid,
+    taker_account: Uuid,
+    taker: Uuid,
+}
+
+/// What the run trades and lends, in base units.
+struct Plan {
+    market: MarketRecord,
+    base: AssetBookRecord,
+    quote: AssetBookRecord,
+    price: BigDecimal,
+    base_units: u64,
+    quote_units: u64,
+    lending: Option<Lending>,
+}
+
+struct Lending {
+    pool: LendingPoolRecord,
+    supply_units: u64,
+    collateral: AssetBookRecord,
+    collateral_units: u64,
+}
+
+/// The candle interval the aggregation step writes.
+const BAR_INTERVAL: TimeSeriesInterval = TimeSeriesInterval::FifteenSecs;
+
+pub async fn run(args: SmokeArgs, ctx: &CliContext, app_config: &AppConfig) -> Result<Value> {
+    let market_id = market_arg(ctx, app_config, args.market.clone())?;
+    let price = ctx.decimal(args.price.clone(), "price", "Price (quote per base token)")?;
+    let zero = BigDecimal::from(0);
+    if price <= zero || args.quantity <= zero {
+        return Err(CliError::ValidationError(
+            "--price and --quantity have to be positive".to_string(),
+        )
+        .into());
+    }
+    let plan = plan(&mut app_config.pool.get()?, &args, market_id, price).await?;
+
+    let run_id = Uuid::new_v4().simple().to_string()[..8].to_string();
+    ctx.confirm(&format!(
+        "Run the smoke test on {}? It creates accounts smoke-{}-maker and -taker, mints test tokens to them and trades {} {} at {}{}.",
+        plan.market.symbol,
+        run_id,
+        args.quantity,
+        plan.base.symbol,
+        plan.price,
+        match &plan.lending {
+            Some(l) => format!(
+                ", then supplies to and borrows from {}",
+                l.pool.name.clone().unwrap_or_else(|| l.pool.id.to_string())
+            ),
+            None => String::new(),
+        }
+    ))?;
+
+    let mut matrix = Matrix {
+        steps: Vec::new(),
+        quiet: ctx.json,
+    };
+    match &args.url {
+        Some(url) => {
+            matrix.run("API", Some(url.as_str()), api_health).await;
+        }
+        None => matrix.skip("API", "no --url given"),
+    }
+
+    let accounts = matrix
+        .run("Create accounts", Some(()), |_| {
+            create_accounts(app_config, &run_id)
+        })
+        .await;
+    let funded = matrix
+        .run("Faucet", accounts, |a| faucet(app_config, &plan, a))
+        .await;
+    let placed = matrix
+        .run("Place order", funded, |a| place_maker(app_config, &plan, a))
+        .await;
+    let maker_order = placed.map(|(_, order)| order);
+    let matched = matrix
+        .run("Match order", placed, |(a, order)| {
+            match_taker(app_config, &plan, a, order)
+        })
+        .await;
+    matrix
+        .run("Aggregate bar", matched, |traded_at| {
+            aggregate_bar(app_config, &plan, traded_at)
+        })
+        .await;
+
+    match &plan.lending {
+        Some(lending) => {
+            let supplied = matrix
+                .run("Supply", funded, |a| supply(app_config, lending, a))
+                .await;
+            let loan = matrix
+                .run("Borrow", supplied, |a| borrow(app_config, lending, a))
+                .await;
+            matrix
+                .run("Repay", loan, |(a, loan)| repay(app_config, a, loan))
+                .await;
+        }
+        None => {
+            for name in ["Supply", "Borrow", "Repay"] {
+                matrix.skip(name, "no --pool given");
+            }
+        }
+    }
+
+    matrix
+        .run("Clean up", accounts, |a| {
+            clean_up(app_config, a, maker_order)
+        })
+        .await;
+
+    let failed = matrix
+        .steps
+        .iter()
+        .filter(|s| s.outcome == Outcome::Fail)
+        .count();
+    let result = json!({
+        "run": run_id,
+        "market": plan.market.symbol,
+        "ran_at": Utc::now().naive_utc(),
+        "passed": failed == 0,
+        "steps": matrix.steps,
+    });
+    if let Some(path) = &args.report {
+        std::fs::write(path, serde_json::to_string_pretty(&result)?)
+            .with_context(|| format!("Couldn't write {}", path.display()))?;
+    }
+
+    if !ctx.json {
+        eprintln!();
+        format_table(
+            vec!["Step", "Result", "Time", "Detail"],
+            matrix
+                .steps
+                .iter()
+                .map(|s| {
+                    vec![
+                        s.name.to_string(),
+                        serde_json::to_value(s.outcome)
+                            .ok()
+                            .and_then(|v| v.as_str().map(str::to_string))
+                            .unwrap_or_default(),
+                        format!("{}ms", s.millis),
+                        s.detail.clone(),
+                    ]
+                })
+                .collect(),
+        );
+        if let Some(path) = &args.report {
+            print_info(&format!("Report written to {}", path.display()));
+        }
+    }
+
+    if failed > 0 {
+        return Err(anyhow!("{} smoke step(s) failed", failed));
+    }
+    Ok(if ctx.json { result } else { Value::Null })
+}
+
+/// Whole tokens to base units, rounded up so a faucet top-up always covers
+/// the order it's for.
+fn to_base_units(amount: &BigDecimal, decimals: i32) -> Result<u64> {
+    let scaled = amount * BigDecimal::from(10u64.pow(decimals.max(0) as u32));
+    scaled
+        .with_scale_round(0, RoundingMode::Up)
+        .to_u64()
+        .ok_or_else(|| anyhow!("{} is too large in base units", amount))
+}
+
+async fn plan(
+    conn: DbConn<'_>,
+    args: &SmokeArgs,
+    market_id: Uuid,
+    price: BigDecimal,
+) -> Result<Plan> {
+    let market = {
+        use cradle_back_end::schema::markets::dsl::*;
+        markets
+            .find(market_id)
+            .first::<MarketRecord>(conn)
+            .optional()?
+            .ok_or_else(|| CliError::NotFound(format!("market {}", market_id)))?
+    };
+    let base = get_asset(conn, market.asset_one).await?;
+    let quote = get_asset(conn, market.asset_two).await?;
+
+    let lending = match &args.pool {
+        Some(given) => {
+            let pool = find_pool(conn, given)?;
+            let collateral = get_asset(conn, args.collateral.unwrap_or(market.asset_one)).await?;
+            let reserve = get_asset(conn, pool.reserve_asset).await?;
+            Some(Lending {
+                supply_units: to_base_units(&args.supply, reserve.decimals)?,
+                collateral_units: to_base_units(&args.collateral_amount, collateral.decimals)?,
+                pool,
+                collateral,
+            })
+        }
+        None => None,
+    };
+
+    Ok(Plan {
+        base_units: to_base_units(&args.quantity, base.decimals)?,
+        quote_units: to_base_units(&(&args.quantity * &price), quote.decimals)?,
+        market,
+        base,
+        quote,
+        price,
+        lending,
+    })
+}
+
+fn find_pool(conn: DbConn<'_>, given: &str) -> Result<LendingPoolRecord> {
+    use cradle_back_end::schema::lendingpool::dsl::*;
+
+    let found = match Uuid::parse_str(given.trim()) {
+        Ok(pool_id) => lendingpool.find(pool_id).first::<LendingPoolRecord>(conn),
+        Err(_) => lendingpool
+            .filter(name.eq(given.trim()))
+            .first::<LendingPoolRecord>(conn),
+    };
+    found
+        .optional()?
+        .ok_or_else(|| CliError::NotFound(format!("lending pool {}", given)).into())
+}
+
+async fn api_health(url: &str) -> Result<((), String)> {
+    let url = format!("{}/health", url.trim_end_matches('/'));
+    let started = Instant::now();
+    let response = reqwest::Client::new()
+        .get(&url)
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .await
+        .with_context(|| format!("No answer from {}", url))?;
+    if !response.status().is_success() {
+        return Err(anyhow!("{} answered {}", url, response.status()));
+    }
+    Ok((
+        (),
+        format!("{} answered in {}ms", url, started.elapsed().as_millis()),
+    ))
+}
+
+async fn create_accounts(app_config: &AppConfig, run_id: &str) -> Result<(TestAccounts, String)> {
+    let mut created = Vec::new();
+    for role in ["maker", "taker"] {
+        let output = dispatch(
+            ActionRouterInput::Accounts(AccountsProcessorInput::CreateAccount(
+                CreateCradleAccount {
+                    linked_account_id: format!("smoke-{}-{}", run_id, role),
+                    account_type: None,
+                    status: None,
+                },
+            )),
+            app_config,
+        )
+        .await
+        .with_context(|| format!("Creating the {} account", role))?;
+        created.push(serde_json::from_value::<CreateAccountOutputArgs>(output)?);
+    }
+    let accounts = TestAccounts {
+        maker_account: created[0].id,
+        maker: created[0].wallet_id,
+        taker_account: created[1].id,
+        taker: created[1].wallet_id,
+    };
+    Ok((
+        accounts,
+        format!(
+            "maker wallet {}, taker wallet {}",
+            accounts.maker, accounts.taker
+        ),
+    ))
+}
+
+/// Mint what each wallet needs and airdrop it, the same as a ramp
+/// fulfillment.
+async fn faucet(
+    app_config: &AppConfig,
+    plan: &Plan,
+    accounts: TestAccounts,
+) -> Result<(TestAccounts, String)> {
+    let mut top_ups = vec![
+        (accounts.maker, &plan.base, plan.base_units),
+        (accounts.taker, &plan.quote, plan.quote_units),
+    ];
+    if let Some(lending) = &plan.lending {
+        top_ups.push((
+            accounts.taker,
+            &lending.collateral,
+            lending.collateral_units,
+        ));
+    }
+
+    let conn = &mut app_config.pool.get()?;
+    let wallet = &app_config.wallet;
+    let mut sent = Vec::new();
+    for (wallet_id, asset, units) in top_ups {
+        mint_asset(conn, wallet, asset.id, units)
+            .await
+            .with_context(|| format!("Minting {}", asset.symbol))?;
+        airdrop_asset(conn, wallet, asset.id, wallet_id, units)
+            .await
+            .with_context(|| format!("Airdropping {}", asset.symbol))?;
+        sent.push(format!("{} {}", units, asset.symbol));
+    }
+    if let Some(lending) = &plan.lending {
+        // the reserve goes to the maker, who supplies it
+        let reserve = get_asset(conn, lending.pool.reserve_asset).await?;
+        mint_asset(conn, wallet, reserve.id, lending.supply_units).await?;
+        airdrop_asset(
+            conn,
+            wallet,
+            reserve.id,
+            accounts.maker,
+            lending.supply_units,
+        )
+        .await
+        .with_context(|| format!("Airdropping {}", reserve.symbol))?;
+        sent.push(format!("{} {}", lending.supply_units, reserve.symbol));
+    }
+    Ok((accounts, format!("sent {}", sent.join(", "))))
+}
+
+async fn place_order(app_config: &AppConfig, order: NewOrderBookRecord) -> Result<OrderFillResult> {
+    let output = dispatch(
+        ActionRouterInput::OrderBook(OrderBookProcessorInput::PlaceOrder(order)),
+        app_config,
+    )
+    .await?;
+    Ok(serde_json::from_value(output)?)
+}
+
+/// The maker's resting sell.
+async fn place_maker(
+    app_config: &AppConfig,
+    plan: &Plan,
+    accounts: TestAccounts,
+) -> Result<((TestAccounts, Uuid), String)> {
+    let placed = place_order(
+        app_config,
+        NewOrderBookRecord {
+            wallet: accounts.maker,
+            market_id: plan.market.id,
+            bid_asset: plan.quote.id,
+            ask_asset: plan.base.id,
+            bid_amount: BigDecimal::from(plan.quote_units),
+            ask_amount: BigDecimal::from(plan.base_units),
+            price: plan.price.clone(),
+            mode: Some(FillMode::GoodTillCancel),
+            expires_at: None,
+            order_type: Some(OrderType::Limit),
+        },
+    )
+    .await?;
+    let detail = if placed.matched_trades.is_empty() {
+        format!("sell order {} resting", placed.id)
+    } else {
+        format!(
+            "sell order {} crossed {} resting order(s) already on the book",
+            placed.id,
+            placed.matched_trades.len()
+        )
+    };
+    Ok(((accounts, placed.id), detail))
+}
+
+/// The taker's buy, which should fill against the maker and settle.
+/// Returns when the trade happened, for the aggregation step.
+async fn match_taker(
+    app_config: &AppConfig,
+    plan: &Plan,
+    accounts: TestAccounts,
+    maker_order: Uuid,
+) -> Result<(NaiveDateTime, String)> {
+    let filled = place_order(
+        app_config,
+        NewOrderBookRecord {
+            wallet: accounts.taker,
+            market_id: plan.market.id,
+            bid_asset: plan.base.id,
+            ask_asset: plan.quote.id,
+            bid_amount: BigDecimal::from(plan.base_units),
+            ask_amount: BigDecimal::from(plan.quote_units),
+            price: plan.price.clone(),
+            mode: Some(FillMode::ImmediateOrCancel),
+            expires_at: None,
+            order_type: Some(OrderType::Limit),
+        },
+    )
+    .await?;
+    if filled.matched_trades.is_empty() {
+        return Err(anyhow!("the buy order {} didn't match", filled.id));
+    }
+
+    let trades = {
+        use cradle_back_end::schema::orderbooktrades::dsl::*;
+        orderbooktrades
+            .filter(id.eq_any(&filled.matched_trades))
+            .select(OrderBookTradeRecord::as_select())
+            .get_results(&mut app_config.pool.get()?)?
+    };
+    let ours = trades
+        .iter()
+        .find(|t| t.maker_order_id == maker_order)
+        .ok_or_else(|| {
+            anyhow!(
+                "the buy order {} matched other orders on the book, not the smoke sell order",
+                filled.id
+            )
+        })?;
+
+    Ok((
+        ours.created_at,
+        format!(
+            "trade {} {:?}{}",
+            ours.id,
+            ours.settlement_status,
+            ours.settlement_tx
+                .as_ref()
+                .map(|tx| format!(" in {}", tx))
+                .unwrap_or_default()
+        ),
+    ))
+}
+
+/// Write the bar for the window the trade fell in, once that window has
+/// closed.
+async fn aggregate_bar(
+    app_config: &AppConfig,
+    plan: &Plan,
+    traded_at: NaiveDateTime,
+) -> Result<((), String)> {
+    let step = BAR_INTERVAL.seconds();
+    let secs = traded_at.and_utc().timestamp();
+    let start = DateTime::from_timestamp(secs - secs.rem_euclid(step), 0)
+        .ok_or_else(|| anyhow!("trade time {} is out of range", traded_at))?
+        .naive_utc();
+    let end = start + Duration::seconds(step);
+    let wait = end - Utc::now().naive_utc();
+    if wait > Duration::zero() {
+        tokio::time::sleep(wait.to_std()?).await;
+    }
+
+    let input = AggregatorsProcessorInput::AggregateTrades(AggregateTradesInputArgs {
+        market_id: plan.market.id,
+        asset_id: plan.market.asset_one,
+        start_time: start,
+        end_time: end,
+        interval: BAR_INTERVAL,
+    });
+    let conn = &mut app_config.pool.get()?;
+    let AggregatorsProcessorOutput::AggregateTrades(bar_id) = input
+        .process(
+            &mut app_config.clone(),
+            &mut AggregatorsConfig::default(),
+            Some(&mut *conn),
+        )
+        .await?
+    else {
+        return Err(anyhow!("the aggregator didn't return a bar"));
+    };
+
+    let bar = {
+        use cradle_back_end::schema::markets_time_series::dsl::*;
+        markets_time_series
+            .find(bar_id)
+            .select(MarketTimeSeriesRecord::as_select())
+            .first(conn)?
+    };
+    if bar.volume <= BigDecimal::from(0) {
+        return Err(anyhow!(
+            "bar {} has no volume; the trade wasn't picked up",
+            bar_id
+        ));
+    }
+    Ok((
+        (),
+        format!(
+            "{} bar {} from {}, close {}, volume {}",
+            BAR_INTERVAL.as_str(),
+            bar_id,
+            start,
+            bar.close.normalized(),
+            bar.volume.normalized()
+        ),
+    ))
+}
+
+async fn supply(
+    app_config: &AppConfig,
+    lending: &Lending,
+    accounts: TestAccounts,
+) -> Result<(TestAccounts, String)> {
+    dispatch(
+        ActionRouterInput::Pool(LendingPoolFunctionsInput::SupplyLiquidity(
+            SupplyLiquidityInputArgs {
+                wallet: accounts.maker,
+                pool: lending.pool.id,
+                amount: lending.supply_units,
+            },
+        )),
+        app_config,
+    )
+    .await?;
+    Ok((
+        accounts,
+        format!("supplied {} base units", lending.supply_units),
+    ))
+}
+
+async fn borrow(
+    app_config: &AppConfig,
+    lending: &Lending,
+    accounts: TestAccounts,
+) -> Result<((TestAccounts, Uuid), String)> {
+    let output = dispatch(
+        ActionRouterInput::Pool(LendingPoolFunctionsInput::BorrowAsset(TakeLoanInputArgs {
+            wallet: accounts.taker,
+            pool: lending.pool.id,
+            amount: lending.collateral_units,
+            collateral: lending.collateral.id,
+        })),
+        app_config,
+    )
+    .await?;
+    let loan: Uuid = serde_json::from_value(output)?;
+    Ok((
+        (accounts, loan),
+        format!(
+            "loan {} against {} {}",
+            loan, lending.collateral_units, lending.collateral.symbol
+        ),
+    ))
+}
+
+/// Pay the principal back; interest accrued over a few seconds is left.
+async fn repay(app_config: &AppConfig, accounts: TestAccounts, loan: Uuid) -> Result<((), String)> {
+    let record = {
+        use cradle_back_end::schema::loans::dsl::*;
+        loans
+            .find(loan)
+            .first::<LoanRecord>(&mut app_config.pool.get()?)?
+    };
+    let amount = record
+        .principal_amount
+        .to_u64()
+        .ok_or_else(|| anyhow!("principal {} is out of range", record.principal_amount))?;
+    dispatch(
+        ActionRouterInput::Pool(LendingPoolFunctionsInput::RepayBorrow(RepayLoanInputArgs {
+            wallet: accounts.taker,
+            loan,
+            amount,
+        })),
+        app_config,
+    )
+    .await?;
+    Ok(((), format!("repaid {} base units", amount)))
+}
+
+/// Cancel the sell order if it's still resting and close both accounts,
+/// so test accounts can't be used after the run. Their tokens stay put.
+async fn clean_up(
+    app_config: &AppConfig,
+    accounts: TestAccounts,
+    maker_order: Option<Uuid>,
+) -> Result<((), String)> {
+    let mut done = Vec::new();
+    if let Some(order_id) = maker_order {
+        let order = {
+            use cradle_back_end::schema::orderbook::dsl::*;
+            orderbook
+                .find(order_id)
+                .select(OrderBookRecord::as_select())
+                .first(&mut app_config.pool.get()?)?
+        };
+        if matches!(order.status, OrderStatus::Open) {
+            dispatch(
+                ActionRouterInput::OrderBook(OrderBookProcessorInput::CancelOrder(
+                    CancelOrderInputArgs {
+                        order_id,
+                        wallet: None,
+                    },
+                )),
+                app_config,
+            )
+            .await?;
+            done.push("cancelled the sell order".to_string());
+        }
+    }
+    for account in [accounts.maker_account, accounts.taker_account] {
+        dispatch(
+            ActionRouterInput::Accounts(AccountsProcessorInput::UpdateAccountStatus(
+                UpdateAccountStatusInputArgs {
+                    cradle_account_id: account,
+                    status: CradleAccountStatus::Closed,
+                },
+            )),
+            app_config,
+        )
+        .await?;
+    }
+    done.push("closed both accounts".to_string());
+    Ok(((), done.join(", ")))
+}
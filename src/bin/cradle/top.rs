@@ -0,0 +1,363 @@
+//! `cradle top`: a live terminal dashboard of markets, trades, lending pools
+//! and candle aggregation, for operators who'd rather not open the admin UI.
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use bigdecimal::{BigDecimal, RoundingMode};
+use chrono::NaiveDateTime;
+use clap::Args;
+use diesel::prelude::*;
+use ratatui::Frame;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Row, Table};
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use cradle_back_end::health::{AggregationLag, aggregation_lag};
+use cradle_back_end::market::stats::{self, MarketStats};
+use cradle_back_end::order_book::depth::{MarketTrade, Side, recent_trades};
+use cradle_back_end::sockets::cluster;
+use cradle_back_end::utils::app_config::AppConfig;
+use cradle_back_end::utils::commons::DbConn;
+
+use crate::context::CliContext;
+
+#[derive(Args)]
+pub struct TopArgs {
+    /// Seconds between refreshes when there's no socket feed to follow
+    #[arg(long, default_value_t = 2)]
+    interval: u64,
+    /// Recent trades to list
+    #[arg(long, default_value_t = 20)]
+    trades: i64,
+}
+
+/// A lending pool's latest snapshot.
+#[derive(Serialize)]
+struct PoolUtilization {
+    pool_id: Uuid,
+    name: String,
+    utilization_rate: BigDecimal,
+    total_supply: BigDecimal,
+    total_borrow: BigDecimal,
+    available_liquidity: BigDecimal,
+    snapshot_at: NaiveDateTime,
+}
+
+#[derive(Serialize)]
+struct Dashboard {
+    taken_at: NaiveDateTime,
+    markets: Vec<MarketStats>,
+    trades: Vec<MarketTrade>,
+    pools: Vec<PoolUtilization>,
+    aggregation: Vec<AggregationLag>,
+}
+
+/// Redraw no more often than this, however busy the feed.
+const MIN_REDRAW: Duration = Duration::from_millis(500);
+
+pub async fn run(args: TopArgs, ctx: &CliContext, app_config: &AppConfig) -> Result<Value> {
+    // one snapshot for scripts, since there's no terminal to draw on
+    if ctx.json {
+        let dashboard = load(&mut app_config.pool.get()?, args.trades)?;
+        return Ok(serde_json::to_value(&dashboard)?);
+    }
+
+    let feed = cluster::follow(Vec::new()).await?;
+    let mut terminal = ratatui::init();
+    let result = watch(&mut terminal, app_config, &args, feed).await;
+    ratatui::restore();
+    result?;
+    Ok(Value::Null)
+}
+
+fn load(conn: DbConn<'_>, trades: i64) -> Result<Dashboard> {
+    stats::refresh(conn)?;
+    Ok(Dashboard {
+        taken_at: chrono::Utc::now().naive_utc(),
+        markets: stats::market_stats(conn)?,
+        trades: recent_trades(conn, trades)?,
+        pools: pool_utilization(conn)?,
+        aggregation: aggregation_lag(conn)?,
+    })
+}
+
+fn pool_utilization(conn: DbConn<'_>) -> Result<Vec<PoolUtilization>> {
+    use cradle_back_end::schema::lendingpool;
+    use cradle_back_end::schema::lendingpoolsnapshots as snapshots;
+
+    let rows = snapshots::table
+        .inner_join(lendingpool::table)
+        .distinct_on(snapshots::lending_pool_id)
+        .order((snapshots::lending_pool_id, snapshots::created_at.desc()))
+        .select((
+            snapshots::lending_pool_id,
+            lendingpool::name,
+            snapshots::utilization_rate,
+            snapshots::total_supply,
+            snapshots::total_borrow,
+            snapshots::available_liquidity,
+            snapshots::created_at,
+        ))
+        .get_results::<(
+            Uuid,
+            Option<String>,
+            BigDecimal,
+            BigDecimal,
+            BigDecimal,
+            BigDecimal,
+            NaiveDateTime,
+        )>(conn)?;
+
+    let mut pools: Vec<PoolUtilization> = rows
+        .into_iter()
+        .map(
+            |(pool_id, name, utilization_rate, supply, borrow, liquidity, snapshot_at)| {
+                PoolUtilization {
+                    pool_id,
+                    name: name.unwrap_or_else(|| pool_id.to_string()),
+                    utilization_rate,
+                    total_supply: supply,
+                    total_borrow: borrow,
+                    available_liquidity: liquidity,
+                    snapshot_at,
+                }
+            },
+        )
+        .collect();
+    pools.sort_by(|a, b| b.utilization_rate.cmp(&a.utilization_rate));
+    Ok(pools)
+}
+
+/// Redraw on socket events when following the cluster feed, otherwise
+/// every `--interval`, until q, Esc or Ctrl-C.
+async fn watch(
+    terminal: &mut ratatui::DefaultTerminal,
+    app_config: &AppConfig,
+    args: &TopArgs,
+    mut feed: Option<tokio::sync::mpsc::UnboundedReceiver<String>>,
+) -> Result<()> {
+    let poll = Duration::from_secs(args.interval.max(1));
+    let mut dashboard = load(&mut app_config.pool.get()?, args.trades)?;
+    let mut loaded_at = Instant::now();
+    let mut changed = false;
+    let mut force = false;
+
+    loop {
+        let live = feed.is_some();
+        terminal.draw(|frame| draw(frame, &dashboard, live, poll))?;
+
+        // raw mode swallows Ctrl-C, so it arrives as a key
+        if event::poll(Duration::from_millis(100))?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            let ctrl_c =
+                key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+            if ctrl_c || matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                return Ok(());
+            }
+            force = key.code == KeyCode::Char('r');
+        }
+
+        if let Some(events) = feed.as_mut() {
+            loop {
+                match events.try_recv() {
+                    Ok(_) => changed = true,
+                    Err(tokio::sync::mpsc::error::TryRecvError::Empty) => break,
+                    Err(tokio::sync::mpsc::error::TryRecvError::Disconnected) => {
+                        // the subscription dropped; carry on polling
+                        feed = None;
+                        break;
+                    }
+                }
+            }
+        }
+
+        let due = force
+            || if feed.is_some() {
+                changed && loaded_at.elapsed() >= MIN_REDRAW
+            } else {
+                changed || loaded_at.elapsed() >= poll
+            };
+        if due {
+            dashboard = load(&mut app_config.pool.get()?, args.trades)?;
+            loaded_at = Instant::now();
+            changed = false;
+            force = false;
+        }
+        tokio::task::yield_now().await;
+    }
+}
+
+fn round(value: &BigDecimal, scale: i64) -> String {
+    value
+        .with_scale_round(scale, RoundingMode::HalfUp)
+        .to_string()
+}
+
+fn or_dash(value: Option<String>) -> String {
+    value.unwrap_or_else(|| "-".to_string())
+}
+
+fn table<'a>(title: &'a str, headers: Vec<&'a str>, rows: Vec<Row<'a>>) -> Table<'a> {
+    let widths = vec![Constraint::Fill(1); headers.len()];
+    Table::new(rows, widths)
+        .header(Row::new(headers).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(Block::default().borders(Borders::ALL).title(title))
+}
+
+fn draw(frame: &mut Frame, dashboard: &Dashboard, live: bool, poll: Duration) {
+    let [header, top, bottom] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Percentage(55),
+        Constraint::Fill(1),
+    ])
+    .areas(frame.area());
+    let [markets_area, trades_area] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Fill(1)]).areas(top);
+    let [pools_area, lag_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Fill(1)]).areas(bottom);
+
+    let source = if live {
+        "live".to_string()
+    } else {
+        format!("every {}s", poll.as_secs())
+    };
+    frame.render_widget(
+        Line::from(format!(
+            " cradle top   {} UTC   refreshing {}   q quit, r refresh",
+            dashboard.taken_at.format("%Y-%m-%d %H:%M:%S"),
+            source
+        )),
+        header,
+    );
+
+    let markets = dashboard
+        .markets
+        .iter()
+        .map(|m| {
+            let change = m.change_percent_24h.clone();
+            let colour = match &change {
+                Some(c) if *c > BigDecimal::from(0) => Color::Green,
+                Some(c) if *c < BigDecimal::from(0) => Color::Red,
+                _ => Color::Reset,
+            };
+            Row::new(vec![
+                m.symbol.clone(),
+                or_dash(m.last_price.as_ref().map(|p| p.normalized().to_string())),
+                or_dash(change.map(|c| format!("{}%", c))),
+                or_dash(m.high_24h.as_ref().map(|p| p.normalized().to_string())),
+                or_dash(m.low_24h.as_ref().map(|p| p.normalized().to_string())),
+                m.volume_24h.normalized().to_string(),
+                m.open_orders.to_string(),
+            ])
+            .style(Style::default().fg(colour))
+        })
+        .collect();
+    frame.render_widget(
+        table(
+            "Markets (24h)",
+            vec![
+                "Market",
+                "Last",
+                "Change",
+                "High",
+                "Low",
+                "Volume",
+                "Open orders",
+            ],
+            markets,
+        ),
+        markets_area,
+    );
+
+    let trades = dashboard
+        .trades
+        .iter()
+        .map(|t| {
+            let (side, colour) = match t.trade.taker_side {
+                Side::Buy => ("buy", Color::Green),
+                Side::Sell => ("sell", Color::Red),
+            };
+            Row::new(vec![
+                t.trade.created_at.format("%H:%M:%S").to_string(),
+                t.symbol.clone(),
+                side.to_string(),
+                t.trade.price.normalized().to_string(),
+                t.trade.quantity.normalized().to_string(),
+            ])
+            .style(Style::default().fg(colour))
+        })
+        .collect();
+    frame.render_widget(
+        table(
+            "Recent trades",
+            vec!["Time", "Market", "Side", "Price", "Quantity"],
+            trades,
+        ),
+        trades_area,
+    );
+
+    let pools = dashboard
+        .pools
+        .iter()
+        .map(|p| {
+            Row::new(vec![
+                p.name.clone(),
+                round(&p.utilization_rate, 2),
+                p.total_supply.normalized().to_string(),
+                p.total_borrow.normalized().to_string(),
+                p.available_liquidity.normalized().to_string(),
+                p.snapshot_at.format("%m-%d %H:%M").to_string(),
+            ])
+        })
+        .collect();
+    frame.render_widget(
+        table(
+            "Lending pools",
+            vec![
+                "Pool",
+                "Utilization",
+                "Supplied",
+                "Borrowed",
+                "Available",
+                "As of",
+            ],
+            pools,
+        ),
+        pools_area,
+    );
+
+    let lags = dashboard
+        .aggregation
+        .iter()
+        .map(|l| {
+            let style = if l.behind {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                l.market_name.clone(),
+                l.interval.as_str().to_string(),
+                l.last_bar_end.format("%m-%d %H:%M:%S").to_string(),
+                format!("{}s", l.lag_secs),
+            ])
+            .style(style)
+        })
+        .collect();
+    frame.render_widget(
+        table(
+            "Aggregator lag",
+            vec!["Market", "Interval", "Last bar", "Lag"],
+            lags,
+        ),
+        lag_area,
+    );
+}
@@ -1,6 +0,0 @@
-use anyhow::Result;
-
-#[tokio::main]
-async fn main() -> Result<()> {
-    Ok(())
-}
@@ -631,7 +631,7 @@ async fn run_interactive_mode(
 
                         let result = execute_with_retry(
                             || {
-                                let app_config = app_config.clone();
+                                let mut app_config = app_config.clone();
                                 let bar_data = bar_clone.clone();
                                 let interval_data = interval_clone.clone();
                                 async move {
@@ -653,7 +653,7 @@ async fn run_interactive_mode(
                                     let input = MarketTimeSeriesProcessorInput::AddRecord(create_input);
                                     let router_input = ActionRouterInput::MarketTimeSeries(input);
 
-                                    match call_action_router(router_input, app_config).await? {
+                                    match call_action_router(router_input, &mut app_config).await? {
                                         ActionRouterOutput::MarketTimeSeries(_) => Ok(()),
                                         _ => Err(anyhow!("Unexpected action router output type")),
                                     }
@@ -743,7 +743,7 @@ async fn run_cli_mode(
 
                 let result = execute_with_retry(
                     || {
-                        let app_config = app_config.clone();
+                        let mut app_config = app_config.clone();
                         let bar_data = bar_clone.clone();
                         let interval_data = interval_clone.clone();
                         async move {
@@ -765,7 +765,7 @@ async fn run_cli_mode(
                             let input = MarketTimeSeriesProcessorInput::AddRecord(create_input);
                             let router_input = ActionRouterInput::MarketTimeSeries(input);
 
-                            match call_action_router(router_input, app_config).await? {
+                            match call_action_router(router_input, &mut app_config).await? {
                                 ActionRouterOutput::MarketTimeSeries(_) => Ok(()),
                                 _ => Err(anyhow!("Unexpected action router output type")),
                             }
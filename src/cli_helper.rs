@@ -10,8 +10,8 @@ pub fn initialize_app_config() -> Result<AppConfig> {
 }
 
 /// Execute an action through the ActionRouter
-pub async fn call_action_router(input: ActionRouterInput, app_config: AppConfig) -> Result<ActionRouterOutput> {
-    input.process(app_config).await
+pub async fn call_action_router(input: ActionRouterInput, app_config: &mut AppConfig) -> Result<ActionRouterOutput> {
+    input.process_as(app_config, None).await
 }
 
 /// Prompt user to retry a failed operation
@@ -38,6 +38,43 @@ impl Input {
         Ok(if input.is_empty() { None } else { Some(input) })
     }
 
+    /// Get an optional UUID
+    pub fn get_optional_uuid(prompt: &str) -> CliResult<Option<Uuid>> {
+        match Self::get_optional_string(prompt)? {
+            Some(input) => Uuid::parse_str(input.trim())
+                .map(Some)
+                .map_err(|_| crate::cli_utils::CliError::ValidationError(
+                    "Invalid UUID format".to_string()
+                )),
+            None => Ok(None),
+        }
+    }
+
+    /// Get an optional integer
+    pub fn get_optional_i64(prompt: &str) -> CliResult<Option<i64>> {
+        match Self::get_optional_string(prompt)? {
+            Some(input) => input.trim().parse::<i64>()
+                .map(Some)
+                .map_err(|_| crate::cli_utils::CliError::ValidationError(
+                    "Invalid number format".to_string()
+                )),
+            None => Ok(None),
+        }
+    }
+
+    /// Get an optional decimal number
+    pub fn get_optional_decimal(prompt: &str) -> CliResult<Option<bigdecimal::BigDecimal>> {
+        use std::str::FromStr;
+        match Self::get_optional_string(prompt)? {
+            Some(input) => bigdecimal::BigDecimal::from_str(input.trim())
+                .map(Some)
+                .map_err(|_| crate::cli_utils::CliError::ValidationError(
+                    "Invalid decimal format".to_string()
+                )),
+            None => Ok(None),
+        }
+    }
+
     /// Get an integer
     pub fn get_i64(prompt: &str) -> CliResult<i64> {
         let input: String = DialoguerInput::new()
@@ -51,6 +88,19 @@ impl Input {
             ))
     }
 
+    /// Get a non-negative integer, e.g. an amount in base units
+    pub fn get_u64(prompt: &str) -> CliResult<u64> {
+        let input: String = DialoguerInput::new()
+            .with_prompt(prompt)
+            .interact()
+            .map_err(|e| crate::cli_utils::CliError::IoError(e.to_string()))?;
+
+        input.trim().parse::<u64>()
+            .map_err(|_| crate::cli_utils::CliError::ValidationError(
+                "Invalid amount, expected a whole non-negative number".to_string()
+            ))
+    }
+
     /// Get a decimal number
     pub fn get_decimal(prompt: &str) -> CliResult<bigdecimal::BigDecimal> {
         use std::str::FromStr;
@@ -95,4 +145,18 @@ impl Input {
             .map_err(|e| crate::cli_utils::CliError::IoError(e.to_string()))?;
         Ok(items[idx].clone())
     }
+
+    /// Select an enum value by its serialized name, e.g. "spot"
+    pub fn select_enum<T: serde::de::DeserializeOwned>(prompt: &str, names: &[&str]) -> CliResult<T> {
+        let idx = Self::select_from_list(prompt, names.to_vec())?;
+        parse_enum(names[idx])
+    }
+}
+
+/// Parse an enum from its serialized name, so CLI values match the API's
+pub fn parse_enum<T: serde::de::DeserializeOwned>(name: &str) -> CliResult<T> {
+    serde_json::from_value(serde_json::Value::String(name.trim().to_string()))
+        .map_err(|_| crate::cli_utils::CliError::ValidationError(
+            format!("Unknown value: {}", name)
+        ))
 }
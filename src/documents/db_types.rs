@@ -0,0 +1,52 @@
+use crate::schema::documents as DocumentsTable;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::DocumentOwner"]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentOwner {
+    Listing,
+    Company,
+}
+
+impl DocumentOwner {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocumentOwner::Listing => "listing",
+            DocumentOwner::Company => "company",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = DocumentsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DocumentRow {
+    pub id: Uuid,
+    pub owner_type: DocumentOwner,
+    pub owner_id: Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    pub content_hash: String,
+    pub backend: String,
+    pub storage_key: String,
+    pub uploaded_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = DocumentsTable)]
+pub struct CreateDocument {
+    pub owner_type: DocumentOwner,
+    pub owner_id: Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    pub content_hash: String,
+    pub backend: String,
+    pub storage_key: String,
+}
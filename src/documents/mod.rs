@@ -0,0 +1,3 @@
+pub mod db_types;
+pub mod operations;
+pub mod storage;
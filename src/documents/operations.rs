@@ -0,0 +1,211 @@
+use anyhow::{Result, anyhow};
+use bytes::Bytes;
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::documents::db_types::{CreateDocument, DocumentOwner, DocumentRow};
+use crate::documents::storage::{DocumentStore, sha256_hex};
+
+pub struct UploadDocumentInput {
+    pub owner_type: DocumentOwner,
+    pub owner_id: Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub data: Bytes,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SignedDocumentUrl {
+    pub document_id: Uuid,
+    pub url: String,
+    pub expires_at: NaiveDateTime,
+}
+
+/// Secret for download signatures, from DOCUMENT_URL_SECRET, falling back to
+/// the API key so links stop working if that is rotated.
+fn url_secret() -> String {
+    std::env::var("DOCUMENT_URL_SECRET")
+        .or_else(|_| std::env::var("API_SECRET_KEY"))
+        .unwrap_or_else(|_| "default-secret-key".to_string())
+}
+
+/// Lifetime of a download link, from DOCUMENT_URL_TTL_SECS.
+fn url_ttl_secs() -> i64 {
+    std::env::var("DOCUMENT_URL_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(900)
+}
+
+fn download_mac(document_id: Uuid, expires: i64) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(url_secret().as_bytes())
+        .expect("hmac accepts any key length");
+    mac.update(format!("{}:{}", document_id, expires).as_bytes());
+    mac
+}
+
+fn expect_owner_exists(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    owner_type: DocumentOwner,
+    owner: Uuid,
+) -> Result<()> {
+    let found = match owner_type {
+        DocumentOwner::Listing => {
+            use crate::schema::cradlenativelistings::dsl::*;
+            diesel::select(diesel::dsl::exists(cradlenativelistings.filter(id.eq(owner))))
+                .get_result::<bool>(conn)?
+        }
+        DocumentOwner::Company => {
+            use crate::schema::cradlelistedcompanies::dsl::*;
+            diesel::select(diesel::dsl::exists(cradlelistedcompanies.filter(id.eq(owner))))
+                .get_result::<bool>(conn)?
+        }
+    };
+
+    if !found {
+        return Err(anyhow!("No {} with id {}", owner_type.as_str(), owner));
+    }
+    Ok(())
+}
+
+/// Store a file for a listing or company, record it, and point the owner's
+/// hash column at its contents.
+pub async fn upload_document(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    storage: &impl DocumentStore,
+    input: UploadDocumentInput,
+) -> Result<DocumentRow> {
+    if input.data.is_empty() {
+        return Err(anyhow!("Document is empty"));
+    }
+    expect_owner_exists(conn, input.owner_type, input.owner_id)?;
+
+    let hash = sha256_hex(&input.data);
+    let key = format!("{}/{}/{}", input.owner_type.as_str(), input.owner_id, Uuid::new_v4());
+    let file_size = input.data.len() as i64;
+    let stored_key = storage.put(&key, &input.content_type, input.data).await?;
+
+    let row = {
+        use crate::schema::documents::dsl::*;
+
+        diesel::insert_into(documents)
+            .values(CreateDocument {
+                owner_type: input.owner_type,
+                owner_id: input.owner_id,
+                file_name: input.file_name,
+                content_type: input.content_type,
+                size: file_size,
+                content_hash: hash.clone(),
+                backend: storage.backend().to_string(),
+                storage_key: stored_key,
+            })
+            .get_result::<DocumentRow>(conn)?
+    };
+
+    match row.owner_type {
+        DocumentOwner::Listing => {
+            use crate::schema::cradlenativelistings::dsl::*;
+            diesel::update(cradlenativelistings.find(row.owner_id))
+                .set(documents_hash.eq(Some(hash)))
+                .execute(conn)?;
+        }
+        DocumentOwner::Company => {
+            use crate::schema::cradlelistedcompanies::dsl::*;
+            diesel::update(cradlelistedcompanies.find(row.owner_id))
+                .set(legal_documents_hash.eq(Some(hash)))
+                .execute(conn)?;
+        }
+    }
+
+    Ok(row)
+}
+
+pub fn get_document(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    document_id: Uuid,
+) -> Result<DocumentRow> {
+    use crate::schema::documents::dsl::*;
+
+    Ok(documents
+        .filter(id.eq(document_id))
+        .get_result::<DocumentRow>(conn)?)
+}
+
+pub fn get_owner_documents(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    owner: DocumentOwner,
+    owner_uuid: Uuid,
+) -> Result<Vec<DocumentRow>> {
+    use crate::schema::documents::dsl::*;
+
+    Ok(documents
+        .filter(owner_type.eq(owner))
+        .filter(owner_id.eq(owner_uuid))
+        .order(uploaded_at.desc())
+        .get_results::<DocumentRow>(conn)?)
+}
+
+/// A time-limited link to the download endpoint, usable without the API key.
+pub fn signed_download_url(document_id: Uuid, base_url: &str) -> SignedDocumentUrl {
+    let expires = Utc::now().timestamp() + url_ttl_secs();
+    let signature = hex::encode(download_mac(document_id, expires).finalize().into_bytes());
+
+    SignedDocumentUrl {
+        document_id,
+        url: format!(
+            "{}/documents/{}/download?expires={}&signature={}",
+            base_url.trim_end_matches('/'),
+            document_id,
+            expires,
+            signature
+        ),
+        expires_at: chrono::DateTime::from_timestamp(expires, 0)
+            .map(|t| t.naive_utc())
+            .unwrap_or_default(),
+    }
+}
+
+pub fn verify_download_signature(document_id: Uuid, expires: i64, signature: &str) -> Result<()> {
+    if Utc::now().timestamp() > expires {
+        return Err(anyhow!("Download link has expired"));
+    }
+
+    let provided = hex::decode(signature).map_err(|_| anyhow!("Malformed signature"))?;
+    download_mac(document_id, expires)
+        .verify_slice(&provided)
+        .map_err(|_| anyhow!("Invalid signature"))
+}
+
+/// Fetch a document's bytes, refusing anything that no longer matches the
+/// recorded hash.
+pub async fn download_document(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    storage: &impl DocumentStore,
+    document_id: Uuid,
+) -> Result<(DocumentRow, Bytes)> {
+    let row = get_document(conn, document_id)?;
+    if row.backend != storage.backend() {
+        return Err(anyhow!(
+            "Document {} is stored in {}, but the {} backend is configured",
+            row.id,
+            row.backend,
+            storage.backend()
+        ));
+    }
+
+    let data = storage.get(&row.storage_key).await?;
+    if sha256_hex(&data) != row.content_hash {
+        return Err(anyhow!("Document {} failed its integrity check", row.id));
+    }
+
+    Ok((row, data))
+}
@@ -0,0 +1,298 @@
+use std::path::PathBuf;
+
+use anyhow::{Result, anyhow};
+use bytes::Bytes;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+/// Where uploaded document bytes are kept. `put` returns the key the object
+/// can be fetched back with.
+pub trait DocumentStore {
+    fn backend(&self) -> &'static str;
+
+    async fn put(&self, key: &str, content_type: &str, data: Bytes) -> Result<String>;
+
+    async fn get(&self, key: &str) -> Result<Bytes>;
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// S3 or any S3-compatible store, signed with SigV4.
+pub struct S3Store {
+    client: reqwest::Client,
+    bucket: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+    /// Custom endpoint (MinIO, R2, ...); objects are addressed path-style
+    endpoint: Option<String>,
+}
+
+impl S3Store {
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| std::env::var(name).map_err(|_| anyhow!("{} is not set", name));
+        Ok(Self {
+            client: reqwest::Client::new(),
+            bucket: var("S3_BUCKET")?,
+            region: std::env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+            access_key: var("S3_ACCESS_KEY_ID")?,
+            secret_key: var("S3_SECRET_ACCESS_KEY")?,
+            endpoint: std::env::var("S3_ENDPOINT").ok().filter(|e| !e.is_empty()),
+        })
+    }
+
+    /// Host header and full URL for `key`. Keys are generated by us and only
+    /// contain URL-safe characters, so they are used without further encoding.
+    fn locate(&self, key: &str) -> (String, String, String) {
+        match &self.endpoint {
+            Some(endpoint) => {
+                let endpoint = endpoint.trim_end_matches('/');
+                let host = endpoint
+                    .trim_start_matches("https://")
+                    .trim_start_matches("http://")
+                    .to_string();
+                let path = format!("/{}/{}", self.bucket, key);
+                (host, format!("{}{}", endpoint, path), path)
+            }
+            None => {
+                let host = format!("{}.s3.{}.amazonaws.com", self.bucket, self.region);
+                let path = format!("/{}", key);
+                (host.clone(), format!("https://{}{}", host, path), path)
+            }
+        }
+    }
+
+    fn sign(
+        &self,
+        method: &str,
+        host: &str,
+        path: &str,
+        payload_hash: &str,
+    ) -> (String, String) {
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            method, path, host, payload_hash, amz_date, signed_headers, payload_hash
+        );
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            sha256_hex(canonical_request.as_bytes())
+        );
+
+        let k_date = hmac_sha256(format!("AWS4{}", self.secret_key).as_bytes(), date.as_bytes());
+        let k_region = hmac_sha256(&k_date, self.region.as_bytes());
+        let k_service = hmac_sha256(&k_region, b"s3");
+        let k_signing = hmac_sha256(&k_service, b"aws4_request");
+        let signature = hex::encode(hmac_sha256(&k_signing, string_to_sign.as_bytes()));
+
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key, scope, signed_headers, signature
+        );
+        (authorization, amz_date)
+    }
+}
+
+impl DocumentStore for S3Store {
+    fn backend(&self) -> &'static str {
+        "s3"
+    }
+
+    async fn put(&self, key: &str, content_type: &str, data: Bytes) -> Result<String> {
+        let (host, url, path) = self.locate(key);
+        let payload_hash = sha256_hex(&data);
+        let (authorization, amz_date) = self.sign("PUT", &host, &path, &payload_hash);
+
+        let res = self
+            .client
+            .put(url)
+            .header("authorization", authorization)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("content-type", content_type)
+            .body(data)
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(anyhow!("S3 upload failed with {}: {}", res.status(), res.text().await?));
+        }
+        Ok(key.to_string())
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes> {
+        let (host, url, path) = self.locate(key);
+        let payload_hash = sha256_hex(b"");
+        let (authorization, amz_date) = self.sign("GET", &host, &path, &payload_hash);
+
+        let res = self
+            .client
+            .get(url)
+            .header("authorization", authorization)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(anyhow!("S3 download failed with {}", res.status()));
+        }
+        Ok(res.bytes().await?)
+    }
+}
+
+/// IPFS through a node's HTTP API. Files are pinned on upload and addressed
+/// by CID, so the key handed back is the CID rather than the requested key.
+pub struct IpfsStore {
+    client: reqwest::Client,
+    api_url: String,
+}
+
+#[derive(Deserialize)]
+struct IpfsAddResponse {
+    #[serde(rename = "Hash")]
+    hash: String,
+}
+
+impl IpfsStore {
+    pub fn from_env() -> Result<Self> {
+        let api_url = std::env::var("IPFS_API_URL").map_err(|_| anyhow!("IPFS_API_URL is not set"))?;
+        Ok(Self {
+            client: reqwest::Client::new(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+        })
+    }
+}
+
+impl DocumentStore for IpfsStore {
+    fn backend(&self) -> &'static str {
+        "ipfs"
+    }
+
+    async fn put(&self, key: &str, content_type: &str, data: Bytes) -> Result<String> {
+        let part = reqwest::multipart::Part::bytes(data.to_vec())
+            .file_name(key.replace('/', "_"))
+            .mime_str(content_type)?;
+        let form = reqwest::multipart::Form::new().part("file", part);
+
+        let res = self
+            .client
+            .post(format!("{}/api/v0/add?pin=true", self.api_url))
+            .multipart(form)
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(anyhow!("IPFS add failed with {}", res.status()));
+        }
+        Ok(res.json::<IpfsAddResponse>().await?.hash)
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes> {
+        let res = self
+            .client
+            .post(format!("{}/api/v0/cat?arg={}", self.api_url, key))
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(anyhow!("IPFS cat failed with {}", res.status()));
+        }
+        Ok(res.bytes().await?)
+    }
+}
+
+/// Files on local disk, for development.
+pub struct LocalStore {
+    root: PathBuf,
+}
+
+impl LocalStore {
+    pub fn from_env() -> Self {
+        Self {
+            root: PathBuf::from(
+                std::env::var("DOCUMENT_STORAGE_DIR").unwrap_or_else(|_| "./documents".to_string()),
+            ),
+        }
+    }
+}
+
+impl DocumentStore for LocalStore {
+    fn backend(&self) -> &'static str {
+        "local"
+    }
+
+    async fn put(&self, key: &str, _content_type: &str, data: Bytes) -> Result<String> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, &data).await?;
+        Ok(key.to_string())
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes> {
+        Ok(Bytes::from(tokio::fs::read(self.root.join(key)).await?))
+    }
+}
+
+/// Backend picked by DOCUMENT_STORAGE (`s3`, `ipfs` or `local`, the default).
+pub enum Storage {
+    S3(S3Store),
+    Ipfs(IpfsStore),
+    Local(LocalStore),
+}
+
+impl Storage {
+    pub fn from_env() -> Result<Self> {
+        match std::env::var("DOCUMENT_STORAGE").unwrap_or_default().as_str() {
+            "s3" => Ok(Storage::S3(S3Store::from_env()?)),
+            "ipfs" => Ok(Storage::Ipfs(IpfsStore::from_env()?)),
+            "" | "local" => Ok(Storage::Local(LocalStore::from_env())),
+            other => Err(anyhow!("Unknown DOCUMENT_STORAGE backend {}", other)),
+        }
+    }
+}
+
+impl DocumentStore for Storage {
+    fn backend(&self) -> &'static str {
+        match self {
+            Storage::S3(s) => s.backend(),
+            Storage::Ipfs(s) => s.backend(),
+            Storage::Local(s) => s.backend(),
+        }
+    }
+
+    async fn put(&self, key: &str, content_type: &str, data: Bytes) -> Result<String> {
+        match self {
+            Storage::S3(s) => s.put(key, content_type, data).await,
+            Storage::Ipfs(s) => s.put(key, content_type, data).await,
+            Storage::Local(s) => s.put(key, content_type, data).await,
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes> {
+        match self {
+            Storage::S3(s) => s.get(key).await,
+            Storage::Ipfs(s) => s.get(key).await,
+            Storage::Local(s) => s.get(key).await,
+        }
+    }
+}
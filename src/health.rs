@@ -0,0 +1,365 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::{NaiveDateTime, Utc};
+use contract_integrator::{utils::functions::commons, wallet::wallet::ActionWallet};
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, Pool},
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    lending_pool::oracle::{OracleStaleness, oracle_staleness},
+    market_time_series::db_types::{DataProviderType, TimeSeriesInterval},
+    order_book::db_types::SettlementStatus,
+    utils::{app_config::AppConfig, commons::DbConn},
+    webhooks::db_types::DeliveryStatus,
+};
+
+/// How long the Hedera probe may take before the client counts as down.
+const HEDERA_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Share of pool or server connections in use above which the report warns.
+const CONNECTION_WARN_RATIO: f64 = 0.8;
+
+/// Matched trades waiting longer than this for settlement are flagged.
+const SETTLEMENT_WARN_SECS: i64 = 5 * 60;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DbHealth {
+    /// This process's r2d2 pool
+    pub pool_max: u32,
+    pub pool_connections: u32,
+    pub pool_idle: u32,
+    /// Connections to the database from every process, per pg_stat_activity
+    pub server_connections: i64,
+    pub server_active: i64,
+    pub server_max: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HederaHealth {
+    pub reachable: bool,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SettlementQueue {
+    /// Trades matched off-chain and not yet settled
+    pub matched: i64,
+    pub oldest_matched_at: Option<NaiveDateTime>,
+    pub failed: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AggregationLag {
+    pub market_id: Uuid,
+    pub market_name: String,
+    pub interval: TimeSeriesInterval,
+    pub last_bar_end: NaiveDateTime,
+    pub lag_secs: i64,
+    /// More than two intervals behind, the same rule as the
+    /// `aggregator_lag` system event
+    pub behind: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WebhookBacklog {
+    pub pending: i64,
+    /// Pending deliveries whose next attempt is already due
+    pub due: i64,
+    /// Pending deliveries that have failed at least once
+    pub retrying: i64,
+    pub oldest_pending_at: Option<NaiveDateTime>,
+    pub failed: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HealthReport {
+    pub checked_at: NaiveDateTime,
+    pub db: DbHealth,
+    pub hedera: HederaHealth,
+    pub settlement: SettlementQueue,
+    pub aggregation: Vec<AggregationLag>,
+    pub webhooks: WebhookBacklog,
+    /// Every published oracle price's age, stalest first
+    pub oracles: Vec<OracleStaleness>,
+    /// Anything above that needs a look, in plain words
+    pub warnings: Vec<String>,
+}
+
+#[derive(QueryableByName)]
+struct ServerConnections {
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    connections: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    active: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    max_connections: i64,
+}
+
+const SERVER_CONNECTIONS_QUERY: &str = r"
+SELECT
+    count(*)::bigint AS connections,
+    count(*) FILTER (WHERE state = 'active')::bigint AS active,
+    current_setting('max_connections')::bigint AS max_connections
+FROM pg_stat_activity
+WHERE datname = current_database();
+";
+
+fn db_health(pool: &Pool<ConnectionManager<PgConnection>>, conn: DbConn<'_>) -> Result<DbHealth> {
+    let state = pool.state();
+    let server =
+        diesel::sql_query(SERVER_CONNECTIONS_QUERY).get_result::<ServerConnections>(conn)?;
+
+    Ok(DbHealth {
+        pool_max: pool.max_size(),
+        pool_connections: state.connections,
+        pool_idle: state.idle_connections,
+        server_connections: server.connections,
+        server_active: server.active,
+        server_max: server.max_connections,
+    })
+}
+
+/// Read the access controller's balances, the cheapest query that has to
+/// reach the network.
+pub async fn hedera_health(wallet: &ActionWallet) -> HederaHealth {
+    let started = Instant::now();
+    let probe = async {
+        let contract_ids = wallet.get_contract_ids()?;
+        commons::get_account_balances(
+            &wallet.client,
+            &contract_ids.access_controller_contract_id.to_string(),
+        )
+        .await?;
+        anyhow::Ok(())
+    };
+
+    let error = match tokio::time::timeout(HEDERA_PROBE_TIMEOUT, probe).await {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(e.to_string()),
+        Err(_) => Some(format!(
+            "No response within {}s",
+            HEDERA_PROBE_TIMEOUT.as_secs()
+        )),
+    };
+
+    HederaHealth {
+        reachable: error.is_none(),
+        latency_ms: error
+            .is_none()
+            .then(|| started.elapsed().as_millis() as u64),
+        error,
+    }
+}
+
+fn settlement_queue(conn: DbConn<'_>) -> Result<SettlementQueue> {
+    use crate::schema::orderbooktrades::dsl::*;
+
+    let matched = orderbooktrades
+        .filter(settlement_status.eq(SettlementStatus::Matched))
+        .count()
+        .get_result::<i64>(conn)?;
+    let oldest_matched_at = orderbooktrades
+        .filter(settlement_status.eq(SettlementStatus::Matched))
+        .select(diesel::dsl::min(created_at))
+        .get_result::<Option<NaiveDateTime>>(conn)?;
+    let failed = orderbooktrades
+        .filter(settlement_status.eq(SettlementStatus::Failed))
+        .count()
+        .get_result::<i64>(conn)?;
+
+    Ok(SettlementQueue {
+        matched,
+        oldest_matched_at,
+        failed,
+    })
+}
+
+/// The newest order book bar per market and interval, against the clock.
+pub fn aggregation_lag(conn: DbConn<'_>) -> Result<Vec<AggregationLag>> {
+    let names: HashMap<Uuid, String> = {
+        use crate::schema::markets::dsl::*;
+
+        markets
+            .select((id, name))
+            .get_results::<(Uuid, String)>(conn)?
+            .into_iter()
+            .collect()
+    };
+
+    let latest = {
+        use crate::schema::markets_time_series::dsl::*;
+
+        markets_time_series
+            .filter(data_provider_type.eq(DataProviderType::OrderBook))
+            .group_by((market_id, interval))
+            .select((market_id, interval, diesel::dsl::max(end_time)))
+            .get_results::<(Uuid, TimeSeriesInterval, Option<NaiveDateTime>)>(conn)?
+    };
+
+    let now = Utc::now().naive_utc();
+    let mut lags: Vec<AggregationLag> = latest
+        .into_iter()
+        .filter_map(|(market, interval, last_bar_end)| {
+            let last_bar_end = last_bar_end?;
+            let lag_secs = (now - last_bar_end).num_seconds().max(0);
+            Some(AggregationLag {
+                market_name: names.get(&market).cloned().unwrap_or_default(),
+                market_id: market,
+                behind: lag_secs > 2 * interval.seconds(),
+                interval,
+                last_bar_end,
+                lag_secs,
+            })
+        })
+        .collect();
+
+    lags.sort_by(|a, b| {
+        a.market_name
+            .cmp(&b.market_name)
+            .then(a.interval.seconds().cmp(&b.interval.seconds()))
+    });
+    Ok(lags)
+}
+
+fn webhook_backlog(conn: DbConn<'_>) -> Result<WebhookBacklog> {
+    use crate::schema::webhook_deliveries::dsl::*;
+
+    let now = Utc::now().naive_utc();
+    let pending = webhook_deliveries
+        .filter(status.eq(DeliveryStatus::Pending))
+        .count()
+        .get_result::<i64>(conn)?;
+    let due = webhook_deliveries
+        .filter(status.eq(DeliveryStatus::Pending))
+        .filter(next_attempt_at.le(now))
+        .count()
+        .get_result::<i64>(conn)?;
+    let retrying = webhook_deliveries
+        .filter(status.eq(DeliveryStatus::Pending))
+        .filter(attempts.gt(0))
+        .count()
+        .get_result::<i64>(conn)?;
+    let oldest_pending_at = webhook_deliveries
+        .filter(status.eq(DeliveryStatus::Pending))
+        .select(diesel::dsl::min(created_at))
+        .get_result::<Option<NaiveDateTime>>(conn)?;
+    let failed = webhook_deliveries
+        .filter(status.eq(DeliveryStatus::Failed))
+        .count()
+        .get_result::<i64>(conn)?;
+
+    Ok(WebhookBacklog {
+        pending,
+        due,
+        retrying,
+        oldest_pending_at,
+        failed,
+    })
+}
+
+fn warnings(report: &HealthReport) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let now = report.checked_at;
+
+    let db = &report.db;
+    let checked_out = db.pool_connections.saturating_sub(db.pool_idle);
+    if db.pool_max > 0 && checked_out as f64 / db.pool_max as f64 > CONNECTION_WARN_RATIO {
+        warnings.push(format!(
+            "DB pool has {} of {} connections checked out",
+            checked_out, db.pool_max
+        ));
+    }
+    if db.server_max > 0
+        && db.server_connections as f64 / db.server_max as f64 > CONNECTION_WARN_RATIO
+    {
+        warnings.push(format!(
+            "Postgres has {} of {} connections open",
+            db.server_connections, db.server_max
+        ));
+    }
+
+    if let Some(error) = &report.hedera.error {
+        warnings.push(format!("Hedera client unreachable: {}", error));
+    }
+
+    if let Some(oldest) = report.settlement.oldest_matched_at
+        && (now - oldest).num_seconds() > SETTLEMENT_WARN_SECS
+    {
+        warnings.push(format!(
+            "{} matched trades waiting on settlement, oldest since {}",
+            report.settlement.matched, oldest
+        ));
+    }
+    if report.settlement.failed > 0 {
+        warnings.push(format!(
+            "{} trades failed to settle",
+            report.settlement.failed
+        ));
+    }
+
+    for lag in report.aggregation.iter().filter(|lag| lag.behind) {
+        warnings.push(format!(
+            "{} {} candles are {}s behind",
+            lag.market_name,
+            lag.interval.as_str(),
+            lag.lag_secs
+        ));
+    }
+
+    if report.webhooks.due > 0 {
+        warnings.push(format!(
+            "{} webhook deliveries are due and waiting",
+            report.webhooks.due
+        ));
+    }
+
+    for oracle in report.oracles.iter().filter(|o| o.stale) {
+        warnings.push(format!(
+            "Oracle price for asset {} in pool {} is {}s old",
+            oracle.asset_id, oracle.lending_pool_id, oracle.age_secs
+        ));
+    }
+
+    warnings
+}
+
+/// Check the database, the Hedera client, the settlement queue, candle
+/// aggregation, webhook delivery and oracle prices in one pass, for
+/// operators.
+pub async fn health_report(app_config: &AppConfig) -> Result<HealthReport> {
+    let hedera = hedera_health(&app_config.wallet).await;
+
+    let pool = app_config.pool.clone();
+    let (db, settlement, aggregation, webhooks, oracles) = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        anyhow::Ok((
+            db_health(&pool, &mut conn)?,
+            settlement_queue(&mut conn)?,
+            aggregation_lag(&mut conn)?,
+            webhook_backlog(&mut conn)?,
+            oracle_staleness(&mut conn)?,
+        ))
+    })
+    .await??;
+
+    let mut report = HealthReport {
+        checked_at: Utc::now().naive_utc(),
+        db,
+        hedera,
+        settlement,
+        aggregation,
+        webhooks,
+        oracles,
+        warnings: Vec::new(),
+    };
+    report.warnings = warnings(&report);
+    Ok(report)
+}
@@ -34,6 +34,9 @@ pub struct LendingPoolRecord {
     pub treasury_wallet: Uuid,
     pub reserve_wallet: Uuid,
     pub pool_account_id: Uuid,
+    /// In reserve base units; `None` means uncapped
+    pub supply_cap: Option<BigDecimal>,
+    pub borrow_cap: Option<BigDecimal>,
 }
 
 impl LendingPoolRecord {
@@ -71,6 +74,9 @@ pub struct CreateLendingPoolRecord {
     pub treasury_wallet: Uuid,
     pub reserve_wallet: Uuid,
     pub pool_account_id: Uuid,
+    /// In reserve base units; `None` means uncapped
+    pub supply_cap: Option<BigDecimal>,
+    pub borrow_cap: Option<BigDecimal>,
 }
 
 #[derive(
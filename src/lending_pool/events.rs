@@ -0,0 +1,46 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::sockets::broadcast;
+use crate::utils::app_config::AppConfig;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PoolEvent {
+    pub pool_id: Uuid,
+    pub wallet_id: Uuid,
+    pub loan_id: Option<Uuid>,
+    pub amount: String,
+}
+
+/// The pool's rates and totals as a snapshot read them, after interest has
+/// accrued. Amounts and rates are as the contract reports them.
+#[derive(Serialize, Debug, Clone)]
+pub struct PoolAccrualEvent {
+    pub pool_id: Uuid,
+    pub snapshot_id: Uuid,
+    pub total_supply: String,
+    pub total_borrow: String,
+    pub available_liquidity: String,
+    pub utilization_rate: String,
+    pub supply_apy: String,
+    pub borrow_apy: String,
+}
+
+/// Emit to the pool's room on the `/lending` and `/admin` namespaces.
+pub async fn emit_pool_event(app_config: &AppConfig, event: &str, payload: &PoolEvent) {
+    if let Ok(io) = app_config.get_io() {
+        broadcast(&io, format!("lending:{}", payload.pool_id), event, payload).await;
+    }
+}
+
+pub async fn emit_pool_accrual(app_config: &AppConfig, payload: &PoolAccrualEvent) {
+    if let Ok(io) = app_config.get_io() {
+        broadcast(
+            &io,
+            format!("lending:{}", payload.pool_id),
+            "lending:accrued",
+            payload,
+        )
+        .await;
+    }
+}
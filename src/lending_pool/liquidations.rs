@@ -0,0 +1,179 @@
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    accounts_ledger::db_types::AccountLedgerTransactionType,
+    asset_book::db_types::AssetBookRecord,
+    lending_pool::{
+        db_types::{LendingPoolRecord, LoanRecord, LoanStatus},
+        oracle::get_price_oracle,
+    },
+    utils::commons::DbConn,
+};
+
+/// Health factor below which a loan counts as at risk when no threshold is
+/// given; liquidation is possible below 1.
+pub const DEFAULT_AT_RISK_HEALTH: &str = "1.1";
+
+/// An active loan's standing, worked out from the database rather than the
+/// pool contract so a whole book can be checked without a call per loan.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LoanHealth {
+    pub loan: LoanRecord,
+    /// Collateral still locked for the loan, in its base units
+    pub collateral_amount: BigDecimal,
+    /// The collateral at the pool's oracle price, in reserve base units
+    pub collateral_value: BigDecimal,
+    /// Principal less repayments and liquidations; accrued interest isn't
+    /// known off-chain, so the real debt is at least this
+    pub outstanding_debt: BigDecimal,
+    /// Collateral value at the liquidation threshold over the debt
+    pub health_factor: BigDecimal,
+}
+
+fn ledger_sum(
+    conn: DbConn<'_>,
+    asset_id: Uuid,
+    kind: AccountLedgerTransactionType,
+    transactions: Vec<String>,
+) -> Result<BigDecimal> {
+    use crate::schema::accountassetsledger::dsl::*;
+
+    if transactions.is_empty() {
+        return Ok(BigDecimal::from(0));
+    }
+    let total = accountassetsledger
+        .filter(asset.eq(asset_id))
+        .filter(transaction_type.eq(kind))
+        .filter(transaction.eq_any(transactions))
+        .select(diesel::dsl::sum(amount))
+        .get_result::<Option<BigDecimal>>(conn)?;
+
+    Ok(total.unwrap_or_default())
+}
+
+/// Collateral locked when the loan was taken, less what repayments and
+/// liquidations have released since.
+fn locked_collateral(conn: DbConn<'_>, loan: &LoanRecord) -> Result<BigDecimal> {
+    let Some(borrow_tx) = loan.transaction.clone() else {
+        return Ok(BigDecimal::from(0));
+    };
+
+    let mut releases = {
+        use crate::schema::loanrepayments::dsl::*;
+
+        loanrepayments
+            .filter(loan_id.eq(loan.id))
+            .select(transaction)
+            .get_results::<Option<String>>(conn)?
+    };
+    releases.extend({
+        use crate::schema::loanliquidations::dsl::*;
+
+        loanliquidations
+            .filter(loan_id.eq(loan.id))
+            .select(transaction)
+            .get_results::<Option<String>>(conn)?
+    });
+
+    let locked = ledger_sum(
+        conn,
+        loan.collateral_asset,
+        AccountLedgerTransactionType::Lock,
+        vec![borrow_tx],
+    )?;
+    let released = ledger_sum(
+        conn,
+        loan.collateral_asset,
+        AccountLedgerTransactionType::UnLock,
+        releases.into_iter().flatten().collect(),
+    )?;
+
+    Ok((locked - released).max(BigDecimal::from(0)))
+}
+
+fn outstanding_debt(conn: DbConn<'_>, loan: &LoanRecord) -> Result<BigDecimal> {
+    let repaid = {
+        use crate::schema::loanrepayments::dsl::*;
+
+        loanrepayments
+            .filter(loan_id.eq(loan.id))
+            .select(diesel::dsl::sum(repayment_amount))
+            .get_result::<Option<BigDecimal>>(conn)?
+    };
+    let liquidated = {
+        use crate::schema::loanliquidations::dsl::*;
+
+        loanliquidations
+            .filter(loan_id.eq(loan.id))
+            .select(diesel::dsl::sum(liquidation_amount))
+            .get_result::<Option<BigDecimal>>(conn)?
+    };
+
+    Ok(&loan.principal_amount - repaid.unwrap_or_default() - liquidated.unwrap_or_default())
+}
+
+/// Where an active loan stands, or `None` if its collateral has no oracle
+/// price yet or there is nothing left to cover.
+pub fn loan_health(conn: DbConn<'_>, loan: LoanRecord) -> Result<Option<LoanHealth>> {
+    let Ok(price) = get_price_oracle(conn, loan.pool, loan.collateral_asset) else {
+        return Ok(None);
+    };
+    let debt = outstanding_debt(conn, &loan)?;
+    if debt <= BigDecimal::from(0) {
+        return Ok(None);
+    }
+
+    let pool = LendingPoolRecord::get(conn, loan.pool)?;
+    let collateral = {
+        use crate::schema::asset_book::dsl::*;
+
+        asset_book
+            .filter(id.eq(loan.collateral_asset))
+            .get_result::<AssetBookRecord>(conn)?
+    };
+
+    // oracle prices are reserve base units per whole collateral token, and
+    // thresholds are in basis points
+    let collateral_amount = locked_collateral(conn, &loan)?;
+    let scale = BigDecimal::from(10i64.pow(collateral.decimals as u32));
+    let collateral_value = &collateral_amount * &price.price / scale;
+    let health_factor =
+        (&collateral_value * &pool.liquidation_threshold / BigDecimal::from(10000) / &debt)
+            .round(4);
+
+    Ok(Some(LoanHealth {
+        loan,
+        collateral_amount,
+        collateral_value: collateral_value.round(0),
+        outstanding_debt: debt,
+        health_factor,
+    }))
+}
+
+/// Active loans whose health factor is below `threshold`, least healthy
+/// first.
+pub fn at_risk_loans(conn: DbConn<'_>, threshold: &BigDecimal) -> Result<Vec<LoanHealth>> {
+    let active = {
+        use crate::schema::loans::dsl::*;
+
+        loans
+            .filter(status.eq(LoanStatus::Active))
+            .get_results::<LoanRecord>(conn)?
+    };
+
+    let mut at_risk = Vec::new();
+    for loan in active {
+        if let Some(health) = loan_health(conn, loan)?
+            && &health.health_factor < threshold
+        {
+            at_risk.push(health);
+        }
+    }
+
+    at_risk.sort_by(|a, b| a.health_factor.cmp(&b.health_factor));
+    Ok(at_risk)
+}
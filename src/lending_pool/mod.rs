@@ -1,5 +1,7 @@
 pub mod config;
 pub mod db_types;
+pub mod events;
+pub mod liquidations;
 pub mod operations;
 pub mod processor;
 pub mod processor_enums;
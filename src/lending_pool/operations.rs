@@ -59,8 +59,12 @@ pub struct CreateLendingPoolArgs {
     pub liquidation_discount: u64,
     pub reserve_factor: u64,
     pub name: String,
+    /// Caps in reserve base units, left out for an uncapped pool
+    pub supply_cap: Option<u64>,
+    pub borrow_cap: Option<u64>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CreateNewYieldAsset {
     pub name: String,
     pub symbol: String,
@@ -68,6 +72,7 @@ pub struct CreateNewYieldAsset {
     pub icon: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum YieldAsset {
     New(CreateNewYieldAsset),
     Existing(Uuid),
@@ -79,6 +84,27 @@ pub async fn create_lending_pool<'a>(
     input: CreateLendingPoolArgs,
     yield_asset_input: YieldAsset,
 ) -> Result<Uuid> {
+    // everything below is basis points
+    if input.ltv >= input.liquidation_threshold || input.liquidation_threshold > 10000 {
+        return Err(anyhow!(
+            "LTV ({}) has to be below the liquidation threshold ({}), which can't exceed 10000",
+            input.ltv,
+            input.liquidation_threshold
+        ));
+    }
+    if [
+        input.optimal_utilization,
+        input.liquidation_discount,
+        input.reserve_factor,
+    ]
+    .iter()
+    .any(|bps| *bps > 10000)
+    {
+        return Err(anyhow!(
+            "Utilization, liquidation discount and reserve factor are basis points of at most 10000"
+        ));
+    }
+
     let reserve_asset = get_asset(conn, input.reserve_asset).await?;
     let yield_asset_data = match yield_asset_input {
         YieldAsset::Existing(id) => get_asset(conn, id).await?,
@@ -216,6 +242,8 @@ pub async fn create_lending_pool<'a>(
         treasury_wallet,
         reserve_wallet,
         pool_account_id: pool_account,
+        supply_cap: input.supply_cap.map(BigDecimal::from),
+        borrow_cap: input.borrow_cap.map(BigDecimal::from),
     };
 
     use crate::schema::lendingpool as lpool;
@@ -228,6 +256,64 @@ pub async fn create_lending_pool<'a>(
     Ok(created_id)
 }
 
+/// Refuse a supply that would take the pool's net deposits past its cap.
+pub fn check_supply_cap(
+    conn: DbConn<'_>,
+    pool: &LendingPoolRecord,
+    supply_amount: u64,
+) -> Result<()> {
+    let Some(cap) = &pool.supply_cap else {
+        return Ok(());
+    };
+    use crate::lending_pool::db_types::PoolTransactionType;
+    use crate::schema::pooltransactions::dsl::*;
+
+    let supplied = pooltransactions
+        .filter(pool_id.eq(pool.id))
+        .filter(transaction_type.eq(PoolTransactionType::Supply))
+        .select(diesel::dsl::sum(amount))
+        .get_result::<Option<BigDecimal>>(conn)?
+        .unwrap_or_default();
+    // withdrawals record the yield tokens burned as `amount` and the
+    // reserve paid out as `yield_token_amount`
+    let withdrawn = pooltransactions
+        .filter(pool_id.eq(pool.id))
+        .filter(transaction_type.eq(PoolTransactionType::Withdraw))
+        .select(diesel::dsl::sum(yield_token_amount))
+        .get_result::<Option<BigDecimal>>(conn)?
+        .unwrap_or_default();
+
+    if supplied - withdrawn + BigDecimal::from(supply_amount) > *cap {
+        return Err(anyhow!(
+            "Supplying {} would take the pool past its supply cap of {}",
+            supply_amount,
+            cap
+        ));
+    }
+    Ok(())
+}
+
+/// Refuse new loans once the principal of the pool's active loans has
+/// reached its cap; how much a loan borrows is only known once it's taken.
+pub fn check_borrow_cap(conn: DbConn<'_>, pool_record: &LendingPoolRecord) -> Result<()> {
+    let Some(cap) = &pool_record.borrow_cap else {
+        return Ok(());
+    };
+    use crate::schema::loans::dsl::*;
+
+    let outstanding = loans
+        .filter(pool.eq(pool_record.id))
+        .filter(status.eq(LoanStatus::Active))
+        .select(diesel::dsl::sum(principal_amount))
+        .get_result::<Option<BigDecimal>>(conn)?
+        .unwrap_or_default();
+
+    if outstanding >= *cap {
+        return Err(anyhow!("The pool has reached its borrow cap of {}", cap));
+    }
+    Ok(())
+}
+
 pub async fn get_pool_treasury<'a>(wallet: TaskWallet<'a>, contract_id: String) -> Result<String> {
     let tx_input = ContractCallInput::AssetLendingPool(
         AssetLendingPoolFunctionsInput::GetTreasuryAccount(contract_id),
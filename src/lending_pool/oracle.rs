@@ -4,7 +4,8 @@ use contract_integrator::utils::functions::asset_lending::UpdateOracleArgs;
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use crate::{asset_book::operations::get_asset, big_to_u64, schema::lending_pool_oracle_prices as lpop, utils::commons::{DbConn, TaskWallet}};
+use crate::{asset_book::operations::get_asset, big_to_u64, schema::lending_pool_oracle_prices as lpop, schema::oracle_price_history as oph, utils::commons::{DbConn, TaskWallet}};
+use serde_json::Value;
 use anyhow::{Result, anyhow};
 
 #[derive(Serialize, Deserialize, Queryable, Identifiable, Debug, Clone)]
@@ -28,6 +29,34 @@ pub struct CreatePriceOracle {
 }
 
 
+/// A price as it was sent to a pool's oracle contract.
+#[derive(Serialize, Deserialize, Queryable, Identifiable, Debug, Clone)]
+#[diesel(table_name= oph)]
+pub struct OraclePriceHistory {
+    pub id: Uuid,
+    pub lending_pool_id: Uuid,
+    pub asset_id: Uuid,
+    /// Reserve base units per whole token, as sent
+    pub price: BigDecimal,
+    /// What the price was made from
+    pub sources: Value,
+    pub published_by: String,
+    pub transaction: String,
+    pub published_at: NaiveDateTime
+}
+
+#[derive(Serialize, Deserialize, Debug, Insertable)]
+#[diesel(table_name= oph)]
+pub struct CreateOraclePriceHistory {
+    pub lending_pool_id: Uuid,
+    pub asset_id: Uuid,
+    pub price: BigDecimal,
+    pub sources: Value,
+    pub published_by: String,
+    pub transaction: String,
+}
+
+
 pub fn create_price_oracle<'a>(conn: DbConn<'a>, args: CreatePriceOracle)->Result<Uuid> {
 
     let res_id = diesel::insert_into(lpop::table).values(&args).returning(lpop::dsl::id).get_result::<Uuid>(conn)?;
@@ -49,7 +78,10 @@ pub fn update_price_oracle<'a>(conn: DbConn<'a>, lending_pool: Uuid, asset: Uuid
         .values(&new_oracle)
         .on_conflict((lpop::dsl::lending_pool_id, lpop::dsl::asset_id))
         .do_update()
-        .set(lpop::dsl::price.eq(&new_oracle.price))
+        .set((
+            lpop::dsl::price.eq(&new_oracle.price),
+            lpop::dsl::recorded_at.eq(new_oracle.recorded_at),
+        ))
         .execute(conn)?;
 
     Ok(())
@@ -65,7 +97,123 @@ pub fn get_price_oracle<'a>(conn: DbConn<'a>, lending_pool: Uuid, asset: Uuid)->
     Ok(res)
 }
 
-pub async fn publish_price<'a>(conn: DbConn<'a>, wallet: TaskWallet<'a>, lending_pool: Uuid, asset_id: Uuid, price: BigDecimal) -> Result<()>{
+/// Age after which an oracle price is stale, from ORACLE_STALE_AFTER_SECS
+/// (default one hour). Borrows, withdrawals and liquidations won't go ahead
+/// on a stale price.
+pub fn stale_after_secs() -> i64 {
+    std::env::var("ORACLE_STALE_AFTER_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(3600)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OracleStaleness {
+    pub lending_pool_id: Uuid,
+    pub asset_id: Uuid,
+    pub recorded_at: NaiveDateTime,
+    pub age_secs: i64,
+    pub stale: bool,
+}
+
+impl PriceOracle {
+    pub fn age_secs(&self) -> i64 {
+        (Utc::now().naive_utc() - self.recorded_at).num_seconds().max(0)
+    }
+
+    pub fn is_stale(&self) -> bool {
+        self.age_secs() > stale_after_secs()
+    }
+}
+
+/// When every recorded price was last published, stalest first.
+pub fn oracle_staleness<'a>(conn: DbConn<'a>) -> Result<Vec<OracleStaleness>> {
+    let mut prices = lpop::dsl::lending_pool_oracle_prices
+        .get_results::<PriceOracle>(conn)?
+        .into_iter()
+        .map(|p| OracleStaleness {
+            lending_pool_id: p.lending_pool_id,
+            asset_id: p.asset_id,
+            recorded_at: p.recorded_at,
+            age_secs: p.age_secs(),
+            stale: p.is_stale(),
+        })
+        .collect::<Vec<_>>();
+
+    prices.sort_by(|a, b| b.age_secs.cmp(&a.age_secs));
+    Ok(prices)
+}
+
+/// The pool's price for the asset, refusing one older than the heartbeat so
+/// nothing is lent or seized against a price the market has moved away from.
+pub fn require_fresh_price<'a>(conn: DbConn<'a>, lending_pool: Uuid, asset: Uuid) -> Result<PriceOracle> {
+    let price = get_price_oracle(conn, lending_pool, asset)
+        .map_err(|_| anyhow!("No oracle price for asset {} in pool {}", asset, lending_pool))?;
+
+    if price.is_stale() {
+        return Err(anyhow!(
+            "The oracle price is stale: asset {} in pool {} was last priced {}s ago, over the {}s limit",
+            asset,
+            lending_pool,
+            price.age_secs(),
+            stale_after_secs()
+        ));
+    }
+    Ok(price)
+}
+
+/// Require fresh prices for every collateral asset backing an active loan in
+/// the pool, since withdrawals lean on those loans being sound.
+pub fn require_fresh_pool_prices<'a>(conn: DbConn<'a>, lending_pool: Uuid) -> Result<()> {
+    let collateral = {
+        use crate::schema::loans::dsl::*;
+
+        loans
+            .filter(pool.eq(lending_pool))
+            .filter(status.eq(crate::lending_pool::db_types::LoanStatus::Active))
+            .select(collateral_asset)
+            .distinct()
+            .get_results::<Uuid>(conn)?
+    };
+
+    for asset in collateral {
+        require_fresh_price(conn, lending_pool, asset)?;
+    }
+    Ok(())
+}
+
+/// Published prices, newest first, optionally for one asset or pool and
+/// between two times.
+pub fn get_price_history<'a>(
+    conn: DbConn<'a>,
+    asset: Option<Uuid>,
+    lending_pool: Option<Uuid>,
+    from: Option<NaiveDateTime>,
+    to: Option<NaiveDateTime>,
+) -> Result<Vec<OraclePriceHistory>> {
+    let mut query = oph::dsl::oracle_price_history.into_boxed();
+    if let Some(asset) = asset {
+        query = query.filter(oph::asset_id.eq(asset));
+    }
+    if let Some(lending_pool) = lending_pool {
+        query = query.filter(oph::lending_pool_id.eq(lending_pool));
+    }
+    if let Some(from) = from {
+        query = query.filter(oph::published_at.ge(from));
+    }
+    if let Some(to) = to {
+        query = query.filter(oph::published_at.lt(to));
+    }
+
+    Ok(query
+        .order(oph::published_at.desc())
+        .limit(1000)
+        .get_results::<OraclePriceHistory>(conn)?)
+}
+
+/// Push a price to the pool's oracle contract and record it. `published_by`
+/// and `sources` say who sent it and what it was made from, for the history.
+pub async fn publish_price<'a>(conn: DbConn<'a>, wallet: TaskWallet<'a>, lending_pool: Uuid, asset_id: Uuid, price: BigDecimal, published_by: &str, sources: Value) -> Result<()>{
 
     let pool = crate::lending_pool::operations::get_pool(conn, lending_pool).await?;
     let asset = get_asset(conn, asset_id).await?;
@@ -79,7 +227,18 @@ pub async fn publish_price<'a>(conn: DbConn<'a>, wallet: TaskWallet<'a>, lending
 
     println!("TX :: {:?}", res.transaction_id);
 
-    update_price_oracle(conn, lending_pool, asset_id, price)?;
+    update_price_oracle(conn, lending_pool, asset_id, price.clone())?;
+
+    diesel::insert_into(oph::table)
+        .values(&CreateOraclePriceHistory {
+            lending_pool_id: lending_pool,
+            asset_id,
+            price,
+            sources,
+            published_by: published_by.to_string(),
+            transaction: res.transaction_id.to_string(),
+        })
+        .execute(conn)?;
 
     Ok(())
 }
\ No newline at end of file
@@ -6,11 +6,17 @@ use crate::accounts_ledger::operations::{
 };
 use crate::asset_book::db_types::AssetBookRecord;
 use crate::lending_pool::config::LendingPoolConfig;
+use crate::lending_pool::events::{
+    PoolAccrualEvent, PoolEvent, emit_pool_accrual, emit_pool_event,
+};
 use crate::lending_pool::db_types::{
     CreateLendingPoolSnapShotRecord, CreateLoanRecord, CreatePoolTransactionRecord,
     LendingPoolRecord, LendingPoolSnapShotRecord, LoanStatus, PoolTransactionType,
 };
-use crate::lending_pool::operations::{UpdateRepaymentArgs, update_repayment};
+use crate::lending_pool::operations::{
+    UpdateRepaymentArgs, check_borrow_cap, check_supply_cap, create_lending_pool, update_repayment,
+};
+use crate::lending_pool::oracle::{require_fresh_pool_prices, require_fresh_price};
 use crate::lending_pool::processor_enums::{
     GetLendingPoolInput, LendingPoolFunctionsInput, LendingPoolFunctionsOutput,
 };
@@ -47,6 +53,16 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
                     .get_result::<Uuid>(app_conn)?;
                 Ok(LendingPoolFunctionsOutput::CreateLendingPool(res))
             }
+            LendingPoolFunctionsInput::DeployLendingPool(args) => {
+                let res = create_lending_pool(
+                    app_conn,
+                    &app_config.wallet,
+                    args.pool.clone(),
+                    args.yield_asset.clone(),
+                )
+                .await?;
+                Ok(LendingPoolFunctionsOutput::DeployLendingPool(res))
+            }
             LendingPoolFunctionsInput::GetLendingPool(filters) => {
                 use crate::schema::lendingpool::dsl::*;
                 let mut query = lendingpool.into_boxed();
@@ -95,6 +111,21 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
                             .returning(crate::schema::lendingpoolsnapshots::dsl::id)
                             .get_result::<Uuid>(app_conn)?;
 
+                    emit_pool_accrual(
+                        app_config,
+                        &PoolAccrualEvent {
+                            pool_id: pool.id,
+                            snapshot_id,
+                            total_supply: new_snapshot.total_supply.to_string(),
+                            total_borrow: new_snapshot.total_borrow.to_string(),
+                            available_liquidity: new_snapshot.available_liquidity.to_string(),
+                            utilization_rate: new_snapshot.utilization_rate.to_string(),
+                            supply_apy: new_snapshot.supply_apy.to_string(),
+                            borrow_apy: new_snapshot.borrow_apy.to_string(),
+                        },
+                    )
+                    .await;
+
                     return Ok(LendingPoolFunctionsOutput::CreateSnapShot(snapshot_id));
                 }
 
@@ -112,6 +143,7 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
             }
             LendingPoolFunctionsInput::SupplyLiquidity(args) => {
                 let pool = LendingPoolRecord::get(app_conn, args.pool)?;
+                check_supply_cap(app_conn, &pool, args.amount)?;
                 use crate::schema::cradlewalletaccounts;
                 let wallet = cradlewalletaccounts::dsl::cradlewalletaccounts
                     .filter(cradlewalletaccounts::dsl::id.eq(args.wallet))
@@ -120,7 +152,7 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
                 // auto associate and grant kyc to account for user
                 associate_token(
                     app_conn,
-                    &mut app_config.wallet,
+                    &app_config.wallet,
                     AssociateTokenToWalletInputArgs {
                         wallet_id: wallet.id,
                         token: pool.yield_asset,
@@ -130,7 +162,7 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
 
                 kyc_token(
                     app_conn,
-                    &mut app_config.wallet,
+                    &app_config.wallet,
                     GrantKYCInputArgs {
                         wallet_id: wallet.id,
                         token: pool.yield_asset,
@@ -144,7 +176,7 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
                         user: wallet.address.clone(),
                         contract_id: pool.pool_contract_id,
                     },
-                    &mut app_config.wallet,
+                    &app_config.wallet,
                 )
                 .await?;
 
@@ -185,10 +217,23 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
                     .returning(crate::schema::pooltransactions::dsl::id)
                     .get_result::<Uuid>(app_conn)?;
 
+                emit_pool_event(
+                    app_config,
+                    "lending:supply",
+                    &PoolEvent {
+                        pool_id: args.pool,
+                        wallet_id: wallet.id,
+                        loan_id: None,
+                        amount: args.amount.to_string(),
+                    },
+                )
+                .await;
+
                 return Ok(LendingPoolFunctionsOutput::SupplyLiquidity(res));
             }
             LendingPoolFunctionsInput::WithdrawLiquidity(args) => {
                 let pool = LendingPoolRecord::get(app_conn, args.pool)?;
+                require_fresh_pool_prices(app_conn, pool.id)?;
 
                 use crate::schema::cradlewalletaccounts::dsl as cwa_dsl;
 
@@ -202,7 +247,7 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
                         user: wallet.address.clone(),
                         contract_id: pool.pool_contract_id,
                     },
-                    &mut app_config.wallet,
+                    &app_config.wallet,
                 )
                 .await?;
                 let result = ContractCallOutput::AssetLendingPool(
@@ -242,10 +287,24 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
                     .returning(crate::schema::pooltransactions::dsl::id)
                     .get_result::<Uuid>(app_conn)?;
 
+                emit_pool_event(
+                    app_config,
+                    "lending:withdraw",
+                    &PoolEvent {
+                        pool_id: args.pool,
+                        wallet_id: wallet.id,
+                        loan_id: None,
+                        amount: args.amount.to_string(),
+                    },
+                )
+                .await;
+
                 return Ok(LendingPoolFunctionsOutput::WithdrawLiquidity(res));
             }
             LendingPoolFunctionsInput::BorrowAsset(args) => {
                 let pool = LendingPoolRecord::get(app_conn, args.pool)?;
+                check_borrow_cap(app_conn, &pool)?;
+                require_fresh_price(app_conn, pool.id, args.collateral)?;
 
                 use crate::schema::asset_book::dsl::*;
                 use crate::schema::cradlewalletaccounts::dsl as cwa_dsl;
@@ -262,7 +321,7 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
                 // auto associate and grant kyc to account for user
                 associate_token(
                     app_conn,
-                    &mut app_config.wallet,
+                    &app_config.wallet,
                     AssociateTokenToWalletInputArgs {
                         wallet_id: wallet.id,
                         token: pool.reserve_asset,
@@ -272,7 +331,7 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
 
                 kyc_token(
                     app_conn,
-                    &mut app_config.wallet,
+                    &app_config.wallet,
                     GrantKYCInputArgs {
                         wallet_id: wallet.id,
                         token: pool.reserve_asset,
@@ -290,7 +349,7 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
                         user: wallet.address.clone(),
                         contract_id: pool.pool_contract_id.to_string(),
                     },
-                    &mut app_config.wallet,
+                    &app_config.wallet,
                 )
                 .await?;
 
@@ -333,6 +392,18 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
                     .returning(crate::schema::loans::dsl::id)
                     .get_result::<Uuid>(app_conn)?;
 
+                emit_pool_event(
+                    app_config,
+                    "lending:borrow",
+                    &PoolEvent {
+                        pool_id: args.pool,
+                        wallet_id: wallet.id,
+                        loan_id: Some(loan_id),
+                        amount: data.borrowed_amount.to_string(),
+                    },
+                )
+                .await;
+
                 return Ok(LendingPoolFunctionsOutput::BorrowAsset(loan_id));
             }
             LendingPoolFunctionsInput::RepayBorrow(args) => {
@@ -360,7 +431,7 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
                         repay_amount: args.amount,
                         contract_id: pool.pool_contract_id,
                     },
-                    &mut app_config.wallet,
+                    &app_config.wallet,
                 )
                 .await?;
 
@@ -391,7 +462,7 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
 
                 update_repayment(
                     app_conn,
-                    &mut app_config.wallet,
+                    &app_config.wallet,
                     UpdateRepaymentArgs {
                         loan_id: loan.id,
                         amount: args.amount,
@@ -400,6 +471,18 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
                 )
                 .await?;
 
+                emit_pool_event(
+                    app_config,
+                    "lending:repay",
+                    &PoolEvent {
+                        pool_id: loan.pool,
+                        wallet_id: wallet.id,
+                        loan_id: Some(loan.id),
+                        amount: args.amount.to_string(),
+                    },
+                )
+                .await;
+
                 return Ok(LendingPoolFunctionsOutput::RepayBorrow());
             }
             LendingPoolFunctionsInput::LiquidatePosition(args) => {
@@ -420,6 +503,7 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
                     .get_result::<CradleWalletAccountRecord>(app_conn)?;
 
                 let pool = LendingPoolRecord::get(app_conn, loan.pool)?;
+                require_fresh_price(app_conn, loan.pool, loan.collateral_asset)?;
 
                 let collateral_record = asset_book
                     .filter(crate::schema::asset_book::dsl::id.eq(loan.collateral_asset))
@@ -428,7 +512,7 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
                 // associate collateral asset and kyc before giving the user the asset
                 associate_token(
                     app_conn,
-                    &mut app_config.wallet,
+                    &app_config.wallet,
                     AssociateTokenToWalletInputArgs {
                         wallet_id: args.wallet,
                         token: loan.collateral_asset,
@@ -438,7 +522,7 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
 
                 kyc_token(
                     app_conn,
-                    &mut app_config.wallet,
+                    &app_config.wallet,
                     GrantKYCInputArgs {
                         wallet_id: args.wallet,
                         token: loan.collateral_asset,
@@ -454,7 +538,7 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
                         collateral_asset: collateral_record.token.clone(),
                         contract_id: pool.pool_contract_id,
                     },
-                    &mut app_config.wallet,
+                    &app_config.wallet,
                 )
                 .await?;
 
@@ -488,7 +572,19 @@ impl ActionProcessor<LendingPoolConfig, LendingPoolFunctionsOutput> for LendingP
                     .returning(crate::schema::loanliquidations::dsl::id)
                     .get_result::<Uuid>(app_conn)?;
 
-                return Ok(LendingPoolFunctionsOutput::LiquidatePosition());
+                emit_pool_event(
+                    app_config,
+                    "lending:liquidation",
+                    &PoolEvent {
+                        pool_id: loan.pool,
+                        wallet_id: liquidator_wallet.id,
+                        loan_id: Some(loan.id),
+                        amount: args.amount.to_string(),
+                    },
+                )
+                .await;
+
+                return Ok(LendingPoolFunctionsOutput::LiquidatePosition(res));
             }
         }
     }
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use crate::lending_pool::db_types::{CreateLendingPoolRecord, LendingPoolRecord, LendingPoolSnapShotRecord};
+use crate::lending_pool::operations::{CreateLendingPoolArgs, YieldAsset};
 
 #[derive(Serialize,Deserialize, Debug, Clone )]
 pub enum GetLendingPoolInput {
@@ -45,9 +46,18 @@ pub struct LiquidatePositionInputArgs {
     pub amount: u64
 }
 
+/// Deploys the pool contract through the factory and registers it, unlike
+/// `CreateLendingPool` which only records a pool that already exists.
+#[derive(Serialize, Deserialize, Debug, Clone )]
+pub struct DeployLendingPoolInputArgs {
+    pub pool: CreateLendingPoolArgs,
+    pub yield_asset: YieldAsset
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub enum LendingPoolFunctionsInput {
     CreateLendingPool(CreateLendingPoolRecord),
+    DeployLendingPool(DeployLendingPoolInputArgs),
     GetLendingPool(GetLendingPoolInput),
     CreateSnapShot(Uuid),
     GetSnapShot(Uuid),
@@ -63,6 +73,7 @@ pub enum LendingPoolFunctionsInput {
 #[derive(Deserialize, Serialize, Debug)]
 pub enum LendingPoolFunctionsOutput {
     CreateLendingPool(Uuid),
+    DeployLendingPool(Uuid),
     GetLendingPool(LendingPoolRecord),
     CreateSnapShot(Uuid),
     GetSnapShot(LendingPoolSnapShotRecord),
@@ -70,7 +81,7 @@ pub enum LendingPoolFunctionsOutput {
     WithdrawLiquidity(Uuid),
     BorrowAsset(Uuid),
     RepayBorrow(),
-    LiquidatePosition()
+    LiquidatePosition(Uuid)
 }
 
 
@@ -5,14 +5,20 @@ pub mod action_router;
 pub mod aggregators;
 pub mod api;
 pub mod asset_book;
+pub mod audit_log;
 pub mod cli_helper;
 pub mod cli_utils;
+pub mod documents;
+pub mod health;
 pub mod lending_pool;
 pub mod listing;
 pub mod market;
 pub mod market_time_series;
+pub mod notifications;
 pub mod order_book;
+pub mod price_feeds;
 pub mod ramper;
 pub mod schema;
 pub mod sockets;
 pub mod utils;
+pub mod webhooks;
@@ -0,0 +1,308 @@
+use anyhow::{Result, anyhow};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::listing::db_types::{
+    AllocationStatus, CreateListingAllocation, ListingAllocationMode, ListingAllocationRow,
+    ListingLifecycle,
+};
+use crate::listing::events::emit_listing_event;
+use crate::listing::lifecycle::expect_lifecycle;
+use crate::listing::operations::{
+    GetPurchaseFeeInputArgs, PurchaseListingAssetInputArgs, execute_purchase, expect_purchasable,
+    get_listing, get_listing_sold_supply, get_purchase_fee,
+};
+use crate::listing::tranches::get_tranches;
+use crate::order_book::operations::{lock_asset, unlock_asset};
+use crate::utils::app_config::AppConfig;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetAllocationModeInputArgs {
+    pub listing_id: Uuid,
+    pub mode: ListingAllocationMode,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ListingAllocationEvent {
+    pub listing_id: Uuid,
+    pub wallet_id: Uuid,
+    pub requested: String,
+    pub allocated: String,
+    pub refunded: String,
+    pub status: AllocationStatus,
+}
+
+impl From<&ListingAllocationRow> for ListingAllocationEvent {
+    fn from(row: &ListingAllocationRow) -> Self {
+        Self {
+            listing_id: row.listing_id,
+            wallet_id: row.wallet_id,
+            requested: row.requested.to_string(),
+            allocated: row.allocated.to_string(),
+            refunded: row.refunded.to_string(),
+            status: row.status.clone(),
+        }
+    }
+}
+
+/// Split `available` across `requests` in proportion to their size, rounding
+/// each share down. Everyone gets their full request when not oversubscribed.
+pub fn pro_rata_shares(requests: &[BigDecimal], available: &BigDecimal) -> Vec<BigDecimal> {
+    let zero = BigDecimal::from(0);
+    let total: BigDecimal = requests.iter().sum();
+
+    if total <= *available {
+        return requests.to_vec();
+    }
+    if *available <= zero {
+        return vec![zero; requests.len()];
+    }
+
+    requests
+        .iter()
+        .map(|r| (r * available / &total).with_scale(0))
+        .collect()
+}
+
+pub fn set_allocation_mode(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    input: SetAllocationModeInputArgs,
+) -> Result<()> {
+    if input.mode == ListingAllocationMode::ProRata
+        && !get_tranches(conn, input.listing_id)?.is_empty()
+    {
+        return Err(anyhow!(
+            "Listing {} sells in tranches and can't allocate pro-rata",
+            input.listing_id
+        ));
+    }
+
+    use crate::schema::cradlenativelistings::dsl::*;
+
+    let updated = diesel::update(cradlenativelistings)
+        .filter(id.eq(input.listing_id))
+        .filter(lifecycle.eq_any(vec![
+            ListingLifecycle::Draft,
+            ListingLifecycle::Submitted,
+            ListingLifecycle::Approved,
+        ]))
+        .set(allocation_mode.eq(input.mode))
+        .execute(conn)?;
+
+    if updated == 0 {
+        return Err(anyhow!(
+            "Allocation mode of listing {} can't change once it has gone live",
+            input.listing_id
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn get_allocation(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: Uuid,
+    wallet: Uuid,
+) -> Result<ListingAllocationRow> {
+    use crate::schema::listing_allocations::dsl::*;
+
+    Ok(listing_allocations
+        .filter(listing_id.eq(listing))
+        .filter(wallet_id.eq(wallet))
+        .get_result::<ListingAllocationRow>(conn)?)
+}
+
+/// Record a purchase on a pro-rata listing as a commitment. The purchase
+/// asset is locked in the buyer's account until the allocation pass.
+pub async fn commit(
+    app_config: &mut AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    input: PurchaseListingAssetInputArgs,
+) -> Result<ListingAllocationRow> {
+    let listing = get_listing(conn, input.listing).await?;
+    expect_purchasable(&listing)?;
+    if listing.allocation_mode != ListingAllocationMode::ProRata {
+        return Err(anyhow!("Listing {} does not take commitments", listing.id));
+    }
+
+    let cost = get_purchase_fee(
+        conn,
+        &app_config.wallet,
+        GetPurchaseFeeInputArgs {
+            listing_id: listing.id,
+            amount: input.amount.clone(),
+        },
+    )
+    .await?;
+
+    lock_asset(app_config, conn, input.wallet, listing.purchase_with_asset, cost).await?;
+
+    use crate::schema::listing_allocations::dsl::*;
+
+    let row = diesel::insert_into(listing_allocations)
+        .values(CreateListingAllocation {
+            listing_id: listing.id,
+            wallet_id: input.wallet,
+            requested: input.amount.clone(),
+            locked: BigDecimal::from(cost),
+        })
+        .on_conflict((listing_id, wallet_id))
+        .do_update()
+        .set((
+            requested.eq(requested + excluded(requested)),
+            locked.eq(locked + excluded(locked)),
+        ))
+        .get_result::<ListingAllocationRow>(conn)?;
+
+    Ok(row)
+}
+
+/// Distribute remaining supply across outstanding commitments. Runs while the
+/// listing is still open on-chain, right before it is closed. Each wallet's
+/// lock is released and its share bought through the contract, so whatever
+/// the share didn't cost is what gets refunded. A wallet whose purchase fails
+/// is marked `failed` with its whole commitment released.
+pub async fn run_allocation(
+    app_config: &mut AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing_id: Uuid,
+) -> Result<Vec<ListingAllocationRow>> {
+    let listing = get_listing(conn, listing_id).await?;
+    expect_lifecycle(&listing, ListingLifecycle::Live)?;
+    if listing.allocation_mode != ListingAllocationMode::ProRata {
+        return Err(anyhow!("Listing {} is not pro-rata", listing.id));
+    }
+
+    let pending = {
+        use crate::schema::listing_allocations::dsl::*;
+
+        listing_allocations
+            .filter(listing_id.eq(listing.id))
+            .filter(status.eq(AllocationStatus::Committed))
+            .order(created_at.asc())
+            .get_results::<ListingAllocationRow>(conn)?
+    };
+
+    let available = &listing.max_supply - get_listing_sold_supply(conn, &listing)?;
+    let requests: Vec<BigDecimal> = pending.iter().map(|r| r.requested.clone()).collect();
+    let shares = pro_rata_shares(&requests, &available);
+
+    let mut results = Vec::with_capacity(pending.len());
+
+    for (row, share) in pending.into_iter().zip(shares) {
+        let held = row
+            .locked
+            .to_u64()
+            .ok_or_else(|| anyhow!("Locked amount out of range for {}", row.id))?;
+
+        unlock_asset(app_config, conn, row.wallet_id, listing.purchase_with_asset, held).await?;
+
+        // Lock is gone; persist that before anything else can fail so a
+        // rerun never unlocks twice.
+        {
+            use crate::schema::listing_allocations::dsl::*;
+            diesel::update(listing_allocations.find(row.id))
+                .set(locked.eq(BigDecimal::from(0)))
+                .execute(conn)?;
+        }
+
+        let outcome = if share > BigDecimal::from(0) {
+            match get_purchase_fee(
+                conn,
+                &app_config.wallet,
+                GetPurchaseFeeInputArgs {
+                    listing_id: listing.id,
+                    amount: share.clone(),
+                },
+            )
+            .await
+            {
+                Ok(cost) => execute_purchase(conn, &app_config.wallet, &listing, row.wallet_id, &share)
+                    .await
+                    .map(|_| (share.clone(), &row.locked - BigDecimal::from(cost))),
+                Err(e) => Err(e),
+            }
+        } else {
+            Ok((BigDecimal::from(0), row.locked.clone()))
+        };
+
+        let (new_status, allocated_amount, refunded_amount) = match outcome {
+            Ok((allocated_amount, refunded_amount)) => {
+                (AllocationStatus::Allocated, allocated_amount, refunded_amount)
+            }
+            Err(e) => {
+                println!("Allocation for wallet {} on listing {} failed: {:?}", row.wallet_id, listing.id, e);
+                (AllocationStatus::Failed, BigDecimal::from(0), row.locked.clone())
+            }
+        };
+
+        let updated = {
+            use crate::schema::listing_allocations::dsl::*;
+            diesel::update(listing_allocations.find(row.id))
+                .set((
+                    status.eq(new_status),
+                    allocated.eq(allocated_amount),
+                    refunded.eq(refunded_amount),
+                    allocated_at.eq(Some(Utc::now().naive_utc())),
+                ))
+                .get_result::<ListingAllocationRow>(conn)?
+        };
+
+        emit_listing_event(
+            app_config,
+            listing.id,
+            "listing:allocation",
+            &ListingAllocationEvent::from(&updated),
+        )
+        .await;
+
+        results.push(updated);
+    }
+
+    Ok(results)
+}
+
+/// Allocation step for the close path; does nothing for first-come listings.
+pub async fn allocate_before_close(
+    app_config: &mut AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing_id: Uuid,
+) -> Result<()> {
+    let listing = get_listing(conn, listing_id).await?;
+    if listing.allocation_mode == ListingAllocationMode::ProRata {
+        let rows = run_allocation(app_config, conn, listing_id).await?;
+        println!("Allocated listing {} across {} commitments", listing_id, rows.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(v: i64) -> BigDecimal {
+        BigDecimal::from(v)
+    }
+
+    #[test]
+    fn full_fill_when_undersubscribed() {
+        let shares = pro_rata_shares(&[big(10), big(20)], &big(100));
+        assert_eq!(shares, vec![big(10), big(20)]);
+    }
+
+    #[test]
+    fn scales_down_and_rounds_when_oversubscribed() {
+        // 300 requested for 100 available
+        let shares = pro_rata_shares(&[big(100), big(200)], &big(100));
+        assert_eq!(shares, vec![big(33), big(66)]);
+        assert!(shares.iter().sum::<BigDecimal>() <= big(100));
+    }
+}
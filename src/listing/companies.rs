@@ -0,0 +1,133 @@
+use anyhow::{Result, anyhow};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::accounts::db_types::AdminOperatorRecord;
+use crate::accounts::operators::require_approver;
+use crate::listing::db_types::{
+    CompanyRow, CompanyVerification, CradleNativeListingRow, UpdateCompany,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UpdateCompanyInputArgs {
+    pub company_id: Uuid,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub legal_documents: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetCompanyVerificationInputArgs {
+    pub company_id: Uuid,
+    pub status: CompanyVerification,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CompanyProfile {
+    pub company: CompanyRow,
+    pub listings: Vec<CradleNativeListingRow>,
+}
+
+pub fn get_company(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    company_id: Uuid,
+) -> Result<CompanyRow> {
+    use crate::schema::cradlelistedcompanies::dsl::*;
+
+    Ok(cradlelistedcompanies
+        .filter(id.eq(company_id))
+        .get_result::<CompanyRow>(conn)?)
+}
+
+/// Change a company's details. Replacing the legal documents of a verified
+/// company sends it back to unverified until it is reviewed again.
+pub fn update_company(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    input: UpdateCompanyInputArgs,
+) -> Result<CompanyRow> {
+    if input.name.is_none() && input.description.is_none() && input.legal_documents.is_none() {
+        return Err(anyhow!("Nothing to update"));
+    }
+    if input.name.as_ref().is_some_and(|n| n.trim().is_empty()) {
+        return Err(anyhow!("Company name can't be empty"));
+    }
+
+    let existing = get_company(conn, input.company_id)?;
+    let documents_changed = input
+        .legal_documents
+        .as_ref()
+        .is_some_and(|d| *d != existing.legal_documents);
+
+    use crate::schema::cradlelistedcompanies::dsl::*;
+
+    let mut updated = diesel::update(cradlelistedcompanies.find(input.company_id))
+        .set(UpdateCompany {
+            name: input.name,
+            description: input.description,
+            legal_documents: input.legal_documents,
+        })
+        .get_result::<CompanyRow>(conn)?;
+
+    if documents_changed && updated.verification_status == CompanyVerification::Verified {
+        updated = diesel::update(cradlelistedcompanies.find(input.company_id))
+            .set((
+                verification_status.eq(CompanyVerification::Unverified),
+                verified_by.eq(None::<Uuid>),
+                verified_at.eq(None::<chrono::NaiveDateTime>),
+            ))
+            .get_result::<CompanyRow>(conn)?;
+    }
+
+    Ok(updated)
+}
+
+pub fn set_company_verification(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    verifier: &AdminOperatorRecord,
+    input: SetCompanyVerificationInputArgs,
+) -> Result<CompanyRow> {
+    require_approver(conn, verifier)?;
+
+    use crate::schema::cradlelistedcompanies::dsl::*;
+
+    let (by, at) = match input.status {
+        CompanyVerification::Verified => (Some(verifier.account_id), Some(Utc::now().naive_utc())),
+        CompanyVerification::Unverified => (None, None),
+    };
+
+    Ok(diesel::update(cradlelistedcompanies.find(input.company_id))
+        .set((
+            verification_status.eq(input.status),
+            verified_by.eq(by),
+            verified_at.eq(at),
+        ))
+        .get_result::<CompanyRow>(conn)?)
+}
+
+/// A company with all of its listings, newest first.
+pub fn get_company_profile(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    company_id: Uuid,
+) -> Result<CompanyProfile> {
+    let company_row = get_company(conn, company_id)?;
+
+    let listings = {
+        use crate::schema::cradlenativelistings::dsl::*;
+
+        cradlenativelistings
+            .filter(company.eq(company_id))
+            .order(created_at.desc())
+            .get_results::<CradleNativeListingRow>(conn)?
+    };
+
+    Ok(CompanyProfile {
+        company: company_row,
+        listings,
+    })
+}
@@ -1 +1,22 @@
-pub struct CradleNativeListingsConfig {}
+use anyhow::{Result, anyhow};
+
+use crate::accounts::db_types::AdminOperatorRecord;
+
+pub struct CradleNativeListingsConfig {
+    /// Operator the action was authenticated as; approvals need one
+    pub operator: Option<AdminOperatorRecord>,
+}
+
+impl CradleNativeListingsConfig {
+    pub fn operator(&self) -> Result<&AdminOperatorRecord> {
+        self.operator
+            .as_ref()
+            .ok_or_else(|| anyhow!("This action needs an authenticated operator"))
+    }
+
+    pub fn approver(&self) -> Result<&AdminOperatorRecord> {
+        self.operator
+            .as_ref()
+            .ok_or_else(|| anyhow!("Approvals need an authenticated operator"))
+    }
+}
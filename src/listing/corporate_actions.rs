@@ -0,0 +1,385 @@
+use anyhow::{Result, anyhow};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::accounts::db_types::AdminOperatorRecord;
+use crate::accounts::operators::require_approver;
+use crate::asset_book::db_types::AssetBookRecord;
+use crate::asset_book::operations::get_asset;
+use crate::listing::db_types::{
+    CorporateActionKind, CorporateActionRow, CradleNativeListingRow, CreateCorporateAction,
+    ListingLifecycle, SplitAirdropRow,
+};
+use crate::listing::events::emit_listing_event;
+use crate::listing::holders::get_onchain_holdings;
+use crate::listing::operations::get_listing;
+use crate::listing::split_airdrops::{get_split_airdrops, queue_split_airdrops, run_job};
+use crate::market::db_types::{MarketRecord, MarketStatus};
+use crate::order_book::db_types::{OrderBookRecord, OrderStatus};
+use crate::order_book::operations::{can_execute_onchain, unlock_asset};
+use crate::utils::app_config::AppConfig;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum CorporateAction {
+    /// `ratio_to` units for every `ratio_from` held, e.g. 1 -> 2 for a
+    /// 2-for-1 split. Reverse splits aren't supported.
+    Split { ratio_from: u32, ratio_to: u32 },
+    /// New asset name and/or symbol
+    Rename {
+        name: Option<String>,
+        symbol: Option<String>,
+    },
+    /// Take the listed asset off every market
+    Delist { reason: Option<String> },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CorporateActionInputArgs {
+    pub listing_id: Uuid,
+    pub action: CorporateAction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CorporateActionResult {
+    pub action: CorporateActionRow,
+    pub cancelled_orders: Vec<Uuid>,
+    /// Extra units a split owes each holder, as they stand after the first
+    /// attempt; ones still unsent are retried in the background
+    pub airdrops: Vec<SplitAirdropRow>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ListingCorporateActionEvent {
+    pub listing_id: Uuid,
+    pub action_id: Uuid,
+    pub kind: CorporateActionKind,
+    pub details: serde_json::Value,
+}
+
+fn expect_sale_over(listing: &CradleNativeListingRow) -> Result<()> {
+    if !matches!(
+        listing.lifecycle,
+        ListingLifecycle::Closed | ListingLifecycle::Settled
+    ) {
+        return Err(anyhow!(
+            "Listing {} is {:?}; corporate actions apply once its sale has closed",
+            listing.id,
+            listing.lifecycle
+        ));
+    }
+    Ok(())
+}
+
+fn validate(listing: &CradleNativeListingRow, action: &CorporateAction) -> Result<()> {
+    if listing.delisted_at.is_some() {
+        return Err(anyhow!("Listing {} has been delisted", listing.id));
+    }
+
+    match action {
+        CorporateAction::Split {
+            ratio_from,
+            ratio_to,
+        } => {
+            if *ratio_from == 0 || ratio_to <= ratio_from {
+                return Err(anyhow!("Split ratio must give holders more units"));
+            }
+            expect_sale_over(listing)
+        }
+        CorporateAction::Rename { name, symbol } => {
+            if name.is_none() && symbol.is_none() {
+                return Err(anyhow!("Nothing to rename"));
+            }
+            if [name, symbol]
+                .iter()
+                .any(|v| v.as_ref().is_some_and(|s| s.trim().is_empty()))
+            {
+                return Err(anyhow!("Asset name and symbol can't be empty"));
+            }
+            Ok(())
+        }
+        CorporateAction::Delist { .. } => expect_sale_over(listing),
+    }
+}
+
+/// Cancel every open order that buys or sells `asset`.
+fn cancel_open_orders(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    asset: Uuid,
+) -> QueryResult<Vec<OrderBookRecord>> {
+    use crate::schema::orderbook::dsl::*;
+
+    diesel::update(orderbook)
+        .filter(status.eq(OrderStatus::Open))
+        .filter(bid_asset.eq(asset).or(ask_asset.eq(asset)))
+        .set((
+            status.eq(OrderStatus::Cancelled),
+            cancelled_at.eq(Some(Utc::now().naive_utc())),
+        ))
+        .get_results::<OrderBookRecord>(conn)
+}
+
+fn markets_for(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    asset: Uuid,
+) -> QueryResult<Vec<MarketRecord>> {
+    use crate::schema::markets::dsl::*;
+
+    markets
+        .filter(asset_one.eq(asset).or(asset_two.eq(asset)))
+        .get_results::<MarketRecord>(conn)
+}
+
+fn apply_split(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: &CradleNativeListingRow,
+    ratio: &BigDecimal,
+) -> QueryResult<()> {
+    {
+        use crate::schema::cradlenativelistings::dsl::*;
+
+        diesel::update(cradlenativelistings.find(listing.id))
+            .set((
+                split_factor.eq(split_factor * ratio.clone()),
+                max_supply.eq(max_supply * ratio.clone()),
+                purchase_price.eq(purchase_price / ratio.clone()),
+                min_purchase.eq(min_purchase * ratio.clone()),
+                max_per_wallet.eq(max_per_wallet * ratio.clone()),
+                soft_cap.eq(soft_cap * ratio.clone()),
+            ))
+            .execute(conn)?;
+    }
+
+    {
+        use crate::schema::listing_tranches::dsl::*;
+
+        diesel::update(listing_tranches.filter(listing_id.eq(listing.id)))
+            .set((
+                supply.eq(supply * ratio.clone()),
+                sold.eq(sold * ratio.clone()),
+                price.eq(price / ratio.clone()),
+            ))
+            .execute(conn)?;
+    }
+
+    Ok(())
+}
+
+fn apply_rename(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    asset: &AssetBookRecord,
+    new_name: &Option<String>,
+    new_symbol: &Option<String>,
+) -> QueryResult<()> {
+    {
+        use crate::schema::asset_book::dsl::*;
+
+        diesel::update(asset_book.find(asset.id))
+            .set((
+                name.eq(new_name.clone().unwrap_or_else(|| asset.name.clone())),
+                symbol.eq(new_symbol.clone().unwrap_or_else(|| asset.symbol.clone())),
+            ))
+            .execute(conn)?;
+    }
+
+    // market names are "<symbol>/<symbol>"; swap the old symbol where it appears
+    if let Some(new_symbol) = new_symbol {
+        for market in markets_for(conn, asset.id)? {
+            let renamed = market
+                .name
+                .split('/')
+                .map(|part| {
+                    if part.trim() == asset.symbol {
+                        new_symbol.as_str()
+                    } else {
+                        part
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+
+            if renamed != market.name {
+                use crate::schema::markets::dsl::*;
+                diesel::update(markets.find(market.id))
+                    .set(name.eq(renamed))
+                    .execute(conn)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn apply_delist(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: &CradleNativeListingRow,
+    asset: Uuid,
+) -> QueryResult<()> {
+    {
+        use crate::schema::cradlenativelistings::dsl::*;
+
+        diesel::update(cradlenativelistings.find(listing.id))
+            .set(delisted_at.eq(Some(Utc::now().naive_utc())))
+            .execute(conn)?;
+    }
+
+    let market_ids = markets_for(conn, asset)?
+        .into_iter()
+        .map(|m| m.id)
+        .collect::<Vec<_>>();
+
+    use crate::schema::markets::dsl::*;
+    diesel::update(markets.filter(id.eq_any(market_ids)))
+        .set(market_status.eq(MarketStatus::InActive))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Apply a corporate action to a listing's asset. Asset book, listing,
+/// tranche, market and order changes commit together, along with the
+/// airdrops a split owes holders; unlocking the funds of cancelled orders and
+/// the airdrops' first attempt follow on-chain. Holders are told through
+/// `listing:corporate_action`.
+pub async fn apply_corporate_action(
+    app_config: &mut AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    approver: &AdminOperatorRecord,
+    input: CorporateActionInputArgs,
+) -> Result<CorporateActionResult> {
+    require_approver(conn, approver)?;
+
+    let listing = get_listing(conn, input.listing_id).await?;
+    validate(&listing, &input.action)?;
+    let asset = get_asset(conn, listing.listed_asset).await?;
+
+    let (action_kind, action_details, ratio) = match &input.action {
+        CorporateAction::Split {
+            ratio_from,
+            ratio_to,
+        } => (
+            CorporateActionKind::Split,
+            json!({ "ratio_from": ratio_from, "ratio_to": ratio_to }),
+            Some(BigDecimal::from(*ratio_to) / BigDecimal::from(*ratio_from)),
+        ),
+        CorporateAction::Rename { name, symbol } => (
+            CorporateActionKind::Rename,
+            json!({
+                "old_name": asset.name,
+                "old_symbol": asset.symbol,
+                "name": name,
+                "symbol": symbol,
+            }),
+            None,
+        ),
+        CorporateAction::Delist { reason } => (
+            CorporateActionKind::Delist,
+            json!({ "reason": reason }),
+            None,
+        ),
+    };
+
+    // on-chain positions, read before anything changes so a balance that
+    // can't be read stops the split instead of shorting a holder
+    let holdings = match ratio {
+        Some(_) if can_execute_onchain() => {
+            get_onchain_holdings(conn, &app_config.wallet, &listing).await?
+        }
+        _ => Vec::new(),
+    };
+
+    let (row, cancelled, airdrop_job) = conn.transaction::<_, anyhow::Error, _>(|conn| {
+        let cancelled = match action_kind {
+            CorporateActionKind::Rename => Vec::new(),
+            _ => cancel_open_orders(conn, asset.id)?,
+        };
+
+        match &input.action {
+            CorporateAction::Split { .. } => {
+                apply_split(conn, &listing, ratio.as_ref().expect("split has a ratio"))?
+            }
+            CorporateAction::Rename { name, symbol } => apply_rename(conn, &asset, name, symbol)?,
+            CorporateAction::Delist { .. } => apply_delist(conn, &listing, asset.id)?,
+        }
+
+        use crate::schema::corporate_actions::dsl::*;
+        let row = diesel::insert_into(corporate_actions)
+            .values(CreateCorporateAction {
+                listing_id: listing.id,
+                asset_id: asset.id,
+                kind: action_kind,
+                details: action_details.clone(),
+                cancelled_orders: cancelled.len() as i32,
+                initiated_by: approver.account_id,
+            })
+            .get_result::<CorporateActionRow>(conn)?;
+
+        let airdrop_job = match &ratio {
+            Some(r) => queue_split_airdrops(conn, row.id, asset.id, &holdings, r)?,
+            None => None,
+        };
+
+        Ok((row, cancelled, airdrop_job))
+    })?;
+
+    for order in &cancelled {
+        let locked = &order.ask_amount - &order.filled_ask_amount;
+        let result = match locked.to_u64() {
+            Some(0) => Ok(()),
+            Some(amount) => unlock_asset(app_config, conn, order.wallet, order.ask_asset, amount).await,
+            None => Err(anyhow!("Locked amount out of range")),
+        };
+        if let Err(e) = result {
+            println!("Failed to unlock funds of cancelled order {}: {:?}", order.id, e);
+        }
+    }
+
+    let airdrops = match airdrop_job {
+        Some(job) => {
+            if let Err(e) = run_job(&app_config.wallet, conn, job.id).await {
+                println!("Failed to run split airdrop {}: {:?}", job.id, e);
+            }
+            get_split_airdrops(conn, job.id)?
+        }
+        None => Vec::new(),
+    };
+
+    emit_listing_event(
+        app_config,
+        listing.id,
+        "listing:corporate_action",
+        &ListingCorporateActionEvent {
+            listing_id: listing.id,
+            action_id: row.id,
+            kind: action_kind,
+            details: action_details,
+        },
+    )
+    .await;
+
+    Ok(CorporateActionResult {
+        action: row,
+        cancelled_orders: cancelled.into_iter().map(|o| o.id).collect(),
+        airdrops,
+    })
+}
+
+/// A listing's corporate actions, newest first.
+pub fn get_corporate_actions(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: Uuid,
+) -> Result<Vec<CorporateActionRow>> {
+    use crate::schema::corporate_actions::dsl::*;
+
+    Ok(corporate_actions
+        .filter(listing_id.eq(listing))
+        .order(created_at.desc())
+        .get_results::<CorporateActionRow>(conn)?)
+}
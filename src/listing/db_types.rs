@@ -1,5 +1,18 @@
+use crate::schema::corporate_actions as CorporateActionsTable;
 use crate::schema::cradlelistedcompanies as CradleCompanyTable;
 use crate::schema::cradlenativelistings as CradleNativeListingTable;
+use crate::schema::distribution_payouts as DistributionPayoutsTable;
+use crate::schema::listing_allocations as ListingAllocationsTable;
+use crate::schema::listing_distributions as ListingDistributionsTable;
+use crate::schema::listing_reconciliations as ListingReconciliationsTable;
+use crate::schema::listing_refunds as ListingRefundsTable;
+use crate::schema::listing_tranche_purchases as ListingTranchePurchasesTable;
+use crate::schema::listing_tranches as ListingTranchesTable;
+use crate::schema::listing_whitelist as ListingWhitelistTable;
+use crate::schema::split_airdrop_jobs as SplitAirdropJobsTable;
+use crate::schema::split_airdrops as SplitAirdropsTable;
+use crate::schema::withdrawal_approvals as WithdrawalApprovalsTable;
+use crate::schema::withdrawal_requests as WithdrawalRequestsTable;
 use bigdecimal::BigDecimal;
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
@@ -7,7 +20,7 @@ use diesel_derive_enum::DbEnum;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, Queryable, QueryableByName, Identifiable)]
+#[derive(Serialize, Deserialize, Queryable, QueryableByName, Identifiable, Debug, Clone)]
 #[diesel(table_name = CradleCompanyTable)]
 pub struct CompanyRow {
     pub id: Uuid,
@@ -16,6 +29,10 @@ pub struct CompanyRow {
     pub listed_at: Option<NaiveDateTime>,
     pub legal_documents: String,
     pub beneficiary_wallet: Uuid,
+    pub legal_documents_hash: Option<String>,
+    pub verification_status: CompanyVerification,
+    pub verified_by: Option<Uuid>,
+    pub verified_at: Option<NaiveDateTime>,
 }
 
 #[derive(Serialize, Deserialize, Insertable)]
@@ -27,6 +44,22 @@ pub struct CreateCompany {
     pub beneficiary_wallet: Uuid,
 }
 
+#[derive(Serialize, Deserialize, AsChangeset, Default)]
+#[diesel(table_name = CradleCompanyTable)]
+pub struct UpdateCompany {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub legal_documents: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::CompanyVerification"]
+#[serde(rename_all = "lowercase")]
+pub enum CompanyVerification {
+    Unverified,
+    Verified,
+}
+
 #[derive(Serialize, Deserialize, DbEnum, Debug, Clone)]
 #[ExistingTypePath = "crate::schema::sql_types::ListingStatus"]
 #[serde(rename_all = "lowercase")]
@@ -38,6 +71,31 @@ pub enum ListingStatus {
     Cancelled,
 }
 
+/// Off-chain lifecycle, tracked alongside the on-chain `ListingStatus`.
+/// Purchases are only accepted while `Live`.
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::ListingLifecycle"]
+#[serde(rename_all = "lowercase")]
+pub enum ListingLifecycle {
+    Draft,
+    Submitted,
+    Approved,
+    Live,
+    Closed,
+    Settled,
+    Cancelled,
+}
+
+/// How supply is handed out. `FirstCome` buys immediately; `ProRata` records
+/// commitments during the window and allocates at close.
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::ListingAllocationMode"]
+#[serde(rename_all = "snake_case")]
+pub enum ListingAllocationMode {
+    FirstCome,
+    ProRata,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Queryable, QueryableByName, Identifiable)]
 #[diesel(table_name = CradleNativeListingTable)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
@@ -58,6 +116,23 @@ pub struct CradleNativeListingRow {
     pub max_supply: BigDecimal,
     pub treasury: Uuid,
     pub shadow_asset: Uuid,
+    pub lifecycle: ListingLifecycle,
+    pub approved_by: Option<Uuid>,
+    pub approved_at: Option<NaiveDateTime>,
+    pub starts_at: Option<NaiveDateTime>,
+    pub ends_at: Option<NaiveDateTime>,
+    pub allocation_mode: ListingAllocationMode,
+    pub soft_cap: Option<BigDecimal>,
+    pub whitelist_enabled: bool,
+    pub min_purchase: Option<BigDecimal>,
+    pub max_per_wallet: Option<BigDecimal>,
+    pub documents_hash: Option<String>,
+    pub create_market_on_close: bool,
+    pub secondary_market: Option<Uuid>,
+    pub tranche_mode: TrancheMode,
+    /// Product of every split ratio applied to the listed asset
+    pub split_factor: BigDecimal,
+    pub delisted_at: Option<NaiveDateTime>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
@@ -78,3 +153,408 @@ pub struct CreateCraldeNativeListing {
     pub treasury: Uuid,
     pub shadow_asset: Uuid,
 }
+
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::AllocationStatus"]
+#[serde(rename_all = "lowercase")]
+pub enum AllocationStatus {
+    Committed,
+    Allocated,
+    Failed,
+    Refunded,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = ListingAllocationsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ListingAllocationRow {
+    pub id: Uuid,
+    pub listing_id: Uuid,
+    pub wallet_id: Uuid,
+    pub requested: BigDecimal,
+    pub locked: BigDecimal,
+    pub allocated: BigDecimal,
+    pub refunded: BigDecimal,
+    pub status: AllocationStatus,
+    pub created_at: NaiveDateTime,
+    pub allocated_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = ListingAllocationsTable)]
+pub struct CreateListingAllocation {
+    pub listing_id: Uuid,
+    pub wallet_id: Uuid,
+    pub requested: BigDecimal,
+    pub locked: BigDecimal,
+}
+
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::RefundStatus"]
+#[serde(rename_all = "lowercase")]
+pub enum RefundStatus {
+    Pending,
+    Refunded,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = ListingRefundsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ListingRefundRow {
+    pub id: Uuid,
+    pub listing_id: Uuid,
+    pub wallet_id: Uuid,
+    pub amount: BigDecimal,
+    pub status: RefundStatus,
+    pub transaction_id: Option<Uuid>,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = ListingRefundsTable)]
+pub struct CreateListingRefund {
+    pub listing_id: Uuid,
+    pub wallet_id: Uuid,
+    pub amount: BigDecimal,
+}
+
+
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::DistributionStatus"]
+#[serde(rename_all = "lowercase")]
+pub enum DistributionStatus {
+    Open,
+    Completed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = ListingDistributionsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ListingDistributionRow {
+    pub id: Uuid,
+    pub listing_id: Uuid,
+    pub payout_asset: Uuid,
+    pub total_amount: BigDecimal,
+    pub allocated: BigDecimal,
+    pub deposit_transaction: Option<Uuid>,
+    pub status: DistributionStatus,
+    pub snapshot_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = ListingDistributionsTable)]
+pub struct CreateListingDistribution {
+    pub listing_id: Uuid,
+    pub payout_asset: Uuid,
+    pub total_amount: BigDecimal,
+    pub allocated: BigDecimal,
+    pub deposit_transaction: Option<Uuid>,
+    pub snapshot_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::PayoutStatus"]
+#[serde(rename_all = "lowercase")]
+pub enum PayoutStatus {
+    Claimable,
+    Paid,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = DistributionPayoutsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DistributionPayoutRow {
+    pub id: Uuid,
+    pub distribution_id: Uuid,
+    pub wallet_id: Uuid,
+    pub holding: BigDecimal,
+    pub amount: BigDecimal,
+    pub status: PayoutStatus,
+    pub transaction_id: Option<Uuid>,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub paid_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = DistributionPayoutsTable)]
+pub struct CreateDistributionPayout {
+    pub distribution_id: Uuid,
+    pub wallet_id: Uuid,
+    pub holding: BigDecimal,
+    pub amount: BigDecimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = ListingWhitelistTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ListingWhitelistRow {
+    pub id: Uuid,
+    pub listing_id: Uuid,
+    pub wallet_id: Uuid,
+    pub added_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = ListingWhitelistTable)]
+pub struct CreateListingWhitelistEntry {
+    pub listing_id: Uuid,
+    pub wallet_id: Uuid,
+}
+
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::ReconciliationStatus"]
+#[serde(rename_all = "lowercase")]
+pub enum ReconciliationStatus {
+    Matched,
+    Mismatch,
+    /// The on-chain balance couldn't be read
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = ListingReconciliationsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ListingReconciliationRow {
+    pub id: Uuid,
+    pub listing_id: Uuid,
+    pub asset: Uuid,
+    pub expected: BigDecimal,
+    pub onchain: Option<BigDecimal>,
+    pub difference: Option<BigDecimal>,
+    pub status: ReconciliationStatus,
+    pub error: Option<String>,
+    pub checked_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = ListingReconciliationsTable)]
+pub struct CreateListingReconciliation {
+    pub listing_id: Uuid,
+    pub asset: Uuid,
+    pub expected: BigDecimal,
+    pub onchain: Option<BigDecimal>,
+    pub difference: Option<BigDecimal>,
+    pub status: ReconciliationStatus,
+    pub error: Option<String>,
+}
+
+/// How a listing's tranches are sold. `Sequential` sells them in position
+/// order; `Parallel` lets the buyer pick any tranche with supply left.
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::TrancheMode"]
+#[serde(rename_all = "lowercase")]
+pub enum TrancheMode {
+    Sequential,
+    Parallel,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = ListingTranchesTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ListingTrancheRow {
+    pub id: Uuid,
+    pub listing_id: Uuid,
+    pub position: i32,
+    pub name: String,
+    pub price: BigDecimal,
+    pub supply: BigDecimal,
+    pub sold: BigDecimal,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = ListingTranchesTable)]
+pub struct CreateListingTranche {
+    pub listing_id: Uuid,
+    pub position: i32,
+    pub name: String,
+    pub price: BigDecimal,
+    pub supply: BigDecimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = ListingTranchePurchasesTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ListingTranchePurchaseRow {
+    pub id: Uuid,
+    pub tranche_id: Uuid,
+    pub wallet_id: Uuid,
+    pub amount: BigDecimal,
+    pub price: BigDecimal,
+    pub transaction_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = ListingTranchePurchasesTable)]
+pub struct CreateListingTranchePurchase {
+    pub tranche_id: Uuid,
+    pub wallet_id: Uuid,
+    pub amount: BigDecimal,
+    pub price: BigDecimal,
+    pub transaction_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::WithdrawalStatus"]
+#[serde(rename_all = "lowercase")]
+pub enum WithdrawalStatus {
+    Pending,
+    /// Has all its approvals; the contract call is running
+    Approved,
+    Rejected,
+    Executed,
+    /// Fully approved, but the contract call failed
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = WithdrawalRequestsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WithdrawalRequestRow {
+    pub id: Uuid,
+    pub listing_id: Uuid,
+    pub amount: BigDecimal,
+    /// Unset for withdrawals small enough to skip approval
+    pub requested_by: Option<Uuid>,
+    pub required_approvals: i32,
+    pub status: WithdrawalStatus,
+    pub reviewed_by: Option<Uuid>,
+    pub reason: Option<String>,
+    pub transaction_id: Option<Uuid>,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = WithdrawalRequestsTable)]
+pub struct CreateWithdrawalRequest {
+    pub listing_id: Uuid,
+    pub amount: BigDecimal,
+    pub requested_by: Option<Uuid>,
+    pub required_approvals: i32,
+    pub status: WithdrawalStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = WithdrawalApprovalsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WithdrawalApprovalRow {
+    pub id: Uuid,
+    pub request_id: Uuid,
+    pub approver: Uuid,
+    pub created_at: NaiveDateTime,
+    pub operator_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = WithdrawalApprovalsTable)]
+pub struct CreateWithdrawalApproval {
+    pub request_id: Uuid,
+    pub approver: Uuid,
+    pub operator_id: Uuid,
+}
+
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::CorporateActionKind"]
+#[serde(rename_all = "lowercase")]
+pub enum CorporateActionKind {
+    Split,
+    Rename,
+    Delist,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = CorporateActionsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct CorporateActionRow {
+    pub id: Uuid,
+    pub listing_id: Uuid,
+    pub asset_id: Uuid,
+    pub kind: CorporateActionKind,
+    pub details: serde_json::Value,
+    pub cancelled_orders: i32,
+    pub initiated_by: Uuid,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = CorporateActionsTable)]
+pub struct CreateCorporateAction {
+    pub listing_id: Uuid,
+    pub asset_id: Uuid,
+    pub kind: CorporateActionKind,
+    pub details: serde_json::Value,
+    pub cancelled_orders: i32,
+    pub initiated_by: Uuid,
+}
+
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::SplitAirdropStatus"]
+#[serde(rename_all = "lowercase")]
+pub enum SplitAirdropStatus {
+    Pending,
+    /// Claimed by a worker until its `next_attempt_at`
+    Running,
+    Completed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = SplitAirdropJobsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct SplitAirdropJobRow {
+    pub id: Uuid,
+    pub action_id: Uuid,
+    pub asset_id: Uuid,
+    /// Extra units owed across every holder
+    pub units: i64,
+    pub status: SplitAirdropStatus,
+    pub minted_at: Option<NaiveDateTime>,
+    /// Total supply before the mint went out
+    pub mint_supply_before: Option<i64>,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = SplitAirdropJobsTable)]
+pub struct CreateSplitAirdropJob {
+    pub action_id: Uuid,
+    pub asset_id: Uuid,
+    pub units: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = SplitAirdropsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct SplitAirdropRow {
+    pub id: Uuid,
+    pub job_id: Uuid,
+    pub wallet_id: Uuid,
+    pub units: i64,
+    /// The holder's balance before the airdrop went out
+    pub balance_before: Option<i64>,
+    pub airdropped_at: Option<NaiveDateTime>,
+    pub airdrop_tx: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = SplitAirdropsTable)]
+pub struct CreateSplitAirdrop {
+    pub job_id: Uuid,
+    pub wallet_id: Uuid,
+    pub units: i64,
+}
@@ -0,0 +1,452 @@
+use anyhow::{Result, anyhow};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::accounts::db_types::CradleWalletAccountRecord;
+use crate::accounts_ledger::db_types::AccountLedgerTransactionType;
+use crate::accounts_ledger::operations::{RecordTransactionAssets, record_transaction};
+use crate::asset_book::db_types::AssetBookRecord;
+use crate::listing::db_types::{
+    CompanyRow, CreateDistributionPayout, CreateListingDistribution, DistributionPayoutRow,
+    DistributionStatus, ListingDistributionRow, ListingLifecycle, PayoutStatus,
+};
+use crate::listing::events::emit_listing_event;
+use crate::listing::operations::{get_listing, get_listing_holdings};
+use crate::order_book::operations::asset_transfer;
+use crate::utils::app_config::AppConfig;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreateDistributionInputArgs {
+    pub listing_id: Uuid,
+    pub payout_asset: Uuid,
+    /// Deposited from the company's beneficiary wallet and split across holders
+    pub amount: BigDecimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClaimPayoutInputArgs {
+    pub distribution_id: Uuid,
+    pub wallet_id: Uuid,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DistributionSummary {
+    pub distribution: ListingDistributionRow,
+    pub claimable: usize,
+    pub paid: usize,
+    pub failed: usize,
+    pub payouts: Vec<DistributionPayoutRow>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ListingDividendEvent {
+    pub listing_id: Uuid,
+    pub distribution_id: Uuid,
+    pub wallet_id: Uuid,
+    pub amount: String,
+    pub status: PayoutStatus,
+}
+
+/// Payouts sent per `PayDistribution` call, from DISTRIBUTION_BATCH_SIZE.
+fn batch_size() -> i64 {
+    std::env::var("DISTRIBUTION_BATCH_SIZE")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(50)
+}
+
+/// Split `total` in proportion to `holdings`, rounding each share down so the
+/// payouts never add up to more than was deposited.
+pub fn holder_payouts(holdings: &[BigDecimal], total: &BigDecimal) -> Vec<BigDecimal> {
+    let held: BigDecimal = holdings.iter().sum();
+    if held <= BigDecimal::from(0) {
+        return vec![BigDecimal::from(0); holdings.len()];
+    }
+
+    holdings
+        .iter()
+        .map(|h| (h * total / &held).with_scale(0))
+        .collect()
+}
+
+fn get_wallet(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    wallet_id: Uuid,
+) -> Result<CradleWalletAccountRecord> {
+    use crate::schema::cradlewalletaccounts::dsl::*;
+
+    Ok(cradlewalletaccounts
+        .filter(id.eq(wallet_id))
+        .get_result::<CradleWalletAccountRecord>(conn)?)
+}
+
+fn get_asset(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    asset_id: Uuid,
+) -> Result<AssetBookRecord> {
+    use crate::schema::asset_book::dsl::*;
+
+    Ok(asset_book
+        .filter(id.eq(asset_id))
+        .get_result::<AssetBookRecord>(conn)?)
+}
+
+pub fn get_distribution(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    distribution: Uuid,
+) -> Result<ListingDistributionRow> {
+    use crate::schema::listing_distributions::dsl::*;
+
+    Ok(listing_distributions
+        .filter(id.eq(distribution))
+        .get_result::<ListingDistributionRow>(conn)?)
+}
+
+pub fn get_listing_distributions(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: Uuid,
+) -> Result<Vec<ListingDistributionRow>> {
+    use crate::schema::listing_distributions::dsl::*;
+
+    Ok(listing_distributions
+        .filter(listing_id.eq(listing))
+        .order(created_at.desc())
+        .get_results::<ListingDistributionRow>(conn)?)
+}
+
+/// Every payout owed to or received by a wallet, newest first.
+pub fn get_wallet_payouts(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    wallet: Uuid,
+) -> Result<Vec<DistributionPayoutRow>> {
+    use crate::schema::distribution_payouts::dsl::*;
+
+    Ok(distribution_payouts
+        .filter(wallet_id.eq(wallet))
+        .order(created_at.desc())
+        .get_results::<DistributionPayoutRow>(conn)?)
+}
+
+pub fn get_distribution_summary(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    distribution: Uuid,
+) -> Result<DistributionSummary> {
+    let row = get_distribution(conn, distribution)?;
+
+    let payouts = {
+        use crate::schema::distribution_payouts::dsl::*;
+
+        distribution_payouts
+            .filter(distribution_id.eq(distribution))
+            .order(created_at.asc())
+            .get_results::<DistributionPayoutRow>(conn)?
+    };
+
+    let count = |s: PayoutStatus| payouts.iter().filter(|p| p.status == s).count();
+
+    Ok(DistributionSummary {
+        distribution: row,
+        claimable: count(PayoutStatus::Claimable),
+        paid: count(PayoutStatus::Paid),
+        failed: count(PayoutStatus::Failed),
+        payouts,
+    })
+}
+
+/// Deposit a payout from the company's beneficiary wallet into the listing
+/// treasury, snapshot current holders of the listed asset and record what
+/// each of them is owed. Nothing is sent to holders until the distribution
+/// is paid or claimed.
+pub async fn create_distribution(
+    app_config: &mut AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    input: CreateDistributionInputArgs,
+) -> Result<ListingDistributionRow> {
+    let listing = get_listing(conn, input.listing_id).await?;
+
+    match listing.lifecycle {
+        ListingLifecycle::Closed | ListingLifecycle::Settled => {}
+        _ => {
+            return Err(anyhow!(
+                "Listing {} is {:?}; distributions are only made once it has closed",
+                listing.id,
+                listing.lifecycle
+            ));
+        }
+    }
+
+    if input.amount <= BigDecimal::from(0) {
+        return Err(anyhow!("Distribution amount must be positive"));
+    }
+
+    let holdings = get_listing_holdings(conn, &listing)?;
+    if holdings.is_empty() {
+        return Err(anyhow!("Listing {} has no holders to distribute to", listing.id));
+    }
+    let snapshot_time = Utc::now().naive_utc();
+
+    let company = {
+        use crate::schema::cradlelistedcompanies::dsl::*;
+
+        cradlelistedcompanies
+            .filter(id.eq(listing.company))
+            .get_result::<CompanyRow>(conn)?
+    };
+
+    let beneficiary = get_wallet(conn, company.beneficiary_wallet)?;
+    let treasury = get_wallet(conn, listing.treasury)?;
+    let asset = get_asset(conn, input.payout_asset)?;
+
+    let tx = asset_transfer(
+        &app_config.wallet,
+        beneficiary.clone(),
+        input.amount.clone(),
+        asset.clone(),
+        treasury.clone(),
+    )
+    .await?;
+
+    let deposit_entry = record_transaction(
+        conn,
+        Some(beneficiary.address),
+        Some(treasury.address),
+        RecordTransactionAssets::Single(asset.id),
+        input.amount.to_u64(),
+        None,
+        Some(AccountLedgerTransactionType::Transfer),
+        Some(tx),
+        None,
+    )?;
+
+    let amounts: Vec<BigDecimal> = holder_payouts(
+        &holdings.iter().map(|h| h.amount.clone()).collect::<Vec<_>>(),
+        &input.amount,
+    );
+    let allocated_total: BigDecimal = amounts.iter().sum();
+
+    let distribution = {
+        use crate::schema::listing_distributions::dsl::*;
+
+        diesel::insert_into(listing_distributions)
+            .values(CreateListingDistribution {
+                listing_id: listing.id,
+                payout_asset: asset.id,
+                total_amount: input.amount.clone(),
+                allocated: allocated_total,
+                deposit_transaction: Some(deposit_entry),
+                snapshot_at: snapshot_time,
+            })
+            .get_result::<ListingDistributionRow>(conn)?
+    };
+
+    let zero = BigDecimal::from(0);
+    let rows: Vec<CreateDistributionPayout> = holdings
+        .into_iter()
+        .zip(amounts)
+        .filter(|(_, amount)| *amount > zero)
+        .map(|(h, amount)| CreateDistributionPayout {
+            distribution_id: distribution.id,
+            wallet_id: h.wallet_id,
+            holding: h.amount,
+            amount,
+        })
+        .collect();
+
+    if !rows.is_empty() {
+        use crate::schema::distribution_payouts::dsl::*;
+
+        diesel::insert_into(distribution_payouts)
+            .values(&rows)
+            .execute(conn)?;
+    }
+
+    Ok(distribution)
+}
+
+/// Send one payout from the treasury. The row is marked paid before the
+/// transfer so a concurrent claim and batch run can't both send it; a failed
+/// transfer puts it back as `failed` for the next run to retry.
+async fn send_payout(
+    app_config: &mut AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    distribution: &ListingDistributionRow,
+    treasury: &CradleWalletAccountRecord,
+    asset: &AssetBookRecord,
+    payout: DistributionPayoutRow,
+) -> Result<Option<DistributionPayoutRow>> {
+    let taken = {
+        use crate::schema::distribution_payouts::dsl::*;
+
+        diesel::update(distribution_payouts)
+            .filter(id.eq(payout.id))
+            .filter(status.eq_any(vec![PayoutStatus::Claimable, PayoutStatus::Failed]))
+            .set((status.eq(PayoutStatus::Paid), paid_at.eq(Some(Utc::now().naive_utc()))))
+            .execute(conn)?
+    };
+    if taken == 0 {
+        return Ok(None);
+    }
+
+    let holder = get_wallet(conn, payout.wallet_id)?;
+
+    let result = match asset_transfer(
+        &app_config.wallet,
+        treasury.clone(),
+        payout.amount.clone(),
+        asset.clone(),
+        holder.clone(),
+    )
+    .await
+    {
+        Ok(tx) => record_transaction(
+            conn,
+            Some(treasury.address.clone()),
+            Some(holder.address),
+            RecordTransactionAssets::Single(asset.id),
+            payout.amount.to_u64(),
+            None,
+            Some(AccountLedgerTransactionType::Transfer),
+            Some(tx),
+            None,
+        ),
+        Err(e) => Err(e),
+    };
+
+    let updated = {
+        use crate::schema::distribution_payouts::dsl::*;
+
+        match result {
+            Ok(ledger_id) => diesel::update(distribution_payouts.find(payout.id))
+                .set((transaction_id.eq(Some(ledger_id)), error.eq(None::<String>)))
+                .get_result::<DistributionPayoutRow>(conn)?,
+            Err(e) => {
+                println!(
+                    "Payout to wallet {} for distribution {} failed: {:?}",
+                    payout.wallet_id, distribution.id, e
+                );
+                diesel::update(distribution_payouts.find(payout.id))
+                    .set((
+                        status.eq(PayoutStatus::Failed),
+                        paid_at.eq(None::<chrono::NaiveDateTime>),
+                        error.eq(Some(e.to_string())),
+                    ))
+                    .get_result::<DistributionPayoutRow>(conn)?
+            }
+        }
+    };
+
+    emit_listing_event(
+        app_config,
+        distribution.listing_id,
+        "listing:dividend",
+        &ListingDividendEvent {
+            listing_id: distribution.listing_id,
+            distribution_id: distribution.id,
+            wallet_id: updated.wallet_id,
+            amount: updated.amount.to_string(),
+            status: updated.status.clone(),
+        },
+    )
+    .await;
+
+    Ok(Some(updated))
+}
+
+fn complete_if_done(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    distribution: Uuid,
+) -> Result<()> {
+    let outstanding = {
+        use crate::schema::distribution_payouts::dsl::*;
+
+        distribution_payouts
+            .filter(distribution_id.eq(distribution))
+            .filter(status.ne(PayoutStatus::Paid))
+            .count()
+            .get_result::<i64>(conn)?
+    };
+
+    if outstanding == 0 {
+        use crate::schema::listing_distributions::dsl::*;
+
+        diesel::update(listing_distributions.find(distribution))
+            .set((
+                status.eq(DistributionStatus::Completed),
+                completed_at.eq(Some(Utc::now().naive_utc())),
+            ))
+            .execute(conn)?;
+    }
+
+    Ok(())
+}
+
+/// Pay the next batch of outstanding payouts, retrying failed ones. Call
+/// repeatedly until the summary shows nothing claimable or failed.
+pub async fn pay_distribution(
+    app_config: &mut AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    distribution_id: Uuid,
+) -> Result<DistributionSummary> {
+    let distribution = get_distribution(conn, distribution_id)?;
+    if distribution.status == DistributionStatus::Completed {
+        return get_distribution_summary(conn, distribution_id);
+    }
+
+    let listing = get_listing(conn, distribution.listing_id).await?;
+    let treasury = get_wallet(conn, listing.treasury)?;
+    let asset = get_asset(conn, distribution.payout_asset)?;
+
+    let batch = {
+        use crate::schema::distribution_payouts::dsl::*;
+
+        distribution_payouts
+            .filter(distribution_id.eq(distribution.id))
+            .filter(status.eq_any(vec![PayoutStatus::Claimable, PayoutStatus::Failed]))
+            .order(created_at.asc())
+            .limit(batch_size())
+            .get_results::<DistributionPayoutRow>(conn)?
+    };
+
+    for payout in batch {
+        send_payout(app_config, conn, &distribution, &treasury, &asset, payout).await?;
+    }
+
+    complete_if_done(conn, distribution.id)?;
+    get_distribution_summary(conn, distribution.id)
+}
+
+/// Let a holder pull their own payout without waiting for the next batch.
+pub async fn claim_payout(
+    app_config: &mut AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    input: ClaimPayoutInputArgs,
+) -> Result<DistributionPayoutRow> {
+    let distribution = get_distribution(conn, input.distribution_id)?;
+    let listing = get_listing(conn, distribution.listing_id).await?;
+    let treasury = get_wallet(conn, listing.treasury)?;
+    let asset = get_asset(conn, distribution.payout_asset)?;
+
+    let payout = {
+        use crate::schema::distribution_payouts::dsl::*;
+
+        distribution_payouts
+            .filter(distribution_id.eq(input.distribution_id))
+            .filter(wallet_id.eq(input.wallet_id))
+            .get_result::<DistributionPayoutRow>(conn)?
+    };
+
+    let updated = send_payout(app_config, conn, &distribution, &treasury, &asset, payout)
+        .await?
+        .ok_or_else(|| anyhow!("Payout for wallet {} was already paid", input.wallet_id))?;
+
+    complete_if_done(conn, distribution.id)?;
+    Ok(updated)
+}
@@ -0,0 +1,101 @@
+use bigdecimal::BigDecimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::listing::db_types::{ListingLifecycle, ListingStatus};
+use crate::notifications::operations::notify_listing_event;
+use crate::sockets::broadcast;
+use crate::utils::app_config::AppConfig;
+use crate::webhooks::operations::enqueue_listing_event;
+
+/// Percent-of-max-supply marks that trigger a `listing:threshold` event.
+pub const SUPPLY_THRESHOLDS: [u32; 4] = [50, 75, 90, 100];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ListingStatusEvent {
+    pub listing_id: Uuid,
+    pub status: ListingStatus,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ListingLifecycleEvent {
+    pub listing_id: Uuid,
+    pub lifecycle: ListingLifecycle,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ListingPurchaseEvent {
+    pub listing_id: Uuid,
+    pub wallet: Uuid,
+    pub amount: String,
+    pub sold: String,
+    pub max_supply: String,
+    pub transaction_id: Uuid,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ListingThresholdEvent {
+    pub listing_id: Uuid,
+    pub threshold_percent: u32,
+    pub sold: String,
+    pub max_supply: String,
+}
+
+/// Thresholds crossed when sold supply moves from `before` to `after`.
+pub fn crossed_thresholds(before: &BigDecimal, after: &BigDecimal, max_supply: &BigDecimal) -> Vec<u32> {
+    if *max_supply <= BigDecimal::from(0) {
+        return Vec::new();
+    }
+
+    SUPPLY_THRESHOLDS
+        .iter()
+        .copied()
+        .filter(|pct| {
+            let mark = max_supply * BigDecimal::from(*pct) / BigDecimal::from(100);
+            *before < mark && *after >= mark
+        })
+        .collect()
+}
+
+/// Emit to both the per-listing room and the `listings` room used by the
+/// listings tab, then pass the event on to webhooks and investor
+/// notifications. Failures there are logged and never reach the caller.
+pub async fn emit_listing_event<T: Serialize>(app_config: &AppConfig, listing_id: Uuid, event: &str, payload: &T) {
+    if let Ok(io) = app_config.get_io() {
+        broadcast(&io, format!("listings:{}", listing_id), event, payload).await;
+        broadcast(&io, "listings".to_string(), event, payload).await;
+    }
+
+    if let Err(e) = fan_out(app_config, listing_id, event, payload).await {
+        println!("Failed to deliver {} for listing {}: {:?}", event, listing_id, e);
+    }
+}
+
+async fn fan_out<T: Serialize>(app_config: &AppConfig, listing_id: Uuid, event: &str, payload: &T) -> anyhow::Result<()> {
+    let data = serde_json::to_value(payload)?;
+    let mut conn = app_config.pool.get()?;
+
+    enqueue_listing_event(&mut conn, listing_id, event, &data)?;
+    notify_listing_event(&mut conn, listing_id, event, &data).await?;
+    Ok(())
+}
+
+pub async fn emit_status_change(app_config: &AppConfig, listing_id: Uuid, status: ListingStatus) {
+    let event = match status {
+        ListingStatus::Open => "listing:opened",
+        ListingStatus::Closed => "listing:closed",
+        _ => "listing:status",
+    };
+
+    emit_listing_event(app_config, listing_id, event, &ListingStatusEvent { listing_id, status }).await;
+}
+
+pub async fn emit_lifecycle_change(app_config: &AppConfig, listing_id: Uuid, lifecycle: ListingLifecycle) {
+    emit_listing_event(
+        app_config,
+        listing_id,
+        "listing:lifecycle",
+        &ListingLifecycleEvent { listing_id, lifecycle },
+    )
+    .await;
+}
@@ -0,0 +1,175 @@
+use anyhow::{Result, anyhow};
+use bigdecimal::BigDecimal;
+use contract_integrator::{
+    hedera::TokenId, utils::functions::commons, wallet::wallet::ActionWallet,
+};
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::accounts::db_types::CradleWalletAccountRecord;
+use crate::asset_book::db_types::AssetBookRecord;
+use crate::listing::db_types::CradleNativeListingRow;
+use crate::listing::operations::{ListingHolding, get_listing, get_listing_holdings};
+use crate::order_book::operations::can_execute_onchain;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ListingHolder {
+    pub wallet_id: Uuid,
+    pub address: String,
+    /// Net bought through the listing, from the ledger
+    pub purchased: BigDecimal,
+    /// Listed-asset balance held on-chain; null when it couldn't be read
+    pub onchain_balance: Option<BigDecimal>,
+    /// On-chain balance when known, otherwise `purchased`
+    pub amount: BigDecimal,
+    /// Share of `total_held`, in percent
+    pub percentage: BigDecimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CapTable {
+    pub listing_id: Uuid,
+    pub listed_asset: Uuid,
+    pub max_supply: BigDecimal,
+    pub total_held: BigDecimal,
+    pub holder_count: usize,
+    pub holders: Vec<ListingHolder>,
+}
+
+/// Current holders of the listed asset among wallets that bought through the
+/// listing, largest first. Balances are read from chain where possible so
+/// transfers after purchase are reflected.
+pub async fn get_cap_table(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    wallet: &ActionWallet,
+    listing_id: Uuid,
+) -> Result<CapTable> {
+    let listing = get_listing(conn, listing_id).await?;
+    let holdings = get_listing_holdings(conn, &listing)?;
+
+    let token_id = if can_execute_onchain() {
+        use crate::schema::asset_book::dsl::*;
+
+        let asset = asset_book
+            .filter(id.eq(listing.listed_asset))
+            .get_result::<AssetBookRecord>(conn)?;
+        TokenId::from_solidity_address(&asset.token).ok()
+    } else {
+        None
+    };
+
+    let mut holders = Vec::with_capacity(holdings.len());
+
+    for holding in holdings {
+        let onchain_balance = match &token_id {
+            Some(token) => {
+                let contract = {
+                    use crate::schema::cradlewalletaccounts::dsl::*;
+
+                    cradlewalletaccounts
+                        .filter(id.eq(holding.wallet_id))
+                        .get_result::<CradleWalletAccountRecord>(conn)?
+                        .contract_id
+                };
+
+                match commons::get_account_balances(&wallet.client, &contract).await {
+                    Ok(balances) => Some(BigDecimal::from(*balances.tokens.get(token).unwrap_or(&0))),
+                    Err(e) => {
+                        println!("Failed to read balance of wallet {}: {:?}", holding.wallet_id, e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        holders.push(ListingHolder {
+            wallet_id: holding.wallet_id,
+            address: holding.address,
+            amount: onchain_balance.clone().unwrap_or_else(|| holding.amount.clone()),
+            purchased: holding.amount,
+            onchain_balance,
+            percentage: BigDecimal::from(0),
+        });
+    }
+
+    let zero = BigDecimal::from(0);
+    holders.retain(|h| h.amount > zero);
+
+    let total_held: BigDecimal = holders.iter().map(|h| &h.amount).sum();
+    if total_held > zero {
+        for holder in holders.iter_mut() {
+            holder.percentage = (&holder.amount * BigDecimal::from(100) / &total_held).round(4);
+        }
+    }
+    holders.sort_by(|a, b| b.amount.cmp(&a.amount));
+
+    Ok(CapTable {
+        listing_id: listing.id,
+        listed_asset: listing.listed_asset,
+        max_supply: listing.max_supply,
+        total_held,
+        holder_count: holders.len(),
+        holders,
+    })
+}
+
+/// Every wallet holding a listing's asset on-chain, largest first. Any
+/// wallet the ledger has seen receive the asset is checked, so positions
+/// bought on the secondary market or airdropped count as well as purchases.
+/// Fails if a balance can't be read, rather than leave a holder out.
+pub async fn get_onchain_holdings(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    wallet: &ActionWallet,
+    listing: &CradleNativeListingRow,
+) -> Result<Vec<ListingHolding>> {
+    let token_id = {
+        use crate::schema::asset_book::dsl::*;
+
+        let record = asset_book
+            .filter(id.eq(listing.listed_asset))
+            .get_result::<AssetBookRecord>(conn)?;
+        TokenId::from_solidity_address(&record.token)
+            .map_err(|e| anyhow!("Invalid token {}: {:?}", record.token, e))?
+    };
+
+    let addresses = {
+        use crate::schema::accountassetsledger::dsl::*;
+
+        accountassetsledger
+            .filter(asset.eq(listing.listed_asset))
+            .select(to_address)
+            .distinct()
+            .load::<String>(conn)?
+    };
+    let wallets = {
+        use crate::schema::cradlewalletaccounts::dsl::*;
+
+        cradlewalletaccounts
+            .filter(address.eq_any(addresses))
+            .get_results::<CradleWalletAccountRecord>(conn)?
+    };
+
+    let mut holdings = Vec::new();
+    for account in wallets {
+        let balances = commons::get_account_balances(&wallet.client, &account.contract_id)
+            .await
+            .map_err(|e| anyhow!("Failed to read balance of wallet {}: {:?}", account.id, e))?;
+        let held = *balances.tokens.get(&token_id).unwrap_or(&0);
+        if held > 0 {
+            holdings.push(ListingHolding {
+                wallet_id: account.id,
+                address: account.address,
+                amount: BigDecimal::from(held),
+            });
+        }
+    }
+    holdings.sort_by(|a, b| b.amount.cmp(&a.amount));
+
+    Ok(holdings)
+}
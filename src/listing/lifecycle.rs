@@ -0,0 +1,204 @@
+use anyhow::{Result, anyhow};
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::accounts::db_types::AdminOperatorRecord;
+use crate::accounts::operators::require_approver;
+use crate::listing::db_types::{CradleNativeListingRow, ListingLifecycle, ListingStatus};
+use crate::listing::operations::{get_listing, update_listing_status};
+use crate::utils::wallet::SharedWallet;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReviewListingInputArgs {
+    pub listing_id: Uuid,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScheduleListingInputArgs {
+    pub listing_id: Uuid,
+    pub starts_at: Option<NaiveDateTime>,
+    pub ends_at: Option<NaiveDateTime>,
+}
+
+/// Move a listing to `to` if it is currently in one of `from`. The check and
+/// update happen in one statement so concurrent transitions can't both win.
+fn transition(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing_id: Uuid,
+    from: &[ListingLifecycle],
+    to: ListingLifecycle,
+) -> Result<()> {
+    use crate::schema::cradlenativelistings::dsl::*;
+
+    let updated = diesel::update(cradlenativelistings)
+        .filter(id.eq(listing_id))
+        .filter(lifecycle.eq_any(from.to_vec()))
+        .set(lifecycle.eq(to.clone()))
+        .execute(conn)?;
+
+    if updated == 0 {
+        let current = cradlenativelistings
+            .filter(id.eq(listing_id))
+            .select(lifecycle)
+            .get_result::<ListingLifecycle>(conn)?;
+        return Err(anyhow!(
+            "Listing {} is {:?}, expected one of {:?} to move to {:?}",
+            listing_id,
+            current,
+            from,
+            to
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn submit_listing(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing_id: Uuid,
+) -> Result<()> {
+    transition(conn, listing_id, &[ListingLifecycle::Draft], ListingLifecycle::Submitted)
+}
+
+pub fn approve_listing(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    approver: &AdminOperatorRecord,
+    input: ReviewListingInputArgs,
+) -> Result<()> {
+    require_approver(conn, approver)?;
+    transition(conn, input.listing_id, &[ListingLifecycle::Submitted], ListingLifecycle::Approved)?;
+
+    use crate::schema::cradlenativelistings::dsl::*;
+    diesel::update(cradlenativelistings)
+        .filter(id.eq(input.listing_id))
+        .set((
+            approved_by.eq(Some(approver.account_id)),
+            approved_at.eq(Some(Utc::now().naive_utc())),
+        ))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Send a submitted listing back to draft for changes.
+pub fn reject_listing(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    approver: &AdminOperatorRecord,
+    input: ReviewListingInputArgs,
+) -> Result<()> {
+    require_approver(conn, approver)?;
+    transition(conn, input.listing_id, &[ListingLifecycle::Submitted], ListingLifecycle::Draft)
+}
+
+/// Open an approved listing on-chain and start accepting purchases.
+pub async fn go_live(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    wallet: &SharedWallet,
+    listing_id: Uuid,
+) -> Result<()> {
+    expect_lifecycle(&get_listing(conn, listing_id).await?, ListingLifecycle::Approved)?;
+    update_listing_status(conn, wallet, listing_id, ListingStatus::Open).await?;
+    transition(conn, listing_id, &[ListingLifecycle::Approved], ListingLifecycle::Live)?;
+
+    use crate::schema::cradlenativelistings::dsl::*;
+    diesel::update(cradlenativelistings)
+        .filter(id.eq(listing_id))
+        .set(opened_at.eq(Some(Utc::now().naive_utc())))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Close a live listing on-chain. No further purchases are accepted.
+pub async fn close_listing(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    wallet: &SharedWallet,
+    listing_id: Uuid,
+) -> Result<()> {
+    expect_lifecycle(&get_listing(conn, listing_id).await?, ListingLifecycle::Live)?;
+    update_listing_status(conn, wallet, listing_id, ListingStatus::Closed).await?;
+    transition(conn, listing_id, &[ListingLifecycle::Live], ListingLifecycle::Closed)?;
+
+    use crate::schema::cradlenativelistings::dsl::*;
+    diesel::update(cradlenativelistings)
+        .filter(id.eq(listing_id))
+        .set(stopped_at.eq(Some(Utc::now().naive_utc())))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Mark a closed listing as settled once proceeds have been paid out.
+pub fn settle_listing(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing_id: Uuid,
+) -> Result<()> {
+    transition(conn, listing_id, &[ListingLifecycle::Closed], ListingLifecycle::Settled)
+}
+
+pub fn expect_lifecycle(listing: &CradleNativeListingRow, expected: ListingLifecycle) -> Result<()> {
+    if listing.lifecycle != expected {
+        return Err(anyhow!(
+            "Listing {} is {:?}, expected {:?}",
+            listing.id,
+            listing.lifecycle,
+            expected
+        ));
+    }
+    Ok(())
+}
+
+/// Set the subscription window. Only allowed before the listing goes live;
+/// the scheduler opens and closes it at these times.
+pub fn schedule_listing(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    input: ScheduleListingInputArgs,
+) -> Result<()> {
+    if let (Some(start), Some(end)) = (input.starts_at, input.ends_at) {
+        if end <= start {
+            return Err(anyhow!("ends_at must be after starts_at"));
+        }
+    }
+
+    use crate::schema::cradlenativelistings::dsl::*;
+
+    let updated = diesel::update(cradlenativelistings)
+        .filter(id.eq(input.listing_id))
+        .filter(lifecycle.eq_any(vec![
+            ListingLifecycle::Draft,
+            ListingLifecycle::Submitted,
+            ListingLifecycle::Approved,
+        ]))
+        .set((starts_at.eq(input.starts_at), ends_at.eq(input.ends_at)))
+        .execute(conn)?;
+
+    if updated == 0 {
+        return Err(anyhow!(
+            "Listing {} can't be rescheduled once it has gone live",
+            input.listing_id
+        ));
+    }
+
+    Ok(())
+}
+
+/// Error unless `at` falls inside the listing's subscription window.
+pub fn expect_within_window(listing: &CradleNativeListingRow, at: NaiveDateTime) -> Result<()> {
+    if let Some(start) = listing.starts_at {
+        if at < start {
+            return Err(anyhow!("Listing {} opens at {}", listing.id, start));
+        }
+    }
+    if let Some(end) = listing.ends_at {
+        if at >= end {
+            return Err(anyhow!("Listing {} closed at {}", listing.id, end));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use anyhow::{Result, anyhow};
+use bigdecimal::BigDecimal;
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::accounts::db_types::CradleWalletAccountRecord;
+use crate::accounts_ledger::db_types::AccountLedgerTransactionType;
+use crate::listing::db_types::{AllocationStatus, CradleNativeListingRow, ListingLifecycle};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetPurchaseLimitsInputArgs {
+    pub listing_id: Uuid,
+    /// Smallest total a wallet may hold through the listing; `None` for no minimum
+    pub min_purchase: Option<BigDecimal>,
+    /// Largest total a wallet may hold through the listing; `None` for no cap
+    pub max_per_wallet: Option<BigDecimal>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WalletAllowance {
+    pub wallet_id: Uuid,
+    /// Net bought plus outstanding pro-rata commitments
+    pub purchased: BigDecimal,
+    /// How much more the wallet may buy; `None` when the listing has no cap
+    pub remaining: Option<BigDecimal>,
+    /// Smallest next purchase that satisfies the minimum ticket
+    pub min_next_purchase: BigDecimal,
+}
+
+pub fn set_purchase_limits(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    input: SetPurchaseLimitsInputArgs,
+) -> Result<()> {
+    let zero = BigDecimal::from(0);
+    if input.min_purchase.as_ref().is_some_and(|m| *m <= zero)
+        || input.max_per_wallet.as_ref().is_some_and(|m| *m <= zero)
+    {
+        return Err(anyhow!("Purchase limits must be positive"));
+    }
+    if let (Some(min), Some(max)) = (&input.min_purchase, &input.max_per_wallet) {
+        if min > max {
+            return Err(anyhow!("min_purchase can't be above max_per_wallet"));
+        }
+    }
+
+    use crate::schema::cradlenativelistings::dsl::*;
+
+    let updated = diesel::update(cradlenativelistings)
+        .filter(id.eq(input.listing_id))
+        .filter(lifecycle.eq_any(vec![
+            ListingLifecycle::Draft,
+            ListingLifecycle::Submitted,
+            ListingLifecycle::Approved,
+        ]))
+        .set((
+            min_purchase.eq(input.min_purchase),
+            max_per_wallet.eq(input.max_per_wallet),
+        ))
+        .execute(conn)?;
+
+    if updated == 0 {
+        return Err(anyhow!(
+            "Purchase limits of listing {} can't change once it has gone live",
+            input.listing_id
+        ));
+    }
+
+    Ok(())
+}
+
+/// What `wallet` has taken from the listing so far: net purchases from the
+/// ledger plus any commitment still waiting for the pro-rata pass.
+pub fn get_wallet_purchased(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: &CradleNativeListingRow,
+    wallet: Uuid,
+) -> Result<BigDecimal> {
+    let wallet_address = {
+        use crate::schema::cradlewalletaccounts::dsl::*;
+
+        cradlewalletaccounts
+            .filter(id.eq(wallet))
+            .get_result::<CradleWalletAccountRecord>(conn)?
+            .address
+    };
+
+    let (bought, returned) = {
+        use crate::schema::accountassetsledger::dsl::*;
+
+        let bought = accountassetsledger
+            .filter(asset.eq(listing.listed_asset))
+            .filter(transaction_type.eq(AccountLedgerTransactionType::BuyListed))
+            .filter(to_address.eq(&wallet_address))
+            .select(diesel::dsl::sum(amount))
+            .first::<Option<BigDecimal>>(conn)?
+            .unwrap_or_default();
+
+        let returned = accountassetsledger
+            .filter(asset.eq(listing.listed_asset))
+            .filter(transaction_type.eq(AccountLedgerTransactionType::SellListed))
+            .filter(from_address.eq(&wallet_address))
+            .select(diesel::dsl::sum(amount))
+            .first::<Option<BigDecimal>>(conn)?
+            .unwrap_or_default();
+
+        (bought, returned)
+    };
+
+    let committed = {
+        use crate::schema::listing_allocations::dsl::*;
+
+        listing_allocations
+            .filter(listing_id.eq(listing.id))
+            .filter(wallet_id.eq(wallet))
+            .filter(status.eq(AllocationStatus::Committed))
+            .select(diesel::dsl::sum(requested))
+            .first::<Option<BigDecimal>>(conn)?
+            .unwrap_or_default()
+    };
+
+    Ok(bought - returned + committed)
+}
+
+pub fn get_wallet_allowance(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: &CradleNativeListingRow,
+    wallet: Uuid,
+) -> Result<WalletAllowance> {
+    let zero = BigDecimal::from(0);
+    let purchased = get_wallet_purchased(conn, listing, wallet)?;
+
+    let remaining = listing.max_per_wallet.as_ref().map(|max| {
+        let left = max - &purchased;
+        if left < zero { zero.clone() } else { left }
+    });
+
+    let min_next_purchase = match &listing.min_purchase {
+        Some(min) if *min > purchased => min - &purchased,
+        _ => zero,
+    };
+
+    Ok(WalletAllowance {
+        wallet_id: wallet,
+        purchased,
+        remaining,
+        min_next_purchase,
+    })
+}
+
+/// Error unless buying `amount` keeps the wallet's total between the
+/// listing's minimum ticket and per-wallet cap.
+pub fn expect_within_limits(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: &CradleNativeListingRow,
+    wallet: Uuid,
+    amount: &BigDecimal,
+) -> Result<()> {
+    if listing.min_purchase.is_none() && listing.max_per_wallet.is_none() {
+        return Ok(());
+    }
+
+    let allowance = get_wallet_allowance(conn, listing, wallet)?;
+
+    if *amount < allowance.min_next_purchase {
+        return Err(anyhow!(
+            "Purchase of {} is below the minimum ticket; wallet {} needs at least {} more",
+            amount,
+            wallet,
+            allowance.min_next_purchase
+        ));
+    }
+
+    if let Some(remaining) = &allowance.remaining {
+        if amount > remaining {
+            return Err(anyhow!(
+                "Purchase of {} exceeds the per-wallet cap; wallet {} can buy {} more",
+                amount,
+                wallet,
+                remaining
+            ));
+        }
+    }
+
+    Ok(())
+}
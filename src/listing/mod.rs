@@ -1,5 +1,23 @@
+pub mod allocation;
+pub mod companies;
 pub mod config;
+pub mod corporate_actions;
 pub mod db_types;
+pub mod distributions;
+pub mod events;
+pub mod holders;
+pub mod lifecycle;
+pub mod limits;
 pub mod operations;
 pub mod processor;
 pub mod processor_enums;
+pub mod reconciliation;
+pub mod refunds;
+pub mod scheduler;
+pub mod search;
+pub mod secondary_market;
+pub mod split_airdrops;
+pub mod stats;
+pub mod tranches;
+pub mod whitelist;
+pub mod withdrawals;
@@ -22,10 +22,12 @@ use crate::{
     },
     big_to_u64, extract_option,
     listing::db_types::{
-        CompanyRow, CradleNativeListingRow, CreateCompany, CreateCraldeNativeListing, ListingStatus,
+        CompanyRow, CradleNativeListingRow, CreateCompany, CreateCraldeNativeListing,
+        ListingAllocationMode, ListingLifecycle, ListingStatus,
     },
+    listing::lifecycle::{expect_lifecycle, expect_within_window},
     schema::cradlenativelistings::{self, shadow_asset},
-    utils::commons::get_system_addresses,
+    utils::{commons::get_system_addresses, wallet::SharedWallet},
 };
 use accounts::operations::*;
 use anyhow::{Result, anyhow};
@@ -45,7 +47,6 @@ use contract_integrator::{
             CradleListingFactoryFunctionsInput, CradleListingFactoryFunctionsOutput, CreateListing,
         },
     },
-    wallet::wallet::ActionWallet,
 };
 use diesel::prelude::*;
 use diesel::{
@@ -67,6 +68,99 @@ pub async fn get_listing(
     Ok(res)
 }
 
+/// Net amount of the listed asset sold through the listing, from the ledger,
+/// in post-split units.
+pub fn get_listing_sold_supply(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: &CradleNativeListingRow,
+) -> Result<BigDecimal> {
+    use crate::schema::accountassetsledger::dsl::*;
+
+    let bought = accountassetsledger
+        .filter(asset.eq(listing.listed_asset))
+        .filter(transaction_type.eq(AccountLedgerTransactionType::BuyListed))
+        .select(diesel::dsl::sum(amount))
+        .first::<Option<BigDecimal>>(conn)?
+        .unwrap_or_default();
+
+    let returned = accountassetsledger
+        .filter(asset.eq(listing.listed_asset))
+        .filter(transaction_type.eq(AccountLedgerTransactionType::SellListed))
+        .select(diesel::dsl::sum(amount))
+        .first::<Option<BigDecimal>>(conn)?
+        .unwrap_or_default();
+
+    Ok((bought - returned) * &listing.split_factor)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ListingHolding {
+    pub wallet_id: Uuid,
+    pub address: String,
+    pub amount: BigDecimal,
+}
+
+/// Net listed-asset position per wallet bought through the listing (purchases
+/// minus returns), from the ledger. Wallets that returned everything are left out.
+pub fn get_listing_holdings(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: &CradleNativeListingRow,
+) -> Result<Vec<ListingHolding>> {
+    use std::collections::HashMap;
+
+    let (bought, returned) = {
+        use crate::schema::accountassetsledger::dsl::*;
+
+        let bought = accountassetsledger
+            .filter(asset.eq(listing.listed_asset))
+            .filter(transaction_type.eq(AccountLedgerTransactionType::BuyListed))
+            .group_by(to_address)
+            .select((to_address, diesel::dsl::sum(amount)))
+            .load::<(String, Option<BigDecimal>)>(conn)?;
+
+        let returned = accountassetsledger
+            .filter(asset.eq(listing.listed_asset))
+            .filter(transaction_type.eq(AccountLedgerTransactionType::SellListed))
+            .group_by(from_address)
+            .select((from_address, diesel::dsl::sum(amount)))
+            .load::<(String, Option<BigDecimal>)>(conn)?;
+
+        (bought, returned)
+    };
+
+    let mut net: HashMap<String, BigDecimal> = HashMap::new();
+    for (addr, total) in bought {
+        *net.entry(addr).or_default() += total.unwrap_or_default();
+    }
+    for (addr, total) in returned {
+        *net.entry(addr).or_default() -= total.unwrap_or_default();
+    }
+
+    let wallets = {
+        use crate::schema::cradlewalletaccounts::dsl::*;
+
+        cradlewalletaccounts
+            .filter(address.eq_any(net.keys().cloned().collect::<Vec<_>>()))
+            .get_results::<CradleWalletAccountRecord>(conn)?
+    };
+
+    let zero = BigDecimal::from(0);
+    let mut holdings: Vec<ListingHolding> = wallets
+        .into_iter()
+        .filter_map(|w| {
+            let amount = net.get(&w.address)? * &listing.split_factor;
+            (amount > zero).then(|| ListingHolding {
+                wallet_id: w.id,
+                address: w.address,
+                amount,
+            })
+        })
+        .collect();
+    holdings.sort_by(|a, b| b.amount.cmp(&a.amount));
+
+    Ok(holdings)
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct CreateCompanyInputArgs {
     pub name: String,
@@ -76,7 +170,7 @@ pub struct CreateCompanyInputArgs {
 
 pub async fn create_company(
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
-    wallet: &mut ActionWallet,
+    wallet: &SharedWallet,
     input_args: CreateCompanyInputArgs,
 ) -> Result<Uuid> {
     use crate::schema::cradlelistedcompanies::{dsl::id, table as CompanyTable};
@@ -135,7 +229,7 @@ pub struct CreateListingInputArgs {
 
 pub async fn create_listing(
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
-    wallet: &mut ActionWallet,
+    wallet: &SharedWallet,
     input: CreateListingInputArgs,
 ) -> Result<Uuid> {
     let company = {
@@ -398,11 +492,14 @@ pub struct PurchaseListingAssetInputArgs {
     pub wallet: Uuid,
     pub amount: BigDecimal,
     pub listing: Uuid,
+    /// Tranche to buy from; required when tranches sell in parallel
+    #[serde(default)]
+    pub tranche: Option<Uuid>,
 }
 
 pub async fn purchase(
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
-    wallet: &mut ActionWallet,
+    wallet: &SharedWallet,
     input: PurchaseListingAssetInputArgs,
 ) -> Result<Uuid> {
     let listing = {
@@ -413,6 +510,44 @@ pub async fn purchase(
             .get_result::<CradleNativeListingRow>(conn)?
     };
 
+    expect_purchasable(&listing)?;
+    if listing.allocation_mode == ListingAllocationMode::ProRata {
+        return Err(anyhow!(
+            "Listing {} allocates pro-rata; purchases are taken as commitments",
+            listing.id
+        ));
+    }
+
+    execute_purchase(conn, wallet, &listing, input.wallet, &input.amount).await
+}
+
+/// Checks shared by direct purchases and pro-rata commitments.
+pub fn expect_purchasable(listing: &CradleNativeListingRow) -> Result<()> {
+    expect_lifecycle(listing, ListingLifecycle::Live)?;
+    expect_within_window(listing, chrono::Utc::now().naive_utc())?;
+    if !matches!(listing.status, ListingStatus::Open) {
+        return Err(anyhow!("Listing {} is {:?}", listing.id, listing.status));
+    }
+    Ok(())
+}
+
+/// Buy from the listing contract and record it in the ledger, without the
+/// lifecycle checks. Used directly by the allocation pass, which runs once
+/// the window has ended.
+pub async fn execute_purchase(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    wallet: &SharedWallet,
+    listing: &CradleNativeListingRow,
+    wallet_id: Uuid,
+    amount: &BigDecimal,
+) -> Result<Uuid> {
+    let input = PurchaseListingAssetInputArgs {
+        wallet: wallet_id,
+        amount: amount.clone(),
+        listing: listing.id,
+        tranche: None,
+    };
+
     let account_wallet = {
         use crate::schema::cradlewalletaccounts::dsl::*;
 
@@ -461,7 +596,7 @@ pub async fn purchase(
 
     let transaction_input = ContractCallInput::CradleNativeListing(
         CradleNativeListingFunctionsInput::Purchase(WithContractId {
-            contract_id: listing.listing_contract_id,
+            contract_id: listing.listing_contract_id.clone(),
             rest: Some(PurchaseInputArgs {
                 buyer: account_wallet.address.clone(),
                 amount: input
@@ -502,7 +637,7 @@ pub struct ReturnAssetListingInputArgs {
 
 pub async fn return_asset(
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
-    wallet: &mut ActionWallet,
+    wallet: &SharedWallet,
     input: ReturnAssetListingInputArgs,
 ) -> Result<Uuid> {
     let listing = {
@@ -600,7 +735,7 @@ pub struct WithdrawToBeneficiaryInputArgsBody {
 
 pub async fn withdraw_to_beneficiary(
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
-    wallet: &mut ActionWallet,
+    wallet: &SharedWallet,
     input: WithdrawToBeneficiaryInputArgsBody,
 ) -> Result<Uuid> {
     let listing = {
@@ -673,12 +808,20 @@ pub async fn withdraw_to_beneficiary(
         None,
     )?;
 
+    // tag the entry with the listing so reconciliation can attribute it
+    {
+        use crate::schema::accountassetsledger::dsl::*;
+        diesel::update(accountassetsledger.find(tx))
+            .set(refference.eq(Some(listing.id.to_string())))
+            .execute(conn)?;
+    }
+
     Ok(tx)
 }
 
 pub async fn get_listing_stats(
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
-    wallet: &mut ActionWallet,
+    wallet: &SharedWallet,
     listing_id: Uuid,
 ) -> Result<ListingStats> {
     let listing = get_listing(conn, listing_id).await?;
@@ -708,7 +851,7 @@ pub struct GetPurchaseFeeInputArgs {
 
 pub async fn get_purchase_fee(
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
-    wallet: &mut ActionWallet,
+    wallet: &SharedWallet,
     args: GetPurchaseFeeInputArgs,
 ) -> Result<u64> {
     let listing = get_listing(conn, args.listing_id).await?;
@@ -732,7 +875,7 @@ pub async fn get_purchase_fee(
 
 pub async fn update_listing_status(
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
-    wallet: &mut ActionWallet,
+    wallet: &SharedWallet,
     listing_id: Uuid,
     new_status: ListingStatus,
 ) -> Result<()> {
@@ -1,4 +1,33 @@
 use crate::listing::config::CradleNativeListingsConfig;
+use crate::listing::allocation::{
+    ListingAllocationEvent, allocate_before_close, commit, set_allocation_mode,
+};
+use crate::listing::companies::{set_company_verification, update_company};
+use crate::listing::corporate_actions::apply_corporate_action;
+use crate::listing::distributions::{claim_payout, create_distribution, pay_distribution};
+use crate::listing::whitelist::{
+    add_to_whitelist, expect_whitelisted, import_whitelist, remove_from_whitelist,
+    set_whitelist_enabled,
+};
+use crate::listing::withdrawals::{
+    approve_withdrawal, reject_withdrawal, request_withdrawal, withdraw_within_threshold,
+};
+use crate::listing::limits::{expect_within_limits, set_purchase_limits};
+use crate::listing::secondary_market::{
+    create_market_after_close, create_secondary_market, set_create_market_on_close,
+};
+use crate::listing::reconciliation::reconcile_listing;
+use crate::listing::refunds::{cancel_listing, refund_listing, set_soft_cap};
+use crate::listing::stats::get_listing_stats_summary;
+use crate::listing::tranches::{
+    record_tranche_purchase, release_tranche, reserve_tranche, select_tranche, set_tranches,
+};
+use crate::listing::db_types::{ListingAllocationMode, ListingLifecycle, ListingStatus};
+use crate::listing::events::{
+    ListingPurchaseEvent, ListingThresholdEvent, crossed_thresholds, emit_lifecycle_change,
+    emit_listing_event, emit_status_change,
+};
+use crate::listing::lifecycle::*;
 use crate::listing::operations::*;
 use crate::{
     listing::processor_enums::{
@@ -22,36 +51,262 @@ impl ActionProcessor<CradleNativeListingsConfig, CradleNativeListingFunctionsOut
         >,
     ) -> anyhow::Result<CradleNativeListingFunctionsOutput> {
         let app_conn = conn.ok_or_else(|| anyhow!("Unable to retrieve conn"))?;
-        let mut wallet = app_config.wallet.clone();
         match self {
             CradleNativeListingFunctionsInput::CreateCompany(input) => {
-                let res = create_company(app_conn, &mut wallet, input.clone()).await?;
+                let res = create_company(app_conn, &app_config.wallet, input.clone()).await?;
                 Ok(CradleNativeListingFunctionsOutput::CreateCompany(res))
             }
+            CradleNativeListingFunctionsInput::UpdateCompany(input) => {
+                let res = update_company(app_conn, input.clone())?;
+                Ok(CradleNativeListingFunctionsOutput::UpdateCompany(res))
+            }
+            CradleNativeListingFunctionsInput::SetCompanyVerification(input) => {
+                let approver = local_config.approver()?;
+                let res = set_company_verification(app_conn, approver, input.clone())?;
+                Ok(CradleNativeListingFunctionsOutput::SetCompanyVerification(res))
+            }
             CradleNativeListingFunctionsInput::CreateListing(input) => {
-                let res = create_listing(app_conn, &mut wallet, input.clone()).await?;
+                let res = create_listing(app_conn, &app_config.wallet, input.clone()).await?;
                 Ok(CradleNativeListingFunctionsOutput::CreateListing(res))
             }
             CradleNativeListingFunctionsInput::Purchase(input) => {
-                purchase(app_conn, &mut wallet, input.clone()).await?;
+                let listing = get_listing(app_conn, input.listing).await?;
+                expect_whitelisted(app_conn, &listing, input.wallet)?;
+                expect_within_limits(app_conn, &listing, input.wallet, &input.amount)?;
+
+                if listing.allocation_mode == ListingAllocationMode::ProRata {
+                    let row = commit(app_config, app_conn, input.clone()).await?;
+                    emit_listing_event(
+                        app_config,
+                        listing.id,
+                        "listing:commitment",
+                        &ListingAllocationEvent::from(&row),
+                    )
+                    .await;
+                    return Ok(CradleNativeListingFunctionsOutput::Purchase);
+                }
+
+                let tranche = select_tranche(app_conn, &listing, input.tranche, &input.amount)?;
+                if let Some(t) = &tranche {
+                    reserve_tranche(app_conn, t.id, &input.amount)?;
+                }
+
+                let sold_before = get_listing_sold_supply(app_conn, &listing)?;
+
+                let transaction_id =
+                    match purchase(app_conn, &app_config.wallet, input.clone()).await {
+                        Ok(tx) => tx,
+                        Err(e) => {
+                            if let Some(t) = &tranche {
+                                release_tranche(app_conn, t.id, &input.amount)?;
+                            }
+                            return Err(e);
+                        }
+                    };
+                if let Some(t) = &tranche {
+                    record_tranche_purchase(
+                        app_conn,
+                        t,
+                        input.wallet,
+                        &input.amount,
+                        transaction_id,
+                    )?;
+                }
+
+                let sold_after = get_listing_sold_supply(app_conn, &listing)?;
+                emit_listing_event(
+                    app_config,
+                    listing.id,
+                    "listing:purchase",
+                    &ListingPurchaseEvent {
+                        listing_id: listing.id,
+                        wallet: input.wallet,
+                        amount: input.amount.to_string(),
+                        sold: sold_after.to_string(),
+                        max_supply: listing.max_supply.to_string(),
+                        transaction_id,
+                    },
+                )
+                .await;
+
+                for threshold in crossed_thresholds(&sold_before, &sold_after, &listing.max_supply) {
+                    emit_listing_event(
+                        app_config,
+                        listing.id,
+                        "listing:threshold",
+                        &ListingThresholdEvent {
+                            listing_id: listing.id,
+                            threshold_percent: threshold,
+                            sold: sold_after.to_string(),
+                            max_supply: listing.max_supply.to_string(),
+                        },
+                    )
+                    .await;
+                }
+
                 Ok(CradleNativeListingFunctionsOutput::Purchase)
             }
             CradleNativeListingFunctionsInput::ReturnAsset(input) => {
-                return_asset(app_conn, &mut wallet, input.clone());
+                return_asset(app_conn, &app_config.wallet, input.clone());
                 Ok(CradleNativeListingFunctionsOutput::ReturnAsset)
             }
             CradleNativeListingFunctionsInput::WithdrawToBeneficiary(input) => {
-                withdraw_to_beneficiary(app_conn, &mut wallet, input.clone());
+                withdraw_within_threshold(app_config, app_conn, input.clone()).await?;
                 Ok(CradleNativeListingFunctionsOutput::WithdrawToBeneficiary)
             }
+            CradleNativeListingFunctionsInput::RequestWithdrawal(input) => {
+                let requester = local_config.operator()?;
+                let row =
+                    request_withdrawal(app_config, app_conn, requester, input.clone()).await?;
+                Ok(CradleNativeListingFunctionsOutput::RequestWithdrawal(row))
+            }
+            CradleNativeListingFunctionsInput::ApproveWithdrawal(input) => {
+                let approver = local_config.approver()?;
+                let row = approve_withdrawal(app_config, app_conn, approver, input.clone()).await?;
+                Ok(CradleNativeListingFunctionsOutput::ApproveWithdrawal(row))
+            }
+            CradleNativeListingFunctionsInput::RejectWithdrawal(input) => {
+                let approver = local_config.approver()?;
+                let row = reject_withdrawal(app_config, app_conn, approver, input.clone()).await?;
+                Ok(CradleNativeListingFunctionsOutput::RejectWithdrawal(row))
+            }
             CradleNativeListingFunctionsInput::GetStats(input) => {
-                let res = get_listing_stats(app_conn, &mut wallet, *input).await?;
+                let res =
+                    get_listing_stats_summary(app_conn, &app_config.wallet, *input, None).await?;
                 Ok(CradleNativeListingFunctionsOutput::GetStats(res))
             }
             CradleNativeListingFunctionsInput::GetFee(input) => {
-                let res = get_purchase_fee(app_conn, &mut wallet, input.clone()).await?;
+                let res = get_purchase_fee(app_conn, &app_config.wallet, input.clone()).await?;
                 Ok(CradleNativeListingFunctionsOutput::GetFee(res))
             }
+            CradleNativeListingFunctionsInput::UpdateStatus(input) => {
+                // Opening goes through GoLive so the approval step can't be skipped;
+                // this stays for pausing/resuming a live listing.
+                if matches!(input.status, ListingStatus::Open) {
+                    let listing = get_listing(app_conn, input.listing_id).await?;
+                    expect_lifecycle(&listing, ListingLifecycle::Live)?;
+                }
+                update_listing_status(
+                    app_conn,
+                    &app_config.wallet,
+                    input.listing_id,
+                    input.status.clone(),
+                )
+                .await?;
+                emit_status_change(app_config, input.listing_id, input.status.clone()).await;
+                Ok(CradleNativeListingFunctionsOutput::UpdateStatus)
+            }
+            CradleNativeListingFunctionsInput::SubmitListing(listing_id) => {
+                submit_listing(app_conn, *listing_id)?;
+                emit_lifecycle_change(app_config, *listing_id, ListingLifecycle::Submitted).await;
+                Ok(CradleNativeListingFunctionsOutput::SubmitListing)
+            }
+            CradleNativeListingFunctionsInput::ApproveListing(input) => {
+                let approver = local_config.approver()?;
+                approve_listing(app_conn, approver, input.clone())?;
+                emit_lifecycle_change(app_config, input.listing_id, ListingLifecycle::Approved).await;
+                Ok(CradleNativeListingFunctionsOutput::ApproveListing)
+            }
+            CradleNativeListingFunctionsInput::RejectListing(input) => {
+                let approver = local_config.approver()?;
+                reject_listing(app_conn, approver, input.clone())?;
+                emit_lifecycle_change(app_config, input.listing_id, ListingLifecycle::Draft).await;
+                Ok(CradleNativeListingFunctionsOutput::RejectListing)
+            }
+            CradleNativeListingFunctionsInput::GoLive(listing_id) => {
+                go_live(app_conn, &app_config.wallet, *listing_id).await?;
+                emit_lifecycle_change(app_config, *listing_id, ListingLifecycle::Live).await;
+                emit_status_change(app_config, *listing_id, ListingStatus::Open).await;
+                Ok(CradleNativeListingFunctionsOutput::GoLive)
+            }
+            CradleNativeListingFunctionsInput::CloseListing(listing_id) => {
+                allocate_before_close(app_config, app_conn, *listing_id).await?;
+                close_listing(app_conn, &app_config.wallet, *listing_id).await?;
+                emit_lifecycle_change(app_config, *listing_id, ListingLifecycle::Closed).await;
+                emit_status_change(app_config, *listing_id, ListingStatus::Closed).await;
+                create_market_after_close(app_config, app_conn, *listing_id).await;
+                Ok(CradleNativeListingFunctionsOutput::CloseListing)
+            }
+            CradleNativeListingFunctionsInput::SettleListing(listing_id) => {
+                settle_listing(app_conn, *listing_id)?;
+                emit_lifecycle_change(app_config, *listing_id, ListingLifecycle::Settled).await;
+                Ok(CradleNativeListingFunctionsOutput::SettleListing)
+            }
+            CradleNativeListingFunctionsInput::ScheduleListing(input) => {
+                schedule_listing(app_conn, input.clone())?;
+                Ok(CradleNativeListingFunctionsOutput::ScheduleListing)
+            }
+            CradleNativeListingFunctionsInput::SetAllocationMode(input) => {
+                set_allocation_mode(app_conn, input.clone())?;
+                Ok(CradleNativeListingFunctionsOutput::SetAllocationMode)
+            }
+            CradleNativeListingFunctionsInput::SetSoftCap(input) => {
+                set_soft_cap(app_conn, input.clone())?;
+                Ok(CradleNativeListingFunctionsOutput::SetSoftCap)
+            }
+            CradleNativeListingFunctionsInput::CancelListing(listing_id) => {
+                cancel_listing(app_conn, &app_config.wallet, *listing_id).await?;
+                emit_lifecycle_change(app_config, *listing_id, ListingLifecycle::Cancelled).await;
+                emit_status_change(app_config, *listing_id, ListingStatus::Cancelled).await;
+                Ok(CradleNativeListingFunctionsOutput::CancelListing)
+            }
+            CradleNativeListingFunctionsInput::RefundListing(listing_id) => {
+                let summary = refund_listing(app_config, app_conn, *listing_id).await?;
+                Ok(CradleNativeListingFunctionsOutput::RefundListing(summary))
+            }
+            CradleNativeListingFunctionsInput::CreateDistribution(input) => {
+                let distribution = create_distribution(app_config, app_conn, input.clone()).await?;
+                Ok(CradleNativeListingFunctionsOutput::CreateDistribution(distribution))
+            }
+            CradleNativeListingFunctionsInput::PayDistribution(distribution_id) => {
+                let summary = pay_distribution(app_config, app_conn, *distribution_id).await?;
+                Ok(CradleNativeListingFunctionsOutput::PayDistribution(summary))
+            }
+            CradleNativeListingFunctionsInput::ClaimPayout(input) => {
+                let payout = claim_payout(app_config, app_conn, input.clone()).await?;
+                Ok(CradleNativeListingFunctionsOutput::ClaimPayout(payout))
+            }
+            CradleNativeListingFunctionsInput::SetWhitelistEnabled(input) => {
+                set_whitelist_enabled(app_conn, input.clone())?;
+                Ok(CradleNativeListingFunctionsOutput::SetWhitelistEnabled)
+            }
+            CradleNativeListingFunctionsInput::AddToWhitelist(input) => {
+                let added = add_to_whitelist(app_conn, input.clone())?;
+                Ok(CradleNativeListingFunctionsOutput::AddToWhitelist(added))
+            }
+            CradleNativeListingFunctionsInput::RemoveFromWhitelist(input) => {
+                let removed = remove_from_whitelist(app_conn, input.clone())?;
+                Ok(CradleNativeListingFunctionsOutput::RemoveFromWhitelist(removed))
+            }
+            CradleNativeListingFunctionsInput::ImportWhitelist(input) => {
+                let added = import_whitelist(app_conn, input.clone())?;
+                Ok(CradleNativeListingFunctionsOutput::ImportWhitelist(added))
+            }
+            CradleNativeListingFunctionsInput::SetPurchaseLimits(input) => {
+                set_purchase_limits(app_conn, input.clone())?;
+                Ok(CradleNativeListingFunctionsOutput::SetPurchaseLimits)
+            }
+            CradleNativeListingFunctionsInput::SetCreateMarketOnClose(input) => {
+                set_create_market_on_close(app_conn, input.clone())?;
+                Ok(CradleNativeListingFunctionsOutput::SetCreateMarketOnClose)
+            }
+            CradleNativeListingFunctionsInput::CreateSecondaryMarket(listing_id) => {
+                let market = create_secondary_market(app_config, app_conn, *listing_id).await?;
+                Ok(CradleNativeListingFunctionsOutput::CreateSecondaryMarket(market))
+            }
+            CradleNativeListingFunctionsInput::SetTranches(input) => {
+                let rows = set_tranches(app_conn, input.clone()).await?;
+                Ok(CradleNativeListingFunctionsOutput::SetTranches(rows))
+            }
+            CradleNativeListingFunctionsInput::CorporateAction(input) => {
+                let approver = local_config.approver()?;
+                let res = apply_corporate_action(app_config, app_conn, approver, input.clone()).await?;
+                Ok(CradleNativeListingFunctionsOutput::CorporateAction(res))
+            }
+            CradleNativeListingFunctionsInput::ReconcileListing(listing_id) => {
+                let row = reconcile_listing(app_conn, &app_config.wallet, *listing_id).await?;
+                Ok(CradleNativeListingFunctionsOutput::ReconcileListing(row))
+            }
         }
     }
 }
@@ -1,9 +1,29 @@
-use contract_integrator::utils::functions::{
-    FunctionCallOutput, cradle_native_listing::ListingStats,
-};
+use contract_integrator::utils::functions::FunctionCallOutput;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::listing::allocation::SetAllocationModeInputArgs;
+use crate::listing::companies::{SetCompanyVerificationInputArgs, UpdateCompanyInputArgs};
+use crate::listing::corporate_actions::{CorporateActionInputArgs, CorporateActionResult};
+use crate::listing::db_types::{
+    CompanyRow, DistributionPayoutRow, ListingDistributionRow, ListingReconciliationRow,
+    ListingStatus, ListingTrancheRow, WithdrawalRequestRow,
+};
+use crate::listing::distributions::{
+    ClaimPayoutInputArgs, CreateDistributionInputArgs, DistributionSummary,
+};
+use crate::listing::refunds::{RefundSummary, SetSoftCapInputArgs};
+use crate::listing::stats::ListingStats;
+use crate::listing::tranches::SetTranchesInputArgs;
+use crate::listing::whitelist::{
+    ImportWhitelistInputArgs, SetWhitelistEnabledInputArgs, WhitelistWalletsInputArgs,
+};
+use crate::listing::withdrawals::{
+    ApproveWithdrawalInputArgs, RejectWithdrawalInputArgs, RequestWithdrawalInputArgs,
+};
+use crate::listing::limits::SetPurchaseLimitsInputArgs;
+use crate::listing::secondary_market::SetCreateMarketOnCloseInputArgs;
+use crate::listing::lifecycle::{ReviewListingInputArgs, ScheduleListingInputArgs};
 use crate::listing::operations::{
     CreateCompanyInputArgs, CreateListingInputArgs, GetPurchaseFeeInputArgs,
     PurchaseListingAssetInputArgs, ReturnAssetListingInputArgs, WithdrawToBeneficiaryInputArgsBody,
@@ -12,21 +32,87 @@ use crate::listing::operations::{
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum CradleNativeListingFunctionsInput {
     CreateCompany(CreateCompanyInputArgs),
+    UpdateCompany(UpdateCompanyInputArgs),
+    SetCompanyVerification(SetCompanyVerificationInputArgs),
     CreateListing(CreateListingInputArgs),
     Purchase(PurchaseListingAssetInputArgs),
     ReturnAsset(ReturnAssetListingInputArgs),
     WithdrawToBeneficiary(WithdrawToBeneficiaryInputArgsBody),
+    RequestWithdrawal(RequestWithdrawalInputArgs),
+    ApproveWithdrawal(ApproveWithdrawalInputArgs),
+    RejectWithdrawal(RejectWithdrawalInputArgs),
     GetStats(Uuid),
     GetFee(GetPurchaseFeeInputArgs),
+    UpdateStatus(UpdateListingStatusInputArgs),
+    SubmitListing(Uuid),
+    ApproveListing(ReviewListingInputArgs),
+    RejectListing(ReviewListingInputArgs),
+    GoLive(Uuid),
+    CloseListing(Uuid),
+    SettleListing(Uuid),
+    ScheduleListing(ScheduleListingInputArgs),
+    SetAllocationMode(SetAllocationModeInputArgs),
+    SetSoftCap(SetSoftCapInputArgs),
+    CancelListing(Uuid),
+    RefundListing(Uuid),
+    CreateDistribution(CreateDistributionInputArgs),
+    PayDistribution(Uuid),
+    ClaimPayout(ClaimPayoutInputArgs),
+    SetWhitelistEnabled(SetWhitelistEnabledInputArgs),
+    AddToWhitelist(WhitelistWalletsInputArgs),
+    RemoveFromWhitelist(WhitelistWalletsInputArgs),
+    ImportWhitelist(ImportWhitelistInputArgs),
+    SetPurchaseLimits(SetPurchaseLimitsInputArgs),
+    SetCreateMarketOnClose(SetCreateMarketOnCloseInputArgs),
+    CreateSecondaryMarket(Uuid),
+    ReconcileListing(Uuid),
+    SetTranches(SetTranchesInputArgs),
+    CorporateAction(CorporateActionInputArgs),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UpdateListingStatusInputArgs {
+    pub listing_id: Uuid,
+    pub status: ListingStatus,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub enum CradleNativeListingFunctionsOutput {
     CreateCompany(Uuid),
+    UpdateCompany(CompanyRow),
+    SetCompanyVerification(CompanyRow),
     CreateListing(Uuid),
     Purchase,
     ReturnAsset,
     WithdrawToBeneficiary,
+    RequestWithdrawal(WithdrawalRequestRow),
+    ApproveWithdrawal(WithdrawalRequestRow),
+    RejectWithdrawal(WithdrawalRequestRow),
     GetStats(ListingStats),
     GetFee(u64),
+    UpdateStatus,
+    SubmitListing,
+    ApproveListing,
+    RejectListing,
+    GoLive,
+    CloseListing,
+    SettleListing,
+    ScheduleListing,
+    SetAllocationMode,
+    SetSoftCap,
+    CancelListing,
+    RefundListing(RefundSummary),
+    CreateDistribution(ListingDistributionRow),
+    PayDistribution(DistributionSummary),
+    ClaimPayout(DistributionPayoutRow),
+    SetWhitelistEnabled,
+    AddToWhitelist(usize),
+    RemoveFromWhitelist(usize),
+    ImportWhitelist(usize),
+    SetPurchaseLimits,
+    SetCreateMarketOnClose,
+    CreateSecondaryMarket(Uuid),
+    ReconcileListing(ListingReconciliationRow),
+    SetTranches(Vec<ListingTrancheRow>),
+    CorporateAction(CorporateActionResult),
 }
@@ -0,0 +1,261 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDateTime, Utc};
+use contract_integrator::{
+    hedera::TokenId, utils::functions::commons, wallet::wallet::ActionWallet,
+};
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::accounts::db_types::CradleWalletAccountRecord;
+use crate::accounts_ledger::db_types::AccountLedgerTransactionType;
+use crate::asset_book::db_types::AssetBookRecord;
+use crate::listing::db_types::{
+    CompanyRow, CradleNativeListingRow, CreateListingReconciliation, ListingLifecycle,
+    ListingReconciliationRow, ReconciliationStatus,
+};
+use crate::listing::events::emit_listing_event;
+use crate::listing::operations::{get_listing, get_listing_sold_supply};
+use crate::listing::tranches::get_listing_raised;
+use crate::order_book::operations::can_execute_onchain;
+use crate::utils::app_config::AppConfig;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReconciliationReport {
+    pub generated_at: NaiveDateTime,
+    pub checked: usize,
+    pub matched: usize,
+    /// Latest check of every listing that didn't match, mismatches first
+    pub discrepancies: Vec<ListingReconciliationRow>,
+}
+
+/// Seconds between reconciliation passes, from LISTING_RECONCILE_INTERVAL_SECS.
+fn poll_interval() -> Duration {
+    let secs = std::env::var("LISTING_RECONCILE_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(3600);
+    Duration::from_secs(secs)
+}
+
+/// Largest difference still treated as matching, from
+/// LISTING_RECONCILE_TOLERANCE, in purchase-asset units.
+fn tolerance() -> BigDecimal {
+    std::env::var("LISTING_RECONCILE_TOLERANCE")
+        .ok()
+        .and_then(|v| BigDecimal::from_str(&v).ok())
+        .unwrap_or_default()
+}
+
+/// Purchase asset the listing contract should be holding according to the
+/// ledger: net sales at their price, less beneficiary withdrawals.
+/// Withdrawals recorded before they were tagged with a listing are matched
+/// on the beneficiary's address instead.
+pub fn expected_escrow(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: &CradleNativeListingRow,
+) -> Result<BigDecimal> {
+    let sold = get_listing_sold_supply(conn, listing)?;
+
+    let beneficiary_address = {
+        use crate::schema::cradlelistedcompanies::dsl as companies;
+        use crate::schema::cradlewalletaccounts::dsl::*;
+
+        let company = companies::cradlelistedcompanies
+            .filter(companies::id.eq(listing.company))
+            .get_result::<CompanyRow>(conn)?;
+
+        cradlewalletaccounts
+            .filter(id.eq(company.beneficiary_wallet))
+            .get_result::<CradleWalletAccountRecord>(conn)?
+            .address
+    };
+
+    let withdrawn = {
+        use crate::schema::accountassetsledger::dsl::*;
+
+        accountassetsledger
+            .filter(asset.eq(listing.purchase_with_asset))
+            .filter(transaction_type.eq(AccountLedgerTransactionType::ListingBeneficiaryWithdrawal))
+            .filter(
+                refference.eq(listing.id.to_string()).or(refference
+                    .is_null()
+                    .and(to_address.eq(&beneficiary_address))),
+            )
+            .select(diesel::dsl::sum(amount))
+            .first::<Option<BigDecimal>>(conn)?
+            .unwrap_or_default()
+    };
+
+    Ok(get_listing_raised(conn, listing, &sold)? - withdrawn)
+}
+
+/// Purchase-asset balance held by the listing contract on-chain.
+pub async fn onchain_escrow(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    wallet: &ActionWallet,
+    listing: &CradleNativeListingRow,
+) -> Result<BigDecimal> {
+    let purchase_asset = {
+        use crate::schema::asset_book::dsl::*;
+
+        asset_book
+            .filter(id.eq(listing.purchase_with_asset))
+            .get_result::<AssetBookRecord>(conn)?
+    };
+    let token = TokenId::from_solidity_address(&purchase_asset.token)
+        .map_err(|e| anyhow!("Invalid token for asset {}: {:?}", purchase_asset.id, e))?;
+
+    let balances = commons::get_account_balances(&wallet.client, &listing.listing_contract_id).await?;
+    Ok(BigDecimal::from(*balances.tokens.get(&token).unwrap_or(&0)))
+}
+
+/// Compare one listing's escrow against the ledger and record the result.
+/// A failed chain read is recorded too, so it shows up in the report.
+pub async fn reconcile_listing(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    wallet: &ActionWallet,
+    listing_id: Uuid,
+) -> Result<ListingReconciliationRow> {
+    let listing = get_listing(conn, listing_id).await?;
+    let expected_amount = expected_escrow(conn, &listing)?;
+
+    let record = match onchain_escrow(conn, wallet, &listing).await {
+        Ok(balance) => {
+            let diff = &balance - &expected_amount;
+            let matched = diff.abs() <= tolerance();
+            CreateListingReconciliation {
+                listing_id: listing.id,
+                asset: listing.purchase_with_asset,
+                expected: expected_amount,
+                onchain: Some(balance),
+                difference: Some(diff),
+                status: if matched {
+                    ReconciliationStatus::Matched
+                } else {
+                    ReconciliationStatus::Mismatch
+                },
+                error: None,
+            }
+        }
+        Err(e) => CreateListingReconciliation {
+            listing_id: listing.id,
+            asset: listing.purchase_with_asset,
+            expected: expected_amount,
+            onchain: None,
+            difference: None,
+            status: ReconciliationStatus::Failed,
+            error: Some(e.to_string()),
+        },
+    };
+
+    let row = {
+        use crate::schema::listing_reconciliations::dsl::*;
+
+        diesel::insert_into(listing_reconciliations)
+            .values(&record)
+            .get_result::<ListingReconciliationRow>(conn)?
+    };
+
+    Ok(row)
+}
+
+/// Reconcile every listing whose contract may hold funds.
+pub async fn run_once(app_config: &AppConfig) -> Result<()> {
+    let mut conn = app_config.pool.get()?;
+
+    let to_check = {
+        use crate::schema::cradlenativelistings::dsl::*;
+
+        cradlenativelistings
+            .filter(lifecycle.eq_any(vec![
+                ListingLifecycle::Live,
+                ListingLifecycle::Closed,
+                ListingLifecycle::Settled,
+                ListingLifecycle::Cancelled,
+            ]))
+            .select(id)
+            .get_results::<Uuid>(&mut conn)?
+    };
+
+    for listing_id in to_check {
+        match reconcile_listing(&mut conn, &app_config.wallet, listing_id).await {
+            Ok(row) if row.status == ReconciliationStatus::Matched => {}
+            Ok(row) => {
+                println!(
+                    "Listing {} escrow {:?}: expected {}, on-chain {:?}",
+                    listing_id, row.status, row.expected, row.onchain
+                );
+                emit_listing_event(app_config, listing_id, "listing:reconciliation", &row).await;
+            }
+            Err(e) => println!("Failed to reconcile listing {}: {:?}", listing_id, e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Background task reconciling listing escrow against the ledger. Does
+/// nothing when on-chain interactions are disabled.
+pub async fn run_reconciler(app_config: AppConfig) {
+    if !can_execute_onchain() {
+        return;
+    }
+
+    let mut ticker = tokio::time::interval(poll_interval());
+    loop {
+        ticker.tick().await;
+        if let Err(e) = run_once(&app_config).await {
+            println!("Listing reconciliation error: {:?}", e);
+        }
+    }
+}
+
+/// Recent checks of one listing, newest first.
+pub fn get_listing_reconciliations(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: Uuid,
+) -> Result<Vec<ListingReconciliationRow>> {
+    use crate::schema::listing_reconciliations::dsl::*;
+
+    Ok(listing_reconciliations
+        .filter(listing_id.eq(listing))
+        .order(checked_at.desc())
+        .limit(100)
+        .get_results::<ListingReconciliationRow>(conn)?)
+}
+
+/// The latest result for every listing, keeping those that didn't match.
+pub fn get_reconciliation_report(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+) -> Result<ReconciliationReport> {
+    use crate::schema::listing_reconciliations::dsl::*;
+
+    let latest = listing_reconciliations
+        .distinct_on(listing_id)
+        .order((listing_id, checked_at.desc()))
+        .get_results::<ListingReconciliationRow>(conn)?;
+
+    let checked = latest.len();
+    let mut discrepancies: Vec<ListingReconciliationRow> = latest
+        .into_iter()
+        .filter(|r| r.status != ReconciliationStatus::Matched)
+        .collect();
+    discrepancies.sort_by_key(|r| r.status != ReconciliationStatus::Mismatch);
+
+    Ok(ReconciliationReport {
+        generated_at: Utc::now().naive_utc(),
+        matched: checked - discrepancies.len(),
+        checked,
+        discrepancies,
+    })
+}
@@ -0,0 +1,298 @@
+use anyhow::{Result, anyhow};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::listing::db_types::{
+    AllocationStatus, CradleNativeListingRow, CreateListingRefund, ListingAllocationRow,
+    ListingLifecycle, ListingRefundRow, ListingStatus, RefundStatus,
+};
+use crate::listing::events::emit_listing_event;
+use crate::listing::operations::{
+    ReturnAssetListingInputArgs, get_listing, get_listing_holdings, get_listing_sold_supply,
+    return_asset, update_listing_status,
+};
+use crate::order_book::operations::unlock_asset;
+use crate::utils::app_config::AppConfig;
+use crate::utils::wallet::SharedWallet;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetSoftCapInputArgs {
+    pub listing_id: Uuid,
+    /// Listed-asset amount that must sell; `None` removes the requirement
+    pub soft_cap: Option<BigDecimal>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RefundSummary {
+    pub listing_id: Uuid,
+    pub pending: usize,
+    pub refunded: usize,
+    pub failed: usize,
+    pub refunds: Vec<ListingRefundRow>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ListingRefundEvent {
+    pub listing_id: Uuid,
+    pub wallet_id: Uuid,
+    pub amount: String,
+    pub status: RefundStatus,
+}
+
+pub fn set_soft_cap(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    input: SetSoftCapInputArgs,
+) -> Result<()> {
+    use crate::schema::cradlenativelistings::dsl::*;
+
+    let updated = diesel::update(cradlenativelistings)
+        .filter(id.eq(input.listing_id))
+        .filter(lifecycle.eq_any(vec![
+            ListingLifecycle::Draft,
+            ListingLifecycle::Submitted,
+            ListingLifecycle::Approved,
+        ]))
+        .set(soft_cap.eq(input.soft_cap))
+        .execute(conn)?;
+
+    if updated == 0 {
+        return Err(anyhow!(
+            "Soft cap of listing {} can't change once it has gone live",
+            input.listing_id
+        ));
+    }
+
+    Ok(())
+}
+
+/// Cancel a listing that hasn't settled. Blocks further purchases on-chain
+/// if it was ever opened; buyers are paid back with `refund_listing`.
+pub async fn cancel_listing(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    wallet: &SharedWallet,
+    listing_id: Uuid,
+) -> Result<()> {
+    let listing = get_listing(conn, listing_id).await?;
+
+    match listing.lifecycle {
+        ListingLifecycle::Settled | ListingLifecycle::Cancelled => {
+            return Err(anyhow!("Listing {} is already {:?}", listing_id, listing.lifecycle));
+        }
+        ListingLifecycle::Live | ListingLifecycle::Closed => {
+            update_listing_status(conn, wallet, listing_id, ListingStatus::Cancelled).await?;
+        }
+        _ => {}
+    }
+
+    mark_cancelled(conn, listing_id)
+}
+
+fn mark_cancelled(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing_id: Uuid,
+) -> Result<()> {
+    use crate::schema::cradlenativelistings::dsl::*;
+
+    diesel::update(cradlenativelistings.find(listing_id))
+        .set((
+            lifecycle.eq(ListingLifecycle::Cancelled),
+            stopped_at.eq(Some(Utc::now().naive_utc())),
+        ))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// A closed listing that sold less than its soft cap has failed.
+pub(crate) fn missed_soft_cap(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: &CradleNativeListingRow,
+) -> Result<bool> {
+    let Some(cap) = &listing.soft_cap else {
+        return Ok(false);
+    };
+    Ok(get_listing_sold_supply(conn, listing)? < *cap)
+}
+
+pub fn get_refund_summary(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: Uuid,
+) -> Result<RefundSummary> {
+    use crate::schema::listing_refunds::dsl::*;
+
+    let refunds = listing_refunds
+        .filter(listing_id.eq(listing))
+        .order(created_at.asc())
+        .get_results::<ListingRefundRow>(conn)?;
+
+    let count = |s: RefundStatus| refunds.iter().filter(|r| r.status == s).count();
+
+    Ok(RefundSummary {
+        listing_id: listing,
+        pending: count(RefundStatus::Pending),
+        refunded: count(RefundStatus::Refunded),
+        failed: count(RefundStatus::Failed),
+        refunds,
+    })
+}
+
+/// Return every buyer's purchase asset through the listing contract's return
+/// path. One refund row per buyer is created on the first run; reruns only
+/// retry rows that are still pending or failed. Outstanding pro-rata
+/// commitments are released as well.
+pub async fn refund_listing(
+    app_config: &mut AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing_id: Uuid,
+) -> Result<RefundSummary> {
+    let listing = get_listing(conn, listing_id).await?;
+
+    match listing.lifecycle {
+        ListingLifecycle::Cancelled => {}
+        ListingLifecycle::Closed if missed_soft_cap(conn, &listing)? => {
+            println!("Listing {} missed its soft cap, cancelling", listing_id);
+            mark_cancelled(conn, listing_id)?;
+        }
+        _ => {
+            return Err(anyhow!(
+                "Listing {} is {:?}; only cancelled listings or closed listings under their soft cap are refunded",
+                listing_id,
+                listing.lifecycle
+            ));
+        }
+    }
+
+    {
+        use crate::schema::listing_refunds::dsl::*;
+
+        let rows: Vec<CreateListingRefund> = get_listing_holdings(conn, &listing)?
+            .into_iter()
+            .map(|h| CreateListingRefund {
+                listing_id: listing.id,
+                wallet_id: h.wallet_id,
+                amount: h.amount,
+            })
+            .collect();
+
+        if !rows.is_empty() {
+            diesel::insert_into(listing_refunds)
+                .values(&rows)
+                .on_conflict((listing_id, wallet_id))
+                .do_nothing()
+                .execute(conn)?;
+        }
+    }
+
+    let outstanding = {
+        use crate::schema::listing_refunds::dsl::*;
+
+        listing_refunds
+            .filter(listing_id.eq(listing.id))
+            .filter(status.eq_any(vec![RefundStatus::Pending, RefundStatus::Failed]))
+            .get_results::<ListingRefundRow>(conn)?
+    };
+
+    for refund in outstanding {
+        let result = return_asset(
+            conn,
+            &app_config.wallet,
+            ReturnAssetListingInputArgs {
+                wallet: refund.wallet_id,
+                amount: refund.amount.clone(),
+                listing: listing.id,
+            },
+        )
+        .await;
+
+        let (new_status, tx, err) = match result {
+            Ok(tx) => (RefundStatus::Refunded, Some(tx), None),
+            Err(e) => {
+                println!("Refund for wallet {} on listing {} failed: {:?}", refund.wallet_id, listing.id, e);
+                (RefundStatus::Failed, None, Some(e.to_string()))
+            }
+        };
+
+        {
+            use crate::schema::listing_refunds::dsl::*;
+            diesel::update(listing_refunds.find(refund.id))
+                .set((
+                    status.eq(new_status.clone()),
+                    transaction_id.eq(tx),
+                    error.eq(err),
+                    updated_at.eq(Utc::now().naive_utc()),
+                ))
+                .execute(conn)?;
+        }
+
+        emit_listing_event(
+            app_config,
+            listing.id,
+            "listing:refund",
+            &ListingRefundEvent {
+                listing_id: listing.id,
+                wallet_id: refund.wallet_id,
+                amount: refund.amount.to_string(),
+                status: new_status,
+            },
+        )
+        .await;
+    }
+
+    release_commitments(app_config, conn, &listing).await?;
+
+    get_refund_summary(conn, listing.id)
+}
+
+async fn release_commitments(
+    app_config: &mut AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: &CradleNativeListingRow,
+) -> Result<()> {
+    let committed = {
+        use crate::schema::listing_allocations::dsl::*;
+
+        listing_allocations
+            .filter(listing_id.eq(listing.id))
+            .filter(status.eq(AllocationStatus::Committed))
+            .get_results::<ListingAllocationRow>(conn)?
+    };
+
+    for row in committed {
+        let held = row
+            .locked
+            .to_u64()
+            .ok_or_else(|| anyhow!("Locked amount out of range for {}", row.id))?;
+        unlock_asset(app_config, conn, row.wallet_id, listing.purchase_with_asset, held).await?;
+
+        use crate::schema::listing_allocations::dsl::*;
+        diesel::update(listing_allocations.find(row.id))
+            .set((
+                status.eq(AllocationStatus::Refunded),
+                refunded.eq(row.locked.clone()),
+                locked.eq(BigDecimal::from(0)),
+            ))
+            .execute(conn)?;
+
+        emit_listing_event(
+            app_config,
+            listing.id,
+            "listing:refund",
+            &ListingRefundEvent {
+                listing_id: listing.id,
+                wallet_id: row.wallet_id,
+                amount: row.locked.to_string(),
+                status: RefundStatus::Refunded,
+            },
+        )
+        .await;
+    }
+
+    Ok(())
+}
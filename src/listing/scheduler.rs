@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::listing::allocation::allocate_before_close;
+use crate::listing::db_types::{ListingLifecycle, ListingStatus};
+use crate::listing::events::{emit_lifecycle_change, emit_status_change};
+use crate::listing::lifecycle::{close_listing, go_live};
+use crate::listing::secondary_market::create_market_after_close;
+use crate::utils::app_config::AppConfig;
+
+/// Seconds between schedule checks, from LISTING_SCHEDULER_INTERVAL_SECS.
+fn poll_interval() -> Duration {
+    let secs = std::env::var("LISTING_SCHEDULER_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(30);
+    Duration::from_secs(secs)
+}
+
+/// Open approved listings whose window has started and close live listings
+/// whose window has ended. Listings without a window are left alone.
+pub async fn run_once(app_config: &AppConfig) -> Result<()> {
+    let mut conn = app_config.pool.get()?;
+    let wallet = &app_config.wallet;
+    let now = Utc::now().naive_utc();
+
+    let (to_open, to_close) = {
+        use crate::schema::cradlenativelistings::dsl::*;
+
+        let to_open = cradlenativelistings
+            .filter(lifecycle.eq(ListingLifecycle::Approved))
+            .filter(starts_at.le(now))
+            .filter(ends_at.is_null().or(ends_at.gt(now)))
+            .select(id)
+            .get_results::<Uuid>(&mut conn)?;
+
+        let to_close = cradlenativelistings
+            .filter(lifecycle.eq(ListingLifecycle::Live))
+            .filter(ends_at.le(now))
+            .select(id)
+            .get_results::<Uuid>(&mut conn)?;
+
+        (to_open, to_close)
+    };
+
+    for listing_id in to_open {
+        match go_live(&mut conn, wallet, listing_id).await {
+            Ok(()) => {
+                println!("Scheduler opened listing {}", listing_id);
+                emit_lifecycle_change(app_config, listing_id, ListingLifecycle::Live).await;
+                emit_status_change(app_config, listing_id, ListingStatus::Open).await;
+            }
+            Err(e) => println!("Scheduler failed to open listing {}: {:?}", listing_id, e),
+        }
+    }
+
+    for listing_id in to_close {
+        let mut config = app_config.clone();
+        if let Err(e) = allocate_before_close(&mut config, &mut conn, listing_id).await {
+            println!("Scheduler failed to allocate listing {}: {:?}", listing_id, e);
+            continue;
+        }
+
+        match close_listing(&mut conn, wallet, listing_id).await {
+            Ok(()) => {
+                println!("Scheduler closed listing {}", listing_id);
+                emit_lifecycle_change(app_config, listing_id, ListingLifecycle::Closed).await;
+                emit_status_change(app_config, listing_id, ListingStatus::Closed).await;
+                create_market_after_close(&mut config, &mut conn, listing_id).await;
+            }
+            Err(e) => println!("Scheduler failed to close listing {}: {:?}", listing_id, e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Background task polling for listings due to open or close.
+pub async fn run_scheduler(app_config: AppConfig) {
+    let mut ticker = tokio::time::interval(poll_interval());
+    loop {
+        ticker.tick().await;
+        if let Err(e) = run_once(&app_config).await {
+            println!("Listing scheduler error: {:?}", e);
+        }
+    }
+}
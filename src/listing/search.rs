@@ -0,0 +1,217 @@
+use anyhow::{Result, anyhow};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDateTime};
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::listing::db_types::{CradleNativeListingRow, ListingLifecycle, ListingStatus};
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 200;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ListingSort {
+    #[default]
+    Newest,
+    Oldest,
+    PriceAsc,
+    PriceDesc,
+    Name,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ListingSearchParams {
+    pub company: Option<Uuid>,
+    pub listed_asset: Option<Uuid>,
+    pub purchase_asset: Option<Uuid>,
+    pub status: Option<ListingStatus>,
+    pub lifecycle: Option<ListingLifecycle>,
+    pub min_price: Option<BigDecimal>,
+    pub max_price: Option<BigDecimal>,
+    /// Case-insensitive match against name and description
+    pub q: Option<String>,
+    pub sort: Option<ListingSort>,
+    pub limit: Option<i64>,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ListingPage {
+    pub listings: Vec<CradleNativeListingRow>,
+    /// Pass back as `cursor` for the next page; null on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Position after the last row of a page: the sort key and the row id, which
+/// breaks ties. Hex-encoded so clients treat it as opaque.
+struct Cursor {
+    key: String,
+    id: Uuid,
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        hex::encode(format!("{}|{}", self.key, self.id))
+    }
+
+    fn decode(raw: &str) -> Result<Self> {
+        let bytes = hex::decode(raw).map_err(|_| anyhow!("Malformed cursor"))?;
+        let text = String::from_utf8(bytes).map_err(|_| anyhow!("Malformed cursor"))?;
+        let (key, row_id) = text.rsplit_once('|').ok_or_else(|| anyhow!("Malformed cursor"))?;
+        Ok(Self {
+            key: key.to_string(),
+            id: Uuid::parse_str(row_id).map_err(|_| anyhow!("Malformed cursor"))?,
+        })
+    }
+
+    fn for_row(sort: ListingSort, row: &CradleNativeListingRow) -> Self {
+        let key = match sort {
+            ListingSort::Newest | ListingSort::Oldest => {
+                row.created_at.and_utc().timestamp_micros().to_string()
+            }
+            ListingSort::PriceAsc | ListingSort::PriceDesc => row.purchase_price.to_string(),
+            ListingSort::Name => row.name.clone(),
+        };
+        Self { key, id: row.id }
+    }
+
+    fn timestamp(&self) -> Result<NaiveDateTime> {
+        let micros = self.key.parse::<i64>().map_err(|_| anyhow!("Malformed cursor"))?;
+        DateTime::from_timestamp_micros(micros)
+            .map(|t| t.naive_utc())
+            .ok_or_else(|| anyhow!("Malformed cursor"))
+    }
+
+    fn price(&self) -> Result<BigDecimal> {
+        BigDecimal::from_str(&self.key).map_err(|_| anyhow!("Malformed cursor"))
+    }
+}
+
+fn like_pattern(term: &str) -> String {
+    let escaped = term
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// Filtered, sorted page of listings using keyset pagination, so pages stay
+/// stable while new listings are created.
+pub fn search_listings(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    params: ListingSearchParams,
+) -> Result<ListingPage> {
+    use crate::schema::cradlenativelistings::dsl::*;
+
+    let sort = params.sort.unwrap_or_default();
+    let page_size = params.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let after = params.cursor.as_deref().map(Cursor::decode).transpose()?;
+
+    let mut query = cradlenativelistings.into_boxed();
+
+    if let Some(value) = params.company {
+        query = query.filter(company.eq(value));
+    }
+    if let Some(value) = params.listed_asset {
+        query = query.filter(listed_asset.eq(value));
+    }
+    if let Some(value) = params.purchase_asset {
+        query = query.filter(purchase_with_asset.eq(value));
+    }
+    if let Some(value) = params.status {
+        query = query.filter(status.eq(value));
+    }
+    if let Some(value) = params.lifecycle {
+        query = query.filter(lifecycle.eq(value));
+    }
+    if let Some(value) = params.min_price {
+        query = query.filter(purchase_price.ge(value));
+    }
+    if let Some(value) = params.max_price {
+        query = query.filter(purchase_price.le(value));
+    }
+    if let Some(term) = params.q.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        let pattern = like_pattern(term);
+        query = query.filter(name.ilike(pattern.clone()).or(description.ilike(pattern)));
+    }
+
+    query = match sort {
+        ListingSort::Newest => {
+            if let Some(c) = &after {
+                let key = c.timestamp()?;
+                query = query.filter(
+                    created_at
+                        .lt(key)
+                        .or(created_at.eq(key).and(id.lt(c.id))),
+                );
+            }
+            query.order((created_at.desc(), id.desc()))
+        }
+        ListingSort::Oldest => {
+            if let Some(c) = &after {
+                let key = c.timestamp()?;
+                query = query.filter(
+                    created_at
+                        .gt(key)
+                        .or(created_at.eq(key).and(id.gt(c.id))),
+                );
+            }
+            query.order((created_at.asc(), id.asc()))
+        }
+        ListingSort::PriceAsc => {
+            if let Some(c) = &after {
+                let key = c.price()?;
+                query = query.filter(
+                    purchase_price
+                        .gt(key.clone())
+                        .or(purchase_price.eq(key).and(id.gt(c.id))),
+                );
+            }
+            query.order((purchase_price.asc(), id.asc()))
+        }
+        ListingSort::PriceDesc => {
+            if let Some(c) = &after {
+                let key = c.price()?;
+                query = query.filter(
+                    purchase_price
+                        .lt(key.clone())
+                        .or(purchase_price.eq(key).and(id.lt(c.id))),
+                );
+            }
+            query.order((purchase_price.desc(), id.desc()))
+        }
+        ListingSort::Name => {
+            if let Some(c) = &after {
+                query = query.filter(
+                    name.gt(c.key.clone())
+                        .or(name.eq(c.key.clone()).and(id.gt(c.id))),
+                );
+            }
+            query.order((name.asc(), id.asc()))
+        }
+    };
+
+    let mut rows = query
+        .limit(page_size + 1)
+        .get_results::<CradleNativeListingRow>(conn)?;
+
+    let next_cursor = if rows.len() as i64 > page_size {
+        rows.truncate(page_size as usize);
+        rows.last().map(|row| Cursor::for_row(sort, row).encode())
+    } else {
+        None
+    };
+
+    Ok(ListingPage {
+        listings: rows,
+        next_cursor,
+    })
+}
@@ -0,0 +1,195 @@
+use anyhow::{Result, anyhow};
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::asset_book::db_types::AssetBookRecord;
+use crate::listing::db_types::{CradleNativeListingRow, ListingLifecycle};
+use crate::listing::events::emit_listing_event;
+use crate::listing::operations::get_listing;
+use crate::listing::refunds::missed_soft_cap;
+use crate::market::config::MarketsConfig;
+use crate::market::db_types::{CreateMarket, MarketRegulation, MarketStatus, MarketType};
+use crate::market::processor_enums::{MarketProcessorInput, MarketProcessorOutput};
+use crate::market_time_series::db_types::{
+    CreateMarketTimeSeriesRecord, DataProviderType, TimeSeriesInterval,
+};
+use crate::utils::app_config::AppConfig;
+use crate::utils::traits::ActionProcessor;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetCreateMarketOnCloseInputArgs {
+    pub listing_id: Uuid,
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ListingMarketEvent {
+    pub listing_id: Uuid,
+    pub market_id: Uuid,
+}
+
+pub fn set_create_market_on_close(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    input: SetCreateMarketOnCloseInputArgs,
+) -> Result<()> {
+    use crate::schema::cradlenativelistings::dsl::*;
+
+    diesel::update(cradlenativelistings.find(input.listing_id))
+        .set(create_market_on_close.eq(input.enabled))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+fn get_asset(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    asset_id: Uuid,
+) -> Result<AssetBookRecord> {
+    use crate::schema::asset_book::dsl::*;
+
+    Ok(asset_book
+        .filter(id.eq(asset_id))
+        .get_result::<AssetBookRecord>(conn)?)
+}
+
+/// Seed the new market's daily series with the listing price so charts and
+/// price lookups have a reference before the first trade.
+fn seed_reference_price(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: &CradleNativeListingRow,
+    market: Uuid,
+) -> Result<()> {
+    use crate::schema::markets_time_series::dsl::*;
+
+    let now = Utc::now().naive_utc();
+    let price = listing.purchase_price.clone();
+
+    diesel::insert_into(markets_time_series)
+        .values(CreateMarketTimeSeriesRecord {
+            market_id: market,
+            asset: listing.listed_asset,
+            open: price.clone(),
+            high: price.clone(),
+            low: price.clone(),
+            close: price,
+            volume: 0.into(),
+            start_time: now,
+            end_time: now + Duration::days(1),
+            interval: Some(TimeSeriesInterval::OneDay),
+            data_provider_type: Some(DataProviderType::Exchange),
+            data_provider: Some(format!("listing:{}", listing.id)),
+        })
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Create a spot market pairing the listed asset with the purchase asset,
+/// through the market processor. Only for closed listings that met their
+/// soft cap; does nothing if the listing already has one.
+pub async fn create_secondary_market(
+    app_config: &mut AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing_id: Uuid,
+) -> Result<Uuid> {
+    let listing = get_listing(conn, listing_id).await?;
+
+    if let Some(existing) = listing.secondary_market {
+        return Ok(existing);
+    }
+    match listing.lifecycle {
+        ListingLifecycle::Closed | ListingLifecycle::Settled => {}
+        _ => {
+            return Err(anyhow!(
+                "Listing {} is {:?}; a market is only opened once it has closed",
+                listing.id,
+                listing.lifecycle
+            ));
+        }
+    }
+    if missed_soft_cap(conn, &listing)? {
+        return Err(anyhow!("Listing {} missed its soft cap", listing.id));
+    }
+
+    let listed = get_asset(conn, listing.listed_asset)?;
+    let paying = get_asset(conn, listing.purchase_with_asset)?;
+
+    let regulation = if listing.whitelist_enabled {
+        MarketRegulation::Regulated
+    } else {
+        MarketRegulation::Unregulated
+    };
+
+    let output = MarketProcessorInput::CreateMarket(CreateMarket {
+        name: format!("{}/{}", listed.symbol, paying.symbol),
+        description: Some(format!("Secondary market for {}", listing.name)),
+        icon: listed.icon.clone(),
+        asset_one: listing.listed_asset,
+        asset_two: listing.purchase_with_asset,
+        market_type: Some(MarketType::Spot),
+        market_status: Some(MarketStatus::Active),
+        market_regulation: Some(regulation),
+        price_precision: None,
+        quantity_precision: None,
+        display_symbol: None,
+        symbol: None,
+    })
+    .process(app_config, &mut MarketsConfig {}, Some(conn))
+    .await?;
+
+    let MarketProcessorOutput::CreateMarket(market) = output else {
+        return Err(anyhow!("Unexpected market processor output"));
+    };
+
+    {
+        use crate::schema::cradlenativelistings::dsl::*;
+        diesel::update(cradlenativelistings.find(listing.id))
+            .set(secondary_market.eq(Some(market)))
+            .execute(conn)?;
+    }
+
+    seed_reference_price(conn, &listing, market)?;
+
+    emit_listing_event(
+        app_config,
+        listing.id,
+        "listing:market",
+        &ListingMarketEvent {
+            listing_id: listing.id,
+            market_id: market,
+        },
+    )
+    .await;
+
+    Ok(market)
+}
+
+/// Close-path hook: opens the market for listings that asked for one. Errors
+/// are logged rather than returned since the listing has already closed.
+pub async fn create_market_after_close(
+    app_config: &mut AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing_id: Uuid,
+) {
+    let wanted = match get_listing(conn, listing_id).await {
+        Ok(listing) => listing.create_market_on_close,
+        Err(e) => {
+            println!("Failed to load listing {} after close: {:?}", listing_id, e);
+            return;
+        }
+    };
+    if !wanted {
+        return;
+    }
+
+    match create_secondary_market(app_config, conn, listing_id).await {
+        Ok(market) => println!("Opened market {} for listing {}", market, listing_id),
+        Err(e) => println!("Failed to open market for listing {}: {:?}", listing_id, e),
+    }
+}
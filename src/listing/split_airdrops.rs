@@ -0,0 +1,347 @@
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::Utc;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::asset_book::operations::{
+    airdrop_asset, get_asset, get_wallet, mint_asset, token_balance, token_supply,
+};
+use crate::listing::db_types::{
+    CreateSplitAirdrop, CreateSplitAirdropJob, SplitAirdropJobRow, SplitAirdropRow,
+    SplitAirdropStatus,
+};
+use crate::listing::operations::ListingHolding;
+use crate::order_book::operations::can_execute_onchain;
+use crate::utils::app_config::AppConfig;
+use crate::utils::commons::{DbConn, TaskWallet};
+
+/// How long a claimed job is left to its worker before another may pick it up.
+const LEASE_SECS: i64 = 300;
+
+/// Seconds between worker passes, from SPLIT_AIRDROP_INTERVAL_SECS.
+fn poll_interval() -> Duration {
+    let secs = std::env::var("SPLIT_AIRDROP_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(60);
+    Duration::from_secs(secs)
+}
+
+/// 30s after the first failure, doubling up to an hour.
+fn retry_delay(attempts: i32) -> chrono::Duration {
+    let exp = attempts.saturating_sub(1).clamp(0, 7) as u32;
+    chrono::Duration::seconds((30i64 * 2i64.pow(exp)).min(3600))
+}
+
+/// Queue the extra units a split owes each holder. Runs inside the split's
+/// transaction, so the split and what it owes commit together. Shortfalls
+/// below one base unit are dropped; returns `None` when nobody is owed
+/// anything.
+pub fn queue_split_airdrops(
+    conn: DbConn<'_>,
+    action: Uuid,
+    asset: Uuid,
+    holdings: &[ListingHolding],
+    ratio: &BigDecimal,
+) -> Result<Option<SplitAirdropJobRow>> {
+    let extra_ratio = ratio - BigDecimal::from(1);
+    let owed = holdings
+        .iter()
+        .filter_map(|holding| {
+            let extra = (&holding.amount * &extra_ratio).with_scale(0);
+            extra
+                .to_i64()
+                .filter(|units| *units > 0)
+                .map(|units| (holding.wallet_id, units))
+        })
+        .collect::<Vec<_>>();
+    if owed.is_empty() {
+        return Ok(None);
+    }
+
+    let job = {
+        use crate::schema::split_airdrop_jobs::dsl::*;
+
+        diesel::insert_into(split_airdrop_jobs)
+            .values(CreateSplitAirdropJob {
+                action_id: action,
+                asset_id: asset,
+                units: owed.iter().map(|(_, units)| units).sum(),
+            })
+            .get_result::<SplitAirdropJobRow>(conn)?
+    };
+
+    {
+        use crate::schema::split_airdrops::dsl::*;
+
+        diesel::insert_into(split_airdrops)
+            .values(
+                owed.into_iter()
+                    .map(|(wallet, units_owed)| CreateSplitAirdrop {
+                        job_id: job.id,
+                        wallet_id: wallet,
+                        units: units_owed,
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .execute(conn)?;
+    }
+
+    Ok(Some(job))
+}
+
+/// Every holder's airdrop for a split job.
+pub fn get_split_airdrops(conn: DbConn<'_>, job: Uuid) -> Result<Vec<SplitAirdropRow>> {
+    use crate::schema::split_airdrops::dsl::*;
+
+    Ok(split_airdrops
+        .filter(job_id.eq(job))
+        .order(created_at.asc())
+        .get_results::<SplitAirdropRow>(conn)?)
+}
+
+/// Take a due job, marking it running until its lease runs out so no other
+/// worker runs it at the same time. A running job whose lease ran out is
+/// taken over, as its worker is gone.
+fn claim(conn: DbConn<'_>, job: Uuid) -> Result<Option<SplitAirdropJobRow>> {
+    use crate::schema::split_airdrop_jobs::dsl::*;
+
+    let now = Utc::now().naive_utc();
+    Ok(diesel::update(split_airdrop_jobs)
+        .filter(id.eq(job))
+        .filter(status.eq_any(vec![
+            SplitAirdropStatus::Pending,
+            SplitAirdropStatus::Running,
+        ]))
+        .filter(next_attempt_at.le(now))
+        .set((
+            status.eq(SplitAirdropStatus::Running),
+            attempts.eq(attempts + 1),
+            next_attempt_at.eq(now + chrono::Duration::seconds(LEASE_SECS)),
+            updated_at.eq(now),
+        ))
+        .get_result::<SplitAirdropJobRow>(conn)
+        .optional()?)
+}
+
+/// End the attempt on a job this worker holds.
+fn release(
+    conn: DbConn<'_>,
+    job: Uuid,
+    outcome: SplitAirdropStatus,
+    error: Option<String>,
+    retry_at: Option<chrono::NaiveDateTime>,
+) -> Result<SplitAirdropJobRow> {
+    use crate::schema::split_airdrop_jobs::dsl::*;
+
+    let now = Utc::now().naive_utc();
+    Ok(diesel::update(split_airdrop_jobs)
+        .filter(id.eq(job))
+        .filter(status.eq(SplitAirdropStatus::Running))
+        .set((
+            status.eq(outcome),
+            last_error.eq(error),
+            next_attempt_at.eq(retry_at.unwrap_or(now)),
+            updated_at.eq(now),
+        ))
+        .get_result::<SplitAirdropJobRow>(conn)?)
+}
+
+/// Mint the job's total, noting the token's supply first so a retry can
+/// tell whether the mint went through: it counts as done if the supply grew
+/// by at least the total, mints again if the supply hasn't moved, and
+/// otherwise fails the attempt without minting.
+async fn mint_once(
+    wallet: TaskWallet<'_>,
+    conn: DbConn<'_>,
+    job: &SplitAirdropJobRow,
+) -> Result<()> {
+    use crate::schema::split_airdrop_jobs::dsl::*;
+
+    let asset = get_asset(conn, job.asset_id).await?;
+    let supply = token_supply(wallet, &asset).await?;
+    let total = job.units as u64;
+    let minted = match job.mint_supply_before.map(|s| s as u64) {
+        Some(before) if supply >= before + total => true,
+        Some(before) if supply != before => {
+            return Err(anyhow!(
+                "Can't tell whether the mint went through: the token's supply moved from {} to {}",
+                before,
+                supply
+            ));
+        }
+        _ => false,
+    };
+
+    if !minted {
+        diesel::update(split_airdrop_jobs.find(job.id))
+            .set(mint_supply_before.eq(Some(supply as i64)))
+            .execute(conn)?;
+        mint_asset(conn, wallet, job.asset_id, total).await?;
+    }
+    diesel::update(split_airdrop_jobs.find(job.id))
+        .set(minted_at.eq(Some(Utc::now().naive_utc())))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Send one holder their units, noting their balance first. A retry that
+/// finds a balance recorded but no airdrop stamped checks the wallet instead
+/// of sending again, the same way the mint checks the supply.
+async fn airdrop_once(
+    wallet: TaskWallet<'_>,
+    conn: DbConn<'_>,
+    job: &SplitAirdropJobRow,
+    row: &SplitAirdropRow,
+) -> Result<()> {
+    use crate::schema::split_airdrops::dsl::*;
+
+    let asset = get_asset(conn, job.asset_id).await?;
+    let account = get_wallet(conn, row.wallet_id).await?;
+    let balance = token_balance(wallet, &account, &asset).await?;
+    let owed = row.units as u64;
+    let landed = match row.balance_before.map(|b| b as u64) {
+        Some(before) if balance >= before + owed => true,
+        Some(before) if balance != before => {
+            return Err(anyhow!(
+                "Can't tell whether the airdrop went out: the wallet's balance moved from {} to {}",
+                before,
+                balance
+            ));
+        }
+        _ => false,
+    };
+
+    let tx = if landed {
+        None
+    } else {
+        diesel::update(split_airdrops.find(row.id))
+            .set(balance_before.eq(Some(balance as i64)))
+            .execute(conn)?;
+        Some(airdrop_asset(conn, wallet, job.asset_id, row.wallet_id, owed).await?)
+    };
+    diesel::update(split_airdrops.find(row.id))
+        .set((
+            airdropped_at.eq(Some(Utc::now().naive_utc())),
+            airdrop_tx.eq(tx),
+            last_error.eq(None::<String>),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Mint the total if it hasn't been, then send every holder still owed
+/// their units. A holder whose airdrop fails keeps the error and doesn't
+/// stop the others; the attempt fails if any did.
+async fn run_steps(
+    wallet: TaskWallet<'_>,
+    conn: DbConn<'_>,
+    job: &SplitAirdropJobRow,
+) -> Result<()> {
+    if job.minted_at.is_none() {
+        mint_once(wallet, conn, job).await?;
+    }
+
+    let mut failed = 0;
+    for row in get_split_airdrops(conn, job.id)? {
+        if row.airdropped_at.is_some() {
+            continue;
+        }
+        if let Err(e) = airdrop_once(wallet, conn, job, &row).await {
+            use crate::schema::split_airdrops::dsl::*;
+
+            failed += 1;
+            diesel::update(split_airdrops.find(row.id))
+                .set(last_error.eq(Some(e.to_string())))
+                .execute(conn)?;
+        }
+    }
+
+    if failed > 0 {
+        return Err(anyhow!("{} holder airdrops failed", failed));
+    }
+    Ok(())
+}
+
+/// Attempt a due job. A failed attempt is scheduled again with backoff and
+/// picks up where it stopped; a split's holders are retried until every one
+/// of them has their units.
+pub async fn run_job(
+    wallet: TaskWallet<'_>,
+    conn: DbConn<'_>,
+    job_id: Uuid,
+) -> Result<Option<SplitAirdropJobRow>> {
+    let Some(job) = claim(conn, job_id)? else {
+        return Ok(None);
+    };
+
+    let updated = match run_steps(wallet, conn, &job).await {
+        Ok(()) => release(conn, job.id, SplitAirdropStatus::Completed, None, None)?,
+        Err(e) => {
+            let retry_at = Utc::now().naive_utc() + retry_delay(job.attempts);
+            release(
+                conn,
+                job.id,
+                SplitAirdropStatus::Pending,
+                Some(e.to_string()),
+                Some(retry_at),
+            )?
+        }
+    };
+
+    Ok(Some(updated))
+}
+
+/// Retry every split airdrop job that is due.
+pub async fn run_once(app_config: &AppConfig) -> Result<()> {
+    let mut conn = app_config.pool.get()?;
+
+    let due = {
+        use crate::schema::split_airdrop_jobs::dsl::*;
+
+        // running jobs that are due lost their worker before the lease ran out
+        split_airdrop_jobs
+            .filter(status.eq_any(vec![
+                SplitAirdropStatus::Pending,
+                SplitAirdropStatus::Running,
+            ]))
+            .filter(next_attempt_at.le(Utc::now().naive_utc()))
+            .order(next_attempt_at.asc())
+            .limit(20)
+            .select(id)
+            .get_results::<Uuid>(&mut conn)?
+    };
+
+    for job in due {
+        match run_job(&app_config.wallet, &mut conn, job).await {
+            Ok(Some(row)) if row.status == SplitAirdropStatus::Pending => println!(
+                "Split airdrop {} attempt {} failed: {:?}",
+                row.id, row.attempts, row.last_error
+            ),
+            Ok(_) => {}
+            Err(e) => println!("Failed to run split airdrop {}: {:?}", job, e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Background task retrying split airdrops. Does nothing when on-chain
+/// interactions are disabled.
+pub async fn run_worker(app_config: AppConfig) {
+    if !can_execute_onchain() {
+        return;
+    }
+
+    let mut ticker = tokio::time::interval(poll_interval());
+    loop {
+        ticker.tick().await;
+        if let Err(e) = run_once(&app_config).await {
+            println!("Split airdrop error: {:?}", e);
+        }
+    }
+}
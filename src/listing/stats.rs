@@ -0,0 +1,105 @@
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use contract_integrator::utils::functions::cradle_native_listing::ListingStats as OnchainListingStats;
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::accounts_ledger::db_types::AccountLedgerTransactionType;
+use crate::listing::db_types::{ListingLifecycle, ListingStatus};
+use crate::listing::limits::{WalletAllowance, get_wallet_allowance};
+use crate::listing::operations::{get_listing, get_listing_sold_supply, get_listing_stats};
+use crate::listing::tranches::{TrancheStats, get_listing_raised, get_tranche_stats};
+use crate::order_book::operations::can_execute_onchain;
+use crate::utils::wallet::SharedWallet;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ListingStats {
+    pub listing_id: Uuid,
+    pub status: ListingStatus,
+    pub lifecycle: ListingLifecycle,
+    /// Listed-asset units sold, net of returns
+    pub total_purchased: BigDecimal,
+    pub max_supply: BigDecimal,
+    pub remaining_supply: BigDecimal,
+    /// Distinct wallets that have bought at least once
+    pub unique_buyers: i64,
+    /// Purchase asset raised, with tranche sales at their tranche's price
+    pub raised_amount: BigDecimal,
+    pub purchase_asset: Uuid,
+    pub min_purchase: Option<BigDecimal>,
+    pub max_per_wallet: Option<BigDecimal>,
+    /// Present when stats are requested for a specific wallet
+    pub allowance: Option<WalletAllowance>,
+    /// Per-tranche figures; empty for listings sold at a single price
+    pub tranches: Vec<TrancheStats>,
+    /// Figures reported by the listing contract; absent when on-chain
+    /// interactions are disabled or the call fails
+    pub onchain: Option<OnchainListingStats>,
+}
+
+fn get_unique_buyers(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listed_asset: Uuid,
+) -> Result<i64> {
+    use crate::schema::accountassetsledger::dsl::*;
+
+    Ok(accountassetsledger
+        .filter(asset.eq(listed_asset))
+        .filter(transaction_type.eq(AccountLedgerTransactionType::BuyListed))
+        .select(diesel::dsl::count_distinct(to_address))
+        .first::<i64>(conn)?)
+}
+
+/// Listing stats from the ledger, with the contract's own view attached when
+/// it can be read.
+pub async fn get_listing_stats_summary(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    wallet: &SharedWallet,
+    listing_id: Uuid,
+    wallet_id: Option<Uuid>,
+) -> Result<ListingStats> {
+    let listing = get_listing(conn, listing_id).await?;
+    let sold = get_listing_sold_supply(conn, &listing)?;
+    let unique_buyers = get_unique_buyers(conn, listing.listed_asset)?;
+    let raised_amount = get_listing_raised(conn, &listing, &sold)?;
+    let tranches = get_tranche_stats(conn, &listing)?;
+
+    let allowance = match wallet_id {
+        Some(w) => Some(get_wallet_allowance(conn, &listing, w)?),
+        None => None,
+    };
+
+    let onchain = if can_execute_onchain() {
+        match get_listing_stats(conn, wallet, listing.id).await {
+            Ok(stats) => Some(stats),
+            Err(e) => {
+                println!("Failed to read on-chain stats for listing {}: {:?}", listing.id, e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    Ok(ListingStats {
+        listing_id: listing.id,
+        status: listing.status,
+        lifecycle: listing.lifecycle,
+        remaining_supply: &listing.max_supply - &sold,
+        raised_amount,
+        total_purchased: sold,
+        max_supply: listing.max_supply,
+        unique_buyers,
+        purchase_asset: listing.purchase_with_asset,
+        min_purchase: listing.min_purchase,
+        max_per_wallet: listing.max_per_wallet,
+        allowance,
+        tranches,
+        onchain,
+    })
+}
@@ -0,0 +1,302 @@
+use anyhow::{Result, anyhow};
+use bigdecimal::BigDecimal;
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::listing::db_types::{
+    CradleNativeListingRow, CreateListingTranche, CreateListingTranchePurchase,
+    ListingAllocationMode, ListingLifecycle, ListingTrancheRow, TrancheMode,
+};
+use crate::listing::operations::get_listing;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TrancheInput {
+    pub name: String,
+    pub price: BigDecimal,
+    pub supply: BigDecimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetTranchesInputArgs {
+    pub listing_id: Uuid,
+    pub mode: TrancheMode,
+    /// In selling order; an empty list removes the listing's tranches
+    pub tranches: Vec<TrancheInput>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TrancheStats {
+    pub tranche: ListingTrancheRow,
+    pub remaining: BigDecimal,
+    /// `sold` at the tranche price
+    pub raised: BigDecimal,
+    pub buyers: i64,
+    /// Whether purchases currently go to this tranche
+    pub active: bool,
+}
+
+/// Replace a listing's tranches. Only before it goes live, and not for
+/// pro-rata listings, whose single allocation pass has no tranche order.
+pub async fn set_tranches(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    input: SetTranchesInputArgs,
+) -> Result<Vec<ListingTrancheRow>> {
+    let listing = get_listing(conn, input.listing_id).await?;
+
+    if !matches!(
+        listing.lifecycle,
+        ListingLifecycle::Draft | ListingLifecycle::Submitted | ListingLifecycle::Approved
+    ) {
+        return Err(anyhow!(
+            "Tranches of listing {} can't change once it has gone live",
+            listing.id
+        ));
+    }
+    if listing.allocation_mode == ListingAllocationMode::ProRata && !input.tranches.is_empty() {
+        return Err(anyhow!("Listing {} allocates pro-rata and can't use tranches", listing.id));
+    }
+
+    let zero = BigDecimal::from(0);
+    if input.tranches.iter().any(|t| t.supply <= zero) {
+        return Err(anyhow!("Tranche supply must be positive"));
+    }
+    // purchases are settled on-chain at the price the listing contract was
+    // created with, and the contract has no call to change it
+    if let Some(t) = input
+        .tranches
+        .iter()
+        .find(|t| t.price != listing.purchase_price)
+    {
+        return Err(anyhow!(
+            "Tranche {} is priced at {}, but listing {} sells at {} on-chain",
+            t.name,
+            t.price,
+            listing.id,
+            listing.purchase_price
+        ));
+    }
+    let total: BigDecimal = input.tranches.iter().map(|t| &t.supply).sum();
+    if total > listing.max_supply {
+        return Err(anyhow!(
+            "Tranches add up to {}, above the listing's max supply of {}",
+            total,
+            listing.max_supply
+        ));
+    }
+
+    let rows = input
+        .tranches
+        .iter()
+        .enumerate()
+        .map(|(index, t)| CreateListingTranche {
+            listing_id: listing.id,
+            position: index as i32,
+            name: t.name.clone(),
+            price: t.price.clone(),
+            supply: t.supply.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    conn.transaction::<_, anyhow::Error, _>(|conn| {
+        {
+            use crate::schema::listing_tranches::dsl::*;
+            diesel::delete(listing_tranches.filter(listing_id.eq(listing.id))).execute(conn)?;
+            diesel::insert_into(listing_tranches).values(&rows).execute(conn)?;
+        }
+
+        {
+            use crate::schema::cradlenativelistings::dsl::*;
+            diesel::update(cradlenativelistings.find(listing.id))
+                .set(tranche_mode.eq(input.mode))
+                .execute(conn)?;
+        }
+        Ok(())
+    })?;
+
+    get_tranches(conn, listing.id)
+}
+
+pub fn get_tranches(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: Uuid,
+) -> Result<Vec<ListingTrancheRow>> {
+    use crate::schema::listing_tranches::dsl::*;
+
+    Ok(listing_tranches
+        .filter(listing_id.eq(listing))
+        .order(position.asc())
+        .get_results::<ListingTrancheRow>(conn)?)
+}
+
+fn first_open(tranches: &[ListingTrancheRow]) -> Option<&ListingTrancheRow> {
+    tranches.iter().find(|t| t.sold < t.supply)
+}
+
+/// The tranche a purchase of `amount` goes to, or `None` for listings
+/// without tranches. Sequential listings sell from the first tranche with
+/// supply left; parallel ones need the buyer to name one. A purchase never
+/// spans two tranches.
+pub fn select_tranche(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: &CradleNativeListingRow,
+    requested: Option<Uuid>,
+    amount: &BigDecimal,
+) -> Result<Option<ListingTrancheRow>> {
+    let tranches = get_tranches(conn, listing.id)?;
+    if tranches.is_empty() {
+        return Ok(None);
+    }
+
+    let tranche = match listing.tranche_mode {
+        TrancheMode::Sequential => {
+            let active = first_open(&tranches)
+                .ok_or_else(|| anyhow!("Every tranche of listing {} is sold out", listing.id))?;
+            if requested.is_some_and(|r| r != active.id) {
+                return Err(anyhow!(
+                    "Listing {} is selling tranche {} ({})",
+                    listing.id,
+                    active.id,
+                    active.name
+                ));
+            }
+            active
+        }
+        TrancheMode::Parallel => {
+            let requested = requested.ok_or_else(|| {
+                anyhow!("Listing {} sells tranches in parallel; pick a tranche", listing.id)
+            })?;
+            tranches
+                .iter()
+                .find(|t| t.id == requested)
+                .ok_or_else(|| anyhow!("Tranche {} is not part of listing {}", requested, listing.id))?
+        }
+    };
+
+    let remaining = &tranche.supply - &tranche.sold;
+    if *amount > remaining {
+        return Err(anyhow!(
+            "Tranche {} ({}) has {} left, less than the {} requested",
+            tranche.id,
+            tranche.name,
+            remaining,
+            amount
+        ));
+    }
+
+    Ok(Some(tranche.clone()))
+}
+
+/// Take `amount` of a tranche's supply ahead of the purchase, so concurrent
+/// buyers can't oversell it. Released again if the purchase fails.
+pub fn reserve_tranche(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    tranche: Uuid,
+    amount: &BigDecimal,
+) -> Result<()> {
+    use crate::schema::listing_tranches::dsl::*;
+
+    let updated = diesel::update(listing_tranches)
+        .filter(id.eq(tranche))
+        .filter((sold + amount.clone()).le(supply))
+        .set(sold.eq(sold + amount.clone()))
+        .execute(conn)?;
+
+    if updated == 0 {
+        return Err(anyhow!("Tranche {} no longer has {} left", tranche, amount));
+    }
+    Ok(())
+}
+
+pub fn release_tranche(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    tranche: Uuid,
+    amount: &BigDecimal,
+) -> Result<()> {
+    use crate::schema::listing_tranches::dsl::*;
+
+    diesel::update(listing_tranches.find(tranche))
+        .set(sold.eq(sold - amount.clone()))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Record a completed purchase against its tranche.
+pub fn record_tranche_purchase(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    tranche: &ListingTrancheRow,
+    wallet: Uuid,
+    bought: &BigDecimal,
+    ledger_entry: Uuid,
+) -> Result<()> {
+    use crate::schema::listing_tranche_purchases::dsl::*;
+
+    diesel::insert_into(listing_tranche_purchases)
+        .values(CreateListingTranchePurchase {
+            tranche_id: tranche.id,
+            wallet_id: wallet,
+            amount: bought.clone(),
+            price: tranche.price.clone(),
+            transaction_id: Some(ledger_entry),
+        })
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Purchase asset raised by a listing that has sold `sold` net. Tranche sales
+/// count at their tranche's price; anything else, including returns, at the
+/// listing's current price.
+pub fn get_listing_raised(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: &CradleNativeListingRow,
+    sold: &BigDecimal,
+) -> Result<BigDecimal> {
+    let tranches = get_tranches(conn, listing.id)?;
+
+    let tranche_sold: BigDecimal = tranches.iter().map(|t| &t.sold).sum();
+    let tranche_raised: BigDecimal = tranches.iter().map(|t| &t.sold * &t.price).sum();
+
+    Ok(tranche_raised + (sold - tranche_sold) * &listing.purchase_price)
+}
+
+pub fn get_tranche_stats(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: &CradleNativeListingRow,
+) -> Result<Vec<TrancheStats>> {
+    let tranches = get_tranches(conn, listing.id)?;
+    let active_id = match listing.tranche_mode {
+        TrancheMode::Sequential => first_open(&tranches).map(|t| t.id),
+        TrancheMode::Parallel => None,
+    };
+
+    let mut stats = Vec::with_capacity(tranches.len());
+    for tranche in tranches {
+        let buyers = {
+            use crate::schema::listing_tranche_purchases::dsl::*;
+
+            listing_tranche_purchases
+                .filter(tranche_id.eq(tranche.id))
+                .select(diesel::dsl::count_distinct(wallet_id))
+                .first::<i64>(conn)?
+        };
+
+        let active = match listing.tranche_mode {
+            TrancheMode::Sequential => active_id == Some(tranche.id),
+            TrancheMode::Parallel => tranche.sold < tranche.supply,
+        };
+
+        stats.push(TrancheStats {
+            remaining: &tranche.supply - &tranche.sold,
+            raised: &tranche.sold * &tranche.price,
+            buyers,
+            active,
+            tranche,
+        });
+    }
+
+    Ok(stats)
+}
@@ -0,0 +1,180 @@
+use anyhow::{Result, anyhow};
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::accounts::db_types::CradleWalletAccountRecord;
+use crate::listing::db_types::{
+    CradleNativeListingRow, CreateListingWhitelistEntry, ListingWhitelistRow,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetWhitelistEnabledInputArgs {
+    pub listing_id: Uuid,
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WhitelistWalletsInputArgs {
+    pub listing_id: Uuid,
+    pub wallets: Vec<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImportWhitelistInputArgs {
+    pub listing_id: Uuid,
+    /// Wallet ids or wallet addresses, separated by newlines or commas
+    pub entries: String,
+}
+
+pub fn set_whitelist_enabled(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    input: SetWhitelistEnabledInputArgs,
+) -> Result<()> {
+    use crate::schema::cradlenativelistings::dsl::*;
+
+    diesel::update(cradlenativelistings.find(input.listing_id))
+        .set(whitelist_enabled.eq(input.enabled))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Add wallets to a listing's whitelist. Wallets already on it are skipped;
+/// returns how many were added.
+pub fn add_to_whitelist(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    input: WhitelistWalletsInputArgs,
+) -> Result<usize> {
+    if input.wallets.is_empty() {
+        return Ok(0);
+    }
+
+    let rows: Vec<CreateListingWhitelistEntry> = input
+        .wallets
+        .iter()
+        .map(|w| CreateListingWhitelistEntry {
+            listing_id: input.listing_id,
+            wallet_id: *w,
+        })
+        .collect();
+
+    use crate::schema::listing_whitelist::dsl::*;
+
+    Ok(diesel::insert_into(listing_whitelist)
+        .values(&rows)
+        .on_conflict((listing_id, wallet_id))
+        .do_nothing()
+        .execute(conn)?)
+}
+
+/// Returns how many of `wallets` were on the whitelist.
+pub fn remove_from_whitelist(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    input: WhitelistWalletsInputArgs,
+) -> Result<usize> {
+    use crate::schema::listing_whitelist::dsl::*;
+
+    Ok(diesel::delete(listing_whitelist)
+        .filter(listing_id.eq(input.listing_id))
+        .filter(wallet_id.eq_any(input.wallets))
+        .execute(conn)?)
+}
+
+/// Bulk add from an exported investor list. Every entry must resolve to a
+/// known wallet, otherwise nothing is imported and the unknown entries are
+/// reported.
+pub fn import_whitelist(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    input: ImportWhitelistInputArgs,
+) -> Result<usize> {
+    let entries: Vec<&str> = input
+        .entries
+        .split(|c: char| c == '\n' || c == ',')
+        .map(|e| e.trim())
+        .filter(|e| !e.is_empty())
+        .collect();
+
+    let (ids, addresses): (Vec<&str>, Vec<&str>) =
+        entries.iter().partition(|e| Uuid::parse_str(e).is_ok());
+    let ids: Vec<Uuid> = ids.iter().filter_map(|e| Uuid::parse_str(e).ok()).collect();
+
+    let wallets = {
+        use crate::schema::cradlewalletaccounts::dsl::*;
+
+        cradlewalletaccounts
+            .filter(id.eq_any(ids.clone()))
+            .or_filter(address.eq_any(addresses.iter().map(|a| a.to_string()).collect::<Vec<_>>()))
+            .get_results::<CradleWalletAccountRecord>(conn)?
+    };
+
+    let unknown: Vec<String> = ids
+        .iter()
+        .filter(|i| !wallets.iter().any(|w| w.id == **i))
+        .map(|i| i.to_string())
+        .chain(
+            addresses
+                .iter()
+                .filter(|a| !wallets.iter().any(|w| w.address == **a))
+                .map(|a| a.to_string()),
+        )
+        .collect();
+
+    if !unknown.is_empty() {
+        return Err(anyhow!("Unknown wallets in whitelist import: {}", unknown.join(", ")));
+    }
+
+    add_to_whitelist(
+        conn,
+        WhitelistWalletsInputArgs {
+            listing_id: input.listing_id,
+            wallets: wallets.into_iter().map(|w| w.id).collect(),
+        },
+    )
+}
+
+pub fn get_whitelist(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: Uuid,
+) -> Result<Vec<ListingWhitelistRow>> {
+    use crate::schema::listing_whitelist::dsl::*;
+
+    Ok(listing_whitelist
+        .filter(listing_id.eq(listing))
+        .order(added_at.asc())
+        .get_results::<ListingWhitelistRow>(conn)?)
+}
+
+/// Error if the listing restricts participation and `wallet` isn't on its list.
+pub fn expect_whitelisted(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: &CradleNativeListingRow,
+    wallet: Uuid,
+) -> Result<()> {
+    if !listing.whitelist_enabled {
+        return Ok(());
+    }
+
+    use crate::schema::listing_whitelist::dsl::*;
+
+    let allowed = diesel::select(diesel::dsl::exists(
+        listing_whitelist
+            .filter(listing_id.eq(listing.id))
+            .filter(wallet_id.eq(wallet)),
+    ))
+    .get_result::<bool>(conn)?;
+
+    if !allowed {
+        return Err(anyhow!(
+            "Wallet {} is not whitelisted for listing {}",
+            wallet,
+            listing.id
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,399 @@
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::accounts::db_types::{AdminOperatorRecord, AdminRole};
+use crate::accounts::operators::{require_approver, require_role};
+use crate::listing::db_types::{
+    CreateWithdrawalApproval, CreateWithdrawalRequest, WithdrawalApprovalRow,
+    WithdrawalRequestRow, WithdrawalStatus,
+};
+use crate::listing::events::emit_listing_event;
+use crate::listing::operations::{
+    WithdrawToBeneficiaryInputArgsBody, get_listing, withdraw_to_beneficiary,
+};
+use crate::utils::app_config::AppConfig;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RequestWithdrawalInputArgs {
+    pub listing_id: Uuid,
+    pub amount: BigDecimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApproveWithdrawalInputArgs {
+    pub request_id: Uuid,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RejectWithdrawalInputArgs {
+    pub request_id: Uuid,
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WithdrawalRequestDetails {
+    pub request: WithdrawalRequestRow,
+    pub approvals: Vec<WithdrawalApprovalRow>,
+}
+
+/// Withdrawals above this amount go through approval, from
+/// LISTING_WITHDRAWAL_APPROVAL_THRESHOLD. Unset means none do.
+pub fn approval_threshold() -> Option<BigDecimal> {
+    std::env::var("LISTING_WITHDRAWAL_APPROVAL_THRESHOLD")
+        .ok()
+        .and_then(|v| BigDecimal::from_str(&v).ok())
+}
+
+/// Approvals a request needs before it runs, from LISTING_WITHDRAWAL_APPROVALS.
+fn required_approvals() -> i32 {
+    std::env::var("LISTING_WITHDRAWAL_APPROVALS")
+        .ok()
+        .and_then(|v| v.parse::<i32>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(2)
+}
+
+/// How far back withdrawals count towards the approval threshold, from
+/// LISTING_WITHDRAWAL_WINDOW_SECS.
+fn threshold_window() -> chrono::Duration {
+    let secs = std::env::var("LISTING_WITHDRAWAL_WINDOW_SECS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(24 * 60 * 60);
+    chrono::Duration::seconds(secs)
+}
+
+/// What a listing has paid out, or is paying out, since `since`.
+fn withdrawn_since(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: Uuid,
+    since: NaiveDateTime,
+) -> Result<BigDecimal> {
+    use crate::schema::withdrawal_requests::dsl::*;
+
+    Ok(withdrawal_requests
+        .filter(listing_id.eq(listing))
+        .filter(status.eq_any([WithdrawalStatus::Approved, WithdrawalStatus::Executed]))
+        .filter(updated_at.ge(since))
+        .select(diesel::dsl::sum(amount))
+        .get_result::<Option<BigDecimal>>(conn)?
+        .unwrap_or_default())
+}
+
+pub fn get_withdrawal_request(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    request: Uuid,
+) -> Result<WithdrawalRequestDetails> {
+    let row = {
+        use crate::schema::withdrawal_requests::dsl::*;
+
+        withdrawal_requests
+            .filter(id.eq(request))
+            .get_result::<WithdrawalRequestRow>(conn)?
+    };
+
+    let approvals = {
+        use crate::schema::withdrawal_approvals::dsl::*;
+
+        withdrawal_approvals
+            .filter(request_id.eq(request))
+            .order(created_at.asc())
+            .get_results::<WithdrawalApprovalRow>(conn)?
+    };
+
+    Ok(WithdrawalRequestDetails {
+        request: row,
+        approvals,
+    })
+}
+
+/// A listing's withdrawal requests, newest first.
+pub fn get_listing_withdrawals(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: Uuid,
+) -> Result<Vec<WithdrawalRequestRow>> {
+    use crate::schema::withdrawal_requests::dsl::*;
+
+    Ok(withdrawal_requests
+        .filter(listing_id.eq(listing))
+        .order(created_at.desc())
+        .get_results::<WithdrawalRequestRow>(conn)?)
+}
+
+/// Open a request to withdraw a listing's proceeds to its beneficiary, on
+/// behalf of `requester`'s account. It needs LISTING_WITHDRAWAL_APPROVALS
+/// distinct approvers to approve it before the contract call runs.
+pub async fn request_withdrawal(
+    app_config: &AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    requester: &AdminOperatorRecord,
+    input: RequestWithdrawalInputArgs,
+) -> Result<WithdrawalRequestRow> {
+    require_role(conn, requester, AdminRole::Operator)?;
+    if input.amount <= BigDecimal::from(0) {
+        return Err(anyhow!("Withdrawal amount must be positive"));
+    }
+    let listing = get_listing(conn, input.listing_id).await?;
+
+    let required = required_approvals();
+    let approvers = {
+        use crate::schema::admin_operators::dsl::*;
+
+        admin_operators
+            .filter(role.eq(AdminRole::Approver))
+            .filter(account_id.ne(requester.account_id))
+            .count()
+            .get_result::<i64>(conn)?
+    };
+    if approvers < required as i64 {
+        return Err(anyhow!(
+            "Withdrawals need {} approvals but only {} operators can approve",
+            required,
+            approvers
+        ));
+    }
+
+    let row = {
+        use crate::schema::withdrawal_requests::dsl::*;
+
+        diesel::insert_into(withdrawal_requests)
+            .values(CreateWithdrawalRequest {
+                listing_id: listing.id,
+                amount: input.amount,
+                requested_by: Some(requester.account_id),
+                required_approvals: required,
+                status: WithdrawalStatus::Pending,
+            })
+            .get_result::<WithdrawalRequestRow>(conn)?
+    };
+
+    emit_listing_event(app_config, listing.id, "listing:withdrawal", &row).await;
+    Ok(row)
+}
+
+fn expect_pending(row: &WithdrawalRequestRow) -> Result<()> {
+    if row.status != WithdrawalStatus::Pending {
+        return Err(anyhow!(
+            "Withdrawal request {} is {:?}, not pending",
+            row.id,
+            row.status
+        ));
+    }
+    Ok(())
+}
+
+/// Pay a listing's proceeds straight to its beneficiary, as long as its
+/// withdrawals over the last LISTING_WITHDRAWAL_WINDOW_SECS, this one
+/// included, stay within LISTING_WITHDRAWAL_APPROVAL_THRESHOLD. Anything
+/// more has to go through a request. The withdrawal is recorded like an
+/// approved request so later ones count it.
+pub async fn withdraw_within_threshold(
+    app_config: &AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    input: WithdrawToBeneficiaryInputArgsBody,
+) -> Result<WithdrawalRequestRow> {
+    if input.amount <= BigDecimal::from(0) {
+        return Err(anyhow!("Withdrawal amount must be positive"));
+    }
+
+    let row = conn.transaction::<_, anyhow::Error, _>(|conn| {
+        // one check at a time per listing, so two withdrawals can't both
+        // fit under the threshold on their own
+        {
+            use crate::schema::cradlenativelistings::dsl::*;
+
+            cradlenativelistings
+                .find(input.listing)
+                .select(id)
+                .for_update()
+                .get_result::<Uuid>(conn)?;
+        }
+
+        if let Some(threshold) = approval_threshold() {
+            let since = Utc::now().naive_utc() - threshold_window();
+            let recent = withdrawn_since(conn, input.listing, since)?;
+            let total = recent + &input.amount;
+            if total > threshold {
+                return Err(anyhow!(
+                    "Withdrawals from listing {} would come to {} over the last {} seconds, above {}; open a withdrawal request instead",
+                    input.listing,
+                    total,
+                    threshold_window().num_seconds(),
+                    threshold
+                ));
+            }
+        }
+
+        use crate::schema::withdrawal_requests::dsl::*;
+
+        Ok(diesel::insert_into(withdrawal_requests)
+            .values(CreateWithdrawalRequest {
+                listing_id: input.listing,
+                amount: input.amount.clone(),
+                requested_by: None,
+                required_approvals: 0,
+                status: WithdrawalStatus::Approved,
+            })
+            .get_result::<WithdrawalRequestRow>(conn)?)
+    })?;
+
+    let row = execute_withdrawal(app_config, conn, row).await?;
+    match row.status {
+        WithdrawalStatus::Executed => Ok(row),
+        _ => Err(anyhow!(
+            "Withdrawal failed: {}",
+            row.error.unwrap_or_default()
+        )),
+    }
+}
+
+/// Record an approval. The approval that completes the quorum runs the
+/// withdrawal; the request ends up `executed`, or `failed` with the error.
+/// Each approver counts once, and the request is locked while the approval
+/// is added and counted so concurrent approvals see each other.
+pub async fn approve_withdrawal(
+    app_config: &AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    approver: &AdminOperatorRecord,
+    input: ApproveWithdrawalInputArgs,
+) -> Result<WithdrawalRequestRow> {
+    require_approver(conn, approver)?;
+
+    let (request, quorum) = conn.transaction::<_, anyhow::Error, _>(|conn| {
+        let request = {
+            use crate::schema::withdrawal_requests::dsl::*;
+
+            withdrawal_requests
+                .find(input.request_id)
+                .for_update()
+                .get_result::<WithdrawalRequestRow>(conn)?
+        };
+        expect_pending(&request)?;
+        if request.requested_by == Some(approver.account_id) {
+            return Err(anyhow!("A withdrawal can't be approved by its requester"));
+        }
+
+        use crate::schema::withdrawal_approvals::dsl::*;
+
+        let inserted = diesel::insert_into(withdrawal_approvals)
+            .values(CreateWithdrawalApproval {
+                request_id: request.id,
+                approver: approver.account_id,
+                operator_id: approver.id,
+            })
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+        if inserted == 0 {
+            return Err(anyhow!(
+                "{} already approved request {}",
+                approver.username,
+                request.id
+            ));
+        }
+
+        let approvals = withdrawal_approvals
+            .filter(request_id.eq(request.id))
+            .count()
+            .get_result::<i64>(conn)?;
+        if approvals < request.required_approvals as i64 {
+            return Ok((request, false));
+        }
+
+        // claimed under the lock, so the request runs once
+        let claimed = {
+            use crate::schema::withdrawal_requests::dsl::*;
+
+            diesel::update(withdrawal_requests.find(request.id))
+                .set((
+                    status.eq(WithdrawalStatus::Approved),
+                    updated_at.eq(Utc::now().naive_utc()),
+                ))
+                .get_result::<WithdrawalRequestRow>(conn)?
+        };
+        Ok((claimed, true))
+    })?;
+
+    if !quorum {
+        emit_listing_event(app_config, request.listing_id, "listing:withdrawal", &request).await;
+        return Ok(request);
+    }
+    execute_withdrawal(app_config, conn, request).await
+}
+
+/// Make the contract call for a request that has been claimed as
+/// `approved`, and record how it went.
+async fn execute_withdrawal(
+    app_config: &AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    request: WithdrawalRequestRow,
+) -> Result<WithdrawalRequestRow> {
+    let result = withdraw_to_beneficiary(
+        conn,
+        &app_config.wallet,
+        WithdrawToBeneficiaryInputArgsBody {
+            amount: request.amount.clone(),
+            listing: request.listing_id,
+        },
+    )
+    .await;
+
+    let (new_status, tx, err) = match result {
+        Ok(tx) => (WithdrawalStatus::Executed, Some(tx), None),
+        Err(e) => (WithdrawalStatus::Failed, None, Some(e.to_string())),
+    };
+
+    let updated = {
+        use crate::schema::withdrawal_requests::dsl::*;
+
+        diesel::update(withdrawal_requests.find(request.id))
+            .set((
+                status.eq(new_status),
+                transaction_id.eq(tx),
+                error.eq(err),
+                updated_at.eq(Utc::now().naive_utc()),
+            ))
+            .get_result::<WithdrawalRequestRow>(conn)?
+    };
+
+    emit_listing_event(app_config, updated.listing_id, "listing:withdrawal", &updated).await;
+    Ok(updated)
+}
+
+/// Turn a pending request down. One rejection is enough.
+pub async fn reject_withdrawal(
+    app_config: &AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    reviewer: &AdminOperatorRecord,
+    input: RejectWithdrawalInputArgs,
+) -> Result<WithdrawalRequestRow> {
+    require_approver(conn, reviewer)?;
+
+    use crate::schema::withdrawal_requests::dsl::*;
+
+    let updated = diesel::update(withdrawal_requests)
+        .filter(id.eq(input.request_id))
+        .filter(status.eq(WithdrawalStatus::Pending))
+        .set((
+            status.eq(WithdrawalStatus::Rejected),
+            reviewed_by.eq(Some(reviewer.account_id)),
+            reason.eq(input.reason),
+            updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .get_result::<WithdrawalRequestRow>(conn)
+        .optional()?
+        .ok_or_else(|| anyhow!("Withdrawal request {} is not pending", input.request_id))?;
+
+    emit_listing_event(app_config, updated.listing_id, "listing:withdrawal", &updated).await;
+    Ok(updated)
+}
@@ -4,24 +4,31 @@ mod action_router;
 mod aggregators;
 pub mod api;
 mod asset_book;
+mod audit_log;
+mod documents;
+mod health;
 mod lending_pool;
 mod listing;
 mod market;
 mod market_time_series;
+mod notifications;
 mod order_book;
+mod price_feeds;
 pub mod ramper;
 pub mod schema;
 mod sockets;
 pub mod utils;
+mod webhooks;
 
 use axum::{
     Router,
+    extract::DefaultBodyLimit,
     middleware::{self, Next},
     response::{IntoResponse, Response},
-    routing::{get, post},
+    routing::{delete, get, post},
 };
 use dotenvy::dotenv;
-use socketioxide::SocketIo;
+use socketioxide::{SocketIo, handler::ConnectHandler};
 use std::env;
 use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
@@ -29,20 +36,51 @@ use tracing_subscriber;
 
 use crate::{
     api::handlers::{
+        documents::{
+            download_document_handler, get_document_handler, get_document_url_handler,
+            get_documents_handler, max_upload_bytes, upload_document_handler,
+        },
         faucet_request::airdrop_request,
-        listings::{get_listing_by_id, get_listings},
-        ramper::{handle_callback, request_payment},
+        listings::{
+            approve_withdrawal_handler, create_withdrawal_request_handler, get_company_by_id,
+            get_distribution_handler, get_listing_allocation, get_listing_by_id,
+            get_listing_corporate_actions,
+            get_listing_distributions_handler, get_listing_holders, get_listing_reconciliation,
+            get_listing_refunds, get_listing_stats_handler, get_listing_tranches,
+            get_listing_whitelist, get_listing_withdrawals_handler, get_listings,
+            get_reconciliation_report_handler, get_wallet_payouts_handler,
+            get_withdrawal_request_handler, reject_withdrawal_handler,
+        },
+        notifications::{get_notifications_handler, mark_notifications_read_handler},
+        price_feeds::{
+            add_oracle_source_handler, disable_oracle_source_handler,
+            get_oracle_aggregate_handler, get_oracle_source_quotes_handler,
+            get_oracle_sources_handler,
+        },
+        ramper::{
+            cancel_ramp_order_handler, create_ramp_quote_handler, get_ramp_fees_handler,
+            get_ramp_fulfillment_handler, get_ramp_order_events_handler, get_ramp_order_handler,
+            get_ramp_receipt_handler, get_ramp_summary_handler, handle_callback,
+            refund_ramp_order_handler, retry_ramp_order_handler, request_offramp, request_payment,
+        },
+        webhooks::{
+            delete_webhook_handler, get_webhook_deliveries_handler, get_webhooks_handler,
+            register_webhook_handler,
+        },
+    },
+    sockets::{
+        namespaces::{require_admin_key, require_api_key},
+        on_accounts_connect, on_admin_connect, on_connect, on_lending_connect, on_markets_connect,
     },
-    sockets::on_connect,
 };
 use api::{
     config::ApiConfig,
     error::ApiError,
     handlers::{
-        accounts::*, assets::*, health, lending_pools::*, markets::*, mutation::*, orders::*,
-        time_series::*,
+        accounts::*, assets::*, health, lending_pools::*, markets::*, metrics::*, mutation::*,
+        orders::*, time_series::*,
     },
-    middleware::auth::validate_auth,
+    middleware::auth::{resolve_operator, validate_auth},
 };
 use utils::app_config::AppConfig;
 
@@ -58,9 +96,22 @@ async fn main() -> anyhow::Result<()> {
         )
         .init();
 
-    let (socket_layer, io) = SocketIo::new_layer();
+    let (socket_layer, io) = SocketIo::builder()
+        .max_buffer_size(sockets::backpressure::socket_buffer_size())
+        .build_layer();
 
     io.ns("/", on_connect);
+    io.ns("/markets", on_markets_connect);
+    io.ns("/accounts", on_accounts_connect.with(require_api_key));
+    io.ns("/lending", on_lending_connect.with(require_api_key));
+    io.ns("/admin", on_admin_connect.with(require_admin_key));
+
+    tokio::spawn(sockets::batching::run_flusher(io.clone()));
+
+    if let Err(e) = sockets::cluster::init().await {
+        tracing::warn!("Socket cluster fan-out unavailable, running single-instance: {}", e);
+    }
+    tokio::spawn(sockets::cluster::run_subscriber(io.clone()));
 
     // Load API configuration
     let api_config = ApiConfig::from_env();
@@ -84,20 +135,56 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::info!("Application configuration loaded successfully");
 
+    // ramps are optional, but a configured ramp has to be valid for its environment
+    if std::env::var("RAMPER_TOKEN").is_ok() {
+        let ramper = ramper::Ramper::from_env()?;
+        ramper.validate()?;
+        tracing::info!(
+            "Ramp provider {} configured for {:?}",
+            ramper.ramper_provider,
+            ramper.ramper_environment
+        );
+    } else {
+        tracing::warn!("RAMPER_TOKEN not set; on- and off-ramping are unavailable");
+    }
+
+    tokio::spawn(listing::scheduler::run_scheduler(app_config.clone()));
+    tokio::spawn(listing::reconciliation::run_reconciler(app_config.clone()));
+    tokio::spawn(webhooks::dispatcher::run_dispatcher(app_config.clone()));
+    tokio::spawn(ramper::reconciler::run_reconciler(app_config.clone()));
+    tokio::spawn(ramper::fulfillment::run_worker(app_config.clone()));
+    tokio::spawn(listing::split_airdrops::run_worker(app_config.clone()));
+    tokio::spawn(price_feeds::publisher::run_publisher(app_config.clone()));
+    tokio::spawn(market::schedule::run_scheduler(app_config.clone()));
+    tokio::spawn(market::stats::run_stats_refresher(app_config.clone()));
+    tokio::spawn(market::status_changes::run_status_changes(app_config.clone()));
+
     // Create authentication middleware that captures the secret key
     let secret_key = api_config.secret_key.clone();
+    let auth_pool = app_config.pool.clone();
 
     // Custom auth middleware
-    let auth_layer = middleware::from_fn(move |req: axum::extract::Request, next: Next| {
+    let auth_layer = middleware::from_fn(move |mut req: axum::extract::Request, next: Next| {
         let secret = secret_key.clone();
+        let pool = auth_pool.clone();
         async move {
             // Skip auth for /health endpoint
             let path = req.uri().path();
             if path == "/health" {
                 return Ok::<Response, ApiError>(next.run(req).await.into_response());
             }
+            // Signed document links carry their own authorisation
+            if path.starts_with("/documents/") && path.ends_with("/download") {
+                return Ok::<Response, ApiError>(next.run(req).await.into_response());
+            }
+            // Provider callbacks are verified by their HMAC signature
+            if path == "/ramp/callback" {
+                return Ok::<Response, ApiError>(next.run(req).await.into_response());
+            }
 
             validate_auth(req.headers(), &secret).await?;
+            // approvals and other per-person actions need to know who's asking
+            resolve_operator(&mut req, pool).await?;
             Ok::<Response, ApiError>(next.run(req).await.into_response())
         }
     });
@@ -106,6 +193,10 @@ async fn main() -> anyhow::Result<()> {
     let router = Router::new()
         // Health check - public endpoint
         .route("/health", get(health::health))
+        // Metrics
+        .route("/metrics", get(metrics))
+        .route("/admin/sockets", get(get_socket_stats))
+        .route("/admin/health", get(get_health_report))
         // Mutation endpoint
         .route("/process", post(process_mutation))
         // Accounts endpoints
@@ -125,7 +216,11 @@ async fn main() -> anyhow::Result<()> {
         .route("/assets/manager/:manager", get(get_asset_by_manager))
         .route("/assets", get(get_assets))
         // Markets endpoints
+        .route("/markets/stats", get(get_markets_stats))
+        .route("/markets/by-symbol/:symbol", get(get_market_by_symbol))
         .route("/markets/:id", get(get_market_by_id))
+        .route("/markets/:id/schedule", get(get_market_schedule))
+        .route("/markets/:id/price-band", get(get_market_price_band))
         .route("/markets", get(get_markets))
         // Orders endpoints
         .route("/orders/:id", get(get_order_by_id))
@@ -137,6 +232,81 @@ async fn main() -> anyhow::Result<()> {
         // listings
         .route("/listings", get(get_listings))
         .route("/listings/:listing_id", get(get_listing_by_id))
+        .route(
+            "/listings/:listing_id/allocations/:wallet_id",
+            get(get_listing_allocation),
+        )
+        .route("/listings/:listing_id/refunds", get(get_listing_refunds))
+        .route(
+            "/listings/:listing_id/reconciliation",
+            get(get_listing_reconciliation),
+        )
+        .route(
+            "/reconciliation/listings",
+            get(get_reconciliation_report_handler),
+        )
+        .route(
+            "/listings/:listing_id/distributions",
+            get(get_listing_distributions_handler),
+        )
+        .route("/listings/:listing_id/whitelist", get(get_listing_whitelist))
+        .route("/listings/:listing_id/tranches", get(get_listing_tranches))
+        .route(
+            "/listings/:listing_id/corporate-actions",
+            get(get_listing_corporate_actions),
+        )
+        .route(
+            "/listings/:listing_id/withdrawals",
+            get(get_listing_withdrawals_handler).post(create_withdrawal_request_handler),
+        )
+        .route("/withdrawals/:request_id", get(get_withdrawal_request_handler))
+        .route(
+            "/withdrawals/:request_id/approve",
+            post(approve_withdrawal_handler),
+        )
+        .route(
+            "/withdrawals/:request_id/reject",
+            post(reject_withdrawal_handler),
+        )
+        .route("/listings/:listing_id/holders", get(get_listing_holders))
+        .route(
+            "/listings/:listing_id/stats",
+            get(get_listing_stats_handler),
+        )
+        .route("/distributions/:distribution_id", get(get_distribution_handler))
+        .route("/companies/:company_id", get(get_company_by_id))
+        .route(
+            "/distributions/payouts/:wallet_id",
+            get(get_wallet_payouts_handler),
+        )
+        // webhooks and notifications
+        .route(
+            "/webhooks",
+            get(get_webhooks_handler).post(register_webhook_handler),
+        )
+        .route("/webhooks/:webhook_id", delete(delete_webhook_handler))
+        .route(
+            "/webhooks/:webhook_id/deliveries",
+            get(get_webhook_deliveries_handler),
+        )
+        .route("/notifications/:wallet_id", get(get_notifications_handler))
+        .route(
+            "/notifications/:wallet_id/read",
+            post(mark_notifications_read_handler),
+        )
+        // documents
+        .route(
+            "/documents",
+            get(get_documents_handler)
+                .post(upload_document_handler)
+                .layer(DefaultBodyLimit::max(max_upload_bytes())),
+        )
+        .route("/documents/:document_id", get(get_document_handler))
+        .route("/documents/:document_id/url", get(get_document_url_handler))
+        .route(
+            "/documents/:document_id/download",
+            get(download_document_handler),
+        )
         // Lending Pool
         .route("/pools", get(get_pools))
         .route("/pools/:id", get(get_pool))
@@ -153,9 +323,57 @@ async fn main() -> anyhow::Result<()> {
         )
         .route("/loan/:loan_id", get(get_repaid_handler))
         .route("/oracle/:pool_id/:asset_id", get(get_oracle_price))
+        .route("/oracle/prices", get(get_oracle_price_history))
+        .route("/oracle-staleness", get(get_oracle_staleness))
+        .route(
+            "/oracle-sources",
+            get(get_oracle_sources_handler).post(add_oracle_source_handler),
+        )
+        .route(
+            "/oracle-sources/:source_id",
+            delete(disable_oracle_source_handler),
+        )
+        .route(
+            "/oracle-sources/:source_id/quotes",
+            get(get_oracle_source_quotes_handler),
+        )
+        .route(
+            "/oracle-aggregate/:pool_id/:asset_id",
+            get(get_oracle_aggregate_handler),
+        )
         // onramp handler
         .route("/onramp-request", post(request_payment))
         .route("/onramp-callback", post(handle_callback))
+        .route("/ramp/callback", post(handle_callback))
+        .route("/offramp-request", post(request_offramp))
+        .route("/ramp/quote", post(create_ramp_quote_handler))
+        .route("/ramp/fees", get(get_ramp_fees_handler))
+        .route("/ramp/orders/:reference", get(get_ramp_order_handler))
+        .route(
+            "/ramp/orders/:reference/events",
+            get(get_ramp_order_events_handler),
+        )
+        .route(
+            "/ramp/orders/:reference/fulfillment",
+            get(get_ramp_fulfillment_handler),
+        )
+        .route(
+            "/ramp/orders/:reference/receipt",
+            get(get_ramp_receipt_handler),
+        )
+        .route(
+            "/ramp/orders/:reference/refund",
+            post(refund_ramp_order_handler),
+        )
+        .route("/admin/ramp/summary", get(get_ramp_summary_handler))
+        .route(
+            "/admin/ramp/orders/:reference/retry",
+            post(retry_ramp_order_handler),
+        )
+        .route(
+            "/admin/ramp/orders/:reference/cancel",
+            post(cancel_ramp_order_handler),
+        )
         // Add middleware layers before state binding
         .layer(TraceLayer::new_for_http())
         .layer(auth_layer)
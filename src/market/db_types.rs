@@ -1,15 +1,18 @@
+use bigdecimal::BigDecimal;
 use chrono::NaiveDateTime;
 use diesel::{Identifiable, Insertable, Queryable};
 use diesel_derive_enum::DbEnum;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use crate::schema::markets as MarketsTable;
+use crate::schema::market_status_schedules as MarketStatusSchedulesTable;
 
 #[derive(DbEnum, Deserialize, Serialize, Debug, Clone)]
 #[ExistingTypePath="crate::schema::sql_types::MarketStatus"]
 #[serde(rename_all = "lowercase")]
 pub enum MarketStatus {
     Active,
+    #[db_rename = "inactive"]
     InActive,
     Suspended
 }
@@ -44,7 +47,21 @@ pub struct MarketRecord {
     pub created_at: NaiveDateTime,
     pub market_type: MarketType,
     pub market_status: MarketStatus,
-    pub market_regulation: MarketRegulation
+    pub market_regulation: MarketRegulation,
+    pub trading_schedule: Option<serde_json::Value>,
+    /// Decimal places prices are quoted in
+    pub price_precision: i32,
+    /// Decimal places of whole base tokens an order can be sized in
+    pub quantity_precision: i32,
+    /// e.g. "cBTC/cUSD"
+    pub display_symbol: String,
+    /// How far, in percent, limit orders on regulated markets may be priced
+    /// from the reference
+    pub price_band_percent: BigDecimal,
+    /// Fixed reference for the band; recent trades are used when unset
+    pub reference_price: Option<BigDecimal>,
+    /// Unique slug API callers can use instead of the id, e.g. "CBTC-CUSD"
+    pub symbol: String
 }
 
 
@@ -59,5 +76,48 @@ pub struct CreateMarket {
     pub asset_two: Uuid,
     pub market_type: Option<MarketType>,
     pub market_status: Option<MarketStatus>,
-    pub market_regulation: Option<MarketRegulation>
+    pub market_regulation: Option<MarketRegulation>,
+    pub price_precision: Option<i32>,
+    /// Defaults to the base asset's decimals
+    pub quantity_precision: Option<i32>,
+    /// Defaults to "BASE/QUOTE" from the asset symbols
+    pub display_symbol: Option<String>,
+    /// Defaults to "BASE-QUOTE" from the asset symbols
+    pub symbol: Option<String>
+}
+
+#[derive(DbEnum, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[ExistingTypePath="crate::schema::sql_types::ScheduledChangeStatus"]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduledChangeStatus {
+    Pending,
+    Executed,
+    Cancelled,
+    Failed
+}
+
+/// A status change set to happen to a market at `execute_at`
+#[derive(Serialize,Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = MarketStatusSchedulesTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct MarketStatusScheduleRecord {
+    pub id: Uuid,
+    pub market_id: Uuid,
+    pub target_status: MarketStatus,
+    pub execute_at: NaiveDateTime,
+    pub reason: Option<String>,
+    pub status: ScheduledChangeStatus,
+    pub executed_at: Option<NaiveDateTime>,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime
+}
+
+#[derive(Serialize,Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = MarketStatusSchedulesTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct CreateMarketStatusSchedule {
+    pub market_id: Uuid,
+    pub target_status: MarketStatus,
+    pub execute_at: NaiveDateTime,
+    pub reason: Option<String>
 }
\ No newline at end of file
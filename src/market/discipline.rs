@@ -0,0 +1,178 @@
+use anyhow::{Result, anyhow};
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    market::db_types::{MarketRecord, MarketRegulation},
+    order_book::db_types::{NewOrderBookRecord, OrderType},
+    price_feeds::{
+        sources::{LastTrade, PriceSource},
+        twap::{DEFAULT_WINDOW_SECS, market_twap},
+    },
+    utils::commons::DbConn,
+};
+
+/// Most a band may be set to either side of its reference.
+pub const MAX_BAND_PERCENT: i64 = 100;
+
+/// Where a band's reference price came from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceKind {
+    /// Set on the market by an operator
+    Fixed,
+    /// Time-weighted average of the market's recent fills
+    Twap,
+    /// No fills in the TWAP window, so the last one
+    LastTrade,
+}
+
+/// The prices a regulated market accepts limit orders at: within
+/// `band_percent` either side of a reference price.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MarketDiscipline {
+    pub reference_price: BigDecimal,
+    pub reference_kind: ReferenceKind,
+    pub band_percent: BigDecimal,
+}
+
+impl MarketDiscipline {
+    fn offset(&self) -> BigDecimal {
+        &self.reference_price * &self.band_percent / BigDecimal::from(100)
+    }
+
+    pub fn lower(&self) -> BigDecimal {
+        &self.reference_price - self.offset()
+    }
+
+    pub fn upper(&self) -> BigDecimal {
+        &self.reference_price + self.offset()
+    }
+
+    pub fn allows(&self, price: &BigDecimal) -> bool {
+        *price >= self.lower() && *price <= self.upper()
+    }
+
+    /// The band a market's orders are held to, or `None` for unregulated
+    /// markets and ones with no price to anchor to yet.
+    pub async fn for_market(conn: DbConn<'_>, market: &MarketRecord) -> Result<Option<Self>> {
+        if !matches!(market.market_regulation, MarketRegulation::Regulated) {
+            return Ok(None);
+        }
+
+        let (reference_price, reference_kind) = match &market.reference_price {
+            Some(price) => (price.clone(), ReferenceKind::Fixed),
+            None => match market_twap(conn, market.id, DEFAULT_WINDOW_SECS) {
+                Ok(twap) => (twap, ReferenceKind::Twap),
+                Err(_) => match (LastTrade {
+                    market_id: market.id,
+                })
+                .fetch(conn)
+                .await
+                {
+                    Ok(quote) => (quote.price, ReferenceKind::LastTrade),
+                    // nothing has traded, so the first prices set the reference
+                    Err(_) => return Ok(None),
+                },
+            },
+        };
+
+        Ok(Some(Self {
+            reference_price,
+            reference_kind,
+            band_percent: market.price_band_percent.clone(),
+        }))
+    }
+}
+
+/// A market's band as reported to clients, with its bounds worked out.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PriceBandStatus {
+    pub market_id: Uuid,
+    /// `None` when the market isn't held to a band right now
+    pub band: Option<MarketDiscipline>,
+    pub lower: Option<BigDecimal>,
+    pub upper: Option<BigDecimal>,
+}
+
+pub async fn price_band_status(conn: DbConn<'_>, market: &MarketRecord) -> Result<PriceBandStatus> {
+    let band = MarketDiscipline::for_market(conn, market).await?;
+    Ok(PriceBandStatus {
+        market_id: market.id,
+        lower: band.as_ref().map(MarketDiscipline::lower),
+        upper: band.as_ref().map(MarketDiscipline::upper),
+        band,
+    })
+}
+
+pub fn validate_band(
+    band_percent: &BigDecimal,
+    reference_price: Option<&BigDecimal>,
+) -> Result<()> {
+    if *band_percent <= BigDecimal::from(0) || *band_percent > BigDecimal::from(MAX_BAND_PERCENT) {
+        return Err(anyhow!(
+            "price_band_percent must be above 0 and at most {}",
+            MAX_BAND_PERCENT
+        ));
+    }
+    if reference_price.is_some_and(|p| *p <= BigDecimal::from(0)) {
+        return Err(anyhow!("reference_price must be positive"));
+    }
+    Ok(())
+}
+
+/// Refuse limit orders priced outside a regulated market's band. Market
+/// orders fill at makers' prices, which were held to the band when placed.
+pub async fn check_price_band(
+    conn: DbConn<'_>,
+    market: &MarketRecord,
+    order: &NewOrderBookRecord,
+) -> Result<()> {
+    if matches!(order.order_type, Some(OrderType::Market)) {
+        return Ok(());
+    }
+    let Some(discipline) = MarketDiscipline::for_market(conn, market).await? else {
+        return Ok(());
+    };
+    if !discipline.allows(&order.price) {
+        return Err(anyhow!(
+            "Price {} is outside the price band for {} ({} to {})",
+            order.price,
+            market.display_symbol,
+            discipline.lower(),
+            discipline.upper()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn band(reference: i64, percent: i64) -> MarketDiscipline {
+        MarketDiscipline {
+            reference_price: BigDecimal::from(reference),
+            reference_kind: ReferenceKind::Fixed,
+            band_percent: BigDecimal::from(percent),
+        }
+    }
+
+    #[test]
+    fn bounds_are_inclusive() {
+        let discipline = band(100, 10);
+        assert!(discipline.allows(&BigDecimal::from(90)));
+        assert!(discipline.allows(&BigDecimal::from(110)));
+        assert!(!discipline.allows(&BigDecimal::from(89)));
+        assert!(!discipline.allows(&BigDecimal::from(111)));
+    }
+
+    #[test]
+    fn band_must_be_a_sensible_percentage() {
+        assert!(validate_band(&BigDecimal::from(10), None).is_ok());
+        assert!(validate_band(&BigDecimal::from(0), None).is_err());
+        assert!(validate_band(&BigDecimal::from(150), None).is_err());
+        assert!(validate_band(&BigDecimal::from(5), Some(&BigDecimal::from(-1))).is_err());
+    }
+}
@@ -1,5 +1,12 @@
 pub mod config;
 pub mod db_types;
+pub mod discipline;
+pub mod operations;
 pub mod processor;
+pub mod precision;
 pub mod processor_enums;
+pub mod schedule;
+pub mod status_changes;
+pub mod stats;
+pub mod symbols;
 pub mod filter;
\ No newline at end of file
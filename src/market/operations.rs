@@ -0,0 +1,92 @@
+use anyhow::{Result, anyhow};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    market::db_types::{CreateMarket, MarketRecord, MarketStatus, MarketType},
+    utils::commons::DbConn,
+};
+
+/// A live (active or suspended) market of type `kind` trading `base`
+/// against `quote`, other than `except`. Suspended markets count since they
+/// come back.
+pub fn find_live_market(
+    conn: DbConn<'_>,
+    base: Uuid,
+    quote: Uuid,
+    kind: &MarketType,
+    except: Option<Uuid>,
+) -> Result<Option<MarketRecord>> {
+    use crate::schema::markets::dsl::*;
+
+    let mut query = markets
+        .filter(asset_one.eq(base))
+        .filter(asset_two.eq(quote))
+        .filter(market_type.eq(kind.clone()))
+        .filter(market_status.eq_any([MarketStatus::Active, MarketStatus::Suspended]))
+        .into_boxed();
+    if let Some(except) = except {
+        query = query.filter(id.ne(except));
+    }
+    Ok(query.first::<MarketRecord>(conn).optional()?)
+}
+
+/// Refuse to have two live markets for the same ordered pair and type.
+pub fn ensure_no_live_duplicate(
+    conn: DbConn<'_>,
+    base: Uuid,
+    quote: Uuid,
+    kind: &MarketType,
+    except: Option<Uuid>,
+) -> Result<()> {
+    if let Some(existing) = find_live_market(conn, base, quote, kind, except)? {
+        return Err(anyhow!(
+            "Duplicate market: {} ({}) already trades this pair as {:?}",
+            existing.symbol,
+            existing.id,
+            kind
+        ));
+    }
+    Ok(())
+}
+
+/// Check a market before it's created: both assets are on the book, they
+/// differ, and a live market would be the only one for its pair and type.
+pub fn validate_new_market(conn: DbConn<'_>, market: &CreateMarket) -> Result<()> {
+    if market.name.trim().is_empty() {
+        return Err(anyhow!("Invalid market: a name is required"));
+    }
+    if market.asset_one == market.asset_two {
+        return Err(anyhow!(
+            "Invalid market: asset_one and asset_two are the same asset"
+        ));
+    }
+
+    let found: Vec<Uuid> = {
+        use crate::schema::asset_book::dsl::*;
+
+        asset_book
+            .filter(id.eq_any([market.asset_one, market.asset_two]))
+            .select(id)
+            .get_results::<Uuid>(conn)?
+    };
+    for (field, asset) in [
+        ("asset_one", market.asset_one),
+        ("asset_two", market.asset_two),
+    ] {
+        if !found.contains(&asset) {
+            return Err(anyhow!(
+                "Unknown market asset: {} {} is not on the asset book",
+                field,
+                asset
+            ));
+        }
+    }
+
+    let status = market.market_status.clone().unwrap_or(MarketStatus::Active);
+    if !matches!(status, MarketStatus::InActive) {
+        let kind = market.market_type.clone().unwrap_or(MarketType::Spot);
+        ensure_no_live_duplicate(conn, market.asset_one, market.asset_two, &kind, None)?;
+    }
+    Ok(())
+}
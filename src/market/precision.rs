@@ -0,0 +1,125 @@
+use anyhow::{Result, anyhow};
+use bigdecimal::{BigDecimal, RoundingMode};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    asset_book::db_types::AssetBookRecord, market::db_types::MarketRecord,
+    order_book::db_types::NewOrderBookRecord, utils::commons::DbConn,
+};
+
+/// Price precision of markets created without one.
+pub const DEFAULT_PRICE_PRECISION: i32 = 8;
+
+/// Most decimal places a market may quote prices or quantities in.
+pub const MAX_PRECISION: i32 = 18;
+
+fn get_asset(conn: DbConn<'_>, asset: Uuid) -> Result<AssetBookRecord> {
+    use crate::schema::asset_book::dsl::*;
+
+    Ok(asset_book.find(asset).get_result::<AssetBookRecord>(conn)?)
+}
+
+/// Whether `value` has no more than `places` decimal places.
+pub fn fits(value: &BigDecimal, places: i32) -> bool {
+    value.with_scale_round(places as i64, RoundingMode::Down) == *value
+}
+
+/// An amount in the token's smallest unit, in whole tokens.
+pub fn whole_units(amount: &BigDecimal, decimals: i32) -> BigDecimal {
+    amount * BigDecimal::new(1.into(), decimals as i64)
+}
+
+/// Check precisions for a market trading `base` before they're saved. A
+/// quantity can't be finer than the base token itself.
+pub fn validate_precision(
+    conn: DbConn<'_>,
+    base: Uuid,
+    price_precision: i32,
+    quantity_precision: i32,
+) -> Result<()> {
+    if !(0..=MAX_PRECISION).contains(&price_precision) {
+        return Err(anyhow!(
+            "price_precision must be between 0 and {}",
+            MAX_PRECISION
+        ));
+    }
+    let decimals = get_asset(conn, base)?.decimals;
+    if quantity_precision < 0 || quantity_precision > decimals {
+        return Err(anyhow!(
+            "quantity_precision must be between 0 and the base asset's {} decimals",
+            decimals
+        ));
+    }
+    Ok(())
+}
+
+/// "BASE/QUOTE" from the assets' symbols, for markets created without a
+/// display symbol.
+pub fn default_display_symbol(
+    conn: DbConn<'_>,
+    asset_one: Uuid,
+    asset_two: Uuid,
+) -> Result<String> {
+    let base = get_asset(conn, asset_one)?;
+    let quote = get_asset(conn, asset_two)?;
+    Ok(format!("{}/{}", base.symbol, quote.symbol))
+}
+
+/// Refuse orders priced or sized finer than the market allows. The base
+/// side is whichever of the order's assets is the market's `asset_one`.
+pub fn check_order_precision(
+    conn: DbConn<'_>,
+    market: &MarketRecord,
+    order: &NewOrderBookRecord,
+) -> Result<()> {
+    if !fits(&order.price, market.price_precision) {
+        return Err(anyhow!(
+            "Price {} has more than the {} decimal places {} allows",
+            order.price,
+            market.price_precision,
+            market.display_symbol
+        ));
+    }
+
+    let base_amount = if order.bid_asset == market.asset_one {
+        &order.bid_amount
+    } else {
+        &order.ask_amount
+    };
+    let decimals = get_asset(conn, market.asset_one)?.decimals;
+    let quantity = whole_units(base_amount, decimals);
+    if !fits(&quantity, market.quantity_precision) {
+        return Err(anyhow!(
+            "Quantity {} has more than the {} decimal places {} allows",
+            quantity.normalized(),
+            market.quantity_precision,
+            market.display_symbol
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn fits_counts_significant_places_only() {
+        assert!(fits(&dec("1.25"), 2));
+        assert!(fits(&dec("1.2500"), 2));
+        assert!(!fits(&dec("1.255"), 2));
+        assert!(fits(&dec("100"), 0));
+    }
+
+    #[test]
+    fn whole_units_scales_by_decimals() {
+        assert_eq!(whole_units(&dec("150000000"), 8), dec("1.5"));
+        assert!(!fits(&whole_units(&dec("150000001"), 8), 4));
+    }
+}
@@ -4,7 +4,11 @@ use diesel::{ExpressionMethods, PgConnection, RunQueryDsl};
 use diesel::r2d2::{ConnectionManager, PooledConnection};
 use uuid::Uuid;
 use crate::market::config::MarketsConfig;
-use crate::market::db_types::MarketRecord;
+use crate::market::db_types::{MarketRecord, MarketStatus, MarketStatusScheduleRecord, ScheduledChangeStatus};
+use crate::market::discipline::validate_band;
+use crate::market::operations::{ensure_no_live_duplicate, validate_new_market};
+use crate::market::symbols::assign_symbol;
+use crate::market::precision::{default_display_symbol, validate_precision, DEFAULT_PRICE_PRECISION};
 use crate::market::processor_enums::{MarketProcessorInput, MarketProcessorOutput};
 use crate::utils::app_config::AppConfig;
 use crate::utils::traits::ActionProcessor;
@@ -15,11 +19,34 @@ impl ActionProcessor<MarketsConfig, MarketProcessorOutput> for MarketProcessorIn
         match self {
             MarketProcessorInput::CreateMarket(create_args) => {
                 use crate::schema::markets::dsl::*;
-                let res = diesel::insert_into(MarketsTable::table).values(create_args).returning(id).get_result::<Uuid>(app_conn)?;
+
+                validate_new_market(app_conn, create_args)?;
+
+                let mut args = create_args.clone();
+                let base_decimals = {
+                    use crate::schema::asset_book::dsl as assets;
+                    assets::asset_book.find(args.asset_one).select(assets::decimals).get_result::<i32>(app_conn)?
+                };
+                let price_places = args.price_precision.unwrap_or(DEFAULT_PRICE_PRECISION);
+                let quantity_places = args.quantity_precision.unwrap_or(base_decimals);
+                validate_precision(app_conn, args.asset_one, price_places, quantity_places)?;
+                args.price_precision = Some(price_places);
+                args.quantity_precision = Some(quantity_places);
+                args.symbol = Some(assign_symbol(app_conn, args.symbol.as_deref(), args.asset_one, args.asset_two)?);
+                if args.display_symbol.as_deref().is_none_or(|s| s.trim().is_empty()) {
+                    args.display_symbol = Some(default_display_symbol(app_conn, args.asset_one, args.asset_two)?);
+                }
+
+                let res = diesel::insert_into(MarketsTable::table).values(&args).returning(id).get_result::<Uuid>(app_conn)?;
                 Ok(MarketProcessorOutput::CreateMarket(res))
             }
             MarketProcessorInput::UpdateMarketStatus(update_args ) => {
                 use crate::schema::markets::dsl::*;
+
+                if !matches!(update_args.status, MarketStatus::InActive) {
+                    let market = markets.find(update_args.market_id).get_result::<MarketRecord>(app_conn)?;
+                    ensure_no_live_duplicate(app_conn, market.asset_one, market.asset_two, &market.market_type, Some(market.id))?;
+                }
                 
                 let _ = diesel::update(MarketsTable::table).filter(
                     id.eq(update_args.market_id)
@@ -32,6 +59,11 @@ impl ActionProcessor<MarketsConfig, MarketProcessorOutput> for MarketProcessorIn
             MarketProcessorInput::UpdateMarketType(update_args) => {
                 use crate::schema::markets::dsl::*;
 
+                let market = markets.find(update_args.market_id).get_result::<MarketRecord>(app_conn)?;
+                if !matches!(market.market_status, MarketStatus::InActive) {
+                    ensure_no_live_duplicate(app_conn, market.asset_one, market.asset_two, &update_args.market_type, Some(market.id))?;
+                }
+
                 let _ = diesel::update(MarketsTable::table).filter(
                     id.eq(update_args.market_id)
                 ).set(
@@ -51,6 +83,99 @@ impl ActionProcessor<MarketsConfig, MarketProcessorOutput> for MarketProcessorIn
 
                 Ok(MarketProcessorOutput::UpdateMarketRegulation)
             }
+            MarketProcessorInput::UpdateMarketSchedule(update_args) => {
+                use crate::schema::markets::dsl::*;
+
+                let schedule = match &update_args.schedule {
+                    Some(s) => {
+                        s.validate()?;
+                        Some(serde_json::to_value(s)?)
+                    }
+                    None => None
+                };
+
+                let _ = diesel::update(MarketsTable::table).filter(
+                    id.eq(update_args.market_id)
+                ).set(
+                    trading_schedule.eq(schedule)
+                ).execute(app_conn)?;
+
+                Ok(MarketProcessorOutput::UpdateMarketSchedule)
+            }
+            MarketProcessorInput::UpdateMarketDisplay(update_args) => {
+                use crate::schema::markets::dsl::*;
+
+                let market = markets.find(update_args.market_id).get_result::<MarketRecord>(app_conn)?;
+                let price_places = update_args.price_precision.unwrap_or(market.price_precision);
+                let quantity_places = update_args.quantity_precision.unwrap_or(market.quantity_precision);
+                validate_precision(app_conn, market.asset_one, price_places, quantity_places)?;
+                let symbol = match &update_args.display_symbol {
+                    Some(s) if s.trim().is_empty() => return Err(anyhow!("display_symbol can't be empty")),
+                    Some(s) => s.trim().to_string(),
+                    None => market.display_symbol
+                };
+
+                let _ = diesel::update(MarketsTable::table).filter(
+                    id.eq(update_args.market_id)
+                ).set((
+                    price_precision.eq(price_places),
+                    quantity_precision.eq(quantity_places),
+                    display_symbol.eq(symbol)
+                )).execute(app_conn)?;
+
+                Ok(MarketProcessorOutput::UpdateMarketDisplay)
+            }
+            MarketProcessorInput::UpdateMarketPriceBand(update_args) => {
+                use crate::schema::markets::dsl::*;
+
+                validate_band(&update_args.price_band_percent, update_args.reference_price.as_ref())?;
+
+                let _ = diesel::update(MarketsTable::table).filter(
+                    id.eq(update_args.market_id)
+                ).set((
+                    price_band_percent.eq(update_args.price_band_percent.clone()),
+                    reference_price.eq(update_args.reference_price.clone())
+                )).execute(app_conn)?;
+
+                Ok(MarketProcessorOutput::UpdateMarketPriceBand)
+            }
+            MarketProcessorInput::ScheduleMarketStatus(schedule_args) => {
+                use crate::schema::market_status_schedules::dsl::*;
+
+                if schedule_args.execute_at <= chrono::Utc::now().naive_utc() {
+                    return Err(anyhow!("execute_at must be in the future"));
+                }
+                let _ = {
+                    use crate::schema::markets::dsl as m;
+                    m::markets.find(schedule_args.market_id).select(m::id).get_result::<Uuid>(app_conn)?
+                };
+
+                let res = diesel::insert_into(market_status_schedules).values(schedule_args).returning(id).get_result::<Uuid>(app_conn)?;
+                Ok(MarketProcessorOutput::ScheduleMarketStatus(res))
+            }
+            MarketProcessorInput::CancelScheduledMarketStatus(schedule_id) => {
+                use crate::schema::market_status_schedules::dsl::*;
+
+                let cancelled = diesel::update(market_status_schedules.find(schedule_id))
+                    .filter(status.eq(ScheduledChangeStatus::Pending))
+                    .set(status.eq(ScheduledChangeStatus::Cancelled))
+                    .execute(app_conn)?;
+                if cancelled == 0 {
+                    return Err(anyhow!("Scheduled change {} isn't pending", schedule_id));
+                }
+
+                Ok(MarketProcessorOutput::CancelScheduledMarketStatus)
+            }
+            MarketProcessorInput::GetScheduledMarketStatuses(market) => {
+                use crate::schema::market_status_schedules::dsl::*;
+
+                let results = market_status_schedules
+                    .filter(market_id.eq(market))
+                    .order(execute_at.desc())
+                    .get_results::<MarketStatusScheduleRecord>(app_conn)?;
+
+                Ok(MarketProcessorOutput::GetScheduledMarketStatuses(results))
+            }
             MarketProcessorInput::GetMarket(market_id) => {
                 use crate::schema::markets::dsl::*;
                 
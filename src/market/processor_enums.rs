@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
+use bigdecimal::BigDecimal;
 use uuid::Uuid;
-use crate::market::db_types::{CreateMarket, MarketRecord, MarketRegulation, MarketStatus, MarketType};
+use crate::market::db_types::{CreateMarket, CreateMarketStatusSchedule, MarketRecord, MarketRegulation, MarketStatus, MarketStatusScheduleRecord, MarketType};
+use crate::market::schedule::TradingSchedule;
 
 
 
@@ -22,6 +24,30 @@ pub struct UpdateMarketRegulationInputArgs {
     pub regulation: MarketRegulation
 }
 
+/// `schedule: None` lets the market trade around the clock again
+#[derive(Deserialize,Serialize, Debug)]
+pub struct UpdateMarketScheduleInputArgs {
+    pub market_id: Uuid,
+    pub schedule: Option<TradingSchedule>
+}
+
+/// Only the fields given are changed
+#[derive(Deserialize,Serialize, Debug)]
+pub struct UpdateMarketDisplayInputArgs {
+    pub market_id: Uuid,
+    pub price_precision: Option<i32>,
+    pub quantity_precision: Option<i32>,
+    pub display_symbol: Option<String>
+}
+
+/// `reference_price: None` anchors the band to recent trades
+#[derive(Deserialize,Serialize, Debug)]
+pub struct UpdateMarketPriceBandInputArgs {
+    pub market_id: Uuid,
+    pub price_band_percent: BigDecimal,
+    pub reference_price: Option<BigDecimal>
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct GetMarketsFilter {
     pub status: Option<MarketStatus>,
@@ -35,6 +61,12 @@ pub enum MarketProcessorInput {
     UpdateMarketStatus(UpdateMarketStatusInputArgs),
     UpdateMarketType(UpdateMarketTypeInputArgs),
     UpdateMarketRegulation(UpdateMarketRegulationInputArgs),
+    UpdateMarketSchedule(UpdateMarketScheduleInputArgs),
+    UpdateMarketDisplay(UpdateMarketDisplayInputArgs),
+    UpdateMarketPriceBand(UpdateMarketPriceBandInputArgs),
+    ScheduleMarketStatus(CreateMarketStatusSchedule),
+    CancelScheduledMarketStatus(Uuid),
+    GetScheduledMarketStatuses(Uuid),
     GetMarket(Uuid),
     GetMarkets(GetMarketsFilter)
 }
@@ -46,6 +78,12 @@ pub enum MarketProcessorOutput {
     UpdateMarketStatus,
     UpdateMarketType,
     UpdateMarketRegulation,
+    UpdateMarketSchedule,
+    UpdateMarketDisplay,
+    UpdateMarketPriceBand,
+    ScheduleMarketStatus(Uuid),
+    CancelScheduledMarketStatus,
+    GetScheduledMarketStatuses(Vec<MarketStatusScheduleRecord>),
     GetMarket(MarketRecord),
     GetMarkets(Vec<MarketRecord>)
 }
\ No newline at end of file
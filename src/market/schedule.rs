@@ -0,0 +1,332 @@
+use std::time::Duration as PollDuration;
+
+use anyhow::{Result, anyhow};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    market::db_types::{MarketRecord, MarketStatus},
+    utils::{app_config::AppConfig, cache, commons::DbConn, kvstore},
+};
+
+/// How far ahead `next_change` looks for a session boundary.
+const LOOKAHEAD_DAYS: i64 = 14;
+
+/// One trading window, repeated on each of `days`, in the schedule's local
+/// time. Sessions can't run past midnight; split them in two.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TradingSession {
+    pub days: Vec<Weekday>,
+    pub open: NaiveTime,
+    pub close: NaiveTime,
+}
+
+/// When a market trades. Days without a session, such as weekends, and
+/// holidays are closed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TradingSchedule {
+    /// Offset of the schedule's local time from UTC, e.g. 180 for Nairobi
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
+    pub sessions: Vec<TradingSession>,
+    #[serde(default)]
+    pub holidays: Vec<NaiveDate>,
+}
+
+impl TradingSchedule {
+    pub fn validate(&self) -> Result<()> {
+        if self.utc_offset_minutes.abs() > 14 * 60 {
+            return Err(anyhow!("utc_offset_minutes must be within 14 hours"));
+        }
+        if self.sessions.is_empty() {
+            return Err(anyhow!("A schedule needs at least one session"));
+        }
+        for session in &self.sessions {
+            if session.days.is_empty() {
+                return Err(anyhow!("Every session needs at least one day"));
+            }
+            if session.open >= session.close {
+                return Err(anyhow!(
+                    "Session {}-{} closes before it opens",
+                    session.open,
+                    session.close
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn to_local(&self, at: NaiveDateTime) -> NaiveDateTime {
+        at + Duration::minutes(self.utc_offset_minutes as i64)
+    }
+
+    fn to_utc(&self, local: NaiveDateTime) -> NaiveDateTime {
+        local - Duration::minutes(self.utc_offset_minutes as i64)
+    }
+
+    /// Whether the market trades at `at`, a UTC time.
+    pub fn is_open_at(&self, at: NaiveDateTime) -> bool {
+        let local = self.to_local(at);
+        if self.holidays.contains(&local.date()) {
+            return false;
+        }
+        let time = local.time();
+        self.sessions
+            .iter()
+            .any(|s| s.days.contains(&local.weekday()) && s.open <= time && time < s.close)
+    }
+
+    /// The next UTC time after `at` the market opens or closes, if there is
+    /// one in the next couple of weeks.
+    pub fn next_change(&self, at: NaiveDateTime) -> Option<NaiveDateTime> {
+        let open_now = self.is_open_at(at);
+        let today = self.to_local(at).date();
+
+        let mut boundaries: Vec<NaiveDateTime> = (0..=LOOKAHEAD_DAYS)
+            .filter_map(|offset| today.checked_add_signed(Duration::days(offset)))
+            .flat_map(|day| {
+                self.sessions
+                    .iter()
+                    .filter(move |s| s.days.contains(&day.weekday()))
+                    .flat_map(move |s| [day.and_time(s.open), day.and_time(s.close)])
+            })
+            .map(|local| self.to_utc(local))
+            .filter(|boundary| *boundary > at)
+            .collect();
+        boundaries.sort();
+
+        // back-to-back sessions and holidays leave boundaries where nothing
+        // changes
+        boundaries
+            .into_iter()
+            .find(|boundary| self.is_open_at(*boundary) != open_now)
+    }
+}
+
+impl MarketRecord {
+    pub fn schedule(&self) -> Result<Option<TradingSchedule>> {
+        match &self.trading_schedule {
+            Some(value) => Ok(Some(serde_json::from_value(value.clone())?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MarketScheduleStatus {
+    pub market_id: Uuid,
+    pub status: MarketStatus,
+    /// `None` for markets that trade around the clock
+    pub schedule: Option<TradingSchedule>,
+    /// Inside a session, or always for unscheduled markets
+    pub in_session: bool,
+    pub next_change: Option<NaiveDateTime>,
+}
+
+pub fn schedule_status(market: &MarketRecord, at: NaiveDateTime) -> Result<MarketScheduleStatus> {
+    let schedule = market.schedule()?;
+    Ok(MarketScheduleStatus {
+        market_id: market.id,
+        status: market.market_status.clone(),
+        in_session: schedule.as_ref().is_none_or(|s| s.is_open_at(at)),
+        next_change: schedule.as_ref().and_then(|s| s.next_change(at)),
+        schedule,
+    })
+}
+
+/// Refuse to trade on a market that isn't active or is outside its
+/// trading hours, returning the market when it's open.
+pub fn ensure_market_open(conn: DbConn<'_>, market: Uuid) -> Result<MarketRecord> {
+    let record = {
+        use crate::schema::markets::dsl::*;
+
+        markets.find(market).get_result::<MarketRecord>(conn)?
+    };
+
+    let now = Utc::now().naive_utc();
+    let status = schedule_status(&record, now)?;
+    if !status.in_session {
+        return Err(match status.next_change {
+            Some(opens) => anyhow!(
+                "Market {} is outside its trading hours; it opens at {} UTC",
+                record.name,
+                opens
+            ),
+            None => anyhow!("Market {} is outside its trading hours", record.name),
+        });
+    }
+
+    if !matches!(record.market_status, MarketStatus::Active) {
+        return Err(anyhow!(
+            "Market {} is not open for trading ({:?})",
+            record.name,
+            record.market_status
+        ));
+    }
+    Ok(record)
+}
+
+/// Seconds between session checks, from MARKET_SCHEDULE_INTERVAL_SECS.
+fn poll_interval() -> PollDuration {
+    let secs = std::env::var("MARKET_SCHEDULE_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(30);
+    PollDuration::from_secs(secs)
+}
+
+/// Markets the scheduler suspended itself are marked, so it only reopens
+/// those and leaves suspensions made by hand alone.
+fn suspended_marker(market: Uuid) -> String {
+    format!("market_schedule_suspended:{}", market)
+}
+
+/// Forget that the scheduler suspended a market, so it won't reopen it. For
+/// status changes made on purpose, which it shouldn't undo.
+pub async fn forget_schedule_suspension(conn: DbConn<'_>, market: Uuid) -> Result<()> {
+    kvstore::set_value_kv(conn, &suspended_marker(market), "").await?;
+    Ok(())
+}
+
+async fn set_status(
+    app_config: &AppConfig,
+    conn: DbConn<'_>,
+    market: &MarketRecord,
+    status: MarketStatus,
+) -> Result<()> {
+    {
+        use crate::schema::markets::dsl::*;
+
+        diesel::update(markets.find(market.id))
+            .set(market_status.eq(status.clone()))
+            .execute(conn)?;
+    }
+    if let Some(redis) = &app_config.redis {
+        cache::cache_del(redis, &format!("market:{}", market.id)).await;
+        cache::cache_del(redis, "markets:all").await;
+    }
+    println!("Market scheduler set {} to {:?}", market.name, status);
+    Ok(())
+}
+
+/// Suspend active markets whose session has ended and reactivate the ones
+/// it suspended once their next session starts.
+pub async fn run_once(app_config: &AppConfig) -> Result<()> {
+    let mut conn = app_config.pool.get()?;
+    let now = Utc::now().naive_utc();
+
+    let scheduled = {
+        use crate::schema::markets::dsl::*;
+
+        markets
+            .filter(trading_schedule.is_not_null())
+            .get_results::<MarketRecord>(&mut conn)?
+    };
+
+    for market in scheduled {
+        let in_session = match market.schedule() {
+            Ok(Some(schedule)) => schedule.is_open_at(now),
+            Ok(None) => continue,
+            Err(e) => {
+                println!("Market {} has an unreadable schedule: {:?}", market.id, e);
+                continue;
+            }
+        };
+        let marker = suspended_marker(market.id);
+        let suspended_by_us = kvstore::get_value_kv(&mut conn, &marker)
+            .await
+            .ok()
+            .flatten()
+            .is_some_and(|v| !v.is_empty());
+
+        match market.market_status {
+            MarketStatus::Active if !in_session => {
+                set_status(app_config, &mut conn, &market, MarketStatus::Suspended).await?;
+                kvstore::set_value_kv(&mut conn, &marker, "1").await?;
+            }
+            MarketStatus::Suspended if in_session && suspended_by_us => {
+                set_status(app_config, &mut conn, &market, MarketStatus::Active).await?;
+                kvstore::set_value_kv(&mut conn, &marker, "").await?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Background task flipping scheduled markets at session boundaries.
+pub async fn run_scheduler(app_config: AppConfig) {
+    let mut ticker = tokio::time::interval(poll_interval());
+    loop {
+        ticker.tick().await;
+        if let Err(e) = run_once(&app_config).await {
+            println!("Market schedule error: {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weekdays() -> TradingSchedule {
+        TradingSchedule {
+            utc_offset_minutes: 180,
+            sessions: vec![TradingSession {
+                days: vec![
+                    Weekday::Mon,
+                    Weekday::Tue,
+                    Weekday::Wed,
+                    Weekday::Thu,
+                    Weekday::Fri,
+                ],
+                open: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                close: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            }],
+            holidays: vec![NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()],
+        }
+    }
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn open_inside_a_session_in_local_time() {
+        let schedule = weekdays();
+        // Thursday 2 Jan 2025, 06:00 UTC is 09:00 local
+        assert!(schedule.is_open_at(utc(2025, 1, 2, 6, 0)));
+        assert!(!schedule.is_open_at(utc(2025, 1, 2, 5, 59)));
+        assert!(!schedule.is_open_at(utc(2025, 1, 2, 14, 0)));
+    }
+
+    #[test]
+    fn closed_on_weekends_and_holidays() {
+        let schedule = weekdays();
+        // Wednesday 1 Jan is a holiday, Saturday 4 Jan a weekend
+        assert!(!schedule.is_open_at(utc(2025, 1, 1, 8, 0)));
+        assert!(!schedule.is_open_at(utc(2025, 1, 4, 8, 0)));
+    }
+
+    #[test]
+    fn next_change_skips_the_weekend() {
+        let schedule = weekdays();
+        // Friday 3 Jan after close reopens Monday 6 Jan at 09:00 local
+        assert_eq!(
+            schedule.next_change(utc(2025, 1, 3, 15, 0)),
+            Some(utc(2025, 1, 6, 6, 0))
+        );
+        // and during a session the next change is the close
+        assert_eq!(
+            schedule.next_change(utc(2025, 1, 3, 7, 0)),
+            Some(utc(2025, 1, 3, 14, 0))
+        );
+    }
+}
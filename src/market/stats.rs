@@ -0,0 +1,393 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration as PollDuration;
+
+use anyhow::Result;
+use bigdecimal::{BigDecimal, RoundingMode};
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    order_book::db_types::OrderStatus,
+    utils::{app_config::AppConfig, commons::DbConn},
+};
+
+/// Width of the buckets the rolling window is kept in; the window's edge is
+/// only this precise.
+const BUCKET_SECS: i64 = 300;
+
+const WINDOW_HOURS: i64 = 24;
+
+/// One bucket of a market's fills, at maker prices with volume in the base
+/// asset's smallest unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bucket {
+    pub start: NaiveDateTime,
+    pub open: BigDecimal,
+    pub high: BigDecimal,
+    pub low: BigDecimal,
+    pub volume: BigDecimal,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MarketWindow {
+    pub buckets: VecDeque<Bucket>,
+    pub last_price: Option<BigDecimal>,
+    pub last_trade_at: Option<NaiveDateTime>,
+}
+
+fn bucket_start(at: NaiveDateTime) -> NaiveDateTime {
+    let ts = at.and_utc().timestamp();
+    let start = ts - ts.rem_euclid(BUCKET_SECS);
+    chrono::DateTime::from_timestamp(start, 0)
+        .map(|d| d.naive_utc())
+        .unwrap_or(at)
+}
+
+impl MarketWindow {
+    /// Add a fill. Fills normally arrive in time order; a late one is put in
+    /// its own bucket.
+    pub fn record(&mut self, at: NaiveDateTime, price: &BigDecimal, volume: &BigDecimal) {
+        let start = bucket_start(at);
+        match self.buckets.iter().rposition(|b| b.start <= start) {
+            Some(i) if self.buckets[i].start == start => {
+                let bucket = &mut self.buckets[i];
+                if *price > bucket.high {
+                    bucket.high = price.clone();
+                }
+                if *price < bucket.low {
+                    bucket.low = price.clone();
+                }
+                bucket.volume += volume;
+            }
+            found => {
+                let at_index = found.map(|i| i + 1).unwrap_or(0);
+                self.buckets.insert(
+                    at_index,
+                    Bucket {
+                        start,
+                        open: price.clone(),
+                        high: price.clone(),
+                        low: price.clone(),
+                        volume: volume.clone(),
+                    },
+                );
+            }
+        }
+
+        if self.last_trade_at.is_none_or(|last| at >= last) {
+            self.last_price = Some(price.clone());
+            self.last_trade_at = Some(at);
+        }
+    }
+
+    /// Drop buckets that have left the window ending at `now`.
+    pub fn evict(&mut self, now: NaiveDateTime) {
+        let cutoff = bucket_start(now - Duration::hours(WINDOW_HOURS));
+        while self.buckets.front().is_some_and(|b| b.start < cutoff) {
+            self.buckets.pop_front();
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct MarketStats {
+    pub market_id: Uuid,
+    pub symbol: String,
+    /// Latest fill, however old
+    pub last_price: Option<BigDecimal>,
+    pub last_trade_at: Option<NaiveDateTime>,
+    pub change_24h: Option<BigDecimal>,
+    pub change_percent_24h: Option<BigDecimal>,
+    pub high_24h: Option<BigDecimal>,
+    pub low_24h: Option<BigDecimal>,
+    /// In the base asset's smallest unit
+    pub volume_24h: BigDecimal,
+    pub open_orders: i64,
+}
+
+/// A market's figures from its window. Change is measured from the first
+/// fill in the window.
+pub fn summarize(
+    market_id: Uuid,
+    symbol: String,
+    window: &MarketWindow,
+    open_orders: i64,
+) -> MarketStats {
+    let buckets = &window.buckets;
+    let opening = buckets.front().map(|b| b.open.clone());
+    let change = match (&window.last_price, &opening) {
+        (Some(last), Some(open)) => Some(last - open),
+        _ => None,
+    };
+    let change_percent = match (&change, &opening) {
+        (Some(change), Some(open)) if *open != BigDecimal::from(0) => Some(
+            (change * BigDecimal::from(100) / open).with_scale_round(2, RoundingMode::HalfEven),
+        ),
+        _ => None,
+    };
+
+    MarketStats {
+        market_id,
+        symbol,
+        last_price: window.last_price.clone(),
+        last_trade_at: window.last_trade_at,
+        change_24h: change,
+        change_percent_24h: change_percent,
+        high_24h: buckets.iter().map(|b| b.high.clone()).max(),
+        low_24h: buckets.iter().map(|b| b.low.clone()).min(),
+        volume_24h: buckets
+            .iter()
+            .fold(BigDecimal::from(0), |total, b| total + &b.volume),
+        open_orders,
+    }
+}
+
+#[derive(Default)]
+struct StatsCache {
+    /// Fills up to here have been folded in
+    watermark: Option<NaiveDateTime>,
+    /// Fills at exactly the watermark already folded in, so the next pass
+    /// can include that instant without counting them twice
+    seen_at_watermark: HashSet<Uuid>,
+    windows: HashMap<Uuid, MarketWindow>,
+    symbols: HashMap<Uuid, String>,
+    open_orders: HashMap<Uuid, i64>,
+    refreshed_at: Option<NaiveDateTime>,
+}
+
+static CACHE: Lazy<Mutex<StatsCache>> = Lazy::new(|| Mutex::new(StatsCache::default()));
+
+/// Seconds between refreshes, from MARKET_STATS_INTERVAL_SECS.
+fn poll_interval() -> PollDuration {
+    let secs = std::env::var("MARKET_STATS_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(10);
+    PollDuration::from_secs(secs)
+}
+
+type FillRow = (
+    Uuid,
+    NaiveDateTime,
+    Uuid,
+    BigDecimal,
+    Uuid,
+    BigDecimal,
+    BigDecimal,
+    Uuid,
+);
+
+/// Fills from `since` on, oldest first, with what's needed to place them:
+/// (trade, time, market, maker price, maker's ask asset, maker filled,
+/// taker filled, market base asset).
+fn fills_since(conn: DbConn<'_>, since: NaiveDateTime) -> Result<Vec<FillRow>> {
+    use crate::schema::markets;
+    use crate::schema::orderbook;
+    use crate::schema::orderbooktrades;
+
+    Ok(orderbooktrades::table
+        .inner_join(orderbook::table.on(orderbook::id.eq(orderbooktrades::maker_order_id)))
+        .inner_join(markets::table.on(markets::id.eq(orderbook::market_id)))
+        .filter(orderbooktrades::created_at.ge(since))
+        .order((orderbooktrades::created_at.asc(), orderbooktrades::id.asc()))
+        .select((
+            orderbooktrades::id,
+            orderbooktrades::created_at,
+            orderbook::market_id,
+            orderbook::price,
+            orderbook::ask_asset,
+            orderbooktrades::maker_filled_amount,
+            orderbooktrades::taker_filled_amount,
+            markets::asset_one,
+        ))
+        .get_results::<FillRow>(conn)?)
+}
+
+/// The latest fill of every market, for markets quiet for longer than the
+/// window.
+fn last_prices(conn: DbConn<'_>) -> Result<Vec<(Uuid, NaiveDateTime, BigDecimal)>> {
+    use crate::schema::orderbook;
+    use crate::schema::orderbooktrades;
+
+    Ok(orderbooktrades::table
+        .inner_join(orderbook::table.on(orderbook::id.eq(orderbooktrades::maker_order_id)))
+        .distinct_on(orderbook::market_id)
+        .order((orderbook::market_id, orderbooktrades::created_at.desc()))
+        .select((
+            orderbook::market_id,
+            orderbooktrades::created_at,
+            orderbook::price,
+        ))
+        .get_results::<(Uuid, NaiveDateTime, BigDecimal)>(conn)?)
+}
+
+/// Fold fills since the last pass into the cache and recount open orders.
+/// The first pass loads the whole window.
+pub fn refresh(conn: DbConn<'_>) -> Result<()> {
+    let now = Utc::now().naive_utc();
+    let since = {
+        let cache = CACHE.lock().unwrap();
+        cache.watermark
+    };
+
+    let seeding = since.is_none();
+    let fills = fills_since(conn, since.unwrap_or(now - Duration::hours(WINDOW_HOURS)))?;
+    let quiet = if seeding {
+        last_prices(conn)?
+    } else {
+        Vec::new()
+    };
+
+    let symbols: HashMap<Uuid, String> = {
+        use crate::schema::markets::dsl::*;
+
+        markets
+            .select((id, symbol))
+            .get_results::<(Uuid, String)>(conn)?
+            .into_iter()
+            .collect()
+    };
+    let open_orders: HashMap<Uuid, i64> = {
+        use crate::schema::orderbook::dsl::*;
+
+        orderbook
+            .filter(status.eq(OrderStatus::Open))
+            .group_by(market_id)
+            .select((market_id, diesel::dsl::count_star()))
+            .get_results::<(Uuid, i64)>(conn)?
+            .into_iter()
+            .collect()
+    };
+
+    let mut cache = CACHE.lock().unwrap();
+    for (market, at, price) in quiet {
+        let window = cache.windows.entry(market).or_default();
+        window.last_price = Some(price);
+        window.last_trade_at = Some(at);
+    }
+    for (trade, at, market, price, maker_ask, maker_filled, taker_filled, base) in fills {
+        if Some(at) == cache.watermark && cache.seen_at_watermark.contains(&trade) {
+            continue;
+        }
+        // the maker gives its ask asset, so whichever side is the base is
+        // the volume
+        let volume = if maker_ask == base {
+            maker_filled
+        } else {
+            taker_filled
+        };
+        cache
+            .windows
+            .entry(market)
+            .or_default()
+            .record(at, &price, &volume);
+
+        if Some(at) != cache.watermark {
+            cache.watermark = Some(at);
+            cache.seen_at_watermark.clear();
+        }
+        cache.seen_at_watermark.insert(trade);
+    }
+    if cache.watermark.is_none() {
+        // nothing in the window; start from the beginning of it next time
+        cache.watermark = Some(now - Duration::hours(WINDOW_HOURS));
+    }
+
+    for window in cache.windows.values_mut() {
+        window.evict(now);
+    }
+    cache.symbols = symbols;
+    cache.open_orders = open_orders;
+    cache.refreshed_at = Some(now);
+    Ok(())
+}
+
+/// Every market's stats from the cache, loading it first if nothing has yet.
+pub fn market_stats(conn: DbConn<'_>) -> Result<Vec<MarketStats>> {
+    let loaded = CACHE.lock().unwrap().refreshed_at.is_some();
+    if !loaded {
+        refresh(conn)?;
+    }
+
+    let cache = CACHE.lock().unwrap();
+    let empty = MarketWindow::default();
+    let mut stats: Vec<MarketStats> = cache
+        .symbols
+        .iter()
+        .map(|(market, symbol)| {
+            summarize(
+                *market,
+                symbol.clone(),
+                cache.windows.get(market).unwrap_or(&empty),
+                cache.open_orders.get(market).copied().unwrap_or(0),
+            )
+        })
+        .collect();
+    stats.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    Ok(stats)
+}
+
+pub async fn run_once(app_config: &AppConfig) -> Result<()> {
+    let mut conn = app_config.pool.get()?;
+    refresh(&mut conn)
+}
+
+/// Background task keeping the stats cache current.
+pub async fn run_stats_refresher(app_config: AppConfig) {
+    let mut ticker = tokio::time::interval(poll_interval());
+    loop {
+        ticker.tick().await;
+        if let Err(e) = run_once(&app_config).await {
+            println!("Market stats error: {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(mins: i64) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            + Duration::minutes(mins)
+    }
+
+    #[test]
+    fn folds_fills_into_buckets_and_summarizes() {
+        let mut window = MarketWindow::default();
+        window.record(at(0), &BigDecimal::from(100), &BigDecimal::from(5));
+        window.record(at(2), &BigDecimal::from(120), &BigDecimal::from(5));
+        window.record(at(10), &BigDecimal::from(90), &BigDecimal::from(10));
+        assert_eq!(window.buckets.len(), 2);
+
+        let stats = summarize(Uuid::nil(), "A-B".to_string(), &window, 3);
+        assert_eq!(stats.last_price, Some(BigDecimal::from(90)));
+        assert_eq!(stats.change_24h, Some(BigDecimal::from(-10)));
+        assert_eq!(stats.change_percent_24h, Some(BigDecimal::from(-10)));
+        assert_eq!(stats.high_24h, Some(BigDecimal::from(120)));
+        assert_eq!(stats.low_24h, Some(BigDecimal::from(90)));
+        assert_eq!(stats.volume_24h, BigDecimal::from(20));
+    }
+
+    #[test]
+    fn old_buckets_leave_the_window_but_the_last_price_stays() {
+        let mut window = MarketWindow::default();
+        window.record(at(0), &BigDecimal::from(100), &BigDecimal::from(5));
+        window.evict(at(25 * 60));
+        assert!(window.buckets.is_empty());
+
+        let stats = summarize(Uuid::nil(), "A-B".to_string(), &window, 0);
+        assert_eq!(stats.last_price, Some(BigDecimal::from(100)));
+        assert_eq!(stats.change_24h, None);
+        assert_eq!(stats.volume_24h, BigDecimal::from(0));
+    }
+}
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use diesel::prelude::*;
+
+use crate::{
+    action_router::ActionRouterInput,
+    audit_log::operations::process_audited,
+    market::{
+        db_types::{MarketStatusScheduleRecord, ScheduledChangeStatus},
+        processor_enums::{MarketProcessorInput, UpdateMarketStatusInputArgs},
+        schedule::forget_schedule_suspension,
+    },
+    utils::{app_config::AppConfig, cache},
+};
+
+/// Who scheduled changes are made as in the audit log.
+const ACTOR: &str = "market-scheduler";
+const AUDIT_SOURCE: &str = "scheduler";
+
+/// Seconds between checks for due changes, from
+/// MARKET_STATUS_CHANGES_INTERVAL_SECS.
+fn poll_interval() -> Duration {
+    let secs = std::env::var("MARKET_STATUS_CHANGES_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(30);
+    Duration::from_secs(secs)
+}
+
+/// Apply every pending change that's due, oldest first. Each goes through
+/// the router like a manual status change, so it's checked and audited the
+/// same way, and is marked executed or failed.
+pub async fn run_once(app_config: &AppConfig) -> Result<()> {
+    let mut conn = app_config.pool.get()?;
+    let now = Utc::now().naive_utc();
+
+    let due = {
+        use crate::schema::market_status_schedules::dsl::*;
+
+        market_status_schedules
+            .filter(status.eq(ScheduledChangeStatus::Pending))
+            .filter(execute_at.le(now))
+            .order(execute_at.asc())
+            .get_results::<MarketStatusScheduleRecord>(&mut conn)?
+    };
+
+    for change in due {
+        let input = ActionRouterInput::Markets(MarketProcessorInput::UpdateMarketStatus(
+            UpdateMarketStatusInputArgs {
+                market_id: change.market_id,
+                status: change.target_status.clone(),
+            },
+        ));
+        let outcome = process_audited(input, &mut app_config.clone(), None, ACTOR, AUDIT_SOURCE).await;
+
+        let (result, failure) = match outcome {
+            Ok(_) => {
+                // a change made on purpose shouldn't be undone at the next
+                // session boundary
+                forget_schedule_suspension(&mut conn, change.market_id).await?;
+                if let Some(redis) = &app_config.redis {
+                    cache::cache_del(redis, &format!("market:{}", change.market_id)).await;
+                    cache::cache_del(redis, "markets:all").await;
+                }
+                println!(
+                    "Scheduled change {} set market {} to {:?}",
+                    change.id, change.market_id, change.target_status
+                );
+                (ScheduledChangeStatus::Executed, None)
+            }
+            Err(e) => {
+                println!("Scheduled change {} failed: {:?}", change.id, e);
+                (ScheduledChangeStatus::Failed, Some(e.to_string()))
+            }
+        };
+
+        use crate::schema::market_status_schedules::dsl::*;
+
+        diesel::update(market_status_schedules.find(change.id))
+            .set((
+                status.eq(result),
+                executed_at.eq(Some(Utc::now().naive_utc())),
+                error.eq(failure),
+            ))
+            .execute(&mut conn)?;
+    }
+
+    Ok(())
+}
+
+/// Background task applying scheduled market status changes.
+pub async fn run_status_changes(app_config: AppConfig) {
+    let mut ticker = tokio::time::interval(poll_interval());
+    loop {
+        ticker.tick().await;
+        if let Err(e) = run_once(&app_config).await {
+            println!("Market status change error: {:?}", e);
+        }
+    }
+}
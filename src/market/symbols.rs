@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{market::db_types::MarketRecord, utils::commons::DbConn};
+
+/// Normalise a market symbol: upper case letters, digits and single dashes,
+/// e.g. "CBTC-CUSD".
+pub fn normalize_symbol(raw: &str) -> Result<String> {
+    let symbol = raw.trim().to_uppercase();
+    let valid = (3..=32).contains(&symbol.len())
+        && symbol
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '-')
+        && !symbol.starts_with('-')
+        && !symbol.ends_with('-')
+        && !symbol.contains("--");
+    if !valid {
+        return Err(anyhow!(
+            "Market symbols are 3 to 32 letters, digits and single dashes, e.g. CBTC-CUSD; got {}",
+            raw
+        ));
+    }
+    Ok(symbol)
+}
+
+/// "BASE-QUOTE" from the assets' symbols, dropping anything a symbol can't
+/// hold.
+pub fn pair_symbol(base: &str, quote: &str) -> String {
+    let clean = |s: &str| -> String {
+        s.chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_uppercase()
+    };
+    format!("{}-{}", clean(base), clean(quote))
+}
+
+/// The symbol a new market gets: the one asked for, refused if another
+/// market has it, or one made from its assets, numbered if a market on the
+/// same pair already has it.
+pub fn assign_symbol(
+    conn: DbConn<'_>,
+    requested: Option<&str>,
+    asset_one: Uuid,
+    asset_two: Uuid,
+) -> Result<String> {
+    let base_symbol = match requested {
+        Some(raw) => {
+            let symbol = normalize_symbol(raw)?;
+            if let Some(existing) = find_by_symbol(conn, &symbol)? {
+                return Err(anyhow!(
+                    "Market symbol {} is already used by market {}",
+                    symbol,
+                    existing.id
+                ));
+            }
+            return Ok(symbol);
+        }
+        None => {
+            use crate::schema::asset_book::dsl::*;
+
+            let base = asset_book
+                .find(asset_one)
+                .select(symbol)
+                .get_result::<String>(conn)?;
+            let quote = asset_book
+                .find(asset_two)
+                .select(symbol)
+                .get_result::<String>(conn)?;
+            normalize_symbol(&pair_symbol(&base, &quote))?
+        }
+    };
+
+    for n in 1..=100 {
+        let candidate = if n == 1 {
+            base_symbol.clone()
+        } else {
+            format!("{}-{}", base_symbol, n)
+        };
+        if find_by_symbol(conn, &candidate)?.is_none() {
+            return Ok(candidate);
+        }
+    }
+    Err(anyhow!(
+        "No free symbol for {}; give the market one",
+        base_symbol
+    ))
+}
+
+/// Case-insensitive lookup by symbol.
+pub fn find_by_symbol(conn: DbConn<'_>, raw: &str) -> Result<Option<MarketRecord>> {
+    use crate::schema::markets::dsl::*;
+
+    let wanted = raw.trim().to_uppercase();
+    Ok(markets
+        .filter(symbol.eq(wanted))
+        .get_result::<MarketRecord>(conn)
+        .optional()?)
+}
+
+/// A market given either its UUID or its symbol, as API callers may use
+/// either.
+pub fn resolve_market(conn: DbConn<'_>, id_or_symbol: &str) -> Result<MarketRecord> {
+    if let Ok(market_id) = Uuid::parse_str(id_or_symbol) {
+        use crate::schema::markets::dsl::*;
+
+        return Ok(markets.find(market_id).get_result::<MarketRecord>(conn)?);
+    }
+    find_by_symbol(conn, id_or_symbol)?
+        .ok_or_else(|| anyhow!("No market with symbol {}", id_or_symbol))
+}
+
+/// Every market's symbol by id, for labelling rows that only carry the id.
+pub fn market_symbols(conn: DbConn<'_>) -> Result<HashMap<Uuid, String>> {
+    use crate::schema::markets::dsl::*;
+
+    Ok(markets
+        .select((id, symbol))
+        .get_results::<(Uuid, String)>(conn)?
+        .into_iter()
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pair_symbols_are_normalised() {
+        assert_eq!(pair_symbol("cBTC", "cUSD"), "CBTC-CUSD");
+        assert_eq!(pair_symbol("t.MTK", "USD Coin"), "TMTK-USDCOIN");
+        assert_eq!(normalize_symbol(" cbtc-cusd ").unwrap(), "CBTC-CUSD");
+    }
+
+    #[test]
+    fn malformed_symbols_are_refused() {
+        assert!(normalize_symbol("AB").is_err());
+        assert!(normalize_symbol("CBTC/CUSD").is_err());
+        assert!(normalize_symbol("-CBTC").is_err());
+        assert!(normalize_symbol("CBTC--CUSD").is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDateTime};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use socketioxide::SocketIo;
+use uuid::Uuid;
+
+use crate::market_time_series::db_types::{CreateMarketTimeSeriesRecord, TimeSeriesInterval};
+use crate::sockets::broadcast;
+
+/// Bar currently forming for a market/asset/interval, built from live trades.
+/// The persisted bar still comes from the aggregator; this only feeds the
+/// socket channel between closes.
+#[derive(Clone, Debug)]
+struct FormingBar {
+    open: BigDecimal,
+    high: BigDecimal,
+    low: BigDecimal,
+    close: BigDecimal,
+    volume: BigDecimal,
+    start_time: NaiveDateTime,
+    end_time: NaiveDateTime,
+}
+
+static FORMING: Lazy<Mutex<HashMap<(Uuid, Uuid, &'static str), FormingBar>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CandleEvent {
+    pub market_id: Uuid,
+    pub asset: Uuid,
+    pub interval: TimeSeriesInterval,
+    pub open: String,
+    pub high: String,
+    pub low: String,
+    pub close: String,
+    pub volume: String,
+    pub start_time: NaiveDateTime,
+    pub end_time: NaiveDateTime,
+    pub closed: bool,
+}
+
+pub fn candle_room(market_id: Uuid, interval: &TimeSeriesInterval) -> String {
+    format!("candles:{}:{}", market_id, interval.as_str())
+}
+
+/// Bucket boundaries containing `at`, aligned to the unix epoch.
+fn bucket(at: NaiveDateTime, interval: &TimeSeriesInterval) -> (NaiveDateTime, NaiveDateTime) {
+    let secs = interval.seconds();
+    let ts = at.and_utc().timestamp();
+    let start = ts - ts.rem_euclid(secs);
+    let to_naive = |t: i64| DateTime::from_timestamp(t, 0).map(|d| d.naive_utc()).unwrap_or(at);
+    (to_naive(start), to_naive(start + secs))
+}
+
+/// Fold a trade into every interval's forming bar and return the updated bars.
+pub fn record_trade(
+    market_id: Uuid,
+    asset: Uuid,
+    price: &BigDecimal,
+    volume: &BigDecimal,
+    at: NaiveDateTime,
+) -> Vec<CandleEvent> {
+    let mut forming = FORMING.lock().unwrap();
+
+    TimeSeriesInterval::ALL
+        .iter()
+        .map(|interval| {
+            let (start_time, end_time) = bucket(at, interval);
+            let bar = forming
+                .entry((market_id, asset, interval.as_str()))
+                .and_modify(|bar| {
+                    if bar.start_time != start_time {
+                        *bar = FormingBar {
+                            open: price.clone(),
+                            high: price.clone(),
+                            low: price.clone(),
+                            close: price.clone(),
+                            volume: BigDecimal::from(0),
+                            start_time,
+                            end_time,
+                        };
+                    }
+                })
+                .or_insert_with(|| FormingBar {
+                    open: price.clone(),
+                    high: price.clone(),
+                    low: price.clone(),
+                    close: price.clone(),
+                    volume: BigDecimal::from(0),
+                    start_time,
+                    end_time,
+                });
+
+            if price > &bar.high {
+                bar.high = price.clone();
+            }
+            if price < &bar.low {
+                bar.low = price.clone();
+            }
+            bar.close = price.clone();
+            bar.volume += volume;
+
+            CandleEvent {
+                market_id,
+                asset,
+                interval: interval.clone(),
+                open: bar.open.to_string(),
+                high: bar.high.to_string(),
+                low: bar.low.to_string(),
+                close: bar.close.to_string(),
+                volume: bar.volume.to_string(),
+                start_time: bar.start_time,
+                end_time: bar.end_time,
+                closed: false,
+            }
+        })
+        .collect()
+}
+
+pub async fn emit_candle_updates(io: &SocketIo, updates: Vec<CandleEvent>) {
+    for update in updates {
+        broadcast(io, candle_room(update.market_id, &update.interval), "candle:update", &update).await;
+    }
+}
+
+/// Emit a bar persisted by the aggregator as closed.
+pub async fn emit_candle_close(io: &SocketIo, record: &CreateMarketTimeSeriesRecord) {
+    let Some(interval) = record.interval.clone() else {
+        return;
+    };
+
+    let event = CandleEvent {
+        market_id: record.market_id,
+        asset: record.asset,
+        interval: interval.clone(),
+        open: record.open.to_string(),
+        high: record.high.to_string(),
+        low: record.low.to_string(),
+        close: record.close.to_string(),
+        volume: record.volume.to_string(),
+        start_time: record.start_time,
+        end_time: record.end_time,
+        closed: true,
+    };
+
+    broadcast(io, candle_room(record.market_id, &interval), "candle:close", &event).await;
+}
@@ -44,6 +44,55 @@ pub enum TimeSeriesInterval {
     OneWeek
 }
 
+impl TimeSeriesInterval {
+    pub const ALL: [TimeSeriesInterval; 11] = [
+        TimeSeriesInterval::FifteenSecs,
+        TimeSeriesInterval::ThirtySecs,
+        TimeSeriesInterval::FortyFiveSecs,
+        TimeSeriesInterval::OneMinute,
+        TimeSeriesInterval::FiveMinutes,
+        TimeSeriesInterval::FifteenMinutes,
+        TimeSeriesInterval::ThirtyMinutes,
+        TimeSeriesInterval::OneHour,
+        TimeSeriesInterval::FourHours,
+        TimeSeriesInterval::OneDay,
+        TimeSeriesInterval::OneWeek,
+    ];
+
+    /// Same labels as the serde/db renames
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeSeriesInterval::FifteenSecs => "15secs",
+            TimeSeriesInterval::ThirtySecs => "30secs",
+            TimeSeriesInterval::FortyFiveSecs => "45secs",
+            TimeSeriesInterval::OneMinute => "1min",
+            TimeSeriesInterval::FiveMinutes => "5min",
+            TimeSeriesInterval::FifteenMinutes => "15min",
+            TimeSeriesInterval::ThirtyMinutes => "30min",
+            TimeSeriesInterval::OneHour => "1hr",
+            TimeSeriesInterval::FourHours => "4hr",
+            TimeSeriesInterval::OneDay => "1day",
+            TimeSeriesInterval::OneWeek => "1week",
+        }
+    }
+
+    pub fn seconds(&self) -> i64 {
+        match self {
+            TimeSeriesInterval::FifteenSecs => 15,
+            TimeSeriesInterval::ThirtySecs => 30,
+            TimeSeriesInterval::FortyFiveSecs => 45,
+            TimeSeriesInterval::OneMinute => 60,
+            TimeSeriesInterval::FiveMinutes => 5 * 60,
+            TimeSeriesInterval::FifteenMinutes => 15 * 60,
+            TimeSeriesInterval::ThirtyMinutes => 30 * 60,
+            TimeSeriesInterval::OneHour => 60 * 60,
+            TimeSeriesInterval::FourHours => 4 * 60 * 60,
+            TimeSeriesInterval::OneDay => 24 * 60 * 60,
+            TimeSeriesInterval::OneWeek => 7 * 24 * 60 * 60,
+        }
+    }
+}
+
 
 #[derive(Deserialize,Serialize, Debug, Clone, DbEnum)]
 #[ExistingTypePath="crate::schema::sql_types::DataProviderType"]
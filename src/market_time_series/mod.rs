@@ -1,3 +1,4 @@
+pub mod candles;
 pub mod config;
 pub mod db_types;
 pub mod processor;
@@ -8,6 +8,8 @@ use diesel::prelude::*;
 use crate::market_time_series::config::MarketTimeSeriesConfig;
 use crate::market_time_series::db_types::MarketTimeSeriesRecord;
 use crate::market_time_series::processor_enum::{MarketTimeSeriesProcessorInput, MarketTimeSeriesProcessorOutput};
+use crate::market_time_series::candles::emit_candle_close;
+use crate::sockets::broadcast;
 use crate::utils::app_config::AppConfig;
 use crate::utils::traits::ActionProcessor;
 use crate::schema::markets_time_series as MarketTimeSeriesTable;
@@ -25,7 +27,8 @@ impl ActionProcessor<MarketTimeSeriesConfig, MarketTimeSeriesProcessorOutput> fo
                 // Emit price-change to subscribers of this market's timeseries room
                 if let Ok(io) = app_config.get_io() {
                     let room = format!("timeseries:{}", args.market_id);
-                    let _ = io.to(room).emit("price-change", &args).await;
+                    broadcast(&io, room, "price-change", &args).await;
+                    emit_candle_close(&io, args).await;
                 }
 
                 Ok(MarketTimeSeriesProcessorOutput::AddRecord(bar_id))
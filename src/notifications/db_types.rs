@@ -0,0 +1,28 @@
+use crate::schema::notifications as NotificationsTable;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = NotificationsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NotificationRow {
+    pub id: Uuid,
+    pub wallet_id: Uuid,
+    pub listing_id: Option<Uuid>,
+    pub event: String,
+    pub payload: Value,
+    pub read_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = NotificationsTable)]
+pub struct CreateNotification {
+    pub wallet_id: Uuid,
+    pub listing_id: Option<Uuid>,
+    pub event: String,
+    pub payload: Value,
+}
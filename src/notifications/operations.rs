@@ -0,0 +1,134 @@
+use anyhow::Result;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::listing::operations::{get_listing, get_listing_holdings};
+use crate::notifications::db_types::{CreateNotification, NotificationRow};
+
+/// Listing events every current holder hears about.
+const HOLDER_EVENTS: [&str; 4] = [
+    "listing:closed",
+    "listing:lifecycle",
+    "listing:market",
+    "listing:corporate_action",
+];
+
+/// The wallet an event is about, for events addressed to a single investor
+/// (purchases, allocations, refunds, dividends).
+fn addressed_wallet(data: &Value) -> Option<Uuid> {
+    ["wallet_id", "wallet"]
+        .iter()
+        .find_map(|key| data.get(key)?.as_str())
+        .and_then(|raw| Uuid::parse_str(raw).ok())
+}
+
+/// Put a listing event in the inbox of the investors it concerns: the wallet
+/// it names, or every holder for listing-wide changes. Other events are
+/// left to sockets and webhooks.
+pub async fn notify_listing_event(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: Uuid,
+    event_name: &str,
+    data: &Value,
+) -> Result<usize> {
+    let recipients = match addressed_wallet(data) {
+        Some(wallet) => vec![wallet],
+        None if HOLDER_EVENTS.contains(&event_name) => {
+            let row = get_listing(conn, listing).await?;
+            get_listing_holdings(conn, &row)?
+                .into_iter()
+                .map(|h| h.wallet_id)
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    if recipients.is_empty() {
+        return Ok(0);
+    }
+
+    let rows = recipients
+        .into_iter()
+        .map(|wallet| CreateNotification {
+            wallet_id: wallet,
+            listing_id: Some(listing),
+            event: event_name.to_string(),
+            payload: data.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    use crate::schema::notifications::dsl::*;
+    Ok(diesel::insert_into(notifications).values(&rows).execute(conn)?)
+}
+
+/// Put an event that isn't about a listing, such as a ramp receipt, in a
+/// single wallet's inbox.
+pub fn notify_wallet(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    wallet: Uuid,
+    event_name: &str,
+    data: &Value,
+) -> Result<NotificationRow> {
+    use crate::schema::notifications::dsl::*;
+
+    Ok(diesel::insert_into(notifications)
+        .values(CreateNotification {
+            wallet_id: wallet,
+            listing_id: None,
+            event: event_name.to_string(),
+            payload: data.clone(),
+        })
+        .get_result::<NotificationRow>(conn)?)
+}
+
+/// A wallet's notifications, newest first.
+pub fn get_wallet_notifications(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    wallet: Uuid,
+    unread_only: bool,
+) -> Result<Vec<NotificationRow>> {
+    use crate::schema::notifications::dsl::*;
+
+    let mut query = notifications.filter(wallet_id.eq(wallet)).into_boxed();
+    if unread_only {
+        query = query.filter(read_at.is_null());
+    }
+
+    Ok(query
+        .order(created_at.desc())
+        .limit(200)
+        .get_results::<NotificationRow>(conn)?)
+}
+
+/// Mark the given notifications read, or all of the wallet's when `ids` is
+/// empty. Returns how many changed.
+pub fn mark_notifications_read(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    wallet: Uuid,
+    ids: Vec<Uuid>,
+) -> Result<usize> {
+    use crate::schema::notifications::dsl::*;
+
+    let now = Utc::now().naive_utc();
+    let unread = notifications
+        .filter(wallet_id.eq(wallet))
+        .filter(read_at.is_null());
+
+    let updated = if ids.is_empty() {
+        diesel::update(unread)
+            .set(read_at.eq(Some(now)))
+            .execute(conn)?
+    } else {
+        diesel::update(unread.filter(id.eq_any(ids)))
+            .set(read_at.eq(Some(now)))
+            .execute(conn)?
+    };
+
+    Ok(updated)
+}
@@ -0,0 +1,325 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    market::db_types::MarketRecord,
+    order_book::db_types::{OrderBookRecord, OrderStatus, OrderType, SettlementStatus},
+    utils::commons::DbConn,
+};
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+/// Unfilled base quantity resting at one price.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DepthLevel {
+    pub price: BigDecimal,
+    pub quantity: BigDecimal,
+    pub orders: usize,
+}
+
+/// A fill at the maker's price, with the side the taker was on.
+#[derive(Serialize, Debug, Clone)]
+pub struct BookTrade {
+    pub id: Uuid,
+    pub created_at: NaiveDateTime,
+    pub price: BigDecimal,
+    pub quantity: BigDecimal,
+    pub taker_side: Side,
+    pub settlement_status: SettlementStatus,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BookSnapshot {
+    pub market_id: Uuid,
+    pub symbol: String,
+    /// Highest price first
+    pub bids: Vec<DepthLevel>,
+    /// Lowest price first
+    pub asks: Vec<DepthLevel>,
+    pub best_bid: Option<BigDecimal>,
+    pub best_ask: Option<BigDecimal>,
+    pub spread: Option<BigDecimal>,
+    /// Newest first
+    pub trades: Vec<BookTrade>,
+    pub taken_at: NaiveDateTime,
+}
+
+/// A fill in any market, for views across the whole exchange.
+#[derive(Serialize, Debug, Clone)]
+pub struct MarketTrade {
+    pub market_id: Uuid,
+    pub symbol: String,
+    #[serde(flatten)]
+    pub trade: BookTrade,
+}
+
+/// A trade with its maker's price and ask asset, as queried.
+type Fill = (
+    Uuid,
+    NaiveDateTime,
+    BigDecimal,
+    Uuid,
+    BigDecimal,
+    BigDecimal,
+    SettlementStatus,
+);
+
+fn to_trade(
+    base: Uuid,
+    (id, created_at, price, maker_ask, maker_filled, taker_filled, settlement_status): Fill,
+) -> BookTrade {
+    // the maker gives its ask asset; when that's the base the taker was
+    // buying
+    let (quantity, taker_side) = if maker_ask == base {
+        (maker_filled, Side::Buy)
+    } else {
+        (taker_filled, Side::Sell)
+    };
+    BookTrade {
+        id,
+        created_at,
+        price,
+        quantity,
+        taker_side,
+        settlement_status,
+    }
+}
+
+/// Buy orders receive the market's base asset, sell orders give it. Orders
+/// for neither side don't belong to the market's book.
+pub fn side(order: &OrderBookRecord, base: Uuid) -> Option<Side> {
+    if order.bid_asset == base {
+        Some(Side::Buy)
+    } else if order.ask_asset == base {
+        Some(Side::Sell)
+    } else {
+        None
+    }
+}
+
+/// Sum orders' unfilled base quantity per price, best price first, keeping
+/// at most `levels` a side.
+pub fn aggregate(
+    orders: &[OrderBookRecord],
+    base: Uuid,
+    levels: usize,
+) -> (Vec<DepthLevel>, Vec<DepthLevel>) {
+    let zero = BigDecimal::from(0);
+    let mut bids: BTreeMap<BigDecimal, DepthLevel> = BTreeMap::new();
+    let mut asks: BTreeMap<BigDecimal, DepthLevel> = BTreeMap::new();
+
+    for order in orders {
+        let (book, remaining) = match side(order, base) {
+            Some(Side::Buy) => (&mut bids, &order.bid_amount - &order.filled_bid_amount),
+            Some(Side::Sell) => (&mut asks, &order.ask_amount - &order.filled_ask_amount),
+            None => continue,
+        };
+        if remaining <= zero {
+            continue;
+        }
+        let level = book
+            .entry(order.price.clone())
+            .or_insert_with(|| DepthLevel {
+                price: order.price.clone(),
+                quantity: zero.clone(),
+                orders: 0,
+            });
+        level.quantity += remaining;
+        level.orders += 1;
+    }
+
+    (
+        bids.into_values().rev().take(levels).collect(),
+        asks.into_values().take(levels).collect(),
+    )
+}
+
+/// A market's resting limit orders as depth, with its latest `trades`
+/// fills.
+pub fn book_snapshot(
+    conn: DbConn<'_>,
+    market: &MarketRecord,
+    levels: usize,
+    trades: i64,
+) -> Result<BookSnapshot> {
+    use crate::schema::orderbook;
+    use crate::schema::orderbooktrades;
+
+    let now = Utc::now().naive_utc();
+    let orders = orderbook::table
+        .filter(orderbook::market_id.eq(market.id))
+        .filter(orderbook::status.eq(OrderStatus::Open))
+        .filter(orderbook::order_type.eq(OrderType::Limit))
+        .filter(
+            orderbook::expires_at
+                .is_null()
+                .or(orderbook::expires_at.gt(now)),
+        )
+        .select(OrderBookRecord::as_select())
+        .get_results::<OrderBookRecord>(conn)?;
+    let (bids, asks) = aggregate(&orders, market.asset_one, levels);
+
+    let fills = orderbooktrades::table
+        .inner_join(orderbook::table.on(orderbook::id.eq(orderbooktrades::maker_order_id)))
+        .filter(orderbook::market_id.eq(market.id))
+        .order((
+            orderbooktrades::created_at.desc(),
+            orderbooktrades::id.desc(),
+        ))
+        .limit(trades)
+        .select((
+            orderbooktrades::id,
+            orderbooktrades::created_at,
+            orderbook::price,
+            orderbook::ask_asset,
+            orderbooktrades::maker_filled_amount,
+            orderbooktrades::taker_filled_amount,
+            orderbooktrades::settlement_status,
+        ))
+        .get_results::<Fill>(conn)?;
+
+    let trades = fills
+        .into_iter()
+        .map(|fill| to_trade(market.asset_one, fill))
+        .collect();
+
+    let best_bid = bids.first().map(|l| l.price.clone());
+    let best_ask = asks.first().map(|l| l.price.clone());
+    let spread = match (&best_bid, &best_ask) {
+        (Some(bid), Some(ask)) => Some(ask - bid),
+        _ => None,
+    };
+
+    Ok(BookSnapshot {
+        market_id: market.id,
+        symbol: market.symbol.clone(),
+        bids,
+        asks,
+        best_bid,
+        best_ask,
+        spread,
+        trades,
+        taken_at: now,
+    })
+}
+
+/// The latest `limit` fills across every market, newest first.
+pub fn recent_trades(conn: DbConn<'_>, limit: i64) -> Result<Vec<MarketTrade>> {
+    use crate::schema::markets;
+    use crate::schema::orderbook;
+    use crate::schema::orderbooktrades;
+
+    let rows = orderbooktrades::table
+        .inner_join(orderbook::table.on(orderbook::id.eq(orderbooktrades::maker_order_id)))
+        .inner_join(markets::table.on(markets::id.eq(orderbook::market_id)))
+        .order((
+            orderbooktrades::created_at.desc(),
+            orderbooktrades::id.desc(),
+        ))
+        .limit(limit)
+        .select((
+            (
+                orderbooktrades::id,
+                orderbooktrades::created_at,
+                orderbook::price,
+                orderbook::ask_asset,
+                orderbooktrades::maker_filled_amount,
+                orderbooktrades::taker_filled_amount,
+                orderbooktrades::settlement_status,
+            ),
+            markets::id,
+            markets::symbol,
+            markets::asset_one,
+        ))
+        .get_results::<(Fill, Uuid, String, Uuid)>(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(fill, market_id, symbol, base)| MarketTrade {
+            market_id,
+            symbol,
+            trade: to_trade(base, fill),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order_book::db_types::FillMode;
+
+    fn order(
+        bid_asset: Uuid,
+        ask_asset: Uuid,
+        price: i64,
+        bid: i64,
+        filled_bid: i64,
+    ) -> OrderBookRecord {
+        OrderBookRecord {
+            id: Uuid::new_v4(),
+            wallet: Uuid::new_v4(),
+            market_id: Uuid::nil(),
+            bid_asset,
+            ask_asset,
+            bid_amount: BigDecimal::from(bid),
+            ask_amount: BigDecimal::from(bid),
+            price: BigDecimal::from(price),
+            filled_bid_amount: BigDecimal::from(filled_bid),
+            filled_ask_amount: BigDecimal::from(filled_bid),
+            mode: FillMode::GoodTillCancel,
+            status: OrderStatus::Open,
+            created_at: Utc::now().naive_utc(),
+            filled_at: None,
+            cancelled_at: None,
+            expires_at: None,
+            order_type: OrderType::Limit,
+        }
+    }
+
+    #[test]
+    fn sums_levels_best_price_first() {
+        let base = Uuid::new_v4();
+        let quote = Uuid::new_v4();
+        let orders = vec![
+            order(base, quote, 99, 10, 0),
+            order(base, quote, 100, 5, 0),
+            order(base, quote, 100, 8, 3),
+            order(quote, base, 102, 4, 0),
+            order(quote, base, 101, 6, 0),
+            order(quote, base, 103, 7, 7),
+        ];
+
+        let (bids, asks) = aggregate(&orders, base, 10);
+        assert_eq!(bids.len(), 2);
+        assert_eq!(bids[0].price, BigDecimal::from(100));
+        assert_eq!(bids[0].quantity, BigDecimal::from(10));
+        assert_eq!(bids[0].orders, 2);
+        assert_eq!(asks.len(), 2, "fully filled orders leave the book");
+        assert_eq!(asks[0].price, BigDecimal::from(101));
+    }
+
+    #[test]
+    fn keeps_only_the_requested_levels() {
+        let base = Uuid::new_v4();
+        let quote = Uuid::new_v4();
+        let orders: Vec<_> = (1..=5).map(|p| order(base, quote, p, 1, 0)).collect();
+
+        let (bids, asks) = aggregate(&orders, base, 2);
+        assert_eq!(
+            bids.iter().map(|l| l.price.clone()).collect::<Vec<_>>(),
+            vec![BigDecimal::from(5), BigDecimal::from(4)]
+        );
+        assert!(asks.is_empty());
+    }
+}
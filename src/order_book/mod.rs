@@ -1,5 +1,6 @@
 pub mod config;
 pub mod db_types;
+pub mod depth;
 pub mod processor_enums;
 pub mod processor;
 mod sql_queries;
@@ -4,7 +4,6 @@ use bigdecimal::{BigDecimal, ToPrimitive};
 use chrono::Utc;
 use contract_integrator::utils::functions::cradle_account::TransferAssetArgs;
 use contract_integrator::utils::functions::orderbook_settler::OrderBookSettlerFunctionOutput;
-use contract_integrator::wallet::wallet::ActionWallet;
 use diesel::prelude::*;
 use crate::accounts::db_types::CradleWalletAccountRecord;
 use crate::accounts_ledger::db_types::{AccountLedgerTransactionType, CreateLedgerEntry};
@@ -13,6 +12,7 @@ use crate::asset_book::db_types::AssetBookRecord;
 use crate::big_to_u64;
 use crate::order_book::db_types::{OrderBookRecord, OrderBookTradeRecord, OrderStatus, SettlementStatus};
 use crate::utils::app_config::AppConfig;
+use crate::utils::wallet::SharedWallet;
 use anyhow::{anyhow, Result};
 use diesel::PgConnection;
 use diesel::r2d2::{ConnectionManager, PooledConnection};
@@ -25,7 +25,7 @@ enum OrderActionSide {
     Ask
 }
 
-fn can_execute_onchain()->bool {
+pub(crate) fn can_execute_onchain()->bool {
     env::var("DISABLE_ONCHAIN_INTERACTIONS").unwrap_or("false".to_string()) != "true".to_string()
 }
 
@@ -167,7 +167,7 @@ pub async fn lock_asset(
 }
 
 pub async fn settle_order(
-    action_wallet: &mut ActionWallet,
+    action_wallet: &SharedWallet,
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
     order_id: Uuid
 )-> Result<()> {
@@ -297,7 +297,7 @@ pub fn get_order_data(
 }
 
 pub async fn asset_transfer(
-    wallet: &mut ActionWallet,
+    wallet: &SharedWallet,
     sender_account: CradleWalletAccountRecord,
     amount: BigDecimal,
     sending_asset: AssetBookRecord,
@@ -338,7 +338,7 @@ pub async fn asset_transfer(
 
 pub async fn settle_onchain(
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
-    wallet: &mut ActionWallet,
+    wallet: &SharedWallet,
     maker: CradleWalletAccountRecord,
     taker: CradleWalletAccountRecord,
     _maker_transfer_amount: BigDecimal,
@@ -585,3 +585,100 @@ pub async fn update_order_status(
 
 
 
+
+
+/// Look up an order, making sure it belongs to `owner` when one is given.
+pub fn get_wallet_order(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    order_id: Uuid,
+    owner: Option<Uuid>
+)-> Result<OrderBookRecord> {
+    use crate::schema::orderbook::dsl::*;
+
+    let order = orderbook.filter(
+        id.eq(order_id)
+    ).get_result::<OrderBookRecord>(conn)?;
+
+    if let Some(owner) = owner && owner != order.wallet {
+        return Err(anyhow!("Order {} does not belong to wallet {}", order_id, owner));
+    }
+
+    Ok(order)
+}
+
+
+/// Cancel an order that is still open and release the part of its ask that
+/// hasn't been filled. Returns `None` if the order was no longer open.
+pub async fn cancel_open_order(
+    config: &mut AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    order_id: Uuid
+)-> Result<Option<OrderBookRecord>> {
+    use crate::schema::orderbook::dsl::*;
+    use crate::schema::orderbook::table as OrderBookTable;
+
+    let cancelled = diesel::update(OrderBookTable)
+        .filter(id.eq(order_id))
+        .filter(status.eq(OrderStatus::Open))
+        .set((
+            status.eq(OrderStatus::Cancelled),
+            cancelled_at.eq(Utc::now().naive_utc())
+        )).get_result::<OrderBookRecord>(conn)
+        .optional()?;
+
+    let Some(order) = cancelled else {
+        return Ok(None);
+    };
+
+    let unfilled_ask = &order.ask_amount - &order.filled_ask_amount;
+    if unfilled_ask > BigDecimal::from(0) {
+        unlock_asset(
+            config,
+            conn,
+            order.wallet,
+            order.ask_asset,
+            unfilled_ask.to_u64().ok_or_else(||anyhow!("Unable to unwrap u64"))?
+        ).await?;
+    }
+
+    Ok(Some(order))
+}
+
+
+/// Put an order cancelled by an amendment back on the book, locking its
+/// unfilled ask again. Only used when the replacement never made it in.
+pub async fn reopen_cancelled_order(
+    config: &mut AppConfig,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    order_id: Uuid
+)-> Result<OrderBookRecord> {
+    use crate::schema::orderbook::dsl::*;
+    use crate::schema::orderbook::table as OrderBookTable;
+
+    let order = orderbook.filter(
+        id.eq(order_id)
+    ).filter(
+        status.eq(OrderStatus::Cancelled)
+    ).get_result::<OrderBookRecord>(conn)?;
+
+    let unfilled_ask = &order.ask_amount - &order.filled_ask_amount;
+    if unfilled_ask > BigDecimal::from(0) {
+        lock_asset(
+            config,
+            conn,
+            order.wallet,
+            order.ask_asset,
+            unfilled_ask.to_u64().ok_or_else(||anyhow!("Unable to unwrap u64"))?
+        ).await?;
+    }
+
+    let reopened = diesel::update(OrderBookTable)
+        .filter(id.eq(order_id))
+        .filter(status.eq(OrderStatus::Cancelled))
+        .set((
+            status.eq(OrderStatus::Open),
+            cancelled_at.eq(None::<chrono::NaiveDateTime>)
+        )).get_result::<OrderBookRecord>(conn)?;
+
+    Ok(reopened)
+}
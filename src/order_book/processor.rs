@@ -1,20 +1,32 @@
 use crate::accounts::operations::{associate_token, kyc_token};
 use crate::accounts::processor_enums::{AssociateTokenToWalletInputArgs, GrantKYCInputArgs};
+use crate::market::discipline::check_price_band;
+use crate::market::precision::check_order_precision;
+use crate::market::schedule::ensure_market_open;
+use crate::market_time_series::candles;
 use crate::order_book::config::OrderBookConfig;
-use crate::order_book::db_types::{FillMode, OrderBookRecord, OrderStatus};
-use crate::order_book::operations::{lock_asset, settle_order, update_order_status};
+use crate::order_book::db_types::{FillMode, NewOrderBookRecord, OrderBookRecord, OrderStatus};
+use crate::order_book::operations::{
+    cancel_open_order, get_wallet_order, lock_asset, reopen_cancelled_order, settle_order,
+    update_order_status,
+};
 use crate::order_book::processor_enums::{
     OrderBookProcessorInput, OrderBookProcessorOutput, OrderFillResult, OrderFillStatus,
 };
 use crate::order_book::sql_queries::{get_matching_orders, get_order_fill_trades};
+use crate::sockets::broadcast;
+use crate::sockets::system_events::{SystemEvent, emit_system_event};
 use crate::utils::app_config::AppConfig;
 use crate::utils::traits::ActionProcessor;
 use anyhow::anyhow;
 use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::NaiveDateTime;
 use diesel::PgConnection;
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, PooledConnection};
 use serde::Serialize;
+use socketioxide::SocketIo;
+use std::collections::HashMap;
 use std::env;
 use uuid::Uuid;
 
@@ -53,12 +65,47 @@ impl From<&OrderBookRecord> for OrderEvent {
 struct TradeEvent {
     order_id: Uuid,
     market_id: Uuid,
+    market_symbol: String,
+    wallet: Uuid,
+    bid_asset: Uuid,
+    ask_asset: Uuid,
     trade_ids: Vec<Uuid>,
     bid_amount_filled: String,
     ask_amount_filled: String,
     status: String,
 }
 
+/// Order events go to the market's book room and to the owning wallet's
+/// account room.
+async fn emit_order_event(io: &SocketIo, name: &str, event: &OrderEvent) {
+    broadcast(io, format!("orderbook:{}", event.market_id), name, event).await;
+    broadcast(io, format!("account:{}", event.wallet), name, event).await;
+}
+
+/// Cancel an open order, releasing its unfilled ask, and tell the book.
+async fn cancel_order(
+    app_config: &mut AppConfig,
+    app_conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    order: &OrderBookRecord,
+) -> anyhow::Result<OrderBookRecord> {
+    let cancelled = cancel_open_order(app_config, app_conn, order.id)
+        .await?
+        .ok_or_else(|| {
+            anyhow!(
+                "Order {} is {:?}; only open orders can be cancelled",
+                order.id,
+                order.status
+            )
+        })?;
+
+    if let Ok(io) = app_config.get_io() {
+        let event = OrderEvent::from(&cancelled);
+        emit_order_event(&io, "order:cancelled", &event).await;
+    }
+
+    Ok(cancelled)
+}
+
 impl ActionProcessor<OrderBookConfig, OrderBookProcessorOutput> for OrderBookProcessorInput {
     async fn process(
         &self,
@@ -75,6 +122,11 @@ impl ActionProcessor<OrderBookConfig, OrderBookProcessorOutput> for OrderBookPro
 
         match self {
             OrderBookProcessorInput::PlaceOrder(_args) => {
+                // Nothing trades on a closed market, so its book doesn't match either
+                let market = ensure_market_open(app_conn, _args.market_id)?;
+                check_order_precision(app_conn, &market, _args)?;
+                check_price_band(app_conn, &market, _args).await?;
+
                 // Lock assets in wallet before anything
                 let mut args = _args.clone();
                 args.ask_amount = args
@@ -98,7 +150,7 @@ impl ActionProcessor<OrderBookConfig, OrderBookProcessorOutput> for OrderBookPro
                 // asspciate ask asset and grant kyc
                 associate_token(
                     app_conn,
-                    &mut app_config.wallet,
+                    &app_config.wallet,
                     AssociateTokenToWalletInputArgs {
                         wallet_id: args.wallet,
                         token: args.bid_asset,
@@ -108,7 +160,7 @@ impl ActionProcessor<OrderBookConfig, OrderBookProcessorOutput> for OrderBookPro
 
                 kyc_token(
                     app_conn,
-                    &mut app_config.wallet,
+                    &app_config.wallet,
                     GrantKYCInputArgs {
                         wallet_id: args.wallet,
                         token: args.bid_asset,
@@ -123,11 +175,14 @@ impl ActionProcessor<OrderBookConfig, OrderBookProcessorOutput> for OrderBookPro
                 // Emit order:placed event
                 if let Ok(io) = app_config.get_io() {
                     let event = OrderEvent::from(&order);
-                    let room = format!("orderbook:{}", order.market_id);
-                    let _ = io.to(room).emit("order:placed", &event).await;
+                    emit_order_event(&io, "order:placed", &event).await;
                 }
 
                 let matching_orders = get_matching_orders(app_conn, order.id).await?;
+                let maker_prices: HashMap<Uuid, BigDecimal> = matching_orders
+                    .iter()
+                    .map(|m| (m.id, m.price.clone()))
+                    .collect();
                 let (remaining_bid, unfilled_ask, trades) =
                     get_order_fill_trades(&order, matching_orders);
                 // Handle FillOrKill
@@ -141,8 +196,7 @@ impl ActionProcessor<OrderBookConfig, OrderBookProcessorOutput> for OrderBookPro
                     if let Ok(io) = app_config.get_io() {
                         let mut event = OrderEvent::from(&order);
                         event.status = "Cancelled".to_string();
-                        let room = format!("orderbook:{}", order.market_id);
-                        let _ = io.to(room).emit("order:cancelled", &event).await;
+                        emit_order_event(&io, "order:cancelled", &event).await;
                     }
 
                     return Ok(OrderBookProcessorOutput::PlaceOrder(OrderFillResult {
@@ -165,7 +219,18 @@ impl ActionProcessor<OrderBookConfig, OrderBookProcessorOutput> for OrderBookPro
                 }
 
                 // Settle orders
-                settle_order(&mut app_config.wallet, app_conn, order.id).await?;
+                if let Err(e) = settle_order(&app_config.wallet, app_conn, order.id).await {
+                    emit_system_event(
+                        app_config,
+                        SystemEvent::SettlementFailed {
+                            order_id: order.id,
+                            market_id: order.market_id,
+                            error: e.to_string(),
+                        },
+                    )
+                    .await;
+                    return Err(e);
+                }
 
                 // Handle ImmediateOrCancel after settlement
                 let final_status = if let Some(FillMode::ImmediateOrCancel) = args.mode {
@@ -194,32 +259,59 @@ impl ActionProcessor<OrderBookConfig, OrderBookProcessorOutput> for OrderBookPro
                         let trade_event = TradeEvent {
                             order_id: order.id,
                             market_id: order.market_id,
+                            market_symbol: market.symbol.clone(),
+                            wallet: order.wallet,
+                            bid_asset: order.bid_asset,
+                            ask_asset: order.ask_asset,
                             trade_ids: matched_trades.clone(),
                             bid_amount_filled: bid_filled.to_string(),
                             ask_amount_filled: ask_filled.to_string(),
                             status: format!("{:?}", final_status),
                         };
                         let trades_room = format!("trades:{}", order.market_id);
-                        let _ = io.to(trades_room).emit("trade:executed", &trade_event).await;
+                        broadcast(&io, trades_room, "trade:executed", &trade_event).await;
+                        let account_room = format!("account:{}", order.wallet);
+                        broadcast(&io, account_room, "trade:executed", &trade_event).await;
+                    }
+                }
+
+                // Feed forming candles for both sides of the market
+                if !trades.is_empty() {
+                    if let Ok(io) = app_config.get_io() {
+                        let now = chrono::Utc::now().naive_utc();
+                        for trade in &trades {
+                            let Some(price) = maker_prices.get(&trade.maker_order_id) else {
+                                continue;
+                            };
+                            for asset in [order.bid_asset, order.ask_asset] {
+                                let updates = candles::record_trade(
+                                    order.market_id,
+                                    asset,
+                                    price,
+                                    &trade.taker_filled_amount,
+                                    now,
+                                );
+                                candles::emit_candle_updates(&io, updates).await;
+                            }
+                        }
                     }
                 }
 
                 // Emit order status event
                 if let Ok(io) = app_config.get_io() {
-                    let room = format!("orderbook:{}", order.market_id);
                     let mut event = OrderEvent::from(&order);
                     match final_status {
                         OrderFillStatus::Filled => {
                             event.status = "Closed".to_string();
-                            let _ = io.to(room).emit("order:filled", &event).await;
+                            emit_order_event(&io, "order:filled", &event).await;
                         }
                         OrderFillStatus::Partial => {
                             event.status = "Open".to_string();
-                            let _ = io.to(room).emit("order:updated", &event).await;
+                            emit_order_event(&io, "order:updated", &event).await;
                         }
                         OrderFillStatus::Cancelled => {
                             event.status = "Cancelled".to_string();
-                            let _ = io.to(room).emit("order:cancelled", &event).await;
+                            emit_order_event(&io, "order:cancelled", &event).await;
                         }
                     }
                 }
@@ -264,6 +356,85 @@ impl ActionProcessor<OrderBookConfig, OrderBookProcessorOutput> for OrderBookPro
 
                 Ok(OrderBookProcessorOutput::GetOrders(orders))
             }
+            OrderBookProcessorInput::CancelOrder(args) => {
+                let order = get_wallet_order(app_conn, args.order_id, args.wallet)?;
+                let cancelled = cancel_order(app_config, app_conn, &order).await?;
+
+                Ok(OrderBookProcessorOutput::CancelOrder(cancelled))
+            }
+            OrderBookProcessorInput::ModifyOrder(args) => {
+                let order = get_wallet_order(app_conn, args.order_id, args.wallet)?;
+
+                let replacement = NewOrderBookRecord {
+                    wallet: order.wallet,
+                    market_id: order.market_id,
+                    bid_asset: order.bid_asset,
+                    ask_asset: order.ask_asset,
+                    bid_amount: args
+                        .bid_amount
+                        .clone()
+                        .unwrap_or(&order.bid_amount - &order.filled_bid_amount),
+                    ask_amount: args
+                        .ask_amount
+                        .clone()
+                        .unwrap_or(&order.ask_amount - &order.filled_ask_amount),
+                    price: args.price.clone().unwrap_or(order.price.clone()),
+                    mode: Some(order.mode.clone()),
+                    expires_at: order.expires_at,
+                    order_type: Some(order.order_type.clone()),
+                };
+                let zero = BigDecimal::from(0);
+                if replacement.bid_amount <= zero
+                    || replacement.ask_amount <= zero
+                    || replacement.price <= zero
+                {
+                    return Err(anyhow!("Amended orders need a positive price and amounts"));
+                }
+
+                // Everything that can turn the replacement away is checked while
+                // the original is still on the book
+                let market = ensure_market_open(app_conn, replacement.market_id)?;
+                check_order_precision(app_conn, &market, &replacement)?;
+                check_price_band(app_conn, &market, &replacement).await?;
+
+                cancel_order(app_config, app_conn, &order).await?;
+                let placing_from =
+                    diesel::select(diesel::dsl::now).get_result::<NaiveDateTime>(app_conn)?;
+
+                let placed = Box::pin(OrderBookProcessorInput::PlaceOrder(replacement).process(
+                    app_config,
+                    local_config,
+                    Some(&mut *app_conn),
+                ))
+                .await;
+
+                match placed {
+                    Ok(OrderBookProcessorOutput::PlaceOrder(result)) => {
+                        Ok(OrderBookProcessorOutput::ModifyOrder(result))
+                    }
+                    Ok(_) => Err(anyhow!("Unexpected output placing amended order")),
+                    Err(e) => {
+                        // Once the replacement is on the book it owns the lock, so the
+                        // original only comes back if the replacement never got there
+                        let replaced = orderbook::table
+                            .filter(orderbook::wallet.eq(order.wallet))
+                            .filter(orderbook::market_id.eq(order.market_id))
+                            .filter(orderbook::created_at.ge(placing_from))
+                            .count()
+                            .get_result::<i64>(app_conn)?
+                            > 0;
+                        if !replaced {
+                            let restored =
+                                reopen_cancelled_order(app_config, app_conn, order.id).await?;
+                            if let Ok(io) = app_config.get_io() {
+                                let event = OrderEvent::from(&restored);
+                                emit_order_event(&io, "order:placed", &event).await;
+                            }
+                        }
+                        Err(e)
+                    }
+                }
+            }
         }
     }
 }
@@ -19,6 +19,28 @@ pub enum OrderBookProcessorInput {
     PlaceOrder(NewOrderBookRecord),
     GetOrder(Uuid),
     GetOrders(GetOrdersFilter),
+    CancelOrder(CancelOrderInputArgs),
+    ModifyOrder(ModifyOrderInputArgs),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CancelOrderInputArgs {
+    pub order_id: Uuid,
+    /// When set, the order has to belong to this wallet
+    pub wallet: Option<Uuid>,
+}
+
+/// Amending cancels the order and places its replacement, so the new order
+/// loses the old one's time priority and gets a new id.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ModifyOrderInputArgs {
+    pub order_id: Uuid,
+    /// When set, the order has to belong to this wallet
+    pub wallet: Option<Uuid>,
+    /// Left unset, these keep the order's price and unfilled amounts
+    pub price: Option<BigDecimal>,
+    pub bid_amount: Option<BigDecimal>,
+    pub ask_amount: Option<BigDecimal>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -42,4 +64,6 @@ pub enum OrderBookProcessorOutput {
     PlaceOrder(OrderFillResult),
     GetOrder(OrderBookRecord),
     GetOrders(Vec<OrderBookRecord>),
+    CancelOrder(OrderBookRecord),
+    ModifyOrder(OrderFillResult),
 }
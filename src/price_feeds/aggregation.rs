@@ -0,0 +1,186 @@
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    price_feeds::{
+        db_types::{OracleSourceKind, OracleSourceRow},
+        sources::{PriceSource, Source},
+    },
+    utils::commons::DbConn,
+};
+
+/// How far, in basis points, a source may sit from the median of all the
+/// sources before it's left out, from ORACLE_MAX_DEVIATION_BPS.
+pub fn max_deviation_bps() -> u32 {
+    std::env::var("ORACLE_MAX_DEVIATION_BPS")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(500)
+}
+
+/// Sources that must agree before a price is published, from
+/// ORACLE_MIN_SOURCES.
+pub fn min_sources() -> usize {
+    std::env::var("ORACLE_MIN_SOURCES")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(1)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SourcePrice {
+    pub source_id: Uuid,
+    pub kind: OracleSourceKind,
+    pub price: Option<BigDecimal>,
+    /// When the source says its price was set, if it says
+    pub observed_at: Option<NaiveDateTime>,
+    /// Where the price came from: the feed, trade or figure behind it
+    pub provenance: Value,
+    /// Why the source had no price
+    pub error: Option<String>,
+    /// Too far from the other sources to be counted
+    pub rejected: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AggregatePrice {
+    pub lending_pool_id: Uuid,
+    pub asset_id: Uuid,
+    /// Median of the accepted sources, in whole reserve tokens per whole
+    /// token of the asset
+    pub price: Option<BigDecimal>,
+    pub accepted: usize,
+    /// Enough sources agreed for the publisher to use the price
+    pub publishable: bool,
+    pub sources: Vec<SourcePrice>,
+}
+
+pub fn median(prices: &[BigDecimal]) -> Option<BigDecimal> {
+    if prices.is_empty() {
+        return None;
+    }
+    let mut sorted = prices.to_vec();
+    sorted.sort();
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 0 {
+        Some((&sorted[mid - 1] + &sorted[mid]) / BigDecimal::from(2))
+    } else {
+        Some(sorted[mid].clone())
+    }
+}
+
+/// Which prices are within `max_bps` of the median of them all.
+pub fn within_deviation(prices: &[BigDecimal], max_bps: u32) -> Vec<bool> {
+    let Some(mid) = median(prices) else {
+        return Vec::new();
+    };
+    if mid <= BigDecimal::from(0) {
+        return vec![true; prices.len()];
+    }
+    let limit = &mid * BigDecimal::from(max_bps) / BigDecimal::from(10000);
+    prices.iter().map(|p| (p - &mid).abs() <= limit).collect()
+}
+
+/// Ask every enabled source for the pool's asset, drop the outliers and
+/// take the median of what's left.
+pub async fn aggregate(conn: DbConn<'_>, pool: Uuid, asset: Uuid) -> Result<AggregatePrice> {
+    let configured = {
+        use crate::schema::oracle_price_sources::dsl::*;
+
+        oracle_price_sources
+            .filter(lending_pool_id.eq(pool))
+            .filter(asset_id.eq(asset))
+            .filter(enabled.eq(true))
+            .order(created_at.asc())
+            .get_results::<OracleSourceRow>(conn)?
+    };
+
+    let mut sources = Vec::with_capacity(configured.len());
+    for source in configured {
+        let fetched = match Source::from_row(&source) {
+            Ok(feed) => feed.fetch(conn).await,
+            Err(e) => Err(e),
+        };
+        sources.push(match fetched {
+            Ok(quote) => SourcePrice {
+                source_id: source.id,
+                kind: source.kind,
+                price: Some(quote.price),
+                observed_at: quote.observed_at,
+                provenance: quote.provenance,
+                error: None,
+                rejected: false,
+            },
+            Err(e) => SourcePrice {
+                source_id: source.id,
+                kind: source.kind,
+                price: None,
+                observed_at: None,
+                provenance: Value::Null,
+                error: Some(e.to_string()),
+                rejected: false,
+            },
+        });
+    }
+
+    let quoted: Vec<BigDecimal> = sources.iter().filter_map(|s| s.price.clone()).collect();
+    let keep = within_deviation(&quoted, max_deviation_bps());
+    let mut accepted = Vec::new();
+    for (source, keep) in sources.iter_mut().filter(|s| s.price.is_some()).zip(keep) {
+        if keep {
+            accepted.extend(source.price.clone());
+        } else {
+            source.rejected = true;
+        }
+    }
+
+    Ok(AggregatePrice {
+        lending_pool_id: pool,
+        asset_id: asset,
+        price: median(&accepted),
+        accepted: accepted.len(),
+        publishable: !accepted.is_empty() && accepted.len() >= min_sources(),
+        sources,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn prices(values: &[&str]) -> Vec<BigDecimal> {
+        values
+            .iter()
+            .map(|v| BigDecimal::from_str(v).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn median_of_odd_and_even_counts() {
+        assert_eq!(median(&prices(&["3", "1", "2"])), Some(BigDecimal::from(2)));
+        assert_eq!(
+            median(&prices(&["4", "1", "2", "3"])),
+            Some(BigDecimal::from_str("2.5").unwrap())
+        );
+        assert_eq!(median(&[]), None);
+    }
+
+    #[test]
+    fn drops_prices_far_from_the_median() {
+        let keep = within_deviation(&prices(&["100", "101", "99", "150"]), 500);
+        assert_eq!(keep, vec![true, true, true, false]);
+    }
+
+    #[test]
+    fn keeps_everything_within_the_band() {
+        let keep = within_deviation(&prices(&["100", "105"]), 500);
+        assert_eq!(keep, vec![true, true]);
+    }
+}
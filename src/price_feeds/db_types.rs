@@ -0,0 +1,74 @@
+use crate::schema::oracle_price_sources as OraclePriceSourcesTable;
+use crate::schema::oracle_source_quotes as OracleSourceQuotesTable;
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::OracleSourceKind"]
+#[serde(rename_all = "snake_case")]
+pub enum OracleSourceKind {
+    /// A price an operator keeps up to date, `{"price": "1.25"}`
+    Fixed,
+    /// The latest fill on a market quoting the asset in the pool's reserve,
+    /// `{"market_id": "..."}`
+    LastTrade,
+    /// The time-weighted average of the same market's fills,
+    /// `{"market_id": "...", "window_secs": 1800}`
+    Twap,
+    /// A Pyth feed pulled from Hermes, `{"feed_id": "0x..."}`
+    Pyth,
+    /// A REST ticker answering JSON, `{"url": "...", "pointer": "/price"}`
+    HttpTicker,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = OraclePriceSourcesTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct OracleSourceRow {
+    pub id: Uuid,
+    pub lending_pool_id: Uuid,
+    pub asset_id: Uuid,
+    pub kind: OracleSourceKind,
+    pub params: Value,
+    pub enabled: bool,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = OraclePriceSourcesTable)]
+pub struct CreateOracleSource {
+    pub lending_pool_id: Uuid,
+    pub asset_id: Uuid,
+    pub kind: OracleSourceKind,
+    pub params: Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = OracleSourceQuotesTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct OracleSourceQuoteRow {
+    pub id: Uuid,
+    pub source_id: Uuid,
+    pub price: Option<BigDecimal>,
+    pub error: Option<String>,
+    pub rejected: bool,
+    pub observed_at: Option<NaiveDateTime>,
+    pub provenance: Value,
+    pub fetched_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = OracleSourceQuotesTable)]
+pub struct CreateOracleSourceQuote {
+    pub source_id: Uuid,
+    pub price: Option<BigDecimal>,
+    pub error: Option<String>,
+    pub rejected: bool,
+    pub observed_at: Option<NaiveDateTime>,
+    pub provenance: Value,
+}
@@ -0,0 +1,195 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::{
+    price_feeds::{
+        db_types::OracleSourceKind,
+        sources::{PriceSource, Quote},
+    },
+    utils::commons::DbConn,
+};
+
+/// How long an external feed may take to answer.
+const FEED_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Age past which a Pyth price is refused when the source doesn't give
+/// `max_age_secs`.
+const DEFAULT_PYTH_MAX_AGE_SECS: i64 = 120;
+
+fn client() -> Result<Client> {
+    Ok(Client::builder().timeout(FEED_TIMEOUT).build()?)
+}
+
+/// Hermes endpoint Pyth prices are pulled from, from PYTH_HERMES_URL.
+fn hermes_url() -> String {
+    std::env::var("PYTH_HERMES_URL").unwrap_or_else(|_| "https://hermes.pyth.network".to_string())
+}
+
+fn decimal_from(value: &Value) -> Option<BigDecimal> {
+    match value {
+        Value::String(s) => BigDecimal::from_str(s.trim()).ok(),
+        Value::Number(n) => BigDecimal::from_str(&n.to_string()).ok(),
+        _ => None,
+    }
+}
+
+#[derive(Deserialize)]
+struct HermesResponse {
+    parsed: Vec<HermesUpdate>,
+}
+
+#[derive(Deserialize)]
+struct HermesUpdate {
+    id: String,
+    price: HermesPrice,
+}
+
+#[derive(Deserialize)]
+struct HermesPrice {
+    price: String,
+    conf: String,
+    expo: i32,
+    publish_time: i64,
+}
+
+/// A Pyth price feed pulled from Hermes, `{"feed_id": "0x...",
+/// "max_age_secs": 120}`. Pyth quotes in USD, so the pool's reserve should
+/// be a dollar token.
+pub struct PythFeed {
+    pub feed_id: String,
+    pub max_age_secs: i64,
+}
+
+impl PythFeed {
+    pub fn from_params(params: &Value) -> Result<Self> {
+        let feed_id = params
+            .get("feed_id")
+            .and_then(Value::as_str)
+            .map(|id| id.trim_start_matches("0x").to_lowercase())
+            .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit()))
+            .ok_or_else(|| anyhow!("Pyth sources need a hex feed_id"))?;
+        let max_age_secs = match params.get("max_age_secs") {
+            None | Some(Value::Null) => DEFAULT_PYTH_MAX_AGE_SECS,
+            Some(v) => v
+                .as_i64()
+                .filter(|secs| *secs > 0)
+                .ok_or_else(|| anyhow!("max_age_secs must be a positive number of seconds"))?,
+        };
+        Ok(Self {
+            feed_id,
+            max_age_secs,
+        })
+    }
+}
+
+impl PriceSource for PythFeed {
+    fn kind(&self) -> OracleSourceKind {
+        OracleSourceKind::Pyth
+    }
+
+    async fn fetch(&self, _conn: DbConn<'_>) -> Result<Quote> {
+        let url = format!("{}/v2/updates/price/latest", hermes_url());
+        let response = client()?
+            .get(&url)
+            .query(&[("ids[]", self.feed_id.as_str()), ("parsed", "true")])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<HermesResponse>()
+            .await?;
+
+        let update = response
+            .parsed
+            .into_iter()
+            .find(|u| {
+                u.id.trim_start_matches("0x")
+                    .eq_ignore_ascii_case(&self.feed_id)
+            })
+            .ok_or_else(|| anyhow!("Hermes returned no price for feed {}", self.feed_id))?;
+
+        let published = DateTime::<Utc>::from_timestamp(update.price.publish_time, 0)
+            .ok_or_else(|| anyhow!("Invalid publish time from Hermes"))?
+            .naive_utc();
+        let age_secs = (Utc::now().naive_utc() - published).num_seconds();
+        if age_secs > self.max_age_secs {
+            return Err(anyhow!("Pyth price is {}s old", age_secs));
+        }
+
+        // the price is an integer scaled by 10^expo
+        let price = BigDecimal::from_str(&format!("{}e{}", update.price.price, update.price.expo))?;
+
+        Ok(Quote {
+            price,
+            observed_at: Some(published),
+            provenance: json!({
+                "url": url,
+                "feed_id": self.feed_id,
+                "price": update.price.price,
+                "conf": update.price.conf,
+                "expo": update.price.expo,
+                "publish_time": update.price.publish_time,
+            }),
+        })
+    }
+}
+
+/// Any REST ticker answering JSON, such as an exchange's last price,
+/// `{"url": "https://...", "pointer": "/price"}`. `pointer` is a JSON
+/// pointer to the price in the response, which may be a string or number.
+pub struct HttpTicker {
+    pub url: String,
+    pub pointer: String,
+}
+
+impl HttpTicker {
+    pub fn from_params(params: &Value) -> Result<Self> {
+        let url = params
+            .get("url")
+            .and_then(Value::as_str)
+            .filter(|u| u.starts_with("https://") || u.starts_with("http://"))
+            .ok_or_else(|| anyhow!("Ticker sources need an http(s) url"))?
+            .to_string();
+        let pointer = params
+            .get("pointer")
+            .and_then(Value::as_str)
+            .filter(|p| p.starts_with('/'))
+            .ok_or_else(|| anyhow!("Ticker sources need a JSON pointer, e.g. /price"))?
+            .to_string();
+        Ok(Self { url, pointer })
+    }
+}
+
+impl PriceSource for HttpTicker {
+    fn kind(&self) -> OracleSourceKind {
+        OracleSourceKind::HttpTicker
+    }
+
+    async fn fetch(&self, _conn: DbConn<'_>) -> Result<Quote> {
+        let body = client()?
+            .get(&self.url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Value>()
+            .await?;
+
+        let raw = body
+            .pointer(&self.pointer)
+            .ok_or_else(|| anyhow!("Nothing at {} in the ticker response", self.pointer))?;
+        let price =
+            decimal_from(raw).ok_or_else(|| anyhow!("{} isn't a price: {}", self.pointer, raw))?;
+
+        Ok(Quote {
+            price,
+            observed_at: None,
+            provenance: json!({ "url": self.url, "pointer": self.pointer, "value": raw }),
+        })
+    }
+}
@@ -0,0 +1,7 @@
+pub mod aggregation;
+pub mod db_types;
+pub mod external;
+pub mod operations;
+pub mod publisher;
+pub mod sources;
+pub mod twap;
@@ -0,0 +1,118 @@
+use anyhow::Result;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    price_feeds::{
+        aggregation::AggregatePrice,
+        db_types::{
+            CreateOracleSource, CreateOracleSourceQuote, OracleSourceKind, OracleSourceQuoteRow,
+            OracleSourceRow,
+        },
+        sources::validate,
+    },
+    utils::commons::DbConn,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AddOracleSourceInputArgs {
+    pub lending_pool_id: Uuid,
+    pub asset_id: Uuid,
+    pub kind: OracleSourceKind,
+    #[serde(default)]
+    pub params: Value,
+}
+
+pub fn add_oracle_source(
+    conn: DbConn<'_>,
+    input: AddOracleSourceInputArgs,
+) -> Result<OracleSourceRow> {
+    validate(
+        conn,
+        input.lending_pool_id,
+        input.asset_id,
+        input.kind,
+        &input.params,
+    )?;
+
+    use crate::schema::oracle_price_sources::dsl::*;
+
+    Ok(diesel::insert_into(oracle_price_sources)
+        .values(CreateOracleSource {
+            lending_pool_id: input.lending_pool_id,
+            asset_id: input.asset_id,
+            kind: input.kind,
+            params: input.params,
+        })
+        .get_result::<OracleSourceRow>(conn)?)
+}
+
+pub fn get_oracle_sources(conn: DbConn<'_>) -> Result<Vec<OracleSourceRow>> {
+    use crate::schema::oracle_price_sources::dsl::*;
+
+    Ok(oracle_price_sources
+        .filter(enabled.eq(true))
+        .order(created_at.desc())
+        .get_results::<OracleSourceRow>(conn)?)
+}
+
+/// Stop asking a source for prices. The row is kept so old aggregates can
+/// still be traced back to it.
+pub fn disable_oracle_source(conn: DbConn<'_>, source: Uuid) -> Result<OracleSourceRow> {
+    use crate::schema::oracle_price_sources::dsl::*;
+
+    Ok(diesel::update(oracle_price_sources.find(source))
+        .set(enabled.eq(false))
+        .get_result::<OracleSourceRow>(conn)?)
+}
+
+/// Pools and assets with at least one enabled source.
+pub fn oracle_targets(conn: DbConn<'_>) -> Result<Vec<(Uuid, Uuid)>> {
+    use crate::schema::oracle_price_sources::dsl::*;
+
+    Ok(oracle_price_sources
+        .filter(enabled.eq(true))
+        .select((lending_pool_id, asset_id))
+        .distinct()
+        .get_results::<(Uuid, Uuid)>(conn)?)
+}
+
+/// Keep what every source said in an aggregation, rejected and failed ones
+/// included, so a published price can be traced to its inputs.
+pub fn record_quotes(conn: DbConn<'_>, aggregate: &AggregatePrice) -> Result<()> {
+    use crate::schema::oracle_source_quotes::dsl::*;
+
+    let rows: Vec<CreateOracleSourceQuote> = aggregate
+        .sources
+        .iter()
+        .map(|s| CreateOracleSourceQuote {
+            source_id: s.source_id,
+            price: s.price.clone(),
+            error: s.error.clone(),
+            rejected: s.rejected,
+            observed_at: s.observed_at,
+            provenance: s.provenance.clone(),
+        })
+        .collect();
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    diesel::insert_into(oracle_source_quotes)
+        .values(&rows)
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Recent quotes from one source, newest first.
+pub fn get_source_quotes(conn: DbConn<'_>, source: Uuid) -> Result<Vec<OracleSourceQuoteRow>> {
+    use crate::schema::oracle_source_quotes::dsl::*;
+
+    Ok(oracle_source_quotes
+        .filter(source_id.eq(source))
+        .order(fetched_at.desc())
+        .limit(100)
+        .get_results::<OracleSourceQuoteRow>(conn)?)
+}
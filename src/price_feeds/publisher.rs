@@ -0,0 +1,229 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use diesel::prelude::*;
+use serde_json::{Value, json};
+
+use crate::{
+    asset_book::db_types::AssetBookRecord,
+    lending_pool::{
+        db_types::LendingPoolRecord,
+        oracle::{PriceOracle, get_price_oracle, publish_price},
+    },
+    price_feeds::{
+        aggregation::aggregate,
+        operations::{oracle_targets, record_quotes},
+    },
+    utils::app_config::AppConfig,
+};
+
+/// Name the publisher's prices are recorded under in the price history.
+pub const PUBLISHER: &str = "oracle-publisher";
+
+/// Seconds between oracle publications, from ORACLE_PUBLISH_INTERVAL_SECS.
+fn poll_interval() -> Duration {
+    let secs = std::env::var("ORACLE_PUBLISH_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(60);
+    Duration::from_secs(secs)
+}
+
+/// Move from the last published price, in basis points, that's worth a
+/// contract call, from ORACLE_DEVIATION_THRESHOLD_BPS.
+fn deviation_threshold_bps() -> u32 {
+    std::env::var("ORACLE_DEVIATION_THRESHOLD_BPS")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(50)
+}
+
+/// Age at which a price is republished even if it hasn't moved, from
+/// ORACLE_HEARTBEAT_SECS. Keep it under ORACLE_STALE_AFTER_SECS.
+fn heartbeat_secs() -> i64 {
+    std::env::var("ORACLE_HEARTBEAT_SECS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(1800)
+}
+
+/// Why a new price should go on-chain, or `None` if the last one will do:
+/// there isn't one yet, the price has moved past the threshold, or the last
+/// one is due a heartbeat.
+pub fn publish_reason(
+    last: Option<&PriceOracle>,
+    price: &BigDecimal,
+    threshold_bps: u32,
+    heartbeat_secs: i64,
+) -> Option<&'static str> {
+    let Some(last) = last else {
+        return Some("first price");
+    };
+    if last.price <= BigDecimal::from(0) {
+        return Some("first price");
+    }
+
+    let moved_bps = (price - &last.price).abs() * BigDecimal::from(10000) / &last.price;
+    if moved_bps >= BigDecimal::from(threshold_bps) {
+        Some("deviation")
+    } else if last.age_secs() >= heartbeat_secs {
+        Some("heartbeat")
+    } else {
+        None
+    }
+}
+
+/// Aggregate a price for every pool asset with sources and publish the ones
+/// that have moved enough or are due a heartbeat. Targets without enough
+/// agreeing sources keep their last price.
+pub async fn run_once(app_config: &AppConfig) -> Result<()> {
+    let mut conn = app_config.pool.get()?;
+    let wallet = &app_config.wallet;
+
+    for (pool_id, asset_id) in oracle_targets(&mut conn)? {
+        let aggregate = aggregate(&mut conn, pool_id, asset_id).await?;
+        record_quotes(&mut conn, &aggregate)?;
+        let price = match (aggregate.publishable, aggregate.price) {
+            (true, Some(price)) => price,
+            _ => {
+                println!(
+                    "Oracle publisher skipped asset {} in pool {}: {} of {} sources usable",
+                    asset_id,
+                    pool_id,
+                    aggregate.accepted,
+                    aggregate.sources.len()
+                );
+                continue;
+            }
+        };
+
+        // the oracle takes reserve base units per whole token
+        let reserve = {
+            use crate::schema::asset_book::dsl::*;
+
+            let pool = LendingPoolRecord::get(&mut conn, pool_id)?;
+            asset_book
+                .find(pool.reserve_asset)
+                .get_result::<AssetBookRecord>(&mut conn)?
+        };
+        let scaled = (price * BigDecimal::from(10i64.pow(reserve.decimals as u32))).round(0);
+        if scaled <= BigDecimal::from(0) {
+            println!(
+                "Oracle publisher skipped asset {} in pool {}: price rounds to zero",
+                asset_id, pool_id
+            );
+            continue;
+        }
+
+        let last = get_price_oracle(&mut conn, pool_id, asset_id).ok();
+        let Some(reason) = publish_reason(
+            last.as_ref(),
+            &scaled,
+            deviation_threshold_bps(),
+            heartbeat_secs(),
+        ) else {
+            continue;
+        };
+
+        // the sources the median was taken over
+        let used: Vec<Value> = aggregate
+            .sources
+            .iter()
+            .filter(|s| s.price.is_some() && !s.rejected)
+            .map(|s| {
+                json!({
+                    "source_id": s.source_id,
+                    "kind": s.kind,
+                    "price": s.price,
+                    "provenance": s.provenance,
+                })
+            })
+            .collect();
+
+        match publish_price(
+            &mut conn,
+            wallet,
+            pool_id,
+            asset_id,
+            scaled.clone(),
+            PUBLISHER,
+            Value::Array(used),
+        )
+        .await
+        {
+            Ok(()) => println!(
+                "Oracle publisher set asset {} in pool {} to {} ({})",
+                asset_id, pool_id, scaled, reason
+            ),
+            Err(e) => println!(
+                "Oracle publisher failed for asset {} in pool {}: {:?}",
+                asset_id, pool_id, e
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+/// Background task publishing aggregated oracle prices.
+pub async fn run_publisher(app_config: AppConfig) {
+    let mut ticker = tokio::time::interval(poll_interval());
+    loop {
+        ticker.tick().await;
+        if let Err(e) = run_once(&app_config).await {
+            println!("Oracle publisher error: {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn published(price: i64, age_secs: i64) -> PriceOracle {
+        PriceOracle {
+            id: Uuid::nil(),
+            lending_pool_id: Uuid::nil(),
+            asset_id: Uuid::nil(),
+            price: BigDecimal::from(price),
+            recorded_at: Utc::now().naive_utc() - chrono::Duration::seconds(age_secs),
+        }
+    }
+
+    #[test]
+    fn publishes_when_nothing_is_on_chain() {
+        assert_eq!(
+            publish_reason(None, &BigDecimal::from(100), 50, 1800),
+            Some("first price")
+        );
+    }
+
+    #[test]
+    fn skips_small_moves_until_the_heartbeat() {
+        let last = published(10000, 60);
+        assert_eq!(
+            publish_reason(Some(&last), &BigDecimal::from(10040), 50, 1800),
+            None
+        );
+
+        let old = published(10000, 1900);
+        assert_eq!(
+            publish_reason(Some(&old), &BigDecimal::from(10040), 50, 1800),
+            Some("heartbeat")
+        );
+    }
+
+    #[test]
+    fn publishes_moves_past_the_threshold() {
+        let last = published(10000, 60);
+        assert_eq!(
+            publish_reason(Some(&last), &BigDecimal::from(9950), 50, 1800),
+            Some("deviation")
+        );
+    }
+}
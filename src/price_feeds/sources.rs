@@ -0,0 +1,232 @@
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use crate::{
+    lending_pool::db_types::LendingPoolRecord,
+    market::db_types::MarketRecord,
+    price_feeds::{
+        db_types::{OracleSourceKind, OracleSourceRow},
+        external::{HttpTicker, PythFeed},
+        twap::{market_twap, window_param},
+    },
+    utils::commons::DbConn,
+};
+
+/// A price from one source, with enough about where it came from to trace
+/// a published figure back to it.
+#[derive(Debug, Clone)]
+pub struct Quote {
+    /// Whole reserve tokens per whole token of the asset
+    pub price: BigDecimal,
+    /// When the source says the price was set, if it says
+    pub observed_at: Option<NaiveDateTime>,
+    pub provenance: Value,
+}
+
+/// Somewhere the oracle can get a price from. Adding one means implementing
+/// this, giving it an `OracleSourceKind` and building it in
+/// `Source::from_params`.
+pub trait PriceSource {
+    fn kind(&self) -> OracleSourceKind;
+
+    async fn fetch(&self, conn: DbConn<'_>) -> Result<Quote>;
+}
+
+pub struct FixedPrice {
+    pub price: BigDecimal,
+}
+
+impl FixedPrice {
+    fn from_params(params: &Value) -> Result<Self> {
+        let raw = match params.get("price") {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Number(n)) => n.to_string(),
+            _ => return Err(anyhow!("Fixed sources need a price")),
+        };
+        let price = BigDecimal::from_str(&raw).map_err(|_| anyhow!("Invalid price {}", raw))?;
+        if price <= BigDecimal::from(0) {
+            return Err(anyhow!("Fixed prices must be positive"));
+        }
+        Ok(Self { price })
+    }
+}
+
+impl PriceSource for FixedPrice {
+    fn kind(&self) -> OracleSourceKind {
+        OracleSourceKind::Fixed
+    }
+
+    async fn fetch(&self, _conn: DbConn<'_>) -> Result<Quote> {
+        Ok(Quote {
+            price: self.price.clone(),
+            observed_at: None,
+            provenance: json!({ "price": self.price.to_string() }),
+        })
+    }
+}
+
+/// The latest fill on a market, at the maker's price.
+pub struct LastTrade {
+    pub market_id: Uuid,
+}
+
+impl PriceSource for LastTrade {
+    fn kind(&self) -> OracleSourceKind {
+        OracleSourceKind::LastTrade
+    }
+
+    async fn fetch(&self, conn: DbConn<'_>) -> Result<Quote> {
+        use crate::schema::orderbook;
+        use crate::schema::orderbooktrades;
+
+        let (trade_id, traded_at, price) = orderbooktrades::table
+            .inner_join(orderbook::table.on(orderbook::id.eq(orderbooktrades::maker_order_id)))
+            .filter(orderbook::market_id.eq(self.market_id))
+            .order(orderbooktrades::created_at.desc())
+            .select((
+                orderbooktrades::id,
+                orderbooktrades::created_at,
+                orderbook::price,
+            ))
+            .first::<(Uuid, NaiveDateTime, BigDecimal)>(conn)
+            .optional()?
+            .ok_or_else(|| anyhow!("No trades on the market yet"))?;
+
+        Ok(Quote {
+            price,
+            observed_at: Some(traded_at),
+            provenance: json!({ "market_id": self.market_id, "trade_id": trade_id }),
+        })
+    }
+}
+
+pub struct Twap {
+    pub market_id: Uuid,
+    pub window_secs: i64,
+}
+
+impl PriceSource for Twap {
+    fn kind(&self) -> OracleSourceKind {
+        OracleSourceKind::Twap
+    }
+
+    async fn fetch(&self, conn: DbConn<'_>) -> Result<Quote> {
+        Ok(Quote {
+            price: market_twap(conn, self.market_id, self.window_secs)?,
+            observed_at: None,
+            provenance: json!({ "market_id": self.market_id, "window_secs": self.window_secs }),
+        })
+    }
+}
+
+pub enum Source {
+    Fixed(FixedPrice),
+    LastTrade(LastTrade),
+    Twap(Twap),
+    Pyth(PythFeed),
+    HttpTicker(HttpTicker),
+}
+
+fn market_param(params: &Value) -> Result<Uuid> {
+    params
+        .get("market_id")
+        .and_then(Value::as_str)
+        .and_then(|s| Uuid::parse_str(s).ok())
+        .ok_or_else(|| anyhow!("Order book sources need a market_id"))
+}
+
+impl Source {
+    pub fn from_params(kind: OracleSourceKind, params: &Value) -> Result<Self> {
+        Ok(match kind {
+            OracleSourceKind::Fixed => Source::Fixed(FixedPrice::from_params(params)?),
+            OracleSourceKind::LastTrade => Source::LastTrade(LastTrade {
+                market_id: market_param(params)?,
+            }),
+            OracleSourceKind::Twap => Source::Twap(Twap {
+                market_id: market_param(params)?,
+                window_secs: window_param(params)?,
+            }),
+            OracleSourceKind::Pyth => Source::Pyth(PythFeed::from_params(params)?),
+            OracleSourceKind::HttpTicker => Source::HttpTicker(HttpTicker::from_params(params)?),
+        })
+    }
+
+    pub fn from_row(row: &OracleSourceRow) -> Result<Self> {
+        Self::from_params(row.kind, &row.params)
+    }
+
+    /// The order book market the source reads, for the kinds that read one.
+    fn market(&self) -> Option<Uuid> {
+        match self {
+            Source::LastTrade(s) => Some(s.market_id),
+            Source::Twap(s) => Some(s.market_id),
+            _ => None,
+        }
+    }
+}
+
+impl PriceSource for Source {
+    fn kind(&self) -> OracleSourceKind {
+        match self {
+            Source::Fixed(s) => s.kind(),
+            Source::LastTrade(s) => s.kind(),
+            Source::Twap(s) => s.kind(),
+            Source::Pyth(s) => s.kind(),
+            Source::HttpTicker(s) => s.kind(),
+        }
+    }
+
+    async fn fetch(&self, conn: DbConn<'_>) -> Result<Quote> {
+        let quote = match self {
+            Source::Fixed(s) => s.fetch(conn).await,
+            Source::LastTrade(s) => s.fetch(conn).await,
+            Source::Twap(s) => s.fetch(conn).await,
+            Source::Pyth(s) => s.fetch(conn).await,
+            Source::HttpTicker(s) => s.fetch(conn).await,
+        }?;
+
+        if quote.price <= BigDecimal::from(0) {
+            return Err(anyhow!("Source gave a price that isn't positive"));
+        }
+        Ok(quote)
+    }
+}
+
+/// Check a source's params before it's saved, so the publisher only meets
+/// sources that can produce a price.
+pub fn validate(
+    conn: DbConn<'_>,
+    pool: Uuid,
+    asset: Uuid,
+    kind: OracleSourceKind,
+    params: &Value,
+) -> Result<()> {
+    let Some(market_id) = Source::from_params(kind, params)?.market() else {
+        return Ok(());
+    };
+
+    let market = {
+        use crate::schema::markets::dsl::*;
+
+        markets
+            .find(market_id)
+            .get_result::<MarketRecord>(conn)
+            .map_err(|_| anyhow!("Market not found"))?
+    };
+    let reserve = LendingPoolRecord::get(conn, pool)?.reserve_asset;
+    // prices are read as reserve per asset, so the market has to quote the
+    // asset in the reserve
+    if market.asset_one != asset || market.asset_two != reserve {
+        return Err(anyhow!(
+            "Market {} doesn't trade the asset against the pool's reserve",
+            market.name
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use anyhow::{Result, anyhow};
+use bigdecimal::BigDecimal;
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::utils::commons::DbConn;
+
+/// Averaging window when a TWAP source doesn't give `window_secs`.
+pub const DEFAULT_WINDOW_SECS: i64 = 1800;
+
+pub fn window_param(params: &Value) -> Result<i64> {
+    match params.get("window_secs") {
+        None | Some(Value::Null) => Ok(DEFAULT_WINDOW_SECS),
+        Some(v) => v
+            .as_i64()
+            .filter(|secs| *secs > 0)
+            .ok_or_else(|| anyhow!("window_secs must be a positive number of seconds")),
+    }
+}
+
+/// Average of a stepwise price over `[start, end]`, each price counting for
+/// as long as it stood. `opening` is the price in force at `start`, if any;
+/// `fills` are `(time, price)` inside the window in time order.
+pub fn time_weighted_average(
+    opening: Option<&BigDecimal>,
+    fills: &[(NaiveDateTime, BigDecimal)],
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+) -> Option<BigDecimal> {
+    let mut steps: Vec<(NaiveDateTime, &BigDecimal)> = Vec::with_capacity(fills.len() + 1);
+    if let Some(price) = opening {
+        steps.push((start, price));
+    }
+    steps.extend(fills.iter().map(|(at, price)| (*at, price)));
+
+    let mut weighted = BigDecimal::from(0);
+    let mut total_ms = 0i64;
+    for (i, (from, price)) in steps.iter().enumerate() {
+        let until = steps.get(i + 1).map(|(at, _)| *at).unwrap_or(end);
+        let ms = (until - *from).num_milliseconds().max(0);
+        weighted += *price * BigDecimal::from(ms);
+        total_ms += ms;
+    }
+
+    if total_ms == 0 {
+        // everything landed on the same instant; fall back to the latest price
+        return steps.last().map(|(_, price)| (*price).clone());
+    }
+    Some(weighted / BigDecimal::from(total_ms))
+}
+
+/// Time-weighted average of the market's fills over the last `window_secs`,
+/// at the maker's price. Needs at least one fill inside the window so an old
+/// trade can't stand in for the market.
+pub fn market_twap(conn: DbConn<'_>, market: Uuid, window_secs: i64) -> Result<BigDecimal> {
+    use crate::schema::orderbook;
+    use crate::schema::orderbooktrades;
+
+    let end = Utc::now().naive_utc();
+    let start = end - Duration::seconds(window_secs);
+
+    let fills = orderbooktrades::table
+        .inner_join(orderbook::table.on(orderbook::id.eq(orderbooktrades::maker_order_id)))
+        .filter(orderbook::market_id.eq(market))
+        .filter(orderbooktrades::created_at.ge(start))
+        .filter(orderbooktrades::created_at.le(end))
+        .order(orderbooktrades::created_at.asc())
+        .select((orderbooktrades::created_at, orderbook::price))
+        .get_results::<(NaiveDateTime, BigDecimal)>(conn)?;
+    if fills.is_empty() {
+        return Err(anyhow!(
+            "No trades on the market in the last {}s",
+            window_secs
+        ));
+    }
+
+    let opening = orderbooktrades::table
+        .inner_join(orderbook::table.on(orderbook::id.eq(orderbooktrades::maker_order_id)))
+        .filter(orderbook::market_id.eq(market))
+        .filter(orderbooktrades::created_at.lt(start))
+        .order(orderbooktrades::created_at.desc())
+        .select(orderbook::price)
+        .first::<BigDecimal>(conn)
+        .optional()?;
+
+    time_weighted_average(opening.as_ref(), &fills, start, end)
+        .ok_or_else(|| anyhow!("No trades on the market in the last {}s", window_secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use std::str::FromStr;
+
+    fn at(secs: i64) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            + Duration::seconds(secs)
+    }
+
+    #[test]
+    fn weights_each_price_by_how_long_it_stood() {
+        let fills = vec![(at(60), BigDecimal::from(20))];
+        let avg = time_weighted_average(Some(&BigDecimal::from(10)), &fills, at(0), at(240));
+        // 10 for 60s, then 20 for 180s
+        assert_eq!(avg, Some(BigDecimal::from_str("17.5").unwrap()));
+    }
+
+    #[test]
+    fn a_spike_just_before_the_end_barely_moves_it() {
+        let fills = vec![
+            (at(0), BigDecimal::from(100)),
+            (at(599), BigDecimal::from(1000)),
+        ];
+        let avg = time_weighted_average(None, &fills, at(0), at(600)).unwrap();
+        assert!(avg < BigDecimal::from(102));
+    }
+
+    #[test]
+    fn no_fills_and_no_opening_price_is_none() {
+        assert_eq!(time_weighted_average(None, &[], at(0), at(60)), None);
+    }
+}
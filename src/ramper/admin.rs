@@ -0,0 +1,327 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Result, anyhow};
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    accounts::{
+        db_types::{AdminOperatorRecord, AdminRole},
+        operators::require_role,
+    },
+    ramper::{
+        Ramper,
+        db_types::{
+            FulfillmentStatus, RampDirection, RampFulfillmentRow, RampOrderRow, RampOrderStatus,
+        },
+        fulfillment::{get_fulfillment, reschedule_fulfillment, run_job, start_fulfillment},
+        operations::{finish_ramp_order, get_ramp_order, record_order_event},
+        providers::RampProvider,
+        reconciler::pending_timeout,
+        refunds::{RampOrderDetails, get_order_details},
+    },
+    utils::commons::{DbConn, TaskWallet},
+};
+
+/// In-flight orders listed on the summary, oldest first.
+const PENDING_LIMIT: i64 = 50;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RampFailureCount {
+    /// Failure reason up to its first `:`, so reasons carrying ids or
+    /// amounts are counted together
+    pub reason: String,
+    pub orders: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RampDailyVolume {
+    pub day: NaiveDate,
+    pub currency: String,
+    pub direction: RampDirection,
+    pub orders: u64,
+    pub token_amount: BigDecimal,
+    /// Fiat of the quoted orders among them
+    pub fiat_amount: BigDecimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RampSummary {
+    pub since: NaiveDateTime,
+    pub pending_count: u64,
+    /// In-flight orders older than RAMP_PENDING_TIMEOUT_SECS
+    pub overdue_count: u64,
+    pub pending_orders: Vec<RampOrderRow>,
+    pub failures: Vec<RampFailureCount>,
+    /// Completed orders per day, currency and direction
+    pub volume: Vec<RampDailyVolume>,
+    /// Pending fulfillments that have failed at least once
+    pub stuck_fulfillments: Vec<RampFulfillmentRow>,
+}
+
+fn failure_key(reason: Option<&str>) -> String {
+    match reason.map(str::trim).filter(|r| !r.is_empty()) {
+        Some(reason) => reason
+            .split_once(':')
+            .map(|(head, _)| head)
+            .unwrap_or(reason)
+            .trim()
+            .to_string(),
+        None => "Unknown".to_string(),
+    }
+}
+
+/// Ramp activity over the last `days` days, for operators: what is still in
+/// flight, why orders failed, how much went through and which fulfillments
+/// keep failing.
+pub fn ramp_summary(conn: DbConn<'_>, days: i64) -> Result<RampSummary> {
+    let now = Utc::now().naive_utc();
+    let since = now - chrono::Duration::days(days.clamp(1, 90));
+    let in_flight = vec![RampOrderStatus::Pending, RampOrderStatus::Processing];
+
+    let (pending_count, overdue_count, pending_orders, recent) = {
+        use crate::schema::ramp_orders::dsl::*;
+
+        let pending_count: i64 = ramp_orders
+            .filter(status.eq_any(in_flight.clone()))
+            .count()
+            .get_result(conn)?;
+        let overdue_count: i64 = ramp_orders
+            .filter(status.eq_any(in_flight.clone()))
+            .filter(updated_at.lt(now - pending_timeout()))
+            .count()
+            .get_result(conn)?;
+        let pending_orders = ramp_orders
+            .filter(status.eq_any(in_flight))
+            .order(created_at.asc())
+            .limit(PENDING_LIMIT)
+            .get_results::<RampOrderRow>(conn)?;
+        let recent = ramp_orders
+            .filter(created_at.ge(since))
+            .filter(status.eq_any(vec![
+                RampOrderStatus::Completed,
+                RampOrderStatus::Failed,
+                RampOrderStatus::Refunded,
+                RampOrderStatus::PayoutFailed,
+            ]))
+            .get_results::<RampOrderRow>(conn)?;
+
+        (pending_count, overdue_count, pending_orders, recent)
+    };
+
+    let stuck_fulfillments = {
+        use crate::schema::ramp_fulfillments::dsl::*;
+
+        ramp_fulfillments
+            .filter(status.eq_any(vec![FulfillmentStatus::Pending, FulfillmentStatus::Running]))
+            .filter(last_error.is_not_null())
+            .order(created_at.asc())
+            .get_results::<RampFulfillmentRow>(conn)?
+    };
+
+    let mut failures: BTreeMap<String, u64> = BTreeMap::new();
+    let mut volume: BTreeMap<(NaiveDate, String, bool), RampDailyVolume> = BTreeMap::new();
+    for order in recent {
+        // refunded orders failed first; the reason is what matters here
+        if order.status != RampOrderStatus::Completed {
+            *failures
+                .entry(failure_key(order.failure_reason.as_deref()))
+                .or_default() += 1;
+            continue;
+        }
+
+        let day = order.created_at.date();
+        let key = (
+            day,
+            order.currency.clone(),
+            order.direction == RampDirection::OnRamp,
+        );
+        let entry = volume.entry(key).or_insert_with(|| RampDailyVolume {
+            day,
+            currency: order.currency.clone(),
+            direction: order.direction,
+            orders: 0,
+            token_amount: BigDecimal::from(0),
+            fiat_amount: BigDecimal::from(0),
+        });
+        entry.orders += 1;
+        entry.token_amount += &order.amount;
+        if let Some(fiat) = &order.fiat_amount {
+            entry.fiat_amount += fiat;
+        }
+    }
+
+    let mut failures: Vec<RampFailureCount> = failures
+        .into_iter()
+        .map(|(reason, orders)| RampFailureCount { reason, orders })
+        .collect();
+    failures.sort_by(|a, b| b.orders.cmp(&a.orders));
+
+    Ok(RampSummary {
+        since,
+        pending_count: pending_count as u64,
+        overdue_count: overdue_count as u64,
+        pending_orders,
+        failures,
+        volume: volume.into_values().collect(),
+        stuck_fulfillments,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RampAdminActionInputArgs {
+    pub reason: Option<String>,
+}
+
+/// Push an order along without waiting on the background workers. An order
+/// still in flight is settled from the provider's current status; a paid
+/// on-ramp whose fulfillment keeps failing gets its next attempt now.
+pub async fn retry_order(
+    ramper: &Ramper,
+    wallet: TaskWallet<'_>,
+    conn: DbConn<'_>,
+    order_id: Uuid,
+    operator: &AdminOperatorRecord,
+    input: RampAdminActionInputArgs,
+) -> Result<RampOrderDetails> {
+    require_role(conn, operator, AdminRole::Operator)?;
+
+    let order = get_ramp_order(conn, order_id)?
+        .ok_or_else(|| anyhow!("Unknown ramp order {}", order_id))?;
+
+    match order.status {
+        RampOrderStatus::Pending | RampOrderStatus::Processing => {
+            let reference = order.provider_reference.clone().ok_or_else(|| {
+                anyhow!(
+                    "Ramp order {} never reached the provider; cancel it instead",
+                    order.id
+                )
+            })?;
+            record_order_event(
+                conn,
+                order.id,
+                "retried",
+                Some(operator.account_id),
+                input.reason,
+            )?;
+
+            let status = ramper.provider()?.status(&reference).await?;
+            ramper.settle_order(wallet, conn, order.id, status).await?;
+        }
+        RampOrderStatus::Completed if order.direction == RampDirection::OnRamp => {
+            let job = get_fulfillment(conn, order.id)?;
+            if job
+                .as_ref()
+                .is_some_and(|job| job.status != FulfillmentStatus::Pending)
+            {
+                return Err(anyhow!(
+                    "Ramp order {} has no pending fulfillment to retry",
+                    order.id
+                ));
+            }
+            record_order_event(
+                conn,
+                order.id,
+                "retried",
+                Some(operator.account_id),
+                input.reason,
+            )?;
+
+            match job {
+                // the first attempt never got queued
+                None => {
+                    start_fulfillment(ramper, wallet, conn, &order).await?;
+                }
+                Some(job) => {
+                    if reschedule_fulfillment(conn, job.id)?.is_some() {
+                        run_job(ramper, wallet, conn, job.id).await?;
+                    }
+                }
+            }
+        }
+        _ => {
+            return Err(anyhow!(
+                "Ramp order {} is {:?}; only in-flight orders and pending fulfillments can be retried",
+                order.id,
+                order.status
+            ));
+        }
+    }
+
+    get_order_details(conn, order.id)
+}
+
+/// Fail an order that is still in flight, e.g. a payment the user abandoned.
+/// An off-ramp whose tokens already went to settlement, but that the
+/// provider never accepted, becomes `payout_failed` so an approver can
+/// refund it and send the tokens back. One the provider accepted has to
+/// settle from the provider's status instead. An on-ramp the provider has a
+/// payment for is only cancelled once the provider says it failed, so a
+/// payment the user did make isn't dropped.
+pub async fn cancel_order(
+    ramper: &Ramper,
+    conn: DbConn<'_>,
+    order_id: Uuid,
+    operator: &AdminOperatorRecord,
+    input: RampAdminActionInputArgs,
+) -> Result<RampOrderDetails> {
+    require_role(conn, operator, AdminRole::Operator)?;
+
+    let order = get_ramp_order(conn, order_id)?
+        .ok_or_else(|| anyhow!("Unknown ramp order {}", order_id))?;
+    let moved = order.direction == RampDirection::OffRamp && order.transaction_id.is_some();
+    if moved && order.provider_reference.is_some() {
+        return Err(anyhow!(
+            "The provider accepted the payout of ramp order {}; retry it to settle from the provider's status",
+            order.id
+        ));
+    }
+    if order.direction == RampDirection::OnRamp
+        && let Some(reference) = &order.provider_reference
+    {
+        match ramper.provider()?.status(reference).await?.outcome {
+            Some(RampOrderStatus::Failed) => {}
+            Some(_) => {
+                return Err(anyhow!(
+                    "The provider has a payment for ramp order {}; retry it to settle and credit it",
+                    order.id
+                ));
+            }
+            None => {
+                return Err(anyhow!(
+                    "The payment for ramp order {} is still pending with the provider; retry it once the provider has finished",
+                    order.id
+                ));
+            }
+        }
+    }
+
+    let reason = match &input.reason {
+        Some(reason) => format!("Cancelled by an operator: {}", reason),
+        None => "Cancelled by an operator".to_string(),
+    };
+    let outcome = if moved {
+        RampOrderStatus::PayoutFailed
+    } else {
+        RampOrderStatus::Failed
+    };
+    finish_ramp_order(conn, order.id, outcome, Some(reason))?.ok_or_else(|| {
+        anyhow!(
+            "Ramp order {} is {:?}; only pending or processing orders can be cancelled",
+            order.id,
+            order.status
+        )
+    })?;
+    record_order_event(
+        conn,
+        order.id,
+        "cancelled",
+        Some(operator.account_id),
+        input.reason,
+    )?;
+
+    get_order_details(conn, order.id)
+}
@@ -0,0 +1,73 @@
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+/// How the user pays in or is paid out.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentChannel {
+    /// Hosted card checkout; the user is sent to the provider's page
+    Card,
+    /// Bank transfer, for payouts
+    Bank,
+    /// STK push to a phone; the user approves on the handset and the result
+    /// arrives later by webhook
+    MobileMoney,
+}
+
+impl PaymentChannel {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value
+            .trim()
+            .to_lowercase()
+            .replace(['-', ' '], "_")
+            .as_str()
+        {
+            "card" => Ok(PaymentChannel::Card),
+            "bank" => Ok(PaymentChannel::Bank),
+            "mobile_money" | "mpesa" | "m_pesa" => Ok(PaymentChannel::MobileMoney),
+            other => Err(anyhow!("Unknown payment channel {}", other)),
+        }
+    }
+
+    /// Name stored on ramp orders and sent to providers.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentChannel::Card => "card",
+            PaymentChannel::Bank => "bank",
+            PaymentChannel::MobileMoney => "mobile_money",
+        }
+    }
+
+    pub fn needs_phone(&self) -> bool {
+        matches!(self, PaymentChannel::MobileMoney)
+    }
+}
+
+/// Bring a phone number to international form without the `+`, e.g.
+/// `+254 700-000 000` to `254700000000`. A leading `0` is taken as a local
+/// number and prefixed with RAMP_DEFAULT_COUNTRY_CODE.
+pub fn normalize_phone(phone: &str) -> Result<String> {
+    let digits: String = phone
+        .trim()
+        .trim_start_matches('+')
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '(' | ')'))
+        .collect();
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(anyhow!("Invalid phone number {}", phone));
+    }
+
+    let number = match digits.strip_prefix('0') {
+        Some(local) => {
+            let country_code =
+                std::env::var("RAMP_DEFAULT_COUNTRY_CODE").unwrap_or_else(|_| "254".to_string());
+            format!("{}{}", country_code.trim_start_matches('+'), local)
+        }
+        None => digits,
+    };
+    if !(10..=15).contains(&number.len()) {
+        return Err(anyhow!("Invalid phone number {}", phone));
+    }
+
+    Ok(number)
+}
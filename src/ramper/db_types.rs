@@ -0,0 +1,252 @@
+use crate::schema::protocol_fees as ProtocolFeesTable;
+use crate::schema::ramp_fulfillments as RampFulfillmentsTable;
+use crate::schema::ramp_order_events as RampOrderEventsTable;
+use crate::schema::ramp_orders as RampOrdersTable;
+use crate::schema::ramp_quotes as RampQuotesTable;
+use crate::schema::ramp_receipts as RampReceiptsTable;
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::RampDirection"]
+#[serde(rename_all = "lowercase")]
+pub enum RampDirection {
+    #[db_rename = "onramp"]
+    OnRamp,
+    #[db_rename = "offramp"]
+    OffRamp,
+}
+
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::RampOrderStatus"]
+#[serde(rename_all = "lowercase")]
+pub enum RampOrderStatus {
+    Pending,
+    /// Accepted by the provider, waiting on its webhook
+    Processing,
+    Completed,
+    Failed,
+    /// Claimed by a refund that is waiting on the provider or the transfer
+    Refunding,
+    /// Payment returned to the user by the provider, or for off-ramps, the
+    /// tokens sent back to the wallet
+    Refunded,
+    /// Off-ramp whose tokens went to settlement but whose payout failed;
+    /// waits for the tokens to go back to the wallet
+    #[db_rename = "payout_failed"]
+    #[serde(rename = "payout_failed")]
+    PayoutFailed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = RampOrdersTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct RampOrderRow {
+    pub id: Uuid,
+    pub direction: RampDirection,
+    pub status: RampOrderStatus,
+    pub wallet_id: Uuid,
+    pub asset_id: Uuid,
+    pub amount: BigDecimal,
+    pub currency: String,
+    pub channel: String,
+    pub destination: Option<String>,
+    pub provider_reference: Option<String>,
+    pub transaction_id: Option<Uuid>,
+    pub failure_reason: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub refund_reference: Option<String>,
+    pub quote_id: Option<Uuid>,
+    /// Fiat the user pays or receives, when the order was quoted
+    pub fiat_amount: Option<BigDecimal>,
+    /// Ramp fee in fiat, included in what the user pays
+    pub fee: BigDecimal,
+    /// The fee in tokens, held back from `amount` when crediting
+    pub fee_tokens: BigDecimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = RampOrdersTable)]
+pub struct CreateRampOrder {
+    pub direction: RampDirection,
+    pub wallet_id: Uuid,
+    pub asset_id: Uuid,
+    pub amount: BigDecimal,
+    pub currency: String,
+    pub channel: String,
+    pub destination: Option<String>,
+    pub quote_id: Option<Uuid>,
+    pub fiat_amount: Option<BigDecimal>,
+    pub fee: BigDecimal,
+    pub fee_tokens: BigDecimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = RampOrderEventsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct RampOrderEventRow {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub event: String,
+    pub actor: Option<Uuid>,
+    pub note: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = RampOrderEventsTable)]
+pub struct CreateRampOrderEvent {
+    pub order_id: Uuid,
+    pub event: String,
+    pub actor: Option<Uuid>,
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = RampQuotesTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct RampQuoteRow {
+    pub id: Uuid,
+    pub asset_id: Uuid,
+    pub currency: String,
+    /// Fiat per token
+    pub rate: BigDecimal,
+    pub fiat_amount: BigDecimal,
+    pub token_amount: BigDecimal,
+    pub source: String,
+    pub expires_at: NaiveDateTime,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    /// Ramp fee in fiat, part of `fiat_amount`
+    pub fee: BigDecimal,
+    /// The fee in tokens; `token_amount - fee_tokens` is credited
+    pub fee_tokens: BigDecimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = RampQuotesTable)]
+pub struct CreateRampQuote {
+    pub asset_id: Uuid,
+    pub currency: String,
+    pub rate: BigDecimal,
+    pub fiat_amount: BigDecimal,
+    pub token_amount: BigDecimal,
+    pub source: String,
+    pub expires_at: NaiveDateTime,
+    pub fee: BigDecimal,
+    pub fee_tokens: BigDecimal,
+}
+
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::FulfillmentStatus"]
+#[serde(rename_all = "lowercase")]
+pub enum FulfillmentStatus {
+    Pending,
+    /// Claimed by a worker until its `next_attempt_at`
+    Running,
+    Completed,
+    /// Gave up after RAMP_FULFILLMENT_MAX_ATTEMPTS
+    Failed,
+}
+
+/// Crediting of a paid on-ramp order. Each step's timestamp is set once it
+/// has gone through, so a retry picks up at the first one still missing.
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = RampFulfillmentsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct RampFulfillmentRow {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub status: FulfillmentStatus,
+    pub associated_at: Option<NaiveDateTime>,
+    pub kyced_at: Option<NaiveDateTime>,
+    pub minted_at: Option<NaiveDateTime>,
+    pub airdropped_at: Option<NaiveDateTime>,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub airdrop_tx: Option<String>,
+    /// The wallet's balance right before the airdrop was sent; set while an
+    /// airdrop may be out without its transaction recorded
+    pub airdrop_balance_before: Option<i64>,
+    /// The token's total supply right before the mint was sent; set while a
+    /// mint may have gone through without being stamped
+    pub mint_supply_before: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = RampFulfillmentsTable)]
+pub struct CreateRampFulfillment {
+    pub order_id: Uuid,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = RampReceiptsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct RampReceiptRow {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub wallet_id: Uuid,
+    pub asset_id: Uuid,
+    pub token_symbol: String,
+    pub token_amount: BigDecimal,
+    pub currency: String,
+    pub fiat_amount: Option<BigDecimal>,
+    pub fee: BigDecimal,
+    pub channel: String,
+    pub provider_reference: Option<String>,
+    pub tx_hash: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = RampReceiptsTable)]
+pub struct CreateRampReceipt {
+    pub order_id: Uuid,
+    pub wallet_id: Uuid,
+    pub asset_id: Uuid,
+    pub token_symbol: String,
+    pub token_amount: BigDecimal,
+    pub currency: String,
+    pub fiat_amount: Option<BigDecimal>,
+    pub fee: BigDecimal,
+    pub channel: String,
+    pub provider_reference: Option<String>,
+    pub tx_hash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = ProtocolFeesTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ProtocolFeeRow {
+    pub id: Uuid,
+    /// What charged the fee, e.g. "ramp"
+    pub source: String,
+    /// The order or trade the fee was charged on
+    pub reference_id: Uuid,
+    pub provider: Option<String>,
+    pub asset_id: Option<Uuid>,
+    pub currency: String,
+    pub amount: BigDecimal,
+    pub token_amount: BigDecimal,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = ProtocolFeesTable)]
+pub struct CreateProtocolFee {
+    pub source: String,
+    pub reference_id: Uuid,
+    pub provider: Option<String>,
+    pub asset_id: Option<Uuid>,
+    pub currency: String,
+    pub amount: BigDecimal,
+    pub token_amount: BigDecimal,
+}
@@ -0,0 +1,291 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+use bigdecimal::{BigDecimal, RoundingMode};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ramper::db_types::{CreateProtocolFee, ProtocolFeeRow, RampOrderRow},
+    utils::commons::DbConn,
+};
+
+/// Source recorded on protocol fee entries charged by the ramps.
+pub const RAMP_FEE_SOURCE: &str = "ramp";
+
+/// A flat fee plus a percentage, both in the payment's fiat currency.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RampFee {
+    pub flat: BigDecimal,
+    /// Percent of the payment, e.g. `1.5`
+    pub percent: BigDecimal,
+}
+
+impl Default for RampFee {
+    fn default() -> Self {
+        Self {
+            flat: BigDecimal::from(0),
+            percent: BigDecimal::from(0),
+        }
+    }
+}
+
+impl RampFee {
+    /// Fee on a payment of `fiat`, rounded up to the cent.
+    pub fn on(&self, fiat: &BigDecimal) -> BigDecimal {
+        (&self.flat + fiat * &self.percent / BigDecimal::from(100))
+            .with_scale_round(2, RoundingMode::Up)
+    }
+}
+
+struct FeeRule {
+    provider: Option<String>,
+    currency: String,
+    fee: RampFee,
+}
+
+/// Parse RAMP_FEES, e.g. `KES=30+1.5%,orion:USD=0.5+1%`. Each entry is an
+/// optional `provider:`, the currency, then a flat fee and/or a percentage.
+fn fee_rules() -> Result<Vec<FeeRule>> {
+    let raw = std::env::var("RAMP_FEES").unwrap_or_default();
+    let mut rules = Vec::new();
+
+    for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let invalid = || anyhow!("Invalid RAMP_FEES entry {}", entry);
+        let (target, charge) = entry.split_once('=').ok_or_else(invalid)?;
+        let (provider, currency) = match target.split_once(':') {
+            Some((provider, currency)) => (Some(provider.trim().to_lowercase()), currency),
+            None => (None, target),
+        };
+
+        let mut fee = RampFee::default();
+        for part in charge.split('+').map(str::trim).filter(|p| !p.is_empty()) {
+            match part.strip_suffix('%') {
+                Some(percent) => {
+                    fee.percent = BigDecimal::from_str(percent.trim()).map_err(|_| invalid())?
+                }
+                None => fee.flat = BigDecimal::from_str(part).map_err(|_| invalid())?,
+            }
+        }
+        if fee.flat < BigDecimal::from(0) || fee.percent < BigDecimal::from(0) {
+            return Err(invalid());
+        }
+
+        rules.push(FeeRule {
+            provider,
+            currency: currency.trim().to_uppercase(),
+            fee,
+        });
+    }
+
+    Ok(rules)
+}
+
+/// The fee for paying in `currency` through `provider`. A rule naming the
+/// provider wins over one for the currency alone; no rule means no fee.
+pub fn fee_for(provider: &str, currency: &str) -> Result<RampFee> {
+    let rules = fee_rules()?;
+    let for_currency = |r: &&FeeRule| r.currency.eq_ignore_ascii_case(currency);
+
+    let rule = rules
+        .iter()
+        .filter(for_currency)
+        .find(|r| r.provider.as_deref() == Some(provider))
+        .or_else(|| {
+            rules
+                .iter()
+                .filter(for_currency)
+                .find(|r| r.provider.is_none())
+        });
+
+    Ok(rule.map(|r| r.fee.clone()).unwrap_or_default())
+}
+
+/// A fiat fee in whole tokens at `rate` fiat per token, rounded up.
+pub fn fee_in_tokens(fee: &BigDecimal, rate: &BigDecimal) -> BigDecimal {
+    (fee / rate).with_scale_round(0, RoundingMode::Up)
+}
+
+/// Fiat an on-ramp order charges, in whole units as providers take it: the
+/// quoted price. Orders from before every on-ramp needed a quote charged
+/// their token amount one to one.
+pub fn charged_fiat(order: &RampOrderRow) -> BigDecimal {
+    order
+        .fiat_amount
+        .clone()
+        .unwrap_or_else(|| order.amount.clone())
+        .with_scale_round(0, RoundingMode::Up)
+}
+
+/// Tokens an on-ramp order puts in the wallet once its fee is held back.
+pub fn credited_tokens(order: &RampOrderRow) -> BigDecimal {
+    &order.amount - &order.fee_tokens
+}
+
+/// Put a fulfilled order's fee in the protocol fee ledger. Orders without
+/// a fee, or already recorded, are skipped.
+pub fn record_ramp_fee(
+    conn: DbConn<'_>,
+    order: &RampOrderRow,
+    provider_name: Option<String>,
+) -> Result<()> {
+    if order.fee <= BigDecimal::from(0) {
+        return Ok(());
+    }
+
+    let entry = CreateProtocolFee {
+        source: RAMP_FEE_SOURCE.to_string(),
+        reference_id: order.id,
+        provider: provider_name,
+        asset_id: Some(order.asset_id),
+        currency: order.currency.clone(),
+        amount: order.fee.clone(),
+        token_amount: order.fee_tokens.clone(),
+    };
+
+    use crate::schema::protocol_fees::dsl::*;
+
+    diesel::insert_into(protocol_fees)
+        .values(&entry)
+        .on_conflict((source, reference_id))
+        .do_nothing()
+        .execute(conn)?;
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RampFeeTotal {
+    pub currency: String,
+    pub provider: Option<String>,
+    pub orders: u64,
+    pub fees: BigDecimal,
+    pub fee_tokens: BigDecimal,
+}
+
+/// Ramp fees collected between `from` and `to`, totalled per currency and
+/// provider.
+pub fn ramp_fee_report(
+    conn: DbConn<'_>,
+    from: Option<NaiveDateTime>,
+    to: Option<NaiveDateTime>,
+) -> Result<Vec<RampFeeTotal>> {
+    let entries = {
+        use crate::schema::protocol_fees::dsl::*;
+
+        let mut query = protocol_fees
+            .filter(source.eq(RAMP_FEE_SOURCE))
+            .into_boxed();
+        if let Some(start) = from {
+            query = query.filter(created_at.ge(start));
+        }
+        if let Some(end) = to {
+            query = query.filter(created_at.lt(end));
+        }
+        query.get_results::<ProtocolFeeRow>(conn)?
+    };
+
+    let mut totals: BTreeMap<(String, Option<String>), RampFeeTotal> = BTreeMap::new();
+    for entry in entries {
+        let total = totals
+            .entry((entry.currency.clone(), entry.provider.clone()))
+            .or_insert_with(|| RampFeeTotal {
+                currency: entry.currency.clone(),
+                provider: entry.provider.clone(),
+                orders: 0,
+                fees: BigDecimal::from(0),
+                fee_tokens: BigDecimal::from(0),
+            });
+        total.orders += 1;
+        total.fees += &entry.amount;
+        total.fee_tokens += &entry.token_amount;
+    }
+
+    Ok(totals.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::ramper::db_types::{RampDirection, RampOrderStatus};
+
+    fn dec(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    fn fee(flat: &str, percent: &str) -> RampFee {
+        RampFee {
+            flat: dec(flat),
+            percent: dec(percent),
+        }
+    }
+
+    fn order(amount: &str, fiat_amount: Option<&str>, fee_tokens: &str) -> RampOrderRow {
+        let now = Utc::now().naive_utc();
+        RampOrderRow {
+            id: Uuid::new_v4(),
+            direction: RampDirection::OnRamp,
+            status: RampOrderStatus::Pending,
+            wallet_id: Uuid::new_v4(),
+            asset_id: Uuid::new_v4(),
+            amount: dec(amount),
+            currency: "KES".to_string(),
+            channel: "mobile".to_string(),
+            destination: None,
+            provider_reference: None,
+            transaction_id: None,
+            failure_reason: None,
+            created_at: now,
+            updated_at: now,
+            refund_reference: None,
+            quote_id: None,
+            fiat_amount: fiat_amount.map(dec),
+            fee: BigDecimal::from(0),
+            fee_tokens: dec(fee_tokens),
+        }
+    }
+
+    #[test]
+    fn fee_rounds_up_to_the_cent() {
+        assert_eq!(fee("0", "1.5").on(&dec("100")), dec("1.50"));
+        assert_eq!(fee("0", "1.5").on(&dec("100.01")), dec("1.51"));
+        assert_eq!(fee("30", "1").on(&dec("0.01")), dec("30.01"));
+        assert_eq!(fee("0.5", "0").on(&dec("1234.567")), dec("0.50"));
+    }
+
+    #[test]
+    fn no_fee_on_nothing() {
+        assert_eq!(RampFee::default().on(&dec("100")), dec("0"));
+        assert_eq!(fee("0", "2").on(&dec("0")), dec("0"));
+    }
+
+    #[test]
+    fn fee_in_tokens_rounds_up_to_a_whole_token() {
+        assert_eq!(fee_in_tokens(&dec("260"), &dec("130")), dec("2"));
+        assert_eq!(fee_in_tokens(&dec("260.01"), &dec("130")), dec("3"));
+        assert_eq!(fee_in_tokens(&dec("0.01"), &dec("130")), dec("1"));
+        assert_eq!(fee_in_tokens(&dec("0"), &dec("130")), dec("0"));
+    }
+
+    #[test]
+    fn charged_fiat_rounds_the_quote_up_to_a_whole_unit() {
+        assert_eq!(charged_fiat(&order("10", Some("1300"), "0")), dec("1300"));
+        assert_eq!(
+            charged_fiat(&order("10", Some("1300.01"), "0")),
+            dec("1301")
+        );
+        assert_eq!(charged_fiat(&order("10", None, "0")), dec("10"));
+    }
+
+    #[test]
+    fn credited_tokens_hold_back_the_fee() {
+        assert_eq!(credited_tokens(&order("10", Some("1300"), "0")), dec("10"));
+        assert_eq!(credited_tokens(&order("10", Some("1300"), "3")), dec("7"));
+        assert_eq!(credited_tokens(&order("10", Some("1300"), "9")), dec("1"));
+    }
+}
@@ -0,0 +1,596 @@
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use bigdecimal::ToPrimitive;
+use chrono::Utc;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    accounts::{
+        operations::{associate_token, kyc_token},
+        processor_enums::{AssociateTokenToWalletInputArgs, GrantKYCInputArgs},
+    },
+    accounts_ledger::{
+        db_types::AccountLedgerTransactionType,
+        operations::{RecordTransactionAssets, record_transaction},
+    },
+    asset_book::operations::{
+        airdrop_asset, get_asset, get_wallet, mint_asset, token_balance, token_supply,
+    },
+    big_to_u64,
+    order_book::operations::can_execute_onchain,
+    ramper::{
+        Ramper,
+        db_types::{
+            CreateRampFulfillment, FulfillmentStatus, RampDirection, RampFulfillmentRow,
+            RampOrderRow, RampOrderStatus,
+        },
+        fees::{credited_tokens, record_ramp_fee},
+        operations::{fail_ramp_order, get_ramp_order, set_order_transaction},
+        providers::RampProvider,
+        receipts::issue_receipt,
+        refunds::{RefundRampOrderInputArgs, refund_order},
+    },
+    utils::{
+        app_config::AppConfig,
+        commons::{DbConn, TaskWallet},
+    },
+};
+
+/// How long a claimed job is left to its worker before another may pick it up.
+const LEASE_SECS: i64 = 300;
+
+#[derive(Debug, Clone)]
+enum Step {
+    Associate,
+    Kyc,
+    Mint,
+    /// Carries the airdrop's transaction id, when it is known
+    Airdrop(Option<String>),
+}
+
+/// Seconds between worker passes, from RAMP_FULFILLMENT_INTERVAL_SECS.
+fn poll_interval() -> Duration {
+    let secs = std::env::var("RAMP_FULFILLMENT_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(30);
+    Duration::from_secs(secs)
+}
+
+/// Attempts before a fulfillment is given up and the payment refunded, from
+/// RAMP_FULFILLMENT_MAX_ATTEMPTS.
+fn max_attempts() -> i32 {
+    std::env::var("RAMP_FULFILLMENT_MAX_ATTEMPTS")
+        .ok()
+        .and_then(|v| v.parse::<i32>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(6)
+}
+
+/// 30s after the first failure, doubling up to an hour.
+fn retry_delay(attempts: i32) -> chrono::Duration {
+    let exp = attempts.saturating_sub(1).clamp(0, 7) as u32;
+    chrono::Duration::seconds((30i64 * 2i64.pow(exp)).min(3600))
+}
+
+pub fn get_fulfillment(conn: DbConn<'_>, order: Uuid) -> Result<Option<RampFulfillmentRow>> {
+    use crate::schema::ramp_fulfillments::dsl::*;
+
+    Ok(ramp_fulfillments
+        .filter(order_id.eq(order))
+        .get_result::<RampFulfillmentRow>(conn)
+        .optional()?)
+}
+
+/// Take a due job, marking it running until its lease runs out so no other
+/// worker runs it at the same time. A running job whose lease ran out is
+/// taken over, as its worker is gone.
+fn claim(conn: DbConn<'_>, job: Uuid) -> Result<Option<RampFulfillmentRow>> {
+    use crate::schema::ramp_fulfillments::dsl::*;
+
+    let now = Utc::now().naive_utc();
+    Ok(diesel::update(ramp_fulfillments)
+        .filter(id.eq(job))
+        .filter(status.eq_any(vec![FulfillmentStatus::Pending, FulfillmentStatus::Running]))
+        .filter(next_attempt_at.le(now))
+        .set((
+            status.eq(FulfillmentStatus::Running),
+            attempts.eq(attempts + 1),
+            next_attempt_at.eq(now + chrono::Duration::seconds(LEASE_SECS)),
+            updated_at.eq(now),
+        ))
+        .get_result::<RampFulfillmentRow>(conn)
+        .optional()?)
+}
+
+fn mark_step(conn: DbConn<'_>, job: Uuid, step: Step) -> Result<()> {
+    use crate::schema::ramp_fulfillments::dsl::*;
+
+    let now = Some(Utc::now().naive_utc());
+    let target = diesel::update(ramp_fulfillments.find(job));
+    match step {
+        Step::Associate => target.set(associated_at.eq(now)).execute(conn)?,
+        Step::Kyc => target.set(kyced_at.eq(now)).execute(conn)?,
+        Step::Mint => target.set(minted_at.eq(now)).execute(conn)?,
+        Step::Airdrop(tx) => target
+            .set((airdropped_at.eq(now), airdrop_tx.eq(tx)))
+            .execute(conn)?,
+    };
+    Ok(())
+}
+
+/// End the attempt on a job this worker holds. Returns `None` when the job
+/// was stopped in the meantime, e.g. by a refund after the lease ran out.
+fn release(
+    conn: DbConn<'_>,
+    job: Uuid,
+    outcome: FulfillmentStatus,
+    error: Option<String>,
+    retry_at: Option<chrono::NaiveDateTime>,
+) -> Result<Option<RampFulfillmentRow>> {
+    use crate::schema::ramp_fulfillments::dsl::*;
+
+    let now = Utc::now().naive_utc();
+    Ok(diesel::update(ramp_fulfillments)
+        .filter(id.eq(job))
+        .filter(status.eq(FulfillmentStatus::Running))
+        .set((
+            status.eq(outcome),
+            last_error.eq(error),
+            next_attempt_at.eq(retry_at.unwrap_or(now)),
+            updated_at.eq(now),
+        ))
+        .get_result::<RampFulfillmentRow>(conn)
+        .optional()?)
+}
+
+/// Lock the order's row and check it is still paid, for a step about to
+/// put tokens on-chain. A refund claims the order through the same row, so
+/// it either waits for the step's intent to be recorded, and sees it, or
+/// gets in first and the step doesn't run.
+fn lock_paid_order(conn: DbConn<'_>, order: Uuid) -> Result<()> {
+    use crate::schema::ramp_orders::dsl::*;
+
+    let current = ramp_orders
+        .find(order)
+        .select(status)
+        .for_update()
+        .get_result::<RampOrderStatus>(conn)?;
+    if current != RampOrderStatus::Completed {
+        return Err(anyhow!(
+            "Ramp order {} is {:?}; it is no longer being credited",
+            order,
+            current
+        ));
+    }
+    Ok(())
+}
+
+/// Note the token's total supply before the mint goes out, so a retry can
+/// tell whether it went through.
+fn record_mint_intent(conn: DbConn<'_>, job: Uuid, order: Uuid, supply: u64) -> Result<()> {
+    use crate::schema::ramp_fulfillments::dsl::*;
+
+    conn.transaction::<_, anyhow::Error, _>(|conn| {
+        lock_paid_order(conn, order)?;
+        diesel::update(ramp_fulfillments.find(job))
+            .set(mint_supply_before.eq(Some(supply as i64)))
+            .execute(conn)?;
+        Ok(())
+    })
+}
+
+/// Note the wallet's balance before the airdrop goes out, so a retry can
+/// tell whether it landed.
+fn record_airdrop_intent(conn: DbConn<'_>, job: Uuid, order: Uuid, balance: u64) -> Result<()> {
+    use crate::schema::ramp_fulfillments::dsl::*;
+
+    conn.transaction::<_, anyhow::Error, _>(|conn| {
+        lock_paid_order(conn, order)?;
+        diesel::update(ramp_fulfillments.find(job))
+            .set(airdrop_balance_before.eq(Some(balance as i64)))
+            .execute(conn)?;
+        Ok(())
+    })
+}
+
+/// The order's token balance of the wallet it is credited to.
+pub(crate) async fn wallet_balance(
+    wallet: TaskWallet<'_>,
+    conn: DbConn<'_>,
+    order: &RampOrderRow,
+) -> Result<u64> {
+    let asset = get_asset(conn, order.asset_id).await?;
+    let account = get_wallet(conn, order.wallet_id).await?;
+    token_balance(wallet, &account, &asset).await
+}
+
+/// The total supply of the order's token.
+async fn total_supply(
+    wallet: TaskWallet<'_>,
+    conn: DbConn<'_>,
+    order: &RampOrderRow,
+) -> Result<u64> {
+    let asset = get_asset(conn, order.asset_id).await?;
+    token_supply(wallet, &asset).await
+}
+
+/// Run every step the job hasn't finished yet, in order. Returns the
+/// airdrop's transaction id.
+///
+/// The mint and the airdrop can't be looked up once sent, so each records
+/// where things stood first: the token's total supply before the mint, and
+/// the wallet's balance before the airdrop. A retry that finds a supply
+/// recorded but the mint unstamped counts it as done if the supply grew by
+/// at least the amount, mints again if it hasn't moved, and otherwise fails
+/// the attempt without minting.
+///
+/// A retry that finds a balance recorded but no airdrop transaction checks
+/// the wallet instead of sending again: the airdrop landed if the balance
+/// grew by the amount, and is sent again only if the balance hasn't moved.
+/// Anything else fails the attempt without sending, and once attempts run
+/// out the refund claws back whatever landed.
+async fn run_steps(
+    wallet: TaskWallet<'_>,
+    conn: DbConn<'_>,
+    job: &RampFulfillmentRow,
+    order: &RampOrderRow,
+) -> Result<Option<String>> {
+    // the fee stays with the protocol; only the rest is minted and sent
+    let amount = big_to_u64!(credited_tokens(order))?;
+
+    if job.associated_at.is_none() {
+        associate_token(
+            conn,
+            wallet,
+            AssociateTokenToWalletInputArgs {
+                wallet_id: order.wallet_id,
+                token: order.asset_id,
+            },
+        )
+        .await?;
+        mark_step(conn, job.id, Step::Associate)?;
+    }
+    if job.kyced_at.is_none() {
+        kyc_token(
+            conn,
+            wallet,
+            GrantKYCInputArgs {
+                wallet_id: order.wallet_id,
+                token: order.asset_id,
+            },
+        )
+        .await?;
+        mark_step(conn, job.id, Step::Kyc)?;
+    }
+    if job.minted_at.is_none() {
+        let supply = total_supply(wallet, conn, order).await?;
+        let minted = match job.mint_supply_before.map(|s| s as u64) {
+            Some(before) if supply >= before + amount => true,
+            Some(before) if supply != before => {
+                return Err(anyhow!(
+                    "Can't tell whether the mint went through: the token's supply moved from {} to {}",
+                    before,
+                    supply
+                ));
+            }
+            _ => false,
+        };
+
+        if !minted {
+            record_mint_intent(conn, job.id, order.id, supply)?;
+            mint_asset(conn, wallet, order.asset_id, amount).await?;
+        }
+        mark_step(conn, job.id, Step::Mint)?;
+    }
+    let mut tx = job.airdrop_tx.clone();
+    if job.airdropped_at.is_none() {
+        let balance = wallet_balance(wallet, conn, order).await?;
+        let landed = match job.airdrop_balance_before.map(|b| b as u64) {
+            Some(before) if balance >= before + amount => true,
+            Some(before) if balance != before => {
+                return Err(anyhow!(
+                    "Can't tell whether the airdrop went out: the wallet's balance moved from {} to {}",
+                    before,
+                    balance
+                ));
+            }
+            _ => false,
+        };
+
+        if landed {
+            mark_step(conn, job.id, Step::Airdrop(None))?;
+        } else {
+            record_airdrop_intent(conn, job.id, order.id, balance)?;
+            let sent = airdrop_asset(conn, wallet, order.asset_id, order.wallet_id, amount).await?;
+            mark_step(conn, job.id, Step::Airdrop(Some(sent.clone())))?;
+            tx = Some(sent);
+        }
+    }
+
+    if order.transaction_id.is_none() {
+        let wallet_data = get_wallet(conn, order.wallet_id).await?;
+        let ledger_entry = record_transaction(
+            conn,
+            None,
+            Some(wallet_data.address),
+            RecordTransactionAssets::Single(order.asset_id),
+            Some(amount),
+            None,
+            Some(AccountLedgerTransactionType::Transfer),
+            tx.clone(),
+            None,
+        )?;
+        set_order_transaction(conn, order.id, ledger_entry)?;
+    }
+
+    Ok(tx)
+}
+
+/// Attempt a due job. A finished job issues the order's receipt; a failed
+/// attempt is scheduled again with backoff, and the last allowed one fails
+/// the order and refunds the payment.
+pub async fn run_job(
+    ramper: &Ramper,
+    wallet: TaskWallet<'_>,
+    conn: DbConn<'_>,
+    job_id: Uuid,
+) -> Result<Option<RampFulfillmentRow>> {
+    let Some(job) = claim(conn, job_id)? else {
+        return Ok(None);
+    };
+    let order = get_ramp_order(conn, job.order_id)?
+        .ok_or_else(|| anyhow!("Unknown ramp order {}", job.order_id))?;
+    // minting is only ever for a payment the provider confirmed
+    if order.direction != RampDirection::OnRamp || order.status != RampOrderStatus::Completed {
+        release(
+            conn,
+            job.id,
+            FulfillmentStatus::Failed,
+            Some("Order isn't a paid on-ramp".to_string()),
+            None,
+        )?;
+        return get_fulfillment(conn, order.id);
+    }
+
+    let outcome = run_steps(wallet, conn, &job, &order).await;
+
+    let now = Utc::now().naive_utc();
+    let updated = match outcome {
+        Ok(tx) => {
+            let Some(updated) = release(conn, job.id, FulfillmentStatus::Completed, None, None)?
+            else {
+                println!(
+                    "Ramp fulfillment {} finished after it was stopped; its refund settles the tokens",
+                    job.id
+                );
+                return get_fulfillment(conn, order.id);
+            };
+
+            let provider_name = ramper.provider().ok().map(|p| p.name().to_string());
+            if let Err(e) = record_ramp_fee(conn, &order, provider_name) {
+                println!("Failed to record fee for ramp order {}: {:?}", order.id, e);
+            }
+            // the tokens are credited either way; a missing receipt is only logged
+            if let Err(e) = issue_receipt(conn, &order, tx).await {
+                println!(
+                    "Failed to issue receipt for ramp order {}: {:?}",
+                    order.id, e
+                );
+            }
+            updated
+        }
+        Err(e) if job.attempts >= max_attempts() => {
+            let Some(updated) = release(
+                conn,
+                job.id,
+                FulfillmentStatus::Failed,
+                Some(e.to_string()),
+                None,
+            )?
+            else {
+                return get_fulfillment(conn, order.id);
+            };
+
+            fail_ramp_order(
+                conn,
+                order.id,
+                format!("Crediting the wallet failed: {}", e),
+            )?;
+            let refund = refund_order(
+                ramper,
+                wallet,
+                conn,
+                order.id,
+                None,
+                RefundRampOrderInputArgs {
+                    reason: Some("The wallet could not be credited".to_string()),
+                },
+            )
+            .await;
+            if let Err(refund_err) = refund {
+                println!("Failed to refund ramp order {}: {:?}", order.id, refund_err);
+            }
+            updated
+        }
+        Err(e) => {
+            let retry_at = now + retry_delay(job.attempts);
+            match release(
+                conn,
+                job.id,
+                FulfillmentStatus::Pending,
+                Some(e.to_string()),
+                Some(retry_at),
+            )? {
+                Some(updated) => updated,
+                None => return get_fulfillment(conn, order.id),
+            }
+        }
+    };
+
+    Ok(Some(updated))
+}
+
+/// Queue the crediting of a paid on-ramp order and make the first attempt
+/// straight away. Starting it again for the same order does nothing new.
+pub async fn start_fulfillment(
+    ramper: &Ramper,
+    wallet: TaskWallet<'_>,
+    conn: DbConn<'_>,
+    order: &RampOrderRow,
+) -> Result<Option<RampFulfillmentRow>> {
+    let job = {
+        use crate::schema::ramp_fulfillments::dsl::*;
+
+        diesel::insert_into(ramp_fulfillments)
+            .values(CreateRampFulfillment { order_id: order.id })
+            .on_conflict(order_id)
+            .do_nothing()
+            .execute(conn)?;
+
+        ramp_fulfillments
+            .filter(order_id.eq(order.id))
+            .get_result::<RampFulfillmentRow>(conn)?
+    };
+
+    run_job(ramper, wallet, conn, job.id).await
+}
+
+/// Make a pending job due now instead of waiting out its backoff. A job a
+/// worker holds the lease on is left to that worker. Returns `None` when the
+/// job isn't pending or changed in the meantime.
+pub fn reschedule_fulfillment(conn: DbConn<'_>, job: Uuid) -> Result<Option<RampFulfillmentRow>> {
+    use crate::schema::ramp_fulfillments::dsl::*;
+
+    let current = ramp_fulfillments
+        .find(job)
+        .get_result::<RampFulfillmentRow>(conn)?;
+    if current.status == FulfillmentStatus::Running {
+        return Err(anyhow!(
+            "Fulfillment {} is being attempted right now",
+            current.id
+        ));
+    }
+    let now = Utc::now().naive_utc();
+
+    Ok(diesel::update(ramp_fulfillments)
+        .filter(id.eq(job))
+        .filter(status.eq(FulfillmentStatus::Pending))
+        .filter(updated_at.eq(current.updated_at))
+        .set((next_attempt_at.eq(now), updated_at.eq(now)))
+        .get_result::<RampFulfillmentRow>(conn)
+        .optional()?)
+}
+
+/// Stop a fulfillment that hasn't finished, e.g. because the order is being
+/// refunded. A job a worker holds the lease on can't be stopped; one whose
+/// lease ran out can. Returns the job when this call stopped it.
+pub fn cancel_fulfillment(
+    conn: DbConn<'_>,
+    order: Uuid,
+    reason: &str,
+) -> Result<Option<RampFulfillmentRow>> {
+    use crate::schema::ramp_fulfillments::dsl::*;
+
+    let now = Utc::now().naive_utc();
+    let stopped = diesel::update(ramp_fulfillments)
+        .filter(order_id.eq(order))
+        .filter(
+            status.eq(FulfillmentStatus::Pending).or(status
+                .eq(FulfillmentStatus::Running)
+                .and(next_attempt_at.le(now))),
+        )
+        .set((
+            status.eq(FulfillmentStatus::Failed),
+            last_error.eq(Some(reason.to_string())),
+            updated_at.eq(now),
+        ))
+        .get_result::<RampFulfillmentRow>(conn)
+        .optional()?;
+
+    if stopped.is_none()
+        && let Some(job) = get_fulfillment(conn, order)?
+        && job.status == FulfillmentStatus::Running
+    {
+        return Err(anyhow!(
+            "Ramp order {} is being credited right now; try again once the attempt is over",
+            order
+        ));
+    }
+    Ok(stopped)
+}
+
+/// Put a job stopped by `cancel_fulfillment` back in the queue, e.g. when
+/// the refund it was stopped for didn't go through.
+pub fn reopen_fulfillment(conn: DbConn<'_>, job: Uuid) -> Result<()> {
+    use crate::schema::ramp_fulfillments::dsl::*;
+
+    let now = Utc::now().naive_utc();
+    diesel::update(ramp_fulfillments)
+        .filter(id.eq(job))
+        .filter(status.eq(FulfillmentStatus::Failed))
+        .set((
+            status.eq(FulfillmentStatus::Pending),
+            next_attempt_at.eq(now),
+            updated_at.eq(now),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Retry every fulfillment that is due.
+pub async fn run_once(app_config: &AppConfig, ramper: &Ramper) -> Result<()> {
+    let mut conn = app_config.pool.get()?;
+
+    let due = {
+        use crate::schema::ramp_fulfillments::dsl::*;
+
+        // running jobs that are due lost their worker before the lease ran out
+        ramp_fulfillments
+            .filter(status.eq_any(vec![FulfillmentStatus::Pending, FulfillmentStatus::Running]))
+            .filter(next_attempt_at.le(Utc::now().naive_utc()))
+            .order(next_attempt_at.asc())
+            .limit(50)
+            .select(id)
+            .get_results::<Uuid>(&mut conn)?
+    };
+
+    for job in due {
+        match run_job(ramper, &app_config.wallet, &mut conn, job).await {
+            Ok(Some(row)) if row.status == FulfillmentStatus::Pending => println!(
+                "Ramp fulfillment {} attempt {} failed: {:?}",
+                row.id, row.attempts, row.last_error
+            ),
+            Ok(_) => {}
+            Err(e) => println!("Failed to run ramp fulfillment {}: {:?}", job, e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Background task retrying on-ramp fulfillments. Does nothing when on-chain
+/// interactions are disabled or the ramp isn't configured.
+pub async fn run_worker(app_config: AppConfig) {
+    if !can_execute_onchain() {
+        return;
+    }
+    let ramper = match Ramper::from_env() {
+        Ok(ramper) => ramper,
+        Err(e) => {
+            println!("Ramp fulfillment worker disabled: {:?}", e);
+            return;
+        }
+    };
+
+    let mut ticker = tokio::time::interval(poll_interval());
+    loop {
+        ticker.tick().await;
+        if let Err(e) = run_once(&app_config, &ramper).await {
+            println!("Ramp fulfillment error: {:?}", e);
+        }
+    }
+}
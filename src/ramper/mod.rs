@@ -1,17 +1,59 @@
+pub mod admin;
+pub mod channels;
+pub mod db_types;
+pub mod fees;
+pub mod fulfillment;
+pub mod operations;
+pub mod providers;
+pub mod quotes;
+pub mod receipts;
+pub mod reconciler;
+pub mod refunds;
+
 use crate::{
     accounts::{operations::associate_token, processor_enums::AssociateTokenToWalletInputArgs},
+    accounts_ledger::{
+        db_types::AccountLedgerTransactionType,
+        operations::{RecordTransactionAssets, record_transaction},
+    },
     asset_book::operations::{get_asset, get_wallet},
     big_to_u64,
+    ramper::{
+        channels::{PaymentChannel, normalize_phone},
+        db_types::{CreateRampOrder, RampDirection, RampOrderRow, RampOrderStatus},
+        fees::charged_fiat,
+        fulfillment::start_fulfillment,
+        operations::{
+            create_ramp_order, fail_ramp_order, finish_ramp_order, get_ramp_order,
+            mark_payout_failed, mark_processing, set_order_transaction,
+        },
+        providers::{
+            PaymentRequest, PayoutRequest, Provider, RampProvider, TransactionStatus, WebhookEvent,
+        },
+        quotes::redeem_quote,
+        refunds::{RefundRampOrderInputArgs, refund_order},
+    },
     utils::commons::{DbConn, TaskWallet},
 };
 use anyhow::{Result, anyhow};
 use bigdecimal::{BigDecimal, ToPrimitive};
 use clap::{Parser, ValueEnum};
-use reqwest::Client;
+use contract_integrator::utils::functions::{
+    ContractCallInput, ContractCallOutput,
+    cradle_account::{CradleAccountFunctionInput, CradleAccountFunctionOutput, WithdrawArgs},
+};
 use serde::{Deserialize, Serialize};
 use tracing::instrument::WithSubscriber;
 use uuid::Uuid;
 
+/// Which of the provider's deployments the ramps talk to.
+#[derive(ValueEnum, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RampEnvironment {
+    Sandbox,
+    Production,
+}
+
 #[derive(Parser, Deserialize, Serialize, Clone)]
 pub struct Ramper {
     #[clap(long, env)]
@@ -20,6 +62,21 @@ pub struct Ramper {
     pub ramper_webhook_secret: String,
     #[clap(long, env)]
     pub ramper_callback: String,
+    /// Payment provider the ramp flows go through
+    #[clap(long, env, default_value = "orion")]
+    pub ramper_provider: String,
+    #[clap(long, env, value_enum, default_value = "sandbox")]
+    pub ramper_environment: RampEnvironment,
+    /// Overrides the provider's API URL for the selected environment
+    #[clap(long, env)]
+    pub ramper_api_url: Option<String>,
+    /// Provider account off-ramped tokens are sent to before the payout
+    #[clap(long, env)]
+    pub ramper_settlement_account: Option<String>,
+    /// Cradle account contract behind the settlement account, which tokens
+    /// of failed payouts are sent back to the wallet from
+    #[clap(long, env)]
+    pub ramper_settlement_contract: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -29,50 +86,42 @@ pub struct OnRampRequest {
     pub wallet_id: Uuid,
     pub result_page: String,
     pub email: String,
+    /// Quote from `POST /ramp/quote` the amount was priced with
+    pub quote_id: Uuid,
+    /// "card" (default) or "mobile_money"
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Phone the mobile money prompt is sent to
+    #[serde(default)]
+    pub phone: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct OnRampResponse {
     pub reference: String,
+    /// Checkout page for card payments; empty for mobile money, which is
+    /// approved on the phone instead
+    #[serde(default)]
     pub authorization_url: String,
+    #[serde(default)]
     pub access_code: String,
+    /// What the user should do next, e.g. approve the prompt on their phone
+    #[serde(default)]
+    pub instructions: Option<String>,
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct RequestMetadata {
-    #[serde(rename = "orderID")]
-    pub order_id: String,
-}
-
-#[derive(Serialize, Deserialize)]
-pub struct RequestToken {
-    pub token: String,
-    pub amount: u64,
-    pub email: String,
+#[derive(Serialize, Deserialize, Clone)]
+pub struct OffRampRequest {
+    pub token: Uuid,
+    pub amount: BigDecimal,
+    pub wallet_id: Uuid,
+    /// Fiat currency the payout is made in
     pub currency: String,
-    pub metadata: RequestMetadata,
-    pub callback_url: String,
-    pub channels: Vec<String>,
-    pub crypto_account: String,
-}
-
-// {
-//   event_type: string;
-//   order_id: <orderid-given-when-initializing-payment>;
-//   token: <token-you-indicated-to-receive>;
-//   amount: <amount-user-paid>;
-//   currency: <currency-user-pay-in> | null;
-//   failureReason?: <optional-further-explanation-incase-failure>;
-// }
-#[derive(Serialize, Deserialize)]
-pub struct CallbackData {
-    pub event_type: String,
-    pub order_id: String,
-    pub token: String,
-    pub amount: String,
-    pub currency: Option<String>,
-    #[serde(rename = "failureReason")]
-    pub failure_reason: Option<String>,
+    /// Payout channel, "bank" or "mobile_money"
+    pub channel: String,
+    /// Bank account or phone number the provider pays out to
+    pub destination: String,
+    pub email: String,
 }
 
 impl Ramper {
@@ -83,12 +132,69 @@ impl Ramper {
         })
     }
 
+    pub fn provider(&self) -> Result<Provider> {
+        Provider::from_config(self)
+    }
+
+    /// Check the configuration makes sense for its environment, so a bad
+    /// setup fails at startup instead of on the first payment. Production
+    /// needs https throughout and can't point at the provider's sandbox.
+    pub fn validate(&self) -> Result<()> {
+        let provider = self.provider()?;
+
+        if self.ramper_token.trim().is_empty() {
+            return Err(anyhow!("RAMPER_TOKEN is empty"));
+        }
+        if self.ramper_webhook_secret.trim().is_empty() {
+            return Err(anyhow!("RAMPER_WEBHOOK_SECRET is empty"));
+        }
+
+        let api_url = reqwest::Url::parse(provider.api_url())
+            .map_err(|e| anyhow!("Invalid ramp API URL {}: {}", provider.api_url(), e))?;
+        let callback = reqwest::Url::parse(&self.ramper_callback)
+            .map_err(|e| anyhow!("Invalid RAMPER_CALLBACK {}: {}", self.ramper_callback, e))?;
+
+        if self.ramper_environment == RampEnvironment::Production {
+            if api_url.scheme() != "https" || provider.is_sandbox_url(provider.api_url()) {
+                return Err(anyhow!(
+                    "{} production needs its live https API, not {}",
+                    provider.name(),
+                    api_url
+                ));
+            }
+            let local = matches!(callback.host_str(), Some("localhost" | "127.0.0.1") | None);
+            if callback.scheme() != "https" || local {
+                return Err(anyhow!(
+                    "RAMPER_CALLBACK must be a public https URL in production, not {}",
+                    callback
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn onramp<'a>(
         &self,
         wallet: TaskWallet<'a>,
         conn: DbConn<'a>,
         req: OnRampRequest,
     ) -> Result<OnRampResponse> {
+        let provider = self.provider()?;
+        let channel = PaymentChannel::parse(req.channel.as_deref().unwrap_or("card"))?;
+        if channel == PaymentChannel::Bank || !provider.supports(channel) {
+            return Err(anyhow!(
+                "{} doesn't take {} payments",
+                provider.name(),
+                channel.as_str()
+            ));
+        }
+        let phone = match (channel.needs_phone(), req.phone.as_deref()) {
+            (true, Some(phone)) => Some(normalize_phone(phone)?),
+            (true, None) => return Err(anyhow!("A phone number is required for mobile money")),
+            (false, _) => None,
+        };
+
         associate_token(
             conn,
             wallet,
@@ -101,43 +207,384 @@ impl Ramper {
 
         let token = get_asset(conn, req.token).await?;
         let wallet_data = get_wallet(conn, req.wallet_id).await?;
-        let order_id = Uuid::new_v4().to_string();
 
-        let ramp_request = RequestToken {
+        // the quote prices the tokens and fee at the current rate; it was
+        // already checked to cover the fee
+        let quote = redeem_quote(conn, &token, req.quote_id, &req.amount).await?;
+
+        let order = create_ramp_order(
+            conn,
+            CreateRampOrder {
+                direction: RampDirection::OnRamp,
+                wallet_id: wallet_data.id,
+                asset_id: token.id,
+                amount: req.amount.clone(),
+                currency: quote.currency,
+                channel: channel.as_str().to_string(),
+                destination: phone.clone(),
+                quote_id: Some(quote.id),
+                fiat_amount: Some(quote.fiat_amount),
+                fee: quote.fee,
+                fee_tokens: quote.fee_tokens,
+            },
+        )?;
+
+        let payment = PaymentRequest {
+            order_id: order.id,
             token: token.name,
-            amount: big_to_u64!(req.amount)?,
+            amount: big_to_u64!(charged_fiat(&order))?,
+            currency: order.currency.clone(),
             email: req.email,
-            currency: "KES".to_string(),
-            metadata: RequestMetadata { order_id },
-            callback_url: req.result_page,
-            channels: vec!["card".to_string()],
+            channel: order.channel.clone(),
+            phone: phone.clone(),
             crypto_account: wallet_data.contract_id,
+            return_url: req.result_page,
         };
 
-        let client = Client::new();
-
-        let response = client
-            .post("https://test.api.orionramp.com/api/transaction/initialize")
-            .header(
-                "Authorization",
-                format!("Bearer {}", self.ramper_token.clone()),
-            )
-            .header("Content-Type", "application/json")
-            .json(&ramp_request)
-            .send()
-            .await?;
+        let mut result = match provider.initialize(&payment).await {
+            Ok(result) => result,
+            Err(e) => {
+                return Err(abort_order(
+                    conn,
+                    order.id,
+                    format!("Payment request failed: {}", e),
+                ));
+            }
+        };
 
-        let result = response.json::<OnRampResponse>().await?;
+        // mobile money is confirmed on the handset; the order stays
+        // processing until the provider's webhook or the reconciler settles it
+        if let Some(phone) = phone.filter(|_| result.instructions.is_none()) {
+            result.instructions = Some(format!(
+                "Approve the payment prompt sent to +{} to complete the purchase",
+                phone
+            ));
+        }
 
+        mark_processing(conn, order.id, result.reference.clone())?;
         Ok(result)
     }
 
+    /// Send tokens from the user's account to the provider's settlement
+    /// account and ask the provider to pay out the fiat. The order is
+    /// persisted first and ends up `processing` until the provider's webhook
+    /// settles it, or `failed` with the reason if the transfer goes wrong.
+    /// A payout that fails after the transfer leaves it `payout_failed`, and
+    /// the tokens are sent back to the wallet.
+    pub async fn offramp<'a>(
+        &self,
+        wallet: TaskWallet<'a>,
+        conn: DbConn<'a>,
+        req: OffRampRequest,
+    ) -> Result<RampOrderRow> {
+        let provider = self.provider()?;
+        let channel = PaymentChannel::parse(&req.channel)?;
+        if channel == PaymentChannel::Card || !provider.supports(channel) {
+            return Err(anyhow!(
+                "{} doesn't pay out to {}",
+                provider.name(),
+                channel.as_str()
+            ));
+        }
+        let destination = if channel.needs_phone() {
+            normalize_phone(&req.destination)?
+        } else {
+            req.destination.clone()
+        };
+        let settlement_account = self
+            .ramper_settlement_account
+            .clone()
+            .ok_or_else(|| anyhow!("No settlement account configured for off-ramping"))?;
+        let amount = big_to_u64!(req.amount)?;
+        if amount == 0 {
+            return Err(anyhow!("Off-ramp amount must be positive"));
+        }
+
+        let token = get_asset(conn, req.token).await?;
+        let wallet_data = get_wallet(conn, req.wallet_id).await?;
+
+        let order = create_ramp_order(
+            conn,
+            CreateRampOrder {
+                direction: RampDirection::OffRamp,
+                wallet_id: wallet_data.id,
+                asset_id: token.id,
+                amount: req.amount.clone(),
+                currency: req.currency.clone(),
+                channel: channel.as_str().to_string(),
+                destination: Some(destination.clone()),
+                quote_id: None,
+                fiat_amount: None,
+                fee: BigDecimal::from(0),
+                fee_tokens: BigDecimal::from(0),
+            },
+        )?;
+
+        let transfer = transfer_to_settlement(
+            wallet,
+            wallet_data.contract_id.clone(),
+            token.token.clone(),
+            amount,
+            settlement_account.clone(),
+        )
+        .await;
+        let tx_id = match transfer {
+            Ok(tx_id) => tx_id,
+            Err(e) => {
+                return Err(abort_order(
+                    conn,
+                    order.id,
+                    format!("Settlement transfer failed: {}", e),
+                ));
+            }
+        };
+
+        let ledger_entry = record_transaction(
+            conn,
+            Some(wallet_data.address.clone()),
+            Some(settlement_account),
+            RecordTransactionAssets::Single(token.id),
+            Some(amount),
+            None,
+            Some(AccountLedgerTransactionType::Withdraw),
+            Some(tx_id),
+            None,
+        )?;
+        set_order_transaction(conn, order.id, ledger_entry)?;
+
+        let payout = PayoutRequest {
+            order_id: order.id,
+            token: token.name,
+            amount,
+            currency: req.currency,
+            email: req.email,
+            channel: channel.as_str().to_string(),
+            destination,
+            crypto_account: wallet_data.contract_id,
+        };
+
+        match provider.payout(&payout).await {
+            Ok(reference) => mark_processing(conn, order.id, reference),
+            Err(e) => {
+                let reason = format!("Payout request failed: {}", e);
+                let order = mark_payout_failed(conn, order.id, reason.clone())?;
+                self.return_payout(wallet, conn, &order).await;
+                Err(anyhow!(reason))
+            }
+        }
+    }
+
+    /// Send the tokens of a `payout_failed` off-ramp back to the wallet.
+    /// When that doesn't work the order stays `payout_failed` for an
+    /// approver to refund.
+    pub(crate) async fn return_payout<'a>(
+        &self,
+        wallet: TaskWallet<'a>,
+        conn: DbConn<'a>,
+        order: &RampOrderRow,
+    ) {
+        let refund = refund_order(
+            self,
+            wallet,
+            conn,
+            order.id,
+            None,
+            RefundRampOrderInputArgs {
+                reason: order.failure_reason.clone(),
+            },
+        )
+        .await;
+        if let Err(e) = refund {
+            println!(
+                "Failed to return the tokens of ramp order {}: {:?}",
+                order.id, e
+            );
+        }
+    }
+
+    /// Settle a ramp order from a verified provider webhook.
     pub async fn callback_handler<'a>(
         &self,
+        wallet: TaskWallet<'a>,
         conn: DbConn<'a>,
-        callback: CallbackData,
+        event: WebhookEvent,
     ) -> Result<()> {
-        //
+        self.settle_order(wallet, conn, event.order_id, event.status)
+            .await?;
         Ok(())
     }
+
+    /// Apply a provider's view of a transaction to its order. This is the
+    /// only way an on-ramp gets credited: once the provider confirms the
+    /// payment, and what it reports paid covers the order, the order is
+    /// queued for fulfillment, which mints and sends the tokens and refunds
+    /// the payment if it keeps failing. A short or mismatched payment fails
+    /// the order and is refunded instead. A failed order keeps the
+    /// provider's reason; a failed off-ramp whose tokens already went to
+    /// settlement becomes `payout_failed` and gets them sent back. Orders
+    /// still in flight, or that already finished,
+    /// are left alone, so redelivered webhooks are harmless. Returns the
+    /// order when it changed.
+    pub async fn settle_order<'a>(
+        &self,
+        wallet: TaskWallet<'a>,
+        conn: DbConn<'a>,
+        order_id: Uuid,
+        status: TransactionStatus,
+    ) -> Result<Option<RampOrderRow>> {
+        let order = get_ramp_order(conn, order_id)?
+            .ok_or_else(|| anyhow!("Unknown ramp order {}", order_id))?;
+
+        let Some(mut outcome) = status.outcome else {
+            return Ok(None);
+        };
+        let mut reason = status.failure_reason.clone();
+        let mut mismatch = false;
+        if order.direction == RampDirection::OnRamp && outcome == RampOrderStatus::Completed {
+            if let Err(e) = check_payment(&order, &status) {
+                outcome = RampOrderStatus::Failed;
+                reason = Some(e.to_string());
+                mismatch = true;
+            }
+        }
+        if order.direction == RampDirection::OffRamp
+            && outcome == RampOrderStatus::Failed
+            && order.transaction_id.is_some()
+        {
+            outcome = RampOrderStatus::PayoutFailed;
+        }
+
+        // claiming the order first keeps a concurrent redelivery from
+        // crediting it twice
+        let Some(order) = finish_ramp_order(conn, order.id, outcome, reason)? else {
+            return Ok(None);
+        };
+
+        if mismatch {
+            let refund = refund_order(
+                self,
+                wallet,
+                conn,
+                order.id,
+                None,
+                RefundRampOrderInputArgs {
+                    reason: order.failure_reason.clone(),
+                },
+            )
+            .await;
+            if let Err(e) = refund {
+                println!("Failed to refund ramp order {}: {:?}", order.id, e);
+            }
+            return Ok(get_ramp_order(conn, order.id)?);
+        }
+        if order.status == RampOrderStatus::PayoutFailed {
+            self.return_payout(wallet, conn, &order).await;
+            return Ok(get_ramp_order(conn, order.id)?);
+        }
+
+        if order.direction == RampDirection::OnRamp && order.status == RampOrderStatus::Completed {
+            // failed steps are retried by the fulfillment worker, so the
+            // webhook is acknowledged either way
+            if let Err(e) = start_fulfillment(self, wallet, conn, &order).await {
+                println!(
+                    "Failed to start fulfillment of ramp order {}: {:?}",
+                    order.id, e
+                );
+            }
+        }
+
+        Ok(Some(order))
+    }
+}
+
+/// Check a confirmed on-ramp payment against its order before anything is
+/// minted. Providers that don't report the amount or currency are trusted
+/// on those.
+fn check_payment(order: &RampOrderRow, status: &TransactionStatus) -> Result<()> {
+    if let Some(currency) = &status.currency {
+        if !currency.eq_ignore_ascii_case(&order.currency) {
+            return Err(anyhow!(
+                "Paid in {}, but the order is in {}",
+                currency,
+                order.currency
+            ));
+        }
+    }
+    let due = charged_fiat(order);
+    if let Some(paid) = &status.amount {
+        if *paid < due {
+            return Err(anyhow!(
+                "Paid {} {}, but the order needs {}",
+                paid,
+                order.currency,
+                due
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Move tokens out of a cradle account to a settlement account, returning
+/// the transaction id.
+pub(crate) async fn transfer_to_settlement(
+    wallet: TaskWallet<'_>,
+    account_contract_id: String,
+    asset: String,
+    amount: u64,
+    settlement_account: String,
+) -> Result<String> {
+    withdraw_from_account(
+        wallet,
+        account_contract_id,
+        asset,
+        amount,
+        settlement_account,
+    )
+    .await
+}
+
+/// Move tokens from the settlement account's contract back to a wallet,
+/// returning the transaction id.
+pub(crate) async fn transfer_from_settlement(
+    wallet: TaskWallet<'_>,
+    settlement_contract: String,
+    asset: String,
+    amount: u64,
+    to: String,
+) -> Result<String> {
+    withdraw_from_account(wallet, settlement_contract, asset, amount, to).await
+}
+
+async fn withdraw_from_account(
+    wallet: TaskWallet<'_>,
+    account_contract_id: String,
+    asset: String,
+    amount: u64,
+    to: String,
+) -> Result<String> {
+    let res = wallet
+        .execute(ContractCallInput::CradleAccount(
+            CradleAccountFunctionInput::Withdraw(WithdrawArgs {
+                account_contract_id,
+                amount,
+                to,
+                asset,
+            }),
+        ))
+        .await?;
+
+    match res {
+        ContractCallOutput::CradleAccount(CradleAccountFunctionOutput::Withdraw(o)) => {
+            Ok(o.transaction_id)
+        }
+        _ => Err(anyhow!("Unexpected output from settlement transfer")),
+    }
+}
+
+/// Mark an order failed and hand back the error to return.
+fn abort_order(conn: DbConn<'_>, order: Uuid, reason: String) -> anyhow::Error {
+    if let Err(e) = fail_ramp_order(conn, order, reason.clone()) {
+        println!("Failed to mark ramp order {} as failed: {:?}", order, e);
+    }
+    anyhow!(reason)
 }
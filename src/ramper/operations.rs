@@ -0,0 +1,207 @@
+use anyhow::Result;
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::ramper::db_types::{
+    CreateRampOrder, CreateRampOrderEvent, RampOrderEventRow, RampOrderRow, RampOrderStatus,
+};
+use crate::utils::commons::DbConn;
+
+pub fn create_ramp_order(conn: DbConn<'_>, order: CreateRampOrder) -> Result<RampOrderRow> {
+    use crate::schema::ramp_orders::dsl::*;
+
+    Ok(diesel::insert_into(ramp_orders)
+        .values(&order)
+        .get_result::<RampOrderRow>(conn)?)
+}
+
+pub fn get_ramp_order(conn: DbConn<'_>, order: Uuid) -> Result<Option<RampOrderRow>> {
+    use crate::schema::ramp_orders::dsl::*;
+
+    Ok(ramp_orders
+        .find(order)
+        .get_result::<RampOrderRow>(conn)
+        .optional()?)
+}
+
+/// Link the ledger entry of the order's token movement: the transfer to the
+/// settlement account for off-ramps, the credit to the wallet for on-ramps.
+pub fn set_order_transaction(
+    conn: DbConn<'_>,
+    order: Uuid,
+    ledger_entry: Uuid,
+) -> Result<RampOrderRow> {
+    use crate::schema::ramp_orders::dsl::*;
+
+    Ok(diesel::update(ramp_orders.find(order))
+        .set((
+            transaction_id.eq(Some(ledger_entry)),
+            updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .get_result::<RampOrderRow>(conn)?)
+}
+
+pub fn mark_processing(conn: DbConn<'_>, order: Uuid, reference: String) -> Result<RampOrderRow> {
+    use crate::schema::ramp_orders::dsl::*;
+
+    Ok(diesel::update(ramp_orders.find(order))
+        .set((
+            status.eq(RampOrderStatus::Processing),
+            provider_reference.eq(Some(reference)),
+            updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .get_result::<RampOrderRow>(conn)?)
+}
+
+/// Move an order that is still in flight to a final status. Returns `None`
+/// when it had already finished, so repeated webhooks change nothing.
+pub fn finish_ramp_order(
+    conn: DbConn<'_>,
+    order: Uuid,
+    outcome: RampOrderStatus,
+    reason: Option<String>,
+) -> Result<Option<RampOrderRow>> {
+    use crate::schema::ramp_orders::dsl::*;
+
+    Ok(diesel::update(ramp_orders)
+        .filter(id.eq(order))
+        .filter(status.eq_any(vec![RampOrderStatus::Pending, RampOrderStatus::Processing]))
+        .set((
+            status.eq(outcome),
+            failure_reason.eq(reason),
+            updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .get_result::<RampOrderRow>(conn)
+        .optional()?)
+}
+
+/// Mark an order failed whatever state it is in, e.g. when crediting a paid
+/// on-ramp order fails after it was settled.
+pub fn fail_ramp_order(conn: DbConn<'_>, order: Uuid, reason: String) -> Result<RampOrderRow> {
+    use crate::schema::ramp_orders::dsl::*;
+
+    Ok(diesel::update(ramp_orders.find(order))
+        .set((
+            status.eq(RampOrderStatus::Failed),
+            failure_reason.eq(Some(reason)),
+            updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .get_result::<RampOrderRow>(conn)?)
+}
+
+/// Mark an off-ramp `payout_failed` once its tokens are in settlement but
+/// the payout won't happen.
+pub fn mark_payout_failed(conn: DbConn<'_>, order: Uuid, reason: String) -> Result<RampOrderRow> {
+    use crate::schema::ramp_orders::dsl::*;
+
+    Ok(diesel::update(ramp_orders.find(order))
+        .set((
+            status.eq(RampOrderStatus::PayoutFailed),
+            failure_reason.eq(Some(reason)),
+            updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .get_result::<RampOrderRow>(conn)?)
+}
+
+/// Look an order up by its id or the provider's reference for it.
+pub fn find_ramp_order(conn: DbConn<'_>, reference: &str) -> Result<Option<RampOrderRow>> {
+    if let Ok(order) = Uuid::parse_str(reference) {
+        if let Some(row) = get_ramp_order(conn, order)? {
+            return Ok(Some(row));
+        }
+    }
+
+    use crate::schema::ramp_orders::dsl::*;
+
+    Ok(ramp_orders
+        .filter(provider_reference.eq(reference))
+        .get_result::<RampOrderRow>(conn)
+        .optional()?)
+}
+
+/// Orders still waiting on the provider that haven't moved since `before`,
+/// oldest first.
+pub fn get_stale_orders(conn: DbConn<'_>, before: NaiveDateTime) -> Result<Vec<RampOrderRow>> {
+    use crate::schema::ramp_orders::dsl::*;
+
+    Ok(ramp_orders
+        .filter(status.eq_any(vec![RampOrderStatus::Pending, RampOrderStatus::Processing]))
+        .filter(updated_at.lt(before))
+        .order(updated_at.asc())
+        .limit(100)
+        .get_results::<RampOrderRow>(conn)?)
+}
+
+/// Claim an order for a refund, moving it to `refunding` as long as it is
+/// still in the status it was checked in. Returns `None` when another
+/// refund, or anything else, changed it first.
+pub fn claim_refund(conn: DbConn<'_>, order: &RampOrderRow) -> Result<Option<RampOrderRow>> {
+    use crate::schema::ramp_orders::dsl::*;
+
+    Ok(diesel::update(ramp_orders)
+        .filter(id.eq(order.id))
+        .filter(status.eq(order.status))
+        .set((
+            status.eq(RampOrderStatus::Refunding),
+            updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .get_result::<RampOrderRow>(conn)
+        .optional()?)
+}
+
+/// Put an order whose refund failed back in the status it was claimed from.
+pub fn release_refund(conn: DbConn<'_>, order: &RampOrderRow) -> Result<()> {
+    use crate::schema::ramp_orders::dsl::*;
+
+    diesel::update(ramp_orders)
+        .filter(id.eq(order.id))
+        .filter(status.eq(RampOrderStatus::Refunding))
+        .set((
+            status.eq(order.status),
+            updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+pub fn mark_refunded(conn: DbConn<'_>, order: Uuid, reference: String) -> Result<RampOrderRow> {
+    use crate::schema::ramp_orders::dsl::*;
+
+    Ok(diesel::update(ramp_orders.find(order))
+        .set((
+            status.eq(RampOrderStatus::Refunded),
+            refund_reference.eq(Some(reference)),
+            updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .get_result::<RampOrderRow>(conn)?)
+}
+
+pub fn record_order_event(
+    conn: DbConn<'_>,
+    order: Uuid,
+    event_name: &str,
+    by: Option<Uuid>,
+    details: Option<String>,
+) -> Result<RampOrderEventRow> {
+    use crate::schema::ramp_order_events::dsl::*;
+
+    Ok(diesel::insert_into(ramp_order_events)
+        .values(CreateRampOrderEvent {
+            order_id: order,
+            event: event_name.to_string(),
+            actor: by,
+            note: details,
+        })
+        .get_result::<RampOrderEventRow>(conn)?)
+}
+
+/// An order's audit trail, oldest first.
+pub fn get_order_events(conn: DbConn<'_>, order: Uuid) -> Result<Vec<RampOrderEventRow>> {
+    use crate::schema::ramp_order_events::dsl::*;
+
+    Ok(ramp_order_events
+        .filter(order_id.eq(order))
+        .order(created_at.asc())
+        .get_results::<RampOrderEventRow>(conn)?)
+}
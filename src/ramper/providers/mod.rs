@@ -0,0 +1,179 @@
+pub mod orion;
+
+use anyhow::{Result, anyhow};
+use axum::http::HeaderMap;
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::ramper::{OnRampResponse, Ramper, channels::PaymentChannel, db_types::RampOrderStatus};
+use orion::OrionProvider;
+
+/// What a provider needs to start collecting a fiat payment.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PaymentRequest {
+    /// Ramp order id, echoed back in the provider's webhooks
+    pub order_id: Uuid,
+    /// Name of the token the user receives
+    pub token: String,
+    pub amount: u64,
+    pub currency: String,
+    pub email: String,
+    pub channel: String,
+    /// Number the STK push goes to, for mobile money
+    pub phone: Option<String>,
+    /// Contract id of the wallet being credited
+    pub crypto_account: String,
+    /// Page the user is sent back to after paying
+    pub return_url: String,
+}
+
+/// What a provider needs to pay fiat out for tokens already settled to it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PayoutRequest {
+    pub order_id: Uuid,
+    pub token: String,
+    pub amount: u64,
+    pub currency: String,
+    pub email: String,
+    pub channel: String,
+    /// Bank account or phone number the payout goes to
+    pub destination: String,
+    pub crypto_account: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionStatus {
+    /// `Completed` or `Failed` once the provider is done with the
+    /// transaction; `None` while it is still in flight
+    pub outcome: Option<RampOrderStatus>,
+    pub failure_reason: Option<String>,
+    /// What the provider says was paid, when it reports it
+    pub amount: Option<BigDecimal>,
+    pub currency: Option<String>,
+}
+
+/// A verified provider webhook.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WebhookEvent {
+    pub order_id: Uuid,
+    pub status: TransactionStatus,
+}
+
+/// A payment provider the ramp flows run through. Adding one means
+/// implementing this and naming it in `Provider::from_config`.
+pub trait RampProvider {
+    fn name(&self) -> &'static str;
+
+    /// Base URL requests go to, for the configured environment.
+    fn api_url(&self) -> &str;
+
+    /// Whether `url` is one of the provider's test deployments, which a
+    /// production setup must not point at.
+    fn is_sandbox_url(&self, url: &str) -> bool;
+
+    /// Whether payments and payouts can go through `channel`.
+    fn supports(&self, channel: PaymentChannel) -> bool;
+
+    /// Start a payment; the response carries the page the user pays on.
+    async fn initialize(&self, payment: &PaymentRequest) -> Result<OnRampResponse>;
+
+    /// Request a payout, returning the provider's reference for it.
+    async fn payout(&self, payout: &PayoutRequest) -> Result<String>;
+
+    async fn status(&self, reference: &str) -> Result<TransactionStatus>;
+
+    /// Refund a payment, in full when `amount` is `None`. Returns the
+    /// provider's reference for the refund.
+    async fn refund(&self, reference: &str, amount: Option<u64>) -> Result<String>;
+
+    /// Verify and decode a webhook from its headers and raw body.
+    fn parse_webhook(&self, headers: &HeaderMap, body: &[u8]) -> Result<WebhookEvent>;
+}
+
+/// Read a provider's event or status name as an outcome. Anything that
+/// doesn't look final is treated as still in flight. A mobile money prompt
+/// the user declined or let lapse counts as failed.
+pub fn outcome_of(event: &str) -> Option<RampOrderStatus> {
+    let event = event.to_lowercase();
+    if ["fail", "cancel", "decline", "expire", "timeout"]
+        .iter()
+        .any(|s| event.contains(s))
+    {
+        Some(RampOrderStatus::Failed)
+    } else if event.contains("success") || event.contains("complete") {
+        Some(RampOrderStatus::Completed)
+    } else {
+        None
+    }
+}
+
+pub enum Provider {
+    Orion(OrionProvider),
+}
+
+impl Provider {
+    /// The provider named by RAMPER_PROVIDER.
+    pub fn from_config(config: &Ramper) -> Result<Self> {
+        match config.ramper_provider.as_str() {
+            "" | "orion" => Ok(Provider::Orion(OrionProvider::new(config))),
+            other => Err(anyhow!("Unknown RAMPER_PROVIDER {}", other)),
+        }
+    }
+}
+
+impl RampProvider for Provider {
+    fn name(&self) -> &'static str {
+        match self {
+            Provider::Orion(p) => p.name(),
+        }
+    }
+
+    fn api_url(&self) -> &str {
+        match self {
+            Provider::Orion(p) => p.api_url(),
+        }
+    }
+
+    fn is_sandbox_url(&self, url: &str) -> bool {
+        match self {
+            Provider::Orion(p) => p.is_sandbox_url(url),
+        }
+    }
+
+    fn supports(&self, channel: PaymentChannel) -> bool {
+        match self {
+            Provider::Orion(p) => p.supports(channel),
+        }
+    }
+
+    async fn initialize(&self, payment: &PaymentRequest) -> Result<OnRampResponse> {
+        match self {
+            Provider::Orion(p) => p.initialize(payment).await,
+        }
+    }
+
+    async fn payout(&self, payout: &PayoutRequest) -> Result<String> {
+        match self {
+            Provider::Orion(p) => p.payout(payout).await,
+        }
+    }
+
+    async fn status(&self, reference: &str) -> Result<TransactionStatus> {
+        match self {
+            Provider::Orion(p) => p.status(reference).await,
+        }
+    }
+
+    async fn refund(&self, reference: &str, amount: Option<u64>) -> Result<String> {
+        match self {
+            Provider::Orion(p) => p.refund(reference, amount).await,
+        }
+    }
+
+    fn parse_webhook(&self, headers: &HeaderMap, body: &[u8]) -> Result<WebhookEvent> {
+        match self {
+            Provider::Orion(p) => p.parse_webhook(headers, body),
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+use axum::http::HeaderMap;
+use bigdecimal::BigDecimal;
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::ramper::{
+    OnRampResponse, RampEnvironment, Ramper,
+    channels::PaymentChannel,
+    providers::{
+        PaymentRequest, PayoutRequest, RampProvider, TransactionStatus, WebhookEvent, outcome_of,
+    },
+};
+
+const ORION_SANDBOX_URL: &str = "https://test.api.orionramp.com/api";
+const ORION_PRODUCTION_URL: &str = "https://api.orionramp.com/api";
+
+/// Header carrying the hex HMAC-SHA256 of a callback body, keyed with
+/// `ramper_webhook_secret`.
+pub const SIGNATURE_HEADER: &str = "x-ramper-signature";
+
+#[derive(Serialize, Deserialize)]
+pub struct RequestMetadata {
+    #[serde(rename = "orderID")]
+    pub order_id: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RequestToken {
+    pub token: String,
+    pub amount: u64,
+    pub email: String,
+    pub currency: String,
+    pub metadata: RequestMetadata,
+    pub callback_url: String,
+    pub channels: Vec<String>,
+    /// Number to send the STK push to when paying by mobile money
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    pub crypto_account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct OrionPayoutRequest {
+    pub token: String,
+    pub amount: u64,
+    pub email: String,
+    pub currency: String,
+    pub metadata: RequestMetadata,
+    pub callback_url: String,
+    pub channel: String,
+    pub destination: String,
+    pub crypto_account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct OrionReference {
+    pub reference: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct OrionRefundRequest {
+    pub reference: String,
+    pub amount: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct OrionStatusResponse {
+    pub status: String,
+    #[serde(rename = "failureReason")]
+    pub failure_reason: Option<String>,
+    #[serde(default)]
+    pub amount: Option<String>,
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+// {
+//   event_type: string;
+//   order_id: <orderid-given-when-initializing-payment>;
+//   token: <token-you-indicated-to-receive>;
+//   amount: <amount-user-paid>;
+//   currency: <currency-user-pay-in> | null;
+//   failureReason?: <optional-further-explanation-incase-failure>;
+// }
+#[derive(Serialize, Deserialize)]
+pub struct CallbackData {
+    pub event_type: String,
+    pub order_id: String,
+    pub token: String,
+    pub amount: String,
+    pub currency: Option<String>,
+    #[serde(rename = "failureReason")]
+    pub failure_reason: Option<String>,
+}
+
+pub struct OrionProvider {
+    client: Client,
+    base_url: String,
+    token: String,
+    webhook_secret: String,
+    callback: String,
+}
+
+impl OrionProvider {
+    pub fn new(config: &Ramper) -> Self {
+        let base_url = config.ramper_api_url.clone().unwrap_or_else(|| {
+            match config.ramper_environment {
+                RampEnvironment::Sandbox => ORION_SANDBOX_URL,
+                RampEnvironment::Production => ORION_PRODUCTION_URL,
+            }
+            .to_string()
+        });
+
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: config.ramper_token.clone(),
+            webhook_secret: config.ramper_webhook_secret.clone(),
+            callback: config.ramper_callback.clone(),
+        }
+    }
+
+    fn post(&self, path: &str) -> reqwest::RequestBuilder {
+        self.client
+            .post(format!("{}{}", self.base_url, path))
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Content-Type", "application/json")
+    }
+
+    /// Check a callback body against the signature sent with it.
+    pub fn verify_signature(&self, body: &[u8], signature: &str) -> Result<()> {
+        let provided = hex::decode(signature.trim()).map_err(|_| anyhow!("Malformed signature"))?;
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.webhook_secret.as_bytes())
+            .expect("hmac accepts any key length");
+        mac.update(body);
+        mac.verify_slice(&provided)
+            .map_err(|_| anyhow!("Invalid signature"))
+    }
+}
+
+impl RampProvider for OrionProvider {
+    fn name(&self) -> &'static str {
+        "orion"
+    }
+
+    fn api_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn is_sandbox_url(&self, url: &str) -> bool {
+        url.trim_end_matches('/') == ORION_SANDBOX_URL
+    }
+
+    fn supports(&self, _channel: PaymentChannel) -> bool {
+        true
+    }
+
+    async fn initialize(&self, payment: &PaymentRequest) -> Result<OnRampResponse> {
+        let request = RequestToken {
+            token: payment.token.clone(),
+            amount: payment.amount,
+            email: payment.email.clone(),
+            currency: payment.currency.clone(),
+            metadata: RequestMetadata {
+                order_id: payment.order_id.to_string(),
+            },
+            callback_url: payment.return_url.clone(),
+            channels: vec![payment.channel.clone()],
+            phone: payment.phone.clone(),
+            crypto_account: payment.crypto_account.clone(),
+        };
+
+        let response = self
+            .post("/transaction/initialize")
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json::<OnRampResponse>().await?)
+    }
+
+    async fn payout(&self, payout: &PayoutRequest) -> Result<String> {
+        let request = OrionPayoutRequest {
+            token: payout.token.clone(),
+            amount: payout.amount,
+            email: payout.email.clone(),
+            currency: payout.currency.clone(),
+            metadata: RequestMetadata {
+                order_id: payout.order_id.to_string(),
+            },
+            callback_url: self.callback.clone(),
+            channel: payout.channel.clone(),
+            destination: payout.destination.clone(),
+            crypto_account: payout.crypto_account.clone(),
+        };
+
+        let response = self
+            .post("/transaction/payout")
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json::<OrionReference>().await?.reference)
+    }
+
+    async fn status(&self, reference: &str) -> Result<TransactionStatus> {
+        let response = self
+            .client
+            .get(format!(
+                "{}/transaction/verify/{}",
+                self.base_url, reference
+            ))
+            .header("Authorization", format!("Bearer {}", self.token))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<OrionStatusResponse>()
+            .await?;
+
+        Ok(TransactionStatus {
+            outcome: outcome_of(&response.status),
+            failure_reason: response.failure_reason,
+            amount: response.amount.as_deref().map(parse_amount).transpose()?,
+            currency: response.currency,
+        })
+    }
+
+    async fn refund(&self, reference: &str, amount: Option<u64>) -> Result<String> {
+        let response = self
+            .post("/transaction/refund")
+            .json(&OrionRefundRequest {
+                reference: reference.to_string(),
+                amount,
+            })
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json::<OrionReference>().await?.reference)
+    }
+
+    fn parse_webhook(&self, headers: &HeaderMap, body: &[u8]) -> Result<WebhookEvent> {
+        let signature = headers
+            .get(SIGNATURE_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| anyhow!("Missing callback signature"))?;
+        self.verify_signature(body, signature)?;
+
+        let callback = serde_json::from_slice::<CallbackData>(body)
+            .map_err(|e| anyhow!("Invalid callback body: {}", e))?;
+        let order_id = Uuid::parse_str(&callback.order_id)
+            .map_err(|_| anyhow!("Invalid order id {}", callback.order_id))?;
+
+        Ok(WebhookEvent {
+            order_id,
+            status: TransactionStatus {
+                outcome: outcome_of(&callback.event_type),
+                failure_reason: callback.failure_reason,
+                amount: Some(parse_amount(&callback.amount)?),
+                currency: callback.currency,
+            },
+        })
+    }
+}
+
+fn parse_amount(amount: &str) -> Result<BigDecimal> {
+    BigDecimal::from_str(amount.trim()).map_err(|_| anyhow!("Invalid amount {}", amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(secret: &str) -> OrionProvider {
+        OrionProvider {
+            client: Client::new(),
+            base_url: ORION_SANDBOX_URL.to_string(),
+            token: "token".to_string(),
+            webhook_secret: secret.to_string(),
+            callback: "https://example.com/callback".to_string(),
+        }
+    }
+
+    fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    const BODY: &[u8] = br#"{"event_type":"payment.success","order_id":"abc","amount":"100"}"#;
+
+    #[test]
+    fn accepts_the_body_it_was_signed_over() {
+        let orion = provider("secret");
+        assert!(orion.verify_signature(BODY, &sign("secret", BODY)).is_ok());
+        assert!(
+            orion
+                .verify_signature(BODY, &format!(" {}\n", sign("secret", BODY)))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn rejects_a_tampered_body_or_another_key() {
+        let orion = provider("secret");
+        let tampered = br#"{"event_type":"payment.success","order_id":"abc","amount":"900"}"#;
+        assert!(
+            orion
+                .verify_signature(tampered, &sign("secret", BODY))
+                .is_err()
+        );
+        assert!(orion.verify_signature(BODY, &sign("other", BODY)).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_signatures() {
+        let orion = provider("secret");
+        let valid = sign("secret", BODY);
+        assert!(orion.verify_signature(BODY, "").is_err());
+        assert!(orion.verify_signature(BODY, "not hex").is_err());
+        assert!(
+            orion
+                .verify_signature(BODY, &valid[..valid.len() - 1])
+                .is_err()
+        );
+        assert!(
+            orion
+                .verify_signature(BODY, &valid[..valid.len() - 2])
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+use bigdecimal::{BigDecimal, RoundingMode};
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::asset_book::db_types::AssetBookRecord;
+use crate::asset_book::operations::get_asset;
+use crate::ramper::db_types::{CreateRampQuote, RampQuoteRow};
+use crate::ramper::fees::{fee_for, fee_in_tokens};
+use crate::utils::commons::DbConn;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RampQuoteInputArgs {
+    pub token: Uuid,
+    pub currency: String,
+    /// Quote for paying this much fiat; set this or `token_amount`
+    pub fiat_amount: Option<BigDecimal>,
+    /// Quote for receiving this many tokens; set this or `fiat_amount`
+    pub token_amount: Option<BigDecimal>,
+}
+
+#[derive(Deserialize)]
+struct RateResponse {
+    rate: BigDecimal,
+}
+
+/// Where fiat/token rates come from, picked by RAMP_RATE_SOURCE.
+pub enum RateSource {
+    /// Rates from RAMP_FIXED_RATES, e.g. `KES/CpUSD=129.5,USD/CpUSD=1`
+    Fixed(Vec<(String, String, BigDecimal)>),
+    /// GET `RAMP_RATE_URL?base=<symbol>&quote=<currency>`, answering
+    /// `{ "rate": "129.5" }`
+    Http { client: Client, url: String },
+}
+
+impl RateSource {
+    pub fn from_env() -> Result<Self> {
+        match std::env::var("RAMP_RATE_SOURCE")
+            .unwrap_or_default()
+            .as_str()
+        {
+            "" | "fixed" => {
+                let rates = std::env::var("RAMP_FIXED_RATES").unwrap_or_default();
+                let mut parsed = Vec::new();
+                for entry in rates.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                    let (pair, rate) = entry
+                        .split_once('=')
+                        .ok_or_else(|| anyhow!("Invalid RAMP_FIXED_RATES entry {}", entry))?;
+                    let (currency, symbol) = pair
+                        .split_once('/')
+                        .ok_or_else(|| anyhow!("Invalid RAMP_FIXED_RATES entry {}", entry))?;
+                    parsed.push((
+                        currency.trim().to_uppercase(),
+                        symbol.trim().to_string(),
+                        BigDecimal::from_str(rate.trim())?,
+                    ));
+                }
+                Ok(RateSource::Fixed(parsed))
+            }
+            "http" => Ok(RateSource::Http {
+                client: Client::new(),
+                url: std::env::var("RAMP_RATE_URL")
+                    .map_err(|_| anyhow!("RAMP_RATE_URL is required for the http rate source"))?,
+            }),
+            other => Err(anyhow!("Unknown RAMP_RATE_SOURCE {}", other)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RateSource::Fixed(_) => "fixed",
+            RateSource::Http { .. } => "http",
+        }
+    }
+
+    /// Fiat in `currency` per unit of the token with `symbol`.
+    pub async fn rate(&self, currency: &str, symbol: &str) -> Result<BigDecimal> {
+        let rate = match self {
+            RateSource::Fixed(rates) => rates
+                .iter()
+                .find(|(c, s, _)| c.eq_ignore_ascii_case(currency) && s == symbol)
+                .map(|(_, _, rate)| rate.clone())
+                .ok_or_else(|| anyhow!("No rate for {}/{}", currency, symbol))?,
+            RateSource::Http { client, url } => {
+                client
+                    .get(url)
+                    .query(&[("base", symbol), ("quote", currency)])
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<RateResponse>()
+                    .await?
+                    .rate
+            }
+        };
+
+        if rate <= BigDecimal::from(0) {
+            return Err(anyhow!("Rate for {}/{} is not positive", currency, symbol));
+        }
+        Ok(rate)
+    }
+}
+
+/// How long a quote can be used for, from RAMP_QUOTE_TTL_SECS.
+fn quote_ttl() -> Duration {
+    let secs = std::env::var("RAMP_QUOTE_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(120);
+    Duration::seconds(secs)
+}
+
+/// Largest move of the rate, in basis points, a quote still holds through,
+/// from RAMP_MAX_SLIPPAGE_BPS.
+fn max_slippage_bps() -> BigDecimal {
+    std::env::var("RAMP_MAX_SLIPPAGE_BPS")
+        .ok()
+        .and_then(|v| BigDecimal::from_str(&v).ok())
+        .unwrap_or_else(|| BigDecimal::from(100))
+}
+
+/// Price a fiat amount in tokens or the other way round. Token amounts are
+/// whole units, rounded down; fiat is rounded up to the cent. The ramp fee
+/// for `provider` is part of the fiat and held back from the tokens.
+pub async fn create_quote(
+    conn: DbConn<'_>,
+    provider: &str,
+    input: RampQuoteInputArgs,
+) -> Result<RampQuoteRow> {
+    let zero = BigDecimal::from(0);
+    let asset = get_asset(conn, input.token).await?;
+    let rate_source = RateSource::from_env()?;
+    let quote_currency = input.currency.to_uppercase();
+    let quoted_rate = rate_source.rate(&quote_currency, &asset.symbol).await?;
+
+    let tokens = match (input.fiat_amount, input.token_amount) {
+        (Some(fiat), None) if fiat > zero => {
+            (fiat / &quoted_rate).with_scale_round(0, RoundingMode::Down)
+        }
+        (None, Some(tokens)) if tokens > zero => tokens.with_scale_round(0, RoundingMode::Down),
+        _ => {
+            return Err(anyhow!(
+                "Give a positive fiat_amount or token_amount, not both"
+            ));
+        }
+    };
+    if tokens <= zero {
+        return Err(anyhow!("Amount is too small to buy a whole token"));
+    }
+    let fiat = (&tokens * &quoted_rate).with_scale_round(2, RoundingMode::Up);
+    let quoted_fee = fee_for(provider, &quote_currency)?.on(&fiat);
+    let quoted_fee_tokens = fee_in_tokens(&quoted_fee, &quoted_rate);
+    if quoted_fee_tokens >= tokens {
+        return Err(anyhow!(
+            "Amount doesn't cover the {} {} ramp fee",
+            quoted_fee,
+            quote_currency
+        ));
+    }
+
+    use crate::schema::ramp_quotes::dsl::*;
+
+    Ok(diesel::insert_into(ramp_quotes)
+        .values(CreateRampQuote {
+            asset_id: asset.id,
+            currency: quote_currency,
+            rate: quoted_rate,
+            fiat_amount: fiat,
+            token_amount: tokens,
+            source: rate_source.name().to_string(),
+            expires_at: Utc::now().naive_utc() + quote_ttl(),
+            fee: quoted_fee,
+            fee_tokens: quoted_fee_tokens,
+        })
+        .get_result::<RampQuoteRow>(conn)?)
+}
+
+pub fn get_quote(conn: DbConn<'_>, quote: Uuid) -> Result<RampQuoteRow> {
+    use crate::schema::ramp_quotes::dsl::*;
+
+    Ok(ramp_quotes.find(quote).get_result::<RampQuoteRow>(conn)?)
+}
+
+/// Check a quote an on-ramp was started with and mark it used. It has to be
+/// for this token and amount, still valid and unused, and the rate can't
+/// have moved beyond RAMP_MAX_SLIPPAGE_BPS since it was given.
+pub async fn redeem_quote(
+    conn: DbConn<'_>,
+    asset: &AssetBookRecord,
+    quote_id: Uuid,
+    tokens: &BigDecimal,
+) -> Result<RampQuoteRow> {
+    let quote = get_quote(conn, quote_id)?;
+    if quote.asset_id != asset.id {
+        return Err(anyhow!("Quote {} is for a different token", quote.id));
+    }
+    if quote.token_amount != *tokens {
+        return Err(anyhow!(
+            "Quote {} is for {} tokens, not {}",
+            quote.id,
+            quote.token_amount,
+            tokens
+        ));
+    }
+    let now = Utc::now().naive_utc();
+    if quote.expires_at < now {
+        return Err(anyhow!("Quote {} has expired; request a new one", quote.id));
+    }
+
+    let current = RateSource::from_env()?
+        .rate(&quote.currency, &asset.symbol)
+        .await?;
+    let drift_bps = (&current - &quote.rate).abs() * BigDecimal::from(10_000) / &quote.rate;
+    if drift_bps > max_slippage_bps() {
+        return Err(anyhow!(
+            "The rate moved from {} to {} since quote {}; request a new one",
+            quote.rate,
+            current,
+            quote.id
+        ));
+    }
+
+    use crate::schema::ramp_quotes::dsl::*;
+
+    diesel::update(ramp_quotes)
+        .filter(id.eq(quote.id))
+        .filter(used_at.is_null())
+        .set(used_at.eq(Some(now)))
+        .get_result::<RampQuoteRow>(conn)
+        .optional()?
+        .ok_or_else(|| anyhow!("Quote {} has already been used", quote.id))
+}
@@ -0,0 +1,76 @@
+use anyhow::{Result, anyhow};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    asset_book::operations::get_asset,
+    notifications::operations::notify_wallet,
+    ramper::{
+        db_types::{CreateRampReceipt, RampOrderRow, RampReceiptRow},
+        fees::credited_tokens,
+    },
+    utils::commons::DbConn,
+};
+
+pub fn get_receipt(conn: DbConn<'_>, order: Uuid) -> Result<Option<RampReceiptRow>> {
+    use crate::schema::ramp_receipts::dsl::*;
+
+    Ok(ramp_receipts
+        .filter(order_id.eq(order))
+        .get_result::<RampReceiptRow>(conn)
+        .optional()?)
+}
+
+/// Record what a fulfilled on-ramp order credited and put it in the wallet's
+/// notifications as `ramp:receipt`. An order only ever gets one receipt;
+/// issuing it again returns the existing one without notifying.
+pub async fn issue_receipt(
+    conn: DbConn<'_>,
+    order: &RampOrderRow,
+    airdrop_tx: Option<String>,
+) -> Result<RampReceiptRow> {
+    if let Some(existing) = get_receipt(conn, order.id)? {
+        return Ok(existing);
+    }
+
+    let asset = get_asset(conn, order.asset_id).await?;
+    let receipt = CreateRampReceipt {
+        order_id: order.id,
+        wallet_id: order.wallet_id,
+        asset_id: order.asset_id,
+        token_symbol: asset.symbol,
+        token_amount: credited_tokens(order),
+        currency: order.currency.clone(),
+        fiat_amount: order.fiat_amount.clone(),
+        fee: order.fee.clone(),
+        channel: order.channel.clone(),
+        provider_reference: order.provider_reference.clone(),
+        tx_hash: airdrop_tx,
+    };
+
+    let inserted = {
+        use crate::schema::ramp_receipts::dsl::*;
+
+        diesel::insert_into(ramp_receipts)
+            .values(&receipt)
+            .on_conflict(order_id)
+            .do_nothing()
+            .get_result::<RampReceiptRow>(conn)
+            .optional()?
+    };
+
+    // lost a race with another worker, which already sent the notification
+    let Some(inserted) = inserted else {
+        return get_receipt(conn, order.id)?
+            .ok_or_else(|| anyhow!("Receipt for ramp order {} disappeared", order.id));
+    };
+
+    notify_wallet(
+        conn,
+        order.wallet_id,
+        "ramp:receipt",
+        &serde_json::to_value(&inserted)?,
+    )?;
+
+    Ok(inserted)
+}
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::order_book::operations::can_execute_onchain;
+use crate::ramper::{
+    Ramper,
+    db_types::{RampDirection, RampOrderRow},
+    operations::{fail_ramp_order, get_stale_orders, mark_payout_failed},
+    providers::{Provider, RampProvider},
+};
+use crate::utils::app_config::AppConfig;
+
+/// Seconds between polls, from RAMP_RECONCILE_INTERVAL_SECS.
+fn poll_interval() -> Duration {
+    let secs = std::env::var("RAMP_RECONCILE_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(300);
+    Duration::from_secs(secs)
+}
+
+/// How long an order can wait on its webhook before the provider is asked
+/// about it, from RAMP_PENDING_TIMEOUT_SECS.
+pub(crate) fn pending_timeout() -> chrono::Duration {
+    let secs = std::env::var("RAMP_PENDING_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(900);
+    chrono::Duration::seconds(secs)
+}
+
+async fn reconcile_order(
+    app_config: &AppConfig,
+    ramper: &Ramper,
+    provider: &Provider,
+    order: &RampOrderRow,
+) -> Result<()> {
+    let mut conn = app_config.pool.get()?;
+
+    let wallet = &app_config.wallet;
+
+    // never got a reference, so the provider has nothing to report on
+    let Some(reference) = &order.provider_reference else {
+        let reason = "The provider never accepted the order".to_string();
+        // an off-ramp's tokens may already be in settlement
+        if order.direction == RampDirection::OffRamp && order.transaction_id.is_some() {
+            let order = mark_payout_failed(&mut conn, order.id, reason)?;
+            ramper.return_payout(wallet, &mut conn, &order).await;
+        } else {
+            fail_ramp_order(&mut conn, order.id, reason)?;
+        }
+        return Ok(());
+    };
+
+    let status = provider.status(reference).await?;
+    if let Some(settled) = ramper
+        .settle_order(wallet, &mut conn, order.id, status)
+        .await?
+    {
+        println!(
+            "Ramp order {} settled as {:?} by reconciliation",
+            settled.id, settled.status
+        );
+    }
+    Ok(())
+}
+
+/// Ask the provider about every order that has waited too long on its
+/// webhook.
+pub async fn run_once(app_config: &AppConfig, ramper: &Ramper) -> Result<()> {
+    let provider = ramper.provider()?;
+    let stale = {
+        let mut conn = app_config.pool.get()?;
+        get_stale_orders(&mut conn, Utc::now().naive_utc() - pending_timeout())?
+    };
+
+    for order in stale {
+        if let Err(e) = reconcile_order(app_config, ramper, &provider, &order).await {
+            println!("Failed to reconcile ramp order {}: {:?}", order.id, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Background task settling ramp orders whose webhooks never arrived. Does
+/// nothing when on-chain interactions are disabled or the ramp isn't
+/// configured.
+pub async fn run_reconciler(app_config: AppConfig) {
+    if !can_execute_onchain() {
+        return;
+    }
+    let ramper = match Ramper::from_env() {
+        Ok(ramper) => ramper,
+        Err(e) => {
+            println!("Ramp reconciliation disabled: {:?}", e);
+            return;
+        }
+    };
+
+    let mut ticker = tokio::time::interval(poll_interval());
+    loop {
+        ticker.tick().await;
+        if let Err(e) = run_once(&app_config, &ramper).await {
+            println!("Ramp reconciliation error: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use anyhow::{Result, anyhow};
+use bigdecimal::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    accounts::{db_types::AdminOperatorRecord, operators::require_approver},
+    accounts_ledger::{
+        db_types::AccountLedgerTransactionType,
+        operations::{RecordTransactionAssets, record_transaction},
+    },
+    asset_book::operations::{get_asset, get_wallet},
+    big_to_u64,
+    ramper::{
+        Ramper,
+        db_types::{
+            FulfillmentStatus, RampDirection, RampOrderEventRow, RampOrderRow, RampOrderStatus,
+        },
+        fees::credited_tokens,
+        fulfillment::{cancel_fulfillment, get_fulfillment, reopen_fulfillment, wallet_balance},
+        operations::{
+            claim_refund, get_order_events, get_ramp_order, mark_refunded, record_order_event,
+            release_refund,
+        },
+        providers::RampProvider,
+        transfer_from_settlement, transfer_to_settlement,
+    },
+    utils::commons::{DbConn, TaskWallet},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RefundRampOrderInputArgs {
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RampOrderDetails {
+    pub order: RampOrderRow,
+    pub events: Vec<RampOrderEventRow>,
+}
+
+pub fn get_order_details(conn: DbConn<'_>, order_id: Uuid) -> Result<RampOrderDetails> {
+    let order = get_ramp_order(conn, order_id)?
+        .ok_or_else(|| anyhow!("Unknown ramp order {}", order_id))?;
+    let events = get_order_events(conn, order.id)?;
+
+    Ok(RampOrderDetails { order, events })
+}
+
+/// Have the provider refund an on-ramp payment, for orders that were paid
+/// but couldn't be credited or that the user disputes. Tokens already
+/// credited are taken back to the settlement account; when that isn't
+/// possible the order gets a `clawback_failed` event for follow-up.
+/// Off-ramps whose payout failed get their tokens sent back to the wallet
+/// instead. The order is claimed before anything is sent, so concurrent
+/// refunds of it go out once, and its fulfillment is stopped before the
+/// provider is asked; an on-ramp a worker is crediting right now can't be
+/// refunded until that attempt is over. Every step is recorded on the
+/// order's audit trail.
+pub async fn refund_order(
+    ramper: &Ramper,
+    wallet: TaskWallet<'_>,
+    conn: DbConn<'_>,
+    order_id: Uuid,
+    approver: Option<&AdminOperatorRecord>,
+    input: RefundRampOrderInputArgs,
+) -> Result<RampOrderDetails> {
+    if let Some(approver) = approver {
+        require_approver(conn, approver)?;
+    }
+    let requested_by = approver.map(|a| a.account_id);
+
+    let order = get_ramp_order(conn, order_id)?
+        .ok_or_else(|| anyhow!("Unknown ramp order {}", order_id))?;
+    if order.direction == RampDirection::OffRamp {
+        return return_tokens(ramper, wallet, conn, order, requested_by, input).await;
+    }
+    if !matches!(
+        order.status,
+        RampOrderStatus::Completed | RampOrderStatus::Failed
+    ) {
+        return Err(anyhow!(
+            "Ramp order {} is {:?}; only completed or failed orders can be refunded",
+            order.id,
+            order.status
+        ));
+    }
+    let payment_reference = order
+        .provider_reference
+        .clone()
+        .ok_or_else(|| anyhow!("Ramp order {} never reached the provider", order.id))?;
+    claim(conn, &order)?;
+    let stopped = match cancel_fulfillment(conn, order.id, "Order refunded") {
+        Ok(stopped) => stopped,
+        Err(e) => {
+            release_refund(conn, &order)?;
+            return Err(e);
+        }
+    };
+
+    record_order_event(
+        conn,
+        order.id,
+        "refund_requested",
+        requested_by,
+        input.reason,
+    )?;
+
+    let refund_reference = match ramper.provider()?.refund(&payment_reference, None).await {
+        Ok(reference) => reference,
+        Err(e) => {
+            release_refund(conn, &order)?;
+            if let Some(job) = stopped {
+                reopen_fulfillment(conn, job.id)?;
+            }
+            record_order_event(
+                conn,
+                order.id,
+                "refund_failed",
+                requested_by,
+                Some(e.to_string()),
+            )?;
+            return Err(e);
+        }
+    };
+    let order = mark_refunded(conn, order.id, refund_reference.clone())?;
+    record_order_event(
+        conn,
+        order.id,
+        "refunded",
+        requested_by,
+        Some(refund_reference),
+    )?;
+
+    // the fulfillment is stopped, so what reached the wallet can't change
+    match credited(wallet, conn, &order).await {
+        Credited::Yes => {
+            match claw_back(ramper, wallet, conn, &order).await {
+                Ok(tx_id) => record_order_event(conn, order.id, "clawed_back", None, Some(tx_id))?,
+                Err(e) => record_order_event(
+                    conn,
+                    order.id,
+                    "clawback_failed",
+                    None,
+                    Some(e.to_string()),
+                )?,
+            };
+        }
+        Credited::No => {}
+        Credited::Unknown(why) => {
+            record_order_event(conn, order.id, "clawback_pending", None, Some(why))?;
+        }
+    }
+
+    get_order_details(conn, order.id)
+}
+
+enum Credited {
+    Yes,
+    No,
+    /// Left for someone to check, with the reason
+    Unknown(String),
+}
+
+/// Whether a refunded on-ramp's tokens reached the wallet, read once its
+/// fulfillment is stopped. An airdrop that may be out without its
+/// transaction recorded is settled from the wallet's balance, the same way
+/// a fulfillment retry would.
+async fn credited(wallet: TaskWallet<'_>, conn: DbConn<'_>, order: &RampOrderRow) -> Credited {
+    if order.transaction_id.is_some() {
+        return Credited::Yes;
+    }
+    let job = match get_fulfillment(conn, order.id) {
+        Ok(Some(job)) => job,
+        Ok(None) => return Credited::No,
+        Err(e) => return Credited::Unknown(format!("Couldn't read the fulfillment: {}", e)),
+    };
+    if job.status == FulfillmentStatus::Running {
+        return Credited::Unknown("The fulfillment is still running".to_string());
+    }
+    if job.airdropped_at.is_some() {
+        return Credited::Yes;
+    }
+    let Some(before) = job.airdrop_balance_before.map(|b| b as u64) else {
+        return Credited::No;
+    };
+
+    let amount = match big_to_u64!(credited_tokens(order)) {
+        Ok(amount) => amount,
+        Err(e) => return Credited::Unknown(e.to_string()),
+    };
+    match wallet_balance(wallet, conn, order).await {
+        Ok(balance) if balance >= before + amount => Credited::Yes,
+        Ok(balance) if balance == before => Credited::No,
+        Ok(balance) => Credited::Unknown(format!(
+            "The wallet's balance moved from {} to {} since the airdrop was sent",
+            before, balance
+        )),
+        Err(e) => Credited::Unknown(format!("Couldn't read the wallet's balance: {}", e)),
+    }
+}
+
+fn claim(conn: DbConn<'_>, order: &RampOrderRow) -> Result<()> {
+    if claim_refund(conn, order)?.is_none() {
+        return Err(anyhow!(
+            "Ramp order {} is already being refunded or has changed",
+            order.id
+        ));
+    }
+    Ok(())
+}
+
+/// Send an off-ramp's tokens back from settlement after its payout failed.
+/// Orders failed before `payout_failed` existed count too, as long as their
+/// tokens moved.
+async fn return_tokens(
+    ramper: &Ramper,
+    wallet: TaskWallet<'_>,
+    conn: DbConn<'_>,
+    order: RampOrderRow,
+    requested_by: Option<Uuid>,
+    input: RefundRampOrderInputArgs,
+) -> Result<RampOrderDetails> {
+    let moved = order.transaction_id.is_some();
+    if !(order.status == RampOrderStatus::PayoutFailed
+        || (order.status == RampOrderStatus::Failed && moved))
+    {
+        return Err(anyhow!(
+            "Ramp order {} is {:?}; only off-ramps whose payout failed after their tokens moved can be refunded",
+            order.id,
+            order.status
+        ));
+    }
+    claim(conn, &order)?;
+
+    record_order_event(
+        conn,
+        order.id,
+        "refund_requested",
+        requested_by,
+        input.reason,
+    )?;
+
+    let tx_id = match send_back(ramper, wallet, conn, &order).await {
+        Ok(tx_id) => tx_id,
+        Err(e) => {
+            release_refund(conn, &order)?;
+            record_order_event(
+                conn,
+                order.id,
+                "return_failed",
+                requested_by,
+                Some(e.to_string()),
+            )?;
+            return Err(e);
+        }
+    };
+    mark_refunded(conn, order.id, tx_id.clone())?;
+    record_order_event(conn, order.id, "tokens_returned", requested_by, Some(tx_id))?;
+
+    get_order_details(conn, order.id)
+}
+
+async fn send_back(
+    ramper: &Ramper,
+    wallet: TaskWallet<'_>,
+    conn: DbConn<'_>,
+    order: &RampOrderRow,
+) -> Result<String> {
+    let settlement_contract = ramper
+        .ramper_settlement_contract
+        .clone()
+        .ok_or_else(|| anyhow!("No settlement contract configured to send tokens back from"))?;
+    let amount = big_to_u64!(order.amount)?;
+    let token = get_asset(conn, order.asset_id).await?;
+    let wallet_data = get_wallet(conn, order.wallet_id).await?;
+
+    let tx_id = transfer_from_settlement(
+        wallet,
+        settlement_contract,
+        token.token,
+        amount,
+        wallet_data.address.clone(),
+    )
+    .await?;
+
+    record_transaction(
+        conn,
+        ramper.ramper_settlement_account.clone(),
+        Some(wallet_data.address),
+        RecordTransactionAssets::Single(order.asset_id),
+        Some(amount),
+        None,
+        Some(AccountLedgerTransactionType::Transfer),
+        Some(tx_id.clone()),
+        None,
+    )?;
+
+    Ok(tx_id)
+}
+
+/// Take a refunded order's tokens back from the wallet.
+async fn claw_back(
+    ramper: &Ramper,
+    wallet: TaskWallet<'_>,
+    conn: DbConn<'_>,
+    order: &RampOrderRow,
+) -> Result<String> {
+    let settlement_account = ramper
+        .ramper_settlement_account
+        .clone()
+        .ok_or_else(|| anyhow!("No settlement account configured to take tokens back to"))?;
+    let amount = big_to_u64!(credited_tokens(order))?;
+    let token = get_asset(conn, order.asset_id).await?;
+    let wallet_data = get_wallet(conn, order.wallet_id).await?;
+
+    let tx_id = transfer_to_settlement(
+        wallet,
+        wallet_data.contract_id,
+        token.token,
+        amount,
+        settlement_account.clone(),
+    )
+    .await?;
+
+    record_transaction(
+        conn,
+        Some(wallet_data.address),
+        Some(settlement_account),
+        RecordTransactionAssets::Single(order.asset_id),
+        Some(amount),
+        None,
+        Some(AccountLedgerTransactionType::Transfer),
+        Some(tx_id.clone()),
+        None,
+    )?;
+
+    Ok(tx_id)
+}
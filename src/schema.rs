@@ -1,10 +1,26 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "admin_role"))]
+    pub struct AdminRole;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "allocation_status"))]
+    pub struct AllocationStatus;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "asset_type"))]
     pub struct AssetType;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "company_verification"))]
+    pub struct CompanyVerification;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "corporate_action_kind"))]
+    pub struct CorporateActionKind;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "cradleaccountstatus"))]
     pub struct Cradleaccountstatus;
@@ -21,10 +37,34 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "data_provider_type"))]
     pub struct DataProviderType;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "delivery_status"))]
+    pub struct DeliveryStatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "distribution_status"))]
+    pub struct DistributionStatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "document_owner"))]
+    pub struct DocumentOwner;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "fill_mode"))]
     pub struct FillMode;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "fulfillment_status"))]
+    pub struct FulfillmentStatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "listing_allocation_mode"))]
+    pub struct ListingAllocationMode;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "listing_lifecycle"))]
+    pub struct ListingLifecycle;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "listing_status"))]
     pub struct ListingStatus;
@@ -45,6 +85,10 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "market_type"))]
     pub struct MarketType;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "oracle_source_kind"))]
+    pub struct OracleSourceKind;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "order_status"))]
     pub struct OrderStatus;
@@ -53,21 +97,57 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "order_type"))]
     pub struct OrderType;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "payout_status"))]
+    pub struct PayoutStatus;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "pool_transaction_type"))]
     pub struct PoolTransactionType;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "scheduled_change_status"))]
+    pub struct ScheduledChangeStatus;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "settlement_status"))]
     pub struct SettlementStatus;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "split_airdrop_status"))]
+    pub struct SplitAirdropStatus;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "time_series_interval"))]
     pub struct TimeSeriesInterval;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "ramp_direction"))]
+    pub struct RampDirection;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "ramp_order_status"))]
+    pub struct RampOrderStatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "reconciliation_status"))]
+    pub struct ReconciliationStatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "refund_status"))]
+    pub struct RefundStatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "tranche_mode"))]
+    pub struct TrancheMode;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "transaction_type"))]
     pub struct TransactionType;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "withdrawal_status"))]
+    pub struct WithdrawalStatus;
 }
 
 diesel::table! {
@@ -100,6 +180,33 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::AdminRole;
+
+    admin_operators (id) {
+        id -> Uuid,
+        account_id -> Uuid,
+        username -> Text,
+        password_hash -> Text,
+        role -> AdminRole,
+        created_at -> Timestamp,
+        last_login_at -> Nullable<Timestamp>,
+        api_key_hash -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    admin_sessions (id) {
+        id -> Uuid,
+        operator_id -> Uuid,
+        token_hash -> Text,
+        csrf_token -> Text,
+        expires_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::AssetType;
@@ -117,6 +224,22 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::CorporateActionKind;
+
+    corporate_actions (id) {
+        id -> Uuid,
+        listing_id -> Uuid,
+        asset_id -> Uuid,
+        kind -> CorporateActionKind,
+        details -> Jsonb,
+        cancelled_orders -> Int4,
+        initiated_by -> Uuid,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::Cradleaccounttype;
@@ -132,6 +255,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::CompanyVerification;
+
     cradlelistedcompanies (id) {
         id -> Uuid,
         name -> Text,
@@ -139,12 +265,19 @@ diesel::table! {
         listed_at -> Nullable<Timestamp>,
         legal_documents -> Text,
         beneficiary_wallet -> Uuid,
+        legal_documents_hash -> Nullable<Text>,
+        verification_status -> CompanyVerification,
+        verified_by -> Nullable<Uuid>,
+        verified_at -> Nullable<Timestamp>,
     }
 }
 
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::ListingStatus;
+    use super::sql_types::ListingLifecycle;
+    use super::sql_types::ListingAllocationMode;
+    use super::sql_types::TrancheMode;
 
     cradlenativelistings (id) {
         id -> Uuid,
@@ -163,6 +296,22 @@ diesel::table! {
         max_supply -> Numeric,
         treasury -> Uuid,
         shadow_asset -> Uuid,
+        lifecycle -> ListingLifecycle,
+        approved_by -> Nullable<Uuid>,
+        approved_at -> Nullable<Timestamp>,
+        starts_at -> Nullable<Timestamp>,
+        ends_at -> Nullable<Timestamp>,
+        allocation_mode -> ListingAllocationMode,
+        soft_cap -> Nullable<Numeric>,
+        whitelist_enabled -> Bool,
+        min_purchase -> Nullable<Numeric>,
+        max_per_wallet -> Nullable<Numeric>,
+        documents_hash -> Nullable<Text>,
+        create_market_on_close -> Bool,
+        secondary_market -> Nullable<Uuid>,
+        tranche_mode -> TrancheMode,
+        split_factor -> Numeric,
+        delisted_at -> Nullable<Timestamp>,
     }
 }
 
@@ -180,6 +329,42 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::PayoutStatus;
+
+    distribution_payouts (id) {
+        id -> Uuid,
+        distribution_id -> Uuid,
+        wallet_id -> Uuid,
+        holding -> Numeric,
+        amount -> Numeric,
+        status -> PayoutStatus,
+        transaction_id -> Nullable<Uuid>,
+        error -> Nullable<Text>,
+        created_at -> Timestamp,
+        paid_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::DocumentOwner;
+
+    documents (id) {
+        id -> Uuid,
+        owner_type -> DocumentOwner,
+        owner_id -> Uuid,
+        file_name -> Text,
+        content_type -> Text,
+        size -> Int8,
+        content_hash -> Text,
+        backend -> Text,
+        storage_key -> Text,
+        uploaded_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     kvstore (key) {
         key -> Text,
@@ -197,6 +382,110 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::AllocationStatus;
+
+    listing_allocations (id) {
+        id -> Uuid,
+        listing_id -> Uuid,
+        wallet_id -> Uuid,
+        requested -> Numeric,
+        locked -> Numeric,
+        allocated -> Numeric,
+        refunded -> Numeric,
+        status -> AllocationStatus,
+        created_at -> Timestamp,
+        allocated_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::DistributionStatus;
+
+    listing_distributions (id) {
+        id -> Uuid,
+        listing_id -> Uuid,
+        payout_asset -> Uuid,
+        total_amount -> Numeric,
+        allocated -> Numeric,
+        deposit_transaction -> Nullable<Uuid>,
+        status -> DistributionStatus,
+        snapshot_at -> Timestamp,
+        created_at -> Timestamp,
+        completed_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ReconciliationStatus;
+
+    listing_reconciliations (id) {
+        id -> Uuid,
+        listing_id -> Uuid,
+        asset -> Uuid,
+        expected -> Numeric,
+        onchain -> Nullable<Numeric>,
+        difference -> Nullable<Numeric>,
+        status -> ReconciliationStatus,
+        error -> Nullable<Text>,
+        checked_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::RefundStatus;
+
+    listing_refunds (id) {
+        id -> Uuid,
+        listing_id -> Uuid,
+        wallet_id -> Uuid,
+        amount -> Numeric,
+        status -> RefundStatus,
+        transaction_id -> Nullable<Uuid>,
+        error -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    listing_tranche_purchases (id) {
+        id -> Uuid,
+        tranche_id -> Uuid,
+        wallet_id -> Uuid,
+        amount -> Numeric,
+        price -> Numeric,
+        transaction_id -> Nullable<Uuid>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    listing_tranches (id) {
+        id -> Uuid,
+        listing_id -> Uuid,
+        position -> Int4,
+        name -> Text,
+        price -> Numeric,
+        supply -> Numeric,
+        sold -> Numeric,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    listing_whitelist (id) {
+        id -> Uuid,
+        listing_id -> Uuid,
+        wallet_id -> Uuid,
+        added_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     lendingpool (id) {
         id -> Uuid,
@@ -219,6 +508,8 @@ diesel::table! {
         treasury_wallet -> Uuid,
         reserve_wallet -> Uuid,
         pool_account_id -> Uuid,
+        supply_cap -> Nullable<Numeric>,
+        borrow_cap -> Nullable<Numeric>,
     }
 }
 
@@ -275,6 +566,24 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::MarketStatus;
+    use super::sql_types::ScheduledChangeStatus;
+
+    market_status_schedules (id) {
+        id -> Uuid,
+        market_id -> Uuid,
+        target_status -> MarketStatus,
+        execute_at -> Timestamp,
+        reason -> Nullable<Text>,
+        status -> ScheduledChangeStatus,
+        executed_at -> Nullable<Timestamp>,
+        error -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::MarketType;
@@ -292,6 +601,13 @@ diesel::table! {
         market_type -> MarketType,
         market_status -> MarketStatus,
         market_regulation -> MarketRegulation,
+        trading_schedule -> Nullable<Jsonb>,
+        price_precision -> Int4,
+        quantity_precision -> Int4,
+        display_symbol -> Text,
+        price_band_percent -> Numeric,
+        reference_price -> Nullable<Numeric>,
+        symbol -> Text,
     }
 }
 
@@ -318,6 +634,72 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    mutation_audit_log (id) {
+        id -> Uuid,
+        actor -> Text,
+        source -> Text,
+        action -> Text,
+        payload -> Jsonb,
+        result -> Nullable<Jsonb>,
+        error -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    notifications (id) {
+        id -> Uuid,
+        wallet_id -> Uuid,
+        listing_id -> Nullable<Uuid>,
+        event -> Text,
+        payload -> Jsonb,
+        read_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::OracleSourceKind;
+
+    oracle_price_sources (id) {
+        id -> Uuid,
+        lending_pool_id -> Uuid,
+        asset_id -> Uuid,
+        kind -> OracleSourceKind,
+        params -> Jsonb,
+        enabled -> Bool,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    oracle_price_history (id) {
+        id -> Uuid,
+        lending_pool_id -> Uuid,
+        asset_id -> Uuid,
+        price -> Numeric,
+        sources -> Jsonb,
+        published_by -> Text,
+        transaction -> Text,
+        published_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    oracle_source_quotes (id) {
+        id -> Uuid,
+        source_id -> Uuid,
+        price -> Nullable<Numeric>,
+        error -> Nullable<Text>,
+        rejected -> Bool,
+        observed_at -> Nullable<Timestamp>,
+        provenance -> Jsonb,
+        fetched_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::FillMode;
@@ -380,15 +762,245 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    protocol_fees (id) {
+        id -> Uuid,
+        source -> Text,
+        reference_id -> Uuid,
+        provider -> Nullable<Text>,
+        asset_id -> Nullable<Uuid>,
+        currency -> Text,
+        amount -> Numeric,
+        token_amount -> Numeric,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::FulfillmentStatus;
+
+    ramp_fulfillments (id) {
+        id -> Uuid,
+        order_id -> Uuid,
+        status -> FulfillmentStatus,
+        associated_at -> Nullable<Timestamp>,
+        kyced_at -> Nullable<Timestamp>,
+        minted_at -> Nullable<Timestamp>,
+        airdropped_at -> Nullable<Timestamp>,
+        attempts -> Int4,
+        last_error -> Nullable<Text>,
+        next_attempt_at -> Timestamp,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        airdrop_tx -> Nullable<Text>,
+        airdrop_balance_before -> Nullable<Int8>,
+        mint_supply_before -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    ramp_order_events (id) {
+        id -> Uuid,
+        order_id -> Uuid,
+        event -> Text,
+        actor -> Nullable<Uuid>,
+        note -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::RampDirection;
+    use super::sql_types::RampOrderStatus;
+
+    ramp_orders (id) {
+        id -> Uuid,
+        direction -> RampDirection,
+        status -> RampOrderStatus,
+        wallet_id -> Uuid,
+        asset_id -> Uuid,
+        amount -> Numeric,
+        currency -> Text,
+        channel -> Text,
+        destination -> Nullable<Text>,
+        provider_reference -> Nullable<Text>,
+        transaction_id -> Nullable<Uuid>,
+        failure_reason -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        refund_reference -> Nullable<Text>,
+        quote_id -> Nullable<Uuid>,
+        fiat_amount -> Nullable<Numeric>,
+        fee -> Numeric,
+        fee_tokens -> Numeric,
+    }
+}
+
+diesel::table! {
+    ramp_quotes (id) {
+        id -> Uuid,
+        asset_id -> Uuid,
+        currency -> Text,
+        rate -> Numeric,
+        fiat_amount -> Numeric,
+        token_amount -> Numeric,
+        source -> Text,
+        expires_at -> Timestamp,
+        used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        fee -> Numeric,
+        fee_tokens -> Numeric,
+    }
+}
+
+diesel::table! {
+    ramp_receipts (id) {
+        id -> Uuid,
+        order_id -> Uuid,
+        wallet_id -> Uuid,
+        asset_id -> Uuid,
+        token_symbol -> Text,
+        token_amount -> Numeric,
+        currency -> Text,
+        fiat_amount -> Nullable<Numeric>,
+        fee -> Numeric,
+        channel -> Text,
+        provider_reference -> Nullable<Text>,
+        tx_hash -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::SplitAirdropStatus;
+
+    split_airdrop_jobs (id) {
+        id -> Uuid,
+        action_id -> Uuid,
+        asset_id -> Uuid,
+        units -> Int8,
+        status -> SplitAirdropStatus,
+        minted_at -> Nullable<Timestamp>,
+        mint_supply_before -> Nullable<Int8>,
+        attempts -> Int4,
+        last_error -> Nullable<Text>,
+        next_attempt_at -> Timestamp,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    split_airdrops (id) {
+        id -> Uuid,
+        job_id -> Uuid,
+        wallet_id -> Uuid,
+        units -> Int8,
+        balance_before -> Nullable<Int8>,
+        airdropped_at -> Nullable<Timestamp>,
+        airdrop_tx -> Nullable<Text>,
+        last_error -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::DeliveryStatus;
+
+    webhook_deliveries (id) {
+        id -> Uuid,
+        webhook_id -> Uuid,
+        event -> Text,
+        listing_id -> Uuid,
+        payload -> Jsonb,
+        status -> DeliveryStatus,
+        attempts -> Int4,
+        last_error -> Nullable<Text>,
+        next_attempt_at -> Timestamp,
+        created_at -> Timestamp,
+        delivered_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    webhooks (id) {
+        id -> Uuid,
+        url -> Text,
+        secret -> Text,
+        company_id -> Nullable<Uuid>,
+        listing_id -> Nullable<Uuid>,
+        events -> Array<Text>,
+        active -> Bool,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    withdrawal_approvals (id) {
+        id -> Uuid,
+        request_id -> Uuid,
+        approver -> Uuid,
+        created_at -> Timestamp,
+        operator_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::WithdrawalStatus;
+
+    withdrawal_requests (id) {
+        id -> Uuid,
+        listing_id -> Uuid,
+        amount -> Numeric,
+        requested_by -> Nullable<Uuid>,
+        required_approvals -> Int4,
+        status -> WithdrawalStatus,
+        reviewed_by -> Nullable<Uuid>,
+        reason -> Nullable<Text>,
+        transaction_id -> Nullable<Uuid>,
+        error -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::joinable!(accountassetbook -> asset_book (asset_id));
 diesel::joinable!(accountassetbook -> cradlewalletaccounts (account_id));
 diesel::joinable!(accountassetsledger -> asset_book (asset));
+diesel::joinable!(admin_operators -> cradleaccounts (account_id));
+diesel::joinable!(admin_sessions -> admin_operators (operator_id));
+diesel::joinable!(corporate_actions -> asset_book (asset_id));
+diesel::joinable!(corporate_actions -> cradleaccounts (initiated_by));
+diesel::joinable!(corporate_actions -> cradlenativelistings (listing_id));
+diesel::joinable!(cradlelistedcompanies -> cradleaccounts (verified_by));
 diesel::joinable!(cradlelistedcompanies -> cradlewalletaccounts (beneficiary_wallet));
+diesel::joinable!(cradlenativelistings -> cradleaccounts (approved_by));
 diesel::joinable!(cradlenativelistings -> cradlelistedcompanies (company));
 diesel::joinable!(cradlenativelistings -> cradlewalletaccounts (treasury));
+diesel::joinable!(cradlenativelistings -> markets (secondary_market));
 diesel::joinable!(cradlewalletaccounts -> cradleaccounts (cradle_account_id));
+diesel::joinable!(distribution_payouts -> cradlewalletaccounts (wallet_id));
+diesel::joinable!(distribution_payouts -> listing_distributions (distribution_id));
 diesel::joinable!(lending_pool_oracle_prices -> asset_book (asset_id));
 diesel::joinable!(lending_pool_oracle_prices -> lendingpool (lending_pool_id));
+diesel::joinable!(listing_allocations -> cradlenativelistings (listing_id));
+diesel::joinable!(listing_allocations -> cradlewalletaccounts (wallet_id));
+diesel::joinable!(listing_distributions -> asset_book (payout_asset));
+diesel::joinable!(listing_distributions -> cradlenativelistings (listing_id));
+diesel::joinable!(listing_reconciliations -> asset_book (asset));
+diesel::joinable!(listing_reconciliations -> cradlenativelistings (listing_id));
+diesel::joinable!(listing_refunds -> cradlenativelistings (listing_id));
+diesel::joinable!(listing_refunds -> cradlewalletaccounts (wallet_id));
+diesel::joinable!(listing_tranche_purchases -> cradlewalletaccounts (wallet_id));
+diesel::joinable!(listing_tranche_purchases -> listing_tranches (tranche_id));
+diesel::joinable!(listing_tranches -> cradlenativelistings (listing_id));
+diesel::joinable!(listing_whitelist -> cradlenativelistings (listing_id));
+diesel::joinable!(listing_whitelist -> cradlewalletaccounts (wallet_id));
 diesel::joinable!(lendingpool -> cradleaccounts (pool_account_id));
 diesel::joinable!(lendingpoolsnapshots -> lendingpool (lending_pool_id));
 diesel::joinable!(loanliquidations -> cradlewalletaccounts (liquidator_wallet_id));
@@ -398,31 +1010,94 @@ diesel::joinable!(loans -> asset_book (collateral_asset));
 diesel::joinable!(loans -> cradleaccounts (account_id));
 diesel::joinable!(loans -> cradlewalletaccounts (wallet_id));
 diesel::joinable!(loans -> lendingpool (pool));
+diesel::joinable!(market_status_schedules -> markets (market_id));
 diesel::joinable!(markets_time_series -> asset_book (asset));
 diesel::joinable!(markets_time_series -> markets (market_id));
+diesel::joinable!(notifications -> cradlenativelistings (listing_id));
+diesel::joinable!(notifications -> cradlewalletaccounts (wallet_id));
+diesel::joinable!(oracle_price_history -> asset_book (asset_id));
+diesel::joinable!(oracle_price_history -> lendingpool (lending_pool_id));
+diesel::joinable!(oracle_price_sources -> asset_book (asset_id));
+diesel::joinable!(oracle_price_sources -> lendingpool (lending_pool_id));
+diesel::joinable!(oracle_source_quotes -> oracle_price_sources (source_id));
 diesel::joinable!(orderbook -> cradlewalletaccounts (wallet));
 diesel::joinable!(orderbook -> markets (market_id));
 diesel::joinable!(pooltransactions -> cradlewalletaccounts (wallet_id));
 diesel::joinable!(pooltransactions -> lendingpool (pool_id));
+diesel::joinable!(protocol_fees -> asset_book (asset_id));
+diesel::joinable!(ramp_fulfillments -> ramp_orders (order_id));
+diesel::joinable!(ramp_order_events -> cradleaccounts (actor));
+diesel::joinable!(ramp_order_events -> ramp_orders (order_id));
+diesel::joinable!(ramp_orders -> accountassetsledger (transaction_id));
+diesel::joinable!(ramp_orders -> asset_book (asset_id));
+diesel::joinable!(ramp_orders -> cradlewalletaccounts (wallet_id));
+diesel::joinable!(ramp_orders -> ramp_quotes (quote_id));
+diesel::joinable!(ramp_quotes -> asset_book (asset_id));
+diesel::joinable!(ramp_receipts -> asset_book (asset_id));
+diesel::joinable!(ramp_receipts -> cradlewalletaccounts (wallet_id));
+diesel::joinable!(ramp_receipts -> ramp_orders (order_id));
+diesel::joinable!(split_airdrop_jobs -> asset_book (asset_id));
+diesel::joinable!(split_airdrop_jobs -> corporate_actions (action_id));
+diesel::joinable!(split_airdrops -> cradlewalletaccounts (wallet_id));
+diesel::joinable!(split_airdrops -> split_airdrop_jobs (job_id));
+diesel::joinable!(webhook_deliveries -> cradlenativelistings (listing_id));
+diesel::joinable!(webhook_deliveries -> webhooks (webhook_id));
+diesel::joinable!(webhooks -> cradlelistedcompanies (company_id));
+diesel::joinable!(webhooks -> cradlenativelistings (listing_id));
+diesel::joinable!(withdrawal_approvals -> admin_operators (operator_id));
+diesel::joinable!(withdrawal_approvals -> cradleaccounts (approver));
+diesel::joinable!(withdrawal_approvals -> withdrawal_requests (request_id));
+diesel::joinable!(withdrawal_requests -> accountassetsledger (transaction_id));
+diesel::joinable!(withdrawal_requests -> cradlenativelistings (listing_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     accountassetbook,
     accountassetsledger,
+    admin_operators,
+    admin_sessions,
     asset_book,
+    corporate_actions,
     cradleaccounts,
     cradlelistedcompanies,
     cradlenativelistings,
     cradlewalletaccounts,
+    distribution_payouts,
+    documents,
     kvstore,
     lending_pool_oracle_prices,
     lendingpool,
     lendingpoolsnapshots,
+    listing_allocations,
+    listing_distributions,
+    listing_reconciliations,
+    listing_refunds,
+    listing_tranche_purchases,
+    listing_tranches,
+    listing_whitelist,
     loanliquidations,
     loanrepayments,
     loans,
+    market_status_schedules,
     markets,
     markets_time_series,
+    mutation_audit_log,
+    notifications,
+    oracle_price_history,
+    oracle_price_sources,
+    oracle_source_quotes,
     orderbook,
     orderbooktrades,
     pooltransactions,
+    protocol_fees,
+    ramp_fulfillments,
+    ramp_order_events,
+    ramp_orders,
+    ramp_quotes,
+    ramp_receipts,
+    split_airdrop_jobs,
+    split_airdrops,
+    webhook_deliveries,
+    webhooks,
+    withdrawal_approvals,
+    withdrawal_requests,
 );
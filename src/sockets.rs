@@ -1,52 +1,474 @@
+pub mod backpressure;
+pub mod batching;
+pub mod cluster;
+pub mod encoding;
+pub mod filters;
+pub mod metrics;
+pub mod namespaces;
+pub mod replay;
+pub mod system_events;
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use socketioxide::SocketIo;
 use socketioxide::extract::{Data, SocketRef};
 
+use crate::market_time_series::db_types::TimeSeriesInterval;
+use crate::sockets::backpressure::{deliver, forget};
+use crate::sockets::encoding::{Encoding, encode_msgpack};
+use crate::sockets::filters::SubscriptionFilter;
+use crate::sockets::metrics::SOCKET_METRICS;
+use crate::sockets::namespaces::SocketNamespace;
+use crate::sockets::batching::BATCH_EVENT;
+use crate::sockets::replay::{BufferedEvent, REPLAY_BUFFER};
+
 #[derive(Deserialize, Debug)]
 struct SubscribePayload {
     market_id: String,
+    /// Last sequence number the client saw for this room, if resuming.
+    #[serde(default)]
+    last_seq: Option<u64>,
+    #[serde(default)]
+    filter: Option<SubscriptionFilter>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ListingSubscribePayload {
+    /// Omit to follow every listing
+    #[serde(default)]
+    listing_id: Option<String>,
+    #[serde(default)]
+    last_seq: Option<u64>,
+    #[serde(default)]
+    filter: Option<SubscriptionFilter>,
+}
+
+#[derive(Deserialize, Debug)]
+struct CandleSubscribePayload {
+    market_id: String,
+    interval: TimeSeriesInterval,
+    #[serde(default)]
+    last_seq: Option<u64>,
+    #[serde(default)]
+    filter: Option<SubscriptionFilter>,
+}
+
+/// Generic form: `{ "channel": "candles", "market_id": "...", "interval": "1min" }`
+#[derive(Deserialize, Debug)]
+struct ChannelSubscribePayload {
+    channel: String,
+    market_id: String,
+    #[serde(default)]
+    interval: Option<TimeSeriesInterval>,
+    #[serde(default)]
+    last_seq: Option<u64>,
+    #[serde(default)]
+    filter: Option<SubscriptionFilter>,
 }
 
-pub async fn on_connect(socket: SocketRef, Data(_data): Data<Value>) {
-    println!("Socket connected: {:?}", socket.id);
+fn channel_room(payload: &ChannelSubscribePayload) -> Option<String> {
+    match (payload.channel.as_str(), &payload.interval) {
+        ("orderbook" | "trades" | "timeseries", _) => {
+            Some(format!("{}:{}", payload.channel, payload.market_id))
+        }
+        ("candles", Some(interval)) => Some(format!("candles:{}:{}", payload.market_id, interval.as_str())),
+        _ => None,
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct AccountSubscribePayload {
+    wallet_id: String,
+    #[serde(default)]
+    last_seq: Option<u64>,
+    #[serde(default)]
+    filter: Option<SubscriptionFilter>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PoolSubscribePayload {
+    pool_id: String,
+    #[serde(default)]
+    last_seq: Option<u64>,
+    #[serde(default)]
+    filter: Option<SubscriptionFilter>,
+}
+
+fn listing_room(payload: &ListingSubscribePayload) -> String {
+    match &payload.listing_id {
+        Some(listing_id) => format!("listings:{}", listing_id),
+        None => "listings".to_string(),
+    }
+}
+
+/// Handshake auth payload: `{ "encoding": "msgpack", "last_seq": { "<room>": <seq> } }`
+#[derive(Deserialize, Debug, Default)]
+struct HandshakePayload {
+    #[serde(default)]
+    encoding: Encoding,
+    #[serde(default)]
+    last_seq: HashMap<String, u64>,
+}
+
+#[derive(Serialize, Debug)]
+struct ResyncRequired {
+    room: String,
+    latest_seq: u64,
+}
+
+/// Per-connection state captured at handshake time
+#[derive(Debug, Default)]
+struct SocketSession {
+    encoding: Encoding,
+    last_seq: HashMap<String, u64>,
+    rooms: HashSet<String>,
+    filters: HashMap<String, SubscriptionFilter>,
+}
+
+static SESSIONS: Lazy<Mutex<HashMap<String, SocketSession>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn session_encoding(socket: &SocketRef) -> Encoding {
+    SESSIONS
+        .lock()
+        .unwrap()
+        .get(&socket.id.to_string())
+        .map(|s| s.encoding)
+        .unwrap_or_default()
+}
+
+fn accepts(socket: &SocketRef, room: &str, event: &str, payload: &Value) -> bool {
+    SESSIONS
+        .lock()
+        .unwrap()
+        .get(&socket.id.to_string())
+        .and_then(|session| session.filters.get(room))
+        .is_none_or(|filter| filter.matches(event, payload))
+}
+
+/// The event as this socket should see it after its subscription filter:
+/// unchanged, trimmed (for batches), or nothing at all.
+fn view_for<'a>(socket: &SocketRef, room: &str, entry: &'a BufferedEvent) -> Option<Cow<'a, BufferedEvent>> {
+    let filter = SESSIONS
+        .lock()
+        .unwrap()
+        .get(&socket.id.to_string())
+        .and_then(|session| session.filters.get(room).cloned());
+
+    let Some(filter) = filter else {
+        return Some(Cow::Borrowed(entry));
+    };
+
+    if entry.event != BATCH_EVENT {
+        return filter.matches(&entry.event, &entry.payload).then_some(Cow::Borrowed(entry));
+    }
+
+    let items: Vec<Value> = entry
+        .payload
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter(|item| {
+                    let event = item.get("event").and_then(|e| e.as_str()).unwrap_or_default();
+                    filter.matches(event, item.get("payload").unwrap_or(&Value::Null))
+                })
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if items.is_empty() {
+        return None;
+    }
+
+    Some(Cow::Owned(BufferedEvent {
+        seq: entry.seq,
+        event: entry.event.clone(),
+        payload: Value::Array(items),
+    }))
+}
+
+/// Emit an event to a room on this instance and, when clustering is on, on
+/// every other replica.
+pub async fn broadcast<T: Serialize>(io: &SocketIo, room: String, event: &str, payload: &T) {
+    let value = match serde_json::to_value(payload) {
+        Ok(v) => v,
+        Err(e) => {
+            println!("Failed to serialize {} for {}: {:?}", event, room, e);
+            return;
+        }
+    };
+
+    cluster::publish(&room, event, &value).await;
+    emit_local(io, &room, event, value);
+}
+
+/// Deliver to this instance's subscribers, recording the event in the replay
+/// buffer first so the payload carries its sequence number. Rooms that are
+/// batched are queued and sent by the batch flusher instead.
+pub(crate) fn emit_local(io: &SocketIo, room: &str, event: &str, value: Value) {
+    let entry = REPLAY_BUFFER.record(room, event, value);
+
+    if batching::enqueue(room, &entry) {
+        return;
+    }
+
+    fan_out(io, room, &entry);
+}
+
+/// Send an already-recorded event to every subscriber of the room. Each
+/// subscriber is sent to individually so a client with a full queue can't
+/// hold up the rest.
+pub(crate) fn fan_out(io: &SocketIo, room: &str, entry: &BufferedEvent) {
+    let msgpack_room = Encoding::Msgpack.room_for(room);
+    let mut msgpack_sockets: Vec<SocketRef> = Vec::new();
+
+    for namespace in SocketNamespace::for_room(room) {
+        let Some(operators) = io.of(namespace.path()) else {
+            continue;
+        };
+
+        for socket in operators.clone().to(room.to_string()).sockets() {
+            if let Some(view) = view_for(&socket, room, entry) {
+                deliver(&socket, Encoding::Json, room, &view, None);
+            }
+        }
+
+        msgpack_sockets.extend(operators.to(msgpack_room.clone()).sockets());
+    }
+
+    if msgpack_sockets.is_empty() {
+        return;
+    }
 
+    let shared = match encode_msgpack(&entry.payload) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            println!("Failed to encode {} as msgpack: {:?}", entry.event, e);
+            return;
+        }
+    };
+
+    for socket in msgpack_sockets {
+        match view_for(&socket, room, entry) {
+            Some(Cow::Borrowed(view)) => deliver(&socket, Encoding::Msgpack, room, view, Some(&shared)),
+            Some(Cow::Owned(view)) => deliver(&socket, Encoding::Msgpack, room, &view, None),
+            None => {}
+        }
+    }
+}
+
+fn replay_missed(socket: &SocketRef, encoding: Encoding, room: &str, last_seq: Option<u64>) {
+    let handshake_seq = SESSIONS
+        .lock()
+        .unwrap()
+        .get_mut(&socket.id.to_string())
+        .and_then(|session| session.last_seq.remove(room));
+
+    let Some(last_seq) = last_seq.or(handshake_seq) else {
+        return;
+    };
+
+    let (events, complete) = REPLAY_BUFFER.since(room, last_seq);
+
+    if !complete {
+        let _ = socket.emit(
+            "resync:required",
+            &ResyncRequired {
+                room: room.to_string(),
+                latest_seq: REPLAY_BUFFER.latest_seq(room),
+            },
+        );
+        return;
+    }
+
+    for entry in events.iter().filter(|e| accepts(socket, room, &e.event, &e.payload)) {
+        deliver(socket, encoding, room, entry, None);
+    }
+    println!("Replayed missed events to {} for room {} from seq {}", socket.id, room, last_seq);
+}
+
+fn join_room(socket: &SocketRef, room: String, last_seq: Option<u64>, filter: Option<SubscriptionFilter>) {
+    let encoding = session_encoding(socket);
+    socket.join(encoding.room_for(&room));
+    let newly_joined = SESSIONS
+        .lock()
+        .unwrap()
+        .get_mut(&socket.id.to_string())
+        .is_some_and(|session| {
+            match filter.filter(|f| !f.is_empty()) {
+                Some(filter) => session.filters.insert(room.clone(), filter),
+                None => session.filters.remove(&room),
+            };
+            session.rooms.insert(room.clone())
+        });
+    if newly_joined {
+        SOCKET_METRICS.subscribed(&room);
+    }
+    println!("Socket {} joined room {} ({:?})", socket.id, room, encoding);
+    replay_missed(socket, encoding, &room, last_seq);
+}
+
+fn leave_room(socket: &SocketRef, room: String) {
+    socket.leave(session_encoding(socket).room_for(&room));
+    let was_joined = SESSIONS
+        .lock()
+        .unwrap()
+        .get_mut(&socket.id.to_string())
+        .is_some_and(|session| {
+            session.filters.remove(&room);
+            session.rooms.remove(&room)
+        });
+    if was_joined {
+        SOCKET_METRICS.unsubscribed(&room);
+    }
+    println!("Socket {} left room {}", socket.id, room);
+}
+
+fn init_session(socket: &SocketRef, data: Value) {
+    println!("Socket connected: {:?} on {}", socket.id, socket.ns());
+
+    let handshake: HandshakePayload = serde_json::from_value(data).unwrap_or_default();
+    SESSIONS.lock().unwrap().insert(
+        socket.id.to_string(),
+        SocketSession {
+            encoding: handshake.encoding,
+            last_seq: handshake.last_seq,
+            rooms: HashSet::new(),
+            filters: HashMap::new(),
+        },
+    );
+    SOCKET_METRICS.connected();
+
+    socket.on_disconnect(|socket: SocketRef| async move {
+        if let Some(session) = SESSIONS.lock().unwrap().remove(&socket.id.to_string()) {
+            for room in &session.rooms {
+                SOCKET_METRICS.unsubscribed(room);
+            }
+        }
+        SOCKET_METRICS.disconnected();
+        forget(&socket);
+        println!("Socket disconnected: {:?}", socket.id);
+    });
+}
+
+fn register_market_handlers(socket: &SocketRef) {
     socket.on("subscribe:orderbook", |socket: SocketRef, Data(payload): Data<SubscribePayload>| async move {
         let room = format!("orderbook:{}", payload.market_id);
-        socket.join(room.clone());
-        println!("Socket {} joined room {}", socket.id, room);
+        join_room(&socket, room, payload.last_seq, payload.filter);
     });
 
     socket.on("unsubscribe:orderbook", |socket: SocketRef, Data(payload): Data<SubscribePayload>| async move {
-        let room = format!("orderbook:{}", payload.market_id);
-        socket.leave(room.clone());
-        println!("Socket {} left room {}", socket.id, room);
+        leave_room(&socket, format!("orderbook:{}", payload.market_id));
     });
 
     socket.on("subscribe:trades", |socket: SocketRef, Data(payload): Data<SubscribePayload>| async move {
         let room = format!("trades:{}", payload.market_id);
-        socket.join(room.clone());
-        println!("Socket {} joined room {}", socket.id, room);
+        join_room(&socket, room, payload.last_seq, payload.filter);
     });
 
     socket.on("unsubscribe:trades", |socket: SocketRef, Data(payload): Data<SubscribePayload>| async move {
-        let room = format!("trades:{}", payload.market_id);
-        socket.leave(room.clone());
-        println!("Socket {} left room {}", socket.id, room);
+        leave_room(&socket, format!("trades:{}", payload.market_id));
     });
 
     socket.on("subscribe:timeseries", |socket: SocketRef, Data(payload): Data<SubscribePayload>| async move {
         let room = format!("timeseries:{}", payload.market_id);
-        socket.join(room.clone());
-        println!("Socket {} joined room {}", socket.id, room);
+        join_room(&socket, room, payload.last_seq, payload.filter);
     });
 
     socket.on("unsubscribe:timeseries", |socket: SocketRef, Data(payload): Data<SubscribePayload>| async move {
-        let room = format!("timeseries:{}", payload.market_id);
-        socket.leave(room.clone());
-        println!("Socket {} left room {}", socket.id, room);
+        leave_room(&socket, format!("timeseries:{}", payload.market_id));
+    });
+
+    socket.on("subscribe:candles", |socket: SocketRef, Data(payload): Data<CandleSubscribePayload>| async move {
+        let room = format!("candles:{}:{}", payload.market_id, payload.interval.as_str());
+        join_room(&socket, room, payload.last_seq, payload.filter);
+    });
+
+    socket.on("unsubscribe:candles", |socket: SocketRef, Data(payload): Data<CandleSubscribePayload>| async move {
+        leave_room(&socket, format!("candles:{}:{}", payload.market_id, payload.interval.as_str()));
+    });
+
+    socket.on("subscribe", |socket: SocketRef, Data(payload): Data<ChannelSubscribePayload>| async move {
+        match channel_room(&payload) {
+            Some(room) => join_room(&socket, room, payload.last_seq, payload.filter),
+            None => println!("Socket {} sent unknown subscription {:?}", socket.id, payload),
+        }
+    });
+
+    socket.on("unsubscribe", |socket: SocketRef, Data(payload): Data<ChannelSubscribePayload>| async move {
+        if let Some(room) = channel_room(&payload) {
+            leave_room(&socket, room);
+        }
     });
 
+    socket.on("subscribe:listings", |socket: SocketRef, Data(payload): Data<ListingSubscribePayload>| async move {
+        join_room(&socket, listing_room(&payload), payload.last_seq, payload.filter);
+    });
+
+    socket.on("unsubscribe:listings", |socket: SocketRef, Data(payload): Data<ListingSubscribePayload>| async move {
+        leave_room(&socket, listing_room(&payload));
+    });
+}
+
+/// Default "/" namespace. Public market data only, kept for existing clients.
+pub async fn on_connect(socket: SocketRef, Data(data): Data<Value>) {
+    init_session(&socket, data);
+    register_market_handlers(&socket);
+
     socket.on("message", |_: SocketRef, Data(payload): Data<Value>| async move {
         println!("message received: {:?}", payload);
     });
 }
+
+/// `/markets`: order book, trades, candles and listings. No auth.
+pub async fn on_markets_connect(socket: SocketRef, Data(data): Data<Value>) {
+    init_session(&socket, data);
+    register_market_handlers(&socket);
+}
+
+/// `/accounts`: per-wallet order activity. Requires the API key.
+pub async fn on_accounts_connect(socket: SocketRef, Data(data): Data<Value>) {
+    init_session(&socket, data);
+
+    socket.on("subscribe:account", |socket: SocketRef, Data(payload): Data<AccountSubscribePayload>| async move {
+        let room = format!("account:{}", payload.wallet_id);
+        join_room(&socket, room, payload.last_seq, payload.filter);
+    });
+
+    socket.on("unsubscribe:account", |socket: SocketRef, Data(payload): Data<AccountSubscribePayload>| async move {
+        leave_room(&socket, format!("account:{}", payload.wallet_id));
+    });
+}
+
+fn register_pool_handlers(socket: &SocketRef) {
+    socket.on("subscribe:pool", |socket: SocketRef, Data(payload): Data<PoolSubscribePayload>| async move {
+        let room = format!("lending:{}", payload.pool_id);
+        join_room(&socket, room, payload.last_seq, payload.filter);
+    });
+
+    socket.on("unsubscribe:pool", |socket: SocketRef, Data(payload): Data<PoolSubscribePayload>| async move {
+        leave_room(&socket, format!("lending:{}", payload.pool_id));
+    });
+}
+
+/// `/lending`: per-pool supply, borrow, repay, liquidation and accrual events. Requires the API key.
+pub async fn on_lending_connect(socket: SocketRef, Data(data): Data<Value>) {
+    init_session(&socket, data);
+    register_pool_handlers(&socket);
+}
+
+/// `/admin`: operational events. Requires ADMIN_SOCKET_KEY; clients join the
+/// admin room on connect and can follow pools like `/lending` clients, so the
+/// dashboard doesn't need the API key.
+pub async fn on_admin_connect(socket: SocketRef, Data(data): Data<Value>) {
+    init_session(&socket, data);
+    join_room(&socket, system_events::ADMIN_ROOM.to_string(), None, None);
+    register_pool_handlers(&socket);
+}
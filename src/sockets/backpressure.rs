@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use bytes::Bytes;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use socketioxide::extract::SocketRef;
+
+use crate::sockets::encoding::Encoding;
+use crate::sockets::metrics::SOCKET_METRICS;
+use crate::sockets::replay::BufferedEvent;
+
+/// Per-socket send queue depth handed to socketioxide. Emits fail fast once a
+/// client has this many undelivered packets.
+pub const DEFAULT_SOCKET_BUFFER: usize = 256;
+
+/// Consecutive failed deliveries before a client is disconnected.
+const DEFAULT_SLOW_CONSUMER_LIMIT: u32 = 50;
+
+/// Close reason sent (best effort) to clients dropped for falling behind.
+pub const SLOW_CONSUMER_REASON: &str = "slow_consumer";
+
+pub fn socket_buffer_size() -> usize {
+    std::env::var("SOCKET_BUFFER_SIZE")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_SOCKET_BUFFER)
+}
+
+#[derive(Serialize, Debug)]
+struct DisconnectNotice<'a> {
+    reason: &'a str,
+}
+
+#[derive(Default)]
+struct ConsumerState {
+    strikes: u32,
+    /// Book updates that could not be delivered, keyed so that newer updates
+    /// for the same order replace older ones.
+    pending: HashMap<String, BufferedEvent>,
+}
+
+struct SlowConsumerTracker {
+    limit: u32,
+    consumers: Mutex<HashMap<String, ConsumerState>>,
+}
+
+static TRACKER: Lazy<SlowConsumerTracker> = Lazy::new(|| SlowConsumerTracker {
+    limit: std::env::var("SOCKET_SLOW_CONSUMER_LIMIT")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(DEFAULT_SLOW_CONSUMER_LIMIT),
+    consumers: Mutex::new(HashMap::new()),
+});
+
+/// Book events are superseded by later events for the same order, so they can
+/// be coalesced. Trades and candles are not, and simply count as a miss.
+fn coalesce_key(room: &str, entry: &BufferedEvent) -> Option<String> {
+    if !room.starts_with("orderbook:") {
+        return None;
+    }
+    let order_id = entry.payload.get("id")?.as_str()?;
+    Some(format!("{}|{}", room, order_id))
+}
+
+fn try_emit(socket: &SocketRef, encoding: Encoding, entry: &BufferedEvent, msgpack: Option<&Bytes>) -> bool {
+    let sent = match encoding {
+        Encoding::Json => socket.emit(entry.event.as_str(), &entry.payload).is_ok(),
+        Encoding::Msgpack => {
+            let encoded = match msgpack {
+                Some(bytes) => bytes.clone(),
+                None => match crate::sockets::encoding::encode_msgpack(&entry.payload) {
+                    Ok(bytes) => bytes,
+                    Err(_) => return false,
+                },
+            };
+            socket.emit(entry.event.as_str(), &encoded).is_ok()
+        }
+    };
+
+    if sent {
+        SOCKET_METRICS.emitted();
+    } else {
+        SOCKET_METRICS.dropped();
+    }
+    sent
+}
+
+/// Deliver an event to one socket without blocking on it. If the socket's
+/// queue is full the event is coalesced (book updates) or dropped, and the
+/// client is disconnected after too many consecutive misses.
+pub fn deliver(socket: &SocketRef, encoding: Encoding, room: &str, entry: &BufferedEvent, msgpack: Option<&Bytes>) {
+    let sid = socket.id.to_string();
+
+    // Flush anything coalesced earlier before sending the new event
+    let pending = {
+        let mut consumers = TRACKER.consumers.lock().unwrap();
+        consumers
+            .get_mut(&sid)
+            .map(|state| std::mem::take(&mut state.pending))
+            .unwrap_or_default()
+    };
+
+    let mut backlog: Vec<(String, BufferedEvent)> = pending.into_iter().collect();
+    backlog.sort_by_key(|(_, e)| e.seq);
+
+    let mut unsent: Vec<(String, BufferedEvent)> = Vec::new();
+    for (key, queued) in backlog {
+        if !unsent.is_empty() || !try_emit(socket, encoding, &queued, None) {
+            unsent.push((key, queued));
+        }
+    }
+
+    let delivered = unsent.is_empty() && try_emit(socket, encoding, entry, msgpack);
+
+    let mut consumers = TRACKER.consumers.lock().unwrap();
+    let state = consumers.entry(sid.clone()).or_default();
+
+    for (key, queued) in unsent {
+        state.pending.entry(key).or_insert(queued);
+    }
+
+    if delivered {
+        state.strikes = 0;
+        return;
+    }
+
+    if let Some(key) = coalesce_key(room, entry) {
+        state.pending.insert(key, entry.clone());
+    }
+    state.strikes += 1;
+
+    if state.strikes >= TRACKER.limit {
+        consumers.remove(&sid);
+        drop(consumers);
+        SOCKET_METRICS.slow_disconnect();
+        println!("Disconnecting slow consumer {} after {} missed events", sid, TRACKER.limit);
+        let _ = socket.emit("disconnect:reason", &DisconnectNotice { reason: SLOW_CONSUMER_REASON });
+        let _ = socket.clone().disconnect();
+    }
+}
+
+pub fn forget(socket: &SocketRef) {
+    TRACKER.consumers.lock().unwrap().remove(&socket.id.to_string());
+}
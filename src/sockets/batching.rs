@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use serde_json::{Value, json};
+use socketioxide::SocketIo;
+
+use crate::sockets::fan_out;
+use crate::sockets::replay::BufferedEvent;
+
+/// Event name used for batched emits. The payload is an array of
+/// `{ "event": ..., "payload": ... }` in emit order.
+pub const BATCH_EVENT: &str = "batch";
+
+/// Room prefixes that see bursts during volatile markets or simulator runs.
+const BATCHED_CHANNELS: [&str; 2] = ["orderbook", "trades"];
+
+/// Flush interval from SOCKET_BATCH_INTERVAL_MS. Unset or 0 disables batching
+/// and every event is emitted as it happens.
+pub static BATCH_INTERVAL: Lazy<Option<Duration>> = Lazy::new(|| {
+    std::env::var("SOCKET_BATCH_INTERVAL_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|ms| *ms > 0)
+        .map(Duration::from_millis)
+});
+
+static PENDING: Lazy<Mutex<HashMap<String, Vec<BufferedEvent>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn is_batched(room: &str) -> bool {
+    BATCH_INTERVAL.is_some() && BATCHED_CHANNELS.contains(&room.split(':').next().unwrap_or(room))
+}
+
+/// Queue the event for the next flush. Returns false when the room isn't
+/// batched and the caller should emit immediately.
+pub fn enqueue(room: &str, entry: &BufferedEvent) -> bool {
+    if !is_batched(room) {
+        return false;
+    }
+
+    PENDING
+        .lock()
+        .unwrap()
+        .entry(room.to_string())
+        .or_default()
+        .push(entry.clone());
+    true
+}
+
+fn flush(io: &SocketIo) {
+    let pending = std::mem::take(&mut *PENDING.lock().unwrap());
+
+    for (room, events) in pending {
+        let Some(last) = events.last() else {
+            continue;
+        };
+
+        let batch = BufferedEvent {
+            seq: last.seq,
+            event: BATCH_EVENT.to_string(),
+            payload: Value::Array(
+                events
+                    .iter()
+                    .map(|e| json!({ "event": e.event, "payload": e.payload }))
+                    .collect(),
+            ),
+        };
+
+        fan_out(io, &room, &batch);
+    }
+}
+
+/// Background task draining queued events every interval. Does nothing when
+/// batching is disabled.
+pub async fn run_flusher(io: SocketIo) {
+    let Some(interval) = *BATCH_INTERVAL else {
+        return;
+    };
+
+    println!("Socket batching enabled, flushing every {:?}", interval);
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        flush(&io);
+    }
+}
@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use once_cell::sync::{Lazy, OnceCell};
+use redis::AsyncCommands;
+use redis::aio::ConnectionManager;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use socketioxide::SocketIo;
+use tokio::sync::mpsc::{UnboundedReceiver, unbounded_channel};
+use uuid::Uuid;
+
+use crate::sockets::emit_local;
+
+/// Redis channel shared by every replica, from SOCKET_CLUSTER_CHANNEL.
+/// Unset means single-instance mode and nothing is published.
+static CHANNEL: Lazy<Option<String>> = Lazy::new(|| {
+    std::env::var("SOCKET_CLUSTER_CHANNEL")
+        .ok()
+        .filter(|c| !c.is_empty())
+});
+
+/// Tags messages so an instance can skip its own on the way back in.
+static INSTANCE_ID: Lazy<String> = Lazy::new(|| Uuid::new_v4().to_string());
+
+static PUBLISHER: OnceCell<ConnectionManager> = OnceCell::new();
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ClusterMessage {
+    origin: String,
+    room: String,
+    event: String,
+    payload: Value,
+}
+
+fn redis_client() -> anyhow::Result<redis::Client> {
+    let redis_url =
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+    Ok(redis::Client::open(redis_url)?)
+}
+
+/// Connect the publisher. Returns Ok without doing anything when clustering
+/// is off.
+pub async fn init() -> anyhow::Result<()> {
+    let Some(channel) = CHANNEL.as_ref() else {
+        return Ok(());
+    };
+
+    let manager = ConnectionManager::new(redis_client()?).await?;
+    let _ = PUBLISHER.set(manager);
+    println!(
+        "Socket cluster fan-out on Redis channel {} as {}",
+        channel, *INSTANCE_ID
+    );
+    Ok(())
+}
+
+/// Forward an event to the other replicas. Payload is sent before sequence
+/// numbers are assigned; each instance numbers events for its own clients.
+pub async fn publish(room: &str, event: &str, payload: &Value) {
+    let (Some(channel), Some(publisher)) = (CHANNEL.as_ref(), PUBLISHER.get()) else {
+        return;
+    };
+
+    let message = ClusterMessage {
+        origin: INSTANCE_ID.clone(),
+        room: room.to_string(),
+        event: event.to_string(),
+        payload: payload.clone(),
+    };
+
+    let Ok(body) = serde_json::to_string(&message) else {
+        return;
+    };
+
+    let mut conn = publisher.clone();
+    if let Err(e) = conn.publish::<_, _, ()>(channel, body).await {
+        println!(
+            "Failed to publish {} for {} to cluster: {:?}",
+            event, room, e
+        );
+    }
+}
+
+async fn subscribe_once(io: &SocketIo, channel: &str) -> anyhow::Result<()> {
+    let mut pubsub = redis_client()?.get_async_pubsub().await?;
+    pubsub.subscribe(channel).await?;
+
+    let mut messages = pubsub.on_message();
+    while let Some(msg) = messages.next().await {
+        let body: String = match msg.get_payload() {
+            Ok(b) => b,
+            Err(e) => {
+                println!("Bad cluster message payload: {:?}", e);
+                continue;
+            }
+        };
+
+        let message: ClusterMessage = match serde_json::from_str(&body) {
+            Ok(m) => m,
+            Err(e) => {
+                println!("Failed to decode cluster message: {:?}", e);
+                continue;
+            }
+        };
+
+        if message.origin == *INSTANCE_ID {
+            continue;
+        }
+
+        emit_local(io, &message.room, &message.event, message.payload);
+    }
+
+    Err(anyhow::anyhow!("cluster subscription closed"))
+}
+
+/// Background task delivering events published by other replicas to this
+/// instance's sockets. Reconnects if Redis drops. Does nothing when
+/// clustering is off.
+pub async fn run_subscriber(io: SocketIo) {
+    let Some(channel) = CHANNEL.as_ref() else {
+        return;
+    };
+
+    loop {
+        if let Err(e) = subscribe_once(&io, channel).await {
+            println!("Socket cluster subscriber error: {:?}, retrying in 5s", e);
+        }
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    }
+}
+
+/// Names of the events any replica publishes for `rooms`, or for every room
+/// when `rooms` is empty, for tools that follow the feed without serving
+/// sockets. None when clustering is off, since events then never leave the
+/// instance that emitted them.
+pub async fn follow(rooms: Vec<String>) -> anyhow::Result<Option<UnboundedReceiver<String>>> {
+    let Some(channel) = CHANNEL.as_ref() else {
+        return Ok(None);
+    };
+
+    let mut pubsub = redis_client()?.get_async_pubsub().await?;
+    pubsub.subscribe(channel).await?;
+
+    let (tx, rx) = unbounded_channel();
+    tokio::spawn(async move {
+        let mut messages = pubsub.on_message();
+        while let Some(msg) = messages.next().await {
+            let Ok(body) = msg.get_payload::<String>() else {
+                continue;
+            };
+            let Ok(message) = serde_json::from_str::<ClusterMessage>(&body) else {
+                continue;
+            };
+            if (rooms.is_empty() || rooms.contains(&message.room))
+                && tx.send(message.event).is_err()
+            {
+                break;
+            }
+        }
+    });
+    Ok(Some(rx))
+}
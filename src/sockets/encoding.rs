@@ -0,0 +1,30 @@
+use anyhow::Result;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+/// Wire encoding negotiated per connection. JSON is the default; market-data
+/// consumers can opt into MessagePack to cut payload size on busy rooms.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Encoding {
+    #[default]
+    Json,
+    Msgpack,
+}
+
+impl Encoding {
+    /// Room a socket actually joins for the given logical room. MessagePack
+    /// subscribers sit in a sibling room so each payload is encoded once per
+    /// encoding rather than once per socket.
+    pub fn room_for(&self, room: &str) -> String {
+        match self {
+            Encoding::Json => room.to_string(),
+            Encoding::Msgpack => format!("msgpack:{}", room),
+        }
+    }
+}
+
+pub fn encode_msgpack<T: Serialize>(payload: &T) -> Result<Bytes> {
+    let buf = rmp_serde::to_vec_named(payload)?;
+    Ok(Bytes::from(buf))
+}
@@ -0,0 +1,72 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Optional per-subscription filter evaluated before an event is sent, so
+/// selective consumers only receive what they asked for. Every set field must
+/// match; events missing the field a filter checks are let through.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct SubscriptionFilter {
+    /// Minimum bid-side amount (`bid_amount_filled` on trades, `bid_amount` on orders)
+    #[serde(default)]
+    pub min_notional: Option<BigDecimal>,
+    /// Only events touching one of these assets (bid or ask side)
+    #[serde(default)]
+    pub assets: Vec<String>,
+    /// Only events for these wallets
+    #[serde(default)]
+    pub wallets: Vec<String>,
+    /// Only these event names, e.g. `["order:filled"]`
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+fn field<'a>(payload: &'a Value, names: &[&str]) -> Option<&'a str> {
+    names.iter().find_map(|name| payload.get(*name)?.as_str())
+}
+
+impl SubscriptionFilter {
+    pub fn is_empty(&self) -> bool {
+        self.min_notional.is_none()
+            && self.assets.is_empty()
+            && self.wallets.is_empty()
+            && self.events.is_empty()
+    }
+
+    pub fn matches(&self, event: &str, payload: &Value) -> bool {
+        if !self.events.is_empty() && !self.events.iter().any(|e| e == event) {
+            return false;
+        }
+
+        if let Some(min) = &self.min_notional {
+            let notional = field(payload, &["bid_amount_filled", "bid_amount", "amount"])
+                .and_then(|v| BigDecimal::from_str(v).ok());
+            if let Some(notional) = notional
+                && &notional < min
+            {
+                return false;
+            }
+        }
+
+        if !self.assets.is_empty() {
+            let touched: Vec<&str> = ["bid_asset", "ask_asset", "asset"]
+                .iter()
+                .filter_map(|name| payload.get(*name)?.as_str())
+                .collect();
+            if !touched.is_empty() && !touched.iter().any(|a| self.assets.iter().any(|f| f == a)) {
+                return false;
+            }
+        }
+
+        if !self.wallets.is_empty()
+            && let Some(wallet) = field(payload, &["wallet"])
+            && !self.wallets.iter().any(|w| w == wallet)
+        {
+            return false;
+        }
+
+        true
+    }
+}
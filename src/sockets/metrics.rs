@@ -0,0 +1,159 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+/// Seconds of history kept for the messages/sec rate.
+const RATE_WINDOW_SECS: i64 = 60;
+
+/// Rooms listed in the snapshot, busiest first.
+const TOP_ROOMS: usize = 10;
+
+#[derive(Default)]
+pub struct SocketMetrics {
+    connected: AtomicU64,
+    emitted: AtomicU64,
+    dropped: AtomicU64,
+    slow_disconnects: AtomicU64,
+    rooms: Mutex<HashMap<String, u64>>,
+    window: Mutex<VecDeque<(i64, u64)>>,
+}
+
+pub static SOCKET_METRICS: Lazy<SocketMetrics> = Lazy::new(SocketMetrics::default);
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RoomSubscribers {
+    pub room: String,
+    pub subscribers: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SocketMetricsSnapshot {
+    pub connected_sockets: u64,
+    pub messages_emitted: u64,
+    pub messages_dropped: u64,
+    pub slow_consumer_disconnects: u64,
+    pub messages_per_sec: f64,
+    pub subscriptions_by_channel: HashMap<String, u64>,
+    pub top_rooms: Vec<RoomSubscribers>,
+}
+
+/// `orderbook:<id>` -> `orderbook`
+fn channel_of(room: &str) -> &str {
+    room.split(':').next().unwrap_or(room)
+}
+
+impl SocketMetrics {
+    pub fn connected(&self) {
+        self.connected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn disconnected(&self) {
+        let _ = self
+            .connected
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| Some(v.saturating_sub(1)));
+    }
+
+    pub fn subscribed(&self, room: &str) {
+        *self.rooms.lock().unwrap().entry(room.to_string()).or_insert(0) += 1;
+    }
+
+    pub fn unsubscribed(&self, room: &str) {
+        let mut rooms = self.rooms.lock().unwrap();
+        if let Some(count) = rooms.get_mut(room) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                rooms.remove(room);
+            }
+        }
+    }
+
+    pub fn emitted(&self) {
+        self.emitted.fetch_add(1, Ordering::Relaxed);
+
+        let now = Utc::now().timestamp();
+        let mut window = self.window.lock().unwrap();
+        match window.back_mut() {
+            Some((sec, count)) if *sec == now => *count += 1,
+            _ => window.push_back((now, 1)),
+        }
+        while window.front().is_some_and(|(sec, _)| *sec <= now - RATE_WINDOW_SECS) {
+            window.pop_front();
+        }
+    }
+
+    pub fn dropped(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn slow_disconnect(&self) {
+        self.slow_disconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> SocketMetricsSnapshot {
+        let now = Utc::now().timestamp();
+        let recent: u64 = self
+            .window
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(sec, _)| *sec > now - RATE_WINDOW_SECS)
+            .map(|(_, count)| count)
+            .sum();
+
+        let rooms = self.rooms.lock().unwrap();
+        let mut subscriptions_by_channel: HashMap<String, u64> = HashMap::new();
+        for (room, count) in rooms.iter() {
+            *subscriptions_by_channel.entry(channel_of(room).to_string()).or_insert(0) += count;
+        }
+
+        let mut top_rooms: Vec<RoomSubscribers> = rooms
+            .iter()
+            .map(|(room, count)| RoomSubscribers {
+                room: room.clone(),
+                subscribers: *count,
+            })
+            .collect();
+        top_rooms.sort_by(|a, b| b.subscribers.cmp(&a.subscribers));
+        top_rooms.truncate(TOP_ROOMS);
+
+        SocketMetricsSnapshot {
+            connected_sockets: self.connected.load(Ordering::Relaxed),
+            messages_emitted: self.emitted.load(Ordering::Relaxed),
+            messages_dropped: self.dropped.load(Ordering::Relaxed),
+            slow_consumer_disconnects: self.slow_disconnects.load(Ordering::Relaxed),
+            messages_per_sec: recent as f64 / RATE_WINDOW_SECS as f64,
+            subscriptions_by_channel,
+            top_rooms,
+        }
+    }
+
+    /// Prometheus text exposition for the /metrics endpoint.
+    pub fn render_prometheus(&self) -> String {
+        let snapshot = self.snapshot();
+        let mut out = String::new();
+
+        out.push_str("# TYPE cradle_socket_connected gauge\n");
+        out.push_str(&format!("cradle_socket_connected {}\n", snapshot.connected_sockets));
+        out.push_str("# TYPE cradle_socket_messages_emitted_total counter\n");
+        out.push_str(&format!("cradle_socket_messages_emitted_total {}\n", snapshot.messages_emitted));
+        out.push_str("# TYPE cradle_socket_messages_dropped_total counter\n");
+        out.push_str(&format!("cradle_socket_messages_dropped_total {}\n", snapshot.messages_dropped));
+        out.push_str("# TYPE cradle_socket_slow_consumer_disconnects_total counter\n");
+        out.push_str(&format!(
+            "cradle_socket_slow_consumer_disconnects_total {}\n",
+            snapshot.slow_consumer_disconnects
+        ));
+        out.push_str("# TYPE cradle_socket_messages_per_second gauge\n");
+        out.push_str(&format!("cradle_socket_messages_per_second {}\n", snapshot.messages_per_sec));
+        out.push_str("# TYPE cradle_socket_subscriptions gauge\n");
+        for (channel, count) in &snapshot.subscriptions_by_channel {
+            out.push_str(&format!("cradle_socket_subscriptions{{channel=\"{}\"}} {}\n", channel, count));
+        }
+
+        out
+    }
+}
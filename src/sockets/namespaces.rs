@@ -0,0 +1,73 @@
+use serde::Deserialize;
+use serde_json::Value;
+use socketioxide::extract::{Data, SocketRef};
+
+/// Socket.IO namespaces. Market data is public; account, lending and admin
+/// events require a token in the handshake auth.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SocketNamespace {
+    /// Legacy default namespace, kept for existing market-data clients
+    Root,
+    Markets,
+    Accounts,
+    Lending,
+    Admin,
+}
+
+impl SocketNamespace {
+    pub fn path(&self) -> &'static str {
+        match self {
+            SocketNamespace::Root => "/",
+            SocketNamespace::Markets => "/markets",
+            SocketNamespace::Accounts => "/accounts",
+            SocketNamespace::Lending => "/lending",
+            SocketNamespace::Admin => "/admin",
+        }
+    }
+
+    /// Namespaces a room's events are delivered on, based on the room prefix.
+    pub fn for_room(room: &str) -> &'static [SocketNamespace] {
+        match room.split(':').next().unwrap_or(room) {
+            "account" => &[SocketNamespace::Accounts],
+            "lending" => &[SocketNamespace::Lending, SocketNamespace::Admin],
+            "admin" => &[SocketNamespace::Admin],
+            _ => &[SocketNamespace::Markets, SocketNamespace::Root],
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct AuthPayload {
+    #[serde(default)]
+    token: Option<String>,
+}
+
+fn check_token(data: Value, expected: Option<String>) -> Result<(), anyhow::Error> {
+    let expected = expected.ok_or_else(|| anyhow::anyhow!("namespace is disabled"))?;
+    let auth: AuthPayload = serde_json::from_value(data).unwrap_or_default();
+
+    match auth.token {
+        Some(token) if token == expected => Ok(()),
+        Some(_) => Err(anyhow::anyhow!("invalid token")),
+        None => Err(anyhow::anyhow!("missing token")),
+    }
+}
+
+/// Connect middleware for `/accounts` and `/lending`: same key as the REST API.
+pub async fn require_api_key(socket: SocketRef, Data(data): Data<Value>) -> Result<(), anyhow::Error> {
+    let res = check_token(data, std::env::var("API_SECRET_KEY").ok());
+    if let Err(e) = &res {
+        println!("Rejected socket {} on {}: {}", socket.id, socket.ns(), e);
+    }
+    res
+}
+
+/// Connect middleware for `/admin`. Uses its own key so API clients can't
+/// observe operational events; the namespace refuses everyone when unset.
+pub async fn require_admin_key(socket: SocketRef, Data(data): Data<Value>) -> Result<(), anyhow::Error> {
+    let res = check_token(data, std::env::var("ADMIN_SOCKET_KEY").ok());
+    if let Err(e) = &res {
+        println!("Rejected socket {} on {}: {}", socket.id, socket.ns(), e);
+    }
+    res
+}
@@ -0,0 +1,154 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Number of events retained per room when SOCKET_REPLAY_BUFFER is not set.
+const DEFAULT_BUFFER_SIZE: usize = 256;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BufferedEvent {
+    pub seq: u64,
+    pub event: String,
+    pub payload: Value,
+}
+
+#[derive(Default)]
+struct RoomBuffer {
+    next_seq: u64,
+    events: VecDeque<BufferedEvent>,
+}
+
+/// Keeps the last N events emitted to each room so reconnecting clients can
+/// catch up from the last sequence number they saw.
+pub struct ReplayBuffer {
+    capacity: usize,
+    rooms: Mutex<HashMap<String, RoomBuffer>>,
+}
+
+pub static REPLAY_BUFFER: Lazy<ReplayBuffer> = Lazy::new(|| {
+    let capacity = std::env::var("SOCKET_REPLAY_BUFFER")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_BUFFER_SIZE);
+    ReplayBuffer::new(capacity)
+});
+
+impl ReplayBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            rooms: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Assigns the next sequence number for the room and stores the event.
+    /// Object payloads get a `seq` field so clients can track their position.
+    pub fn record(&self, room: &str, event: &str, payload: Value) -> BufferedEvent {
+        let mut rooms = self.rooms.lock().unwrap();
+        let buffer = rooms.entry(room.to_string()).or_default();
+
+        buffer.next_seq += 1;
+        let seq = buffer.next_seq;
+
+        let payload = match payload {
+            Value::Object(mut map) => {
+                map.insert("seq".to_string(), Value::from(seq));
+                Value::Object(map)
+            }
+            other => other,
+        };
+
+        let entry = BufferedEvent {
+            seq,
+            event: event.to_string(),
+            payload,
+        };
+
+        if buffer.events.len() >= self.capacity {
+            buffer.events.pop_front();
+        }
+        buffer.events.push_back(entry.clone());
+
+        entry
+    }
+
+    /// Returns every buffered event after `last_seq`. The boolean is false when
+    /// the buffer has already dropped events the client missed, or when
+    /// `last_seq` is ahead of the room (the counter reset with a restart), in
+    /// which case it should refetch state over REST.
+    pub fn since(&self, room: &str, last_seq: u64) -> (Vec<BufferedEvent>, bool) {
+        let rooms = self.rooms.lock().unwrap();
+        let Some(buffer) = rooms.get(room) else {
+            return (Vec::new(), last_seq == 0);
+        };
+        if last_seq > buffer.next_seq {
+            return (Vec::new(), false);
+        }
+
+        let complete = match buffer.events.front() {
+            Some(first) => first.seq <= last_seq + 1,
+            None => true,
+        };
+
+        let events = buffer
+            .events
+            .iter()
+            .filter(|e| e.seq > last_seq)
+            .cloned()
+            .collect();
+
+        (events, complete)
+    }
+
+    pub fn latest_seq(&self, room: &str) -> u64 {
+        let rooms = self.rooms.lock().unwrap();
+        rooms.get(room).map(|b| b.next_seq).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_replay_since_returns_missed_events() {
+        let buffer = ReplayBuffer::new(10);
+        for i in 0..5 {
+            buffer.record("trades:a", "trade:executed", json!({ "i": i }));
+        }
+
+        let (events, complete) = buffer.since("trades:a", 3);
+        assert!(complete);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].seq, 4);
+        assert_eq!(events[0].payload["seq"], 4);
+    }
+
+    #[test]
+    fn test_replay_reports_gap_when_evicted() {
+        let buffer = ReplayBuffer::new(3);
+        for i in 0..6 {
+            buffer.record("orderbook:a", "order:placed", json!({ "i": i }));
+        }
+
+        let (events, complete) = buffer.since("orderbook:a", 1);
+        assert!(!complete);
+        assert_eq!(events.len(), 3);
+        assert_eq!(buffer.latest_seq("orderbook:a"), 6);
+    }
+
+    #[test]
+    fn test_replay_reports_gap_when_client_is_ahead() {
+        let buffer = ReplayBuffer::new(10);
+        buffer.record("trades:a", "trade:executed", json!({ "i": 0 }));
+
+        let (events, complete) = buffer.since("trades:a", 40);
+        assert!(!complete);
+        assert!(events.is_empty());
+        assert!(!buffer.since("trades:b", 40).1);
+    }
+}
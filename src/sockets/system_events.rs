@@ -0,0 +1,58 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::market_time_series::db_types::TimeSeriesInterval;
+use crate::sockets::broadcast;
+use crate::utils::app_config::AppConfig;
+
+pub const ADMIN_ROOM: &str = "admin";
+pub const SYSTEM_EVENT: &str = "system:event";
+
+/// Operational events for the admin feed. Serialized with a `kind` tag so
+/// the UI can switch on it.
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SystemEvent {
+    SettlementFailed {
+        order_id: Uuid,
+        market_id: Uuid,
+        error: String,
+    },
+    AggregatorLag {
+        market_id: Uuid,
+        asset_id: Uuid,
+        interval: TimeSeriesInterval,
+        lag_secs: i64,
+    },
+    OracleStale {
+        pool_id: Uuid,
+        asset_id: Uuid,
+        age_secs: i64,
+    },
+    ContractCallRetry {
+        operation: String,
+        attempt: u32,
+        error: String,
+    },
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct SystemEventEnvelope<'a> {
+    #[serde(flatten)]
+    event: &'a SystemEvent,
+    at: chrono::NaiveDateTime,
+}
+
+/// Log the event and push it to the `/admin` namespace. Goes through the
+/// replay buffer like any other room, so a reconnecting dashboard can catch up.
+pub async fn emit_system_event(app_config: &AppConfig, event: SystemEvent) {
+    println!("[system] {:?}", event);
+
+    if let Ok(io) = app_config.get_io() {
+        let envelope = SystemEventEnvelope {
+            event: &event,
+            at: chrono::Utc::now().naive_utc(),
+        };
+        broadcast(&io, ADMIN_ROOM.to_string(), SYSTEM_EVENT, &envelope).await;
+    }
+}
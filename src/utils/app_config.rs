@@ -6,11 +6,12 @@ use contract_integrator::wallet::wallet::ActionWallet;
 use dotenvy::dotenv;
 use socketioxide::SocketIo;
 use crate::utils::cache::RedisPool;
+use crate::utils::wallet::SharedWallet;
 
 #[derive(Clone)]
 pub struct AppConfig {
     pub pool: diesel::r2d2::Pool<ConnectionManager<PgConnection>>,
-    pub wallet: ActionWallet,
+    pub wallet: SharedWallet,
     pub redis: Option<RedisPool>,
     io: Option<SocketIo>
 }
@@ -30,7 +31,7 @@ impl AppConfig {
     pub fn new(pool: diesel::r2d2::Pool<ConnectionManager<PgConnection>>, wallet: ActionWallet)-> Self {
         Self {
             pool,
-            wallet,
+            wallet: SharedWallet::new(wallet),
             redis: None,
             io: None
         }
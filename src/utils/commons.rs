@@ -1,12 +1,13 @@
 use anyhow::anyhow;
 use bigdecimal::BigDecimal;
-use contract_integrator::wallet::wallet::ActionWallet;
 use diesel::{
     PgConnection,
     r2d2::{ConnectionManager, PooledConnection},
 };
 use std::env;
 
+use crate::utils::wallet::SharedWallet;
+
 pub struct SystemAddresses {
     pub fee_collector: String,
 }
@@ -17,7 +18,7 @@ pub fn get_system_addresses() -> SystemAddresses {
 }
 
 pub type DbConn<'db> = &'db mut PooledConnection<ConnectionManager<PgConnection>>;
-pub type TaskWallet<'wt> = &'wt mut ActionWallet;
+pub type TaskWallet<'wt> = &'wt SharedWallet;
 
 #[macro_export]
 macro_rules! extract_option {
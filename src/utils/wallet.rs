@@ -0,0 +1,41 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use anyhow::Result;
+use contract_integrator::{
+    utils::functions::{ContractCallInput, ContractCallOutput},
+    wallet::wallet::ActionWallet,
+};
+use tokio::sync::Mutex;
+
+/// The operator wallet, shared by every clone of the config. Contract calls
+/// take turns on the one signer, holding it only for the call; queries read
+/// the client and contract ids through `Deref` without waiting for it.
+#[derive(Clone, Debug)]
+pub struct SharedWallet {
+    signer: Arc<Mutex<ActionWallet>>,
+    reader: ActionWallet,
+}
+
+impl SharedWallet {
+    pub fn new(wallet: ActionWallet) -> Self {
+        Self {
+            reader: wallet.clone(),
+            signer: Arc::new(Mutex::new(wallet)),
+        }
+    }
+
+    /// Sign and send a contract call once any call in flight has finished.
+    pub async fn execute(&self, input: ContractCallInput) -> Result<ContractCallOutput> {
+        let mut signer = self.signer.lock().await;
+        Ok(signer.execute(input).await?)
+    }
+}
+
+impl Deref for SharedWallet {
+    type Target = ActionWallet;
+
+    fn deref(&self) -> &ActionWallet {
+        &self.reader
+    }
+}
@@ -0,0 +1,69 @@
+use crate::schema::webhook_deliveries as WebhookDeliveriesTable;
+use crate::schema::webhooks as WebhooksTable;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::DeliveryStatus"]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = WebhooksTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WebhookRow {
+    pub id: Uuid,
+    pub url: String,
+    /// Only handed out once, when the webhook is registered
+    #[serde(skip_serializing, default)]
+    pub secret: String,
+    pub company_id: Option<Uuid>,
+    pub listing_id: Option<Uuid>,
+    pub events: Vec<String>,
+    pub active: bool,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = WebhooksTable)]
+pub struct CreateWebhook {
+    pub url: String,
+    pub secret: String,
+    pub company_id: Option<Uuid>,
+    pub listing_id: Option<Uuid>,
+    pub events: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = WebhookDeliveriesTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WebhookDeliveryRow {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: String,
+    pub listing_id: Uuid,
+    pub payload: Value,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub delivered_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Insertable)]
+#[diesel(table_name = WebhookDeliveriesTable)]
+pub struct CreateWebhookDelivery {
+    pub webhook_id: Uuid,
+    pub event: String,
+    pub listing_id: Uuid,
+    pub payload: Value,
+}
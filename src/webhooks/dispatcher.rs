@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use chrono::Utc;
+use diesel::prelude::*;
+use reqwest::Client;
+use serde_json::json;
+
+use crate::utils::app_config::AppConfig;
+use crate::webhooks::db_types::{DeliveryStatus, WebhookDeliveryRow, WebhookRow};
+use crate::webhooks::operations::sign_payload;
+
+/// Seconds between dispatch passes, from WEBHOOK_DISPATCH_INTERVAL_SECS.
+fn poll_interval() -> Duration {
+    let secs = std::env::var("WEBHOOK_DISPATCH_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(5);
+    Duration::from_secs(secs)
+}
+
+/// Attempts before a delivery is marked failed, from WEBHOOK_MAX_ATTEMPTS.
+fn max_attempts() -> i32 {
+    std::env::var("WEBHOOK_MAX_ATTEMPTS")
+        .ok()
+        .and_then(|v| v.parse::<i32>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(8)
+}
+
+/// Wait before retrying after `attempts` failures: 30s doubling, capped at 6h.
+fn retry_delay(attempts: i32) -> chrono::Duration {
+    let secs = 30i64.saturating_mul(1i64 << attempts.clamp(0, 20));
+    chrono::Duration::seconds(secs.min(6 * 60 * 60))
+}
+
+async fn deliver(client: &Client, webhook: &WebhookRow, delivery: &WebhookDeliveryRow) -> Result<()> {
+    let body = serde_json::to_vec(&json!({
+        "id": delivery.id,
+        "event": delivery.event,
+        "listing_id": delivery.listing_id,
+        "created_at": delivery.created_at,
+        "data": delivery.payload,
+    }))?;
+
+    let response = client
+        .post(&webhook.url)
+        .timeout(Duration::from_secs(10))
+        .header("content-type", "application/json")
+        .header("x-cradle-event", &delivery.event)
+        .header("x-cradle-delivery", delivery.id.to_string())
+        .header("x-cradle-signature", sign_payload(&webhook.secret, &body))
+        .body(body)
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(anyhow!("Endpoint responded with {}", response.status()));
+    }
+    Ok(())
+}
+
+/// Attempt every delivery that is due.
+pub async fn run_once(app_config: &AppConfig, client: &Client) -> Result<()> {
+    let mut conn = app_config.pool.get()?;
+    let now = Utc::now().naive_utc();
+
+    let due = {
+        use crate::schema::webhook_deliveries::dsl::*;
+
+        webhook_deliveries
+            .filter(status.eq(DeliveryStatus::Pending))
+            .filter(next_attempt_at.le(now))
+            .order(next_attempt_at.asc())
+            .limit(100)
+            .get_results::<WebhookDeliveryRow>(&mut conn)?
+    };
+
+    for delivery in due {
+        let webhook = {
+            use crate::schema::webhooks::dsl::*;
+
+            webhooks
+                .filter(id.eq(delivery.webhook_id))
+                .get_result::<WebhookRow>(&mut conn)?
+        };
+
+        let result = if webhook.active {
+            deliver(client, &webhook, &delivery).await
+        } else {
+            Err(anyhow!("Webhook was deactivated"))
+        };
+
+        use crate::schema::webhook_deliveries::dsl::*;
+        let tries = delivery.attempts + 1;
+
+        match result {
+            Ok(()) => {
+                diesel::update(webhook_deliveries.find(delivery.id))
+                    .set((
+                        status.eq(DeliveryStatus::Delivered),
+                        attempts.eq(tries),
+                        last_error.eq(None::<String>),
+                        delivered_at.eq(Some(Utc::now().naive_utc())),
+                    ))
+                    .execute(&mut conn)?;
+            }
+            Err(e) => {
+                let give_up = !webhook.active || tries >= max_attempts();
+                diesel::update(webhook_deliveries.find(delivery.id))
+                    .set((
+                        status.eq(if give_up {
+                            DeliveryStatus::Failed
+                        } else {
+                            DeliveryStatus::Pending
+                        }),
+                        attempts.eq(tries),
+                        last_error.eq(Some(e.to_string())),
+                        next_attempt_at.eq(Utc::now().naive_utc() + retry_delay(delivery.attempts)),
+                    ))
+                    .execute(&mut conn)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Background task delivering queued listing events to webhooks.
+pub async fn run_dispatcher(app_config: AppConfig) {
+    let client = Client::new();
+    let mut ticker = tokio::time::interval(poll_interval());
+    loop {
+        ticker.tick().await;
+        if let Err(e) = run_once(&app_config, &client).await {
+            println!("Webhook dispatch error: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod db_types;
+pub mod dispatcher;
+pub mod operations;
@@ -0,0 +1,165 @@
+use anyhow::{Result, anyhow};
+use diesel::prelude::*;
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, PooledConnection},
+};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::webhooks::db_types::{
+    CreateWebhook, CreateWebhookDelivery, WebhookDeliveryRow, WebhookRow,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RegisterWebhookInputArgs {
+    pub url: String,
+    /// Only events for this company's listings
+    pub company_id: Option<Uuid>,
+    /// Only events for this listing
+    pub listing_id: Option<Uuid>,
+    /// Event names to deliver, e.g. `listing:closed`; empty for all of them
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Signing secret; generated when not given
+    pub secret: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RegisteredWebhook {
+    pub webhook: WebhookRow,
+    pub secret: String,
+}
+
+fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Hex HMAC-SHA256 of a delivery body, sent as `X-Cradle-Signature`.
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("hmac accepts any key length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+pub fn register_webhook(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    input: RegisterWebhookInputArgs,
+) -> Result<RegisteredWebhook> {
+    if !(input.url.starts_with("https://") || input.url.starts_with("http://")) {
+        return Err(anyhow!("Webhook url must be http(s)"));
+    }
+    if input.events.iter().any(|e| !e.starts_with("listing:")) {
+        return Err(anyhow!("Only listing:* events can be subscribed to"));
+    }
+
+    let secret = input
+        .secret
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(generate_secret);
+
+    use crate::schema::webhooks::dsl::*;
+
+    let webhook = diesel::insert_into(webhooks)
+        .values(CreateWebhook {
+            url: input.url,
+            secret: secret.clone(),
+            company_id: input.company_id,
+            listing_id: input.listing_id,
+            events: input.events,
+        })
+        .get_result::<WebhookRow>(conn)?;
+
+    Ok(RegisteredWebhook { webhook, secret })
+}
+
+pub fn get_webhooks(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+) -> Result<Vec<WebhookRow>> {
+    use crate::schema::webhooks::dsl::*;
+
+    Ok(webhooks
+        .filter(active.eq(true))
+        .order(created_at.desc())
+        .get_results::<WebhookRow>(conn)?)
+}
+
+/// Stop delivering to a webhook. The row stays so its delivery history can
+/// still be read.
+pub fn deactivate_webhook(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    webhook: Uuid,
+) -> Result<WebhookRow> {
+    use crate::schema::webhooks::dsl::*;
+
+    Ok(diesel::update(webhooks.find(webhook))
+        .set(active.eq(false))
+        .get_result::<WebhookRow>(conn)?)
+}
+
+/// Recent deliveries to one webhook, newest first.
+pub fn get_webhook_deliveries(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    webhook: Uuid,
+) -> Result<Vec<WebhookDeliveryRow>> {
+    use crate::schema::webhook_deliveries::dsl::*;
+
+    Ok(webhook_deliveries
+        .filter(webhook_id.eq(webhook))
+        .order(created_at.desc())
+        .limit(100)
+        .get_results::<WebhookDeliveryRow>(conn)?)
+}
+
+/// Queue a listing event for every active webhook scoped to it. Delivery
+/// happens in the dispatcher, so a slow endpoint never holds up the caller.
+pub fn enqueue_listing_event(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    listing: Uuid,
+    event_name: &str,
+    data: &Value,
+) -> Result<usize> {
+    let listing_company = {
+        use crate::schema::cradlenativelistings::dsl::*;
+
+        cradlenativelistings
+            .filter(id.eq(listing))
+            .select(company)
+            .first::<Uuid>(conn)?
+    };
+
+    let targets = {
+        use crate::schema::webhooks::dsl::*;
+
+        webhooks
+            .filter(active.eq(true))
+            .filter(listing_id.is_null().or(listing_id.eq(listing)))
+            .filter(company_id.is_null().or(company_id.eq(listing_company)))
+            .get_results::<WebhookRow>(conn)?
+    };
+
+    let rows = targets
+        .iter()
+        .filter(|w| w.events.is_empty() || w.events.iter().any(|e| e == event_name))
+        .map(|w| CreateWebhookDelivery {
+            webhook_id: w.id,
+            event: event_name.to_string(),
+            listing_id: listing,
+            payload: data.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    if rows.is_empty() {
+        return Ok(0);
+    }
+
+    use crate::schema::webhook_deliveries::dsl::*;
+    Ok(diesel::insert_into(webhook_deliveries).values(&rows).execute(conn)?)
+}